@@ -0,0 +1,127 @@
+use chrono::{Duration, NaiveDate, Utc};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use portfoliodb_rust::db;
+use portfoliodb_rust::models::{
+    DataExport, ImportMode, Investment, InvestmentPrice, Movement, DATA_EXPORT_VERSION,
+};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::PortfolioCalculator;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+const INVESTMENTS: i64 = 20;
+
+/// Database with `movements` buys and sells spread over `days` daily prices per investment
+async fn setup(movements: i64, days: i64) -> Repositories {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    db::run_migrations(&pool).await.unwrap();
+    let repos = Repositories::sqlite(pool);
+
+    let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+    let per_investment = movements / INVESTMENTS;
+    let mut data = DataExport {
+        version: DATA_EXPORT_VERSION,
+        exported_at: Utc::now(),
+        settings: None,
+        action_types: Vec::new(),
+        portfolios: Vec::new(),
+        investments: Vec::new(),
+        movements: Vec::new(),
+        cash_movements: Vec::new(),
+        prices: Vec::new(),
+    };
+    for investment_id in 1..=INVESTMENTS {
+        data.investments.push(Investment {
+            id: investment_id,
+            name: Some(format!("Investment {}", investment_id)),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        });
+        for i in 0..per_investment {
+            // Every third movement sells a third of a buy
+            let sell = i % 3 == 2;
+            data.movements.push(Movement {
+                id: investment_id * per_investment + i,
+                date: Some(start + Duration::days(i * days / per_investment)),
+                action_id: Some(if sell { 2 } else { 1 }),
+                investment_id: Some(investment_id),
+                quantity: Some(if sell { dec!(1.0) } else { dec!(3.0) }),
+                amount: Some(if sell { dec!(100.0) } else { dec!(300.0) }),
+                fee: Some(dec!(1.0)),
+                tax: None,
+                portfolio_id: None,
+                external_id: None,
+            });
+        }
+        for day in 0..days {
+            data.prices.push(InvestmentPrice {
+                date: Some(start + Duration::days(day)),
+                investment_id: Some(investment_id),
+                price: Some(Decimal::from(100 + day % 50)),
+                source: Some("bench".to_string()),
+                currency: None,
+                original_price: None,
+            });
+        }
+    }
+    repos
+        .data_import
+        .import(&data, ImportMode::Replace)
+        .await
+        .unwrap();
+    repos
+}
+
+fn bench_developments(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("calculate_developments");
+    group.sample_size(10);
+
+    // Up to 10k movements with five years of daily prices
+    for (movements, days) in [(1_000, 365), (10_000, 5 * 365)] {
+        let repos = runtime.block_on(setup(movements, days));
+        let calculator = Arc::new(PortfolioCalculator::new(
+            repos.movements.clone(),
+            repos.investment_prices.clone(),
+        ));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}_movements_{}_days", movements, days)),
+            &calculator,
+            |b, calculator| {
+                b.to_async(&runtime)
+                    .iter(|| async { calculator.calculate_developments(None, None).await.unwrap() })
+            },
+        );
+        // The last month only, which reads earlier movements as summed quantities
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap() + Duration::days(days - 30);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}_movements_last_month", movements)),
+            &calculator,
+            |b, calculator| {
+                b.to_async(&runtime).iter(|| async {
+                    calculator
+                        .calculate_developments(Some(start), None)
+                        .await
+                        .unwrap()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_developments);
+criterion_main!(benches);
@@ -0,0 +1,114 @@
+-- Bootstrap schema. Table and column names are quoted so they keep the same
+-- casing as the SQLite schema, which lets both backends share the model
+-- definitions. Tables may already exist in databases created before versioned
+-- migrations, so everything here is idempotent.
+
+CREATE TABLE IF NOT EXISTS "ActionType" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "Name" VARCHAR(10) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS "Investment" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "Name" TEXT,
+    "ISIN" VARCHAR(20),
+    "ShortName" VARCHAR(30),
+    "QuoteProvider" VARCHAR(100),
+    "TickerSymbol" VARCHAR(20)
+);
+
+-- Provider fallback chains need more room than a single provider ID
+ALTER TABLE "Investment" ALTER COLUMN "QuoteProvider" TYPE VARCHAR(100);
+
+CREATE TABLE IF NOT EXISTS "Portfolio" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "Name" TEXT NOT NULL,
+    "Description" TEXT
+);
+
+CREATE TABLE IF NOT EXISTS "Movement" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "Date" DATE,
+    "Quantity" NUMERIC,
+    "Amount" NUMERIC,
+    "Fee" NUMERIC,
+    "ActionID" BIGINT REFERENCES "ActionType"("ID"),
+    "InvestmentID" BIGINT REFERENCES "Investment"("ID"),
+    "PortfolioID" BIGINT REFERENCES "Portfolio"("ID") ON DELETE SET NULL
+);
+
+ALTER TABLE "Movement" ADD COLUMN IF NOT EXISTS "PortfolioID" BIGINT REFERENCES "Portfolio"("ID") ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS "Movement_ActionID_idx" ON "Movement"("ActionID");
+CREATE INDEX IF NOT EXISTS "Movement_InvestmentID_idx" ON "Movement"("InvestmentID");
+CREATE INDEX IF NOT EXISTS "Movement_PortfolioID_idx" ON "Movement"("PortfolioID");
+
+-- Deposits and withdrawals of uninvested cash
+CREATE TABLE IF NOT EXISTS "CashMovement" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "Date" DATE NOT NULL,
+    "ActionID" BIGINT NOT NULL REFERENCES "ActionType"("ID"),
+    "Amount" NUMERIC NOT NULL,
+    "PortfolioID" BIGINT REFERENCES "Portfolio"("ID") ON DELETE SET NULL,
+    "Description" TEXT
+);
+
+CREATE INDEX IF NOT EXISTS "CashMovement_PortfolioID_idx" ON "CashMovement"("PortfolioID");
+
+CREATE TABLE IF NOT EXISTS "InvestmentPrice" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "Date" DATE,
+    "InvestmentID" BIGINT,
+    "Price" NUMERIC,
+    "Source" VARCHAR(20),
+    "Currency" VARCHAR(3),
+    "OriginalPrice" NUMERIC,
+    UNIQUE("Date", "InvestmentID", "Source")
+);
+
+ALTER TABLE "InvestmentPrice" ADD COLUMN IF NOT EXISTS "Currency" VARCHAR(3);
+ALTER TABLE "InvestmentPrice" ADD COLUMN IF NOT EXISTS "OriginalPrice" NUMERIC;
+
+CREATE INDEX IF NOT EXISTS "InvestmentPrice_InvestmentID_idx" ON "InvestmentPrice"("InvestmentID");
+
+-- Cache of exchange rates
+CREATE TABLE IF NOT EXISTS "FxRate" (
+    "Date" DATE NOT NULL,
+    "FromCurrency" VARCHAR(3) NOT NULL,
+    "ToCurrency" VARCHAR(3) NOT NULL,
+    "Rate" NUMERIC NOT NULL,
+    PRIMARY KEY("Date", "FromCurrency", "ToCurrency")
+);
+
+-- Outcome of every quote fetch
+CREATE TABLE IF NOT EXISTS "QuoteFetchLog" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "FetchedAt" TIMESTAMPTZ NOT NULL,
+    "InvestmentID" BIGINT NOT NULL,
+    "Provider" VARCHAR(20),
+    "Success" BOOLEAN NOT NULL,
+    "Error" TEXT,
+    "QuotesStored" BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS "QuoteFetchLog_InvestmentID_idx" ON "QuoteFetchLog"("InvestmentID", "FetchedAt");
+
+CREATE TABLE IF NOT EXISTS "Settings" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "BaseCurrency" VARCHAR(3) NOT NULL,
+    "CostBasisMethod" VARCHAR(10) NOT NULL DEFAULT 'fifo'
+);
+
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "CostBasisMethod" VARCHAR(10) NOT NULL DEFAULT 'fifo';
+
+-- Seed data, moving the ID sequences past the explicitly inserted rows
+INSERT INTO "ActionType" ("ID", "Name") VALUES
+    (1, 'Buy'), (2, 'Sell'), (3, 'Payout'), (4, 'Deposit'), (5, 'Withdrawal')
+ON CONFLICT ("ID") DO NOTHING;
+
+SELECT setval(pg_get_serial_sequence('"ActionType"', 'ID'), (SELECT MAX("ID") FROM "ActionType"));
+
+INSERT INTO "Settings" ("ID", "BaseCurrency")
+SELECT 1, 'EUR' WHERE NOT EXISTS (SELECT 1 FROM "Settings");
+
+SELECT setval(pg_get_serial_sequence('"Settings"', 'ID'), (SELECT MAX("ID") FROM "Settings"));
@@ -0,0 +1,18 @@
+-- User defined layouts of CSV files for the generic importer
+CREATE TABLE IF NOT EXISTS "ImportProfile" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "Name" TEXT NOT NULL,
+    "Delimiter" VARCHAR(1) NOT NULL DEFAULT ',',
+    "DateFormat" VARCHAR(50) NOT NULL,
+    "DecimalSeparator" VARCHAR(1) NOT NULL DEFAULT '.',
+    "Currency" VARCHAR(3) NOT NULL,
+    "DateColumn" TEXT NOT NULL,
+    "ActionColumn" TEXT NOT NULL,
+    "IsinColumn" TEXT NOT NULL,
+    "ProductColumn" TEXT,
+    "QuantityColumn" TEXT,
+    "AmountColumn" TEXT NOT NULL,
+    "FeeColumn" TEXT,
+    -- Maps keywords of the action column to action type IDs
+    "ActionKeywords" JSONB NOT NULL DEFAULT '{}'
+);
@@ -0,0 +1,5 @@
+-- Stock splits, recorded as movements with the split ratio as quantity
+INSERT INTO "ActionType" ("ID", "Name") VALUES (6, 'Split')
+ON CONFLICT ("ID") DO NOTHING;
+
+SELECT setval(pg_get_serial_sequence('"ActionType"', 'ID'), (SELECT MAX("ID") FROM "ActionType"));
@@ -0,0 +1,8 @@
+-- Transfers of shares between portfolios, recorded as a pair of movements.
+-- "TransferOut" does not fit the original name length of 10.
+ALTER TABLE "ActionType" ALTER COLUMN "Name" TYPE VARCHAR(50);
+
+INSERT INTO "ActionType" ("ID", "Name") VALUES (7, 'TransferOut'), (8, 'TransferIn')
+ON CONFLICT ("ID") DO NOTHING;
+
+SELECT setval(pg_get_serial_sequence('"ActionType"', 'ID'), (SELECT MAX("ID") FROM "ActionType"));
@@ -0,0 +1,8 @@
+-- How movements of an action type are calculated, so that custom types need no code changes
+ALTER TABLE "ActionType" ADD COLUMN IF NOT EXISTS "Effect" VARCHAR(20) NOT NULL DEFAULT 'cash_only';
+
+UPDATE "ActionType" SET "Effect" = 'increases_quantity' WHERE "ID" = 1;
+UPDATE "ActionType" SET "Effect" = 'decreases_quantity' WHERE "ID" = 2;
+UPDATE "ActionType" SET "Effect" = 'split' WHERE "ID" = 6;
+UPDATE "ActionType" SET "Effect" = 'transfer_out' WHERE "ID" = 7;
+UPDATE "ActionType" SET "Effect" = 'transfer_in' WHERE "ID" = 8;
@@ -0,0 +1,3 @@
+-- Benchmark for performance comparisons: an investment or a ticker symbol
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "BenchmarkInvestmentID" BIGINT;
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "BenchmarkTicker" VARCHAR(20);
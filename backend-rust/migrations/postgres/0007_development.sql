@@ -0,0 +1,9 @@
+-- Calculated developments, kept up to date incrementally when prices or movements change
+CREATE TABLE IF NOT EXISTS "Development" (
+    "InvestmentID" BIGINT NOT NULL,
+    "Date" DATE NOT NULL,
+    "Price" DOUBLE PRECISION NOT NULL,
+    "Quantity" DOUBLE PRECISION NOT NULL,
+    "Value" DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY("InvestmentID", "Date")
+);
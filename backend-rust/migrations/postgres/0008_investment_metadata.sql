@@ -0,0 +1,3 @@
+-- Trading currency and asset class, filled in from the quote providers
+ALTER TABLE "Investment" ADD COLUMN IF NOT EXISTS "Currency" VARCHAR(3);
+ALTER TABLE "Investment" ADD COLUMN IF NOT EXISTS "AssetClass" VARCHAR(20);
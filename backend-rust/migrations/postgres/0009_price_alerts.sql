@@ -0,0 +1,25 @@
+-- Alerts on the price of an investment, evaluated after every quote fetch
+CREATE TABLE IF NOT EXISTS "PriceAlert" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "InvestmentID" BIGINT NOT NULL REFERENCES "Investment"("ID") ON DELETE CASCADE,
+    "Threshold" DOUBLE PRECISION NOT NULL,
+    -- 'above' or 'below'
+    "Direction" VARCHAR(10) NOT NULL,
+    "Active" BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+CREATE INDEX IF NOT EXISTS "PriceAlert_InvestmentID_idx" ON "PriceAlert"("InvestmentID");
+
+-- Alerts that were triggered, with the price that crossed the threshold
+CREATE TABLE IF NOT EXISTS "TriggeredAlert" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "AlertID" BIGINT NOT NULL REFERENCES "PriceAlert"("ID") ON DELETE CASCADE,
+    "InvestmentID" BIGINT NOT NULL,
+    "TriggeredAt" TIMESTAMPTZ NOT NULL,
+    "PriceDate" DATE NOT NULL,
+    "Price" DOUBLE PRECISION NOT NULL,
+    "Threshold" DOUBLE PRECISION NOT NULL,
+    "Direction" VARCHAR(10) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS "TriggeredAlert_TriggeredAt_idx" ON "TriggeredAlert"("TriggeredAt");
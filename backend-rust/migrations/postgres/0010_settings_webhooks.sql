@@ -0,0 +1,3 @@
+-- Webhook targets for quote fetch and price alert events, with the signing secret
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "WebhookUrls" JSONB NOT NULL DEFAULT '[]';
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "WebhookSecret" TEXT;
@@ -0,0 +1,2 @@
+-- Investments tracked for their quotes only, without being held
+ALTER TABLE "Investment" ADD COLUMN IF NOT EXISTS "Watchlist" BOOLEAN NOT NULL DEFAULT FALSE;
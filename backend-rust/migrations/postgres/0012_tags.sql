@@ -0,0 +1,16 @@
+-- Tags to group investments, e.g. "retirement"
+CREATE TABLE IF NOT EXISTS "Tag" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "Name" VARCHAR(100) NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "Tag_Name_idx" ON "Tag"(LOWER("Name"));
+
+-- Tags assigned to investments
+CREATE TABLE IF NOT EXISTS "InvestmentTag" (
+    "InvestmentID" BIGINT NOT NULL REFERENCES "Investment"("ID") ON DELETE CASCADE,
+    "TagID" BIGINT NOT NULL REFERENCES "Tag"("ID") ON DELETE CASCADE,
+    PRIMARY KEY ("InvestmentID", "TagID")
+);
+
+CREATE INDEX IF NOT EXISTS "InvestmentTag_TagID_idx" ON "InvestmentTag"("TagID");
@@ -0,0 +1,7 @@
+-- Reference of a movement in an external system, e.g. a broker's order ID, so that
+-- imports can be repeated without creating duplicates
+ALTER TABLE "Movement" ADD COLUMN IF NOT EXISTS "ExternalID" VARCHAR(255);
+
+-- Unique per portfolio, movements without portfolio count as one portfolio
+CREATE UNIQUE INDEX IF NOT EXISTS "Movement_ExternalID_idx"
+    ON "Movement"(COALESCE("PortfolioID", 0), "ExternalID") WHERE "ExternalID" IS NOT NULL;
@@ -0,0 +1,14 @@
+-- Prices without source never matched the (Date, InvestmentID, Source) upsert key, so
+-- entering them again added duplicates. They are stored as manual prices from now on:
+-- keep the latest manual price per investment and day, then fill in the source.
+DELETE FROM "InvestmentPrice" AS older
+WHERE (older."Source" IS NULL OR older."Source" = 'manual')
+  AND EXISTS (
+      SELECT 1 FROM "InvestmentPrice" AS newer
+      WHERE newer."Date" = older."Date"
+        AND newer."InvestmentID" = older."InvestmentID"
+        AND (newer."Source" IS NULL OR newer."Source" = 'manual')
+        AND newer."id" > older."id"
+  );
+
+UPDATE "InvestmentPrice" SET "Source" = 'manual' WHERE "Source" IS NULL;
@@ -0,0 +1,5 @@
+-- Developments are calculated with decimals; store them like movements and prices
+ALTER TABLE "Development"
+    ALTER COLUMN "Price" TYPE NUMERIC,
+    ALTER COLUMN "Quantity" TYPE NUMERIC,
+    ALTER COLUMN "Value" TYPE NUMERIC;
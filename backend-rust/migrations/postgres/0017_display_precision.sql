@@ -0,0 +1,6 @@
+-- Decimal places that values, prices and quantities in API responses are rounded to,
+-- NULL for all places, and the rounding mode
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "ValueDecimals" INTEGER;
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "PriceDecimals" INTEGER;
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "QuantityDecimals" INTEGER;
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "RoundingMode" VARCHAR(20) NOT NULL DEFAULT 'half_even';
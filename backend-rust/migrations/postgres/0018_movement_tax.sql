@@ -0,0 +1,3 @@
+-- Taxes withheld or paid on a movement, e.g. capital gains tax on a sell or withholding
+-- tax on a payout, kept apart from the fee
+ALTER TABLE "Movement" ADD COLUMN IF NOT EXISTS "Tax" NUMERIC;
@@ -0,0 +1,9 @@
+-- Holdings frozen at a date, unaffected by later price or movement corrections
+CREATE TABLE IF NOT EXISTS "Snapshot" (
+    "Date" DATE NOT NULL,
+    "InvestmentID" BIGINT NOT NULL,
+    "Price" NUMERIC NOT NULL,
+    "Quantity" NUMERIC NOT NULL,
+    "Value" NUMERIC NOT NULL,
+    PRIMARY KEY("Date", "InvestmentID")
+);
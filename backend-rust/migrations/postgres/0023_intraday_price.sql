@@ -0,0 +1,12 @@
+-- Latest live quote per investment, kept apart from the daily closes in "InvestmentPrice"
+CREATE TABLE IF NOT EXISTS "IntradayPrice" (
+    "InvestmentID" BIGINT PRIMARY KEY REFERENCES "Investment"("ID") ON DELETE CASCADE,
+    -- Price in the base currency
+    "Price" NUMERIC NOT NULL,
+    "Currency" VARCHAR(3) NOT NULL,
+    "OriginalPrice" NUMERIC NOT NULL,
+    -- Time of the last trade reported by the provider
+    "QuotedAt" TIMESTAMPTZ NOT NULL,
+    "FetchedAt" TIMESTAMPTZ NOT NULL,
+    "Source" VARCHAR(20) NOT NULL
+);
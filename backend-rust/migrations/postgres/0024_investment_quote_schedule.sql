@@ -0,0 +1,4 @@
+-- Investments left out of the scheduled quote fetch, e.g. delisted ones
+ALTER TABLE "Investment" ADD COLUMN IF NOT EXISTS "QuoteFetchEnabled" BOOLEAN NOT NULL DEFAULT TRUE;
+-- Days between scheduled quote fetches of rarely traded investments, every run if NULL
+ALTER TABLE "Investment" ADD COLUMN IF NOT EXISTS "QuoteFetchIntervalDays" BIGINT;
@@ -0,0 +1,7 @@
+-- API keys of the quote providers stored through the settings API, taking precedence
+-- over the keys of the config file and environment
+CREATE TABLE IF NOT EXISTS "ProviderConfig" (
+    "Provider" VARCHAR(20) PRIMARY KEY,
+    "ApiKey" TEXT NOT NULL,
+    "UpdatedAt" TIMESTAMPTZ NOT NULL
+);
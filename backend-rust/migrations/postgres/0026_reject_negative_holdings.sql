@@ -0,0 +1,2 @@
+-- Reject sells of more than the holding on their date
+ALTER TABLE "Settings" ADD COLUMN IF NOT EXISTS "RejectNegativeHoldings" BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- Investments no longer in use, e.g. sold years ago, hidden from lists and the quote fetch
+ALTER TABLE "Investment" ADD COLUMN IF NOT EXISTS "Archived" BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,8 @@
+-- Bearer tokens for the API; only the SHA-256 hash of a token is stored
+CREATE TABLE IF NOT EXISTS "ApiToken" (
+    "ID" BIGSERIAL PRIMARY KEY,
+    "Name" TEXT NOT NULL,
+    "Scope" VARCHAR(10) NOT NULL,
+    "TokenHash" VARCHAR(64) NOT NULL UNIQUE,
+    "CreatedAt" TIMESTAMPTZ NOT NULL
+);
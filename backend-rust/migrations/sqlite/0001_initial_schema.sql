@@ -0,0 +1,97 @@
+-- Bootstrap schema. Tables may already exist in databases created before
+-- versioned migrations, so everything here is idempotent.
+
+CREATE TABLE IF NOT EXISTS ActionType (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    Name VARCHAR(10) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS Investment (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    Name TEXT,
+    ISIN VARCHAR(20),
+    ShortName VARCHAR(30),
+    QuoteProvider VARCHAR(100),
+    TickerSymbol VARCHAR(20)
+);
+
+CREATE TABLE IF NOT EXISTS Portfolio (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    Name TEXT NOT NULL,
+    Description TEXT
+);
+
+CREATE TABLE IF NOT EXISTS Movement (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    Date DATE,
+    Quantity DECIMAL,
+    Amount DECIMAL,
+    Fee DECIMAL,
+    ActionID INTEGER REFERENCES ActionType(ID),
+    InvestmentID INTEGER REFERENCES Investment(ID),
+    PortfolioID INTEGER REFERENCES Portfolio(ID) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS Movement_ActionID_idx ON Movement(ActionID);
+CREATE INDEX IF NOT EXISTS Movement_InvestmentID_idx ON Movement(InvestmentID);
+CREATE INDEX IF NOT EXISTS Movement_PortfolioID_idx ON Movement(PortfolioID);
+
+-- Deposits and withdrawals of uninvested cash
+CREATE TABLE IF NOT EXISTS CashMovement (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    Date DATE NOT NULL,
+    ActionID INTEGER NOT NULL REFERENCES ActionType(ID),
+    Amount DECIMAL NOT NULL,
+    PortfolioID INTEGER REFERENCES Portfolio(ID) ON DELETE SET NULL,
+    Description TEXT
+);
+
+CREATE INDEX IF NOT EXISTS CashMovement_PortfolioID_idx ON CashMovement(PortfolioID);
+
+CREATE TABLE IF NOT EXISTS InvestmentPrice (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    Date DATE,
+    InvestmentID INTEGER,
+    Price DECIMAL,
+    Source VARCHAR(20),
+    Currency VARCHAR(3),
+    OriginalPrice DECIMAL,
+    UNIQUE(Date, InvestmentID, Source)
+);
+
+CREATE INDEX IF NOT EXISTS InvestmentPrice_InvestmentID_idx ON InvestmentPrice(InvestmentID);
+
+CREATE TABLE IF NOT EXISTS Settings (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    BaseCurrency VARCHAR(3) NOT NULL,
+    CostBasisMethod VARCHAR(10) NOT NULL DEFAULT 'fifo'
+);
+
+-- Cache of exchange rates
+CREATE TABLE IF NOT EXISTS FxRate (
+    Date DATE NOT NULL,
+    FromCurrency VARCHAR(3) NOT NULL,
+    ToCurrency VARCHAR(3) NOT NULL,
+    Rate DECIMAL NOT NULL,
+    PRIMARY KEY(Date, FromCurrency, ToCurrency)
+);
+
+-- Outcome of every quote fetch
+CREATE TABLE IF NOT EXISTS QuoteFetchLog (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    FetchedAt DATETIME NOT NULL,
+    InvestmentID INTEGER NOT NULL,
+    Provider VARCHAR(20),
+    Success BOOLEAN NOT NULL,
+    Error TEXT,
+    QuotesStored INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS QuoteFetchLog_InvestmentID_idx ON QuoteFetchLog(InvestmentID, FetchedAt);
+
+-- Seed data
+INSERT OR IGNORE INTO ActionType (ID, Name) VALUES
+    (1, 'Buy'), (2, 'Sell'), (3, 'Payout'), (4, 'Deposit'), (5, 'Withdrawal');
+
+INSERT INTO Settings (ID, BaseCurrency)
+SELECT 1, 'EUR' WHERE NOT EXISTS (SELECT 1 FROM Settings);
@@ -0,0 +1,2 @@
+-- Stock splits, recorded as movements with the split ratio as quantity
+INSERT OR IGNORE INTO ActionType (ID, Name) VALUES (6, 'Split');
@@ -0,0 +1,2 @@
+-- Transfers of shares between portfolios, recorded as a pair of movements
+INSERT OR IGNORE INTO ActionType (ID, Name) VALUES (7, 'TransferOut'), (8, 'TransferIn');
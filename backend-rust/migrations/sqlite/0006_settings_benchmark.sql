@@ -0,0 +1,3 @@
+-- Benchmark for performance comparisons: an investment or a ticker symbol
+ALTER TABLE Settings ADD COLUMN BenchmarkInvestmentID INTEGER;
+ALTER TABLE Settings ADD COLUMN BenchmarkTicker VARCHAR(20);
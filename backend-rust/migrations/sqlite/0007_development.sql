@@ -0,0 +1,9 @@
+-- Calculated developments, kept up to date incrementally when prices or movements change
+CREATE TABLE IF NOT EXISTS Development (
+    InvestmentID INTEGER NOT NULL,
+    Date DATE NOT NULL,
+    Price REAL NOT NULL,
+    Quantity REAL NOT NULL,
+    Value REAL NOT NULL,
+    PRIMARY KEY(InvestmentID, Date)
+);
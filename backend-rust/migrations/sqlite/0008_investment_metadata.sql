@@ -0,0 +1,3 @@
+-- Trading currency and asset class, filled in from the quote providers
+ALTER TABLE Investment ADD COLUMN Currency VARCHAR(3);
+ALTER TABLE Investment ADD COLUMN AssetClass VARCHAR(20);
@@ -0,0 +1,25 @@
+-- Alerts on the price of an investment, evaluated after every quote fetch
+CREATE TABLE IF NOT EXISTS PriceAlert (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    InvestmentID INTEGER NOT NULL REFERENCES Investment(ID) ON DELETE CASCADE,
+    Threshold REAL NOT NULL,
+    -- 'above' or 'below'
+    Direction VARCHAR(10) NOT NULL,
+    Active BOOLEAN NOT NULL DEFAULT 1
+);
+
+CREATE INDEX IF NOT EXISTS PriceAlert_InvestmentID_idx ON PriceAlert(InvestmentID);
+
+-- Alerts that were triggered, with the price that crossed the threshold
+CREATE TABLE IF NOT EXISTS TriggeredAlert (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    AlertID INTEGER NOT NULL REFERENCES PriceAlert(ID) ON DELETE CASCADE,
+    InvestmentID INTEGER NOT NULL,
+    TriggeredAt DATETIME NOT NULL,
+    PriceDate DATE NOT NULL,
+    Price REAL NOT NULL,
+    Threshold REAL NOT NULL,
+    Direction VARCHAR(10) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS TriggeredAlert_TriggeredAt_idx ON TriggeredAlert(TriggeredAt);
@@ -0,0 +1,6 @@
+-- Quote sources in order of preference, used when several sources have a price for
+-- the same investment and date
+ALTER TABLE Settings ADD COLUMN PriceSourcePriority TEXT NOT NULL DEFAULT '[]';
+
+-- Source of the quote a development is valued with, empty for transaction prices
+ALTER TABLE Development ADD COLUMN Source VARCHAR(50);
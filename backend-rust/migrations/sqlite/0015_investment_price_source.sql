@@ -0,0 +1,14 @@
+-- Prices without source never matched the (Date, InvestmentID, Source) upsert key, so
+-- entering them again added duplicates. They are stored as manual prices from now on:
+-- keep the latest manual price per investment and day, then fill in the source.
+DELETE FROM InvestmentPrice
+WHERE (Source IS NULL OR Source = 'manual')
+  AND EXISTS (
+      SELECT 1 FROM InvestmentPrice AS newer
+      WHERE newer.Date = InvestmentPrice.Date
+        AND newer.InvestmentID = InvestmentPrice.InvestmentID
+        AND (newer.Source IS NULL OR newer.Source = 'manual')
+        AND newer.id > InvestmentPrice.id
+  );
+
+UPDATE InvestmentPrice SET Source = 'manual' WHERE Source IS NULL;
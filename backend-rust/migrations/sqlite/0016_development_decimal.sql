@@ -0,0 +1,16 @@
+-- Developments are calculated with decimals; store them like movements and prices
+CREATE TABLE Development_new (
+    InvestmentID INTEGER NOT NULL,
+    Date DATE NOT NULL,
+    Price DECIMAL NOT NULL,
+    Quantity DECIMAL NOT NULL,
+    Value DECIMAL NOT NULL,
+    Source VARCHAR(50),
+    PRIMARY KEY(InvestmentID, Date)
+);
+
+INSERT INTO Development_new (InvestmentID, Date, Price, Quantity, Value, Source)
+SELECT InvestmentID, Date, Price, Quantity, Value, Source FROM Development;
+
+DROP TABLE Development;
+ALTER TABLE Development_new RENAME TO Development;
@@ -0,0 +1,6 @@
+-- Decimal places that values, prices and quantities in API responses are rounded to,
+-- NULL for all places, and the rounding mode
+ALTER TABLE Settings ADD COLUMN ValueDecimals INTEGER;
+ALTER TABLE Settings ADD COLUMN PriceDecimals INTEGER;
+ALTER TABLE Settings ADD COLUMN QuantityDecimals INTEGER;
+ALTER TABLE Settings ADD COLUMN RoundingMode VARCHAR(20) NOT NULL DEFAULT 'half_even';
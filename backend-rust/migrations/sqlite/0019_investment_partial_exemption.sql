@@ -0,0 +1,2 @@
+-- Share of fund income exempt from German tax (Teilfreistellung) in percent, e.g. 30
+ALTER TABLE Investment ADD COLUMN PartialExemption DECIMAL;
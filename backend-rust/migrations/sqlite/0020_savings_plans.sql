@@ -0,0 +1,12 @@
+-- Recurring purchases of an investment
+CREATE TABLE IF NOT EXISTS SavingsPlan (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    InvestmentID INTEGER NOT NULL REFERENCES Investment(ID) ON DELETE CASCADE,
+    Amount DECIMAL NOT NULL,
+    -- 'weekly', 'monthly', 'quarterly' or 'yearly'
+    Interval VARCHAR(10) NOT NULL,
+    StartDate DATE NOT NULL,
+    EndDate DATE
+);
+
+CREATE INDEX IF NOT EXISTS SavingsPlan_InvestmentID_idx ON SavingsPlan(InvestmentID);
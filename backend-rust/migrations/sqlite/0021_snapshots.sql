@@ -0,0 +1,9 @@
+-- Holdings frozen at a date, unaffected by later price or movement corrections
+CREATE TABLE IF NOT EXISTS Snapshot (
+    Date DATE NOT NULL,
+    InvestmentID INTEGER NOT NULL,
+    Price DECIMAL NOT NULL,
+    Quantity DECIMAL NOT NULL,
+    Value DECIMAL NOT NULL,
+    PRIMARY KEY(Date, InvestmentID)
+);
@@ -0,0 +1,26 @@
+-- Dividends and splits reported by the quote providers
+CREATE TABLE IF NOT EXISTS DividendEvent (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    InvestmentID INTEGER NOT NULL REFERENCES Investment(ID) ON DELETE CASCADE,
+    -- Ex-dividend date
+    Date DATE NOT NULL,
+    -- Amount per share in the currency of the quotes
+    Amount DECIMAL NOT NULL,
+    Currency VARCHAR(3),
+    Source VARCHAR(20) NOT NULL,
+    -- 'pending' until a payout is recorded for it, 'confirmed' or 'dismissed'
+    Status VARCHAR(10) NOT NULL DEFAULT 'pending',
+    MovementID INTEGER REFERENCES Movement(ID) ON DELETE SET NULL,
+    UNIQUE(InvestmentID, Date, Source)
+);
+
+CREATE TABLE IF NOT EXISTS SplitEvent (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    InvestmentID INTEGER NOT NULL REFERENCES Investment(ID) ON DELETE CASCADE,
+    Date DATE NOT NULL,
+    -- New shares per `Denominator` old shares
+    Numerator DECIMAL NOT NULL,
+    Denominator DECIMAL NOT NULL,
+    Source VARCHAR(20) NOT NULL,
+    UNIQUE(InvestmentID, Date, Source)
+);
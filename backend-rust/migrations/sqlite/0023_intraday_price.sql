@@ -0,0 +1,12 @@
+-- Latest live quote per investment, kept apart from the daily closes in InvestmentPrice
+CREATE TABLE IF NOT EXISTS IntradayPrice (
+    InvestmentID INTEGER PRIMARY KEY REFERENCES Investment(ID) ON DELETE CASCADE,
+    -- Price in the base currency
+    Price DECIMAL NOT NULL,
+    Currency VARCHAR(3) NOT NULL,
+    OriginalPrice DECIMAL NOT NULL,
+    -- Time of the last trade reported by the provider
+    QuotedAt DATETIME NOT NULL,
+    FetchedAt DATETIME NOT NULL,
+    Source VARCHAR(20) NOT NULL
+);
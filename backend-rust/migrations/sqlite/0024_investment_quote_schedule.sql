@@ -0,0 +1,4 @@
+-- Investments left out of the scheduled quote fetch, e.g. delisted ones
+ALTER TABLE Investment ADD COLUMN QuoteFetchEnabled BOOLEAN NOT NULL DEFAULT 1;
+-- Days between scheduled quote fetches of rarely traded investments, every run if NULL
+ALTER TABLE Investment ADD COLUMN QuoteFetchIntervalDays INTEGER;
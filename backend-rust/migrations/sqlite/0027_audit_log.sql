@@ -0,0 +1,7 @@
+-- Record of changes that cannot be told from the data afterwards, e.g. investment merges
+CREATE TABLE IF NOT EXISTS AuditLog (
+    ID INTEGER PRIMARY KEY AUTOINCREMENT,
+    CreatedAt DATETIME NOT NULL,
+    Action VARCHAR(50) NOT NULL,
+    Details TEXT NOT NULL
+);
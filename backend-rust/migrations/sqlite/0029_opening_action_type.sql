@@ -0,0 +1,10 @@
+-- Positions held before the first recorded movement, with their cost basis.
+-- A custom action type that already has the ID gets a new one.
+INSERT INTO ActionType (Name, Effect) SELECT Name, Effect FROM ActionType WHERE ID = 9;
+UPDATE Movement SET ActionID = (SELECT MAX(ID) FROM ActionType)
+WHERE ActionID = 9 AND EXISTS (SELECT 1 FROM ActionType WHERE ID = 9);
+UPDATE CashMovement SET ActionID = (SELECT MAX(ID) FROM ActionType)
+WHERE ActionID = 9 AND EXISTS (SELECT 1 FROM ActionType WHERE ID = 9);
+DELETE FROM ActionType WHERE ID = 9;
+
+INSERT INTO ActionType (ID, Name, Effect) VALUES (9, 'Opening', 'opening');
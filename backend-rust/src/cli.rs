@@ -0,0 +1,400 @@
+use crate::config::Config;
+use crate::models::{DataExport, ImportMode, ImportSummary, TokenScope};
+use crate::repository::Repositories;
+use crate::routes::{self, RouterSettings};
+use crate::services::api_tokens::IssuedToken;
+use crate::services::quote_fetcher::QuoteFetchResult;
+use crate::services::quotes::ProviderApiKeys;
+use crate::services::{
+    ApiTokenService, CashLedgerService, DashboardService, DataRevision, DataTransferService,
+    DevelopmentCache, EmailNotifier, PendingDevelopments, PortfolioCalculator, PositionCalculator,
+    QuoteFetchStatusTracker, QuoteFetcherService, QuoteScheduler, ReportService, WebhookNotifier,
+    WeeklySummaryScheduler,
+};
+use crate::{db, telemetry};
+use axum_server::tls_rustls::RustlsConfig;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Parser)]
+#[command(name = "portfoliodb-rust", version, about = "PortfolioDB backend")]
+pub struct Cli {
+    /// Config file [default: portfoliodb.toml in the working directory]
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Directory of the SQLite database `portfoliodb.db`, created if missing; ignored
+    /// if DATABASE_URL is set [env: DATA_DIR]
+    #[arg(long, global = true)]
+    pub data_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Run the HTTP server (default)
+    Serve,
+    /// Apply pending database migrations and exit
+    Migrate,
+    /// Import a JSON export, as written by `export` or `GET /api/export`
+    Import {
+        file: PathBuf,
+        #[arg(long, value_enum, default_value_t = ImportModeArg::Merge)]
+        mode: ImportModeArg,
+    },
+    /// Fetch quotes for all investments with a quote provider, or only the given ones
+    FetchQuotes {
+        #[arg(long = "investment", value_name = "ID")]
+        investments: Vec<i64>,
+    },
+    /// Write all data as JSON export
+    Export {
+        /// Output file [default: stdout]
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Issue an API token and print it, e.g. to regain access after losing the admin token
+    CreateToken {
+        #[arg(long)]
+        name: String,
+        #[arg(long, value_enum, default_value_t = TokenScopeArg::Admin)]
+        scope: TokenScopeArg,
+    },
+}
+
+impl Command {
+    /// Administrative commands keep stdout free for their output
+    pub fn log_output(&self) -> telemetry::LogOutput {
+        match self {
+            Command::Serve => telemetry::LogOutput::Stdout,
+            _ => telemetry::LogOutput::Stderr,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportModeArg {
+    Merge,
+    Replace,
+}
+
+impl From<ImportModeArg> for ImportMode {
+    fn from(mode: ImportModeArg) -> Self {
+        match mode {
+            ImportModeArg::Merge => ImportMode::Merge,
+            ImportModeArg::Replace => ImportMode::Replace,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TokenScopeArg {
+    Read,
+    Write,
+    Admin,
+}
+
+impl From<TokenScopeArg> for TokenScope {
+    fn from(scope: TokenScopeArg) -> Self {
+        match scope {
+            TokenScopeArg::Read => TokenScope::Read,
+            TokenScopeArg::Write => TokenScope::Write,
+            TokenScopeArg::Admin => TokenScope::Admin,
+        }
+    }
+}
+
+/// Run a command; all but `serve` exit when done
+pub async fn run(command: Command, config: &Config) -> anyhow::Result<()> {
+    // Connecting runs the migrations
+    let connect = || db::connect_with(&config.database_url, &config.database_pool);
+
+    match command {
+        Command::Serve => serve(config).await?,
+        Command::Migrate => {
+            let repos = connect().await?;
+            let status = repos.health.check().await?;
+            println!(
+                "Database is at migration {}",
+                status.applied_version.unwrap_or_default()
+            );
+        }
+        Command::Import { file, mode } => {
+            let repos = connect().await?;
+            let summary = import(&repos, &file, mode.into()).await?;
+            println!(
+                "Imported {} portfolio(s), {} investment(s), {} movement(s), {} cash movement(s) and {} price(s)",
+                summary.portfolios,
+                summary.investments,
+                summary.movements,
+                summary.cash_movements,
+                summary.prices
+            );
+        }
+        Command::FetchQuotes { investments } => {
+            let repos = connect().await?;
+            let ids = (!investments.is_empty()).then_some(investments);
+            let results = fetch_quotes(
+                &repos,
+                config.api_keys.clone(),
+                config.quote_fetch_days,
+                ids,
+            )
+            .await?;
+            let mut failed = 0;
+            for result in &results {
+                if result.success {
+                    println!(
+                        "Investment {}: {} quote(s) from {}",
+                        result.investment_id,
+                        result.quotes_stored,
+                        result.provider.as_deref().unwrap_or("unknown provider")
+                    );
+                } else {
+                    failed += 1;
+                    println!(
+                        "Investment {}: failed: {}",
+                        result.investment_id,
+                        result.error.as_deref().unwrap_or("unknown error")
+                    );
+                }
+            }
+            if failed > 0 {
+                return Err(anyhow::anyhow!(
+                    "{} of {} quote fetches failed",
+                    failed,
+                    results.len()
+                ));
+            }
+        }
+        Command::Export { output } => {
+            let repos = connect().await?;
+            match output {
+                Some(path) => {
+                    let mut file = std::fs::File::create(&path).map_err(|e| {
+                        anyhow::anyhow!("Failed to create {}: {}", path.display(), e)
+                    })?;
+                    export(&repos, &mut file).await?;
+                }
+                None => export(&repos, &mut std::io::stdout().lock()).await?,
+            }
+        }
+        Command::CreateToken { name, scope } => {
+            let repos = connect().await?;
+            let issued = create_token(&repos, &name, scope.into()).await?;
+            println!("{}", issued.token);
+        }
+    }
+    Ok(())
+}
+
+/// Start the HTTP server and the scheduled quote fetch
+pub async fn serve(config: &Config) -> anyhow::Result<()> {
+    // Setup database connection, run migrations and create repository
+    // implementations for the backend selected by the database URL
+    tracing::info!("Connecting to database: {}", config.database_url);
+    let repos = db::connect_with(&config.database_url, &config.database_pool).await?;
+
+    // Stored developments are recalculated from the changed dates on the next read. The
+    // changes are recorded before the cache is dropped, so no outdated result gets cached.
+    let pending_developments = config
+        .incremental_developments
+        .then(PendingDevelopments::new);
+    let repos = match &pending_developments {
+        Some(pending) => repos.with_change_listener(Arc::new(pending.clone())),
+        None => repos,
+    };
+
+    // Cached developments are dropped on every write, including scheduled quote fetches
+    let development_cache = DevelopmentCache::new();
+    let repos = repos.with_change_listener(Arc::new(development_cache.clone()));
+
+    // Read endpoints answer 304 Not Modified until the next write
+    let data_revision = DataRevision::new();
+    let repos = repos.with_change_listener(Arc::new(data_revision.clone()));
+
+    tracing::info!("Database connection established");
+
+    // Email price alerts and the weekly summary if an SMTP host is configured
+    let email = config.email.as_ref().map(EmailNotifier::new).transpose()?;
+    if let Some(email_config) = &config.email {
+        tracing::info!(
+            "Email notifications enabled via {}:{}",
+            email_config.smtp_host,
+            email_config.smtp_port
+        );
+    }
+    let summary_schedule = config
+        .email
+        .as_ref()
+        .and_then(|c| c.weekly_summary_schedule.as_deref());
+    if let (Some(email), Some(schedule)) = (&email, summary_schedule) {
+        WeeklySummaryScheduler::new(schedule, Arc::new(report_service(&repos)), email.clone())?
+            .spawn();
+    }
+
+    // Start background quote fetching if a schedule is configured
+    let fetch_status = QuoteFetchStatusTracker::new();
+    if let Some(ref schedule) = config.quote_fetch_schedule {
+        let mut scheduler = QuoteScheduler::new(
+            schedule,
+            repos.investments.clone(),
+            repos.investment_prices.clone(),
+            repos.settings.clone(),
+            repos.quote_fetch_log.clone(),
+            repos.fx_rates.clone(),
+            fetch_status.clone(),
+        )?
+        .with_api_keys(config.api_keys.clone())
+        .with_provider_configs(repos.provider_configs.clone())
+        .with_fetch_days(config.quote_fetch_days)
+        .with_price_alerts(repos.price_alerts.clone())
+        .with_corporate_events(repos.corporate_events.clone())
+        .with_webhooks(WebhookNotifier::new(repos.settings.clone()));
+        if let Some(email) = &email {
+            scheduler = scheduler.with_email(email.clone());
+        }
+        if config.quote_fetch_held_only {
+            let positions = PositionCalculator::new(repos.movements.clone())
+                .with_action_types(repos.action_types.clone());
+            scheduler = scheduler.with_held_only(Arc::new(positions));
+        }
+        scheduler.spawn();
+    }
+
+    // Create router with injected dependencies
+    let settings = RouterSettings {
+        cors_allowed_origins: config.cors_allowed_origins.clone(),
+        api_keys: config.api_keys.clone(),
+        email,
+        quote_fetch_days: Some(config.quote_fetch_days),
+        request_timeout: Some(Duration::from_secs(config.request_timeout_seconds)),
+        import_body_limit: Some(config.import_body_limit_mb * 1024 * 1024),
+    };
+    let app = routes::create_router(
+        repos,
+        fetch_status,
+        development_cache,
+        data_revision,
+        pending_developments,
+        settings,
+    );
+
+    // Start server, with TLS termination if a certificate is configured
+    let addr: SocketAddr = format!("{}:{}", config.host, config.port).parse()?;
+    match &config.tls {
+        Some(tls) => {
+            // Fails only if a provider is installed already, which is fine
+            let _ = rustls::crypto::ring::default_provider().install_default();
+            let rustls_config = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to load TLS certificate {} or key {}: {}",
+                        tls.cert_path.display(),
+                        tls.key_path.display(),
+                        e
+                    )
+                })?;
+            tracing::info!("Server listening on https://{}", addr);
+            axum_server::bind_rustls(addr, rustls_config)
+                .serve(app.into_make_service())
+                .await?;
+        }
+        None => {
+            tracing::info!("Server listening on http://{}", addr);
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            axum::serve(listener, app).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Report service for the weekly summary email, which needs no development cache
+fn report_service(repos: &Repositories) -> ReportService {
+    let calculator = Arc::new(
+        PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone())
+            .with_action_types(repos.action_types.clone())
+            .with_price_sources(repos.settings.clone(), repos.investments.clone()),
+    );
+    let cash_ledger = Arc::new(
+        CashLedgerService::new(repos.cash_movements.clone(), repos.movements.clone())
+            .with_action_types(repos.action_types.clone()),
+    );
+    ReportService::new(
+        Arc::new(DashboardService::new(calculator, cash_ledger)),
+        repos.movements.clone(),
+        repos.settings.clone(),
+    )
+    .with_action_types(repos.action_types.clone())
+}
+
+/// Import the JSON export in `file`
+pub async fn import(
+    repos: &Repositories,
+    file: &Path,
+    mode: ImportMode,
+) -> anyhow::Result<ImportSummary> {
+    let contents = std::fs::read_to_string(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file.display(), e))?;
+    let data: DataExport = serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid export file {}: {}", file.display(), e))?;
+    Ok(DataTransferService::new(repos.clone())
+        .import(&data, mode)
+        .await?)
+}
+
+/// Write all data as pretty printed JSON export
+pub async fn export(repos: &Repositories, output: &mut impl Write) -> anyhow::Result<()> {
+    let data = DataTransferService::new(repos.clone()).export().await?;
+    serde_json::to_writer_pretty(&mut *output, &data)?;
+    writeln!(output)?;
+    Ok(())
+}
+
+/// Issue an API token; unlike `POST /api/tokens` this needs no existing admin token
+pub async fn create_token(
+    repos: &Repositories,
+    name: &str,
+    scope: TokenScope,
+) -> anyhow::Result<IssuedToken> {
+    Ok(ApiTokenService::new(repos.api_tokens.clone())
+        .issue(name, scope)
+        .await?)
+}
+
+/// Fetch the quotes of the last `fetch_days` days in the base currency of the settings,
+/// recording them in the fetch log
+pub async fn fetch_quotes(
+    repos: &Repositories,
+    api_keys: ProviderApiKeys,
+    fetch_days: u32,
+    investment_ids: Option<Vec<i64>>,
+) -> anyhow::Result<Vec<QuoteFetchResult>> {
+    let base_currency = repos
+        .settings
+        .get()
+        .await?
+        .map(|s| s.base_currency)
+        .unwrap_or_else(|| "EUR".to_string());
+    let service = QuoteFetcherService::new(
+        repos.investments.clone(),
+        repos.investment_prices.clone(),
+        base_currency,
+    )
+    .with_fetch_log(repos.quote_fetch_log.clone())
+    .with_fx_rates(repos.fx_rates.clone())
+    .with_price_alerts(repos.price_alerts.clone())
+    .with_corporate_events(repos.corporate_events.clone())
+    .with_api_keys(api_keys)
+    .with_provider_configs(repos.provider_configs.clone())
+    .with_fetch_days(fetch_days);
+    Ok(service.fetch_quotes(investment_ids).await?)
+}
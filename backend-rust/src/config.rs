@@ -1,30 +1,647 @@
+use crate::db::{
+    PoolSettings, DATABASE_FILE_NAME, DEFAULT_ACQUIRE_TIMEOUT_SECONDS, DEFAULT_MAX_CONNECTIONS,
+};
+use crate::routes::{DEFAULT_IMPORT_BODY_LIMIT_MB, DEFAULT_REQUEST_TIMEOUT_SECONDS};
+use crate::services::quote_fetcher::DEFAULT_FETCH_DAYS;
+use crate::services::quotes::ProviderApiKeys;
+use serde::Deserialize;
 use std::env;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Config file read from the working directory unless `--config` names another one
+pub const DEFAULT_CONFIG_FILE: &str = "portfoliodb.toml";
+
+/// Output format of the log lines written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per line, including the fields of the current request span
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(anyhow::anyhow!(
+                "Invalid LOG_FORMAT '{}', expected 'text' or 'json'",
+                other
+            )),
+        }
+    }
+}
+
+/// Certificate and private key for serving HTTPS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// PEM file with the certificate chain
+    pub cert_path: PathBuf,
+    /// PEM file with the private key
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    /// TLS is enabled when both paths are given; giving only one is an error
+    pub fn from_paths(
+        cert_path: Option<String>,
+        key_path: Option<String>,
+    ) -> anyhow::Result<Option<Self>> {
+        match (cert_path, key_path) {
+            (Some(cert_path), Some(key_path)) => Ok(Some(Self {
+                cert_path: cert_path.into(),
+                key_path: key_path.into(),
+            })),
+            (None, None) => Ok(None),
+            _ => Err(anyhow::anyhow!(
+                "server.tls_cert_path (TLS_CERT_PATH) and server.tls_key_path (TLS_KEY_PATH) must be set together"
+            )),
+        }
+    }
+}
+
+/// How the connection to the SMTP server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SmtpTls {
+    /// Upgrade a plain connection with STARTTLS, port 587 by default
+    #[default]
+    Starttls,
+    /// TLS from the start, port 465 by default
+    Tls,
+    /// Unencrypted, port 25 by default; only for relays on the local network
+    None,
+}
+
+impl SmtpTls {
+    pub fn default_port(self) -> u16 {
+        match self {
+            SmtpTls::Starttls => 587,
+            SmtpTls::Tls => 465,
+            SmtpTls::None => 25,
+        }
+    }
+}
+
+impl FromStr for SmtpTls {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "starttls" => Ok(SmtpTls::Starttls),
+            "tls" => Ok(SmtpTls::Tls),
+            "none" => Ok(SmtpTls::None),
+            other => Err(anyhow::anyhow!(
+                "email.smtp_tls (SMTP_TLS): invalid value '{}', expected 'starttls', 'tls' or 'none'",
+                other
+            )),
+        }
+    }
+}
+
+/// Weekly summary schedule unless configured otherwise: Mondays at 08:00 UTC
+pub const DEFAULT_WEEKLY_SUMMARY_SCHEDULE: &str = "0 0 8 * * Mon";
+
+/// SMTP server and addresses for email notifications
+#[derive(Clone, PartialEq, Eq)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_tls: SmtpTls,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    /// Sender address, e.g. `PortfolioDB <portfolio@example.com>`
+    pub from: String,
+    /// Recipient addresses
+    pub to: Vec<String>,
+    /// Cron expression (with seconds) for the weekly summary, disabled if unset
+    pub weekly_summary_schedule: Option<String>,
+}
+
+impl std::fmt::Debug for EmailConfig {
+    // The password must not end up in logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmailConfig")
+            .field("smtp_host", &self.smtp_host)
+            .field("smtp_port", &self.smtp_port)
+            .field("smtp_tls", &self.smtp_tls)
+            .field("smtp_username", &self.smtp_username)
+            .field("smtp_password", &self.smtp_password.as_ref().map(|_| "***"))
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("weekly_summary_schedule", &self.weekly_summary_schedule)
+            .finish()
+    }
+}
+
+impl EmailConfig {
+    /// Email is enabled when an SMTP host is given, which then requires the addresses
+    fn from_section(section: EmailSection) -> anyhow::Result<Option<Self>> {
+        let Some(smtp_host) = section.smtp_host.filter(|h| !h.trim().is_empty()) else {
+            return Ok(None);
+        };
+
+        let smtp_tls = match section.smtp_tls {
+            Some(value) => value.parse()?,
+            None => SmtpTls::default(),
+        };
+
+        let from = section.from.ok_or_else(|| {
+            anyhow::anyhow!(
+                "email.from (EMAIL_FROM) must be set when email.smtp_host (SMTP_HOST) is"
+            )
+        })?;
+        let to = section.to.unwrap_or_default();
+        if to.is_empty() {
+            return Err(anyhow::anyhow!(
+                "email.to (EMAIL_TO) must be set when email.smtp_host (SMTP_HOST) is"
+            ));
+        }
+        for (name, address) in std::iter::once(("email.from (EMAIL_FROM)", &from))
+            .chain(to.iter().map(|address| ("email.to (EMAIL_TO)", address)))
+        {
+            address
+                .parse::<lettre::message::Mailbox>()
+                .map_err(|e| anyhow::anyhow!("{}: invalid address '{}': {}", name, address, e))?;
+        }
+
+        if section.smtp_username.is_some() != section.smtp_password.is_some() {
+            return Err(anyhow::anyhow!(
+                "email.smtp_username (SMTP_USERNAME) and email.smtp_password (SMTP_PASSWORD) must be set together"
+            ));
+        }
+
+        // An empty schedule in the file sends only price alerts
+        let weekly_summary_schedule = match section.weekly_summary_schedule {
+            Some(schedule) => Some(schedule).filter(|s| !s.trim().is_empty()),
+            None => Some(DEFAULT_WEEKLY_SUMMARY_SCHEDULE.to_string()),
+        };
+        if let Some(schedule) = &weekly_summary_schedule {
+            cron::Schedule::from_str(schedule).map_err(|e| {
+                anyhow::anyhow!(
+                    "email.weekly_summary_schedule (EMAIL_WEEKLY_SUMMARY_SCHEDULE): invalid cron expression '{}': {}",
+                    schedule,
+                    e
+                )
+            })?;
+        }
+
+        Ok(Some(Self {
+            smtp_host,
+            smtp_port: section.smtp_port.unwrap_or(smtp_tls.default_port()),
+            smtp_tls,
+            smtp_username: section.smtp_username,
+            smtp_password: section.smtp_password,
+            from,
+            to,
+            weekly_summary_schedule,
+        }))
+    }
+}
+
+/// Settings read from the config file; every field can be overridden by the environment
+/// variable named in its comment
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub server: ServerSection,
+    pub database: DatabaseSection,
+    pub providers: ProviderApiKeys,
+    pub scheduler: SchedulerSection,
+    pub cors: CorsSection,
+    pub email: EmailSection,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSection {
+    /// `HOST`
+    pub host: Option<String>,
+    /// `PORT`
+    pub port: Option<u16>,
+    /// `TLS_CERT_PATH`
+    pub tls_cert_path: Option<String>,
+    /// `TLS_KEY_PATH`
+    pub tls_key_path: Option<String>,
+    /// `REQUEST_TIMEOUT_SECONDS`
+    pub request_timeout_seconds: Option<u64>,
+    /// `IMPORT_BODY_LIMIT_MB`
+    pub import_body_limit_mb: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSection {
+    /// `DATABASE_URL`
+    pub url: Option<String>,
+    /// `DATA_DIR`, directory of the SQLite database unless `url` is set
+    pub data_dir: Option<String>,
+    /// `INCREMENTAL_DEVELOPMENTS`
+    pub incremental_developments: Option<bool>,
+    /// `DATABASE_MAX_CONNECTIONS`
+    pub max_connections: Option<u32>,
+    /// `DATABASE_ACQUIRE_TIMEOUT_SECONDS`
+    pub acquire_timeout_seconds: Option<u64>,
+    /// `DATABASE_LOG_STATEMENTS`
+    pub log_statements: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulerSection {
+    /// `QUOTE_FETCH_SCHEDULE`
+    pub quote_fetch_schedule: Option<String>,
+    /// `QUOTE_FETCH_DAYS`
+    pub quote_fetch_days: Option<u32>,
+    /// `QUOTE_FETCH_HELD_ONLY`
+    pub quote_fetch_held_only: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsSection {
+    /// `CORS_ALLOWED_ORIGINS`, comma separated
+    pub allowed_origins: Option<Vec<String>>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmailSection {
+    /// `SMTP_HOST`, email notifications are disabled if unset
+    pub smtp_host: Option<String>,
+    /// `SMTP_PORT`
+    pub smtp_port: Option<u16>,
+    /// `SMTP_TLS`: `starttls`, `tls` or `none`
+    pub smtp_tls: Option<String>,
+    /// `SMTP_USERNAME`
+    pub smtp_username: Option<String>,
+    /// `SMTP_PASSWORD`
+    pub smtp_password: Option<String>,
+    /// `EMAIL_FROM`
+    pub from: Option<String>,
+    /// `EMAIL_TO`, comma separated
+    pub to: Option<Vec<String>>,
+    /// `EMAIL_WEEKLY_SUMMARY_SCHEDULE`
+    pub weekly_summary_schedule: Option<String>,
+}
+
+impl std::fmt::Debug for EmailSection {
+    // The password must not end up in logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmailSection")
+            .field("smtp_host", &self.smtp_host)
+            .field("smtp_port", &self.smtp_port)
+            .field("smtp_tls", &self.smtp_tls)
+            .field("smtp_username", &self.smtp_username)
+            .field("smtp_password", &self.smtp_password.as_ref().map(|_| "***"))
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("weekly_summary_schedule", &self.weekly_summary_schedule)
+            .finish()
+    }
+}
+
+/// Split a comma separated list, dropping empty entries
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+impl ConfigFile {
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        toml::from_str(contents).map_err(|e| anyhow::anyhow!("{}", e))
+    }
+
+    /// Replace the values of the file with the environment variables that are set
+    pub fn apply_env(&mut self, env: impl Fn(&str) -> Option<String>) -> anyhow::Result<()> {
+        let var = |name: &str| env(name).filter(|s| !s.trim().is_empty());
+
+        if let Some(host) = var("HOST") {
+            self.server.host = Some(host);
+        }
+        if let Some(port) = var("PORT") {
+            self.server.port = Some(
+                port.trim()
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid PORT '{}': {}", port, e))?,
+            );
+        }
+        if let Some(path) = var("TLS_CERT_PATH") {
+            self.server.tls_cert_path = Some(path);
+        }
+        if let Some(path) = var("TLS_KEY_PATH") {
+            self.server.tls_key_path = Some(path);
+        }
+        if let Some(seconds) = var("REQUEST_TIMEOUT_SECONDS") {
+            self.server.request_timeout_seconds = Some(seconds.trim().parse().map_err(|e| {
+                anyhow::anyhow!("Invalid REQUEST_TIMEOUT_SECONDS '{}': {}", seconds, e)
+            })?);
+        }
+        if let Some(limit) = var("IMPORT_BODY_LIMIT_MB") {
+            self.server.import_body_limit_mb =
+                Some(limit.trim().parse().map_err(|e| {
+                    anyhow::anyhow!("Invalid IMPORT_BODY_LIMIT_MB '{}': {}", limit, e)
+                })?);
+        }
+        if let Some(url) = var("DATABASE_URL") {
+            self.database.url = Some(url);
+        }
+        if let Some(dir) = var("DATA_DIR") {
+            self.database.data_dir = Some(dir);
+        }
+        if let Some(value) = var("INCREMENTAL_DEVELOPMENTS") {
+            self.database.incremental_developments = Some(value.trim().parse().map_err(|e| {
+                anyhow::anyhow!("Invalid INCREMENTAL_DEVELOPMENTS '{}': {}", value, e)
+            })?);
+        }
+        if let Some(value) = var("DATABASE_MAX_CONNECTIONS") {
+            self.database.max_connections = Some(value.trim().parse().map_err(|e| {
+                anyhow::anyhow!("Invalid DATABASE_MAX_CONNECTIONS '{}': {}", value, e)
+            })?);
+        }
+        if let Some(value) = var("DATABASE_ACQUIRE_TIMEOUT_SECONDS") {
+            self.database.acquire_timeout_seconds = Some(value.trim().parse().map_err(|e| {
+                anyhow::anyhow!(
+                    "Invalid DATABASE_ACQUIRE_TIMEOUT_SECONDS '{}': {}",
+                    value,
+                    e
+                )
+            })?);
+        }
+        if let Some(value) = var("DATABASE_LOG_STATEMENTS") {
+            self.database.log_statements = Some(value.trim().parse().map_err(|e| {
+                anyhow::anyhow!("Invalid DATABASE_LOG_STATEMENTS '{}': {}", value, e)
+            })?);
+        }
+        if let Some(key) = var("COINGECKO_API_KEY") {
+            self.providers.coingecko_api_key = Some(key);
+        }
+        if let Some(schedule) = var("QUOTE_FETCH_SCHEDULE") {
+            self.scheduler.quote_fetch_schedule = Some(schedule);
+        }
+        if let Some(days) = var("QUOTE_FETCH_DAYS") {
+            self.scheduler.quote_fetch_days = Some(
+                days.trim()
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid QUOTE_FETCH_DAYS '{}': {}", days, e))?,
+            );
+        }
+        if let Some(value) = var("QUOTE_FETCH_HELD_ONLY") {
+            self.scheduler.quote_fetch_held_only = Some(value.trim().parse().map_err(|e| {
+                anyhow::anyhow!("Invalid QUOTE_FETCH_HELD_ONLY '{}': {}", value, e)
+            })?);
+        }
+        if let Some(origins) = var("CORS_ALLOWED_ORIGINS") {
+            self.cors.allowed_origins = Some(split_list(&origins));
+        }
+        if let Some(host) = var("SMTP_HOST") {
+            self.email.smtp_host = Some(host);
+        }
+        if let Some(port) = var("SMTP_PORT") {
+            self.email.smtp_port = Some(
+                port.trim()
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid SMTP_PORT '{}': {}", port, e))?,
+            );
+        }
+        if let Some(tls) = var("SMTP_TLS") {
+            self.email.smtp_tls = Some(tls);
+        }
+        if let Some(username) = var("SMTP_USERNAME") {
+            self.email.smtp_username = Some(username);
+        }
+        if let Some(password) = var("SMTP_PASSWORD") {
+            self.email.smtp_password = Some(password);
+        }
+        if let Some(from) = var("EMAIL_FROM") {
+            self.email.from = Some(from);
+        }
+        if let Some(to) = var("EMAIL_TO") {
+            self.email.to = Some(split_list(&to));
+        }
+        if let Some(schedule) = var("EMAIL_WEEKLY_SUMMARY_SCHEDULE") {
+            self.email.weekly_summary_schedule = Some(schedule);
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
+    /// Directory of the SQLite database if the database URL is derived from it
+    pub data_dir: Option<PathBuf>,
+    /// Size and statement logging of the database connection pool
+    pub database_pool: PoolSettings,
     pub host: String,
     pub port: u16,
+    /// Cron expression (with seconds) for the background quote fetch, disabled if unset
+    pub quote_fetch_schedule: Option<String>,
+    /// Days of history requested by a regular quote fetch; the full history is only
+    /// requested by an explicit backfill
+    pub quote_fetch_days: u32,
+    /// Only fetch quotes of investments currently held or on the watchlist in the
+    /// scheduled fetch
+    pub quote_fetch_held_only: bool,
+    /// Store developments in the database and only recalculate them from changed dates on
+    pub incremental_developments: bool,
+    pub log_format: LogFormat,
+    /// OTLP collector receiving the traces, export is disabled if unset
+    pub otlp_endpoint: Option<String>,
+    /// Service name reported with exported traces
+    pub otel_service_name: String,
+    /// Serve HTTPS instead of HTTP if set
+    pub tls: Option<TlsConfig>,
+    /// Seconds an API request may take before it is answered with 408
+    pub request_timeout_seconds: u64,
+    /// Body size limit of imports in MiB, larger bodies are answered with 413
+    pub import_body_limit_mb: usize,
+    pub api_keys: ProviderApiKeys,
+    /// Origins allowed to call the API from a browser, any origin if empty
+    pub cors_allowed_origins: Vec<String>,
+    /// Email notifications, disabled if no SMTP host is configured
+    pub email: Option<EmailConfig>,
 }
 
 impl Config {
-    pub fn from_env() -> anyhow::Result<Self> {
+    /// Load the configuration: defaults, overridden by the config file, overridden by
+    /// environment variables (including a `.env` file)
+    ///
+    /// Without `config_path` the default config file is read if it exists. A `data_dir`
+    /// given on the command line takes precedence over `DATA_DIR`.
+    pub fn load(config_path: Option<&Path>, data_dir: Option<&Path>) -> anyhow::Result<Self> {
         dotenvy::dotenv().ok();
 
-        let database_url =
-            env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite:file::memory:?cache=shared".to_string());
+        let contents = match config_path {
+            Some(path) => Some(std::fs::read_to_string(path).map_err(|e| {
+                anyhow::anyhow!("Failed to read config file {}: {}", path.display(), e)
+            })?),
+            None => std::fs::read_to_string(DEFAULT_CONFIG_FILE).ok(),
+        };
+        let path = config_path.unwrap_or(Path::new(DEFAULT_CONFIG_FILE));
+
+        let data_dir = data_dir.map(|dir| dir.display().to_string());
+        Self::from_sources(contents.as_deref(), |name| match name {
+            "DATA_DIR" if data_dir.is_some() => data_dir.clone(),
+            _ => env::var(name).ok(),
+        })
+        .map_err(|e| anyhow::anyhow!("Invalid configuration ({}): {}", path.display(), e))
+    }
+
+    /// Build the configuration from the contents of a config file and an environment
+    pub fn from_sources(
+        file: Option<&str>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> anyhow::Result<Self> {
+        let mut file = match file {
+            Some(contents) => ConfigFile::parse(contents)?,
+            None => ConfigFile::default(),
+        };
+        file.apply_env(&env)?;
+        let var = |name: &str| env(name).filter(|s| !s.trim().is_empty());
+
+        // A URL takes precedence over the data directory
+        let data_dir = file
+            .database
+            .data_dir
+            .filter(|_| file.database.url.is_none())
+            .map(PathBuf::from);
+        let database_url = match (file.database.url, &data_dir) {
+            (Some(url), _) => url,
+            (None, Some(dir)) => format!("sqlite:{}", dir.join(DATABASE_FILE_NAME).display()),
+            (None, None) => "sqlite:file::memory:?cache=shared".to_string(),
+        };
+        if database_url.trim().is_empty() {
+            return Err(anyhow::anyhow!(
+                "database.url (DATABASE_URL) must not be empty"
+            ));
+        }
+
+        let max_connections = file
+            .database
+            .max_connections
+            .unwrap_or(DEFAULT_MAX_CONNECTIONS);
+        if max_connections == 0 {
+            return Err(anyhow::anyhow!(
+                "database.max_connections (DATABASE_MAX_CONNECTIONS) must be at least 1"
+            ));
+        }
+        let acquire_timeout_seconds = file
+            .database
+            .acquire_timeout_seconds
+            .unwrap_or(DEFAULT_ACQUIRE_TIMEOUT_SECONDS);
+        if acquire_timeout_seconds == 0 {
+            return Err(anyhow::anyhow!(
+                "database.acquire_timeout_seconds (DATABASE_ACQUIRE_TIMEOUT_SECONDS) must be at least 1"
+            ));
+        }
+        let database_pool = PoolSettings {
+            max_connections,
+            acquire_timeout: std::time::Duration::from_secs(acquire_timeout_seconds),
+            log_statements: file.database.log_statements.unwrap_or(false),
+        };
+
+        let port = file.server.port.unwrap_or(8001);
+        if port == 0 {
+            return Err(anyhow::anyhow!("server.port (PORT) must not be 0"));
+        }
+
+        let request_timeout_seconds = file
+            .server
+            .request_timeout_seconds
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECONDS);
+        if request_timeout_seconds == 0 {
+            return Err(anyhow::anyhow!(
+                "server.request_timeout_seconds (REQUEST_TIMEOUT_SECONDS) must be at least 1"
+            ));
+        }
+        let import_body_limit_mb = file
+            .server
+            .import_body_limit_mb
+            .unwrap_or(DEFAULT_IMPORT_BODY_LIMIT_MB);
+        if import_body_limit_mb == 0 {
+            return Err(anyhow::anyhow!(
+                "server.import_body_limit_mb (IMPORT_BODY_LIMIT_MB) must be at least 1"
+            ));
+        }
+
+        let quote_fetch_schedule = file
+            .scheduler
+            .quote_fetch_schedule
+            .filter(|s| !s.trim().is_empty());
+        if let Some(schedule) = &quote_fetch_schedule {
+            cron::Schedule::from_str(schedule).map_err(|e| {
+                anyhow::anyhow!(
+                    "scheduler.quote_fetch_schedule (QUOTE_FETCH_SCHEDULE): invalid cron expression '{}': {}",
+                    schedule,
+                    e
+                )
+            })?;
+        }
+
+        let quote_fetch_days = file
+            .scheduler
+            .quote_fetch_days
+            .unwrap_or(DEFAULT_FETCH_DAYS);
+        if quote_fetch_days == 0 {
+            return Err(anyhow::anyhow!(
+                "scheduler.quote_fetch_days (QUOTE_FETCH_DAYS) must be at least 1"
+            ));
+        }
+
+        let cors_allowed_origins = file.cors.allowed_origins.unwrap_or_default();
+        for origin in &cors_allowed_origins {
+            let valid = (origin.starts_with("http://") || origin.starts_with("https://"))
+                && axum::http::HeaderValue::from_str(origin).is_ok();
+            if !valid {
+                return Err(anyhow::anyhow!(
+                    "cors.allowed_origins (CORS_ALLOWED_ORIGINS): invalid origin '{}', expected e.g. 'https://example.com'",
+                    origin
+                ));
+            }
+        }
+
+        let log_format = match var("LOG_FORMAT") {
+            Some(value) => value.parse()?,
+            None => LogFormat::default(),
+        };
+
+        // The exporter reads the endpoint from the same variables
+        let otlp_endpoint = var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")
+            .or_else(|| var("OTEL_EXPORTER_OTLP_ENDPOINT"));
 
-        let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let otel_service_name =
+            var("OTEL_SERVICE_NAME").unwrap_or_else(|| "portfoliodb".to_string());
 
-        let port = env::var("PORT")
-            .unwrap_or_else(|_| "8001".to_string())
-            .parse()
-            .map_err(|e| anyhow::anyhow!("Invalid PORT: {}", e))?;
+        let tls = TlsConfig::from_paths(file.server.tls_cert_path, file.server.tls_key_path)?;
+        let email = EmailConfig::from_section(file.email)?;
 
         Ok(Self {
             database_url,
-            host,
+            data_dir,
+            database_pool,
+            host: file.server.host.unwrap_or_else(|| "127.0.0.1".to_string()),
             port,
+            quote_fetch_schedule,
+            quote_fetch_days,
+            quote_fetch_held_only: file.scheduler.quote_fetch_held_only.unwrap_or(true),
+            incremental_developments: file.database.incremental_developments.unwrap_or(false),
+            log_format,
+            otlp_endpoint,
+            otel_service_name,
+            tls,
+            request_timeout_seconds,
+            import_body_limit_mb,
+            api_keys: file.providers,
+            cors_allowed_origins,
+            email,
         })
     }
 }
@@ -1,13 +1,24 @@
 use crate::error::Result;
+use sqlx::migrate::Migrator;
 use sqlx::SqlitePool;
 
+/// Versioned SQLite migrations from `migrations/sqlite`
+///
+/// Schema changes are added as new numbered files; applied files must never be edited.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+/// Version of the latest migration shipped with this build
+pub fn latest_version() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
 /// Run all database migrations
 pub async fn run_migrations(pool: &SqlitePool) -> Result<()> {
     tracing::info!("Running database migrations...");
 
     enable_foreign_keys(pool).await?;
-    create_schema(pool).await?;
-    seed_initial_data(pool).await?;
+    upgrade_unversioned_schema(pool).await?;
+    MIGRATOR.run(pool).await.map_err(sqlx::Error::from)?;
 
     tracing::info!("Database migrations completed");
     Ok(())
@@ -21,131 +32,67 @@ async fn enable_foreign_keys(pool: &SqlitePool) -> Result<()> {
     Ok(())
 }
 
-/// Create database schema
-async fn create_schema(pool: &SqlitePool) -> Result<()> {
-    tracing::info!("Creating database schema...");
-
-    // ActionType table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS ActionType (
-            ID INTEGER PRIMARY KEY AUTOINCREMENT,
-            Name VARCHAR(10) NOT NULL
-        )
-        "#,
+/// Bring databases created before versioned migrations up to the bootstrap schema
+///
+/// The bootstrap migration only creates missing tables, so columns that were
+/// added to existing tables over time are added here before it runs.
+async fn upgrade_unversioned_schema(pool: &SqlitePool) -> Result<()> {
+    let (versioned,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
     )
-    .execute(pool)
+    .fetch_one(pool)
     .await?;
+    if versioned {
+        return Ok(());
+    }
 
-    // Investment table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS Investment (
-            ID INTEGER PRIMARY KEY AUTOINCREMENT,
-            Name TEXT,
-            ISIN VARCHAR(20),
-            ShortName VARCHAR(30),
-            QuoteProvider VARCHAR(20),
-            TickerSymbol VARCHAR(20)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
-
-    // Movement table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS Movement (
-            ID INTEGER PRIMARY KEY AUTOINCREMENT,
-            Date DATE,
-            Quantity DECIMAL,
-            Amount DECIMAL,
-            Fee DECIMAL,
-            ActionID INTEGER REFERENCES ActionType(ID),
-            InvestmentID INTEGER REFERENCES Investment(ID)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
-
-    // Create indexes for Movement
-    sqlx::query("CREATE INDEX IF NOT EXISTS Movement_ActionID_idx ON Movement(ActionID)")
-        .execute(pool)
-        .await?;
-
-    sqlx::query("CREATE INDEX IF NOT EXISTS Movement_InvestmentID_idx ON Movement(InvestmentID)")
-        .execute(pool)
-        .await?;
-
-    // InvestmentPrice table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS InvestmentPrice (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            Date DATE,
-            InvestmentID INTEGER,
-            Price DECIMAL,
-            Source VARCHAR(20),
-            UNIQUE(Date, InvestmentID, Source)
-        )
-        "#,
+    add_column_if_missing(
+        pool,
+        "Movement",
+        "PortfolioID",
+        "INTEGER REFERENCES Portfolio(ID) ON DELETE SET NULL",
     )
-    .execute(pool)
     .await?;
-
-    // Create index for InvestmentPrice
-    sqlx::query("CREATE INDEX IF NOT EXISTS InvestmentPrice_InvestmentID_idx ON InvestmentPrice(InvestmentID)")
-        .execute(pool)
-        .await?;
-
-    // Settings table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS Settings (
-            ID INTEGER PRIMARY KEY AUTOINCREMENT,
-            BaseCurrency VARCHAR(3) NOT NULL
-        )
-        "#,
+    add_column_if_missing(pool, "InvestmentPrice", "Currency", "VARCHAR(3)").await?;
+    add_column_if_missing(pool, "InvestmentPrice", "OriginalPrice", "DECIMAL").await?;
+    add_column_if_missing(
+        pool,
+        "Settings",
+        "CostBasisMethod",
+        "VARCHAR(10) NOT NULL DEFAULT 'fifo'",
     )
-    .execute(pool)
     .await?;
 
-    tracing::info!("Database schema created");
     Ok(())
 }
 
-/// Seed initial data
-async fn seed_initial_data(pool: &SqlitePool) -> Result<()> {
-    tracing::info!("Seeding initial data...");
-
-    // Check if ActionTypes already exist
-    let action_type_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM ActionType")
-        .fetch_one(pool)
-        .await?;
+/// Add a column to an existing table unless it is already present
+///
+/// Tables that do not exist yet are left to the bootstrap migration.
+async fn add_column_if_missing(
+    pool: &SqlitePool,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let columns: Vec<(String,)> =
+        sqlx::query_as(&format!("SELECT name FROM pragma_table_info('{}')", table))
+            .fetch_all(pool)
+            .await?;
 
-    if action_type_count.0 == 0 {
-        tracing::info!("Inserting ActionTypes...");
-        sqlx::query(
-            "INSERT INTO ActionType (ID, Name) VALUES (1, 'Buy'), (2, 'Sell'), (3, 'Payout')",
-        )
+    if !columns.is_empty()
+        && !columns
+            .iter()
+            .any(|(name,)| name.eq_ignore_ascii_case(column))
+    {
+        tracing::info!("Adding column {}.{}", table, column);
+        sqlx::query(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))
         .execute(pool)
         .await?;
     }
 
-    // Check if Settings already exists
-    let settings_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM Settings")
-        .fetch_one(pool)
-        .await?;
-
-    if settings_count.0 == 0 {
-        tracing::info!("Inserting default Settings...");
-        sqlx::query("INSERT INTO Settings (ID, BaseCurrency) VALUES (1, 'EUR')")
-            .execute(pool)
-            .await?;
-    }
-
-    tracing::info!("Initial data seeded");
     Ok(())
 }
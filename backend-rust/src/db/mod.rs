@@ -1,3 +1,153 @@
 pub mod migrations;
+pub mod postgres;
 
 pub use migrations::run_migrations;
+
+use crate::error::{AppError, Result};
+use crate::repository::Repositories;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use sqlx::ConnectOptions;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// SQLite database file created in the data directory
+pub const DATABASE_FILE_NAME: &str = "portfoliodb.db";
+
+/// Seconds a SQLite connection waits for a lock held by another connection
+pub const SQLITE_BUSY_TIMEOUT_SECONDS: u64 = 10;
+
+/// Open connections of the pool unless configured otherwise
+pub const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+
+/// Seconds a query waits for a free connection unless configured otherwise
+pub const DEFAULT_ACQUIRE_TIMEOUT_SECONDS: u64 = 30;
+
+/// Size and logging of the connection pool, for SQLite and PostgreSQL alike
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolSettings {
+    pub max_connections: u32,
+    /// Time a query waits for a free connection before it fails
+    pub acquire_timeout: Duration,
+    /// Log every statement at info level; otherwise only at debug level, and slow
+    /// statements as warnings
+    pub log_statements: bool,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        Self {
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            acquire_timeout: Duration::from_secs(DEFAULT_ACQUIRE_TIMEOUT_SECONDS),
+            log_statements: false,
+        }
+    }
+}
+
+impl PoolSettings {
+    fn apply<O: ConnectOptions>(&self, options: O) -> O {
+        if self.log_statements {
+            options.log_statements(log::LevelFilter::Info)
+        } else {
+            options
+        }
+    }
+}
+
+/// Returns true if the database URL points to a PostgreSQL server
+pub fn is_postgres_url(database_url: &str) -> bool {
+    database_url.starts_with("postgres://") || database_url.starts_with("postgresql://")
+}
+
+/// Connect to the database selected by `database_url`, run migrations and
+/// return the matching repository implementations
+pub async fn connect(database_url: &str) -> Result<Repositories> {
+    connect_with(database_url, &PoolSettings::default()).await
+}
+
+/// Like [`connect`], with the given pool settings
+pub async fn connect_with(database_url: &str, settings: &PoolSettings) -> Result<Repositories> {
+    if is_postgres_url(database_url) {
+        let options = settings.apply(PgConnectOptions::from_str(database_url)?);
+        let pool = PgPoolOptions::new()
+            .max_connections(settings.max_connections)
+            .acquire_timeout(settings.acquire_timeout)
+            .connect_with(options)
+            .await?;
+        postgres::run_migrations(&pool).await?;
+        Ok(Repositories::postgres(pool))
+    } else {
+        let path = sqlite_file(database_url)?;
+        if let Some(path) = &path {
+            create_parent_dir(path)?;
+            tracing::info!("Using SQLite database {}", path.display());
+        }
+        let options = settings.apply(sqlite_options(database_url)?);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(settings.max_connections)
+            .acquire_timeout(settings.acquire_timeout)
+            .connect_with(options)
+            .await
+            .map_err(|e| match &path {
+                Some(path) => AppError::Internal(anyhow::anyhow!(
+                    "Cannot open SQLite database {}: {}",
+                    path.display(),
+                    e
+                )),
+                None => e.into(),
+            })?;
+        run_migrations(&pool).await?;
+        Ok(Repositories::sqlite(pool))
+    }
+}
+
+/// Connection options of a SQLite database
+///
+/// WAL mode lets the quote fetch write while the API reads, and the busy timeout makes
+/// concurrent writers wait for each other instead of failing with "database is locked".
+/// In-memory databases keep their own journal mode. A missing database file is created.
+pub fn sqlite_options(database_url: &str) -> Result<SqliteConnectOptions> {
+    Ok(SqliteConnectOptions::from_str(database_url)?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(Duration::from_secs(SQLITE_BUSY_TIMEOUT_SECONDS))
+        .foreign_keys(true))
+}
+
+/// Absolute path of the file of a SQLite database URL, `None` for in-memory databases
+/// and `file:` URIs
+pub fn sqlite_file(database_url: &str) -> Result<Option<PathBuf>> {
+    let options = SqliteConnectOptions::from_str(database_url)?;
+    let filename = options.get_filename();
+    if filename.as_os_str().is_empty()
+        || filename.to_string_lossy().starts_with("file:")
+        || database_url.contains("mode=memory")
+    {
+        return Ok(None);
+    }
+    let path = std::path::absolute(filename).map_err(|e| {
+        AppError::InvalidInput(format!(
+            "Invalid SQLite database path {}: {}",
+            filename.display(),
+            e
+        ))
+    })?;
+    Ok(Some(path))
+}
+
+/// SQLite creates missing database files, but not their directory
+fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!(
+                "Cannot create directory {} for SQLite database {}: {}",
+                dir.display(),
+                path.display(),
+                e
+            ))
+        })?;
+    }
+    Ok(())
+}
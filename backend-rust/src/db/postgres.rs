@@ -0,0 +1,23 @@
+use crate::error::Result;
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+
+/// Versioned PostgreSQL migrations from `migrations/postgres`
+///
+/// Schema changes are added as new numbered files; applied files must never be edited.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+/// Version of the latest migration shipped with this build
+pub fn latest_version() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+/// Run all database migrations against a PostgreSQL database
+pub async fn run_migrations(pool: &PgPool) -> Result<()> {
+    tracing::info!("Running PostgreSQL database migrations...");
+
+    MIGRATOR.run(pool).await.map_err(sqlx::Error::from)?;
+
+    tracing::info!("Database migrations completed");
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use crate::validation::ValidationErrors;
+use async_graphql::ErrorExtensions;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -10,8 +12,8 @@ pub enum AppError {
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
-    #[error("Not found")]
-    NotFound,
+    #[error("{entity} {id} not found")]
+    NotFound { entity: &'static str, id: String },
 
     #[error("External API error: {0}")]
     ExternalApi(String),
@@ -22,22 +24,118 @@ pub enum AppError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    #[error("Validation failed: {0}")]
+    Validation(ValidationErrors),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Invalid quote provider '{provider}'. Valid providers are: {}", .valid.join(", "))]
+    InvalidProvider {
+        provider: String,
+        valid: Vec<&'static str>,
+    },
+
+    #[error("Request timed out after {0} seconds")]
+    RequestTimeout(u64),
+
+    #[error("Request body exceeds the limit of {0} bytes")]
+    PayloadTooLarge(usize),
+
     #[error("Internal server error: {0}")]
     Internal(#[from] anyhow::Error),
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, message) = match self {
-            AppError::NotFound => (StatusCode::NOT_FOUND, "Resource not found".to_string()),
-            AppError::Database(ref e) => {
+impl AppError {
+    /// The entity of the given type and identifier does not exist
+    pub fn not_found(entity: &'static str, id: impl std::fmt::Display) -> Self {
+        AppError::NotFound {
+            entity,
+            id: id.to_string(),
+        }
+    }
+
+    /// Returns true if SQLite gave up waiting for a lock held by another connection
+    /// (`SQLITE_BUSY` or `SQLITE_LOCKED`, including their extended codes)
+    pub fn is_busy(&self) -> bool {
+        match self {
+            AppError::Database(sqlx::Error::Database(e)) => e
+                .code()
+                .and_then(|code| code.parse::<i32>().ok())
+                .is_some_and(|code| matches!(code & 0xff, 5 | 6)),
+            _ => false,
+        }
+    }
+
+    /// Stable code of the error for clients, which must not change with the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(sqlx::Error::Database(e)) if e.is_unique_violation() => "DUPLICATE",
+            AppError::Database(_) if self.is_busy() => "DATABASE_BUSY",
+            AppError::Database(_) => "DATABASE_ERROR",
+            AppError::NotFound { .. } => "NOT_FOUND",
+            AppError::ExternalApi(_) => "EXTERNAL_API_ERROR",
+            AppError::CurrencyConversion => "CURRENCY_CONVERSION_FAILED",
+            AppError::InvalidInput(_) => "INVALID_INPUT",
+            AppError::Validation(_) => "VALIDATION_FAILED",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::InvalidProvider { .. } => "INVALID_PROVIDER",
+            AppError::RequestTimeout(_) => "REQUEST_TIMEOUT",
+            AppError::PayloadTooLarge(_) => "PAYLOAD_TOO_LARGE",
+            AppError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// Values of the error a client can act on, e.g. the invalid fields
+    pub fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            AppError::Validation(errors) => Some(json!({ "fields": errors.errors })),
+            AppError::NotFound { entity, id } => Some(json!({ "entity": entity, "id": id })),
+            AppError::InvalidProvider { provider, valid } => Some(json!({
+                "provider": provider,
+                "valid_providers": valid,
+            })),
+            AppError::RequestTimeout(seconds) => Some(json!({ "timeout_seconds": seconds })),
+            AppError::PayloadTooLarge(limit) => Some(json!({ "limit_bytes": limit })),
+            _ => None,
+        }
+    }
+
+    /// Status and message for clients; details of database and internal errors are only logged
+    pub fn status_and_message(&self) -> (StatusCode, String) {
+        match self {
+            AppError::Validation(_) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Validation failed".to_string(),
+            ),
+            AppError::NotFound { .. } => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::Database(sqlx::Error::Database(e)) if e.is_unique_violation() => (
+                StatusCode::CONFLICT,
+                "A record with the same unique values already exists".to_string(),
+            ),
+            AppError::Database(e) if self.is_busy() => {
+                tracing::warn!("Database busy: {}", e);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "Database is busy, please retry".to_string(),
+                )
+            }
+            AppError::Database(e) => {
                 tracing::error!("Database error: {}", e);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "Database error".to_string(),
                 )
             }
-            AppError::ExternalApi(ref msg) => {
+            AppError::ExternalApi(msg) => {
                 tracing::error!("External API error: {}", msg);
                 (
                     StatusCode::BAD_GATEWAY,
@@ -48,20 +146,69 @@ impl IntoResponse for AppError {
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Currency conversion failed".to_string(),
             ),
-            AppError::InvalidInput(ref msg) => {
+            AppError::InvalidInput(msg) => {
                 (StatusCode::BAD_REQUEST, format!("Invalid input: {}", msg))
             }
-            AppError::Internal(ref e) => {
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
+            AppError::InvalidProvider { .. } => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::RequestTimeout(_) => (StatusCode::REQUEST_TIMEOUT, self.to_string()),
+            AppError::PayloadTooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
+            AppError::Internal(e) => {
                 tracing::error!("Internal error: {}", e);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "Internal server error".to_string(),
                 )
             }
-        };
+        }
+    }
+}
 
-        (status, Json(json!({ "error": message }))).into_response()
+/// `{"error": {"code": ..., "message": ..., "details": {...}}}`, without `details` if the
+/// error has none
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, message) = self.status_and_message();
+        let mut error = json!({ "code": self.code(), "message": message });
+        if let Some(details) = self.details() {
+            error["details"] = details;
+        }
+
+        (status, Json(json!({ "error": error }))).into_response()
+    }
+}
+
+/// GraphQL error with the message of the REST response, its status and code as `status`
+/// and `code` extensions, plus the invalid `fields` of validation errors
+impl ErrorExtensions for AppError {
+    fn extend(&self) -> async_graphql::Error {
+        let (status, message) = self.status_and_message();
+        async_graphql::Error::new(message).extend_with(|_, extensions| {
+            extensions.set("status", status.as_u16());
+            extensions.set("code", self.code());
+            if let AppError::Validation(errors) = self {
+                let fields = serde_json::to_value(&errors.errors).unwrap_or_default();
+                extensions.set(
+                    "fields",
+                    async_graphql::Value::from_json(fields).unwrap_or_default(),
+                );
+            }
+        })
     }
 }
 
 pub type Result<T> = std::result::Result<T, AppError>;
+
+/// Turns an update or delete that affected no rows into `NotFound` for the given entity
+pub fn ensure_found(
+    rows_affected: u64,
+    entity: &'static str,
+    id: impl std::fmt::Display,
+) -> Result<()> {
+    if rows_affected == 0 {
+        return Err(AppError::not_found(entity, id));
+    }
+    Ok(())
+}
@@ -1,17 +1,21 @@
-use crate::error::{AppError, Result};
-use crate::models::ActionType;
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::{ActionEffect, ActionType, MAX_BUILT_IN_ACTION_ID};
 use crate::repository::traits::ActionTypeRepository;
 use axum::{
     extract::{Path, State},
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// Longest name of an action type
+const MAX_NAME_LENGTH: usize = 50;
+
 #[derive(Debug, Serialize)]
 pub struct ActionTypeResponse {
     pub id: i64,
     pub name: String,
+    pub effect: String,
 }
 
 impl From<ActionType> for ActionTypeResponse {
@@ -19,10 +23,54 @@ impl From<ActionType> for ActionTypeResponse {
         Self {
             id: at.id,
             name: at.name,
+            effect: at.effect,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActionTypeRequest {
+    pub name: String,
+    /// `increases_quantity`, `decreases_quantity` or `cash_only`
+    pub effect: String,
+}
+
+impl ActionTypeRequest {
+    fn into_action_type(self, id: i64) -> Result<ActionType> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(AppError::InvalidInput(format!(
+                "name must have 1 to {} characters",
+                MAX_NAME_LENGTH
+            )));
+        }
+        let effect: ActionEffect = self.effect.parse()?;
+        if !effect.is_custom() {
+            return Err(AppError::InvalidInput(format!(
+                "Effect '{}' is reserved for built-in action types",
+                effect
+            )));
         }
+
+        Ok(ActionType {
+            id,
+            name,
+            effect: effect.as_str().to_string(),
+        })
     }
 }
 
+/// Built-in action types are calculated by their ID and cannot be changed
+fn ensure_custom(id: i64) -> Result<()> {
+    if id <= MAX_BUILT_IN_ACTION_ID {
+        return Err(AppError::Conflict(format!(
+            "Action type {} is built in and cannot be changed",
+            id
+        )));
+    }
+    Ok(())
+}
+
 pub async fn list_action_types(
     State(repo): State<Arc<dyn ActionTypeRepository>>,
 ) -> Result<Json<Vec<ActionTypeResponse>>> {
@@ -35,6 +83,50 @@ pub async fn get_action_type(
     State(repo): State<Arc<dyn ActionTypeRepository>>,
     Path(id): Path<i64>,
 ) -> Result<Json<ActionTypeResponse>> {
-    let action_type = repo.find_by_id(id).await?.ok_or(AppError::NotFound)?;
+    let action_type = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("ActionType", id))?;
     Ok(Json(action_type.into()))
 }
+
+/// POST /api/actiontypes - Create a custom action type
+pub async fn create_action_type(
+    State(repo): State<Arc<dyn ActionTypeRepository>>,
+    Json(req): Json<ActionTypeRequest>,
+) -> Result<Json<ActionTypeResponse>> {
+    let action_type = req.into_action_type(0)?;
+
+    let id = repo.create(&action_type).await?;
+    let created = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("ActionType", id))?;
+    Ok(Json(created.into()))
+}
+
+/// PUT /api/actiontypes/:id - Rename a custom action type or change its effect
+pub async fn update_action_type(
+    State(repo): State<Arc<dyn ActionTypeRepository>>,
+    Path(id): Path<i64>,
+    Json(req): Json<ActionTypeRequest>,
+) -> Result<Json<ActionTypeResponse>> {
+    ensure_custom(id)?;
+    let action_type = req.into_action_type(id)?;
+    ensure_found(repo.update(id, &action_type).await?, "ActionType", id)?;
+    let updated = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("ActionType", id))?;
+    Ok(Json(updated.into()))
+}
+
+/// DELETE /api/actiontypes/:id - Delete a custom action type that no movement uses
+pub async fn delete_action_type(
+    State(repo): State<Arc<dyn ActionTypeRepository>>,
+    Path(id): Path<i64>,
+) -> Result<Json<()>> {
+    ensure_custom(id)?;
+    ensure_found(repo.delete(id).await?, "ActionType", id)?;
+    Ok(Json(()))
+}
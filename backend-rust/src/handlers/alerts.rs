@@ -0,0 +1,147 @@
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::{AlertDirection, PriceAlert, TriggeredAlert, VALID_ALERT_DIRECTIONS};
+use crate::routes::PriceAlertState;
+use crate::validation::{Validate, ValidationErrors};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct PriceAlertRequest {
+    pub investment_id: i64,
+    /// Price in the base currency
+    pub threshold: f64,
+    /// `above` or `below`
+    pub direction: String,
+    /// Defaults to true
+    pub active: Option<bool>,
+}
+
+impl Validate for PriceAlertRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.positive("threshold", Some(self.threshold));
+        if self.direction.parse::<AlertDirection>().is_err() {
+            errors.add(
+                "direction",
+                format!("must be one of {}", VALID_ALERT_DIRECTIONS.join(", ")),
+            );
+        }
+    }
+}
+
+impl PriceAlertRequest {
+    async fn into_alert(self, id: i64, state: &PriceAlertState) -> Result<PriceAlert> {
+        self.validate()?;
+        if state
+            .investment_repo
+            .find_by_id(self.investment_id)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Investment {} does not exist",
+                self.investment_id
+            )));
+        }
+
+        Ok(PriceAlert {
+            id,
+            investment_id: self.investment_id,
+            threshold: self.threshold,
+            direction: self.direction.parse::<AlertDirection>()?.to_string(),
+            active: self.active.unwrap_or(true),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlertQuery {
+    pub investment_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TriggeredAlertQuery {
+    pub investment_id: Option<i64>,
+    /// Defaults to 100
+    pub limit: Option<i64>,
+}
+
+/// GET /api/alerts - List price alerts, optionally of one investment
+pub async fn list_alerts(
+    State(state): State<PriceAlertState>,
+    Query(query): Query<AlertQuery>,
+) -> Result<Json<Vec<PriceAlert>>> {
+    let alerts = state.alert_repo.find_all(query.investment_id).await?;
+    Ok(Json(alerts))
+}
+
+/// GET /api/alerts/:id - Get a single price alert
+pub async fn get_alert(
+    State(state): State<PriceAlertState>,
+    Path(id): Path<i64>,
+) -> Result<Json<PriceAlert>> {
+    let alert = state
+        .alert_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("PriceAlert", id))?;
+    Ok(Json(alert))
+}
+
+/// POST /api/alerts - Create a price alert
+pub async fn create_alert(
+    State(state): State<PriceAlertState>,
+    Json(req): Json<PriceAlertRequest>,
+) -> Result<Json<PriceAlert>> {
+    let alert = req.into_alert(0, &state).await?;
+
+    let id = state.alert_repo.create(&alert).await?;
+    let created = state
+        .alert_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("PriceAlert", id))?;
+    Ok(Json(created))
+}
+
+/// PUT /api/alerts/:id - Update a price alert, e.g. to reactivate it after it triggered
+pub async fn update_alert(
+    State(state): State<PriceAlertState>,
+    Path(id): Path<i64>,
+    Json(req): Json<PriceAlertRequest>,
+) -> Result<Json<PriceAlert>> {
+    let alert = req.into_alert(id, &state).await?;
+
+    let rows = state.alert_repo.update(id, &alert).await?;
+    ensure_found(rows, "PriceAlert", id)?;
+    let updated = state
+        .alert_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("PriceAlert", id))?;
+    Ok(Json(updated))
+}
+
+/// DELETE /api/alerts/:id - Delete a price alert together with its triggers
+pub async fn delete_alert(
+    State(state): State<PriceAlertState>,
+    Path(id): Path<i64>,
+) -> Result<Json<()>> {
+    ensure_found(state.alert_repo.delete(id).await?, "PriceAlert", id)?;
+    Ok(Json(()))
+}
+
+/// GET /api/alerts/triggered - Triggered alerts, most recent first
+pub async fn list_triggered_alerts(
+    State(state): State<PriceAlertState>,
+    Query(query): Query<TriggeredAlertQuery>,
+) -> Result<Json<Vec<TriggeredAlert>>> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let triggered = state
+        .alert_repo
+        .find_triggered(query.investment_id, limit)
+        .await?;
+    Ok(Json(triggered))
+}
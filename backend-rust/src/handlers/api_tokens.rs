@@ -0,0 +1,88 @@
+use crate::error::{AppError, Result};
+use crate::models::{ApiToken, TokenScope};
+use crate::routes::TokenGuard;
+use crate::services::api_tokens::IssuedToken;
+use crate::services::ApiTokenService;
+use axum::{
+    extract::{Path, Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTokenRequest {
+    pub name: String,
+    /// `read`, `write` or `admin`
+    pub scope: String,
+}
+
+/// GET /api/tokens - List the API tokens without their secrets
+pub async fn list_tokens(
+    State(service): State<Arc<ApiTokenService>>,
+) -> Result<Json<Vec<ApiToken>>> {
+    Ok(Json(service.list().await?))
+}
+
+/// POST /api/tokens - Issue a token; the response is the only time its secret is shown
+pub async fn create_token(
+    State(service): State<Arc<ApiTokenService>>,
+    Json(req): Json<CreateTokenRequest>,
+) -> Result<Json<IssuedToken>> {
+    let scope: TokenScope = req.scope.parse()?;
+    Ok(Json(service.issue(&req.name, scope).await?))
+}
+
+/// DELETE /api/tokens/:id - Revoke a token
+pub async fn delete_token(
+    State(service): State<Arc<ApiTokenService>>,
+    Path(id): Path<i64>,
+) -> Result<Json<()>> {
+    service.revoke(id).await?;
+    Ok(Json(()))
+}
+
+/// Middleware that requires a bearer token with the scope of its route group once any
+/// token exists
+///
+/// Groups without a fixed scope need `read` for GET and HEAD requests and `write` for
+/// everything else. The scope of the token is added to the request extensions.
+pub async fn require_token(
+    State(guard): State<TokenGuard>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let required = guard.scope.unwrap_or_else(|| {
+        if request.method().is_safe() {
+            TokenScope::Read
+        } else {
+            TokenScope::Write
+        }
+    });
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+
+    match guard.service.authorize(authorization, required).await {
+        Ok(scope) => {
+            if let Some(scope) = scope {
+                request.extensions_mut().insert(scope);
+            }
+            next.run(request).await
+        }
+        Err(e) => {
+            let unauthorized = matches!(e, AppError::Unauthorized(_));
+            let mut response = e.into_response();
+            if unauthorized {
+                response
+                    .headers_mut()
+                    .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            }
+            response
+        }
+    }
+}
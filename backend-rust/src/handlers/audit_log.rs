@@ -0,0 +1,24 @@
+use crate::error::Result;
+use crate::models::AuditLogEntry;
+use crate::repository::traits::AuditLogRepository;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    pub limit: Option<i64>,
+}
+
+/// GET /api/audit-log - Recorded changes, most recent first
+pub async fn get_audit_log(
+    State(repo): State<Arc<dyn AuditLogRepository>>,
+    Query(params): Query<AuditLogQuery>,
+) -> Result<Json<Vec<AuditLogEntry>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+    let entries = repo.find_recent(limit).await?;
+    Ok(Json(entries))
+}
@@ -0,0 +1,62 @@
+use crate::error::Result;
+use crate::services::import::{
+    BrokerImportOptions, BrokerImportResult, DegiroParser, TradeRepublicParser,
+};
+use crate::services::BrokerImportService;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct BrokerImportQuery {
+    pub portfolio_id: Option<i64>,
+    /// Create investments for unknown ISINs, the default depends on the broker
+    pub create_investments: Option<bool>,
+    /// Import nothing if a transaction was already recorded, defaults to `true`
+    pub reject_duplicates: Option<bool>,
+}
+
+impl BrokerImportQuery {
+    pub(crate) fn into_options(self, create_investments_default: bool) -> BrokerImportOptions {
+        BrokerImportOptions {
+            portfolio_id: self.portfolio_id,
+            create_investments: self
+                .create_investments
+                .unwrap_or(create_investments_default),
+            reject_duplicates: self.reject_duplicates.unwrap_or(true),
+        }
+    }
+}
+
+/// POST /api/import/degiro - Import the CSV transaction export of Degiro as movements
+pub async fn import_degiro(
+    State(service): State<Arc<BrokerImportService>>,
+    Query(query): Query<BrokerImportQuery>,
+    body: String,
+) -> Result<Json<BrokerImportResult>> {
+    let result = service
+        .import(&DegiroParser::new(), &body, query.into_options(true))
+        .await?;
+    Ok(Json(result))
+}
+
+/// POST /api/import/trade-republic - Import the CSV transaction export of Trade Republic
+///
+/// Unknown securities are reported instead of created unless `create_investments=true`.
+pub async fn import_trade_republic(
+    State(service): State<Arc<BrokerImportService>>,
+    Query(query): Query<BrokerImportQuery>,
+    body: String,
+) -> Result<Json<BrokerImportResult>> {
+    let result = service
+        .import(
+            &TradeRepublicParser::new(),
+            &body,
+            query.into_options(false),
+        )
+        .await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,130 @@
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::CashMovement;
+use crate::repository::traits::CashMovementRepository;
+use crate::services::cash_ledger::{CashBalance, DEPOSIT_ACTION_ID, WITHDRAWAL_ACTION_ID};
+use crate::services::display_precision;
+use crate::services::CashLedgerService;
+use crate::validation::{Validate, ValidationErrors};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Serialize)]
+pub struct CashMovementResponse {
+    pub id: i64,
+    pub date: NaiveDate,
+    pub action_id: i64,
+    #[serde(serialize_with = "display_precision::value")]
+    pub amount: Decimal,
+    pub portfolio_id: Option<i64>,
+    pub description: Option<String>,
+}
+
+impl From<CashMovement> for CashMovementResponse {
+    fn from(m: CashMovement) -> Self {
+        Self {
+            id: m.id,
+            date: m.date,
+            action_id: m.action_id,
+            amount: m.amount,
+            portfolio_id: m.portfolio_id,
+            description: m.description,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCashMovementRequest {
+    pub date: NaiveDate,
+    pub action_id: i64,
+    pub amount: Decimal,
+    pub portfolio_id: Option<i64>,
+    pub description: Option<String>,
+}
+
+impl Validate for CreateCashMovementRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.date("date", Some(self.date));
+        if self.action_id != DEPOSIT_ACTION_ID && self.action_id != WITHDRAWAL_ACTION_ID {
+            errors.add(
+                "action_id",
+                format!(
+                    "must be {} (Deposit) or {} (Withdrawal)",
+                    DEPOSIT_ACTION_ID, WITHDRAWAL_ACTION_ID
+                ),
+            );
+        }
+        errors.positive("amount", Some(self.amount));
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CashMovementQuery {
+    pub portfolio_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CashBalanceQuery {
+    pub portfolio_id: Option<i64>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// GET /api/cash/movements - List deposits and withdrawals
+pub async fn list_cash_movements(
+    State(repo): State<Arc<dyn CashMovementRepository>>,
+    Query(params): Query<CashMovementQuery>,
+) -> Result<Json<Vec<CashMovementResponse>>> {
+    let movements = repo.find_all(params.portfolio_id).await?;
+    let response: Vec<CashMovementResponse> = movements.into_iter().map(Into::into).collect();
+    Ok(Json(response))
+}
+
+/// POST /api/cash/movements - Record a deposit or withdrawal
+pub async fn create_cash_movement(
+    State(repo): State<Arc<dyn CashMovementRepository>>,
+    Json(req): Json<CreateCashMovementRequest>,
+) -> Result<Json<CashMovementResponse>> {
+    req.validate()?;
+
+    let movement = CashMovement {
+        id: 0,
+        date: req.date,
+        action_id: req.action_id,
+        amount: req.amount,
+        portfolio_id: req.portfolio_id,
+        description: req.description,
+    };
+
+    let id = repo.create(&movement).await?;
+    let created = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("CashMovement", id))?;
+    Ok(Json(created.into()))
+}
+
+/// DELETE /api/cash/movements/:id - Delete a deposit or withdrawal
+pub async fn delete_cash_movement(
+    State(repo): State<Arc<dyn CashMovementRepository>>,
+    Path(id): Path<i64>,
+) -> Result<Json<()>> {
+    ensure_found(repo.delete(id).await?, "CashMovement", id)?;
+    Ok(Json(()))
+}
+
+/// GET /api/cash/balance - Cash position over time, optionally per portfolio
+pub async fn get_cash_balance(
+    State(ledger): State<Arc<CashLedgerService>>,
+    Query(params): Query<CashBalanceQuery>,
+) -> Result<Json<Vec<CashBalance>>> {
+    let balances = ledger
+        .calculate_balance(params.portfolio_id, params.start_date, params.end_date)
+        .await?;
+    Ok(Json(balances))
+}
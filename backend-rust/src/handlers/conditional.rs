@@ -0,0 +1,69 @@
+use crate::services::data_revision::{DataRevision, Revision};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+
+/// Format of HTTP dates, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Whether the client's copy is still current: `If-None-Match` lists the entity tag, or
+/// without it, nothing was written after `If-Modified-Since`
+///
+/// Last-Modified has whole seconds, so only the entity tag sees two writes within one second.
+pub fn not_modified(headers: &HeaderMap, revision: &Revision) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        let etag = revision.etag();
+        // Weak comparison, so a tag without the W/ prefix matches as well
+        let opaque = etag.trim_start_matches("W/");
+        return if_none_match.to_str().is_ok_and(|tags| {
+            tags.split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == opaque)
+        });
+    }
+
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+        .is_some_and(|since| revision.modified <= since.with_timezone(&Utc))
+}
+
+/// Middleware for read endpoints that answers `304 Not Modified` if no data was written
+/// since the client's last request, and otherwise adds `ETag` and `Last-Modified`
+pub async fn conditional_get(
+    State(revision): State<DataRevision>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return next.run(request).await;
+    }
+
+    // Taken before the handler reads, so a write during the request changes the tag
+    let revision = revision.current();
+    let etag = HeaderValue::from_str(&revision.etag()).ok();
+    let last_modified =
+        HeaderValue::from_str(&revision.modified.format(HTTP_DATE_FORMAT).to_string()).ok();
+
+    let mut response = if not_modified(request.headers(), &revision) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        next.run(request).await
+    };
+
+    if matches!(response.status(), StatusCode::OK | StatusCode::NOT_MODIFIED) {
+        let headers = response.headers_mut();
+        if let Some(etag) = etag {
+            headers.insert(header::ETAG, etag);
+        }
+        if let Some(last_modified) = last_modified {
+            headers.insert(header::LAST_MODIFIED, last_modified);
+        }
+    }
+    response
+}
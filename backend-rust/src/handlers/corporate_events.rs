@@ -0,0 +1,62 @@
+use crate::error::Result;
+use crate::models::{DividendEvent, DividendStatus, SplitEvent};
+use crate::services::corporate_events::DividendPayout;
+use crate::services::CorporateEventService;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct CorporateEventQuery {
+    pub investment_id: Option<i64>,
+    /// `pending`, `confirmed` or `dismissed`
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmDividendRequest {
+    /// Amount received in the base currency, defaults to the calculated payout
+    pub amount: Option<Decimal>,
+}
+
+/// GET /api/dividend-events?investment_id=&status= - Detected dividends with the payout they would book
+pub async fn list_dividend_events(
+    State(service): State<Arc<CorporateEventService>>,
+    Query(params): Query<CorporateEventQuery>,
+) -> Result<Json<Vec<DividendPayout>>> {
+    let status = params
+        .status
+        .as_deref()
+        .map(str::parse::<DividendStatus>)
+        .transpose()?;
+    Ok(Json(service.dividends(params.investment_id, status).await?))
+}
+
+/// POST /api/dividend-events/:id/confirm - Record the payout of a pending dividend
+pub async fn confirm_dividend_event(
+    State(service): State<Arc<CorporateEventService>>,
+    Path(id): Path<i64>,
+    Json(req): Json<ConfirmDividendRequest>,
+) -> Result<Json<DividendPayout>> {
+    Ok(Json(service.confirm_dividend(id, req.amount).await?))
+}
+
+/// POST /api/dividend-events/:id/dismiss - Mark a pending dividend as not received
+pub async fn dismiss_dividend_event(
+    State(service): State<Arc<CorporateEventService>>,
+    Path(id): Path<i64>,
+) -> Result<Json<DividendEvent>> {
+    Ok(Json(service.dismiss_dividend(id).await?))
+}
+
+/// GET /api/split-events?investment_id= - Detected stock splits
+pub async fn list_split_events(
+    State(service): State<Arc<CorporateEventService>>,
+    Query(params): Query<CorporateEventQuery>,
+) -> Result<Json<Vec<SplitEvent>>> {
+    Ok(Json(service.splits(params.investment_id).await?))
+}
@@ -0,0 +1,26 @@
+use crate::error::Result;
+use crate::services::dashboard::{Dashboard, DashboardPeriod, DEFAULT_TOP_MOVERS};
+use crate::services::DashboardService;
+use axum::{extract::Query, extract::State, Json};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct DashboardQuery {
+    /// Period of the top gainers and losers (default: day)
+    #[serde(default)]
+    pub period: DashboardPeriod,
+    /// Number of top gainers and losers
+    pub top: Option<usize>,
+}
+
+/// GET /api/dashboard - Total value, changes, top movers and cash balance in one response
+pub async fn get_dashboard(
+    State(service): State<Arc<DashboardService>>,
+    Query(params): Query<DashboardQuery>,
+) -> Result<Json<Dashboard>> {
+    let dashboard = service
+        .dashboard(params.period, params.top.unwrap_or(DEFAULT_TOP_MOVERS))
+        .await?;
+    Ok(Json(dashboard))
+}
@@ -0,0 +1,33 @@
+use crate::error::Result;
+use crate::models::{DataExport, ImportMode, ImportSummary};
+use crate::services::DataTransferService;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub mode: ImportMode,
+}
+
+/// GET /api/export - Export all data as one JSON document
+pub async fn export_data(
+    State(service): State<Arc<DataTransferService>>,
+) -> Result<Json<DataExport>> {
+    let export = service.export().await?;
+    Ok(Json(export))
+}
+
+/// POST /api/import - Restore an export, merging with or replacing existing data
+pub async fn import_data(
+    State(service): State<Arc<DataTransferService>>,
+    Query(query): Query<ImportQuery>,
+    Json(data): Json<DataExport>,
+) -> Result<Json<ImportSummary>> {
+    let summary = service.import(&data, query.mode).await?;
+    Ok(Json(summary))
+}
@@ -1,23 +1,56 @@
 use crate::error::Result;
+use crate::handlers::ndjson::json_rows;
+use crate::handlers::tags::tagged_investments;
+use crate::routes::CalculatorState;
+use crate::services::display_precision;
+use crate::services::portfolio_calculator::{
+    DevelopmentFill, DevelopmentRecalculation, Granularity, TotalDevelopment,
+};
 use crate::services::PortfolioCalculator;
-use axum::{extract::Query, extract::State, Json};
+use crate::validation::{Validate, ValidationErrors};
+use axum::{extract::Query, extract::State, http::HeaderMap, response::Response, Json};
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::HashSet;
 
 #[derive(Debug, Deserialize)]
 pub struct DevelopmentQuery {
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
+    pub portfolio_id: Option<i64>,
+    /// Only this investment
+    pub investment_id: Option<i64>,
+    /// Only the investments with this tag
+    pub tag: Option<String>,
+    /// `daily` for one development per day while an investment is held
+    #[serde(default)]
+    pub fill: DevelopmentFill,
+    /// `weekly` or `monthly` to keep only the last value per period
+    #[serde(default)]
+    pub granularity: Granularity,
+    /// Currency to report prices and values in instead of the base currency, e.g. `USD`
+    pub currency: Option<String>,
+}
+
+impl Validate for DevelopmentQuery {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.currency("currency", self.currency.as_deref());
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub struct DevelopmentResponse {
     pub investment: i64,
     pub date: String,
-    pub price: f64,
-    pub quantity: f64,
-    pub value: f64,
+    #[serde(serialize_with = "display_precision::price")]
+    pub price: Decimal,
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub quantity: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub value: Decimal,
+    /// Source of the quote the price comes from, `null` for transaction prices
+    pub source: Option<String>,
 }
 
 impl From<crate::services::portfolio_calculator::Development> for DevelopmentResponse {
@@ -28,18 +61,83 @@ impl From<crate::services::portfolio_calculator::Development> for DevelopmentRes
             price: dev.price,
             quantity: dev.quantity,
             value: dev.value,
+            source: dev.source,
         }
     }
 }
 
+/// GET /api/developments - Developments per investment
+///
+/// With `Accept: application/x-ndjson` the developments are streamed one per line.
 pub async fn list_developments(
-    State(calculator): State<Arc<PortfolioCalculator>>,
+    State(state): State<CalculatorState>,
     Query(params): Query<DevelopmentQuery>,
-) -> Result<Json<Vec<DevelopmentResponse>>> {
-    let developments = calculator
-        .calculate_developments(params.start_date, params.end_date)
+    headers: HeaderMap,
+) -> Result<Response> {
+    params.validate()?;
+    let investments = tagged_investments(state.tag_repo.as_ref(), params.tag.as_deref()).await?;
+    let mut developments = state
+        .calculator
+        .calculate_filled_developments(
+            params.portfolio_id,
+            params.investment_id,
+            params.start_date,
+            params.end_date,
+            params.fill,
+        )
+        .await?;
+    if let Some(ids) = investments {
+        developments.retain(|dev| ids.contains(&dev.investment));
+    }
+    let developments = PortfolioCalculator::resample_developments(developments, params.granularity);
+    let developments = state
+        .calculator
+        .convert_developments(developments, params.currency.as_deref())
         .await?;
 
     let response: Vec<DevelopmentResponse> = developments.into_iter().map(Into::into).collect();
-    Ok(Json(response))
+    Ok(json_rows(&headers, response))
+}
+
+/// GET /api/developments/total - Value of all investments per day
+///
+/// Days without a price are filled with the last known price of each investment. With
+/// `tag` only the investments with the tag are summed, with `investment_id` only that one.
+pub async fn get_total_developments(
+    State(state): State<CalculatorState>,
+    Query(params): Query<DevelopmentQuery>,
+) -> Result<Json<Vec<TotalDevelopment>>> {
+    params.validate()?;
+    let mut investments =
+        tagged_investments(state.tag_repo.as_ref(), params.tag.as_deref()).await?;
+    if let Some(id) = params.investment_id {
+        let only: HashSet<i64> = HashSet::from([id]);
+        investments = Some(match investments {
+            Some(tagged) => tagged.intersection(&only).copied().collect(),
+            None => only,
+        });
+    }
+    let totals = state
+        .calculator
+        .calculate_total_developments(
+            params.portfolio_id,
+            investments.as_ref(),
+            params.start_date,
+            params.end_date,
+        )
+        .await?;
+    let totals = PortfolioCalculator::resample_totals(totals, params.granularity);
+    let totals = state
+        .calculator
+        .convert_totals(totals, params.currency.as_deref())
+        .await?;
+    Ok(Json(totals))
+}
+
+/// POST /api/developments/recalculate - Drop cached developments and calculate them again
+pub async fn recalculate_developments(
+    State(state): State<CalculatorState>,
+) -> Result<Json<DevelopmentRecalculation>> {
+    let result = state.calculator.recalculate().await?;
+    Ok(Json(result))
 }
@@ -0,0 +1,20 @@
+use crate::error::Result;
+use crate::services::dividends::DividendSummary;
+use crate::services::DividendService;
+use axum::{extract::Query, extract::State, Json};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct DividendQuery {
+    pub year: Option<i32>,
+}
+
+/// GET /api/dividends/summary - Payouts per investment and month with yield-on-cost
+pub async fn get_dividend_summary(
+    State(service): State<Arc<DividendService>>,
+    Query(params): Query<DividendQuery>,
+) -> Result<Json<DividendSummary>> {
+    let summary = service.summary(params.year).await?;
+    Ok(Json(summary))
+}
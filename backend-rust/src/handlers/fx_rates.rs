@@ -0,0 +1,57 @@
+use crate::error::Result;
+use crate::models::FxRate;
+use crate::repository::traits::FxRateRepository;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Serialize)]
+pub struct FxRateResponse {
+    pub date: NaiveDate,
+    pub from_currency: String,
+    pub to_currency: String,
+    pub rate: f64,
+}
+
+impl From<FxRate> for FxRateResponse {
+    fn from(r: FxRate) -> Self {
+        Self {
+            date: r.date,
+            from_currency: r.from_currency,
+            to_currency: r.to_currency,
+            rate: r.rate,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FxRateQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// GET /api/fx-rates - List cached exchange rates, newest first
+pub async fn list_fx_rates(
+    State(repo): State<Arc<dyn FxRateRepository>>,
+    Query(params): Query<FxRateQuery>,
+) -> Result<Json<Vec<FxRateResponse>>> {
+    let from = params.from.map(|c| c.to_uppercase());
+    let to = params.to.map(|c| c.to_uppercase());
+
+    let rates = repo
+        .find_all(
+            from.as_deref(),
+            to.as_deref(),
+            params.start_date,
+            params.end_date,
+        )
+        .await?;
+
+    Ok(Json(rates.into_iter().map(FxRateResponse::from).collect()))
+}
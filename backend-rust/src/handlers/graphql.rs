@@ -0,0 +1,254 @@
+use crate::error::AppError;
+use crate::handlers::investments::{
+    create_investment, delete_investment, get_investment, list_investments, update_investment,
+    CreateInvestmentRequest, DeleteInvestmentQuery, InvestmentResponse, ListInvestmentsQuery,
+};
+use crate::handlers::movements::{
+    create_movement, delete_movement, update_movement, CreateMovementRequest, MovementResponse,
+};
+use crate::handlers::prices::{upsert_investment_price, CreatePriceRequest, PriceResponse};
+use crate::models::{InvestmentDependents, TokenScope};
+use crate::repository::traits::{
+    InvestmentPriceRepository, InvestmentRepository, MovementRepository,
+};
+use crate::routes::MovementState;
+use crate::services::investment_summary::InvestmentSummary;
+use crate::services::InvestmentSummaryService;
+use async_graphql::parser::types::OperationType;
+use async_graphql::{
+    ComplexObject, Context, EmptySubscription, ErrorExtensions, Object, Pos, Result, ResultExt,
+    Schema,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+/// Schema of the GraphQL endpoint, resolved with the same repositories as the REST routes
+pub type PortfolioSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Build the schema; mutations run through the REST handlers, so they validate,
+/// normalize and notify change listeners the same way
+pub fn build_schema(
+    investment_repo: Arc<dyn InvestmentRepository>,
+    movements: MovementState,
+    price_repo: Arc<dyn InvestmentPriceRepository>,
+    summary: Arc<InvestmentSummaryService>,
+) -> PortfolioSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(investment_repo)
+        .data(movements.movement_repo.clone())
+        .data(movements)
+        .data(price_repo)
+        .data(summary)
+        .finish()
+}
+
+/// POST /api/graphql - Execute a GraphQL query or mutation
+///
+/// Errors are answered with status 200 and listed in `errors`, with the HTTP status the
+/// REST endpoint would use in the `status` extension. The route accepts read tokens, so
+/// documents with a mutation are refused unless the token has the `write` scope.
+pub async fn execute_graphql(
+    State(schema): State<PortfolioSchema>,
+    scope: Option<Extension<TokenScope>>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    let read_only = scope.is_some_and(|Extension(scope)| !scope.allows(TokenScope::Write));
+    if read_only && has_mutation(&request.query) {
+        let error = AppError::Forbidden("Mutations need a token with the write scope".to_string())
+            .extend()
+            .into_server_error(Pos::default());
+        return Json(async_graphql::Response::from_errors(vec![error]));
+    }
+    Json(schema.execute(request).await)
+}
+
+/// Whether the document defines a mutation; unparsable documents are left to the schema
+fn has_mutation(query: &str) -> bool {
+    async_graphql::parser::parse_query(query).is_ok_and(|document| {
+        document
+            .operations
+            .iter()
+            .any(|(_, operation)| operation.node.ty == OperationType::Mutation)
+    })
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// All investments except archived ones unless `includeArchived`, optionally only
+    /// those on or off the watchlist, or the best `limit` matches of the `search` text
+    async fn investments(
+        &self,
+        ctx: &Context<'_>,
+        watchlist: Option<bool>,
+        search: Option<String>,
+        limit: Option<i64>,
+        #[graphql(default)] include_archived: bool,
+    ) -> Result<Vec<InvestmentResponse>> {
+        let repo = ctx.data::<Arc<dyn InvestmentRepository>>()?;
+        let Json(investments) = list_investments(
+            State(repo.clone()),
+            Query(ListInvestmentsQuery {
+                watchlist,
+                q: search,
+                limit,
+                include_archived,
+            }),
+        )
+        .await
+        .extend()?;
+        Ok(investments)
+    }
+
+    /// One investment, `null` if it does not exist
+    async fn investment(&self, ctx: &Context<'_>, id: i64) -> Result<Option<InvestmentResponse>> {
+        let repo = ctx.data::<Arc<dyn InvestmentRepository>>()?;
+        match get_investment(State(repo.clone()), Path(id)).await {
+            Ok(Json(investment)) => Ok(Some(investment)),
+            Err(AppError::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e).extend(),
+        }
+    }
+}
+
+#[ComplexObject]
+impl InvestmentResponse {
+    /// Movements of the investment ordered by date, optionally within a date range
+    async fn movements(
+        &self,
+        ctx: &Context<'_>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<MovementResponse>> {
+        let repo = ctx.data::<Arc<dyn MovementRepository>>()?;
+        let movements = repo
+            .find_filtered(Some(self.id), None, start_date, end_date)
+            .await
+            .extend()?;
+        Ok(movements.into_iter().map(Into::into).collect())
+    }
+
+    /// Stored prices of the investment, optionally within a date range
+    async fn prices(
+        &self,
+        ctx: &Context<'_>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<PriceResponse>> {
+        let repo = ctx.data::<Arc<dyn InvestmentPriceRepository>>()?;
+        let prices = repo
+            .find_all(Some(self.id), start_date, end_date)
+            .await
+            .extend()?;
+        Ok(prices.into_iter().map(Into::into).collect())
+    }
+
+    /// Current position, totals and simple return
+    async fn summary(&self, ctx: &Context<'_>) -> Result<InvestmentSummary> {
+        let service = ctx.data::<Arc<InvestmentSummaryService>>()?;
+        service.summary(self.id).await.extend()
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn create_investment(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateInvestmentRequest,
+    ) -> Result<InvestmentResponse> {
+        let repo = ctx.data::<Arc<dyn InvestmentRepository>>()?;
+        let Json(created) = create_investment(State(repo.clone()), Json(input))
+            .await
+            .extend()?;
+        Ok(created)
+    }
+
+    /// Update an investment; currency, asset class and watchlist are kept if omitted
+    async fn update_investment(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        input: CreateInvestmentRequest,
+    ) -> Result<InvestmentResponse> {
+        let repo = ctx.data::<Arc<dyn InvestmentRepository>>()?;
+        let Json(updated) = update_investment(State(repo.clone()), Path(id), Json(input))
+            .await
+            .extend()?;
+        Ok(updated)
+    }
+
+    /// Delete an investment; with movements or prices only if `cascade` is set, which
+    /// deletes those as well
+    async fn delete_investment(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        #[graphql(default)] cascade: bool,
+    ) -> Result<InvestmentDependents> {
+        let repo = ctx.data::<Arc<dyn InvestmentRepository>>()?;
+        let Json(deleted) = delete_investment(
+            State(repo.clone()),
+            Path(id),
+            Query(DeleteInvestmentQuery { cascade }),
+        )
+        .await
+        .extend()?;
+        Ok(deleted)
+    }
+
+    async fn create_movement(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateMovementRequest,
+    ) -> Result<MovementResponse> {
+        let state = ctx.data::<MovementState>()?;
+        let Json(created) = create_movement(State(state.clone()), Json(input))
+            .await
+            .extend()?;
+        Ok(created)
+    }
+
+    /// Update a movement; the external reference is kept if omitted
+    async fn update_movement(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        input: CreateMovementRequest,
+    ) -> Result<MovementResponse> {
+        let state = ctx.data::<MovementState>()?;
+        let Json(updated) = update_movement(State(state.clone()), Path(id), Json(input))
+            .await
+            .extend()?;
+        Ok(updated)
+    }
+
+    async fn delete_movement(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
+        let state = ctx.data::<MovementState>()?;
+        let Json(()) = delete_movement(State(state.clone()), Path(id))
+            .await
+            .extend()?;
+        Ok(true)
+    }
+
+    /// Insert a price or replace the one of the same investment, date and source;
+    /// prices without source are stored as entered by hand
+    async fn upsert_price(
+        &self,
+        ctx: &Context<'_>,
+        input: CreatePriceRequest,
+    ) -> Result<PriceResponse> {
+        let repo = ctx.data::<Arc<dyn InvestmentPriceRepository>>()?;
+        let Json(price) = upsert_investment_price(State(repo.clone()), Json(input))
+            .await
+            .extend()?;
+        Ok(price)
+    }
+}
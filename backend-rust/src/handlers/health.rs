@@ -1,4 +1,10 @@
-use axum::Json;
+use crate::error::Result;
+use crate::models::MigrationStatus;
+use crate::routes::{HealthState, QuoteFetchState};
+use crate::services::integration_status::IntegrationStatus;
+use crate::services::{IntegrationStatusService, QuoteFetcherService};
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -6,8 +12,100 @@ pub struct HealthResponse {
     pub status: String,
 }
 
+/// GET /health/live - The process is running and serving requests
 pub async fn health() -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "ok".to_string(),
     })
 }
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    /// `ready` or `not_ready`
+    pub status: String,
+    /// `ok` or `unavailable`
+    pub database: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub migrations: Option<MigrationStatus>,
+    /// Latest successful quote fetch, for information only
+    pub last_quote_fetch: Option<DateTime<Utc>>,
+}
+
+/// GET /health/ready - 503 unless the database answers and all migrations are applied
+pub async fn health_ready(
+    State(state): State<HealthState>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let (database, error, migrations) = match state.health_repo.check().await {
+        Ok(migrations) => {
+            let error = (!migrations.is_current()).then(|| {
+                format!(
+                    "Database schema is not at migration {}",
+                    migrations.latest_version
+                )
+            });
+            ("ok", error, Some(migrations))
+        }
+        Err(e) => ("unavailable", Some(e.to_string()), None),
+    };
+    let ready = error.is_none();
+
+    // A failing fetch log does not make the service unready
+    let last_quote_fetch = state.fetch_log_repo.last_success().await.ok().flatten();
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(ReadinessResponse {
+            status: if ready { "ready" } else { "not_ready" }.to_string(),
+            database: database.to_string(),
+            error,
+            migrations,
+            last_quote_fetch,
+        }),
+    )
+}
+
+#[derive(Debug, Serialize)]
+pub struct IntegrationStatusResponse {
+    /// `ok` if every integration answered, else `degraded`
+    pub status: String,
+    pub integrations: Vec<IntegrationStatus>,
+}
+
+/// GET /api/status/integrations - Probe the quote providers in use and the exchange rate
+/// API
+pub async fn get_integration_status(
+    State(state): State<QuoteFetchState>,
+) -> Result<Json<IntegrationStatusResponse>> {
+    let base_currency = state
+        .settings_repo
+        .get()
+        .await?
+        .map(|s| s.base_currency)
+        .unwrap_or_else(|| "EUR".to_string());
+
+    let quote_fetcher = QuoteFetcherService::new(
+        state.investment_repo.clone(),
+        state.price_repo.clone(),
+        base_currency,
+    )
+    .with_api_keys(state.api_keys.clone())
+    .with_provider_configs(state.provider_config_repo.clone());
+    let service = IntegrationStatusService::new(state.investment_repo.clone(), quote_fetcher);
+
+    let integrations = service.check().await?;
+    let status = if integrations.iter().all(|i| i.reachable) {
+        "ok"
+    } else {
+        "degraded"
+    };
+    Ok(Json(IntegrationStatusResponse {
+        status: status.to_string(),
+        integrations,
+    }))
+}
@@ -0,0 +1,208 @@
+use crate::error::{ensure_found, AppError, Result};
+use crate::handlers::BrokerImportQuery;
+use crate::models::ImportProfile;
+use crate::repository::traits::ImportProfileRepository;
+use crate::routes::ProfileImportState;
+use crate::services::import::profile::PROFILE_ACTION_IDS;
+use crate::services::import::{BrokerImportResult, ProfileParser};
+use crate::validation::{Validate, ValidationErrors};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::format::{Item, StrftimeItems};
+use serde::Deserialize;
+use sqlx::types::Json as JsonColumn;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct ImportProfileRequest {
+    pub name: String,
+    /// Defaults to `,`
+    pub delimiter: Option<String>,
+    pub date_format: String,
+    /// Defaults to `.`
+    pub decimal_separator: Option<String>,
+    pub currency: String,
+    pub date_column: String,
+    pub action_column: String,
+    pub isin_column: String,
+    pub product_column: Option<String>,
+    pub quantity_column: Option<String>,
+    pub amount_column: String,
+    pub fee_column: Option<String>,
+    pub action_keywords: BTreeMap<String, i64>,
+}
+
+fn required(field: &str, value: String) -> Result<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "{} must not be empty",
+            field
+        )));
+    }
+    Ok(value.to_string())
+}
+
+fn optional(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl Validate for ImportProfileRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.currency("currency", Some(&self.currency.trim().to_uppercase()));
+    }
+}
+
+impl ImportProfileRequest {
+    fn into_profile(self, id: i64) -> Result<ImportProfile> {
+        self.validate()?;
+
+        let delimiter = self.delimiter.unwrap_or_else(|| ",".to_string());
+        if delimiter.len() != 1 || !delimiter.is_ascii() {
+            return Err(AppError::InvalidInput(
+                "delimiter must be a single ASCII character".to_string(),
+            ));
+        }
+
+        let decimal_separator = self.decimal_separator.unwrap_or_else(|| ".".to_string());
+        if decimal_separator != "." && decimal_separator != "," {
+            return Err(AppError::InvalidInput(
+                "decimal_separator must be '.' or ','".to_string(),
+            ));
+        }
+
+        let date_format = required("date_format", self.date_format)?;
+        if StrftimeItems::new(&date_format).any(|item| matches!(item, Item::Error)) {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid date_format '{}'",
+                date_format
+            )));
+        }
+
+        let currency = self.currency.trim().to_uppercase();
+
+        let mut action_keywords = BTreeMap::new();
+        for (keyword, action_id) in self.action_keywords {
+            if !PROFILE_ACTION_IDS.contains(&action_id) {
+                return Err(AppError::InvalidInput(format!(
+                    "Keyword '{}' maps to action {}, expected one of {:?}",
+                    keyword, action_id, PROFILE_ACTION_IDS
+                )));
+            }
+            action_keywords.insert(keyword.trim().to_lowercase(), action_id);
+        }
+        if action_keywords.is_empty() {
+            return Err(AppError::InvalidInput(
+                "action_keywords must not be empty".to_string(),
+            ));
+        }
+
+        Ok(ImportProfile {
+            id,
+            name: required("name", self.name)?,
+            delimiter,
+            date_format,
+            decimal_separator,
+            currency,
+            date_column: required("date_column", self.date_column)?,
+            action_column: required("action_column", self.action_column)?,
+            isin_column: required("isin_column", self.isin_column)?,
+            product_column: optional(self.product_column),
+            quantity_column: optional(self.quantity_column),
+            amount_column: required("amount_column", self.amount_column)?,
+            fee_column: optional(self.fee_column),
+            action_keywords: JsonColumn(action_keywords),
+        })
+    }
+}
+
+/// GET /api/import-profiles - List all import profiles
+pub async fn list_import_profiles(
+    State(repo): State<Arc<dyn ImportProfileRepository>>,
+) -> Result<Json<Vec<ImportProfile>>> {
+    let profiles = repo.find_all().await?;
+    Ok(Json(profiles))
+}
+
+/// GET /api/import-profiles/:id - Get a single import profile
+pub async fn get_import_profile(
+    State(repo): State<Arc<dyn ImportProfileRepository>>,
+    Path(id): Path<i64>,
+) -> Result<Json<ImportProfile>> {
+    let profile = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("ImportProfile", id))?;
+    Ok(Json(profile))
+}
+
+/// POST /api/import-profiles - Create an import profile
+pub async fn create_import_profile(
+    State(repo): State<Arc<dyn ImportProfileRepository>>,
+    Json(req): Json<ImportProfileRequest>,
+) -> Result<Json<ImportProfile>> {
+    let profile = req.into_profile(0)?;
+
+    let id = repo.create(&profile).await?;
+    let created = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("ImportProfile", id))?;
+    Ok(Json(created))
+}
+
+/// PUT /api/import-profiles/:id - Update an import profile
+pub async fn update_import_profile(
+    State(repo): State<Arc<dyn ImportProfileRepository>>,
+    Path(id): Path<i64>,
+    Json(req): Json<ImportProfileRequest>,
+) -> Result<Json<ImportProfile>> {
+    let profile = req.into_profile(id)?;
+
+    ensure_found(repo.update(id, &profile).await?, "ImportProfile", id)?;
+    let updated = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("ImportProfile", id))?;
+    Ok(Json(updated))
+}
+
+/// DELETE /api/import-profiles/:id - Delete an import profile
+pub async fn delete_import_profile(
+    State(repo): State<Arc<dyn ImportProfileRepository>>,
+    Path(id): Path<i64>,
+) -> Result<Json<()>> {
+    ensure_found(repo.delete(id).await?, "ImportProfile", id)?;
+    Ok(Json(()))
+}
+
+/// POST /api/import-profiles/:id/import - Import a CSV file as movements using a profile
+///
+/// Unknown securities are reported instead of created unless `create_investments=true`.
+pub async fn import_with_profile(
+    State(state): State<ProfileImportState>,
+    Path(id): Path<i64>,
+    Query(query): Query<BrokerImportQuery>,
+    body: String,
+) -> Result<Json<BrokerImportResult>> {
+    let profile = state
+        .profile_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("ImportProfile", id))?;
+
+    let result = state
+        .import_service
+        .import(
+            &ProfileParser::new(profile),
+            &body,
+            query.into_options(false),
+        )
+        .await?;
+    Ok(Json(result))
+}
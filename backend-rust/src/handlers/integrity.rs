@@ -0,0 +1,20 @@
+use crate::error::Result;
+use crate::services::integrity::{IntegrityFix, IntegrityReport};
+use crate::services::IntegrityService;
+use axum::{extract::State, Json};
+use std::sync::Arc;
+
+/// GET /api/admin/integrity - Check the stored data for orphaned, undated, oversold and
+/// duplicate rows
+pub async fn get_integrity_report(
+    State(service): State<Arc<IntegrityService>>,
+) -> Result<Json<IntegrityReport>> {
+    Ok(Json(service.check().await?))
+}
+
+/// POST /api/admin/integrity/fix - Delete orphaned and duplicate prices and check again
+pub async fn fix_integrity(
+    State(service): State<Arc<IntegrityService>>,
+) -> Result<Json<IntegrityFix>> {
+    Ok(Json(service.fix().await?))
+}
@@ -1,15 +1,24 @@
-use crate::error::{AppError, Result};
-use crate::models::Investment;
+use crate::error::{ensure_found, AppError, Result};
+use crate::isin;
+use crate::models::{Investment, InvestmentDependents, InvestmentFilter, InvestmentMerge};
 use crate::repository::traits::InvestmentRepository;
-use crate::services::quote_fetcher::VALID_PROVIDER_IDS;
+use crate::services::investment_summary::InvestmentSummary;
+use crate::services::positions::PositionPoint;
+use crate::services::quote_fetcher::{parse_provider_chain, VALID_PROVIDER_IDS};
+use crate::services::InvestmentSummaryService;
+use crate::validation::{Validate, ValidationErrors};
+use async_graphql::{InputObject, SimpleObject};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, SimpleObject)]
+#[graphql(name = "Investment", complex)]
 pub struct InvestmentResponse {
     pub id: i64,
     pub name: Option<String>,
@@ -17,6 +26,13 @@ pub struct InvestmentResponse {
     pub shortname: Option<String>,
     pub ticker_symbol: Option<String>,
     pub quote_provider: Option<String>,
+    pub currency: Option<String>,
+    pub asset_class: Option<String>,
+    pub watchlist: bool,
+    pub partial_exemption: Option<Decimal>,
+    pub quote_fetch_enabled: bool,
+    pub quote_fetch_interval_days: Option<i64>,
+    pub archived: bool,
 }
 
 impl From<Investment> for InvestmentResponse {
@@ -28,36 +44,146 @@ impl From<Investment> for InvestmentResponse {
             shortname: inv.shortname,
             ticker_symbol: inv.ticker_symbol,
             quote_provider: inv.quote_provider,
+            currency: inv.currency,
+            asset_class: inv.asset_class,
+            watchlist: inv.watchlist,
+            partial_exemption: inv.partial_exemption,
+            quote_fetch_enabled: inv.quote_fetch_enabled,
+            quote_fetch_interval_days: inv.quote_fetch_interval_days,
+            archived: inv.archived,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, InputObject)]
+#[graphql(name = "InvestmentInput")]
 pub struct CreateInvestmentRequest {
     pub name: Option<String>,
     pub isin: Option<String>,
     pub shortname: Option<String>,
     pub ticker_symbol: Option<String>,
     pub quote_provider: Option<String>,
+    /// Kept as stored when omitted on update
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Kept as stored when omitted on update
+    #[serde(default)]
+    pub asset_class: Option<String>,
+    /// Track the quotes only; `false` on create and kept as stored when omitted on update
+    #[serde(default)]
+    pub watchlist: Option<bool>,
+    /// Teilfreistellung in percent; kept as stored when omitted on update
+    #[serde(default)]
+    pub partial_exemption: Option<Decimal>,
+    /// Include in the scheduled quote fetch; `true` on create and kept as stored when
+    /// omitted on update
+    #[serde(default)]
+    pub quote_fetch_enabled: Option<bool>,
+    /// Days between scheduled quote fetches; kept as stored when omitted on update
+    #[serde(default)]
+    pub quote_fetch_interval_days: Option<i64>,
+    /// Hide from lists and the scheduled quote fetch; `false` on create and kept as stored
+    /// when omitted on update
+    #[serde(default)]
+    pub archived: Option<bool>,
 }
 
-fn validate_quote_provider(provider: &str) -> Result<()> {
-    if !VALID_PROVIDER_IDS.contains(&provider) {
-        return Err(AppError::InvalidInput(format!(
-            "Invalid quote provider '{}'. Valid providers are: {}",
-            provider,
-            VALID_PROVIDER_IDS.join(", ")
-        )));
+impl Validate for CreateInvestmentRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        if self.quote_fetch_interval_days.is_some_and(|days| days < 1) {
+            errors.add("quote_fetch_interval_days", "must be at least 1");
+        }
+        if self
+            .partial_exemption
+            .is_some_and(|p| p < Decimal::ZERO || p > Decimal::ONE_HUNDRED)
+        {
+            errors.add("partial_exemption", "must be between 0 and 100");
+        }
+        errors.isin(
+            "isin",
+            self.isin.as_deref().filter(|i| !i.trim().is_empty()),
+        );
+        errors.currency("currency", self.currency.as_deref());
+    }
+}
+
+/// Store ISINs without whitespace in upper case, so imports and quote providers match them
+fn normalize_isin(isin: Option<String>) -> Option<String> {
+    isin.map(|i| isin::normalize(&i)).filter(|i| !i.is_empty())
+}
+
+/// Validate a provider fallback chain and return it normalized (e.g. `"yahoo,justetf"`)
+fn validate_quote_provider(providers: &str) -> Result<String> {
+    let chain = parse_provider_chain(providers);
+    if chain.is_empty() {
+        return Err(AppError::InvalidProvider {
+            provider: providers.to_string(),
+            valid: VALID_PROVIDER_IDS.to_vec(),
+        });
+    }
+
+    for provider in &chain {
+        if !VALID_PROVIDER_IDS.contains(provider) {
+            return Err(AppError::InvalidProvider {
+                provider: provider.to_string(),
+                valid: VALID_PROVIDER_IDS.to_vec(),
+            });
+        }
     }
 
-    Ok(())
+    Ok(chain.join(","))
+}
+
+/// Results of an investment search unless another `limit` is given
+pub const DEFAULT_SEARCH_LIMIT: i64 = 50;
+
+/// Most results of an investment search
+pub const MAX_SEARCH_LIMIT: i64 = 500;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListInvestmentsQuery {
+    /// Only investments on (`true`) or off (`false`) the watchlist
+    pub watchlist: Option<bool>,
+    /// Search text, matched against name, short name, ISIN and ticker
+    pub q: Option<String>,
+    /// Most results of a search
+    pub limit: Option<i64>,
+    /// Include archived investments
+    #[serde(default)]
+    pub include_archived: bool,
 }
 
+impl ListInvestmentsQuery {
+    fn filter(&self) -> InvestmentFilter {
+        InvestmentFilter {
+            watchlist: self.watchlist,
+            include_archived: self.include_archived,
+        }
+    }
+}
+
+/// GET /api/investments - All investments except archived ones, or the best matches of
+/// the search text `q`
 pub async fn list_investments(
     State(repo): State<Arc<dyn InvestmentRepository>>,
+    Query(query): Query<ListInvestmentsQuery>,
 ) -> Result<Json<Vec<InvestmentResponse>>> {
+    if let Some(text) = query.q.as_deref().filter(|q| !q.trim().is_empty()) {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
+        let investments = repo.search(text, &query.filter(), limit).await?;
+        return Ok(Json(investments.into_iter().map(Into::into).collect()));
+    }
+
+    let filter = query.filter();
     let investments = repo.find_all().await?;
-    let response: Vec<InvestmentResponse> = investments.into_iter().map(Into::into).collect();
+    let response: Vec<InvestmentResponse> = investments
+        .into_iter()
+        .filter(|i| filter.matches(i))
+        .map(Into::into)
+        .collect();
     Ok(Json(response))
 }
 
@@ -65,7 +191,10 @@ pub async fn get_investment(
     State(repo): State<Arc<dyn InvestmentRepository>>,
     Path(id): Path<i64>,
 ) -> Result<Json<InvestmentResponse>> {
-    let investment = repo.find_by_id(id).await?.ok_or(AppError::NotFound)?;
+    let investment = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Investment", id))?;
     Ok(Json(investment.into()))
 }
 
@@ -73,22 +202,36 @@ pub async fn create_investment(
     State(repo): State<Arc<dyn InvestmentRepository>>,
     Json(req): Json<CreateInvestmentRequest>,
 ) -> Result<Json<InvestmentResponse>> {
+    req.validate()?;
+
     // Validate quote_provider if provided
-    if let Some(ref provider) = req.quote_provider {
-        validate_quote_provider(provider)?;
-    }
+    let quote_provider = req
+        .quote_provider
+        .as_deref()
+        .map(validate_quote_provider)
+        .transpose()?;
 
     let investment = Investment {
         id: 0,
         name: req.name,
-        isin: req.isin,
+        isin: normalize_isin(req.isin),
         shortname: req.shortname,
         ticker_symbol: req.ticker_symbol,
-        quote_provider: req.quote_provider,
+        quote_provider,
+        currency: req.currency,
+        asset_class: req.asset_class,
+        watchlist: req.watchlist.unwrap_or(false),
+        partial_exemption: req.partial_exemption,
+        quote_fetch_enabled: req.quote_fetch_enabled.unwrap_or(true),
+        quote_fetch_interval_days: req.quote_fetch_interval_days,
+        archived: req.archived.unwrap_or(false),
     };
 
     let id = repo.create(&investment).await?;
-    let created = repo.find_by_id(id).await?.ok_or(AppError::NotFound)?;
+    let created = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Investment", id))?;
     Ok(Json(created.into()))
 }
 
@@ -97,29 +240,110 @@ pub async fn update_investment(
     Path(id): Path<i64>,
     Json(req): Json<CreateInvestmentRequest>,
 ) -> Result<Json<InvestmentResponse>> {
+    req.validate()?;
+
     // Validate quote_provider if provided
-    if let Some(ref provider) = req.quote_provider {
-        validate_quote_provider(provider)?;
-    }
+    let quote_provider = req
+        .quote_provider
+        .as_deref()
+        .map(validate_quote_provider)
+        .transpose()?;
 
+    let existing = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Investment", id))?;
     let investment = Investment {
         id,
         name: req.name,
-        isin: req.isin,
+        isin: normalize_isin(req.isin),
         shortname: req.shortname,
         ticker_symbol: req.ticker_symbol,
-        quote_provider: req.quote_provider,
+        quote_provider,
+        currency: req.currency.or(existing.currency),
+        asset_class: req.asset_class.or(existing.asset_class),
+        watchlist: req.watchlist.unwrap_or(existing.watchlist),
+        partial_exemption: req.partial_exemption.or(existing.partial_exemption),
+        quote_fetch_enabled: req
+            .quote_fetch_enabled
+            .unwrap_or(existing.quote_fetch_enabled),
+        quote_fetch_interval_days: req
+            .quote_fetch_interval_days
+            .or(existing.quote_fetch_interval_days),
+        archived: req.archived.unwrap_or(existing.archived),
     };
 
-    repo.update(id, &investment).await?;
-    let updated = repo.find_by_id(id).await?.ok_or(AppError::NotFound)?;
+    ensure_found(repo.update(id, &investment).await?, "Investment", id)?;
+    let updated = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Investment", id))?;
     Ok(Json(updated.into()))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DeleteInvestmentQuery {
+    #[serde(default)]
+    pub cascade: bool,
+}
+
+/// DELETE /api/investments/:id - Delete an investment
+///
+/// Investments with movements or prices are only deleted with `cascade=true`,
+/// which removes those as well. Returns the number of deleted dependents.
 pub async fn delete_investment(
     State(repo): State<Arc<dyn InvestmentRepository>>,
     Path(id): Path<i64>,
-) -> Result<Json<()>> {
-    repo.delete(id).await?;
-    Ok(Json(()))
+    Query(query): Query<DeleteInvestmentQuery>,
+) -> Result<Json<InvestmentDependents>> {
+    let deleted = repo.delete_with_dependents(id, query.cascade).await?;
+    Ok(Json(deleted))
+}
+
+/// POST /api/investments/:id/merge-into/:target_id - Merge a duplicate investment
+///
+/// Moves its movements, prices, price alerts, savings plans and tags to the target and
+/// deletes it, recording the merge in the audit log.
+pub async fn merge_investment(
+    State(repo): State<Arc<dyn InvestmentRepository>>,
+    Path((id, target_id)): Path<(i64, i64)>,
+) -> Result<Json<InvestmentMerge>> {
+    if id == target_id {
+        return Err(AppError::InvalidInput(format!(
+            "Investment {} cannot be merged into itself",
+            id
+        )));
+    }
+    let merge = repo.merge_into(id, target_id).await?;
+    Ok(Json(merge))
+}
+
+/// GET /api/investments/:id/summary - Current position, totals and simple return
+pub async fn get_investment_summary(
+    State(service): State<Arc<InvestmentSummaryService>>,
+    Path(id): Path<i64>,
+) -> Result<Json<InvestmentSummary>> {
+    let summary = service.summary(id).await?;
+    Ok(Json(summary))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PositionHistoryQuery {
+    /// Only the movements of this portfolio
+    pub portfolio_id: Option<i64>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// GET /api/investments/:id/position-history - Quantity held after every day with a buy,
+/// sell, transfer or split
+pub async fn get_position_history(
+    State(service): State<Arc<InvestmentSummaryService>>,
+    Path(id): Path<i64>,
+    Query(query): Query<PositionHistoryQuery>,
+) -> Result<Json<Vec<PositionPoint>>> {
+    let history = service
+        .position_history(id, query.portfolio_id, query.start_date, query.end_date)
+        .await?;
+    Ok(Json(history))
 }
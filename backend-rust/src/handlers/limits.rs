@@ -0,0 +1,46 @@
+use crate::error::AppError;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use std::time::Duration;
+
+/// Middleware that answers `408 Request Timeout` if the handler does not finish in time
+///
+/// The handler is dropped at the timeout, so a stuck provider call does not keep the
+/// request open; writes in an open transaction are rolled back.
+pub async fn request_timeout(
+    State(timeout): State<Duration>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!("Request timed out after {:?}", timeout);
+            AppError::RequestTimeout(timeout.as_secs()).into_response()
+        }
+    }
+}
+
+/// Middleware that answers `413 Payload Too Large` for request bodies over the limit,
+/// including bodies sent without `Content-Length`
+pub async fn body_limit(State(limit): State<usize>, request: Request, next: Next) -> Response {
+    let (parts, body) = request.into_parts();
+    // Rejects a declared length over the limit before reading the body
+    let bytes = match Limited::new(body, limit).collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) if e.is::<LengthLimitError>() => {
+            return AppError::PayloadTooLarge(limit).into_response()
+        }
+        Err(e) => {
+            return AppError::InvalidInput(format!("Failed to read the request body: {}", e))
+                .into_response()
+        }
+    };
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
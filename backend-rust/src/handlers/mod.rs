@@ -1,17 +1,65 @@
 pub mod action_types;
+pub mod alerts;
+pub mod api_tokens;
+pub mod audit_log;
+pub mod broker_import;
+pub mod cash;
+pub mod conditional;
+pub mod corporate_events;
+pub mod dashboard;
+pub mod data_transfer;
 pub mod developments;
+pub mod dividends;
+pub mod fx_rates;
+pub mod graphql;
 pub mod health;
+pub mod import_profiles;
+pub mod integrity;
 pub mod investments;
+pub mod limits;
 pub mod movements;
+pub mod ndjson;
+pub mod performance;
+pub mod portfolios;
 pub mod prices;
 pub mod quotes;
+pub mod reports;
+pub mod savings_plans;
 pub mod settings;
+pub mod snapshots;
+pub mod symbols;
+pub mod tags;
+pub mod xlsx_export;
 
 pub use action_types::*;
+pub use alerts::*;
+pub use api_tokens::*;
+pub use audit_log::*;
+pub use broker_import::*;
+pub use cash::*;
+pub use conditional::*;
+pub use corporate_events::*;
+pub use dashboard::*;
+pub use data_transfer::*;
 pub use developments::*;
+pub use dividends::*;
+pub use fx_rates::*;
+pub use graphql::*;
 pub use health::*;
+pub use import_profiles::*;
+pub use integrity::*;
 pub use investments::*;
+pub use limits::*;
 pub use movements::*;
+pub use ndjson::*;
+pub use performance::*;
+pub use portfolios::*;
 pub use prices::*;
 pub use quotes::*;
+pub use reports::*;
+pub use savings_plans::*;
 pub use settings::*;
+pub use snapshots::*;
+pub use symbols::*;
+pub use tags::*;
+pub use xlsx_export::*;
@@ -1,23 +1,48 @@
-use crate::error::{AppError, Result};
-use crate::models::Movement;
-use crate::repository::traits::MovementRepository;
+use crate::error::{ensure_found, AppError, Result};
+use crate::handlers::ndjson::json_rows;
+use crate::models::{Movement, MovementListOptions, MovementSortField, SortOrder};
+use crate::routes::MovementState;
+use crate::services::action_effects::ActionEffects;
+use crate::services::cost_basis::{TRANSFER_IN_ACTION_ID, TRANSFER_OUT_ACTION_ID};
+use crate::services::display_precision;
+use crate::services::duplicates::{
+    DuplicateDetector, DuplicateMovement, DEFAULT_DUPLICATE_TOLERANCE,
+};
+use crate::services::positions::quantity_at;
+use crate::validation::{Validate, ValidationErrors};
+use async_graphql::{InputObject, SimpleObject};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 
-#[derive(Debug, Serialize)]
+/// Longest accepted external reference of a movement
+pub const MAX_EXTERNAL_ID_LENGTH: usize = 255;
+
+#[derive(Debug, Serialize, SimpleObject)]
+#[graphql(name = "Movement")]
 pub struct MovementResponse {
     pub id: i64,
     pub date: Option<NaiveDate>,
     pub action_id: Option<i64>,
     pub investment_id: Option<i64>,
-    pub quantity: Option<f64>,
-    pub amount: Option<f64>,
-    pub fee: Option<f64>,
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub quantity: Option<Decimal>,
+    #[serde(serialize_with = "display_precision::value")]
+    pub amount: Option<Decimal>,
+    #[serde(serialize_with = "display_precision::value")]
+    pub fee: Option<Decimal>,
+    #[serde(serialize_with = "display_precision::value")]
+    pub tax: Option<Decimal>,
+    pub portfolio_id: Option<i64>,
+    pub external_id: Option<String>,
 }
 
 impl From<Movement> for MovementResponse {
@@ -30,79 +55,546 @@ impl From<Movement> for MovementResponse {
             quantity: m.quantity,
             amount: m.amount,
             fee: m.fee,
+            tax: m.tax,
+            portfolio_id: m.portfolio_id,
+            external_id: m.external_id,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, InputObject)]
+#[graphql(name = "MovementInput")]
 pub struct CreateMovementRequest {
     pub date: Option<NaiveDate>,
     pub action_id: Option<i64>,
     pub investment_id: Option<i64>,
-    pub quantity: Option<f64>,
-    pub amount: Option<f64>,
-    pub fee: Option<f64>,
+    pub quantity: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    pub fee: Option<Decimal>,
+    /// Taxes paid or withheld, e.g. capital gains tax on a sell
+    pub tax: Option<Decimal>,
+    pub portfolio_id: Option<i64>,
+    /// Reference in an external system, e.g. a broker's order ID, unique per portfolio
+    pub external_id: Option<String>,
+}
+
+impl Validate for CreateMovementRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.date("date", self.date);
+        errors.non_negative("quantity", self.quantity);
+        errors.non_negative("amount", self.amount);
+        errors.non_negative("fee", self.fee);
+        errors.non_negative("tax", self.tax);
+        if self
+            .external_id
+            .as_deref()
+            .is_some_and(|id| id.trim().is_empty() || id.len() > MAX_EXTERNAL_ID_LENGTH)
+        {
+            errors.add(
+                "external_id",
+                format!("must be 1 to {} characters", MAX_EXTERNAL_ID_LENGTH),
+            );
+        }
+    }
+}
+
+impl CreateMovementRequest {
+    fn into_movement(self, id: i64) -> Movement {
+        Movement {
+            id,
+            date: self.date,
+            action_id: self.action_id,
+            investment_id: self.investment_id,
+            quantity: self.quantity,
+            amount: self.amount,
+            fee: self.fee,
+            tax: self.tax,
+            portfolio_id: self.portfolio_id,
+            external_id: self.external_id,
+        }
+    }
+}
+
+/// Looks up the investments and action types referenced by movements, each ID once
+struct ReferenceCheck<'a> {
+    state: &'a MovementState,
+    investments: HashMap<i64, bool>,
+    action_types: HashMap<i64, bool>,
+}
+
+impl<'a> ReferenceCheck<'a> {
+    fn new(state: &'a MovementState) -> Self {
+        Self {
+            state,
+            investments: HashMap::new(),
+            action_types: HashMap::new(),
+        }
+    }
+
+    /// Add an error for a referenced investment or action type that does not exist
+    async fn check(
+        &mut self,
+        investment_id: Option<i64>,
+        action_id: Option<i64>,
+        errors: &mut ValidationErrors,
+    ) -> Result<()> {
+        if let Some(id) = investment_id {
+            if !self.investments.contains_key(&id) {
+                let found = self.state.investment_repo.find_by_id(id).await?.is_some();
+                self.investments.insert(id, found);
+            }
+            if !self.investments[&id] {
+                errors.add(
+                    "investment_id",
+                    format!("refers to investment {}, which does not exist", id),
+                );
+            }
+        }
+        if let Some(id) = action_id {
+            if !self.action_types.contains_key(&id) {
+                let found = self.state.action_type_repo.find_by_id(id).await?.is_some();
+                self.action_types.insert(id, found);
+            }
+            if !self.action_types[&id] {
+                errors.add(
+                    "action_id",
+                    format!("refers to action type {}, which does not exist", id),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check the fields of a movement and that its investment and action type exist
+async fn validate_movement(state: &MovementState, req: &CreateMovementRequest) -> Result<()> {
+    let mut errors = ValidationErrors::default();
+    req.check(&mut errors);
+    ReferenceCheck::new(state)
+        .check(req.investment_id, req.action_id, &mut errors)
+        .await?;
+    errors.into_result()
+}
+
+/// Check several movements like `validate_movement`, prefixing the fields with the position
+async fn validate_movements(state: &MovementState, reqs: &[CreateMovementRequest]) -> Result<()> {
+    let mut references = ReferenceCheck::new(state);
+    let mut errors = ValidationErrors::default();
+    for (index, req) in reqs.iter().enumerate() {
+        let mut nested = ValidationErrors::default();
+        req.check(&mut nested);
+        references
+            .check(req.investment_id, req.action_id, &mut nested)
+            .await?;
+        errors.add_nested(index, nested);
+    }
+    errors.into_result()
+}
+
+/// Sells of more than the holding of their portfolio on their date, with their position
+/// and the reason, if the settings ask to reject them
+///
+/// The movements are counted together with the stored ones, so a batch may sell what it
+/// buys; `replaced` is the stored movement that an update overwrites.
+async fn oversold(
+    state: &MovementState,
+    movements: &[Movement],
+    replaced: Option<i64>,
+) -> Result<Vec<(usize, String)>> {
+    let settings = state.settings_repo.get().await?;
+    if !settings.is_some_and(|settings| settings.reject_negative_holdings) {
+        return Ok(Vec::new());
+    }
+
+    let effects = ActionEffects::load(Some(&state.action_type_repo)).await?;
+    let mut movements = movements.to_vec();
+    effects.normalize(&mut movements);
+
+    let mut stored: HashMap<i64, Vec<Movement>> = HashMap::new();
+    let mut oversold = Vec::new();
+    for (index, sell) in movements.iter().enumerate() {
+        let (Some(2), Some(investment_id), Some(date), Some(quantity)) =
+            (sell.action_id, sell.investment_id, sell.date, sell.quantity)
+        else {
+            continue;
+        };
+        if let Entry::Vacant(entry) = stored.entry(investment_id) {
+            let mut found = state
+                .movement_repo
+                .find_filtered(Some(investment_id), None, None, None)
+                .await?;
+            found.retain(|m| Some(m.id) != replaced);
+            effects.normalize(&mut found);
+            entry.insert(found);
+        }
+
+        let others = movements
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != index)
+            .map(|(_, m)| m);
+        let position: Vec<Movement> = stored[&investment_id]
+            .iter()
+            .chain(others)
+            .filter(|m| m.portfolio_id == sell.portfolio_id)
+            .cloned()
+            .collect();
+        let held = quantity_at(&position, investment_id, date);
+        if quantity > held {
+            oversold.push((
+                index,
+                format!(
+                    "sells {} but only {} are held on {}",
+                    quantity.normalize(),
+                    held.normalize(),
+                    date
+                ),
+            ));
+        }
+    }
+    Ok(oversold)
+}
+
+/// Reject a sell of more than the holding, see `oversold`
+async fn check_holding(
+    state: &MovementState,
+    movement: &Movement,
+    replaced: Option<i64>,
+) -> Result<()> {
+    let mut errors = ValidationErrors::default();
+    for (_, message) in oversold(state, std::slice::from_ref(movement), replaced).await? {
+        errors.add("quantity", message);
+    }
+    errors.into_result()
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkCreateMovementsResponse {
+    pub ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransferRequest {
+    pub date: NaiveDate,
+    pub investment_id: i64,
+    pub quantity: Decimal,
+    /// `None` for movements without portfolio
+    pub from_portfolio_id: Option<i64>,
+    pub to_portfolio_id: Option<i64>,
+}
+
+impl Validate for TransferRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.date("date", Some(self.date));
+        errors.positive("quantity", Some(self.quantity));
+    }
+}
+
+impl TransferRequest {
+    /// The transfer out and transfer in movement
+    fn into_movements(self) -> Result<[Movement; 2]> {
+        self.validate()?;
+        if self.from_portfolio_id == self.to_portfolio_id {
+            return Err(AppError::InvalidInput(
+                "Source and target portfolio must differ".to_string(),
+            ));
+        }
+
+        let movement = |action_id: i64, portfolio_id: Option<i64>| Movement {
+            id: 0,
+            date: Some(self.date),
+            action_id: Some(action_id),
+            investment_id: Some(self.investment_id),
+            quantity: Some(self.quantity),
+            amount: None,
+            fee: None,
+            tax: None,
+            portfolio_id,
+            external_id: None,
+        };
+        Ok([
+            movement(TRANSFER_OUT_ACTION_ID, self.from_portfolio_id),
+            movement(TRANSFER_IN_ACTION_ID, self.to_portfolio_id),
+        ])
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BulkCreateQuery {
+    /// Reject the request if a movement was already recorded
+    #[serde(default)]
+    pub reject_duplicates: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DuplicateCheckQuery {
+    /// Largest difference in quantity and amount, defaults to 0.01
+    pub tolerance: Option<Decimal>,
+}
+
+/// Header carrying the number of movements matching the filter, independent of the page
+pub const TOTAL_COUNT_HEADER: HeaderName = HeaderName::from_static("x-total-count");
+
+#[derive(Debug, Deserialize)]
+pub struct MovementQuery {
+    pub portfolio_id: Option<i64>,
+    pub investment_id: Option<i64>,
+    pub action_id: Option<i64>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    #[serde(default)]
+    pub sort_by: MovementSortField,
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
+impl MovementQuery {
+    fn into_options(self) -> Result<MovementListOptions> {
+        if self.limit.is_some_and(|limit| limit < 1) {
+            return Err(AppError::InvalidInput(
+                "limit must be at least 1".to_string(),
+            ));
+        }
+        if self.offset.is_some_and(|offset| offset < 0) {
+            return Err(AppError::InvalidInput(
+                "offset must not be negative".to_string(),
+            ));
+        }
+
+        Ok(MovementListOptions {
+            portfolio_id: self.portfolio_id,
+            investment_id: self.investment_id,
+            action_id: self.action_id,
+            start_date: self.start_date,
+            end_date: self.end_date,
+            sort_by: self.sort_by,
+            order: self.order,
+            limit: self.limit,
+            offset: self.offset.unwrap_or(0),
+        })
+    }
 }
 
+/// GET /api/movements - List movements, optionally filtered, paginated and sorted
+///
+/// The total number of matching movements is returned in the `X-Total-Count` header. With
+/// `Accept: application/x-ndjson` the movements are streamed one per line.
 pub async fn list_movements(
-    State(repo): State<Arc<dyn MovementRepository>>,
-) -> Result<Json<Vec<MovementResponse>>> {
-    let movements = repo.find_all().await?;
+    State(state): State<MovementState>,
+    Query(query): Query<MovementQuery>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let (movements, total) = state
+        .movement_repo
+        .find_page(&query.into_options()?)
+        .await?;
     let response: Vec<MovementResponse> = movements.into_iter().map(Into::into).collect();
-    Ok(Json(response))
+    Ok((
+        [(TOTAL_COUNT_HEADER, total.to_string())],
+        json_rows(&headers, response),
+    )
+        .into_response())
 }
 
 pub async fn get_movement(
-    State(repo): State<Arc<dyn MovementRepository>>,
+    State(state): State<MovementState>,
     Path(id): Path<i64>,
 ) -> Result<Json<MovementResponse>> {
-    let movement = repo.find_by_id(id).await?.ok_or(AppError::NotFound)?;
+    let movement = state
+        .movement_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Movement", id))?;
     Ok(Json(movement.into()))
 }
 
 pub async fn create_movement(
-    State(repo): State<Arc<dyn MovementRepository>>,
+    State(state): State<MovementState>,
     Json(req): Json<CreateMovementRequest>,
 ) -> Result<Json<MovementResponse>> {
-    let movement = Movement {
-        id: 0,
-        date: req.date,
-        action_id: req.action_id,
-        investment_id: req.investment_id,
-        quantity: req.quantity,
-        amount: req.amount,
-        fee: req.fee,
-    };
+    validate_movement(&state, &req).await?;
+    let movement = req.into_movement(0);
+    check_holding(&state, &movement, None).await?;
 
-    let id = repo.create(&movement).await?;
-    let created = repo.find_by_id(id).await?.ok_or(AppError::NotFound)?;
+    let id = state.movement_repo.create(&movement).await?;
+    let created = state
+        .movement_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Movement", id))?;
     Ok(Json(created.into()))
 }
 
+/// POST /api/movements/bulk - Create several movements at once, either all or none
+///
+/// With `reject_duplicates=true` nothing is created if any movement matches a recorded one.
+pub async fn create_movements_bulk(
+    State(state): State<MovementState>,
+    Query(query): Query<BulkCreateQuery>,
+    Json(reqs): Json<Vec<CreateMovementRequest>>,
+) -> Result<Json<BulkCreateMovementsResponse>> {
+    validate_movements(&state, &reqs).await?;
+    let movements: Vec<Movement> = reqs.into_iter().map(|r| r.into_movement(0)).collect();
+
+    let mut errors = ValidationErrors::default();
+    for (index, message) in oversold(&state, &movements, None).await? {
+        let mut nested = ValidationErrors::default();
+        nested.add("quantity", message);
+        errors.add_nested(index, nested);
+    }
+    errors.into_result()?;
+
+    if query.reject_duplicates {
+        let duplicates = DuplicateDetector::new(state.movement_repo.clone())
+            .find_duplicates(&movements, DEFAULT_DUPLICATE_TOLERANCE)
+            .await?;
+        if !duplicates.is_empty() {
+            let described: Vec<String> = duplicates
+                .iter()
+                .map(|d| format!("{} (matches {:?})", d.index, d.existing_ids))
+                .collect();
+            return Err(AppError::Conflict(format!(
+                "Movements already recorded: {}",
+                described.join(", ")
+            )));
+        }
+    }
+
+    let ids = state.movement_repo.create_many(&movements).await?;
+    Ok(Json(BulkCreateMovementsResponse { ids }))
+}
+
+/// POST /api/movements/transfer - Move shares of an investment to another portfolio
+///
+/// Creates a transfer out and a transfer in movement in one transaction and returns their
+/// IDs. Transfers carry the lots with their cost to the target portfolio and do not count
+/// as sale or purchase.
+pub async fn create_transfer(
+    State(state): State<MovementState>,
+    Json(req): Json<TransferRequest>,
+) -> Result<Json<BulkCreateMovementsResponse>> {
+    let investment_id = req.investment_id;
+    let movements = req.into_movements()?;
+    let mut errors = ValidationErrors::default();
+    ReferenceCheck::new(&state)
+        .check(Some(investment_id), None, &mut errors)
+        .await?;
+    errors.into_result()?;
+
+    let ids = state.movement_repo.create_many(&movements).await?;
+    Ok(Json(BulkCreateMovementsResponse { ids }))
+}
+
+/// POST /api/movements/check-duplicates - Find movements that were already recorded
+///
+/// Returns the position of every given movement that matches a stored movement of the
+/// same investment, date and action, together with the IDs of the matches.
+pub async fn check_duplicate_movements(
+    State(state): State<MovementState>,
+    Query(query): Query<DuplicateCheckQuery>,
+    Json(reqs): Json<Vec<CreateMovementRequest>>,
+) -> Result<Json<Vec<DuplicateMovement>>> {
+    let tolerance = query.tolerance.unwrap_or(DEFAULT_DUPLICATE_TOLERANCE);
+    if tolerance < Decimal::ZERO {
+        return Err(AppError::InvalidInput(
+            "tolerance must not be negative".to_string(),
+        ));
+    }
+
+    let movements: Vec<Movement> = reqs.into_iter().map(|r| r.into_movement(0)).collect();
+    let duplicates = DuplicateDetector::new(state.movement_repo)
+        .find_duplicates(&movements, tolerance)
+        .await?;
+    Ok(Json(duplicates))
+}
+
+/// PUT /api/movements/:id - Update a movement; the external reference is kept if omitted
 pub async fn update_movement(
-    State(repo): State<Arc<dyn MovementRepository>>,
+    State(state): State<MovementState>,
     Path(id): Path<i64>,
     Json(req): Json<CreateMovementRequest>,
 ) -> Result<Json<MovementResponse>> {
-    let movement = Movement {
+    validate_movement(&state, &req).await?;
+    let existing = state
+        .movement_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Movement", id))?;
+    let mut movement = req.into_movement(id);
+    if movement.external_id.is_none() {
+        movement.external_id = existing.external_id;
+    }
+    check_holding(&state, &movement, Some(id)).await?;
+
+    ensure_found(
+        state.movement_repo.update(id, &movement).await?,
+        "Movement",
         id,
-        date: req.date,
-        action_id: req.action_id,
-        investment_id: req.investment_id,
-        quantity: req.quantity,
-        amount: req.amount,
-        fee: req.fee,
+    )?;
+    let updated = state
+        .movement_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Movement", id))?;
+    Ok(Json(updated.into()))
+}
+
+/// PUT /api/movements/by-external-id/:external_id - Create or update the movement with the
+/// external reference in the portfolio of the request
+///
+/// Responds with 201 if the movement was created and 200 if it was updated, so the same
+/// request can be repeated without creating a duplicate.
+pub async fn upsert_movement_by_external_id(
+    State(state): State<MovementState>,
+    Path(external_id): Path<String>,
+    Json(mut req): Json<CreateMovementRequest>,
+) -> Result<(StatusCode, Json<MovementResponse>)> {
+    if req
+        .external_id
+        .as_ref()
+        .is_some_and(|id| *id != external_id)
+    {
+        return Err(AppError::InvalidInput(format!(
+            "external_id of the body differs from '{}' of the path",
+            external_id
+        )));
+    }
+    req.external_id = Some(external_id.clone());
+    validate_movement(&state, &req).await?;
+
+    let existing = state
+        .movement_repo
+        .find_by_external_id(req.portfolio_id, &external_id)
+        .await?;
+    let (status, id) = match existing {
+        Some(existing) => {
+            let movement = req.into_movement(existing.id);
+            check_holding(&state, &movement, Some(existing.id)).await?;
+            state.movement_repo.update(existing.id, &movement).await?;
+            (StatusCode::OK, existing.id)
+        }
+        None => {
+            let movement = req.into_movement(0);
+            check_holding(&state, &movement, None).await?;
+            let id = state.movement_repo.create(&movement).await?;
+            (StatusCode::CREATED, id)
+        }
     };
 
-    repo.update(id, &movement).await?;
-    let updated = repo.find_by_id(id).await?.ok_or(AppError::NotFound)?;
-    Ok(Json(updated.into()))
+    let movement = state
+        .movement_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Movement", id))?;
+    Ok((status, Json(movement.into())))
 }
 
 pub async fn delete_movement(
-    State(repo): State<Arc<dyn MovementRepository>>,
+    State(state): State<MovementState>,
     Path(id): Path<i64>,
 ) -> Result<Json<()>> {
-    repo.delete(id).await?;
+    ensure_found(state.movement_repo.delete(id).await?, "Movement", id)?;
     Ok(Json(()))
 }
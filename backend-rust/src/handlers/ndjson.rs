@@ -0,0 +1,67 @@
+use crate::services::DisplayPrecision;
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+
+/// Content type of newline delimited JSON, one row per line
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Rows serialized into one chunk of the streamed body
+const NDJSON_CHUNK_ROWS: usize = 256;
+
+/// Whether the `Accept` header asks for newline delimited JSON
+pub fn accepts_ndjson(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media_type| {
+            media_type
+                .split(';')
+                .next()
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case(NDJSON_CONTENT_TYPE))
+        })
+}
+
+/// Rows as one JSON array, or streamed as newline delimited JSON if the client accepts it
+///
+/// The stream serializes the rows chunk by chunk while the body is sent, so the first rows
+/// reach the client before the last ones are serialized.
+pub fn json_rows<T>(headers: &HeaderMap, rows: Vec<T>) -> Response
+where
+    T: Serialize + Send + 'static,
+{
+    if !accepts_ndjson(headers) {
+        return Json(rows).into_response();
+    }
+
+    // The body is serialized after the request's display precision went out of scope
+    let precision = DisplayPrecision::current();
+    let body = stream::iter(rows)
+        .chunks(NDJSON_CHUNK_ROWS)
+        .map(move |chunk| {
+            precision.sync_scope(|| {
+                let mut bytes = Vec::new();
+                for row in chunk {
+                    serde_json::to_writer(&mut bytes, &row)?;
+                    bytes.push(b'\n');
+                }
+                Ok::<_, serde_json::Error>(bytes)
+            })
+        });
+
+    (
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(NDJSON_CONTENT_TYPE),
+        )],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
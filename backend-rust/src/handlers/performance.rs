@@ -0,0 +1,189 @@
+use crate::error::{AppError, Result};
+use crate::handlers::tags::tagged_investments;
+use crate::routes::{BenchmarkState, CalculatorState, GainsState, HoldingsState, RiskState};
+use crate::services::cost_basis::{CostBasisMethod, InvestmentGains};
+use crate::services::holding_stats::HoldingsReport;
+use crate::services::portfolio_calculator::{BenchmarkComparison, TimeWeightedReturn};
+use crate::services::risk_metrics::RiskReport;
+use crate::validation::{Validate, ValidationErrors};
+use axum::{extract::Query, extract::State, Json};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct PerformanceQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    /// Only the investments with this tag
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReturnQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    /// Only the investments with this tag
+    pub tag: Option<String>,
+    /// Currency to measure the return in instead of the base currency, e.g. `USD`
+    pub currency: Option<String>,
+}
+
+impl Validate for ReturnQuery {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.currency("currency", self.currency.as_deref());
+    }
+}
+
+/// GET /api/performance/twr - Time-weighted return per investment and in total
+pub async fn get_time_weighted_return(
+    State(state): State<CalculatorState>,
+    Query(params): Query<ReturnQuery>,
+) -> Result<Json<TimeWeightedReturn>> {
+    params.validate()?;
+    let investments = tagged_investments(state.tag_repo.as_ref(), params.tag.as_deref()).await?;
+    let twr = state
+        .calculator
+        .calculate_time_weighted_return(
+            investments.as_ref(),
+            params.start_date,
+            params.end_date,
+            params.currency.as_deref(),
+        )
+        .await?;
+    Ok(Json(twr))
+}
+
+/// GET /api/performance/risk - Drawdowns and volatility per investment and in total
+pub async fn get_risk_metrics(
+    State(state): State<RiskState>,
+    Query(params): Query<PerformanceQuery>,
+) -> Result<Json<RiskReport>> {
+    let investments = tagged_investments(state.tag_repo.as_ref(), params.tag.as_deref()).await?;
+    let report = state
+        .service
+        .calculate(investments.as_ref(), params.start_date, params.end_date)
+        .await?;
+    Ok(Json(report))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HoldingsQuery {
+    /// Only the investments with this tag
+    pub tag: Option<String>,
+}
+
+/// GET /api/performance/holdings - Holding period, annualized return and contribution to
+/// the portfolio return per investment
+pub async fn get_holdings(
+    State(state): State<HoldingsState>,
+    Query(params): Query<HoldingsQuery>,
+) -> Result<Json<HoldingsReport>> {
+    let investments = tagged_investments(state.tag_repo.as_ref(), params.tag.as_deref()).await?;
+    let report = state.service.holdings(investments.as_ref()).await?;
+    Ok(Json(report))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GainsQuery {
+    pub method: Option<String>,
+    /// Restrict to the lots held in one portfolio
+    pub portfolio_id: Option<i64>,
+    pub end_date: Option<NaiveDate>,
+    /// Only the investments with this tag
+    pub tag: Option<String>,
+}
+
+/// GET /api/performance/gains - Cost basis and realized gains per investment
+///
+/// Uses the cost basis method from the settings unless `method` is given. With
+/// `portfolio_id` only the lots of that portfolio are reported, including lots
+/// transferred in from other portfolios. With `tag` only the investments with the
+/// tag are reported.
+pub async fn get_gains(
+    State(state): State<GainsState>,
+    Query(params): Query<GainsQuery>,
+) -> Result<Json<Vec<InvestmentGains>>> {
+    let method = match params.method {
+        Some(method) => method.parse()?,
+        None => state
+            .settings_repo
+            .get()
+            .await?
+            .map(|s| s.cost_basis_method.parse())
+            .transpose()?
+            .unwrap_or(CostBasisMethod::Fifo),
+    };
+
+    let investments = tagged_investments(state.tag_repo.as_ref(), params.tag.as_deref()).await?;
+    let mut gains = match params.portfolio_id {
+        Some(portfolio_id) => {
+            state
+                .calculator
+                .calculate_portfolio_gains(portfolio_id, method, params.end_date)
+                .await?
+        }
+        None => {
+            state
+                .calculator
+                .calculate_gains(method, params.end_date)
+                .await?
+        }
+    };
+    if let Some(ids) = investments {
+        gains.retain(|g| ids.contains(&g.investment));
+    }
+    Ok(Json(gains))
+}
+
+/// GET /api/performance/vs-benchmark - Portfolio and benchmark indexed to 100
+///
+/// The benchmark is the investment from the settings, or the investment with the
+/// ticker symbol from the settings.
+pub async fn get_benchmark_comparison(
+    State(state): State<BenchmarkState>,
+    Query(params): Query<PerformanceQuery>,
+) -> Result<Json<BenchmarkComparison>> {
+    let settings = state
+        .settings_repo
+        .get()
+        .await?
+        .ok_or_else(|| AppError::not_found("Settings", 1))?;
+    let benchmark_investment_id =
+        match (settings.benchmark_investment_id, settings.benchmark_ticker) {
+            (Some(investment_id), _) => investment_id,
+            (None, Some(ticker)) => state
+                .investment_repo
+                .find_all()
+                .await?
+                .into_iter()
+                .find(|inv| {
+                    inv.ticker_symbol
+                        .as_deref()
+                        .is_some_and(|symbol| symbol.eq_ignore_ascii_case(&ticker))
+                })
+                .map(|inv| inv.id)
+                .ok_or_else(|| {
+                    AppError::InvalidInput(format!(
+                        "No investment with ticker {}; add it as investment to fetch its quotes",
+                        ticker
+                    ))
+                })?,
+            (None, None) => {
+                return Err(AppError::InvalidInput(
+                    "No benchmark set in the settings".to_string(),
+                ))
+            }
+        };
+
+    let investments = tagged_investments(state.tag_repo.as_ref(), params.tag.as_deref()).await?;
+    let comparison = state
+        .calculator
+        .calculate_benchmark_comparison(
+            benchmark_investment_id,
+            investments.as_ref(),
+            params.start_date,
+            params.end_date,
+        )
+        .await?;
+    Ok(Json(comparison))
+}
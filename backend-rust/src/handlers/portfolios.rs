@@ -0,0 +1,110 @@
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::Portfolio;
+use crate::repository::traits::PortfolioRepository;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Serialize)]
+pub struct PortfolioResponse {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl From<Portfolio> for PortfolioResponse {
+    fn from(p: Portfolio) -> Self {
+        Self {
+            id: p.id,
+            name: p.name,
+            description: p.description,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePortfolioRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl CreatePortfolioRequest {
+    fn into_portfolio(self, id: i64) -> Result<Portfolio> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidInput(
+                "Portfolio name must not be empty".to_string(),
+            ));
+        }
+
+        Ok(Portfolio {
+            id,
+            name: name.to_string(),
+            description: self.description,
+        })
+    }
+}
+
+/// GET /api/portfolios - List all portfolios
+pub async fn list_portfolios(
+    State(repo): State<Arc<dyn PortfolioRepository>>,
+) -> Result<Json<Vec<PortfolioResponse>>> {
+    let portfolios = repo.find_all().await?;
+    let response: Vec<PortfolioResponse> = portfolios.into_iter().map(Into::into).collect();
+    Ok(Json(response))
+}
+
+/// GET /api/portfolios/:id - Get a single portfolio
+pub async fn get_portfolio(
+    State(repo): State<Arc<dyn PortfolioRepository>>,
+    Path(id): Path<i64>,
+) -> Result<Json<PortfolioResponse>> {
+    let portfolio = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Portfolio", id))?;
+    Ok(Json(portfolio.into()))
+}
+
+/// POST /api/portfolios - Create a portfolio
+pub async fn create_portfolio(
+    State(repo): State<Arc<dyn PortfolioRepository>>,
+    Json(req): Json<CreatePortfolioRequest>,
+) -> Result<Json<PortfolioResponse>> {
+    let portfolio = req.into_portfolio(0)?;
+
+    let id = repo.create(&portfolio).await?;
+    let created = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Portfolio", id))?;
+    Ok(Json(created.into()))
+}
+
+/// PUT /api/portfolios/:id - Update a portfolio
+pub async fn update_portfolio(
+    State(repo): State<Arc<dyn PortfolioRepository>>,
+    Path(id): Path<i64>,
+    Json(req): Json<CreatePortfolioRequest>,
+) -> Result<Json<PortfolioResponse>> {
+    let portfolio = req.into_portfolio(id)?;
+
+    ensure_found(repo.update(id, &portfolio).await?, "Portfolio", id)?;
+    let updated = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Portfolio", id))?;
+    Ok(Json(updated.into()))
+}
+
+/// DELETE /api/portfolios/:id - Delete a portfolio; its movements become unassigned
+pub async fn delete_portfolio(
+    State(repo): State<Arc<dyn PortfolioRepository>>,
+    Path(id): Path<i64>,
+) -> Result<Json<()>> {
+    ensure_found(repo.delete(id).await?, "Portfolio", id)?;
+    Ok(Json(()))
+}
@@ -1,11 +1,15 @@
 use crate::error::Result;
-use crate::models::InvestmentPrice;
+use crate::models::{InvestmentPrice, MANUAL_PRICE_SOURCE};
 use crate::repository::traits::InvestmentPriceRepository;
+use crate::services::display_precision;
+use crate::validation::{Validate, ValidationErrors};
+use async_graphql::{InputObject, SimpleObject};
 use axum::{
     extract::{Query, State},
     Json,
 };
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -16,20 +20,54 @@ pub struct ListPricesQuery {
     pub end_date: Option<NaiveDate>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, InputObject)]
+#[graphql(name = "PriceInput")]
 pub struct CreatePriceRequest {
     pub date: NaiveDate,
     pub investment_id: i64,
-    pub price: f64,
+    pub price: Decimal,
     pub source: Option<String>,
+    pub currency: Option<String>,
+    pub original_price: Option<Decimal>,
+}
+
+impl Validate for CreatePriceRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.date("date", Some(self.date));
+        errors.non_negative("price", Some(self.price));
+        errors.currency("currency", self.currency.as_deref());
+        errors.non_negative("original_price", self.original_price);
+    }
+}
+
+impl CreatePriceRequest {
+    /// Price to store, entered by hand if no source is given
+    fn into_price(self) -> InvestmentPrice {
+        InvestmentPrice {
+            date: Some(self.date),
+            investment_id: Some(self.investment_id),
+            price: Some(self.price),
+            source: Some(
+                self.source
+                    .unwrap_or_else(|| MANUAL_PRICE_SOURCE.to_string()),
+            ),
+            currency: self.currency,
+            original_price: self.original_price,
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, SimpleObject)]
+#[graphql(name = "Price")]
 pub struct PriceResponse {
     pub date: NaiveDate,
     pub investment_id: i64,
-    pub price: f64,
+    #[serde(serialize_with = "display_precision::price")]
+    pub price: Decimal,
     pub source: Option<String>,
+    pub currency: Option<String>,
+    #[serde(serialize_with = "display_precision::price")]
+    pub original_price: Option<Decimal>,
 }
 
 impl From<InvestmentPrice> for PriceResponse {
@@ -39,6 +77,8 @@ impl From<InvestmentPrice> for PriceResponse {
             investment_id: price.investment_id.unwrap_or_default(),
             price: price.price.unwrap_or_default(),
             source: price.source,
+            currency: price.currency,
+            original_price: price.original_price,
         }
     }
 }
@@ -60,12 +100,8 @@ pub async fn create_investment_price(
     State(repo): State<Arc<dyn InvestmentPriceRepository>>,
     Json(req): Json<CreatePriceRequest>,
 ) -> Result<Json<PriceResponse>> {
-    let price = InvestmentPrice {
-        date: Some(req.date),
-        investment_id: Some(req.investment_id),
-        price: Some(req.price),
-        source: req.source,
-    };
+    req.validate()?;
+    let price = req.into_price();
 
     repo.create(&price).await?;
 
@@ -77,14 +113,26 @@ pub async fn upsert_investment_price(
     State(repo): State<Arc<dyn InvestmentPriceRepository>>,
     Json(req): Json<CreatePriceRequest>,
 ) -> Result<Json<PriceResponse>> {
-    let price = InvestmentPrice {
-        date: Some(req.date),
-        investment_id: Some(req.investment_id),
-        price: Some(req.price),
-        source: req.source,
-    };
+    req.validate()?;
+    let price = req.into_price();
 
     repo.upsert(&price).await?;
 
     Ok(Json(price.into()))
 }
+
+/// POST /api/investmentprices/bulk-upsert - Upsert several prices in one transaction
+///
+/// Meant for prices entered by hand, e.g. pasted from a fund's fact sheet. If one price is
+/// invalid, none are stored.
+pub async fn bulk_upsert_investment_prices(
+    State(repo): State<Arc<dyn InvestmentPriceRepository>>,
+    Json(reqs): Json<Vec<CreatePriceRequest>>,
+) -> Result<Json<Vec<PriceResponse>>> {
+    reqs.validate()?;
+    let prices: Vec<InvestmentPrice> = reqs.into_iter().map(|req| req.into_price()).collect();
+
+    repo.upsert_many(&prices).await?;
+
+    Ok(Json(prices.into_iter().map(Into::into).collect()))
+}
@@ -1,12 +1,21 @@
 use crate::error::Result;
+use crate::models::{IntradayPrice, QuoteFetchLog};
+use crate::repository::traits::QuoteFetchLogRepository;
 use crate::routes::QuoteFetchState;
-use crate::services::quote_fetcher::{ProviderInfo, QuoteFetchResult, QuoteFetcherService};
+use crate::services::display_precision;
+use crate::services::price_gaps::InvestmentPriceGaps;
+use crate::services::quote_fetcher::{
+    InvestmentEnrichment, ProviderInfo, QuoteFetchResult, QuoteFetcherService,
+};
+use crate::services::quote_scheduler::{QuoteFetchStatus, QuoteFetchStatusTracker};
+use crate::services::PriceGapService;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
 use chrono::NaiveDate;
-use serde::Serialize;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 #[derive(Debug, Serialize)]
@@ -35,7 +44,8 @@ pub struct GetQuotesResponse {
 #[derive(Debug, Serialize)]
 pub struct QuoteInfo {
     pub date: NaiveDate,
-    pub price: f64,
+    #[serde(serialize_with = "display_precision::price")]
+    pub price: Decimal,
     pub source: String,
 }
 
@@ -94,11 +104,22 @@ pub async fn fetch_latest_quotes(
     };
 
     // Instantiate service on-the-fly
-    let service = QuoteFetcherService::new(
+    let mut service = QuoteFetcherService::new(
         state.investment_repo.clone(),
         state.price_repo.clone(),
         base_currency,
-    );
+    )
+    .with_fetch_log(state.fetch_log_repo.clone())
+    .with_fx_rates(state.fx_rate_repo.clone())
+    .with_price_alerts(state.alert_repo.clone())
+    .with_corporate_events(state.event_repo.clone())
+    .with_webhooks(state.webhooks.clone())
+    .with_api_keys(state.api_keys.clone())
+    .with_provider_configs(state.provider_config_repo.clone())
+    .with_fetch_days(state.fetch_days);
+    if let Some(email) = &state.email {
+        service = service.with_email(email.clone());
+    }
 
     // Fetch quotes for this investment
     let result = service.fetch_quotes_for_investment(&investment).await?;
@@ -108,7 +129,133 @@ pub async fn fetch_latest_quotes(
         success: result.success,
         error: result.error,
         quotes_fetched: result.quotes_stored,
-        provider: Some(quote_provider),
+        provider: result.provider.or(Some(quote_provider)),
+    }))
+}
+
+/// POST /api/investments/:id/enrich - Fill in missing investment fields from its quote provider
+pub async fn enrich_investment(
+    State(state): State<QuoteFetchState>,
+    Path(investment_id): Path<i64>,
+) -> Result<Json<InvestmentEnrichment>> {
+    tracing::info!("Enriching investment ID: {}", investment_id);
+
+    let base_currency = state
+        .settings_repo
+        .get()
+        .await?
+        .map(|s| s.base_currency)
+        .unwrap_or_else(|| "EUR".to_string());
+
+    let service = QuoteFetcherService::new(
+        state.investment_repo.clone(),
+        state.price_repo.clone(),
+        base_currency,
+    )
+    .with_api_keys(state.api_keys.clone())
+    .with_provider_configs(state.provider_config_repo.clone());
+
+    Ok(Json(service.enrich_investment(investment_id).await?))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LatestQuoteQuery {
+    pub investment_id: i64,
+}
+
+/// GET /api/quotes/latest?investment_id= - Live price during the trading day, stored apart
+/// from the daily closes
+pub async fn get_latest_quote(
+    State(state): State<QuoteFetchState>,
+    Query(params): Query<LatestQuoteQuery>,
+) -> Result<Json<IntradayPrice>> {
+    let base_currency = state
+        .settings_repo
+        .get()
+        .await?
+        .map(|s| s.base_currency)
+        .unwrap_or_else(|| "EUR".to_string());
+
+    let service = QuoteFetcherService::new(
+        state.investment_repo.clone(),
+        state.price_repo.clone(),
+        base_currency,
+    )
+    .with_fx_rates(state.fx_rate_repo.clone())
+    .with_intraday_prices(state.intraday_repo.clone())
+    .with_api_keys(state.api_keys.clone())
+    .with_provider_configs(state.provider_config_repo.clone());
+
+    Ok(Json(service.live_quote(params.investment_id).await?))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BackfillQuotesRequest {
+    /// The full history the provider has if unset
+    pub start_date: Option<NaiveDate>,
+    /// Defaults to today
+    pub end_date: Option<NaiveDate>,
+}
+
+/// POST /api/quotes/:investment_id/backfill - Load historical quotes for a date range,
+/// or the full history without start date
+pub async fn backfill_quotes(
+    State(state): State<QuoteFetchState>,
+    Path(investment_id): Path<i64>,
+    Json(req): Json<BackfillQuotesRequest>,
+) -> Result<Json<FetchQuotesForInvestmentResponse>> {
+    let end_date = req
+        .end_date
+        .unwrap_or_else(|| chrono::Utc::now().date_naive());
+
+    // Get base currency from settings
+    let base_currency = state
+        .settings_repo
+        .get()
+        .await?
+        .map(|s| s.base_currency)
+        .unwrap_or_else(|| "EUR".to_string());
+
+    let service = QuoteFetcherService::new(
+        state.investment_repo.clone(),
+        state.price_repo.clone(),
+        base_currency,
+    )
+    .with_fetch_log(state.fetch_log_repo.clone())
+    .with_fx_rates(state.fx_rate_repo.clone())
+    .with_corporate_events(state.event_repo.clone())
+    .with_api_keys(state.api_keys.clone())
+    .with_provider_configs(state.provider_config_repo.clone());
+
+    let result = match req.start_date {
+        Some(start_date) => {
+            tracing::info!(
+                "Backfilling quotes for investment ID: {} ({} to {})",
+                investment_id,
+                start_date,
+                end_date
+            );
+            service
+                .backfill_quotes_for_investment(investment_id, start_date, end_date)
+                .await?
+        }
+        None => {
+            tracing::info!(
+                "Backfilling the full quote history for investment ID: {}",
+                investment_id
+            );
+            service
+                .backfill_full_history_for_investment(investment_id)
+                .await?
+        }
+    };
+
+    Ok(Json(FetchQuotesForInvestmentResponse {
+        investment_id: result.investment_id,
+        success: result.success,
+        error: result.error,
+        quotes_fetched: result.quotes_stored,
+        provider: result.provider,
     }))
 }
 
@@ -161,3 +308,45 @@ pub async fn fetch_quotes(
         failed,
     }))
 }
+
+/// GET /api/quotes/fetch-status - Status of the scheduled background quote fetch
+pub async fn get_fetch_status(
+    State(tracker): State<QuoteFetchStatusTracker>,
+) -> Result<Json<QuoteFetchStatus>> {
+    Ok(Json(tracker.get().await))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FetchLogQuery {
+    pub investment_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/quotes/fetch-log - Recorded quote fetch results, most recent first
+pub async fn get_fetch_log(
+    State(repo): State<Arc<dyn QuoteFetchLogRepository>>,
+    Query(params): Query<FetchLogQuery>,
+) -> Result<Json<Vec<QuoteFetchLog>>> {
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+    let entries = repo.find_recent(params.investment_id, limit).await?;
+    Ok(Json(entries))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceGapQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub include_weekends: bool,
+}
+
+/// GET /api/quotes/gaps - Date ranges without prices for held investments
+pub async fn get_price_gaps(
+    State(service): State<Arc<PriceGapService>>,
+    Query(params): Query<PriceGapQuery>,
+) -> Result<Json<Vec<InvestmentPriceGaps>>> {
+    let gaps = service
+        .find_gaps(params.start_date, params.end_date, params.include_weekends)
+        .await?;
+    Ok(Json(gaps))
+}
@@ -0,0 +1,41 @@
+use crate::error::Result;
+use crate::services::reports::{PeriodPerformance, ReportPeriod, WeeklySummary};
+use crate::services::tax::germany::GermanTaxReport;
+use crate::services::{GermanTaxService, ReportService};
+use axum::{extract::Query, extract::State, Json};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// GET /api/reports/weekly-summary - Value, change and dividends of the latest week, as emailed
+pub async fn get_weekly_summary(
+    State(service): State<Arc<ReportService>>,
+) -> Result<Json<WeeklySummary>> {
+    Ok(Json(service.weekly_summary().await?))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PeriodicReportQuery {
+    #[serde(default)]
+    pub granularity: ReportPeriod,
+}
+
+/// GET /api/reports/periodic - Values, cash flows and return per month or year
+pub async fn get_periodic_report(
+    State(service): State<Arc<ReportService>>,
+    Query(params): Query<PeriodicReportQuery>,
+) -> Result<Json<Vec<PeriodPerformance>>> {
+    Ok(Json(service.periodic_report(params.granularity).await?))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaxReportQuery {
+    pub year: Option<i32>,
+}
+
+/// GET /api/reports/tax/de - Capital income of a year for the German Anlage KAP
+pub async fn get_german_tax_report(
+    State(service): State<Arc<GermanTaxService>>,
+    Query(params): Query<TaxReportQuery>,
+) -> Result<Json<GermanTaxReport>> {
+    Ok(Json(service.report(params.year).await?))
+}
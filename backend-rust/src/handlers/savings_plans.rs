@@ -0,0 +1,167 @@
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::{SavingsInterval, SavingsPlan, VALID_SAVINGS_INTERVALS};
+use crate::routes::SavingsPlanState;
+use crate::services::savings_plans::{PlanComparison, SavingsPlanProjection};
+use crate::validation::{Validate, ValidationErrors};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SavingsPlanRequest {
+    pub investment_id: i64,
+    /// Amount per execution in the base currency
+    pub amount: Decimal,
+    /// `weekly`, `monthly`, `quarterly` or `yearly`
+    pub interval: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+}
+
+impl Validate for SavingsPlanRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.positive("amount", Some(self.amount));
+        if self.interval.parse::<SavingsInterval>().is_err() {
+            errors.add(
+                "interval",
+                format!("must be one of {}", VALID_SAVINGS_INTERVALS.join(", ")),
+            );
+        }
+        if self.end_date.is_some_and(|end| end < self.start_date) {
+            errors.add("end_date", "must not be before start_date");
+        }
+    }
+}
+
+impl SavingsPlanRequest {
+    async fn into_plan(self, id: i64, state: &SavingsPlanState) -> Result<SavingsPlan> {
+        self.validate()?;
+        if state
+            .investment_repo
+            .find_by_id(self.investment_id)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Investment {} does not exist",
+                self.investment_id
+            )));
+        }
+
+        Ok(SavingsPlan {
+            id,
+            investment_id: self.investment_id,
+            amount: self.amount,
+            interval: self.interval.parse::<SavingsInterval>()?.to_string(),
+            start_date: self.start_date,
+            end_date: self.end_date,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavingsPlanQuery {
+    pub investment_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectionQuery {
+    /// Defaults to 120
+    pub months: Option<u32>,
+    /// Expected yearly return as a fraction, defaults to 0.05
+    pub annual_return: Option<f64>,
+}
+
+/// GET /api/savings-plans - List savings plans, optionally of one investment
+pub async fn list_savings_plans(
+    State(state): State<SavingsPlanState>,
+    Query(query): Query<SavingsPlanQuery>,
+) -> Result<Json<Vec<SavingsPlan>>> {
+    let plans = state.plan_repo.find_all(query.investment_id).await?;
+    Ok(Json(plans))
+}
+
+/// GET /api/savings-plans/:id - Get a single savings plan
+pub async fn get_savings_plan(
+    State(state): State<SavingsPlanState>,
+    Path(id): Path<i64>,
+) -> Result<Json<SavingsPlan>> {
+    let plan = state
+        .plan_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("SavingsPlan", id))?;
+    Ok(Json(plan))
+}
+
+/// POST /api/savings-plans - Create a savings plan
+pub async fn create_savings_plan(
+    State(state): State<SavingsPlanState>,
+    Json(req): Json<SavingsPlanRequest>,
+) -> Result<Json<SavingsPlan>> {
+    let plan = req.into_plan(0, &state).await?;
+
+    let id = state.plan_repo.create(&plan).await?;
+    let created = state
+        .plan_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("SavingsPlan", id))?;
+    Ok(Json(created))
+}
+
+/// PUT /api/savings-plans/:id - Update a savings plan
+pub async fn update_savings_plan(
+    State(state): State<SavingsPlanState>,
+    Path(id): Path<i64>,
+    Json(req): Json<SavingsPlanRequest>,
+) -> Result<Json<SavingsPlan>> {
+    let plan = req.into_plan(id, &state).await?;
+
+    let rows = state.plan_repo.update(id, &plan).await?;
+    ensure_found(rows, "SavingsPlan", id)?;
+    let updated = state
+        .plan_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("SavingsPlan", id))?;
+    Ok(Json(updated))
+}
+
+/// DELETE /api/savings-plans/:id - Delete a savings plan
+pub async fn delete_savings_plan(
+    State(state): State<SavingsPlanState>,
+    Path(id): Path<i64>,
+) -> Result<Json<()>> {
+    ensure_found(state.plan_repo.delete(id).await?, "SavingsPlan", id)?;
+    Ok(Json(()))
+}
+
+/// GET /api/savings-plans/projection - Future portfolio value with all savings plans
+pub async fn get_savings_plan_projection(
+    State(state): State<SavingsPlanState>,
+    Query(query): Query<ProjectionQuery>,
+) -> Result<Json<SavingsPlanProjection>> {
+    let months = query.months.unwrap_or(120).clamp(1, 600);
+    let annual_return = query.annual_return.unwrap_or(0.05);
+    if !annual_return.is_finite() || annual_return <= -1.0 {
+        return Err(AppError::InvalidInput(
+            "annual_return must be greater than -1".to_string(),
+        ));
+    }
+
+    let projection = state.service.projection(months, annual_return).await?;
+    Ok(Json(projection))
+}
+
+/// GET /api/savings-plans/:id/comparison - Planned executions up to today vs. recorded buys
+pub async fn get_savings_plan_comparison(
+    State(state): State<SavingsPlanState>,
+    Path(id): Path<i64>,
+) -> Result<Json<PlanComparison>> {
+    Ok(Json(state.service.comparison(id).await?))
+}
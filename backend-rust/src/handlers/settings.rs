@@ -1,14 +1,41 @@
 use crate::error::{AppError, Result};
-use crate::models::Settings;
+use crate::models::{ProviderConfig, Settings};
 use crate::repository::traits::SettingsRepository;
-use axum::{extract::State, Json};
-use serde::{Deserialize, Serialize};
+use crate::routes::ProviderSettingsState;
+use crate::services::cost_basis::CostBasisMethod;
+use crate::services::display_precision::{RoundingMode, MAX_DISPLAY_DECIMALS};
+use crate::services::price_recalculation::PriceRecalculationResult;
+use crate::services::quote_fetcher::AVAILABLE_PROVIDERS;
+use crate::services::quotes::ProviderApiKeys;
+use crate::services::{DisplayPrecision, PriceRecalculationService};
+use crate::validation::{Validate, ValidationErrors};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::types::Json as JsonColumn;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 #[derive(Debug, Serialize)]
 pub struct SettingsResponse {
     pub id: i64,
     pub base_currency: String,
+    pub cost_basis_method: String,
+    pub benchmark_investment_id: Option<i64>,
+    pub benchmark_ticker: Option<String>,
+    pub webhook_urls: Vec<String>,
+    /// The secret itself is never returned
+    pub webhook_secret_set: bool,
+    pub price_source_priority: Vec<String>,
+    pub value_decimals: Option<i32>,
+    pub price_decimals: Option<i32>,
+    pub quantity_decimals: Option<i32>,
+    pub rounding_mode: String,
+    pub reject_negative_holdings: bool,
 }
 
 impl From<Settings> for SettingsResponse {
@@ -16,19 +43,108 @@ impl From<Settings> for SettingsResponse {
         Self {
             id: s.id,
             base_currency: s.base_currency,
+            cost_basis_method: s.cost_basis_method,
+            benchmark_investment_id: s.benchmark_investment_id,
+            benchmark_ticker: s.benchmark_ticker,
+            webhook_urls: s.webhook_urls.0,
+            webhook_secret_set: s.webhook_secret.is_some(),
+            price_source_priority: s.price_source_priority.0,
+            value_decimals: s.value_decimals,
+            price_decimals: s.price_decimals,
+            quantity_decimals: s.quantity_decimals,
+            rounding_mode: s.rounding_mode,
+            reject_negative_holdings: s.reject_negative_holdings,
         }
     }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateSettingsRequest {
-    pub base_currency: String,
+    pub base_currency: Option<String>,
+    pub cost_basis_method: Option<String>,
+    /// Benchmark investment; `null` removes the benchmark
+    #[serde(default, deserialize_with = "present")]
+    pub benchmark_investment_id: Option<Option<i64>>,
+    /// Benchmark ticker symbol; `null` removes the benchmark
+    #[serde(default, deserialize_with = "present")]
+    pub benchmark_ticker: Option<Option<String>>,
+    /// Replaces all webhook URLs; an empty list disables webhooks
+    pub webhook_urls: Option<Vec<String>>,
+    /// Secret for signing webhook requests; `null` sends them unsigned
+    #[serde(default, deserialize_with = "present")]
+    pub webhook_secret: Option<Option<String>>,
+    /// Quote sources in order of preference when several have a price for the same day;
+    /// an investment's quote provider chain takes precedence
+    pub price_source_priority: Option<Vec<String>>,
+    /// Decimal places of amounts, fees and market values in responses; `null` keeps all
+    #[serde(default, deserialize_with = "present")]
+    pub value_decimals: Option<Option<i32>>,
+    /// Decimal places of prices per unit in responses; `null` keeps all
+    #[serde(default, deserialize_with = "present")]
+    pub price_decimals: Option<Option<i32>>,
+    /// Decimal places of quantities in responses; `null` keeps all
+    #[serde(default, deserialize_with = "present")]
+    pub quantity_decimals: Option<Option<i32>>,
+    /// Rounding to the display precision, e.g. `half_even` or `half_up`
+    pub rounding_mode: Option<String>,
+    /// Reject sells of more than the holding of their portfolio on their date
+    pub reject_negative_holdings: Option<bool>,
+}
+
+impl Validate for UpdateSettingsRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        errors.currency("base_currency", self.base_currency.as_deref());
+        for (index, url) in self.webhook_urls.iter().flatten().enumerate() {
+            let valid = reqwest::Url::parse(url.trim())
+                .is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+            if !valid {
+                errors.add(
+                    &format!("webhook_urls[{}]", index),
+                    format!("'{}' is not an http(s) URL", url),
+                );
+            }
+        }
+        for (index, source) in self.price_source_priority.iter().flatten().enumerate() {
+            if source.trim().is_empty() {
+                errors.add(
+                    &format!("price_source_priority[{}]", index),
+                    "Source must not be empty",
+                );
+            }
+        }
+        for (field, decimals) in [
+            ("value_decimals", self.value_decimals),
+            ("price_decimals", self.price_decimals),
+            ("quantity_decimals", self.quantity_decimals),
+        ] {
+            if let Some(Some(decimals)) = decimals {
+                if !(0..=MAX_DISPLAY_DECIMALS as i32).contains(&decimals) {
+                    errors.add(
+                        field,
+                        format!("Must be between 0 and {}", MAX_DISPLAY_DECIMALS),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Distinguish a field set to `null` (`Some(None)`) from a missing field (`None`)
+fn present<'de, T, D>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 pub async fn get_settings(
     State(repo): State<Arc<dyn SettingsRepository>>,
 ) -> Result<Json<SettingsResponse>> {
-    let settings = repo.get().await?.ok_or(AppError::NotFound)?;
+    let settings = repo
+        .get()
+        .await?
+        .ok_or_else(|| AppError::not_found("Settings", 1))?;
     Ok(Json(settings.into()))
 }
 
@@ -36,11 +152,208 @@ pub async fn update_settings(
     State(repo): State<Arc<dyn SettingsRepository>>,
     Json(req): Json<UpdateSettingsRequest>,
 ) -> Result<Json<SettingsResponse>> {
-    let settings = Settings {
-        id: 1,
-        base_currency: req.base_currency,
-    };
+    req.validate()?;
+    let mut settings = repo
+        .get()
+        .await?
+        .ok_or_else(|| AppError::not_found("Settings", 1))?;
+
+    if let Some(base_currency) = req.base_currency {
+        settings.base_currency = base_currency;
+    }
+    if let Some(method) = req.cost_basis_method {
+        settings.cost_basis_method = method.parse::<CostBasisMethod>()?.to_string();
+    }
+    // Only one benchmark is kept, so setting one replaces the other
+    if let Some(investment_id) = req.benchmark_investment_id {
+        settings.benchmark_investment_id = investment_id;
+        settings.benchmark_ticker = None;
+    }
+    if let Some(ticker) = req.benchmark_ticker {
+        let ticker = ticker.map(|t| t.trim().to_string());
+        if ticker.as_deref() == Some("") {
+            return Err(AppError::InvalidInput(
+                "Benchmark ticker must not be empty".to_string(),
+            ));
+        }
+        settings.benchmark_ticker = ticker;
+        settings.benchmark_investment_id = None;
+    }
+
+    if let Some(urls) = req.webhook_urls {
+        settings.webhook_urls = JsonColumn(urls.iter().map(|url| url.trim().to_string()).collect());
+    }
+    if let Some(secret) = req.webhook_secret {
+        settings.webhook_secret = secret.filter(|secret| !secret.is_empty());
+    }
+
+    if let Some(sources) = req.price_source_priority {
+        settings.price_source_priority = JsonColumn(
+            sources
+                .iter()
+                .map(|source| source.trim().to_lowercase())
+                .collect(),
+        );
+    }
+
+    if let Some(decimals) = req.value_decimals {
+        settings.value_decimals = decimals;
+    }
+    if let Some(decimals) = req.price_decimals {
+        settings.price_decimals = decimals;
+    }
+    if let Some(decimals) = req.quantity_decimals {
+        settings.quantity_decimals = decimals;
+    }
+    if let Some(mode) = req.rounding_mode {
+        settings.rounding_mode = mode.parse::<RoundingMode>()?.to_string();
+    }
+    if let Some(reject) = req.reject_negative_holdings {
+        settings.reject_negative_holdings = reject;
+    }
+
     repo.update(&settings).await?;
-    let updated = repo.get().await?.ok_or(AppError::NotFound)?;
+    let updated = repo
+        .get()
+        .await?
+        .ok_or_else(|| AppError::not_found("Settings", 1))?;
     Ok(Json(updated.into()))
 }
+
+#[derive(Debug, Serialize)]
+pub struct ProviderSettingsResponse {
+    pub id: String,
+    pub name: String,
+    /// Masked key in effect, the key itself is never returned
+    pub api_key: Option<String>,
+    /// `settings` for a key stored through this API, `config` for one of the config file
+    /// or environment
+    pub api_key_source: Option<String>,
+}
+
+/// API keys by provider ID; `null` or an empty string removes the stored key, so that
+/// the configured one applies again
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+pub struct UpdateProviderSettingsRequest {
+    pub api_keys: BTreeMap<String, Option<String>>,
+}
+
+impl Validate for UpdateProviderSettingsRequest {
+    fn check(&self, errors: &mut ValidationErrors) {
+        for provider in self.api_keys.keys() {
+            if !ProviderApiKeys::PROVIDERS.contains(&provider.as_str()) {
+                errors.add(
+                    provider,
+                    format!(
+                        "Provider does not accept an API key, expected one of: {}",
+                        ProviderApiKeys::PROVIDERS.join(", ")
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Last four characters of longer keys, enough to tell keys apart without revealing them
+fn mask_api_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() < 12 {
+        return "****".to_string();
+    }
+    format!(
+        "****{}",
+        chars[chars.len() - 4..].iter().collect::<String>()
+    )
+}
+
+async fn provider_settings(state: &ProviderSettingsState) -> Result<Vec<ProviderSettingsResponse>> {
+    let stored = state.provider_config_repo.find_all().await?;
+    let providers = ProviderApiKeys::PROVIDERS
+        .iter()
+        .map(|&id| {
+            let name = AVAILABLE_PROVIDERS
+                .iter()
+                .find(|(provider, _)| *provider == id)
+                .map_or(id, |(_, name)| name);
+            let (api_key, source) = match stored.iter().find(|config| config.provider == id) {
+                Some(config) => (Some(config.api_key.as_str()), Some("settings")),
+                None => {
+                    let key = state.api_keys.get(id);
+                    (key, key.map(|_| "config"))
+                }
+            };
+            ProviderSettingsResponse {
+                id: id.to_string(),
+                name: name.to_string(),
+                api_key: api_key.map(mask_api_key),
+                api_key_source: source.map(str::to_string),
+            }
+        })
+        .collect();
+    Ok(providers)
+}
+
+/// GET /api/settings/providers - API keys of the providers that accept one, masked
+pub async fn get_provider_settings(
+    State(state): State<ProviderSettingsState>,
+) -> Result<Json<Vec<ProviderSettingsResponse>>> {
+    Ok(Json(provider_settings(&state).await?))
+}
+
+/// PUT /api/settings/providers - Store or remove provider API keys, applied from the next
+/// quote fetch on
+pub async fn update_provider_settings(
+    State(state): State<ProviderSettingsState>,
+    Json(req): Json<UpdateProviderSettingsRequest>,
+) -> Result<Json<Vec<ProviderSettingsResponse>>> {
+    req.validate()?;
+
+    for (provider, api_key) in req.api_keys {
+        match api_key.map(|key| key.trim().to_string()) {
+            Some(api_key) if !api_key.is_empty() => {
+                state
+                    .provider_config_repo
+                    .upsert(&ProviderConfig {
+                        provider,
+                        api_key,
+                        updated_at: chrono::Utc::now(),
+                    })
+                    .await?;
+            }
+            _ => {
+                state.provider_config_repo.delete(&provider).await?;
+            }
+        }
+    }
+
+    Ok(Json(provider_settings(&state).await?))
+}
+
+/// POST /api/settings/recalculate-prices - Re-derive stored prices in the current base currency
+pub async fn recalculate_prices(
+    State(service): State<Arc<PriceRecalculationService>>,
+) -> Result<Json<PriceRecalculationResult>> {
+    let result = service.recalculate().await?;
+    Ok(Json(result))
+}
+
+/// Middleware that serializes the response with the display precision of the settings
+///
+/// Without settings, or if they cannot be read, nothing is rounded.
+pub async fn display_precision(
+    State(repo): State<Arc<dyn SettingsRepository>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let precision = match repo.get().await {
+        Ok(settings) => settings
+            .map(|settings| DisplayPrecision::from_settings(&settings))
+            .unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Failed to read the display precision: {}", e);
+            DisplayPrecision::default()
+        }
+    };
+    precision.scope(next.run(request)).await
+}
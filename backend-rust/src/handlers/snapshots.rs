@@ -0,0 +1,66 @@
+use crate::error::Result;
+use crate::services::snapshots::{Snapshot, SnapshotComparison, SnapshotSummary};
+use crate::services::SnapshotService;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct TakeSnapshotRequest {
+    /// Defaults to today
+    pub date: Option<NaiveDate>,
+    /// Overwrite an existing snapshot of the date
+    #[serde(default)]
+    pub replace: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompareSnapshotsQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// GET /api/snapshots - Dates of the stored snapshots with their total value
+pub async fn list_snapshots(
+    State(service): State<Arc<SnapshotService>>,
+) -> Result<Json<Vec<SnapshotSummary>>> {
+    Ok(Json(service.list().await?))
+}
+
+/// POST /api/snapshots - Freeze the holdings of a date
+pub async fn take_snapshot(
+    State(service): State<Arc<SnapshotService>>,
+    Json(req): Json<TakeSnapshotRequest>,
+) -> Result<Json<Snapshot>> {
+    let date = req.date.unwrap_or_else(|| Utc::now().date_naive());
+    Ok(Json(service.take(date, req.replace).await?))
+}
+
+/// GET /api/snapshots/:date - Holdings of a stored snapshot
+pub async fn get_snapshot(
+    State(service): State<Arc<SnapshotService>>,
+    Path(date): Path<NaiveDate>,
+) -> Result<Json<Snapshot>> {
+    Ok(Json(service.get(date).await?))
+}
+
+/// DELETE /api/snapshots/:date - Delete a snapshot
+pub async fn delete_snapshot(
+    State(service): State<Arc<SnapshotService>>,
+    Path(date): Path<NaiveDate>,
+) -> Result<Json<()>> {
+    service.delete(date).await?;
+    Ok(Json(()))
+}
+
+/// GET /api/snapshots/compare?from=&to= - Change of the holdings between two snapshots
+pub async fn compare_snapshots(
+    State(service): State<Arc<SnapshotService>>,
+    Query(query): Query<CompareSnapshotsQuery>,
+) -> Result<Json<SnapshotComparison>> {
+    Ok(Json(service.compare(query.from, query.to).await?))
+}
@@ -0,0 +1,37 @@
+use crate::error::{AppError, Result};
+use crate::services::quotes::SymbolMatch;
+use crate::services::symbol_search::MAX_SEARCH_LIMIT;
+use crate::services::SymbolSearchService;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct SymbolSearchQuery {
+    /// Name, ticker or ISIN
+    pub q: String,
+    /// Defaults to the maximum of 10
+    pub limit: Option<usize>,
+}
+
+/// GET /api/symbols/search - Instruments matching a name, ticker or ISIN
+///
+/// Returns ticker, name, exchange and currency of every match, best match first.
+pub async fn search_symbols(
+    State(service): State<Arc<SymbolSearchService>>,
+    Query(query): Query<SymbolSearchQuery>,
+) -> Result<Json<Vec<SymbolMatch>>> {
+    let limit = query.limit.unwrap_or(MAX_SEARCH_LIMIT);
+    if !(1..=MAX_SEARCH_LIMIT).contains(&limit) {
+        return Err(AppError::InvalidInput(format!(
+            "limit must be between 1 and {}",
+            MAX_SEARCH_LIMIT
+        )));
+    }
+
+    let matches = service.search(&query.q, limit).await?;
+    Ok(Json(matches))
+}
@@ -0,0 +1,154 @@
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::Tag;
+use crate::repository::traits::TagRepository;
+use crate::routes::TagState;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Deserialize)]
+pub struct TagRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InvestmentTagsRequest {
+    pub tag_ids: Vec<i64>,
+}
+
+impl TagRequest {
+    /// Validate the name and reject names used by another tag
+    async fn into_tag(self, id: i64, repo: &dyn TagRepository) -> Result<Tag> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidInput(
+                "Tag name must not be empty".to_string(),
+            ));
+        }
+        if repo
+            .find_by_name(name)
+            .await?
+            .is_some_and(|existing| existing.id != id)
+        {
+            return Err(AppError::Conflict(format!("Tag '{}' already exists", name)));
+        }
+
+        Ok(Tag {
+            id,
+            name: name.to_string(),
+        })
+    }
+}
+
+/// IDs of the investments with the tag named `tag`, `None` without a tag filter
+pub async fn tagged_investments(
+    repo: &dyn TagRepository,
+    tag: Option<&str>,
+) -> Result<Option<HashSet<i64>>> {
+    let Some(name) = tag else {
+        return Ok(None);
+    };
+    let tag = repo
+        .find_by_name(name.trim())
+        .await?
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown tag '{}'", name)))?;
+    let ids = repo.find_investment_ids(tag.id).await?;
+    Ok(Some(ids.into_iter().collect()))
+}
+
+/// GET /api/tags - List all tags
+pub async fn list_tags(State(state): State<TagState>) -> Result<Json<Vec<Tag>>> {
+    let tags = state.tag_repo.find_all().await?;
+    Ok(Json(tags))
+}
+
+/// GET /api/tags/:id - Get a single tag
+pub async fn get_tag(State(state): State<TagState>, Path(id): Path<i64>) -> Result<Json<Tag>> {
+    let tag = state
+        .tag_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Tag", id))?;
+    Ok(Json(tag))
+}
+
+/// POST /api/tags - Create a tag
+pub async fn create_tag(
+    State(state): State<TagState>,
+    Json(req): Json<TagRequest>,
+) -> Result<Json<Tag>> {
+    let tag = req.into_tag(0, state.tag_repo.as_ref()).await?;
+
+    let id = state.tag_repo.create(&tag).await?;
+    let created = state
+        .tag_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Tag", id))?;
+    Ok(Json(created))
+}
+
+/// PUT /api/tags/:id - Rename a tag
+pub async fn update_tag(
+    State(state): State<TagState>,
+    Path(id): Path<i64>,
+    Json(req): Json<TagRequest>,
+) -> Result<Json<Tag>> {
+    state
+        .tag_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Tag", id))?;
+    let tag = req.into_tag(id, state.tag_repo.as_ref()).await?;
+
+    ensure_found(state.tag_repo.update(id, &tag).await?, "Tag", id)?;
+    Ok(Json(tag))
+}
+
+/// DELETE /api/tags/:id - Delete a tag and remove it from all investments
+pub async fn delete_tag(State(state): State<TagState>, Path(id): Path<i64>) -> Result<Json<()>> {
+    ensure_found(state.tag_repo.delete(id).await?, "Tag", id)?;
+    Ok(Json(()))
+}
+
+/// GET /api/investments/:id/tags - Tags of an investment
+pub async fn get_investment_tags(
+    State(state): State<TagState>,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<Tag>>> {
+    state
+        .investment_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Investment", id))?;
+    let tags = state.tag_repo.find_by_investment(id).await?;
+    Ok(Json(tags))
+}
+
+/// PUT /api/investments/:id/tags - Replace the tags of an investment
+pub async fn set_investment_tags(
+    State(state): State<TagState>,
+    Path(id): Path<i64>,
+    Json(req): Json<InvestmentTagsRequest>,
+) -> Result<Json<Vec<Tag>>> {
+    state
+        .investment_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Investment", id))?;
+    for &tag_id in &req.tag_ids {
+        if state.tag_repo.find_by_id(tag_id).await?.is_none() {
+            return Err(AppError::InvalidInput(format!(
+                "Tag {} does not exist",
+                tag_id
+            )));
+        }
+    }
+
+    state.tag_repo.set_investment_tags(id, &req.tag_ids).await?;
+    let tags = state.tag_repo.find_by_investment(id).await?;
+    Ok(Json(tags))
+}
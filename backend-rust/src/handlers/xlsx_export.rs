@@ -0,0 +1,67 @@
+use crate::error::Result;
+use crate::handlers::DevelopmentQuery;
+use crate::models::MovementListOptions;
+use crate::services::xlsx_export::XLSX_CONTENT_TYPE;
+use crate::services::XlsxExportService;
+use axum::{
+    extract::{Query, State},
+    http::header,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::sync::Arc;
+
+type XlsxResponse = ([(header::HeaderName, &'static str); 2], Vec<u8>);
+
+#[derive(Debug, Deserialize)]
+pub struct MovementExportQuery {
+    pub portfolio_id: Option<i64>,
+    pub investment_id: Option<i64>,
+    pub action_id: Option<i64>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+fn xlsx_response(filename: &'static str, body: Vec<u8>) -> XlsxResponse {
+    (
+        [
+            (header::CONTENT_TYPE, XLSX_CONTENT_TYPE),
+            (header::CONTENT_DISPOSITION, filename),
+        ],
+        body,
+    )
+}
+
+/// GET /api/movements/export.xlsx - Movements as Excel workbook, one sheet per investment
+pub async fn export_movements_xlsx(
+    State(service): State<Arc<XlsxExportService>>,
+    Query(query): Query<MovementExportQuery>,
+) -> Result<XlsxResponse> {
+    let options = MovementListOptions {
+        portfolio_id: query.portfolio_id,
+        investment_id: query.investment_id,
+        action_id: query.action_id,
+        start_date: query.start_date,
+        end_date: query.end_date,
+        ..Default::default()
+    };
+    let body = service.movements(options).await?;
+    Ok(xlsx_response(
+        "attachment; filename=\"movements.xlsx\"",
+        body,
+    ))
+}
+
+/// GET /api/developments/export.xlsx - Developments as Excel workbook, one sheet per investment
+pub async fn export_developments_xlsx(
+    State(service): State<Arc<XlsxExportService>>,
+    Query(params): Query<DevelopmentQuery>,
+) -> Result<XlsxResponse> {
+    let body = service
+        .developments(params.portfolio_id, params.start_date, params.end_date)
+        .await?;
+    Ok(xlsx_response(
+        "attachment; filename=\"developments.xlsx\"",
+        body,
+    ))
+}
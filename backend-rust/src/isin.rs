@@ -0,0 +1,64 @@
+use crate::error::{AppError, Result};
+
+/// Remove all whitespace and upper-case, e.g. `" ie00 b4l5 y983"` becomes `"IE00B4L5Y983"`
+pub fn normalize(isin: &str) -> String {
+    isin.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+/// Two letter country code, nine alphanumeric characters and the Luhn check digit
+/// over the digits, with letters counted as two digits (A = 10 ... Z = 35)
+///
+/// Expects a normalized ISIN, lower case letters are rejected.
+pub fn is_valid(isin: &str) -> bool {
+    let bytes = isin.as_bytes();
+    if bytes.len() != 12
+        || !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..11]
+            .iter()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        || !bytes[11].is_ascii_digit()
+    {
+        return false;
+    }
+
+    let digits: Vec<u32> = bytes
+        .iter()
+        .flat_map(|&b| {
+            let value = (b as char).to_digit(36).unwrap_or(0);
+            if value >= 10 {
+                vec![value / 10, value % 10]
+            } else {
+                vec![value]
+            }
+        })
+        .collect();
+
+    // Every second digit from the right, starting with the one left of the check digit,
+    // is doubled
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                doubled / 10 + doubled % 10
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Normalize an ISIN and check it, rejecting typos before they reach the quote providers
+pub fn parse(isin: &str) -> Result<String> {
+    let normalized = normalize(isin);
+    if !is_valid(&normalized) {
+        return Err(AppError::InvalidInput(format!("Invalid ISIN '{}'", isin)));
+    }
+    Ok(normalized)
+}
@@ -1,9 +1,13 @@
 // Library exports for testing
+pub mod cli;
 pub mod config;
 pub mod db;
 pub mod error;
 pub mod handlers;
+pub mod isin;
 pub mod models;
 pub mod repository;
 pub mod routes;
 pub mod services;
+pub mod telemetry;
+pub mod validation;
@@ -1,4 +1,7 @@
+use crate::error::{AppError, Result};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ActionType {
@@ -6,4 +9,87 @@ pub struct ActionType {
     pub id: i64,
     #[sqlx(rename = "Name")]
     pub name: String,
+    /// How movements of this type are calculated, see [`ActionEffect`]
+    #[sqlx(rename = "Effect")]
+    #[serde(default = "default_effect")]
+    pub effect: String,
+}
+
+/// Exports written before action types had an effect only contain the built-in types
+fn default_effect() -> String {
+    ActionEffect::CashOnly.as_str().to_string()
+}
+
+/// Highest ID of the action types shipped with the application
+pub const MAX_BUILT_IN_ACTION_ID: i64 = 9;
+
+/// What a movement of an action type does to the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionEffect {
+    /// Adds the quantity to the position at the amount as cost, like a buy
+    IncreasesQuantity,
+    /// Removes the quantity from the position for the amount, like a sell
+    DecreasesQuantity,
+    /// Only moves cash: positive amounts are received, negative amounts are paid
+    CashOnly,
+    /// Multiplies the position by the quantity (built-in type only)
+    Split,
+    /// Moves lots to another portfolio (built-in type only)
+    TransferOut,
+    /// Receives lots from another portfolio (built-in type only)
+    TransferIn,
+    /// Starts the position at a date before which no movements were recorded, with the
+    /// amount as cost but without cash flow (built-in type only)
+    Opening,
+}
+
+/// Effects that custom action types can have
+pub const VALID_ACTION_EFFECTS: &[&str] =
+    &["increases_quantity", "decreases_quantity", "cash_only"];
+
+impl ActionEffect {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionEffect::IncreasesQuantity => "increases_quantity",
+            ActionEffect::DecreasesQuantity => "decreases_quantity",
+            ActionEffect::CashOnly => "cash_only",
+            ActionEffect::Split => "split",
+            ActionEffect::TransferOut => "transfer_out",
+            ActionEffect::TransferIn => "transfer_in",
+            ActionEffect::Opening => "opening",
+        }
+    }
+
+    /// Whether custom action types may have this effect
+    pub fn is_custom(&self) -> bool {
+        VALID_ACTION_EFFECTS.contains(&self.as_str())
+    }
+}
+
+impl fmt::Display for ActionEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ActionEffect {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "increases_quantity" => Ok(ActionEffect::IncreasesQuantity),
+            "decreases_quantity" => Ok(ActionEffect::DecreasesQuantity),
+            "cash_only" => Ok(ActionEffect::CashOnly),
+            "split" => Ok(ActionEffect::Split),
+            "transfer_out" => Ok(ActionEffect::TransferOut),
+            "transfer_in" => Ok(ActionEffect::TransferIn),
+            "opening" => Ok(ActionEffect::Opening),
+            _ => Err(AppError::InvalidInput(format!(
+                "Invalid action effect '{}'. Valid effects are: {}",
+                s,
+                VALID_ACTION_EFFECTS.join(", ")
+            ))),
+        }
+    }
 }
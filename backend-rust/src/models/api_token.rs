@@ -0,0 +1,91 @@
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Bearer token for the API, stored as hash of the token
+///
+/// Tokens belong to the installation and are not part of data exports.
+#[derive(Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct ApiToken {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    /// What the token is for, e.g. "Dashboard"
+    #[sqlx(rename = "Name")]
+    pub name: String,
+    /// `read`, `write` or `admin`, see [`TokenScope`]
+    #[sqlx(rename = "Scope")]
+    pub scope: String,
+    /// Hex encoded SHA-256 hash of the token
+    #[sqlx(rename = "TokenHash")]
+    #[serde(skip)]
+    pub token_hash: String,
+    #[sqlx(rename = "CreatedAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl fmt::Debug for ApiToken {
+    // Hashes must not end up in logs
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiToken")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("scope", &self.scope)
+            .field("token_hash", &"***")
+            .field("created_at", &self.created_at)
+            .finish()
+    }
+}
+
+/// What a token may do; each scope includes the ones before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    /// Read requests only
+    Read,
+    /// Reads and changes of the portfolio data
+    Write,
+    /// Everything, including tokens, provider keys, exports, imports and data fixes
+    Admin,
+}
+
+pub const VALID_TOKEN_SCOPES: &[&str] = &["read", "write", "admin"];
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Write => "write",
+            TokenScope::Admin => "admin",
+        }
+    }
+
+    /// Whether a token with this scope may do what `required` allows
+    pub fn allows(&self, required: TokenScope) -> bool {
+        *self >= required
+    }
+}
+
+impl fmt::Display for TokenScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TokenScope {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "read" => Ok(TokenScope::Read),
+            "write" => Ok(TokenScope::Write),
+            "admin" => Ok(TokenScope::Admin),
+            _ => Err(AppError::InvalidInput(format!(
+                "Invalid token scope '{}'. Valid scopes are: {}",
+                s,
+                VALID_TOKEN_SCOPES.join(", ")
+            ))),
+        }
+    }
+}
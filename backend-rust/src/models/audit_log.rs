@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Action of the audit log entry written when an investment is merged into another
+pub const INVESTMENT_MERGED: &str = "investment_merged";
+
+/// A recorded change
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AuditLogEntry {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "CreatedAt")]
+    pub created_at: DateTime<Utc>,
+    /// Kind of change, e.g. `investment_merged`
+    #[sqlx(rename = "Action")]
+    pub action: String,
+    #[sqlx(rename = "Details")]
+    pub details: String,
+}
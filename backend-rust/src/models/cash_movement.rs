@@ -0,0 +1,21 @@
+use crate::models::DecimalColumn;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Deposit or withdrawal of uninvested cash
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CashMovement {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "Date")]
+    pub date: NaiveDate,
+    #[sqlx(rename = "ActionID")]
+    pub action_id: i64,
+    #[sqlx(rename = "Amount", try_from = "DecimalColumn")]
+    pub amount: Decimal,
+    #[sqlx(rename = "PortfolioID")]
+    pub portfolio_id: Option<i64>,
+    #[sqlx(rename = "Description")]
+    pub description: Option<String>,
+}
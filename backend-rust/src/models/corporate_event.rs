@@ -0,0 +1,97 @@
+use crate::error::{AppError, Result};
+use crate::models::DecimalColumn;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Dividend per share reported by a quote provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DividendEvent {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "InvestmentID")]
+    pub investment_id: i64,
+    /// Ex-dividend date
+    #[sqlx(rename = "Date")]
+    pub date: NaiveDate,
+    /// Amount per share in `currency`
+    #[sqlx(rename = "Amount", try_from = "DecimalColumn")]
+    pub amount: Decimal,
+    #[sqlx(rename = "Currency")]
+    pub currency: Option<String>,
+    #[sqlx(rename = "Source")]
+    pub source: String,
+    /// `pending`, `confirmed` or `dismissed`, see [`DividendStatus`]
+    #[sqlx(rename = "Status")]
+    pub status: String,
+    /// Payout recorded when the dividend was confirmed
+    #[sqlx(rename = "MovementID")]
+    pub movement_id: Option<i64>,
+}
+
+/// Stock split reported by a quote provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SplitEvent {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "InvestmentID")]
+    pub investment_id: i64,
+    #[sqlx(rename = "Date")]
+    pub date: NaiveDate,
+    /// New shares per `denominator` old shares
+    #[sqlx(rename = "Numerator", try_from = "DecimalColumn")]
+    pub numerator: Decimal,
+    #[sqlx(rename = "Denominator", try_from = "DecimalColumn")]
+    pub denominator: Decimal,
+    #[sqlx(rename = "Source")]
+    pub source: String,
+}
+
+/// Whether a payout has been recorded for a detected dividend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DividendStatus {
+    /// Detected, waiting for the payout to be confirmed or dismissed
+    Pending,
+    /// Recorded as payout movement
+    Confirmed,
+    /// Not received, e.g. because the investment was not held
+    Dismissed,
+}
+
+pub const VALID_DIVIDEND_STATUSES: &[&str] = &["pending", "confirmed", "dismissed"];
+
+impl DividendStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DividendStatus::Pending => "pending",
+            DividendStatus::Confirmed => "confirmed",
+            DividendStatus::Dismissed => "dismissed",
+        }
+    }
+}
+
+impl fmt::Display for DividendStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DividendStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(DividendStatus::Pending),
+            "confirmed" => Ok(DividendStatus::Confirmed),
+            "dismissed" => Ok(DividendStatus::Dismissed),
+            _ => Err(AppError::InvalidInput(format!(
+                "Invalid dividend status '{}'. Valid statuses are: {}",
+                s,
+                VALID_DIVIDEND_STATUSES.join(", ")
+            ))),
+        }
+    }
+}
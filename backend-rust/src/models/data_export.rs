@@ -0,0 +1,44 @@
+use super::{ActionType, CashMovement, Investment, InvestmentPrice, Movement, Portfolio, Settings};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Version of the export format, increased on incompatible changes
+pub const DATA_EXPORT_VERSION: u32 = 1;
+
+/// Complete content of a database, independent of the storage backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataExport {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub settings: Option<Settings>,
+    pub action_types: Vec<ActionType>,
+    pub portfolios: Vec<Portfolio>,
+    pub investments: Vec<Investment>,
+    pub movements: Vec<Movement>,
+    #[serde(default)]
+    pub cash_movements: Vec<CashMovement>,
+    pub prices: Vec<InvestmentPrice>,
+}
+
+/// How imported data is combined with existing data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Add to existing data. Investments are matched by ISIN and portfolios by
+    /// name, everything else is inserted with new IDs.
+    #[default]
+    Merge,
+    /// Delete existing portfolios, investments, movements and prices first and
+    /// keep the IDs and settings of the export.
+    Replace,
+}
+
+/// Number of rows inserted by an import
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub portfolios: usize,
+    pub investments: usize,
+    pub movements: usize,
+    pub cash_movements: usize,
+    pub prices: usize,
+}
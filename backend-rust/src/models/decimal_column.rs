@@ -0,0 +1,81 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use sqlx::encode::IsNull;
+use sqlx::error::{BoxDynError, UnexpectedNullError};
+use sqlx::postgres::{PgTypeInfo, PgValueRef};
+use sqlx::sqlite::{SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use sqlx::{Decode, Encode, Postgres, Sqlite, Type, ValueRef};
+
+/// Decimal column of the models, read as `#[sqlx(try_from = "DecimalColumn")]`
+///
+/// PostgreSQL stores NUMERIC and reads it exactly. SQLite has no decimal type and keeps
+/// DECIMAL columns as REAL or INTEGER, which holds 15 significant digits, so a value
+/// read back equals the decimal that was written. Sums are only formed as `Decimal`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecimalColumn(pub Option<Decimal>);
+
+impl From<DecimalColumn> for Option<Decimal> {
+    fn from(column: DecimalColumn) -> Self {
+        column.0
+    }
+}
+
+impl TryFrom<DecimalColumn> for Decimal {
+    type Error = UnexpectedNullError;
+
+    fn try_from(column: DecimalColumn) -> Result<Self, Self::Error> {
+        column.0.ok_or(UnexpectedNullError)
+    }
+}
+
+impl Type<Sqlite> for DecimalColumn {
+    fn type_info() -> SqliteTypeInfo {
+        <f64 as Type<Sqlite>>::type_info()
+    }
+
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <f64 as Type<Sqlite>>::compatible(ty) || <i64 as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for DecimalColumn {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_null() {
+            return Ok(Self(None));
+        }
+        let real = <f64 as Decode<Sqlite>>::decode(value)?;
+        // Drops the binary noise beyond the digits a REAL keeps, so 0.1 reads as 0.1
+        Ok(Self(Some(Decimal::try_from(real)?)))
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for DecimalColumn {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<SqliteArgumentValue<'q>>,
+    ) -> Result<IsNull, BoxDynError> {
+        match self.0.and_then(|value| value.to_f64()) {
+            Some(real) => <f64 as Encode<Sqlite>>::encode_by_ref(&real, args),
+            None => Ok(IsNull::Yes),
+        }
+    }
+}
+
+impl Type<Postgres> for DecimalColumn {
+    fn type_info() -> PgTypeInfo {
+        <Decimal as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <Decimal as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for DecimalColumn {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_null() {
+            return Ok(Self(None));
+        }
+        Ok(Self(Some(<Decimal as Decode<Postgres>>::decode(value)?)))
+    }
+}
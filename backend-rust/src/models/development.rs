@@ -0,0 +1,22 @@
+use crate::models::DecimalColumn;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+/// Quantity held and value of an investment on one date
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct Development {
+    #[sqlx(rename = "InvestmentID")]
+    pub investment: i64,
+    #[sqlx(rename = "Date")]
+    pub date: NaiveDate,
+    #[sqlx(rename = "Price", try_from = "DecimalColumn")]
+    pub price: Decimal,
+    #[sqlx(rename = "Quantity", try_from = "DecimalColumn")]
+    pub quantity: Decimal,
+    #[sqlx(rename = "Value", try_from = "DecimalColumn")]
+    pub value: Decimal,
+    /// Source of the quote the price comes from, `None` for transaction prices
+    #[sqlx(rename = "Source")]
+    pub source: Option<String>,
+}
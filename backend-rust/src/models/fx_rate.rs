@@ -0,0 +1,15 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Cached exchange rate: 1 unit of `from_currency` equals `rate` units of `to_currency`
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FxRate {
+    #[sqlx(rename = "Date")]
+    pub date: NaiveDate,
+    #[sqlx(rename = "FromCurrency")]
+    pub from_currency: String,
+    #[sqlx(rename = "ToCurrency")]
+    pub to_currency: String,
+    #[sqlx(rename = "Rate")]
+    pub rate: f64,
+}
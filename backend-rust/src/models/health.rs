@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+/// Database migrations applied compared to the ones shipped with this build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationStatus {
+    /// Latest successfully applied migration, none if no migration ran
+    pub applied_version: Option<i64>,
+    pub latest_version: i64,
+}
+
+impl MigrationStatus {
+    pub fn is_current(&self) -> bool {
+        self.applied_version == Some(self.latest_version)
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use std::collections::BTreeMap;
+
+/// Layout of a broker CSV export, applied by the generic CSV importer
+///
+/// Columns are referenced by their header names.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ImportProfile {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "Name")]
+    pub name: String,
+    #[sqlx(rename = "Delimiter")]
+    pub delimiter: String,
+    /// chrono format string, e.g. `%d.%m.%Y`
+    #[sqlx(rename = "DateFormat")]
+    pub date_format: String,
+    /// `.` or `,`
+    #[sqlx(rename = "DecimalSeparator")]
+    pub decimal_separator: String,
+    /// Currency of amounts and fees
+    #[sqlx(rename = "Currency")]
+    pub currency: String,
+    #[sqlx(rename = "DateColumn")]
+    pub date_column: String,
+    #[sqlx(rename = "ActionColumn")]
+    pub action_column: String,
+    #[sqlx(rename = "IsinColumn")]
+    pub isin_column: String,
+    #[sqlx(rename = "ProductColumn")]
+    pub product_column: Option<String>,
+    /// Required for buys and sells
+    #[sqlx(rename = "QuantityColumn")]
+    pub quantity_column: Option<String>,
+    #[sqlx(rename = "AmountColumn")]
+    pub amount_column: String,
+    #[sqlx(rename = "FeeColumn")]
+    pub fee_column: Option<String>,
+    /// Lowercase keywords of the action column and their action type; rows with
+    /// other keywords are skipped
+    #[sqlx(rename = "ActionKeywords")]
+    pub action_keywords: Json<BTreeMap<String, i64>>,
+}
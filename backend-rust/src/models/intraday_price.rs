@@ -0,0 +1,27 @@
+use crate::models::DecimalColumn;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Latest live quote of an investment during the trading day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct IntradayPrice {
+    #[sqlx(rename = "InvestmentID")]
+    pub investment_id: i64,
+    /// Price in the base currency
+    #[sqlx(rename = "Price", try_from = "DecimalColumn")]
+    pub price: Decimal,
+    /// Currency the quote was delivered in
+    #[sqlx(rename = "Currency")]
+    pub currency: String,
+    /// Price as delivered, before conversion to the base currency
+    #[sqlx(rename = "OriginalPrice", try_from = "DecimalColumn")]
+    pub original_price: Decimal,
+    /// Time of the last trade reported by the provider
+    #[sqlx(rename = "QuotedAt")]
+    pub quoted_at: DateTime<Utc>,
+    #[sqlx(rename = "FetchedAt")]
+    pub fetched_at: DateTime<Utc>,
+    #[sqlx(rename = "Source")]
+    pub source: String,
+}
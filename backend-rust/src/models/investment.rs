@@ -1,3 +1,6 @@
+use crate::models::DecimalColumn;
+use async_graphql::SimpleObject;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -14,4 +17,80 @@ pub struct Investment {
     pub ticker_symbol: Option<String>,
     #[sqlx(rename = "QuoteProvider")]
     pub quote_provider: Option<String>,
+    /// Trading currency, e.g. `EUR`
+    #[sqlx(rename = "Currency")]
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Kind of instrument: `stock`, `etf`, `fund` or `crypto`
+    #[sqlx(rename = "AssetClass")]
+    #[serde(default)]
+    pub asset_class: Option<String>,
+    /// Tracked for its quotes only; ignored by the portfolio calculations while it has no
+    /// movements
+    #[sqlx(rename = "Watchlist")]
+    #[serde(default)]
+    pub watchlist: bool,
+    /// Share of the income exempt from German tax (Teilfreistellung) in percent, e.g. `30`
+    /// for an equity fund
+    #[sqlx(rename = "PartialExemption", try_from = "DecimalColumn")]
+    #[serde(default)]
+    pub partial_exemption: Option<Decimal>,
+    /// Included in the scheduled quote fetch; disabled e.g. for delisted instruments
+    #[sqlx(rename = "QuoteFetchEnabled")]
+    #[serde(default = "default_quote_fetch_enabled")]
+    pub quote_fetch_enabled: bool,
+    /// Days between scheduled quote fetches, every run if none
+    #[sqlx(rename = "QuoteFetchIntervalDays")]
+    #[serde(default)]
+    pub quote_fetch_interval_days: Option<i64>,
+    /// No longer in use; hidden from lists and the scheduled quote fetch, but still part
+    /// of the developments
+    #[sqlx(rename = "Archived")]
+    #[serde(default)]
+    pub archived: bool,
+}
+
+fn default_quote_fetch_enabled() -> bool {
+    true
+}
+
+/// Which investments a list or search returns
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InvestmentFilter {
+    /// Only investments on (`true`) or off (`false`) the watchlist
+    pub watchlist: Option<bool>,
+    /// Include archived investments, which are left out by default
+    pub include_archived: bool,
+}
+
+impl InvestmentFilter {
+    pub fn matches(&self, investment: &Investment) -> bool {
+        self.watchlist
+            .is_none_or(|watchlist| investment.watchlist == watchlist)
+            && (self.include_archived || !investment.archived)
+    }
+}
+
+/// Rows that reference an investment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, SimpleObject)]
+pub struct InvestmentDependents {
+    pub movements: i64,
+    pub prices: i64,
+}
+
+/// Rows moved to the target when merging an investment into another
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct InvestmentMerge {
+    pub source_id: i64,
+    pub target_id: i64,
+    pub movements: i64,
+    pub prices: i64,
+    /// Prices of the source dropped since the target has one of the same day and source
+    pub duplicate_prices: i64,
+}
+
+impl InvestmentDependents {
+    pub fn is_empty(&self) -> bool {
+        self.movements == 0 && self.prices == 0
+    }
 }
@@ -1,14 +1,34 @@
+use crate::models::DecimalColumn;
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+/// Source of prices entered by hand
+pub const MANUAL_PRICE_SOURCE: &str = "manual";
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct InvestmentPrice {
     #[sqlx(rename = "Date")]
     pub date: Option<NaiveDate>,
     #[sqlx(rename = "InvestmentID")]
     pub investment_id: Option<i64>,
-    #[sqlx(rename = "Price")]
-    pub price: Option<f64>,
+    /// Price in the base currency
+    #[sqlx(rename = "Price", try_from = "DecimalColumn")]
+    pub price: Option<Decimal>,
     #[sqlx(rename = "Source")]
     pub source: Option<String>,
+    /// Currency the quote was delivered in
+    #[sqlx(rename = "Currency")]
+    pub currency: Option<String>,
+    /// Price as delivered, before conversion to the base currency
+    #[sqlx(rename = "OriginalPrice", try_from = "DecimalColumn")]
+    pub original_price: Option<Decimal>,
+}
+
+impl InvestmentPrice {
+    /// Source as stored; prices without source are stored as manual, since the unique
+    /// (Date, InvestmentID, Source) key would not match a missing source on upsert
+    pub fn stored_source(&self) -> &str {
+        self.source.as_deref().unwrap_or(MANUAL_PRICE_SOURCE)
+    }
 }
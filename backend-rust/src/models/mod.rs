@@ -1,11 +1,47 @@
 pub mod action_type;
+pub mod api_token;
+pub mod audit_log;
+pub mod cash_movement;
+pub mod corporate_event;
+pub mod data_export;
+pub mod decimal_column;
+pub mod development;
+pub mod fx_rate;
+pub mod health;
+pub mod import_profile;
+pub mod intraday_price;
 pub mod investment;
 pub mod investment_price;
 pub mod movement;
+pub mod portfolio;
+pub mod price_alert;
+pub mod provider_config;
+pub mod quote_fetch_log;
+pub mod savings_plan;
 pub mod settings;
+pub mod snapshot;
+pub mod tag;
 
-pub use action_type::ActionType;
-pub use investment::Investment;
-pub use investment_price::InvestmentPrice;
-pub use movement::Movement;
+pub use action_type::{ActionEffect, ActionType, MAX_BUILT_IN_ACTION_ID, VALID_ACTION_EFFECTS};
+pub use api_token::{ApiToken, TokenScope, VALID_TOKEN_SCOPES};
+pub use audit_log::{AuditLogEntry, INVESTMENT_MERGED};
+pub use cash_movement::CashMovement;
+pub use corporate_event::{DividendEvent, DividendStatus, SplitEvent, VALID_DIVIDEND_STATUSES};
+pub use data_export::{DataExport, ImportMode, ImportSummary, DATA_EXPORT_VERSION};
+pub use decimal_column::DecimalColumn;
+pub use development::Development;
+pub use fx_rate::FxRate;
+pub use health::MigrationStatus;
+pub use import_profile::ImportProfile;
+pub use intraday_price::IntradayPrice;
+pub use investment::{Investment, InvestmentDependents, InvestmentFilter, InvestmentMerge};
+pub use investment_price::{InvestmentPrice, MANUAL_PRICE_SOURCE};
+pub use movement::{Movement, MovementListOptions, MovementSortField, SortOrder};
+pub use portfolio::Portfolio;
+pub use price_alert::{AlertDirection, PriceAlert, TriggeredAlert, VALID_ALERT_DIRECTIONS};
+pub use provider_config::ProviderConfig;
+pub use quote_fetch_log::QuoteFetchLog;
+pub use savings_plan::{SavingsInterval, SavingsPlan, VALID_SAVINGS_INTERVALS};
 pub use settings::Settings;
+pub use snapshot::SnapshotHolding;
+pub use tag::Tag;
@@ -1,4 +1,6 @@
+use crate::models::DecimalColumn;
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -11,10 +13,81 @@ pub struct Movement {
     pub action_id: Option<i64>,
     #[sqlx(rename = "InvestmentID")]
     pub investment_id: Option<i64>,
-    #[sqlx(rename = "Quantity")]
-    pub quantity: Option<f64>,
-    #[sqlx(rename = "Amount")]
-    pub amount: Option<f64>,
-    #[sqlx(rename = "Fee")]
-    pub fee: Option<f64>,
+    #[sqlx(rename = "Quantity", try_from = "DecimalColumn")]
+    pub quantity: Option<Decimal>,
+    #[sqlx(rename = "Amount", try_from = "DecimalColumn")]
+    pub amount: Option<Decimal>,
+    #[sqlx(rename = "Fee", try_from = "DecimalColumn")]
+    pub fee: Option<Decimal>,
+    /// Taxes paid or withheld, e.g. capital gains tax on a sell
+    #[sqlx(rename = "Tax", try_from = "DecimalColumn")]
+    #[serde(default)]
+    pub tax: Option<Decimal>,
+    #[sqlx(rename = "PortfolioID")]
+    pub portfolio_id: Option<i64>,
+    /// Reference in an external system, unique per portfolio
+    #[sqlx(rename = "ExternalID")]
+    pub external_id: Option<String>,
+}
+
+impl Movement {
+    /// Fee and taxes of the movement, always positive
+    pub fn costs(&self) -> Decimal {
+        self.fee.unwrap_or_default().abs() + self.tax.unwrap_or_default().abs()
+    }
+}
+
+/// Column to sort a movement list by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementSortField {
+    #[default]
+    Id,
+    Date,
+    Amount,
+    Quantity,
+}
+
+impl MovementSortField {
+    /// Column name in the Movement table
+    pub fn column(&self) -> &'static str {
+        match self {
+            MovementSortField::Id => "ID",
+            MovementSortField::Date => "Date",
+            MovementSortField::Amount => "Amount",
+            MovementSortField::Quantity => "Quantity",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
+
+/// Filter, sort order and page of a movement list
+#[derive(Debug, Clone, Default)]
+pub struct MovementListOptions {
+    pub portfolio_id: Option<i64>,
+    pub investment_id: Option<i64>,
+    pub action_id: Option<i64>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub sort_by: MovementSortField,
+    pub order: SortOrder,
+    /// Maximum number of movements, all when `None`
+    pub limit: Option<i64>,
+    pub offset: i64,
 }
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Portfolio {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "Name")]
+    pub name: String,
+    #[sqlx(rename = "Description")]
+    pub description: Option<String>,
+}
@@ -0,0 +1,96 @@
+use crate::error::{AppError, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Alert when the price of an investment crosses a threshold
+///
+/// The threshold is compared with the stored price in the base currency.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PriceAlert {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "InvestmentID")]
+    pub investment_id: i64,
+    #[sqlx(rename = "Threshold")]
+    pub threshold: f64,
+    /// `above` or `below`, see [`AlertDirection`]
+    #[sqlx(rename = "Direction")]
+    pub direction: String,
+    /// Cleared when the alert triggers, so it fires only once until reactivated
+    #[sqlx(rename = "Active")]
+    pub active: bool,
+}
+
+/// A price that crossed the threshold of an alert
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TriggeredAlert {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "AlertID")]
+    pub alert_id: i64,
+    #[sqlx(rename = "InvestmentID")]
+    pub investment_id: i64,
+    #[sqlx(rename = "TriggeredAt")]
+    pub triggered_at: DateTime<Utc>,
+    #[sqlx(rename = "PriceDate")]
+    pub price_date: NaiveDate,
+    #[sqlx(rename = "Price")]
+    pub price: f64,
+    /// Threshold and direction of the alert when it triggered
+    #[sqlx(rename = "Threshold")]
+    pub threshold: f64,
+    #[sqlx(rename = "Direction")]
+    pub direction: String,
+}
+
+/// Which side of the threshold triggers an alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertDirection {
+    /// Prices at or above the threshold
+    Above,
+    /// Prices at or below the threshold
+    Below,
+}
+
+pub const VALID_ALERT_DIRECTIONS: &[&str] = &["above", "below"];
+
+impl AlertDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertDirection::Above => "above",
+            AlertDirection::Below => "below",
+        }
+    }
+
+    pub fn is_crossed(&self, price: f64, threshold: f64) -> bool {
+        match self {
+            AlertDirection::Above => price >= threshold,
+            AlertDirection::Below => price <= threshold,
+        }
+    }
+}
+
+impl fmt::Display for AlertDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AlertDirection {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "above" => Ok(AlertDirection::Above),
+            "below" => Ok(AlertDirection::Below),
+            _ => Err(AppError::InvalidInput(format!(
+                "Invalid alert direction '{}'. Valid directions are: {}",
+                s,
+                VALID_ALERT_DIRECTIONS.join(", ")
+            ))),
+        }
+    }
+}
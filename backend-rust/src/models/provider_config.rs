@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+
+/// API key of a quote provider stored through the settings API
+///
+/// Keys belong to the installation and are not part of data exports.
+#[derive(Clone, PartialEq, sqlx::FromRow)]
+pub struct ProviderConfig {
+    /// Provider ID, e.g. `coingecko`
+    #[sqlx(rename = "Provider")]
+    pub provider: String,
+    #[sqlx(rename = "ApiKey")]
+    pub api_key: String,
+    #[sqlx(rename = "UpdatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl std::fmt::Debug for ProviderConfig {
+    // Keys must not end up in logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderConfig")
+            .field("provider", &self.provider)
+            .field("api_key", &"***")
+            .field("updated_at", &self.updated_at)
+            .finish()
+    }
+}
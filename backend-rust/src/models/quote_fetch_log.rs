@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of one quote fetch for an investment
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QuoteFetchLog {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "FetchedAt")]
+    pub fetched_at: DateTime<Utc>,
+    #[sqlx(rename = "InvestmentID")]
+    pub investment_id: i64,
+    #[sqlx(rename = "Provider")]
+    pub provider: Option<String>,
+    #[sqlx(rename = "Success")]
+    pub success: bool,
+    #[sqlx(rename = "Error")]
+    pub error: Option<String>,
+    #[sqlx(rename = "QuotesStored")]
+    pub quotes_stored: i64,
+}
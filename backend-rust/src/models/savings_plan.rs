@@ -0,0 +1,102 @@
+use crate::error::{AppError, Result};
+use crate::models::DecimalColumn;
+use chrono::{Days, Months, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Recurring purchase of an investment for a fixed amount
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SavingsPlan {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "InvestmentID")]
+    pub investment_id: i64,
+    /// Amount invested per execution in the base currency
+    #[sqlx(rename = "Amount", try_from = "DecimalColumn")]
+    pub amount: Decimal,
+    /// `weekly`, `monthly`, `quarterly` or `yearly`, see [`SavingsInterval`]
+    #[sqlx(rename = "Interval")]
+    pub interval: String,
+    /// Date of the first execution; later executions follow on the same day of the period
+    #[sqlx(rename = "StartDate")]
+    pub start_date: NaiveDate,
+    /// Last day an execution may fall on, open-ended if unset
+    #[sqlx(rename = "EndDate")]
+    pub end_date: Option<NaiveDate>,
+}
+
+impl SavingsPlan {
+    /// Execution dates from the start up to and including `until`
+    ///
+    /// Dates are counted from the start date, so a plan starting on the 31st executes on
+    /// the last day of shorter months.
+    pub fn executions(&self, until: NaiveDate) -> Vec<NaiveDate> {
+        let Ok(interval) = self.interval.parse::<SavingsInterval>() else {
+            return Vec::new();
+        };
+        let last = self.end_date.map_or(until, |end| end.min(until));
+        (0..)
+            .map_while(|n| interval.nth_execution(self.start_date, n))
+            .take_while(|date| *date <= last)
+            .collect()
+    }
+}
+
+/// How often a savings plan executes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SavingsInterval {
+    Weekly,
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+pub const VALID_SAVINGS_INTERVALS: &[&str] = &["weekly", "monthly", "quarterly", "yearly"];
+
+impl SavingsInterval {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SavingsInterval::Weekly => "weekly",
+            SavingsInterval::Monthly => "monthly",
+            SavingsInterval::Quarterly => "quarterly",
+            SavingsInterval::Yearly => "yearly",
+        }
+    }
+
+    /// Date of the `n`th execution after `start`, `None` beyond the supported dates
+    pub fn nth_execution(&self, start: NaiveDate, n: u32) -> Option<NaiveDate> {
+        match self {
+            SavingsInterval::Weekly => start.checked_add_days(Days::new(7 * n as u64)),
+            SavingsInterval::Monthly => start.checked_add_months(Months::new(n)),
+            SavingsInterval::Quarterly => start.checked_add_months(Months::new(3 * n)),
+            SavingsInterval::Yearly => start.checked_add_months(Months::new(12 * n)),
+        }
+    }
+}
+
+impl fmt::Display for SavingsInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SavingsInterval {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "weekly" => Ok(SavingsInterval::Weekly),
+            "monthly" => Ok(SavingsInterval::Monthly),
+            "quarterly" => Ok(SavingsInterval::Quarterly),
+            "yearly" => Ok(SavingsInterval::Yearly),
+            _ => Err(AppError::InvalidInput(format!(
+                "Invalid savings plan interval '{}'. Valid intervals are: {}",
+                s,
+                VALID_SAVINGS_INTERVALS.join(", ")
+            ))),
+        }
+    }
+}
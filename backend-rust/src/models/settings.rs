@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Settings {
@@ -6,4 +7,52 @@ pub struct Settings {
     pub id: i64,
     #[sqlx(rename = "BaseCurrency")]
     pub base_currency: String,
+    #[sqlx(rename = "CostBasisMethod")]
+    pub cost_basis_method: String,
+    /// Investment whose prices the portfolio performance is compared with
+    #[sqlx(rename = "BenchmarkInvestmentID")]
+    #[serde(default)]
+    pub benchmark_investment_id: Option<i64>,
+    /// Ticker symbol of the benchmark, resolved to the investment with this ticker
+    #[sqlx(rename = "BenchmarkTicker")]
+    #[serde(default)]
+    pub benchmark_ticker: Option<String>,
+    /// URLs that receive quote fetch and price alert events
+    ///
+    /// Webhooks belong to the installation and are not part of data exports.
+    #[sqlx(rename = "WebhookUrls")]
+    #[serde(skip)]
+    pub webhook_urls: Json<Vec<String>>,
+    /// Key of the HMAC-SHA256 signature of webhook requests
+    #[sqlx(rename = "WebhookSecret")]
+    #[serde(skip)]
+    pub webhook_secret: Option<String>,
+    /// Quote sources in order of preference, e.g. `["justetf", "yahoo"]`
+    #[sqlx(rename = "PriceSourcePriority")]
+    #[serde(default)]
+    pub price_source_priority: Json<Vec<String>>,
+    /// Decimal places of amounts, fees and market values in responses, all if none
+    #[sqlx(rename = "ValueDecimals")]
+    #[serde(default)]
+    pub value_decimals: Option<i32>,
+    /// Decimal places of prices per unit in responses, all if none
+    #[sqlx(rename = "PriceDecimals")]
+    #[serde(default)]
+    pub price_decimals: Option<i32>,
+    /// Decimal places of quantities in responses, all if none
+    #[sqlx(rename = "QuantityDecimals")]
+    #[serde(default)]
+    pub quantity_decimals: Option<i32>,
+    /// Rounding to the display precision, e.g. `half_even`
+    #[sqlx(rename = "RoundingMode")]
+    #[serde(default = "default_rounding_mode")]
+    pub rounding_mode: String,
+    /// Reject sells of more than the holding on their date
+    #[sqlx(rename = "RejectNegativeHoldings")]
+    #[serde(default)]
+    pub reject_negative_holdings: bool,
+}
+
+fn default_rounding_mode() -> String {
+    "half_even".to_string()
 }
@@ -0,0 +1,20 @@
+use crate::models::DecimalColumn;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Value of one holding frozen in the snapshot of a date
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SnapshotHolding {
+    #[sqlx(rename = "Date")]
+    pub date: NaiveDate,
+    #[sqlx(rename = "InvestmentID")]
+    pub investment: i64,
+    /// Price in the base currency at the time the snapshot was taken
+    #[sqlx(rename = "Price", try_from = "DecimalColumn")]
+    pub price: Decimal,
+    #[sqlx(rename = "Quantity", try_from = "DecimalColumn")]
+    pub quantity: Decimal,
+    #[sqlx(rename = "Value", try_from = "DecimalColumn")]
+    pub value: Decimal,
+}
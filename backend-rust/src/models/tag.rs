@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Label to group investments, e.g. "retirement"; names are unique ignoring case
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Tag {
+    #[sqlx(rename = "ID")]
+    pub id: i64,
+    #[sqlx(rename = "Name")]
+    pub name: String,
+}
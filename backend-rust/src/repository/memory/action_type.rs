@@ -0,0 +1,64 @@
+use crate::error::{AppError, Result};
+use crate::models::ActionType;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryActionTypeRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryActionTypeRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::ActionTypeRepository for InMemoryActionTypeRepository {
+    async fn find_all(&self) -> Result<Vec<ActionType>> {
+        Ok(self.store.lock().action_types.values().cloned().collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<ActionType>> {
+        Ok(self.store.lock().action_types.get(id).cloned())
+    }
+
+    async fn create(&self, action_type: &ActionType) -> Result<i64> {
+        let id = self.store.lock().action_types.insert(|id| ActionType {
+            id,
+            ..action_type.clone()
+        });
+        Ok(id)
+    }
+
+    async fn update(&self, id: i64, action_type: &ActionType) -> Result<u64> {
+        let updated = self.store.lock().action_types.update(
+            id,
+            ActionType {
+                id,
+                ..action_type.clone()
+            },
+        );
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let mut tables = self.store.lock();
+        let movements = tables
+            .movements
+            .values()
+            .filter(|m| m.action_id == Some(id))
+            .count();
+        if movements > 0 {
+            return Err(AppError::Conflict(format!(
+                "Action type {} is used by {} movement(s)",
+                id, movements
+            )));
+        }
+
+        let deleted = tables.action_types.remove(id);
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,57 @@
+use crate::error::Result;
+use crate::models::ApiToken;
+use crate::repository::memory::{unique_violation, MemoryStore};
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryApiTokenRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryApiTokenRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::ApiTokenRepository for InMemoryApiTokenRepository {
+    async fn find_all(&self) -> Result<Vec<ApiToken>> {
+        Ok(self.store.lock().api_tokens.values().cloned().collect())
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>> {
+        Ok(self
+            .store
+            .lock()
+            .api_tokens
+            .values()
+            .find(|token| token.token_hash == token_hash)
+            .cloned())
+    }
+
+    async fn count(&self) -> Result<i64> {
+        Ok(self.store.lock().api_tokens.values().count() as i64)
+    }
+
+    async fn create(&self, token: &ApiToken) -> Result<i64> {
+        let mut tables = self.store.lock();
+        if tables
+            .api_tokens
+            .values()
+            .any(|existing| existing.token_hash == token.token_hash)
+        {
+            return Err(unique_violation("API token"));
+        }
+        let id = tables.api_tokens.insert(|id| ApiToken {
+            id,
+            ..token.clone()
+        });
+        Ok(id)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        Ok(self.store.lock().api_tokens.remove(id))
+    }
+}
@@ -0,0 +1,27 @@
+use crate::error::Result;
+use crate::models::AuditLogEntry;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryAuditLogRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryAuditLogRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::AuditLogRepository for InMemoryAuditLogRepository {
+    async fn find_recent(&self, limit: i64) -> Result<Vec<AuditLogEntry>> {
+        let mut entries: Vec<AuditLogEntry> =
+            self.store.lock().audit_log.values().cloned().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse((e.created_at, e.id)));
+        entries.truncate(limit.max(0) as usize);
+        Ok(entries)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::error::Result;
+use crate::models::CashMovement;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryCashMovementRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryCashMovementRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::CashMovementRepository for InMemoryCashMovementRepository {
+    async fn find_all(&self, portfolio_id: Option<i64>) -> Result<Vec<CashMovement>> {
+        let mut movements: Vec<CashMovement> = self
+            .store
+            .lock()
+            .cash_movements
+            .values()
+            .filter(|m| portfolio_id.is_none() || m.portfolio_id == portfolio_id)
+            .cloned()
+            .collect();
+        movements.sort_by_key(|m| (m.date, m.id));
+        Ok(movements)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<CashMovement>> {
+        Ok(self.store.lock().cash_movements.get(id).cloned())
+    }
+
+    async fn create(&self, movement: &CashMovement) -> Result<i64> {
+        let id = self.store.lock().cash_movements.insert(|id| CashMovement {
+            id,
+            ..movement.clone()
+        });
+        Ok(id)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let deleted = self.store.lock().cash_movements.remove(id);
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,108 @@
+use crate::error::Result;
+use crate::models::{DividendEvent, SplitEvent};
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryCorporateEventRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryCorporateEventRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::CorporateEventRepository for InMemoryCorporateEventRepository {
+    async fn find_dividends(
+        &self,
+        investment_id: Option<i64>,
+        status: Option<&str>,
+    ) -> Result<Vec<DividendEvent>> {
+        let mut dividends: Vec<DividendEvent> = self
+            .store
+            .lock()
+            .dividend_events
+            .values()
+            .filter(|d| investment_id.is_none_or(|id| d.investment_id == id))
+            .filter(|d| status.is_none_or(|status| d.status == status))
+            .cloned()
+            .collect();
+        dividends.sort_by_key(|d| (d.date, d.id));
+        Ok(dividends)
+    }
+
+    async fn find_dividend(&self, id: i64) -> Result<Option<DividendEvent>> {
+        Ok(self.store.lock().dividend_events.get(id).cloned())
+    }
+
+    async fn add_dividends(&self, dividends: &[DividendEvent]) -> Result<usize> {
+        let mut tables = self.store.lock();
+        let mut inserted = 0;
+        for dividend in dividends {
+            let known = tables.dividend_events.values().any(|d| {
+                d.investment_id == dividend.investment_id
+                    && d.date == dividend.date
+                    && d.source == dividend.source
+            });
+            if !known {
+                tables.dividend_events.insert(|id| DividendEvent {
+                    id,
+                    movement_id: None,
+                    ..dividend.clone()
+                });
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+
+    async fn set_dividend_status(
+        &self,
+        id: i64,
+        status: &str,
+        movement_id: Option<i64>,
+    ) -> Result<()> {
+        if let Some(dividend) = self.store.lock().dividend_events.rows.get_mut(&id) {
+            dividend.status = status.to_string();
+            dividend.movement_id = movement_id;
+        }
+        Ok(())
+    }
+
+    async fn find_splits(&self, investment_id: Option<i64>) -> Result<Vec<SplitEvent>> {
+        let mut splits: Vec<SplitEvent> = self
+            .store
+            .lock()
+            .split_events
+            .values()
+            .filter(|s| investment_id.is_none_or(|id| s.investment_id == id))
+            .cloned()
+            .collect();
+        splits.sort_by_key(|s| (s.date, s.id));
+        Ok(splits)
+    }
+
+    async fn add_splits(&self, splits: &[SplitEvent]) -> Result<usize> {
+        let mut tables = self.store.lock();
+        let mut inserted = 0;
+        for split in splits {
+            let known = tables.split_events.values().any(|s| {
+                s.investment_id == split.investment_id
+                    && s.date == split.date
+                    && s.source == split.source
+            });
+            if !known {
+                tables.split_events.insert(|id| SplitEvent {
+                    id,
+                    ..split.clone()
+                });
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+}
@@ -0,0 +1,161 @@
+use crate::error::Result;
+use crate::models::{
+    CashMovement, DataExport, ImportMode, ImportSummary, Investment, InvestmentPrice, Movement,
+    Portfolio,
+};
+use crate::repository::memory::{investment_price, MemoryStore, Table};
+use crate::repository::traits;
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+#[derive(Clone, Default)]
+pub struct InMemoryDataImportRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryDataImportRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+/// Insert a row under the ID of the export on replace and under a new ID otherwise
+fn insert<T>(table: &mut Table<T>, replace: bool, id: i64, row: impl Fn(i64) -> T) -> i64 {
+    if replace {
+        table.insert_with_id(id, row(id));
+        id
+    } else {
+        table.insert(row)
+    }
+}
+
+#[async_trait]
+impl traits::DataImportRepository for InMemoryDataImportRepository {
+    async fn import(&self, data: &DataExport, mode: ImportMode) -> Result<ImportSummary> {
+        let mut tables = self.store.lock();
+        let mut summary = ImportSummary::default();
+        let replace = mode == ImportMode::Replace;
+
+        if replace {
+            tables.prices.clear();
+            tables.cash_movements.clear();
+            tables.movements.clear();
+            tables.investments.clear();
+            tables.portfolios.clear();
+
+            if let Some(settings) = &data.settings {
+                // Webhooks are not part of the import, like in the database implementations
+                let current = &mut tables.settings;
+                current.base_currency = settings.base_currency.clone();
+                current.cost_basis_method = settings.cost_basis_method.clone();
+                current.benchmark_investment_id = settings.benchmark_investment_id;
+                current.benchmark_ticker = settings.benchmark_ticker.clone();
+                current.price_source_priority = settings.price_source_priority.clone();
+                current.value_decimals = settings.value_decimals;
+                current.price_decimals = settings.price_decimals;
+                current.quantity_decimals = settings.quantity_decimals;
+                current.rounding_mode = settings.rounding_mode.clone();
+                current.reject_negative_holdings = settings.reject_negative_holdings;
+            }
+        }
+
+        for action_type in &data.action_types {
+            if tables.action_types.get(action_type.id).is_none() {
+                tables
+                    .action_types
+                    .insert_with_id(action_type.id, action_type.clone());
+            }
+        }
+
+        let mut portfolio_ids: HashMap<i64, i64> = HashMap::new();
+        for portfolio in &data.portfolios {
+            if !replace {
+                let existing = tables
+                    .portfolios
+                    .values()
+                    .find(|p| p.name == portfolio.name)
+                    .map(|p| p.id);
+                if let Some(id) = existing {
+                    portfolio_ids.insert(portfolio.id, id);
+                    continue;
+                }
+            }
+
+            let id = insert(&mut tables.portfolios, replace, portfolio.id, |id| {
+                Portfolio {
+                    id,
+                    ..portfolio.clone()
+                }
+            });
+            portfolio_ids.insert(portfolio.id, id);
+            summary.portfolios += 1;
+        }
+
+        let mut investment_ids: HashMap<i64, i64> = HashMap::new();
+        for investment in &data.investments {
+            if let (false, Some(isin)) = (replace, &investment.isin) {
+                let existing = tables
+                    .investments
+                    .values()
+                    .find(|i| i.isin.as_ref() == Some(isin))
+                    .map(|i| i.id);
+                if let Some(id) = existing {
+                    investment_ids.insert(investment.id, id);
+                    continue;
+                }
+            }
+
+            let id = insert(&mut tables.investments, replace, investment.id, |id| {
+                Investment {
+                    id,
+                    ..investment.clone()
+                }
+            });
+            investment_ids.insert(investment.id, id);
+            summary.investments += 1;
+        }
+
+        for movement in &data.movements {
+            let investment_id = movement
+                .investment_id
+                .and_then(|id| investment_ids.get(&id).copied());
+            let portfolio_id = movement
+                .portfolio_id
+                .and_then(|id| portfolio_ids.get(&id).copied());
+            insert(&mut tables.movements, replace, movement.id, |id| Movement {
+                id,
+                investment_id,
+                portfolio_id,
+                ..movement.clone()
+            });
+            summary.movements += 1;
+        }
+
+        for cash in &data.cash_movements {
+            let portfolio_id = cash
+                .portfolio_id
+                .and_then(|id| portfolio_ids.get(&id).copied());
+            insert(&mut tables.cash_movements, replace, cash.id, |id| {
+                CashMovement {
+                    id,
+                    portfolio_id,
+                    ..cash.clone()
+                }
+            });
+            summary.cash_movements += 1;
+        }
+
+        for price in &data.prices {
+            let price = InvestmentPrice {
+                investment_id: price
+                    .investment_id
+                    .and_then(|id| investment_ids.get(&id).copied()),
+                ..price.clone()
+            };
+            investment_price::upsert(&mut tables.prices, &price);
+            summary.prices += 1;
+        }
+
+        Ok(summary)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::error::Result;
+use crate::models::Development;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+#[derive(Clone, Default)]
+pub struct InMemoryDevelopmentRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryDevelopmentRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::DevelopmentRepository for InMemoryDevelopmentRepository {
+    async fn find_all(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>> {
+        let mut developments: Vec<Development> = self
+            .store
+            .lock()
+            .developments
+            .iter()
+            .filter(|dev| start_date.is_none_or(|start| dev.date >= start))
+            .filter(|dev| end_date.is_none_or(|end| dev.date <= end))
+            .cloned()
+            .collect();
+        developments.sort_by_key(|dev| (dev.investment, dev.date));
+        Ok(developments)
+    }
+
+    async fn replace(
+        &self,
+        investment_id: Option<i64>,
+        from_date: Option<NaiveDate>,
+        developments: &[Development],
+    ) -> Result<()> {
+        let mut tables = self.store.lock();
+        tables.developments.retain(|dev| {
+            !(investment_id.is_none_or(|id| dev.investment == id)
+                && from_date.is_none_or(|from| dev.date >= from))
+        });
+        tables.developments.extend_from_slice(developments);
+        Ok(())
+    }
+}
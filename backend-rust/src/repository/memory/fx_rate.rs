@@ -0,0 +1,70 @@
+use crate::error::Result;
+use crate::models::FxRate;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+#[derive(Clone, Default)]
+pub struct InMemoryFxRateRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryFxRateRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::FxRateRepository for InMemoryFxRateRepository {
+    async fn find(&self, date: NaiveDate, from: &str, to: &str) -> Result<Option<FxRate>> {
+        Ok(self
+            .store
+            .lock()
+            .fx_rates
+            .iter()
+            .find(|r| r.date == date && r.from_currency == from && r.to_currency == to)
+            .cloned())
+    }
+
+    async fn find_all(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<FxRate>> {
+        let mut rates: Vec<FxRate> = self
+            .store
+            .lock()
+            .fx_rates
+            .iter()
+            .filter(|r| from.is_none_or(|from| r.from_currency == from))
+            .filter(|r| to.is_none_or(|to| r.to_currency == to))
+            .filter(|r| start_date.is_none_or(|start| r.date >= start))
+            .filter(|r| end_date.is_none_or(|end| r.date <= end))
+            .cloned()
+            .collect();
+        rates.sort_by(|a, b| {
+            b.date
+                .cmp(&a.date)
+                .then_with(|| a.from_currency.cmp(&b.from_currency))
+                .then_with(|| a.to_currency.cmp(&b.to_currency))
+        });
+        Ok(rates)
+    }
+
+    async fn upsert(&self, rate: &FxRate) -> Result<()> {
+        let mut tables = self.store.lock();
+        match tables.fx_rates.iter_mut().find(|r| {
+            r.date == rate.date
+                && r.from_currency == rate.from_currency
+                && r.to_currency == rate.to_currency
+        }) {
+            Some(existing) => existing.rate = rate.rate,
+            None => tables.fx_rates.push(rate.clone()),
+        }
+        Ok(())
+    }
+}
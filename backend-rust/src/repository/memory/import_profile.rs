@@ -0,0 +1,61 @@
+use crate::error::Result;
+use crate::models::ImportProfile;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryImportProfileRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryImportProfileRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::ImportProfileRepository for InMemoryImportProfileRepository {
+    async fn find_all(&self) -> Result<Vec<ImportProfile>> {
+        Ok(self
+            .store
+            .lock()
+            .import_profiles
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<ImportProfile>> {
+        Ok(self.store.lock().import_profiles.get(id).cloned())
+    }
+
+    async fn create(&self, profile: &ImportProfile) -> Result<i64> {
+        let id = self
+            .store
+            .lock()
+            .import_profiles
+            .insert(|id| ImportProfile {
+                id,
+                ..profile.clone()
+            });
+        Ok(id)
+    }
+
+    async fn update(&self, id: i64, profile: &ImportProfile) -> Result<u64> {
+        let updated = self.store.lock().import_profiles.update(
+            id,
+            ImportProfile {
+                id,
+                ..profile.clone()
+            },
+        );
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let deleted = self.store.lock().import_profiles.remove(id);
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,36 @@
+use crate::error::Result;
+use crate::models::IntradayPrice;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryIntradayPriceRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryIntradayPriceRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::IntradayPriceRepository for InMemoryIntradayPriceRepository {
+    async fn find(&self, investment_id: i64) -> Result<Option<IntradayPrice>> {
+        Ok(self
+            .store
+            .lock()
+            .intraday_prices
+            .get(investment_id)
+            .cloned())
+    }
+
+    async fn upsert(&self, price: &IntradayPrice) -> Result<()> {
+        self.store
+            .lock()
+            .intraday_prices
+            .insert_with_id(price.investment_id, price.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use crate::error::{AppError, Result};
+use crate::models::{
+    AuditLogEntry, Investment, InvestmentDependents, InvestmentFilter, InvestmentMerge,
+    INVESTMENT_MERGED,
+};
+use crate::repository::memory::{MemoryStore, Tables};
+use crate::repository::traits;
+use crate::repository::{investment_in_use, merge_details};
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryInvestmentRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryInvestmentRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+/// Remove an investment and the rows that reference it with `ON DELETE CASCADE`
+fn remove_investment(tables: &mut Tables, id: i64) -> u64 {
+    let deleted = tables.investments.remove(id);
+    let alerts: Vec<i64> = tables
+        .price_alerts
+        .values()
+        .filter(|alert| alert.investment_id == id)
+        .map(|alert| alert.id)
+        .collect();
+    tables
+        .triggered_alerts
+        .rows
+        .retain(|_, triggered| !alerts.contains(&triggered.alert_id));
+    tables
+        .price_alerts
+        .rows
+        .retain(|_, alert| alert.investment_id != id);
+    tables
+        .savings_plans
+        .rows
+        .retain(|_, plan| plan.investment_id != id);
+    tables
+        .dividend_events
+        .rows
+        .retain(|_, dividend| dividend.investment_id != id);
+    tables
+        .split_events
+        .rows
+        .retain(|_, split| split.investment_id != id);
+    tables.intraday_prices.rows.remove(&id);
+    tables
+        .investment_tags
+        .retain(|(investment_id, _)| *investment_id != id);
+    deleted
+}
+
+#[async_trait]
+impl traits::InvestmentRepository for InMemoryInvestmentRepository {
+    async fn find_all(&self) -> Result<Vec<Investment>> {
+        Ok(self.store.lock().investments.values().cloned().collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Investment>> {
+        Ok(self.store.lock().investments.get(id).cloned())
+    }
+
+    async fn search(
+        &self,
+        text: &str,
+        filter: &InvestmentFilter,
+        limit: i64,
+    ) -> Result<Vec<Investment>> {
+        let needle = text.trim().to_lowercase();
+        let mut ranked: Vec<(u8, String, Investment)> = self
+            .store
+            .lock()
+            .investments
+            .values()
+            .filter(|i| filter.matches(i))
+            .filter_map(|i| {
+                let fields: Vec<String> = [&i.name, &i.shortname, &i.isin, &i.ticker_symbol]
+                    .into_iter()
+                    .flatten()
+                    .map(|field| field.to_lowercase())
+                    .collect();
+                let rank = if fields.contains(&needle) {
+                    0
+                } else if fields.iter().any(|f| f.starts_with(&needle)) {
+                    1
+                } else if fields.iter().any(|f| f.contains(&needle)) {
+                    2
+                } else {
+                    return None;
+                };
+                let name = i.name.as_deref().unwrap_or_default().to_lowercase();
+                Some((rank, name, i.clone()))
+            })
+            .collect();
+        ranked.sort_by(|a, b| (a.0, &a.1, a.2.id).cmp(&(b.0, &b.1, b.2.id)));
+        ranked.truncate(limit.max(0) as usize);
+        Ok(ranked.into_iter().map(|(_, _, i)| i).collect())
+    }
+
+    async fn create(&self, investment: &Investment) -> Result<i64> {
+        let id = self.store.lock().investments.insert(|id| Investment {
+            id,
+            ..investment.clone()
+        });
+        Ok(id)
+    }
+
+    async fn update(&self, id: i64, investment: &Investment) -> Result<u64> {
+        let updated = self.store.lock().investments.update(
+            id,
+            Investment {
+                id,
+                ..investment.clone()
+            },
+        );
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let mut tables = self.store.lock();
+        // Movements reference the investment without cascade
+        if tables
+            .movements
+            .values()
+            .any(|m| m.investment_id == Some(id))
+        {
+            return Err(AppError::Conflict(format!(
+                "Investment {} is referenced by movements",
+                id
+            )));
+        }
+        Ok(remove_investment(&mut tables, id))
+    }
+
+    async fn delete_with_dependents(&self, id: i64, cascade: bool) -> Result<InvestmentDependents> {
+        let mut tables = self.store.lock();
+        if tables.investments.get(id).is_none() {
+            return Err(AppError::not_found("Investment", id));
+        }
+        let dependents = InvestmentDependents {
+            movements: tables
+                .movements
+                .values()
+                .filter(|m| m.investment_id == Some(id))
+                .count() as i64,
+            prices: tables
+                .prices
+                .iter()
+                .filter(|p| p.investment_id == Some(id))
+                .count() as i64,
+        };
+
+        if !cascade && !dependents.is_empty() {
+            return Err(investment_in_use(id, dependents));
+        }
+
+        tables.prices.retain(|p| p.investment_id != Some(id));
+        tables
+            .movements
+            .rows
+            .retain(|_, m| m.investment_id != Some(id));
+        remove_investment(&mut tables, id);
+        Ok(dependents)
+    }
+
+    async fn merge_into(&self, id: i64, target_id: i64) -> Result<InvestmentMerge> {
+        let mut tables = self.store.lock();
+        let mut names = Vec::with_capacity(2);
+        for investment in [id, target_id] {
+            let name = tables
+                .investments
+                .get(investment)
+                .ok_or_else(|| AppError::not_found("Investment", investment))?
+                .name
+                .clone();
+            names.push(name);
+        }
+
+        let target_keys: Vec<_> = tables
+            .prices
+            .iter()
+            .filter(|p| p.investment_id == Some(target_id))
+            .map(|p| (p.date, p.stored_source().to_string()))
+            .collect();
+        let before = tables.prices.len();
+        tables.prices.retain(|p| {
+            p.investment_id != Some(id)
+                || p.date.is_none()
+                || !target_keys.contains(&(p.date, p.stored_source().to_string()))
+        });
+        let duplicate_prices = (before - tables.prices.len()) as i64;
+        let mut prices = 0;
+        for price in tables.prices.iter_mut() {
+            if price.investment_id == Some(id) {
+                price.investment_id = Some(target_id);
+                prices += 1;
+            }
+        }
+        let mut movements = 0;
+        for movement in tables.movements.rows.values_mut() {
+            if movement.investment_id == Some(id) {
+                movement.investment_id = Some(target_id);
+                movements += 1;
+            }
+        }
+        for alert in tables.price_alerts.rows.values_mut() {
+            if alert.investment_id == id {
+                alert.investment_id = target_id;
+            }
+        }
+        for plan in tables.savings_plans.rows.values_mut() {
+            if plan.investment_id == id {
+                plan.investment_id = target_id;
+            }
+        }
+        let tags: Vec<(i64, i64)> = tables
+            .investment_tags
+            .iter()
+            .filter(|(investment_id, _)| *investment_id == id)
+            .map(|(_, tag_id)| (target_id, *tag_id))
+            .collect();
+        tables.investment_tags.extend(tags);
+        remove_investment(&mut tables, id);
+
+        let merge = InvestmentMerge {
+            source_id: id,
+            target_id,
+            movements,
+            prices,
+            duplicate_prices,
+        };
+        let details = merge_details(&merge, names[0].as_deref(), names[1].as_deref());
+        tables.audit_log.insert(|id| AuditLogEntry {
+            id,
+            created_at: chrono::Utc::now(),
+            action: INVESTMENT_MERGED.to_string(),
+            details,
+        });
+        Ok(merge)
+    }
+}
@@ -0,0 +1,149 @@
+use crate::error::Result;
+use crate::models::InvestmentPrice;
+use crate::repository::memory::{unique_violation, MemoryStore};
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+#[derive(Clone, Default)]
+pub struct InMemoryInvestmentPriceRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryInvestmentPriceRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+
+    /// Repository with its own store holding the prices
+    pub fn with_prices(prices: Vec<InvestmentPrice>) -> Self {
+        let repo = Self::default();
+        {
+            let mut tables = repo.store.lock();
+            for price in &prices {
+                upsert(&mut tables.prices, price);
+            }
+        }
+        repo
+    }
+}
+
+/// Whether two prices have the same (Date, InvestmentID, Source) key
+fn same_key(a: &InvestmentPrice, b: &InvestmentPrice) -> bool {
+    a.date == b.date && a.investment_id == b.investment_id && a.stored_source() == b.stored_source()
+}
+
+fn stored(price: &InvestmentPrice) -> InvestmentPrice {
+    InvestmentPrice {
+        source: Some(price.stored_source().to_string()),
+        ..price.clone()
+    }
+}
+
+/// Insert the price or update price and currency of the price with the same key
+pub(crate) fn upsert(prices: &mut Vec<InvestmentPrice>, price: &InvestmentPrice) {
+    match prices.iter_mut().find(|existing| same_key(existing, price)) {
+        Some(existing) => {
+            existing.price = price.price;
+            existing.currency = price.currency.clone();
+            existing.original_price = price.original_price;
+        }
+        None => prices.push(stored(price)),
+    }
+}
+
+#[async_trait]
+impl traits::InvestmentPriceRepository for InMemoryInvestmentPriceRepository {
+    async fn find_all(
+        &self,
+        investment_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<InvestmentPrice>> {
+        let mut prices: Vec<InvestmentPrice> = self
+            .store
+            .lock()
+            .prices
+            .iter()
+            .filter(|p| investment_id.is_none() || p.investment_id == investment_id)
+            .filter(|p| start_date.is_none_or(|start| p.date.is_some_and(|date| date >= start)))
+            .filter(|p| end_date.is_none_or(|end| p.date.is_some_and(|date| date <= end)))
+            .cloned()
+            .collect();
+        prices.sort_by_key(|p| std::cmp::Reverse(p.date));
+        Ok(prices)
+    }
+
+    async fn create(&self, price: &InvestmentPrice) -> Result<()> {
+        let mut tables = self.store.lock();
+        if tables
+            .prices
+            .iter()
+            .any(|existing| same_key(existing, price))
+        {
+            return Err(unique_violation(format!(
+                "A price of investment {:?} on {:?} from {}",
+                price.investment_id,
+                price.date,
+                price.stored_source()
+            )));
+        }
+        tables.prices.push(stored(price));
+        Ok(())
+    }
+
+    async fn upsert(&self, price: &InvestmentPrice) -> Result<()> {
+        upsert(&mut self.store.lock().prices, price);
+        Ok(())
+    }
+
+    async fn upsert_many(&self, prices: &[InvestmentPrice]) -> Result<()> {
+        let mut tables = self.store.lock();
+        for price in prices {
+            upsert(&mut tables.prices, price);
+        }
+        Ok(())
+    }
+
+    async fn find_latest_date(
+        &self,
+        investment_id: i64,
+        source: &str,
+    ) -> Result<Option<NaiveDate>> {
+        Ok(self
+            .store
+            .lock()
+            .prices
+            .iter()
+            .filter(|p| p.investment_id == Some(investment_id) && p.stored_source() == source)
+            .filter_map(|p| p.date)
+            .max())
+    }
+
+    async fn delete_orphaned(&self) -> Result<u64> {
+        let mut tables = self.store.lock();
+        let tables = &mut *tables;
+        let before = tables.prices.len();
+        let investments = &tables.investments;
+        tables.prices.retain(|p| {
+            p.investment_id
+                .is_some_and(|id| investments.get(id).is_some())
+        });
+        Ok((before - tables.prices.len()) as u64)
+    }
+
+    async fn delete_duplicates(&self) -> Result<u64> {
+        let mut tables = self.store.lock();
+        let before = tables.prices.len();
+        // Later entries were stored last, as the highest ID is in the databases
+        let mut kept: Vec<InvestmentPrice> = Vec::with_capacity(before);
+        for price in tables.prices.drain(..).rev() {
+            if !kept.iter().any(|existing| same_key(existing, &price)) {
+                kept.push(price);
+            }
+        }
+        kept.reverse();
+        tables.prices = kept;
+        Ok((before - tables.prices.len()) as u64)
+    }
+}
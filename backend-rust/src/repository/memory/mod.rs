@@ -0,0 +1,244 @@
+//! Repositories that keep all data in memory, for tests and embedding the library
+//! without a database
+//!
+//! All repositories created from the same [`MemoryStore`] see the same data, so that
+//! e.g. deleting an investment also removes its price alerts.
+
+pub mod action_type;
+pub mod api_token;
+pub mod audit_log;
+pub mod cash_movement;
+pub mod corporate_event;
+pub mod data_import;
+pub mod development;
+pub mod fx_rate;
+pub mod health;
+pub mod import_profile;
+pub mod intraday_price;
+pub mod investment;
+pub mod investment_price;
+pub mod movement;
+pub mod portfolio;
+pub mod price_alert;
+pub mod provider_config;
+pub mod quote_fetch_log;
+pub mod savings_plan;
+pub mod settings;
+pub mod snapshot;
+pub mod tag;
+
+pub use action_type::InMemoryActionTypeRepository;
+pub use api_token::InMemoryApiTokenRepository;
+pub use audit_log::InMemoryAuditLogRepository;
+pub use cash_movement::InMemoryCashMovementRepository;
+pub use corporate_event::InMemoryCorporateEventRepository;
+pub use data_import::InMemoryDataImportRepository;
+pub use development::InMemoryDevelopmentRepository;
+pub use fx_rate::InMemoryFxRateRepository;
+pub use health::InMemoryHealthRepository;
+pub use import_profile::InMemoryImportProfileRepository;
+pub use intraday_price::InMemoryIntradayPriceRepository;
+pub use investment::InMemoryInvestmentRepository;
+pub use investment_price::InMemoryInvestmentPriceRepository;
+pub use movement::InMemoryMovementRepository;
+pub use portfolio::InMemoryPortfolioRepository;
+pub use price_alert::InMemoryPriceAlertRepository;
+pub use provider_config::InMemoryProviderConfigRepository;
+pub use quote_fetch_log::InMemoryQuoteFetchLogRepository;
+pub use savings_plan::InMemorySavingsPlanRepository;
+pub use settings::InMemorySettingsRepository;
+pub use snapshot::InMemorySnapshotRepository;
+pub use tag::InMemoryTagRepository;
+
+use crate::error::AppError;
+use crate::models::{
+    ActionType, ApiToken, AuditLogEntry, CashMovement, Development, DividendEvent, FxRate,
+    ImportProfile, IntradayPrice, Investment, InvestmentPrice, Movement, Portfolio, PriceAlert,
+    ProviderConfig, QuoteFetchLog, SavingsPlan, Settings, SnapshotHolding, SplitEvent, Tag,
+    TriggeredAlert,
+};
+use sqlx::types::Json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Rows of a table by ID, with IDs assigned like an autoincrement column
+#[derive(Debug, Clone)]
+pub(crate) struct Table<T> {
+    pub(crate) rows: BTreeMap<i64, T>,
+    last_id: i64,
+}
+
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+            last_id: 0,
+        }
+    }
+}
+
+impl<T> Table<T> {
+    /// Insert a row under the next free ID, which is passed to `row`
+    pub(crate) fn insert(&mut self, row: impl FnOnce(i64) -> T) -> i64 {
+        self.last_id += 1;
+        self.rows.insert(self.last_id, row(self.last_id));
+        self.last_id
+    }
+
+    /// Insert or overwrite a row with an explicit ID
+    pub(crate) fn insert_with_id(&mut self, id: i64, row: T) {
+        self.last_id = self.last_id.max(id);
+        self.rows.insert(id, row);
+    }
+
+    /// Replace an existing row; unknown IDs are ignored like an `UPDATE` without match
+    /// Replace an existing row, returning the number of updated rows
+    pub(crate) fn update(&mut self, id: i64, row: T) -> u64 {
+        match self.rows.get_mut(&id) {
+            Some(existing) => {
+                *existing = row;
+                1
+            }
+            None => 0,
+        }
+    }
+
+    /// Remove a row, returning the number of deleted rows
+    pub(crate) fn remove(&mut self, id: i64) -> u64 {
+        u64::from(self.rows.remove(&id).is_some())
+    }
+
+    pub(crate) fn get(&self, id: i64) -> Option<&T> {
+        self.rows.get(&id)
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &T> {
+        self.rows.values()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.rows.clear();
+    }
+}
+
+/// Content of the in-memory database
+#[derive(Debug)]
+pub(crate) struct Tables {
+    pub(crate) investments: Table<Investment>,
+    pub(crate) movements: Table<Movement>,
+    pub(crate) prices: Vec<InvestmentPrice>,
+    pub(crate) action_types: Table<ActionType>,
+    pub(crate) settings: Settings,
+    pub(crate) portfolios: Table<Portfolio>,
+    pub(crate) cash_movements: Table<CashMovement>,
+    pub(crate) quote_fetch_log: Table<QuoteFetchLog>,
+    pub(crate) fx_rates: Vec<FxRate>,
+    pub(crate) import_profiles: Table<ImportProfile>,
+    /// Live quotes by investment ID
+    pub(crate) intraday_prices: Table<IntradayPrice>,
+    /// Provider API keys by provider ID
+    pub(crate) provider_configs: BTreeMap<String, ProviderConfig>,
+    pub(crate) developments: Vec<Development>,
+    pub(crate) price_alerts: Table<PriceAlert>,
+    pub(crate) triggered_alerts: Table<TriggeredAlert>,
+    pub(crate) savings_plans: Table<SavingsPlan>,
+    pub(crate) dividend_events: Table<DividendEvent>,
+    pub(crate) split_events: Table<SplitEvent>,
+    pub(crate) snapshots: Vec<SnapshotHolding>,
+    pub(crate) tags: Table<Tag>,
+    pub(crate) audit_log: Table<AuditLogEntry>,
+    pub(crate) api_tokens: Table<ApiToken>,
+    /// Tag assignments as (investment ID, tag ID)
+    pub(crate) investment_tags: BTreeSet<(i64, i64)>,
+}
+
+impl Default for Tables {
+    /// Tables with the built-in action types and default settings of the migrations
+    fn default() -> Self {
+        let mut action_types = Table::default();
+        for (id, name, effect) in [
+            (1, "Buy", "increases_quantity"),
+            (2, "Sell", "decreases_quantity"),
+            (3, "Payout", "cash_only"),
+            (4, "Deposit", "cash_only"),
+            (5, "Withdrawal", "cash_only"),
+            (6, "Split", "split"),
+            (7, "TransferOut", "transfer_out"),
+            (8, "TransferIn", "transfer_in"),
+            (9, "Opening", "opening"),
+        ] {
+            action_types.insert_with_id(
+                id,
+                ActionType {
+                    id,
+                    name: name.to_string(),
+                    effect: effect.to_string(),
+                },
+            );
+        }
+
+        Self {
+            investments: Table::default(),
+            movements: Table::default(),
+            prices: Vec::new(),
+            action_types,
+            settings: Settings {
+                id: 1,
+                base_currency: "EUR".to_string(),
+                cost_basis_method: "fifo".to_string(),
+                benchmark_investment_id: None,
+                benchmark_ticker: None,
+                webhook_urls: Json(Vec::new()),
+                webhook_secret: None,
+                price_source_priority: Json(Vec::new()),
+                value_decimals: None,
+                price_decimals: None,
+                quantity_decimals: None,
+                rounding_mode: "half_even".to_string(),
+                reject_negative_holdings: false,
+            },
+            portfolios: Table::default(),
+            cash_movements: Table::default(),
+            quote_fetch_log: Table::default(),
+            fx_rates: Vec::new(),
+            import_profiles: Table::default(),
+            intraday_prices: Table::default(),
+            provider_configs: BTreeMap::new(),
+            developments: Vec::new(),
+            price_alerts: Table::default(),
+            triggered_alerts: Table::default(),
+            savings_plans: Table::default(),
+            dividend_events: Table::default(),
+            split_events: Table::default(),
+            snapshots: Vec::new(),
+            tags: Table::default(),
+            audit_log: Table::default(),
+            api_tokens: Table::default(),
+            investment_tags: BTreeSet::new(),
+        }
+    }
+}
+
+/// Shared in-memory database of the `InMemory*` repositories
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl MemoryStore {
+    /// Empty database with the built-in action types and default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Tables> {
+        // A panic while holding the lock leaves the tables consistent, as every
+        // operation validates before it writes
+        self.tables.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Error of a write that violates a unique key, like the database constraint would
+pub(crate) fn unique_violation(what: impl std::fmt::Display) -> AppError {
+    AppError::Conflict(format!("{} already exists", what))
+}
@@ -0,0 +1,287 @@
+use crate::error::Result;
+use crate::models::{Movement, MovementListOptions, MovementSortField, SortOrder};
+use crate::repository::memory::{unique_violation, MemoryStore, Table};
+use crate::repository::traits;
+use crate::services::cost_basis::SPLIT_ACTION_ID;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Default)]
+pub struct InMemoryMovementRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryMovementRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+
+    /// Repository with its own store holding the movements; movements with ID 0 get
+    /// the next free ID
+    pub fn with_movements(movements: Vec<Movement>) -> Self {
+        let repo = Self::default();
+        {
+            let mut tables = repo.store.lock();
+            for movement in movements {
+                if movement.id == 0 {
+                    tables.movements.insert(|id| Movement { id, ..movement });
+                } else {
+                    tables.movements.insert_with_id(movement.id, movement);
+                }
+            }
+        }
+        repo
+    }
+}
+
+fn matches(movement: &Movement, options: &MovementListOptions) -> bool {
+    (options.portfolio_id.is_none() || movement.portfolio_id == options.portfolio_id)
+        && (options.investment_id.is_none() || movement.investment_id == options.investment_id)
+        && (options.action_id.is_none() || movement.action_id == options.action_id)
+        && options
+            .start_date
+            .is_none_or(|start| movement.date.is_some_and(|date| date >= start))
+        && options
+            .end_date
+            .is_none_or(|end| movement.date.is_some_and(|date| date <= end))
+}
+
+fn compare(a: &Movement, b: &Movement, sort_by: MovementSortField) -> Ordering {
+    // Missing values sort first, like NULL in SQLite
+    match sort_by {
+        MovementSortField::Id => Ordering::Equal,
+        MovementSortField::Date => a.date.cmp(&b.date),
+        MovementSortField::Amount => a.amount.cmp(&b.amount),
+        MovementSortField::Quantity => a.quantity.cmp(&b.quantity),
+    }
+    .then(a.id.cmp(&b.id))
+}
+
+/// Reject a second movement with the same external reference in a portfolio
+fn check_external_id(movements: &Table<Movement>, id: i64, movement: &Movement) -> Result<()> {
+    let Some(external_id) = &movement.external_id else {
+        return Ok(());
+    };
+    let duplicate = movements.values().any(|other| {
+        other.id != id
+            && other.external_id.as_ref() == Some(external_id)
+            && other.portfolio_id.unwrap_or(0) == movement.portfolio_id.unwrap_or(0)
+    });
+    if duplicate {
+        return Err(unique_violation(format!(
+            "A movement with external ID '{}'",
+            external_id
+        )));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl traits::MovementRepository for InMemoryMovementRepository {
+    async fn find_all(&self) -> Result<Vec<Movement>> {
+        Ok(self.store.lock().movements.values().cloned().collect())
+    }
+
+    async fn find_by_portfolio(&self, portfolio_id: i64) -> Result<Vec<Movement>> {
+        Ok(self
+            .store
+            .lock()
+            .movements
+            .values()
+            .filter(|m| m.portfolio_id == Some(portfolio_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn find_page(&self, options: &MovementListOptions) -> Result<(Vec<Movement>, i64)> {
+        let mut movements: Vec<Movement> = self
+            .store
+            .lock()
+            .movements
+            .values()
+            .filter(|m| matches(m, options))
+            .cloned()
+            .collect();
+        let total = movements.len() as i64;
+
+        movements.sort_by(|a, b| {
+            let ordering = compare(a, b, options.sort_by);
+            match options.order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            }
+        });
+        let page = movements
+            .into_iter()
+            .skip(options.offset.max(0) as usize)
+            .take(
+                options
+                    .limit
+                    .map_or(usize::MAX, |limit| limit.max(0) as usize),
+            )
+            .collect();
+
+        Ok((page, total))
+    }
+
+    async fn find_by_investment_and_range(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Movement>> {
+        let options = MovementListOptions {
+            portfolio_id,
+            investment_id,
+            start_date,
+            end_date,
+            ..Default::default()
+        };
+        let mut movements: Vec<Movement> = self
+            .store
+            .lock()
+            .movements
+            .values()
+            .filter(|m| matches(m, &options))
+            .cloned()
+            .collect();
+        movements.sort_by(|a, b| compare(a, b, MovementSortField::Date));
+        Ok(movements)
+    }
+
+    async fn find_quantity_totals_before(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        before: NaiveDate,
+    ) -> Result<Vec<Movement>> {
+        let options = MovementListOptions {
+            portfolio_id,
+            investment_id,
+            end_date: before.pred_opt(),
+            ..Default::default()
+        };
+        let movements: Vec<Movement> = self
+            .store
+            .lock()
+            .movements
+            .values()
+            .filter(|m| matches(m, &options) && m.investment_id.is_some() && m.quantity.is_some())
+            .cloned()
+            .collect();
+        let (splits, others): (Vec<Movement>, Vec<Movement>) = movements
+            .into_iter()
+            .partition(|m| m.action_id == Some(SPLIT_ACTION_ID));
+
+        // Summed per investment, action type, sign and number of splits up to the movement
+        let mut totals: BTreeMap<(Option<i64>, Option<i64>, bool, usize), Movement> =
+            BTreeMap::new();
+        for movement in others {
+            let period = splits
+                .iter()
+                .filter(|s| s.investment_id == movement.investment_id && s.date <= movement.date)
+                .count();
+            let quantity = movement.quantity.unwrap_or_default();
+            let key = (
+                movement.investment_id,
+                movement.action_id,
+                quantity < Decimal::ZERO,
+                period,
+            );
+            let total = totals.entry(key).or_insert_with(|| Movement {
+                id: 0,
+                date: movement.date,
+                action_id: movement.action_id,
+                investment_id: movement.investment_id,
+                quantity: Some(Decimal::ZERO),
+                amount: None,
+                fee: None,
+                tax: None,
+                portfolio_id,
+                external_id: None,
+            });
+            total.date = total.date.min(movement.date);
+            total.quantity = Some(total.quantity.unwrap_or_default() + quantity);
+        }
+
+        let mut condensed: Vec<Movement> = totals.into_values().chain(splits).collect();
+        condensed.sort_by_key(|m| m.date);
+        Ok(condensed)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Movement>> {
+        Ok(self.store.lock().movements.get(id).cloned())
+    }
+
+    async fn find_by_external_id(
+        &self,
+        portfolio_id: Option<i64>,
+        external_id: &str,
+    ) -> Result<Option<Movement>> {
+        Ok(self
+            .store
+            .lock()
+            .movements
+            .values()
+            .find(|m| {
+                m.portfolio_id.unwrap_or(0) == portfolio_id.unwrap_or(0)
+                    && m.external_id.as_deref() == Some(external_id)
+            })
+            .cloned())
+    }
+
+    async fn create(&self, movement: &Movement) -> Result<i64> {
+        let mut tables = self.store.lock();
+        check_external_id(&tables.movements, 0, movement)?;
+        let id = tables.movements.insert(|id| Movement {
+            id,
+            ..movement.clone()
+        });
+        Ok(id)
+    }
+
+    async fn create_many(&self, movements: &[Movement]) -> Result<Vec<i64>> {
+        let mut tables = self.store.lock();
+        // Validate against a copy, so that either all or none are inserted
+        let mut inserted = tables.movements.clone();
+        let mut ids = Vec::with_capacity(movements.len());
+        for movement in movements {
+            check_external_id(&inserted, 0, movement)?;
+            ids.push(inserted.insert(|id| Movement {
+                id,
+                ..movement.clone()
+            }));
+        }
+        tables.movements = inserted;
+        Ok(ids)
+    }
+
+    async fn update(&self, id: i64, movement: &Movement) -> Result<u64> {
+        let mut tables = self.store.lock();
+        check_external_id(&tables.movements, id, movement)?;
+        let updated = tables.movements.update(
+            id,
+            Movement {
+                id,
+                ..movement.clone()
+            },
+        );
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let mut tables = self.store.lock();
+        let deleted = tables.movements.remove(id);
+        // Confirmed dividends keep their status, like with `ON DELETE SET NULL`
+        for dividend in tables.dividend_events.rows.values_mut() {
+            if dividend.movement_id == Some(id) {
+                dividend.movement_id = None;
+            }
+        }
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::error::Result;
+use crate::models::Portfolio;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryPortfolioRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryPortfolioRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::PortfolioRepository for InMemoryPortfolioRepository {
+    async fn find_all(&self) -> Result<Vec<Portfolio>> {
+        Ok(self.store.lock().portfolios.values().cloned().collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Portfolio>> {
+        Ok(self.store.lock().portfolios.get(id).cloned())
+    }
+
+    async fn create(&self, portfolio: &Portfolio) -> Result<i64> {
+        let id = self.store.lock().portfolios.insert(|id| Portfolio {
+            id,
+            ..portfolio.clone()
+        });
+        Ok(id)
+    }
+
+    async fn update(&self, id: i64, portfolio: &Portfolio) -> Result<u64> {
+        let updated = self.store.lock().portfolios.update(
+            id,
+            Portfolio {
+                id,
+                ..portfolio.clone()
+            },
+        );
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let mut tables = self.store.lock();
+        let deleted = tables.portfolios.remove(id);
+        // Movements and cash movements reference the portfolio with ON DELETE SET NULL
+        for movement in tables.movements.rows.values_mut() {
+            if movement.portfolio_id == Some(id) {
+                movement.portfolio_id = None;
+            }
+        }
+        for movement in tables.cash_movements.rows.values_mut() {
+            if movement.portfolio_id == Some(id) {
+                movement.portfolio_id = None;
+            }
+        }
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,104 @@
+use crate::error::Result;
+use crate::models::{PriceAlert, TriggeredAlert};
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryPriceAlertRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryPriceAlertRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::PriceAlertRepository for InMemoryPriceAlertRepository {
+    async fn find_all(&self, investment_id: Option<i64>) -> Result<Vec<PriceAlert>> {
+        Ok(self
+            .store
+            .lock()
+            .price_alerts
+            .values()
+            .filter(|alert| investment_id.is_none_or(|id| alert.investment_id == id))
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<PriceAlert>> {
+        Ok(self.store.lock().price_alerts.get(id).cloned())
+    }
+
+    async fn find_active(&self, investment_id: i64) -> Result<Vec<PriceAlert>> {
+        Ok(self
+            .store
+            .lock()
+            .price_alerts
+            .values()
+            .filter(|alert| alert.investment_id == investment_id && alert.active)
+            .cloned()
+            .collect())
+    }
+
+    async fn create(&self, alert: &PriceAlert) -> Result<i64> {
+        let id = self.store.lock().price_alerts.insert(|id| PriceAlert {
+            id,
+            ..alert.clone()
+        });
+        Ok(id)
+    }
+
+    async fn update(&self, id: i64, alert: &PriceAlert) -> Result<u64> {
+        let updated = self.store.lock().price_alerts.update(
+            id,
+            PriceAlert {
+                id,
+                ..alert.clone()
+            },
+        );
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let mut tables = self.store.lock();
+        let deleted = tables.price_alerts.remove(id);
+        tables
+            .triggered_alerts
+            .rows
+            .retain(|_, triggered| triggered.alert_id != id);
+        Ok(deleted)
+    }
+
+    async fn record_trigger(&self, triggered: &TriggeredAlert) -> Result<i64> {
+        let mut tables = self.store.lock();
+        let id = tables.triggered_alerts.insert(|id| TriggeredAlert {
+            id,
+            ..triggered.clone()
+        });
+        if let Some(alert) = tables.price_alerts.rows.get_mut(&triggered.alert_id) {
+            alert.active = false;
+        }
+        Ok(id)
+    }
+
+    async fn find_triggered(
+        &self,
+        investment_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<TriggeredAlert>> {
+        let mut triggered: Vec<TriggeredAlert> = self
+            .store
+            .lock()
+            .triggered_alerts
+            .values()
+            .filter(|t| investment_id.is_none_or(|id| t.investment_id == id))
+            .cloned()
+            .collect();
+        triggered.sort_by_key(|t| std::cmp::Reverse((t.triggered_at, t.id)));
+        triggered.truncate(limit.max(0) as usize);
+        Ok(triggered)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::error::Result;
+use crate::models::ProviderConfig;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryProviderConfigRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryProviderConfigRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::ProviderConfigRepository for InMemoryProviderConfigRepository {
+    async fn find_all(&self) -> Result<Vec<ProviderConfig>> {
+        Ok(self
+            .store
+            .lock()
+            .provider_configs
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn upsert(&self, config: &ProviderConfig) -> Result<()> {
+        self.store
+            .lock()
+            .provider_configs
+            .insert(config.provider.clone(), config.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, provider: &str) -> Result<u64> {
+        let deleted = self.store.lock().provider_configs.remove(provider);
+        Ok(u64::from(deleted.is_some()))
+    }
+}
@@ -0,0 +1,61 @@
+use crate::error::Result;
+use crate::models::QuoteFetchLog;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Default)]
+pub struct InMemoryQuoteFetchLogRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryQuoteFetchLogRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::QuoteFetchLogRepository for InMemoryQuoteFetchLogRepository {
+    async fn find_recent(
+        &self,
+        investment_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<QuoteFetchLog>> {
+        let mut entries: Vec<QuoteFetchLog> = self
+            .store
+            .lock()
+            .quote_fetch_log
+            .values()
+            .filter(|entry| investment_id.is_none_or(|id| entry.investment_id == id))
+            .cloned()
+            .collect();
+        entries.sort_by_key(|e| std::cmp::Reverse((e.fetched_at, e.id)));
+        entries.truncate(limit.max(0) as usize);
+        Ok(entries)
+    }
+
+    async fn create(&self, entry: &QuoteFetchLog) -> Result<i64> {
+        let id = self
+            .store
+            .lock()
+            .quote_fetch_log
+            .insert(|id| QuoteFetchLog {
+                id,
+                ..entry.clone()
+            });
+        Ok(id)
+    }
+
+    async fn last_success(&self) -> Result<Option<DateTime<Utc>>> {
+        Ok(self
+            .store
+            .lock()
+            .quote_fetch_log
+            .values()
+            .filter(|entry| entry.success)
+            .map(|entry| entry.fetched_at)
+            .max())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::error::Result;
+use crate::models::SavingsPlan;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemorySavingsPlanRepository {
+    store: MemoryStore,
+}
+
+impl InMemorySavingsPlanRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::SavingsPlanRepository for InMemorySavingsPlanRepository {
+    async fn find_all(&self, investment_id: Option<i64>) -> Result<Vec<SavingsPlan>> {
+        Ok(self
+            .store
+            .lock()
+            .savings_plans
+            .values()
+            .filter(|plan| investment_id.is_none_or(|id| plan.investment_id == id))
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<SavingsPlan>> {
+        Ok(self.store.lock().savings_plans.get(id).cloned())
+    }
+
+    async fn create(&self, plan: &SavingsPlan) -> Result<i64> {
+        let id = self
+            .store
+            .lock()
+            .savings_plans
+            .insert(|id| SavingsPlan { id, ..plan.clone() });
+        Ok(id)
+    }
+
+    async fn update(&self, id: i64, plan: &SavingsPlan) -> Result<u64> {
+        let updated = self
+            .store
+            .lock()
+            .savings_plans
+            .update(id, SavingsPlan { id, ..plan.clone() });
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let deleted = self.store.lock().savings_plans.remove(id);
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,31 @@
+use crate::error::Result;
+use crate::models::Settings;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemorySettingsRepository {
+    store: MemoryStore,
+}
+
+impl InMemorySettingsRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::SettingsRepository for InMemorySettingsRepository {
+    async fn get(&self) -> Result<Option<Settings>> {
+        Ok(Some(self.store.lock().settings.clone()))
+    }
+
+    async fn update(&self, settings: &Settings) -> Result<()> {
+        self.store.lock().settings = Settings {
+            id: 1,
+            ..settings.clone()
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use crate::error::Result;
+use crate::models::SnapshotHolding;
+use crate::repository::memory::MemoryStore;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+#[derive(Clone, Default)]
+pub struct InMemorySnapshotRepository {
+    store: MemoryStore,
+}
+
+impl InMemorySnapshotRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl traits::SnapshotRepository for InMemorySnapshotRepository {
+    async fn find_all(&self, date: Option<NaiveDate>) -> Result<Vec<SnapshotHolding>> {
+        let mut holdings: Vec<SnapshotHolding> = self
+            .store
+            .lock()
+            .snapshots
+            .iter()
+            .filter(|h| date.is_none_or(|date| h.date == date))
+            .cloned()
+            .collect();
+        holdings.sort_by_key(|h| (h.date, h.investment));
+        Ok(holdings)
+    }
+
+    async fn replace(&self, date: NaiveDate, holdings: &[SnapshotHolding]) -> Result<()> {
+        let mut tables = self.store.lock();
+        tables.snapshots.retain(|h| h.date != date);
+        tables.snapshots.extend(
+            holdings
+                .iter()
+                .map(|h| SnapshotHolding { date, ..h.clone() }),
+        );
+        Ok(())
+    }
+
+    async fn delete(&self, date: NaiveDate) -> Result<u64> {
+        let mut tables = self.store.lock();
+        let before = tables.snapshots.len();
+        tables.snapshots.retain(|h| h.date != date);
+        Ok((before - tables.snapshots.len()) as u64)
+    }
+}
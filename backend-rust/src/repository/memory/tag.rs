@@ -0,0 +1,120 @@
+use crate::error::Result;
+use crate::models::Tag;
+use crate::repository::memory::{unique_violation, MemoryStore, Tables};
+use crate::repository::traits;
+use async_trait::async_trait;
+
+#[derive(Clone, Default)]
+pub struct InMemoryTagRepository {
+    store: MemoryStore,
+}
+
+impl InMemoryTagRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+/// Tag names are unique ignoring case
+fn check_name(tables: &Tables, id: Option<i64>, name: &str) -> Result<()> {
+    let taken = tables
+        .tags
+        .values()
+        .any(|tag| Some(tag.id) != id && tag.name.eq_ignore_ascii_case(name));
+    if taken {
+        return Err(unique_violation(format!("Tag '{}'", name)));
+    }
+    Ok(())
+}
+
+fn sorted_by_name(mut tags: Vec<Tag>) -> Vec<Tag> {
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+    tags
+}
+
+#[async_trait]
+impl traits::TagRepository for InMemoryTagRepository {
+    async fn find_all(&self) -> Result<Vec<Tag>> {
+        let tags = self.store.lock().tags.values().cloned().collect();
+        Ok(sorted_by_name(tags))
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Tag>> {
+        Ok(self.store.lock().tags.get(id).cloned())
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Tag>> {
+        Ok(self
+            .store
+            .lock()
+            .tags
+            .values()
+            .find(|tag| tag.name.eq_ignore_ascii_case(name))
+            .cloned())
+    }
+
+    async fn create(&self, tag: &Tag) -> Result<i64> {
+        let mut tables = self.store.lock();
+        check_name(&tables, None, &tag.name)?;
+        let id = tables.tags.insert(|id| Tag {
+            id,
+            name: tag.name.clone(),
+        });
+        Ok(id)
+    }
+
+    async fn update(&self, id: i64, tag: &Tag) -> Result<u64> {
+        let mut tables = self.store.lock();
+        check_name(&tables, Some(id), &tag.name)?;
+        let updated = tables.tags.update(
+            id,
+            Tag {
+                id,
+                name: tag.name.clone(),
+            },
+        );
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let mut tables = self.store.lock();
+        let deleted = tables.tags.remove(id);
+        tables.investment_tags.retain(|&(_, tag_id)| tag_id != id);
+        Ok(deleted)
+    }
+
+    async fn find_by_investment(&self, investment_id: i64) -> Result<Vec<Tag>> {
+        let tables = self.store.lock();
+        let tags = tables
+            .investment_tags
+            .iter()
+            .filter(|&&(investment, _)| investment == investment_id)
+            .filter_map(|&(_, tag_id)| tables.tags.get(tag_id).cloned())
+            .collect();
+        Ok(sorted_by_name(tags))
+    }
+
+    async fn set_investment_tags(&self, investment_id: i64, tag_ids: &[i64]) -> Result<()> {
+        let mut tables = self.store.lock();
+        tables
+            .investment_tags
+            .retain(|&(investment, _)| investment != investment_id);
+        for &tag_id in tag_ids {
+            tables.investment_tags.insert((investment_id, tag_id));
+        }
+        Ok(())
+    }
+
+    async fn find_investment_ids(&self, tag_id: i64) -> Result<Vec<i64>> {
+        let mut ids: Vec<i64> = self
+            .store
+            .lock()
+            .investment_tags
+            .iter()
+            .filter(|&&(_, tag)| tag == tag_id)
+            .map(|&(investment, _)| investment)
+            .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    }
+}
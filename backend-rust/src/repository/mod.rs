@@ -1,8 +1,247 @@
+#[cfg(feature = "test-util")]
+pub mod memory;
+pub mod notifying;
+pub mod postgres;
 pub mod sqlite;
 pub mod traits;
 
+use crate::error::AppError;
+use crate::models::{InvestmentDependents, InvestmentMerge};
+use notifying::{
+    ChangeListener, NotifyingActionTypeRepository, NotifyingDataImportRepository,
+    NotifyingInvestmentPriceRepository, NotifyingInvestmentRepository, NotifyingMovementRepository,
+    NotifyingSettingsRepository,
+};
+use std::sync::Arc;
+use traits::{
+    ActionTypeRepository, ApiTokenRepository, AuditLogRepository, CashMovementRepository,
+    CorporateEventRepository, DataImportRepository, DevelopmentRepository, FxRateRepository,
+    HealthRepository, ImportProfileRepository, IntradayPriceRepository, InvestmentPriceRepository,
+    InvestmentRepository, MovementRepository, PortfolioRepository, PriceAlertRepository,
+    ProviderConfigRepository, QuoteFetchLogRepository, SavingsPlanRepository, SettingsRepository,
+    SnapshotRepository, TagRepository,
+};
+
 // Re-export concrete implementations for convenience
+#[cfg(feature = "test-util")]
+pub use memory::{
+    InMemoryActionTypeRepository, InMemoryApiTokenRepository, InMemoryAuditLogRepository,
+    InMemoryCashMovementRepository, InMemoryCorporateEventRepository, InMemoryDataImportRepository,
+    InMemoryDevelopmentRepository, InMemoryFxRateRepository, InMemoryHealthRepository,
+    InMemoryImportProfileRepository, InMemoryIntradayPriceRepository,
+    InMemoryInvestmentPriceRepository, InMemoryInvestmentRepository, InMemoryMovementRepository,
+    InMemoryPortfolioRepository, InMemoryPriceAlertRepository, InMemoryProviderConfigRepository,
+    InMemoryQuoteFetchLogRepository, InMemorySavingsPlanRepository, InMemorySettingsRepository,
+    InMemorySnapshotRepository, InMemoryTagRepository, MemoryStore,
+};
+pub use postgres::{
+    PostgresActionTypeRepository, PostgresApiTokenRepository, PostgresAuditLogRepository,
+    PostgresCashMovementRepository, PostgresCorporateEventRepository, PostgresDataImportRepository,
+    PostgresDevelopmentRepository, PostgresFxRateRepository, PostgresHealthRepository,
+    PostgresImportProfileRepository, PostgresIntradayPriceRepository,
+    PostgresInvestmentPriceRepository, PostgresInvestmentRepository, PostgresMovementRepository,
+    PostgresPortfolioRepository, PostgresPriceAlertRepository, PostgresProviderConfigRepository,
+    PostgresQuoteFetchLogRepository, PostgresSavingsPlanRepository, PostgresSettingsRepository,
+    PostgresSnapshotRepository, PostgresTagRepository,
+};
 pub use sqlite::{
-    SqliteActionTypeRepository, SqliteInvestmentPriceRepository, SqliteInvestmentRepository,
-    SqliteMovementRepository, SqliteSettingsRepository,
+    SqliteActionTypeRepository, SqliteApiTokenRepository, SqliteAuditLogRepository,
+    SqliteCashMovementRepository, SqliteCorporateEventRepository, SqliteDataImportRepository,
+    SqliteDevelopmentRepository, SqliteFxRateRepository, SqliteHealthRepository,
+    SqliteImportProfileRepository, SqliteIntradayPriceRepository, SqliteInvestmentPriceRepository,
+    SqliteInvestmentRepository, SqliteMovementRepository, SqlitePortfolioRepository,
+    SqlitePriceAlertRepository, SqliteProviderConfigRepository, SqliteQuoteFetchLogRepository,
+    SqliteSavingsPlanRepository, SqliteSettingsRepository, SqliteSnapshotRepository,
+    SqliteTagRepository,
 };
+
+/// Error for deleting an investment that is still referenced, with a preview of what
+/// a cascading delete would remove
+pub(crate) fn investment_in_use(id: i64, dependents: InvestmentDependents) -> AppError {
+    AppError::Conflict(format!(
+        "Investment {} has {} movement(s) and {} price(s); delete with cascade=true to remove them as well",
+        id, dependents.movements, dependents.prices
+    ))
+}
+
+/// Audit log details of an investment merge, naming both investments as they were
+pub(crate) fn merge_details(
+    merge: &InvestmentMerge,
+    source_name: Option<&str>,
+    target_name: Option<&str>,
+) -> String {
+    format!(
+        "Merged investment {} ({}) into {} ({}): moved {} movement(s) and {} price(s), dropped {} duplicate price(s)",
+        merge.source_id,
+        source_name.unwrap_or("unnamed"),
+        merge.target_id,
+        target_name.unwrap_or("unnamed"),
+        merge.movements,
+        merge.prices,
+        merge.duplicate_prices
+    )
+}
+
+/// Lower case patterns of an investment search, with the LIKE wildcards of the text
+/// escaped by `\`
+pub(crate) struct SearchPatterns {
+    pub exact: String,
+    pub prefix: String,
+    pub contains: String,
+}
+
+impl SearchPatterns {
+    pub(crate) fn new(text: &str) -> Self {
+        let exact = text.trim().to_lowercase();
+        let escaped = exact
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        Self {
+            prefix: format!("{}%", escaped),
+            contains: format!("%{}%", escaped),
+            exact,
+        }
+    }
+}
+
+/// The full set of repositories for one storage backend
+#[derive(Clone)]
+pub struct Repositories {
+    pub investments: Arc<dyn InvestmentRepository>,
+    pub movements: Arc<dyn MovementRepository>,
+    pub investment_prices: Arc<dyn InvestmentPriceRepository>,
+    pub action_types: Arc<dyn ActionTypeRepository>,
+    pub settings: Arc<dyn SettingsRepository>,
+    pub portfolios: Arc<dyn PortfolioRepository>,
+    pub cash_movements: Arc<dyn CashMovementRepository>,
+    pub quote_fetch_log: Arc<dyn QuoteFetchLogRepository>,
+    pub audit_log: Arc<dyn AuditLogRepository>,
+    pub fx_rates: Arc<dyn FxRateRepository>,
+    pub data_import: Arc<dyn DataImportRepository>,
+    pub import_profiles: Arc<dyn ImportProfileRepository>,
+    pub developments: Arc<dyn DevelopmentRepository>,
+    pub price_alerts: Arc<dyn PriceAlertRepository>,
+    pub savings_plans: Arc<dyn SavingsPlanRepository>,
+    pub corporate_events: Arc<dyn CorporateEventRepository>,
+    pub intraday_prices: Arc<dyn IntradayPriceRepository>,
+    pub provider_configs: Arc<dyn ProviderConfigRepository>,
+    pub snapshots: Arc<dyn SnapshotRepository>,
+    pub tags: Arc<dyn TagRepository>,
+    pub api_tokens: Arc<dyn ApiTokenRepository>,
+    pub health: Arc<dyn HealthRepository>,
+}
+
+impl Repositories {
+    pub fn sqlite(pool: sqlx::SqlitePool) -> Self {
+        Self {
+            investments: Arc::new(SqliteInvestmentRepository::new(pool.clone())),
+            movements: Arc::new(SqliteMovementRepository::new(pool.clone())),
+            investment_prices: Arc::new(SqliteInvestmentPriceRepository::new(pool.clone())),
+            action_types: Arc::new(SqliteActionTypeRepository::new(pool.clone())),
+            settings: Arc::new(SqliteSettingsRepository::new(pool.clone())),
+            portfolios: Arc::new(SqlitePortfolioRepository::new(pool.clone())),
+            cash_movements: Arc::new(SqliteCashMovementRepository::new(pool.clone())),
+            quote_fetch_log: Arc::new(SqliteQuoteFetchLogRepository::new(pool.clone())),
+            audit_log: Arc::new(SqliteAuditLogRepository::new(pool.clone())),
+            fx_rates: Arc::new(SqliteFxRateRepository::new(pool.clone())),
+            data_import: Arc::new(SqliteDataImportRepository::new(pool.clone())),
+            import_profiles: Arc::new(SqliteImportProfileRepository::new(pool.clone())),
+            developments: Arc::new(SqliteDevelopmentRepository::new(pool.clone())),
+            price_alerts: Arc::new(SqlitePriceAlertRepository::new(pool.clone())),
+            savings_plans: Arc::new(SqliteSavingsPlanRepository::new(pool.clone())),
+            corporate_events: Arc::new(SqliteCorporateEventRepository::new(pool.clone())),
+            intraday_prices: Arc::new(SqliteIntradayPriceRepository::new(pool.clone())),
+            provider_configs: Arc::new(SqliteProviderConfigRepository::new(pool.clone())),
+            snapshots: Arc::new(SqliteSnapshotRepository::new(pool.clone())),
+            tags: Arc::new(SqliteTagRepository::new(pool.clone())),
+            api_tokens: Arc::new(SqliteApiTokenRepository::new(pool.clone())),
+            health: Arc::new(SqliteHealthRepository::new(pool)),
+        }
+    }
+
+    pub fn postgres(pool: sqlx::PgPool) -> Self {
+        Self {
+            investments: Arc::new(PostgresInvestmentRepository::new(pool.clone())),
+            movements: Arc::new(PostgresMovementRepository::new(pool.clone())),
+            investment_prices: Arc::new(PostgresInvestmentPriceRepository::new(pool.clone())),
+            action_types: Arc::new(PostgresActionTypeRepository::new(pool.clone())),
+            settings: Arc::new(PostgresSettingsRepository::new(pool.clone())),
+            portfolios: Arc::new(PostgresPortfolioRepository::new(pool.clone())),
+            cash_movements: Arc::new(PostgresCashMovementRepository::new(pool.clone())),
+            quote_fetch_log: Arc::new(PostgresQuoteFetchLogRepository::new(pool.clone())),
+            audit_log: Arc::new(PostgresAuditLogRepository::new(pool.clone())),
+            fx_rates: Arc::new(PostgresFxRateRepository::new(pool.clone())),
+            data_import: Arc::new(PostgresDataImportRepository::new(pool.clone())),
+            import_profiles: Arc::new(PostgresImportProfileRepository::new(pool.clone())),
+            developments: Arc::new(PostgresDevelopmentRepository::new(pool.clone())),
+            price_alerts: Arc::new(PostgresPriceAlertRepository::new(pool.clone())),
+            savings_plans: Arc::new(PostgresSavingsPlanRepository::new(pool.clone())),
+            corporate_events: Arc::new(PostgresCorporateEventRepository::new(pool.clone())),
+            intraday_prices: Arc::new(PostgresIntradayPriceRepository::new(pool.clone())),
+            provider_configs: Arc::new(PostgresProviderConfigRepository::new(pool.clone())),
+            snapshots: Arc::new(PostgresSnapshotRepository::new(pool.clone())),
+            tags: Arc::new(PostgresTagRepository::new(pool.clone())),
+            api_tokens: Arc::new(PostgresApiTokenRepository::new(pool.clone())),
+            health: Arc::new(PostgresHealthRepository::new(pool)),
+        }
+    }
+
+    /// Repositories sharing one empty in-memory database
+    #[cfg(feature = "test-util")]
+    pub fn in_memory() -> Self {
+        let store = MemoryStore::new();
+        Self {
+            investments: Arc::new(InMemoryInvestmentRepository::new(store.clone())),
+            movements: Arc::new(InMemoryMovementRepository::new(store.clone())),
+            investment_prices: Arc::new(InMemoryInvestmentPriceRepository::new(store.clone())),
+            action_types: Arc::new(InMemoryActionTypeRepository::new(store.clone())),
+            settings: Arc::new(InMemorySettingsRepository::new(store.clone())),
+            portfolios: Arc::new(InMemoryPortfolioRepository::new(store.clone())),
+            cash_movements: Arc::new(InMemoryCashMovementRepository::new(store.clone())),
+            quote_fetch_log: Arc::new(InMemoryQuoteFetchLogRepository::new(store.clone())),
+            audit_log: Arc::new(InMemoryAuditLogRepository::new(store.clone())),
+            fx_rates: Arc::new(InMemoryFxRateRepository::new(store.clone())),
+            data_import: Arc::new(InMemoryDataImportRepository::new(store.clone())),
+            import_profiles: Arc::new(InMemoryImportProfileRepository::new(store.clone())),
+            developments: Arc::new(InMemoryDevelopmentRepository::new(store.clone())),
+            price_alerts: Arc::new(InMemoryPriceAlertRepository::new(store.clone())),
+            savings_plans: Arc::new(InMemorySavingsPlanRepository::new(store.clone())),
+            corporate_events: Arc::new(InMemoryCorporateEventRepository::new(store.clone())),
+            intraday_prices: Arc::new(InMemoryIntradayPriceRepository::new(store.clone())),
+            provider_configs: Arc::new(InMemoryProviderConfigRepository::new(store.clone())),
+            snapshots: Arc::new(InMemorySnapshotRepository::new(store.clone())),
+            tags: Arc::new(InMemoryTagRepository::new(store.clone())),
+            api_tokens: Arc::new(InMemoryApiTokenRepository::new(store)),
+            health: Arc::new(InMemoryHealthRepository::new()),
+        }
+    }
+
+    /// Report writes to movements, prices, investments, action types and imports
+    pub fn with_change_listener(self, listener: Arc<dyn ChangeListener>) -> Self {
+        Self {
+            investments: Arc::new(NotifyingInvestmentRepository::new(
+                self.investments,
+                listener.clone(),
+            )),
+            movements: Arc::new(NotifyingMovementRepository::new(
+                self.movements,
+                listener.clone(),
+            )),
+            investment_prices: Arc::new(NotifyingInvestmentPriceRepository::new(
+                self.investment_prices,
+                listener.clone(),
+            )),
+            action_types: Arc::new(NotifyingActionTypeRepository::new(
+                self.action_types,
+                listener.clone(),
+            )),
+            data_import: Arc::new(NotifyingDataImportRepository::new(
+                self.data_import,
+                listener.clone(),
+            )),
+            settings: Arc::new(NotifyingSettingsRepository::new(self.settings, listener)),
+            ..self
+        }
+    }
+}
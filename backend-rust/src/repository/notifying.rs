@@ -0,0 +1,404 @@
+use crate::error::Result;
+use crate::models::{
+    ActionType, DataExport, ImportMode, ImportSummary, Investment, InvestmentDependents,
+    InvestmentFilter, InvestmentMerge, InvestmentPrice, Movement, MovementListOptions, Settings,
+};
+use crate::repository::traits::{
+    ActionTypeRepository, DataImportRepository, InvestmentPriceRepository, InvestmentRepository,
+    MovementRepository, SettingsRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+/// Data that changed; `None` fields mean the change may affect every investment or date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DataChange {
+    pub investment_id: Option<i64>,
+    /// Earliest date whose developments may have changed
+    pub from_date: Option<NaiveDate>,
+}
+
+impl DataChange {
+    /// A change that may affect everything
+    pub const ALL: DataChange = DataChange {
+        investment_id: None,
+        from_date: None,
+    };
+
+    fn movement(movement: &Movement) -> Self {
+        Self {
+            investment_id: movement.investment_id,
+            from_date: movement.date,
+        }
+    }
+
+    fn price(price: &InvestmentPrice) -> Self {
+        Self {
+            investment_id: price.investment_id,
+            from_date: price.date,
+        }
+    }
+}
+
+/// Receives the changes made through the notifying repositories
+///
+/// The `Notifying*` repositories delegate to the wrapped repository and report every
+/// successful write, so caches of calculated data can be invalidated.
+pub trait ChangeListener: Send + Sync {
+    fn data_changed(&self, change: DataChange);
+}
+
+pub struct NotifyingMovementRepository {
+    inner: Arc<dyn MovementRepository>,
+    listener: Arc<dyn ChangeListener>,
+}
+
+impl NotifyingMovementRepository {
+    pub fn new(inner: Arc<dyn MovementRepository>, listener: Arc<dyn ChangeListener>) -> Self {
+        Self { inner, listener }
+    }
+}
+
+#[async_trait]
+impl MovementRepository for NotifyingMovementRepository {
+    async fn find_all(&self) -> Result<Vec<Movement>> {
+        self.inner.find_all().await
+    }
+
+    async fn find_by_portfolio(&self, portfolio_id: i64) -> Result<Vec<Movement>> {
+        self.inner.find_by_portfolio(portfolio_id).await
+    }
+
+    async fn find_page(&self, options: &MovementListOptions) -> Result<(Vec<Movement>, i64)> {
+        self.inner.find_page(options).await
+    }
+
+    async fn find_filtered(
+        &self,
+        investment_id: Option<i64>,
+        action_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Movement>> {
+        self.inner
+            .find_filtered(investment_id, action_id, start_date, end_date)
+            .await
+    }
+
+    async fn find_by_investment_and_range(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Movement>> {
+        self.inner
+            .find_by_investment_and_range(investment_id, portfolio_id, start_date, end_date)
+            .await
+    }
+
+    async fn find_quantity_totals_before(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        before: NaiveDate,
+    ) -> Result<Vec<Movement>> {
+        self.inner
+            .find_quantity_totals_before(investment_id, portfolio_id, before)
+            .await
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Movement>> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_by_external_id(
+        &self,
+        portfolio_id: Option<i64>,
+        external_id: &str,
+    ) -> Result<Option<Movement>> {
+        self.inner
+            .find_by_external_id(portfolio_id, external_id)
+            .await
+    }
+
+    async fn create(&self, movement: &Movement) -> Result<i64> {
+        let id = self.inner.create(movement).await?;
+        self.listener.data_changed(DataChange::movement(movement));
+        Ok(id)
+    }
+
+    async fn create_many(&self, movements: &[Movement]) -> Result<Vec<i64>> {
+        let ids = self.inner.create_many(movements).await?;
+        for movement in movements {
+            self.listener.data_changed(DataChange::movement(movement));
+        }
+        Ok(ids)
+    }
+
+    async fn update(&self, id: i64, movement: &Movement) -> Result<u64> {
+        // The previous investment and date are unknown
+        let updated = self.inner.update(id, movement).await?;
+        if updated > 0 {
+            self.listener.data_changed(DataChange::ALL);
+        }
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let deleted = self.inner.delete(id).await?;
+        if deleted > 0 {
+            self.listener.data_changed(DataChange::ALL);
+        }
+        Ok(deleted)
+    }
+}
+
+pub struct NotifyingInvestmentPriceRepository {
+    inner: Arc<dyn InvestmentPriceRepository>,
+    listener: Arc<dyn ChangeListener>,
+}
+
+impl NotifyingInvestmentPriceRepository {
+    pub fn new(
+        inner: Arc<dyn InvestmentPriceRepository>,
+        listener: Arc<dyn ChangeListener>,
+    ) -> Self {
+        Self { inner, listener }
+    }
+}
+
+#[async_trait]
+impl InvestmentPriceRepository for NotifyingInvestmentPriceRepository {
+    async fn find_all(
+        &self,
+        investment_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<InvestmentPrice>> {
+        self.inner
+            .find_all(investment_id, start_date, end_date)
+            .await
+    }
+
+    async fn create(&self, price: &InvestmentPrice) -> Result<()> {
+        self.inner.create(price).await?;
+        self.listener.data_changed(DataChange::price(price));
+        Ok(())
+    }
+
+    async fn upsert(&self, price: &InvestmentPrice) -> Result<()> {
+        self.inner.upsert(price).await?;
+        self.listener.data_changed(DataChange::price(price));
+        Ok(())
+    }
+
+    async fn upsert_many(&self, prices: &[InvestmentPrice]) -> Result<()> {
+        self.inner.upsert_many(prices).await?;
+        for price in prices {
+            self.listener.data_changed(DataChange::price(price));
+        }
+        Ok(())
+    }
+
+    async fn find_latest_date(
+        &self,
+        investment_id: i64,
+        source: &str,
+    ) -> Result<Option<NaiveDate>> {
+        self.inner.find_latest_date(investment_id, source).await
+    }
+
+    async fn delete_orphaned(&self) -> Result<u64> {
+        let deleted = self.inner.delete_orphaned().await?;
+        if deleted > 0 {
+            self.listener.data_changed(DataChange::ALL);
+        }
+        Ok(deleted)
+    }
+
+    async fn delete_duplicates(&self) -> Result<u64> {
+        let deleted = self.inner.delete_duplicates().await?;
+        if deleted > 0 {
+            self.listener.data_changed(DataChange::ALL);
+        }
+        Ok(deleted)
+    }
+}
+
+/// Deleting an investment with `cascade` removes its movements and prices, and its quote
+/// provider chain decides which of its prices are preferred
+pub struct NotifyingInvestmentRepository {
+    inner: Arc<dyn InvestmentRepository>,
+    listener: Arc<dyn ChangeListener>,
+}
+
+impl NotifyingInvestmentRepository {
+    pub fn new(inner: Arc<dyn InvestmentRepository>, listener: Arc<dyn ChangeListener>) -> Self {
+        Self { inner, listener }
+    }
+}
+
+#[async_trait]
+impl InvestmentRepository for NotifyingInvestmentRepository {
+    async fn find_all(&self) -> Result<Vec<Investment>> {
+        self.inner.find_all().await
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Investment>> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn search(
+        &self,
+        text: &str,
+        filter: &InvestmentFilter,
+        limit: i64,
+    ) -> Result<Vec<Investment>> {
+        self.inner.search(text, filter, limit).await
+    }
+
+    async fn create(&self, investment: &Investment) -> Result<i64> {
+        self.inner.create(investment).await
+    }
+
+    async fn update(&self, id: i64, investment: &Investment) -> Result<u64> {
+        let previous = self.inner.find_by_id(id).await?;
+        let updated = self.inner.update(id, investment).await?;
+        if previous.is_some_and(|p| p.quote_provider != investment.quote_provider) {
+            self.listener.data_changed(DataChange {
+                investment_id: Some(id),
+                from_date: None,
+            });
+        }
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let deleted = self.inner.delete(id).await?;
+        if deleted > 0 {
+            self.listener.data_changed(DataChange {
+                investment_id: Some(id),
+                from_date: None,
+            });
+        }
+        Ok(deleted)
+    }
+
+    async fn delete_with_dependents(&self, id: i64, cascade: bool) -> Result<InvestmentDependents> {
+        let deleted = self.inner.delete_with_dependents(id, cascade).await?;
+        self.listener.data_changed(DataChange {
+            investment_id: Some(id),
+            from_date: None,
+        });
+        Ok(deleted)
+    }
+
+    async fn merge_into(&self, id: i64, target_id: i64) -> Result<InvestmentMerge> {
+        let merge = self.inner.merge_into(id, target_id).await?;
+        for investment_id in [id, target_id] {
+            self.listener.data_changed(DataChange {
+                investment_id: Some(investment_id),
+                from_date: None,
+            });
+        }
+        Ok(merge)
+    }
+}
+
+/// Changing the effect of an action type changes how its movements are calculated
+pub struct NotifyingActionTypeRepository {
+    inner: Arc<dyn ActionTypeRepository>,
+    listener: Arc<dyn ChangeListener>,
+}
+
+impl NotifyingActionTypeRepository {
+    pub fn new(inner: Arc<dyn ActionTypeRepository>, listener: Arc<dyn ChangeListener>) -> Self {
+        Self { inner, listener }
+    }
+}
+
+#[async_trait]
+impl ActionTypeRepository for NotifyingActionTypeRepository {
+    async fn find_all(&self) -> Result<Vec<ActionType>> {
+        self.inner.find_all().await
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<ActionType>> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn create(&self, action_type: &ActionType) -> Result<i64> {
+        self.inner.create(action_type).await
+    }
+
+    async fn update(&self, id: i64, action_type: &ActionType) -> Result<u64> {
+        let updated = self.inner.update(id, action_type).await?;
+        if updated > 0 {
+            self.listener.data_changed(DataChange::ALL);
+        }
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        self.inner.delete(id).await
+    }
+}
+
+pub struct NotifyingDataImportRepository {
+    inner: Arc<dyn DataImportRepository>,
+    listener: Arc<dyn ChangeListener>,
+}
+
+impl NotifyingDataImportRepository {
+    pub fn new(inner: Arc<dyn DataImportRepository>, listener: Arc<dyn ChangeListener>) -> Self {
+        Self { inner, listener }
+    }
+}
+
+#[async_trait]
+impl DataImportRepository for NotifyingDataImportRepository {
+    async fn import(&self, data: &DataExport, mode: ImportMode) -> Result<ImportSummary> {
+        let summary = self.inner.import(data, mode).await?;
+        self.listener.data_changed(DataChange::ALL);
+        Ok(summary)
+    }
+}
+
+/// The price source priority decides which prices developments are valued with, and the
+/// display precision how responses are rounded
+pub struct NotifyingSettingsRepository {
+    inner: Arc<dyn SettingsRepository>,
+    listener: Arc<dyn ChangeListener>,
+}
+
+impl NotifyingSettingsRepository {
+    pub fn new(inner: Arc<dyn SettingsRepository>, listener: Arc<dyn ChangeListener>) -> Self {
+        Self { inner, listener }
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for NotifyingSettingsRepository {
+    async fn get(&self) -> Result<Option<Settings>> {
+        self.inner.get().await
+    }
+
+    async fn update(&self, settings: &Settings) -> Result<()> {
+        let previous = self.inner.get().await?;
+        self.inner.update(settings).await?;
+        // The display precision changes the responses, so their entity tags must change too
+        let changed = previous.is_some_and(|p| {
+            p.price_source_priority != settings.price_source_priority
+                || p.value_decimals != settings.value_decimals
+                || p.price_decimals != settings.price_decimals
+                || p.quantity_decimals != settings.quantity_decimals
+                || p.rounding_mode != settings.rounding_mode
+        });
+        if changed {
+            self.listener.data_changed(DataChange::ALL);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::error::{AppError, Result};
+use crate::models::ActionType;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresActionTypeRepository {
+    pool: PgPool,
+}
+
+impl PostgresActionTypeRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::ActionTypeRepository for PostgresActionTypeRepository {
+    async fn find_all(&self) -> Result<Vec<ActionType>> {
+        let action_types = sqlx::query_as::<_, ActionType>(
+            r#"SELECT "ID", "Name", "Effect" FROM "ActionType" ORDER BY "ID""#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(action_types)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<ActionType>> {
+        let action_type = sqlx::query_as::<_, ActionType>(
+            r#"SELECT "ID", "Name", "Effect" FROM "ActionType" WHERE "ID" = $1"#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(action_type)
+    }
+
+    async fn create(&self, action_type: &ActionType) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "ActionType" ("Name", "Effect") VALUES ($1, $2) RETURNING "ID""#,
+        )
+        .bind(&action_type.name)
+        .bind(&action_type.effect)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn update(&self, id: i64, action_type: &ActionType) -> Result<u64> {
+        let result =
+            sqlx::query(r#"UPDATE "ActionType" SET "Name" = $1, "Effect" = $2 WHERE "ID" = $3"#)
+                .bind(&action_type.name)
+                .bind(&action_type.effect)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        let (movements,): (i64,) =
+            sqlx::query_as(r#"SELECT COUNT(*) FROM "Movement" WHERE "ActionID" = $1"#)
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+        if movements > 0 {
+            return Err(AppError::Conflict(format!(
+                "Action type {} is used by {} movement(s)",
+                id, movements
+            )));
+        }
+
+        let result = sqlx::query(r#"DELETE FROM "ActionType" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+}
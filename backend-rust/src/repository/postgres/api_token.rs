@@ -0,0 +1,66 @@
+use crate::error::Result;
+use crate::models::ApiToken;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresApiTokenRepository {
+    pool: PgPool,
+}
+
+impl PostgresApiTokenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::ApiTokenRepository for PostgresApiTokenRepository {
+    async fn find_all(&self) -> Result<Vec<ApiToken>> {
+        let tokens = sqlx::query_as::<_, ApiToken>(r#"SELECT * FROM "ApiToken" ORDER BY "ID""#)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tokens)
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>> {
+        let token =
+            sqlx::query_as::<_, ApiToken>(r#"SELECT * FROM "ApiToken" WHERE "TokenHash" = $1"#)
+                .bind(token_hash)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(token)
+    }
+
+    async fn count(&self) -> Result<i64> {
+        let (count,): (i64,) = sqlx::query_as(r#"SELECT COUNT(*) FROM "ApiToken""#)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    async fn create(&self, token: &ApiToken) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "ApiToken" ("Name", "Scope", "TokenHash", "CreatedAt")
+               VALUES ($1, $2, $3, $4) RETURNING "ID""#,
+        )
+        .bind(&token.name)
+        .bind(&token.scope)
+        .bind(&token.token_hash)
+        .bind(token.created_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "ApiToken" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::error::Result;
+use crate::models::AuditLogEntry;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresAuditLogRepository {
+    pool: PgPool,
+}
+
+impl PostgresAuditLogRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::AuditLogRepository for PostgresAuditLogRepository {
+    async fn find_recent(&self, limit: i64) -> Result<Vec<AuditLogEntry>> {
+        let entries = sqlx::query_as::<_, AuditLogEntry>(
+            r#"SELECT * FROM "AuditLog" ORDER BY "CreatedAt" DESC, "ID" DESC LIMIT $1"#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+}
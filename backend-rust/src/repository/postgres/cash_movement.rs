@@ -0,0 +1,66 @@
+use crate::error::Result;
+use crate::models::CashMovement;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+const SELECT_CASH_MOVEMENT: &str = r#"SELECT "ID", "Date", "ActionID", "Amount", "PortfolioID", "Description" FROM "CashMovement""#;
+
+#[derive(Clone)]
+pub struct PostgresCashMovementRepository {
+    pool: PgPool,
+}
+
+impl PostgresCashMovementRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::CashMovementRepository for PostgresCashMovementRepository {
+    async fn find_all(&self, portfolio_id: Option<i64>) -> Result<Vec<CashMovement>> {
+        let query = format!(
+            r#"{} WHERE ($1::BIGINT IS NULL OR "PortfolioID" = $1) ORDER BY "Date", "ID""#,
+            SELECT_CASH_MOVEMENT
+        );
+        let movements = sqlx::query_as::<_, CashMovement>(&query)
+            .bind(portfolio_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(movements)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<CashMovement>> {
+        let query = format!(r#"{} WHERE "ID" = $1"#, SELECT_CASH_MOVEMENT);
+        let movement = sqlx::query_as::<_, CashMovement>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(movement)
+    }
+
+    async fn create(&self, movement: &CashMovement) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "CashMovement" ("Date", "ActionID", "Amount", "PortfolioID", "Description") VALUES ($1, $2, $3, $4, $5) RETURNING "ID""#,
+        )
+        .bind(movement.date)
+        .bind(movement.action_id)
+        .bind(movement.amount)
+        .bind(movement.portfolio_id)
+        .bind(&movement.description)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "CashMovement" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,115 @@
+use crate::error::Result;
+use crate::models::{DividendEvent, SplitEvent};
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresCorporateEventRepository {
+    pool: PgPool,
+}
+
+impl PostgresCorporateEventRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::CorporateEventRepository for PostgresCorporateEventRepository {
+    async fn find_dividends(
+        &self,
+        investment_id: Option<i64>,
+        status: Option<&str>,
+    ) -> Result<Vec<DividendEvent>> {
+        let dividends = sqlx::query_as::<_, DividendEvent>(
+            r#"SELECT * FROM "DividendEvent" WHERE ($1::BIGINT IS NULL OR "InvestmentID" = $1) AND ($2::VARCHAR IS NULL OR "Status" = $2) ORDER BY "Date", "ID""#,
+        )
+        .bind(investment_id)
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(dividends)
+    }
+
+    async fn find_dividend(&self, id: i64) -> Result<Option<DividendEvent>> {
+        let dividend =
+            sqlx::query_as::<_, DividendEvent>(r#"SELECT * FROM "DividendEvent" WHERE "ID" = $1"#)
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(dividend)
+    }
+
+    async fn add_dividends(&self, dividends: &[DividendEvent]) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        let mut inserted = 0;
+        for dividend in dividends {
+            let result = sqlx::query(
+                r#"INSERT INTO "DividendEvent" ("InvestmentID", "Date", "Amount", "Currency", "Source", "Status")
+                   VALUES ($1, $2, $3, $4, $5, $6)
+                   ON CONFLICT("InvestmentID", "Date", "Source") DO NOTHING"#,
+            )
+            .bind(dividend.investment_id)
+            .bind(dividend.date)
+            .bind(dividend.amount)
+            .bind(&dividend.currency)
+            .bind(&dividend.source)
+            .bind(&dividend.status)
+            .execute(&mut *tx)
+            .await?;
+            inserted += result.rows_affected() as usize;
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn set_dividend_status(
+        &self,
+        id: i64,
+        status: &str,
+        movement_id: Option<i64>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"UPDATE "DividendEvent" SET "Status" = $1, "MovementID" = $2 WHERE "ID" = $3"#,
+        )
+        .bind(status)
+        .bind(movement_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_splits(&self, investment_id: Option<i64>) -> Result<Vec<SplitEvent>> {
+        let splits = sqlx::query_as::<_, SplitEvent>(
+            r#"SELECT * FROM "SplitEvent" WHERE ($1::BIGINT IS NULL OR "InvestmentID" = $1) ORDER BY "Date", "ID""#,
+        )
+        .bind(investment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(splits)
+    }
+
+    async fn add_splits(&self, splits: &[SplitEvent]) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        let mut inserted = 0;
+        for split in splits {
+            let result = sqlx::query(
+                r#"INSERT INTO "SplitEvent" ("InvestmentID", "Date", "Numerator", "Denominator", "Source")
+                   VALUES ($1, $2, $3, $4, $5)
+                   ON CONFLICT("InvestmentID", "Date", "Source") DO NOTHING"#,
+            )
+            .bind(split.investment_id)
+            .bind(split.date)
+            .bind(split.numerator)
+            .bind(split.denominator)
+            .bind(&split.source)
+            .execute(&mut *tx)
+            .await?;
+            inserted += result.rows_affected() as usize;
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+}
@@ -0,0 +1,220 @@
+use crate::error::Result;
+use crate::models::{DataExport, ImportMode, ImportSummary};
+use crate::repository::postgres::investment_price::UPSERT_PRICE;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct PostgresDataImportRepository {
+    pool: PgPool,
+}
+
+impl PostgresDataImportRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::DataImportRepository for PostgresDataImportRepository {
+    async fn import(&self, data: &DataExport, mode: ImportMode) -> Result<ImportSummary> {
+        let mut tx = self.pool.begin().await?;
+        let mut summary = ImportSummary::default();
+        let replace = mode == ImportMode::Replace;
+
+        if replace {
+            for table in [
+                "InvestmentPrice",
+                "CashMovement",
+                "Movement",
+                "Investment",
+                "Portfolio",
+            ] {
+                sqlx::query(&format!(r#"DELETE FROM "{}""#, table))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
+            if let Some(settings) = &data.settings {
+                // IDs are kept on replace, so the benchmark investment ID stays valid
+                sqlx::query(
+                    r#"UPDATE "Settings" SET "BaseCurrency" = $1, "CostBasisMethod" = $2,
+                       "BenchmarkInvestmentID" = $3, "BenchmarkTicker" = $4,
+                       "PriceSourcePriority" = $5, "ValueDecimals" = $6, "PriceDecimals" = $7,
+                       "QuantityDecimals" = $8, "RoundingMode" = $9,
+                       "RejectNegativeHoldings" = $10"#,
+                )
+                .bind(&settings.base_currency)
+                .bind(&settings.cost_basis_method)
+                .bind(settings.benchmark_investment_id)
+                .bind(&settings.benchmark_ticker)
+                .bind(&settings.price_source_priority)
+                .bind(settings.value_decimals)
+                .bind(settings.price_decimals)
+                .bind(settings.quantity_decimals)
+                .bind(&settings.rounding_mode)
+                .bind(settings.reject_negative_holdings)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        for action_type in &data.action_types {
+            sqlx::query(
+                r#"INSERT INTO "ActionType" ("ID", "Name", "Effect") VALUES ($1, $2, $3) ON CONFLICT ("ID") DO NOTHING"#,
+            )
+            .bind(action_type.id)
+            .bind(&action_type.name)
+            .bind(&action_type.effect)
+            .execute(&mut *tx)
+            .await?;
+        }
+        // Custom action types keep their IDs, so move the sequence past them
+        sqlx::query(
+            r#"SELECT setval(pg_get_serial_sequence('"ActionType"', 'ID'), MAX("ID")) FROM "ActionType""#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Map IDs of the export to IDs in this database. Without an explicit ID
+        // the next value of the serial sequence is used.
+        let mut portfolio_ids: HashMap<i64, i64> = HashMap::new();
+        for portfolio in &data.portfolios {
+            if !replace {
+                let existing: Option<(i64,)> = sqlx::query_as(
+                    r#"SELECT "ID" FROM "Portfolio" WHERE "Name" = $1 ORDER BY "ID" LIMIT 1"#,
+                )
+                .bind(&portfolio.name)
+                .fetch_optional(&mut *tx)
+                .await?;
+                if let Some((id,)) = existing {
+                    portfolio_ids.insert(portfolio.id, id);
+                    continue;
+                }
+            }
+
+            let (id,): (i64,) = sqlx::query_as(
+                r#"INSERT INTO "Portfolio" ("ID", "Name", "Description")
+                   VALUES (COALESCE($1, nextval(pg_get_serial_sequence('"Portfolio"', 'ID'))), $2, $3)
+                   RETURNING "ID""#,
+            )
+            .bind(replace.then_some(portfolio.id))
+            .bind(&portfolio.name)
+            .bind(&portfolio.description)
+            .fetch_one(&mut *tx)
+            .await?;
+            portfolio_ids.insert(portfolio.id, id);
+            summary.portfolios += 1;
+        }
+
+        let mut investment_ids: HashMap<i64, i64> = HashMap::new();
+        for investment in &data.investments {
+            if let (false, Some(isin)) = (replace, &investment.isin) {
+                let existing: Option<(i64,)> = sqlx::query_as(
+                    r#"SELECT "ID" FROM "Investment" WHERE "ISIN" = $1 ORDER BY "ID" LIMIT 1"#,
+                )
+                .bind(isin)
+                .fetch_optional(&mut *tx)
+                .await?;
+                if let Some((id,)) = existing {
+                    investment_ids.insert(investment.id, id);
+                    continue;
+                }
+            }
+
+            let (id,): (i64,) = sqlx::query_as(
+                r#"INSERT INTO "Investment" ("ID", "Name", "ISIN", "ShortName", "TickerSymbol", "QuoteProvider", "Currency", "AssetClass", "Watchlist", "PartialExemption", "QuoteFetchEnabled", "QuoteFetchIntervalDays", "Archived")
+                   VALUES (COALESCE($1, nextval(pg_get_serial_sequence('"Investment"', 'ID'))), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                   RETURNING "ID""#,
+            )
+            .bind(replace.then_some(investment.id))
+            .bind(&investment.name)
+            .bind(&investment.isin)
+            .bind(&investment.shortname)
+            .bind(&investment.ticker_symbol)
+            .bind(&investment.quote_provider)
+            .bind(&investment.currency)
+            .bind(&investment.asset_class)
+            .bind(investment.watchlist)
+            .bind(investment.partial_exemption)
+            .bind(investment.quote_fetch_enabled)
+            .bind(investment.quote_fetch_interval_days)
+            .bind(investment.archived)
+            .fetch_one(&mut *tx)
+            .await?;
+            investment_ids.insert(investment.id, id);
+            summary.investments += 1;
+        }
+
+        for movement in &data.movements {
+            sqlx::query(
+                r#"INSERT INTO "Movement" ("ID", "Date", "ActionID", "InvestmentID", "Quantity", "Amount", "Fee", "Tax", "PortfolioID", "ExternalID")
+                   VALUES (COALESCE($1, nextval(pg_get_serial_sequence('"Movement"', 'ID'))), $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+            )
+            .bind(replace.then_some(movement.id))
+            .bind(movement.date)
+            .bind(movement.action_id)
+            .bind(movement.investment_id.and_then(|id| investment_ids.get(&id).copied()))
+            .bind(movement.quantity)
+            .bind(movement.amount)
+            .bind(movement.fee)
+            .bind(movement.tax)
+            .bind(movement.portfolio_id.and_then(|id| portfolio_ids.get(&id).copied()))
+            .bind(&movement.external_id)
+            .execute(&mut *tx)
+            .await?;
+            summary.movements += 1;
+        }
+
+        for cash in &data.cash_movements {
+            sqlx::query(
+                r#"INSERT INTO "CashMovement" ("ID", "Date", "ActionID", "Amount", "PortfolioID", "Description")
+                   VALUES (COALESCE($1, nextval(pg_get_serial_sequence('"CashMovement"', 'ID'))), $2, $3, $4, $5, $6)"#,
+            )
+            .bind(replace.then_some(cash.id))
+            .bind(cash.date)
+            .bind(cash.action_id)
+            .bind(cash.amount)
+            .bind(cash.portfolio_id.and_then(|id| portfolio_ids.get(&id).copied()))
+            .bind(&cash.description)
+            .execute(&mut *tx)
+            .await?;
+            summary.cash_movements += 1;
+        }
+
+        for price in &data.prices {
+            sqlx::query(UPSERT_PRICE)
+                .bind(price.date)
+                .bind(
+                    price
+                        .investment_id
+                        .and_then(|id| investment_ids.get(&id).copied()),
+                )
+                .bind(price.price)
+                .bind(price.stored_source())
+                .bind(&price.currency)
+                .bind(price.original_price)
+                .execute(&mut *tx)
+                .await?;
+            summary.prices += 1;
+        }
+
+        if replace {
+            // Explicit IDs bypass the serial sequences, so move them past the
+            // imported rows
+            for table in ["Portfolio", "Investment", "Movement", "CashMovement"] {
+                sqlx::query(&format!(
+                    r#"SELECT setval(pg_get_serial_sequence('"{0}"', 'ID'), COALESCE(MAX("ID"), 1), MAX("ID") IS NOT NULL) FROM "{0}""#,
+                    table
+                ))
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(summary)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::error::Result;
+use crate::models::Development;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresDevelopmentRepository {
+    pool: PgPool,
+}
+
+impl PostgresDevelopmentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::DevelopmentRepository for PostgresDevelopmentRepository {
+    async fn find_all(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>> {
+        let developments = sqlx::query_as::<_, Development>(
+            r#"SELECT * FROM "Development" WHERE ($1::DATE IS NULL OR "Date" >= $1) AND ($2::DATE IS NULL OR "Date" <= $2) ORDER BY "InvestmentID", "Date""#,
+        )
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(developments)
+    }
+
+    async fn replace(
+        &self,
+        investment_id: Option<i64>,
+        from_date: Option<NaiveDate>,
+        developments: &[Development],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"DELETE FROM "Development" WHERE ($1::BIGINT IS NULL OR "InvestmentID" = $1) AND ($2::DATE IS NULL OR "Date" >= $2)"#,
+        )
+        .bind(investment_id)
+        .bind(from_date)
+        .execute(&mut *tx)
+        .await?;
+
+        for development in developments {
+            sqlx::query(
+                r#"INSERT INTO "Development" ("InvestmentID", "Date", "Price", "Quantity", "Value", "Source") VALUES ($1, $2, $3, $4, $5, $6)"#,
+            )
+            .bind(development.investment)
+            .bind(development.date)
+            .bind(development.price)
+            .bind(development.quantity)
+            .bind(development.value)
+            .bind(&development.source)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
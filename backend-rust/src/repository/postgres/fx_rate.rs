@@ -0,0 +1,72 @@
+use crate::error::Result;
+use crate::models::FxRate;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+const SELECT_FX_RATE: &str = r#"SELECT "Date", "FromCurrency", "ToCurrency", CAST("Rate" AS DOUBLE PRECISION) AS "Rate" FROM "FxRate""#;
+
+#[derive(Clone)]
+pub struct PostgresFxRateRepository {
+    pool: PgPool,
+}
+
+impl PostgresFxRateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::FxRateRepository for PostgresFxRateRepository {
+    async fn find(&self, date: NaiveDate, from: &str, to: &str) -> Result<Option<FxRate>> {
+        let query = format!(
+            r#"{} WHERE "Date" = $1 AND "FromCurrency" = $2 AND "ToCurrency" = $3"#,
+            SELECT_FX_RATE
+        );
+        let rate = sqlx::query_as::<_, FxRate>(&query)
+            .bind(date)
+            .bind(from)
+            .bind(to)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(rate)
+    }
+
+    async fn find_all(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<FxRate>> {
+        let query = format!(
+            r#"{} WHERE ($1::TEXT IS NULL OR "FromCurrency" = $1) AND ($2::TEXT IS NULL OR "ToCurrency" = $2) AND ($3::DATE IS NULL OR "Date" >= $3) AND ($4::DATE IS NULL OR "Date" <= $4) ORDER BY "Date" DESC, "FromCurrency", "ToCurrency""#,
+            SELECT_FX_RATE
+        );
+        let rates = sqlx::query_as::<_, FxRate>(&query)
+            .bind(from)
+            .bind(to)
+            .bind(start_date)
+            .bind(end_date)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rates)
+    }
+
+    async fn upsert(&self, rate: &FxRate) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO "FxRate" ("Date", "FromCurrency", "ToCurrency", "Rate") VALUES ($1, $2, $3, $4)
+             ON CONFLICT ("Date", "FromCurrency", "ToCurrency") DO UPDATE SET "Rate" = EXCLUDED."Rate""#,
+        )
+        .bind(rate.date)
+        .bind(&rate.from_currency)
+        .bind(&rate.to_currency)
+        .bind(rate.rate)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
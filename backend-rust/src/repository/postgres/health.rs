@@ -0,0 +1,32 @@
+use crate::db::postgres;
+use crate::error::Result;
+use crate::models::MigrationStatus;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresHealthRepository {
+    pool: PgPool,
+}
+
+impl PostgresHealthRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::HealthRepository for PostgresHealthRepository {
+    async fn check(&self) -> Result<MigrationStatus> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        let (applied_version,): (Option<i64>,) =
+            sqlx::query_as("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(MigrationStatus {
+            applied_version,
+            latest_version: postgres::latest_version(),
+        })
+    }
+}
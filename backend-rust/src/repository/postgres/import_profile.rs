@@ -0,0 +1,92 @@
+use crate::error::Result;
+use crate::models::ImportProfile;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresImportProfileRepository {
+    pool: PgPool,
+}
+
+impl PostgresImportProfileRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::ImportProfileRepository for PostgresImportProfileRepository {
+    async fn find_all(&self) -> Result<Vec<ImportProfile>> {
+        let profiles =
+            sqlx::query_as::<_, ImportProfile>(r#"SELECT * FROM "ImportProfile" ORDER BY "ID""#)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(profiles)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<ImportProfile>> {
+        let profile =
+            sqlx::query_as::<_, ImportProfile>(r#"SELECT * FROM "ImportProfile" WHERE "ID" = $1"#)
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(profile)
+    }
+
+    async fn create(&self, profile: &ImportProfile) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "ImportProfile" ("Name", "Delimiter", "DateFormat", "DecimalSeparator", "Currency", "DateColumn", "ActionColumn", "IsinColumn", "ProductColumn", "QuantityColumn", "AmountColumn", "FeeColumn", "ActionKeywords") VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) RETURNING "ID""#,
+        )
+        .bind(&profile.name)
+        .bind(&profile.delimiter)
+        .bind(&profile.date_format)
+        .bind(&profile.decimal_separator)
+        .bind(&profile.currency)
+        .bind(&profile.date_column)
+        .bind(&profile.action_column)
+        .bind(&profile.isin_column)
+        .bind(&profile.product_column)
+        .bind(&profile.quantity_column)
+        .bind(&profile.amount_column)
+        .bind(&profile.fee_column)
+        .bind(&profile.action_keywords)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn update(&self, id: i64, profile: &ImportProfile) -> Result<u64> {
+        let result = sqlx::query(
+            r#"UPDATE "ImportProfile" SET "Name" = $1, "Delimiter" = $2, "DateFormat" = $3, "DecimalSeparator" = $4, "Currency" = $5, "DateColumn" = $6, "ActionColumn" = $7, "IsinColumn" = $8, "ProductColumn" = $9, "QuantityColumn" = $10, "AmountColumn" = $11, "FeeColumn" = $12, "ActionKeywords" = $13 WHERE "ID" = $14"#,
+        )
+        .bind(&profile.name)
+        .bind(&profile.delimiter)
+        .bind(&profile.date_format)
+        .bind(&profile.decimal_separator)
+        .bind(&profile.currency)
+        .bind(&profile.date_column)
+        .bind(&profile.action_column)
+        .bind(&profile.isin_column)
+        .bind(&profile.product_column)
+        .bind(&profile.quantity_column)
+        .bind(&profile.amount_column)
+        .bind(&profile.fee_column)
+        .bind(&profile.action_keywords)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "ImportProfile" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,53 @@
+use crate::error::Result;
+use crate::models::IntradayPrice;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresIntradayPriceRepository {
+    pool: PgPool,
+}
+
+impl PostgresIntradayPriceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::IntradayPriceRepository for PostgresIntradayPriceRepository {
+    async fn find(&self, investment_id: i64) -> Result<Option<IntradayPrice>> {
+        let price = sqlx::query_as::<_, IntradayPrice>(
+            r#"SELECT * FROM "IntradayPrice" WHERE "InvestmentID" = $1"#,
+        )
+        .bind(investment_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(price)
+    }
+
+    async fn upsert(&self, price: &IntradayPrice) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO "IntradayPrice" ("InvestmentID", "Price", "Currency", "OriginalPrice", "QuotedAt", "FetchedAt", "Source")
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT("InvestmentID") DO UPDATE SET
+                  "Price" = EXCLUDED."Price",
+                  "Currency" = EXCLUDED."Currency",
+                  "OriginalPrice" = EXCLUDED."OriginalPrice",
+                  "QuotedAt" = EXCLUDED."QuotedAt",
+                  "FetchedAt" = EXCLUDED."FetchedAt",
+                  "Source" = EXCLUDED."Source""#,
+        )
+        .bind(price.investment_id)
+        .bind(price.price)
+        .bind(&price.currency)
+        .bind(price.original_price)
+        .bind(price.quoted_at)
+        .bind(price.fetched_at)
+        .bind(&price.source)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::{
+    Investment, InvestmentDependents, InvestmentFilter, InvestmentMerge, INVESTMENT_MERGED,
+};
+use crate::repository::traits;
+use crate::repository::{investment_in_use, merge_details, SearchPatterns};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+const SELECT_INVESTMENT: &str = r#"SELECT "ID", "Name", "ISIN", "ShortName", "TickerSymbol", "QuoteProvider", "Currency", "AssetClass", "Watchlist", "PartialExemption", "QuoteFetchEnabled", "QuoteFetchIntervalDays", "Archived" FROM "Investment""#;
+
+#[derive(Clone)]
+pub struct PostgresInvestmentRepository {
+    pool: PgPool,
+}
+
+impl PostgresInvestmentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::InvestmentRepository for PostgresInvestmentRepository {
+    async fn find_all(&self) -> Result<Vec<Investment>> {
+        let query = format!(r#"{} ORDER BY "ID""#, SELECT_INVESTMENT);
+        let investments = sqlx::query_as::<_, Investment>(&query)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(investments)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Investment>> {
+        let query = format!(r#"{} WHERE "ID" = $1"#, SELECT_INVESTMENT);
+        let investment = sqlx::query_as::<_, Investment>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(investment)
+    }
+
+    async fn search(
+        &self,
+        text: &str,
+        filter: &InvestmentFilter,
+        limit: i64,
+    ) -> Result<Vec<Investment>> {
+        let patterns = SearchPatterns::new(text);
+        // `\` is the default escape character of LIKE in PostgreSQL
+        let query = format!(
+            r#"{} WHERE (LOWER("Name") LIKE $3 OR LOWER("ShortName") LIKE $3
+                       OR LOWER("ISIN") LIKE $3 OR LOWER("TickerSymbol") LIKE $3)
+                  AND ($4::BOOLEAN IS NULL OR "Watchlist" = $4) AND ($6 OR NOT "Archived")
+                ORDER BY
+                  CASE
+                    WHEN $1 IN (LOWER("Name"), LOWER("ShortName"), LOWER("ISIN"), LOWER("TickerSymbol")) THEN 0
+                    WHEN LOWER("Name") LIKE $2 OR LOWER("ShortName") LIKE $2
+                         OR LOWER("ISIN") LIKE $2 OR LOWER("TickerSymbol") LIKE $2 THEN 1
+                    ELSE 2
+                  END,
+                  LOWER(COALESCE("Name", '')), "ID"
+                LIMIT $5"#,
+            SELECT_INVESTMENT
+        );
+        let investments = sqlx::query_as::<_, Investment>(&query)
+            .bind(&patterns.exact)
+            .bind(&patterns.prefix)
+            .bind(&patterns.contains)
+            .bind(filter.watchlist)
+            .bind(limit)
+            .bind(filter.include_archived)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(investments)
+    }
+
+    async fn create(&self, investment: &Investment) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "Investment" ("Name", "ISIN", "ShortName", "TickerSymbol", "QuoteProvider", "Currency", "AssetClass", "Watchlist", "PartialExemption", "QuoteFetchEnabled", "QuoteFetchIntervalDays", "Archived") VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) RETURNING "ID""#,
+        )
+        .bind(&investment.name)
+        .bind(&investment.isin)
+        .bind(&investment.shortname)
+        .bind(&investment.ticker_symbol)
+        .bind(&investment.quote_provider)
+        .bind(&investment.currency)
+        .bind(&investment.asset_class)
+        .bind(investment.watchlist)
+        .bind(investment.partial_exemption)
+        .bind(investment.quote_fetch_enabled)
+        .bind(investment.quote_fetch_interval_days)
+        .bind(investment.archived)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn update(&self, id: i64, investment: &Investment) -> Result<u64> {
+        let result = sqlx::query(
+            r#"UPDATE "Investment" SET "Name" = $1, "ISIN" = $2, "ShortName" = $3, "TickerSymbol" = $4, "QuoteProvider" = $5, "Currency" = $6, "AssetClass" = $7, "Watchlist" = $8, "PartialExemption" = $9, "QuoteFetchEnabled" = $10, "QuoteFetchIntervalDays" = $11, "Archived" = $12 WHERE "ID" = $13"#,
+        )
+        .bind(&investment.name)
+        .bind(&investment.isin)
+        .bind(&investment.shortname)
+        .bind(&investment.ticker_symbol)
+        .bind(&investment.quote_provider)
+        .bind(&investment.currency)
+        .bind(&investment.asset_class)
+        .bind(investment.watchlist)
+        .bind(investment.partial_exemption)
+        .bind(investment.quote_fetch_enabled)
+        .bind(investment.quote_fetch_interval_days)
+        .bind(investment.archived)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "Investment" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_with_dependents(&self, id: i64, cascade: bool) -> Result<InvestmentDependents> {
+        let mut tx = self.pool.begin().await?;
+
+        let (movements,): (i64,) =
+            sqlx::query_as(r#"SELECT COUNT(*) FROM "Movement" WHERE "InvestmentID" = $1"#)
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+        let (prices,): (i64,) =
+            sqlx::query_as(r#"SELECT COUNT(*) FROM "InvestmentPrice" WHERE "InvestmentID" = $1"#)
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+        let dependents = InvestmentDependents { movements, prices };
+
+        if !cascade && !dependents.is_empty() {
+            return Err(investment_in_use(id, dependents));
+        }
+
+        sqlx::query(r#"DELETE FROM "InvestmentPrice" WHERE "InvestmentID" = $1"#)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(r#"DELETE FROM "Movement" WHERE "InvestmentID" = $1"#)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let deleted = sqlx::query(r#"DELETE FROM "Investment" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        ensure_found(deleted.rows_affected(), "Investment", id)?;
+
+        tx.commit().await?;
+        Ok(dependents)
+    }
+
+    async fn merge_into(&self, id: i64, target_id: i64) -> Result<InvestmentMerge> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut names = Vec::with_capacity(2);
+        for investment in [id, target_id] {
+            // Locks both investments against concurrent merges and deletions
+            let name: Option<(Option<String>,)> =
+                sqlx::query_as(r#"SELECT "Name" FROM "Investment" WHERE "ID" = $1 FOR UPDATE"#)
+                    .bind(investment)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            let (name,) = name.ok_or_else(|| AppError::not_found("Investment", investment))?;
+            names.push(name);
+        }
+
+        let duplicate_prices = sqlx::query(
+            r#"DELETE FROM "InvestmentPrice" AS source
+               WHERE source."InvestmentID" = $1 AND EXISTS (
+                   SELECT 1 FROM "InvestmentPrice" AS target
+                   WHERE target."InvestmentID" = $2
+                     AND target."Date" = source."Date"
+                     AND target."Source" = source."Source"
+               )"#,
+        )
+        .bind(id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        let prices = sqlx::query(
+            r#"UPDATE "InvestmentPrice" SET "InvestmentID" = $1 WHERE "InvestmentID" = $2"#,
+        )
+        .bind(target_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        let movements =
+            sqlx::query(r#"UPDATE "Movement" SET "InvestmentID" = $1 WHERE "InvestmentID" = $2"#)
+                .bind(target_id)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        for table in ["PriceAlert", "SavingsPlan"] {
+            sqlx::query(&format!(
+                r#"UPDATE "{}" SET "InvestmentID" = $1 WHERE "InvestmentID" = $2"#,
+                table
+            ))
+            .bind(target_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query(
+            r#"INSERT INTO "InvestmentTag" ("InvestmentID", "TagID")
+               SELECT $1, "TagID" FROM "InvestmentTag" WHERE "InvestmentID" = $2
+               ON CONFLICT DO NOTHING"#,
+        )
+        .bind(target_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(r#"DELETE FROM "Investment" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        let merge = InvestmentMerge {
+            source_id: id,
+            target_id,
+            movements: movements as i64,
+            prices: prices as i64,
+            duplicate_prices: duplicate_prices as i64,
+        };
+        sqlx::query(
+            r#"INSERT INTO "AuditLog" ("CreatedAt", "Action", "Details") VALUES ($1, $2, $3)"#,
+        )
+        .bind(chrono::Utc::now())
+        .bind(INVESTMENT_MERGED)
+        .bind(merge_details(
+            &merge,
+            names[0].as_deref(),
+            names[1].as_deref(),
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(merge)
+    }
+}
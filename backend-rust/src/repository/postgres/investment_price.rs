@@ -0,0 +1,160 @@
+use crate::error::Result;
+use crate::models::InvestmentPrice;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+/// Shared with the data import, so that prices are merged on the same key
+pub(super) const UPSERT_PRICE: &str = r#"INSERT INTO "InvestmentPrice" ("Date", "InvestmentID", "Price", "Source", "Currency", "OriginalPrice")
+    VALUES ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT ("Date", "InvestmentID", "Source") DO UPDATE SET
+        "Price" = EXCLUDED."Price",
+        "Currency" = EXCLUDED."Currency",
+        "OriginalPrice" = EXCLUDED."OriginalPrice""#;
+
+#[derive(Clone)]
+pub struct PostgresInvestmentPriceRepository {
+    pool: PgPool,
+}
+
+impl PostgresInvestmentPriceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::InvestmentPriceRepository for PostgresInvestmentPriceRepository {
+    async fn find_all(
+        &self,
+        investment_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<InvestmentPrice>> {
+        let mut query = String::from(
+            r#"SELECT "Date", "InvestmentID", "Price", "Source", "Currency", "OriginalPrice" FROM "InvestmentPrice" WHERE 1=1"#,
+        );
+
+        // Postgres uses numbered placeholders, so track the next index
+        let mut param = 0;
+        let mut next_param = || {
+            param += 1;
+            param
+        };
+
+        if investment_id.is_some() {
+            query.push_str(&format!(r#" AND "InvestmentID" = ${}"#, next_param()));
+        }
+        if start_date.is_some() {
+            query.push_str(&format!(r#" AND "Date" >= ${}"#, next_param()));
+        }
+        if end_date.is_some() {
+            query.push_str(&format!(r#" AND "Date" <= ${}"#, next_param()));
+        }
+        query.push_str(r#" ORDER BY "Date" DESC"#);
+
+        let mut q = sqlx::query_as::<_, InvestmentPrice>(&query);
+
+        if let Some(inv_id) = investment_id {
+            q = q.bind(inv_id);
+        }
+        if let Some(start) = start_date {
+            q = q.bind(start);
+        }
+        if let Some(end) = end_date {
+            q = q.bind(end);
+        }
+
+        let prices = q.fetch_all(&self.pool).await?;
+        Ok(prices)
+    }
+
+    async fn create(&self, price: &InvestmentPrice) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO "InvestmentPrice" ("Date", "InvestmentID", "Price", "Source", "Currency", "OriginalPrice") VALUES ($1, $2, $3, $4, $5, $6)"#,
+        )
+        .bind(price.date)
+        .bind(price.investment_id)
+        .bind(price.price)
+        .bind(price.stored_source())
+        .bind(&price.currency)
+        .bind(price.original_price)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn upsert(&self, price: &InvestmentPrice) -> Result<()> {
+        sqlx::query(UPSERT_PRICE)
+            .bind(price.date)
+            .bind(price.investment_id)
+            .bind(price.price)
+            .bind(price.stored_source())
+            .bind(&price.currency)
+            .bind(price.original_price)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_many(&self, prices: &[InvestmentPrice]) -> Result<()> {
+        // The statement is prepared once and reused for every price
+        let mut tx = self.pool.begin().await?;
+        for price in prices {
+            sqlx::query(UPSERT_PRICE)
+                .bind(price.date)
+                .bind(price.investment_id)
+                .bind(price.price)
+                .bind(price.stored_source())
+                .bind(&price.currency)
+                .bind(price.original_price)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn find_latest_date(
+        &self,
+        investment_id: i64,
+        source: &str,
+    ) -> Result<Option<NaiveDate>> {
+        let date = sqlx::query_scalar(
+            r#"SELECT MAX("Date") FROM "InvestmentPrice" WHERE "InvestmentID" = $1 AND "Source" = $2"#,
+        )
+        .bind(investment_id)
+        .bind(source)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(date)
+    }
+
+    async fn delete_orphaned(&self) -> Result<u64> {
+        let result = sqlx::query(
+            r#"DELETE FROM "InvestmentPrice"
+               WHERE "InvestmentID" IS NULL
+                  OR "InvestmentID" NOT IN (SELECT "ID" FROM "Investment")"#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_duplicates(&self) -> Result<u64> {
+        // The unique key does not apply to rows with a NULL column, GROUP BY does
+        let result = sqlx::query(
+            r#"DELETE FROM "InvestmentPrice"
+               WHERE "id" NOT IN (
+                   SELECT MAX("id") FROM "InvestmentPrice"
+                   GROUP BY "Date", "InvestmentID", COALESCE("Source", 'manual')
+               )"#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
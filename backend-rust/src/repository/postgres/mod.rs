@@ -0,0 +1,45 @@
+pub mod action_type;
+pub mod api_token;
+pub mod audit_log;
+pub mod cash_movement;
+pub mod corporate_event;
+pub mod data_import;
+pub mod development;
+pub mod fx_rate;
+pub mod health;
+pub mod import_profile;
+pub mod intraday_price;
+pub mod investment;
+pub mod investment_price;
+pub mod movement;
+pub mod portfolio;
+pub mod price_alert;
+pub mod provider_config;
+pub mod quote_fetch_log;
+pub mod savings_plan;
+pub mod settings;
+pub mod snapshot;
+pub mod tag;
+
+pub use action_type::PostgresActionTypeRepository;
+pub use api_token::PostgresApiTokenRepository;
+pub use audit_log::PostgresAuditLogRepository;
+pub use cash_movement::PostgresCashMovementRepository;
+pub use corporate_event::PostgresCorporateEventRepository;
+pub use data_import::PostgresDataImportRepository;
+pub use development::PostgresDevelopmentRepository;
+pub use fx_rate::PostgresFxRateRepository;
+pub use health::PostgresHealthRepository;
+pub use import_profile::PostgresImportProfileRepository;
+pub use intraday_price::PostgresIntradayPriceRepository;
+pub use investment::PostgresInvestmentRepository;
+pub use investment_price::PostgresInvestmentPriceRepository;
+pub use movement::PostgresMovementRepository;
+pub use portfolio::PostgresPortfolioRepository;
+pub use price_alert::PostgresPriceAlertRepository;
+pub use provider_config::PostgresProviderConfigRepository;
+pub use quote_fetch_log::PostgresQuoteFetchLogRepository;
+pub use savings_plan::PostgresSavingsPlanRepository;
+pub use settings::PostgresSettingsRepository;
+pub use snapshot::PostgresSnapshotRepository;
+pub use tag::PostgresTagRepository;
@@ -0,0 +1,238 @@
+use crate::error::Result;
+use crate::models::{Movement, MovementListOptions};
+use crate::repository::traits;
+use crate::services::cost_basis::SPLIT_ACTION_ID;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+const SELECT_MOVEMENT: &str = r#"SELECT "ID", "Date", "ActionID", "InvestmentID", "Quantity", "Amount", "Fee", "Tax", "PortfolioID", "ExternalID" FROM "Movement""#;
+
+/// Optional filters of `MovementListOptions`, bound as parameters 1 to 5
+const FILTER_CLAUSE: &str = r#"($1::BIGINT IS NULL OR "PortfolioID" = $1) AND ($2::BIGINT IS NULL OR "InvestmentID" = $2) AND ($3::BIGINT IS NULL OR "ActionID" = $3) AND ($4::DATE IS NULL OR "Date" >= $4) AND ($5::DATE IS NULL OR "Date" <= $5)"#;
+
+#[derive(Clone)]
+pub struct PostgresMovementRepository {
+    pool: PgPool,
+}
+
+impl PostgresMovementRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::MovementRepository for PostgresMovementRepository {
+    async fn find_all(&self) -> Result<Vec<Movement>> {
+        let query = format!(r#"{} ORDER BY "ID""#, SELECT_MOVEMENT);
+        let movements = sqlx::query_as::<_, Movement>(&query)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(movements)
+    }
+
+    async fn find_by_portfolio(&self, portfolio_id: i64) -> Result<Vec<Movement>> {
+        let query = format!(
+            r#"{} WHERE "PortfolioID" = $1 ORDER BY "ID""#,
+            SELECT_MOVEMENT
+        );
+        let movements = sqlx::query_as::<_, Movement>(&query)
+            .bind(portfolio_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(movements)
+    }
+
+    async fn find_page(&self, options: &MovementListOptions) -> Result<(Vec<Movement>, i64)> {
+        let total: (i64,) = sqlx::query_as(&format!(
+            r#"SELECT COUNT(*) FROM "Movement" WHERE {}"#,
+            FILTER_CLAUSE
+        ))
+        .bind(options.portfolio_id)
+        .bind(options.investment_id)
+        .bind(options.action_id)
+        .bind(options.start_date)
+        .bind(options.end_date)
+        .fetch_one(&self.pool)
+        .await?;
+
+        // Sort column and direction come from enums, so they are safe to interpolate
+        let query = format!(
+            r#"{} WHERE {} ORDER BY "{}" {}, "ID" {} LIMIT $6 OFFSET $7"#,
+            SELECT_MOVEMENT,
+            FILTER_CLAUSE,
+            options.sort_by.column(),
+            options.order.as_sql(),
+            options.order.as_sql()
+        );
+        // LIMIT NULL returns all rows
+        let movements = sqlx::query_as::<_, Movement>(&query)
+            .bind(options.portfolio_id)
+            .bind(options.investment_id)
+            .bind(options.action_id)
+            .bind(options.start_date)
+            .bind(options.end_date)
+            .bind(options.limit)
+            .bind(options.offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((movements, total.0))
+    }
+
+    async fn find_by_investment_and_range(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Movement>> {
+        let query = format!(
+            r#"{} WHERE {} ORDER BY "Date", "ID""#,
+            SELECT_MOVEMENT, FILTER_CLAUSE
+        );
+        let movements = sqlx::query_as::<_, Movement>(&query)
+            .bind(portfolio_id)
+            .bind(investment_id)
+            .bind(None::<i64>)
+            .bind(start_date)
+            .bind(end_date)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(movements)
+    }
+
+    async fn find_quantity_totals_before(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        before: NaiveDate,
+    ) -> Result<Vec<Movement>> {
+        // The splits of the investment up to a movement number the period it belongs to
+        let movements = sqlx::query_as::<_, Movement>(
+            r#"SELECT 0::BIGINT AS "ID", MIN(m."Date") AS "Date", m."ActionID", m."InvestmentID", SUM(m."Quantity") AS "Quantity",
+                      NULL::NUMERIC AS "Amount", NULL::NUMERIC AS "Fee", NULL::NUMERIC AS "Tax", $2::BIGINT AS "PortfolioID", NULL::TEXT AS "ExternalID"
+               FROM "Movement" m
+               WHERE m."Date" < $3 AND m."ActionID" <> $4 AND m."InvestmentID" IS NOT NULL AND m."Quantity" IS NOT NULL
+                 AND ($1::BIGINT IS NULL OR m."InvestmentID" = $1) AND ($2::BIGINT IS NULL OR m."PortfolioID" = $2)
+               GROUP BY m."InvestmentID", m."ActionID", m."Quantity" < 0,
+                 (SELECT COUNT(*) FROM "Movement" s WHERE s."ActionID" = $4 AND s."InvestmentID" = m."InvestmentID"
+                    AND s."Date" <= m."Date" AND ($2::BIGINT IS NULL OR s."PortfolioID" = $2))
+               UNION ALL
+               SELECT "ID"::BIGINT, "Date", "ActionID", "InvestmentID", "Quantity",
+                      NULL::NUMERIC, NULL::NUMERIC, NULL::NUMERIC, "PortfolioID", NULL::TEXT
+               FROM "Movement"
+               WHERE "Date" < $3 AND "ActionID" = $4
+                 AND ($1::BIGINT IS NULL OR "InvestmentID" = $1) AND ($2::BIGINT IS NULL OR "PortfolioID" = $2)
+               ORDER BY "Date""#,
+        )
+        .bind(investment_id)
+        .bind(portfolio_id)
+        .bind(before)
+        .bind(SPLIT_ACTION_ID)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(movements)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Movement>> {
+        let query = format!(r#"{} WHERE "ID" = $1"#, SELECT_MOVEMENT);
+        let movement = sqlx::query_as::<_, Movement>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(movement)
+    }
+
+    async fn find_by_external_id(
+        &self,
+        portfolio_id: Option<i64>,
+        external_id: &str,
+    ) -> Result<Option<Movement>> {
+        let query = format!(
+            r#"{} WHERE COALESCE("PortfolioID", 0) = COALESCE($1, 0) AND "ExternalID" = $2"#,
+            SELECT_MOVEMENT
+        );
+        let movement = sqlx::query_as::<_, Movement>(&query)
+            .bind(portfolio_id)
+            .bind(external_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(movement)
+    }
+
+    async fn create(&self, movement: &Movement) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "Movement" ("Date", "ActionID", "InvestmentID", "Quantity", "Amount", "Fee", "Tax", "PortfolioID", "ExternalID") VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING "ID""#,
+        )
+        .bind(movement.date)
+        .bind(movement.action_id)
+        .bind(movement.investment_id)
+        .bind(movement.quantity)
+        .bind(movement.amount)
+        .bind(movement.fee)
+        .bind(movement.tax)
+        .bind(movement.portfolio_id)
+        .bind(&movement.external_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn create_many(&self, movements: &[Movement]) -> Result<Vec<i64>> {
+        let mut tx = self.pool.begin().await?;
+        let mut ids = Vec::with_capacity(movements.len());
+
+        for movement in movements {
+            let id: (i64,) = sqlx::query_as(
+                r#"INSERT INTO "Movement" ("Date", "ActionID", "InvestmentID", "Quantity", "Amount", "Fee", "Tax", "PortfolioID", "ExternalID") VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING "ID""#,
+            )
+            .bind(movement.date)
+            .bind(movement.action_id)
+            .bind(movement.investment_id)
+            .bind(movement.quantity)
+            .bind(movement.amount)
+            .bind(movement.fee)
+            .bind(movement.tax)
+            .bind(movement.portfolio_id)
+            .bind(&movement.external_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            ids.push(id.0);
+        }
+
+        tx.commit().await?;
+        Ok(ids)
+    }
+
+    async fn update(&self, id: i64, movement: &Movement) -> Result<u64> {
+        let result = sqlx::query(
+            r#"UPDATE "Movement" SET "Date" = $1, "ActionID" = $2, "InvestmentID" = $3, "Quantity" = $4, "Amount" = $5, "Fee" = $6, "Tax" = $7, "PortfolioID" = $8, "ExternalID" = $9 WHERE "ID" = $10"#,
+        )
+        .bind(movement.date)
+        .bind(movement.action_id)
+        .bind(movement.investment_id)
+        .bind(movement.quantity)
+        .bind(movement.amount)
+        .bind(movement.fee)
+        .bind(movement.tax)
+        .bind(movement.portfolio_id)
+        .bind(&movement.external_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "Movement" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
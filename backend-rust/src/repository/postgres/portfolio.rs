@@ -0,0 +1,70 @@
+use crate::error::Result;
+use crate::models::Portfolio;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresPortfolioRepository {
+    pool: PgPool,
+}
+
+impl PostgresPortfolioRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::PortfolioRepository for PostgresPortfolioRepository {
+    async fn find_all(&self) -> Result<Vec<Portfolio>> {
+        let portfolios =
+            sqlx::query_as::<_, Portfolio>(r#"SELECT * FROM "Portfolio" ORDER BY "ID""#)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(portfolios)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Portfolio>> {
+        let portfolio =
+            sqlx::query_as::<_, Portfolio>(r#"SELECT * FROM "Portfolio" WHERE "ID" = $1"#)
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(portfolio)
+    }
+
+    async fn create(&self, portfolio: &Portfolio) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "Portfolio" ("Name", "Description") VALUES ($1, $2) RETURNING "ID""#,
+        )
+        .bind(&portfolio.name)
+        .bind(&portfolio.description)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn update(&self, id: i64, portfolio: &Portfolio) -> Result<u64> {
+        let result = sqlx::query(
+            r#"UPDATE "Portfolio" SET "Name" = $1, "Description" = $2 WHERE "ID" = $3"#,
+        )
+        .bind(&portfolio.name)
+        .bind(&portfolio.description)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "Portfolio" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
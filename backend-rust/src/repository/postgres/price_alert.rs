@@ -0,0 +1,125 @@
+use crate::error::Result;
+use crate::models::{PriceAlert, TriggeredAlert};
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresPriceAlertRepository {
+    pool: PgPool,
+}
+
+impl PostgresPriceAlertRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::PriceAlertRepository for PostgresPriceAlertRepository {
+    async fn find_all(&self, investment_id: Option<i64>) -> Result<Vec<PriceAlert>> {
+        let alerts = sqlx::query_as::<_, PriceAlert>(
+            r#"SELECT * FROM "PriceAlert" WHERE ($1::BIGINT IS NULL OR "InvestmentID" = $1) ORDER BY "ID""#,
+        )
+        .bind(investment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(alerts)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<PriceAlert>> {
+        let alert =
+            sqlx::query_as::<_, PriceAlert>(r#"SELECT * FROM "PriceAlert" WHERE "ID" = $1"#)
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(alert)
+    }
+
+    async fn find_active(&self, investment_id: i64) -> Result<Vec<PriceAlert>> {
+        let alerts = sqlx::query_as::<_, PriceAlert>(
+            r#"SELECT * FROM "PriceAlert" WHERE "InvestmentID" = $1 AND "Active" ORDER BY "ID""#,
+        )
+        .bind(investment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(alerts)
+    }
+
+    async fn create(&self, alert: &PriceAlert) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "PriceAlert" ("InvestmentID", "Threshold", "Direction", "Active") VALUES ($1, $2, $3, $4) RETURNING "ID""#,
+        )
+        .bind(alert.investment_id)
+        .bind(alert.threshold)
+        .bind(&alert.direction)
+        .bind(alert.active)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn update(&self, id: i64, alert: &PriceAlert) -> Result<u64> {
+        let result = sqlx::query(
+            r#"UPDATE "PriceAlert" SET "InvestmentID" = $1, "Threshold" = $2, "Direction" = $3, "Active" = $4 WHERE "ID" = $5"#,
+        )
+        .bind(alert.investment_id)
+        .bind(alert.threshold)
+        .bind(&alert.direction)
+        .bind(alert.active)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "PriceAlert" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn record_trigger(&self, triggered: &TriggeredAlert) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "TriggeredAlert" ("AlertID", "InvestmentID", "TriggeredAt", "PriceDate", "Price", "Threshold", "Direction") VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING "ID""#,
+        )
+        .bind(triggered.alert_id)
+        .bind(triggered.investment_id)
+        .bind(triggered.triggered_at)
+        .bind(triggered.price_date)
+        .bind(triggered.price)
+        .bind(triggered.threshold)
+        .bind(&triggered.direction)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query(r#"UPDATE "PriceAlert" SET "Active" = FALSE WHERE "ID" = $1"#)
+            .bind(triggered.alert_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(id.0)
+    }
+
+    async fn find_triggered(
+        &self,
+        investment_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<TriggeredAlert>> {
+        let triggered = sqlx::query_as::<_, TriggeredAlert>(
+            r#"SELECT * FROM "TriggeredAlert" WHERE ($1::BIGINT IS NULL OR "InvestmentID" = $1) ORDER BY "TriggeredAt" DESC, "ID" DESC LIMIT $2"#,
+        )
+        .bind(investment_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(triggered)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::error::Result;
+use crate::models::ProviderConfig;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresProviderConfigRepository {
+    pool: PgPool,
+}
+
+impl PostgresProviderConfigRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::ProviderConfigRepository for PostgresProviderConfigRepository {
+    async fn find_all(&self) -> Result<Vec<ProviderConfig>> {
+        let configs = sqlx::query_as::<_, ProviderConfig>(
+            r#"SELECT * FROM "ProviderConfig" ORDER BY "Provider""#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(configs)
+    }
+
+    async fn upsert(&self, config: &ProviderConfig) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO "ProviderConfig" ("Provider", "ApiKey", "UpdatedAt")
+               VALUES ($1, $2, $3)
+               ON CONFLICT("Provider") DO UPDATE SET
+                  "ApiKey" = EXCLUDED."ApiKey",
+                  "UpdatedAt" = EXCLUDED."UpdatedAt""#,
+        )
+        .bind(&config.provider)
+        .bind(&config.api_key)
+        .bind(config.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, provider: &str) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "ProviderConfig" WHERE "Provider" = $1"#)
+            .bind(provider)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,60 @@
+use crate::error::Result;
+use crate::models::QuoteFetchLog;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresQuoteFetchLogRepository {
+    pool: PgPool,
+}
+
+impl PostgresQuoteFetchLogRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::QuoteFetchLogRepository for PostgresQuoteFetchLogRepository {
+    async fn find_recent(
+        &self,
+        investment_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<QuoteFetchLog>> {
+        let entries = sqlx::query_as::<_, QuoteFetchLog>(
+            r#"SELECT * FROM "QuoteFetchLog" WHERE ($1::BIGINT IS NULL OR "InvestmentID" = $1) ORDER BY "FetchedAt" DESC, "ID" DESC LIMIT $2"#,
+        )
+        .bind(investment_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+
+    async fn create(&self, entry: &QuoteFetchLog) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "QuoteFetchLog" ("FetchedAt", "InvestmentID", "Provider", "Success", "Error", "QuotesStored") VALUES ($1, $2, $3, $4, $5, $6) RETURNING "ID""#,
+        )
+        .bind(entry.fetched_at)
+        .bind(entry.investment_id)
+        .bind(&entry.provider)
+        .bind(entry.success)
+        .bind(&entry.error)
+        .bind(entry.quotes_stored)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn last_success(&self) -> Result<Option<DateTime<Utc>>> {
+        let fetched_at: Option<(DateTime<Utc>,)> = sqlx::query_as(
+            r#"SELECT "FetchedAt" FROM "QuoteFetchLog" WHERE "Success" ORDER BY "FetchedAt" DESC LIMIT 1"#,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(fetched_at.map(|(at,)| at))
+    }
+}
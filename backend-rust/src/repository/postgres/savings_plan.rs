@@ -0,0 +1,78 @@
+use crate::error::Result;
+use crate::models::SavingsPlan;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresSavingsPlanRepository {
+    pool: PgPool,
+}
+
+impl PostgresSavingsPlanRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::SavingsPlanRepository for PostgresSavingsPlanRepository {
+    async fn find_all(&self, investment_id: Option<i64>) -> Result<Vec<SavingsPlan>> {
+        let plans = sqlx::query_as::<_, SavingsPlan>(
+            r#"SELECT * FROM "SavingsPlan" WHERE ($1::BIGINT IS NULL OR "InvestmentID" = $1) ORDER BY "ID""#,
+        )
+        .bind(investment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(plans)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<SavingsPlan>> {
+        let plan =
+            sqlx::query_as::<_, SavingsPlan>(r#"SELECT * FROM "SavingsPlan" WHERE "ID" = $1"#)
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(plan)
+    }
+
+    async fn create(&self, plan: &SavingsPlan) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(
+            r#"INSERT INTO "SavingsPlan" ("InvestmentID", "Amount", "Interval", "StartDate", "EndDate") VALUES ($1, $2, $3, $4, $5) RETURNING "ID""#,
+        )
+        .bind(plan.investment_id)
+        .bind(plan.amount)
+        .bind(&plan.interval)
+        .bind(plan.start_date)
+        .bind(plan.end_date)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id.0)
+    }
+
+    async fn update(&self, id: i64, plan: &SavingsPlan) -> Result<u64> {
+        let result = sqlx::query(
+            r#"UPDATE "SavingsPlan" SET "InvestmentID" = $1, "Amount" = $2, "Interval" = $3, "StartDate" = $4, "EndDate" = $5 WHERE "ID" = $6"#,
+        )
+        .bind(plan.investment_id)
+        .bind(plan.amount)
+        .bind(&plan.interval)
+        .bind(plan.start_date)
+        .bind(plan.end_date)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "SavingsPlan" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::error::Result;
+use crate::models::Settings;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresSettingsRepository {
+    pool: PgPool,
+}
+
+impl PostgresSettingsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::SettingsRepository for PostgresSettingsRepository {
+    async fn get(&self) -> Result<Option<Settings>> {
+        let settings = sqlx::query_as::<_, Settings>(
+            r#"SELECT "ID", "BaseCurrency", "CostBasisMethod", "BenchmarkInvestmentID", "BenchmarkTicker",
+               "WebhookUrls", "WebhookSecret", "PriceSourcePriority", "ValueDecimals", "PriceDecimals",
+               "QuantityDecimals", "RoundingMode", "RejectNegativeHoldings"
+               FROM "Settings" ORDER BY "ID" LIMIT 1"#,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(settings)
+    }
+
+    async fn update(&self, settings: &Settings) -> Result<()> {
+        sqlx::query(
+            r#"UPDATE "Settings" SET "BaseCurrency" = $1, "CostBasisMethod" = $2,
+               "BenchmarkInvestmentID" = $3, "BenchmarkTicker" = $4, "WebhookUrls" = $5,
+               "WebhookSecret" = $6, "PriceSourcePriority" = $7, "ValueDecimals" = $8,
+               "PriceDecimals" = $9, "QuantityDecimals" = $10, "RoundingMode" = $11,
+               "RejectNegativeHoldings" = $12 WHERE "ID" = 1"#,
+        )
+        .bind(&settings.base_currency)
+        .bind(&settings.cost_basis_method)
+        .bind(settings.benchmark_investment_id)
+        .bind(&settings.benchmark_ticker)
+        .bind(&settings.webhook_urls)
+        .bind(&settings.webhook_secret)
+        .bind(&settings.price_source_priority)
+        .bind(settings.value_decimals)
+        .bind(settings.price_decimals)
+        .bind(settings.quantity_decimals)
+        .bind(&settings.rounding_mode)
+        .bind(settings.reject_negative_holdings)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use crate::error::Result;
+use crate::models::SnapshotHolding;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresSnapshotRepository {
+    pool: PgPool,
+}
+
+impl PostgresSnapshotRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::SnapshotRepository for PostgresSnapshotRepository {
+    async fn find_all(&self, date: Option<NaiveDate>) -> Result<Vec<SnapshotHolding>> {
+        let holdings = sqlx::query_as::<_, SnapshotHolding>(
+            r#"SELECT * FROM "Snapshot" WHERE ($1::DATE IS NULL OR "Date" = $1) ORDER BY "Date", "InvestmentID""#,
+        )
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(holdings)
+    }
+
+    async fn replace(&self, date: NaiveDate, holdings: &[SnapshotHolding]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(r#"DELETE FROM "Snapshot" WHERE "Date" = $1"#)
+            .bind(date)
+            .execute(&mut *tx)
+            .await?;
+
+        for holding in holdings {
+            sqlx::query(
+                r#"INSERT INTO "Snapshot" ("Date", "InvestmentID", "Price", "Quantity", "Value") VALUES ($1, $2, $3, $4, $5)"#,
+            )
+            .bind(date)
+            .bind(holding.investment)
+            .bind(holding.price)
+            .bind(holding.quantity)
+            .bind(holding.value)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn delete(&self, date: NaiveDate) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "Snapshot" WHERE "Date" = $1"#)
+            .bind(date)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
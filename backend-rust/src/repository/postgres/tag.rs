@@ -0,0 +1,114 @@
+use crate::error::Result;
+use crate::models::Tag;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct PostgresTagRepository {
+    pool: PgPool,
+}
+
+impl PostgresTagRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::TagRepository for PostgresTagRepository {
+    async fn find_all(&self) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as::<_, Tag>(r#"SELECT * FROM "Tag" ORDER BY "Name""#)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tags)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Tag>> {
+        let tag = sqlx::query_as::<_, Tag>(r#"SELECT * FROM "Tag" WHERE "ID" = $1"#)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(tag)
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Tag>> {
+        let tag =
+            sqlx::query_as::<_, Tag>(r#"SELECT * FROM "Tag" WHERE LOWER("Name") = LOWER($1)"#)
+                .bind(name)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(tag)
+    }
+
+    async fn create(&self, tag: &Tag) -> Result<i64> {
+        let id: (i64,) = sqlx::query_as(r#"INSERT INTO "Tag" ("Name") VALUES ($1) RETURNING "ID""#)
+            .bind(&tag.name)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(id.0)
+    }
+
+    async fn update(&self, id: i64, tag: &Tag) -> Result<u64> {
+        let result = sqlx::query(r#"UPDATE "Tag" SET "Name" = $1 WHERE "ID" = $2"#)
+            .bind(&tag.name)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query(r#"DELETE FROM "Tag" WHERE "ID" = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn find_by_investment(&self, investment_id: i64) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"SELECT "Tag".* FROM "Tag" JOIN "InvestmentTag" ON "InvestmentTag"."TagID" = "Tag"."ID"
+               WHERE "InvestmentTag"."InvestmentID" = $1 ORDER BY "Tag"."Name""#,
+        )
+        .bind(investment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tags)
+    }
+
+    async fn set_investment_tags(&self, investment_id: i64, tag_ids: &[i64]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(r#"DELETE FROM "InvestmentTag" WHERE "InvestmentID" = $1"#)
+            .bind(investment_id)
+            .execute(&mut *tx)
+            .await?;
+        for tag_id in tag_ids {
+            sqlx::query(
+                r#"INSERT INTO "InvestmentTag" ("InvestmentID", "TagID") VALUES ($1, $2)
+                   ON CONFLICT DO NOTHING"#,
+            )
+            .bind(investment_id)
+            .bind(tag_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn find_investment_ids(&self, tag_id: i64) -> Result<Vec<i64>> {
+        let ids: Vec<(i64,)> = sqlx::query_as(
+            r#"SELECT "InvestmentID" FROM "InvestmentTag" WHERE "TagID" = $1 ORDER BY "InvestmentID""#,
+        )
+        .bind(tag_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+}
@@ -1,5 +1,6 @@
-use crate::error::Result;
+use crate::error::{AppError, Result};
 use crate::models::ActionType;
+use crate::repository::sqlite::{begin_write, retry_busy};
 use crate::repository::traits;
 use async_trait::async_trait;
 use sqlx::SqlitePool;
@@ -18,7 +19,7 @@ impl SqliteActionTypeRepository {
 #[async_trait]
 impl traits::ActionTypeRepository for SqliteActionTypeRepository {
     async fn find_all(&self) -> Result<Vec<ActionType>> {
-        let action_types = sqlx::query_as::<_, ActionType>("SELECT * FROM ActionType")
+        let action_types = sqlx::query_as::<_, ActionType>("SELECT * FROM ActionType ORDER BY ID")
             .fetch_all(&self.pool)
             .await?;
         Ok(action_types)
@@ -31,4 +32,52 @@ impl traits::ActionTypeRepository for SqliteActionTypeRepository {
             .await?;
         Ok(action_type)
     }
+
+    async fn create(&self, action_type: &ActionType) -> Result<i64> {
+        let result = sqlx::query("INSERT INTO ActionType (Name, Effect) VALUES (?, ?)")
+            .bind(&action_type.name)
+            .bind(&action_type.effect)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn update(&self, id: i64, action_type: &ActionType) -> Result<u64> {
+        let result = sqlx::query("UPDATE ActionType SET Name = ?, Effect = ? WHERE ID = ?")
+            .bind(&action_type.name)
+            .bind(&action_type.effect)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        retry_busy(|| async move {
+            let mut tx = begin_write(&self.pool).await?;
+
+            let (movements,): (i64,) =
+                sqlx::query_as("SELECT COUNT(*) FROM Movement WHERE ActionID = ?")
+                    .bind(id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+            if movements > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Action type {} is used by {} movement(s)",
+                    id, movements
+                )));
+            }
+
+            let result = sqlx::query("DELETE FROM ActionType WHERE ID = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await?;
+            Ok(result.rows_affected())
+        })
+        .await
+    }
 }
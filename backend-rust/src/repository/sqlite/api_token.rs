@@ -0,0 +1,64 @@
+use crate::error::Result;
+use crate::models::ApiToken;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteApiTokenRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteApiTokenRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::ApiTokenRepository for SqliteApiTokenRepository {
+    async fn find_all(&self) -> Result<Vec<ApiToken>> {
+        let tokens = sqlx::query_as::<_, ApiToken>("SELECT * FROM ApiToken ORDER BY ID")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tokens)
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>> {
+        let token = sqlx::query_as::<_, ApiToken>("SELECT * FROM ApiToken WHERE TokenHash = ?")
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(token)
+    }
+
+    async fn count(&self) -> Result<i64> {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM ApiToken")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    async fn create(&self, token: &ApiToken) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO ApiToken (Name, Scope, TokenHash, CreatedAt) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&token.name)
+        .bind(&token.scope)
+        .bind(&token.token_hash)
+        .bind(token.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM ApiToken WHERE ID = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
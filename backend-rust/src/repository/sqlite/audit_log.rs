@@ -0,0 +1,29 @@
+use crate::error::Result;
+use crate::models::AuditLogEntry;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteAuditLogRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteAuditLogRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::AuditLogRepository for SqliteAuditLogRepository {
+    async fn find_recent(&self, limit: i64) -> Result<Vec<AuditLogEntry>> {
+        let entries = sqlx::query_as::<_, AuditLogEntry>(
+            "SELECT * FROM AuditLog ORDER BY CreatedAt DESC, ID DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+}
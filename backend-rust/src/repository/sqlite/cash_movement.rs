@@ -0,0 +1,76 @@
+use crate::error::Result;
+use crate::models::{CashMovement, DecimalColumn};
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+const SELECT_CASH_MOVEMENT: &str = "SELECT ID, Date, ActionID, CAST(Amount AS REAL) as Amount, PortfolioID, Description FROM CashMovement";
+
+#[derive(Clone)]
+pub struct SqliteCashMovementRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteCashMovementRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::CashMovementRepository for SqliteCashMovementRepository {
+    async fn find_all(&self, portfolio_id: Option<i64>) -> Result<Vec<CashMovement>> {
+        let movements = match portfolio_id {
+            Some(portfolio_id) => {
+                let query = format!(
+                    "{} WHERE PortfolioID = ? ORDER BY Date, ID",
+                    SELECT_CASH_MOVEMENT
+                );
+                sqlx::query_as::<_, CashMovement>(&query)
+                    .bind(portfolio_id)
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+            None => {
+                let query = format!("{} ORDER BY Date, ID", SELECT_CASH_MOVEMENT);
+                sqlx::query_as::<_, CashMovement>(&query)
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+        };
+        Ok(movements)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<CashMovement>> {
+        let query = format!("{} WHERE ID = ?", SELECT_CASH_MOVEMENT);
+        let movement = sqlx::query_as::<_, CashMovement>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(movement)
+    }
+
+    async fn create(&self, movement: &CashMovement) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO CashMovement (Date, ActionID, Amount, PortfolioID, Description) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(movement.date)
+        .bind(movement.action_id)
+        .bind(DecimalColumn(Some(movement.amount)))
+        .bind(movement.portfolio_id)
+        .bind(&movement.description)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM CashMovement WHERE ID = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
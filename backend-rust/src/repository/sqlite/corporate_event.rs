@@ -0,0 +1,127 @@
+use crate::error::Result;
+use crate::models::{DecimalColumn, DividendEvent, SplitEvent};
+use crate::repository::sqlite::{begin_write, retry_busy};
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+const SELECT_DIVIDEND: &str = "SELECT ID, InvestmentID, Date, CAST(Amount AS REAL) as Amount, Currency, Source, Status, MovementID FROM DividendEvent";
+
+#[derive(Clone)]
+pub struct SqliteCorporateEventRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteCorporateEventRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::CorporateEventRepository for SqliteCorporateEventRepository {
+    async fn find_dividends(
+        &self,
+        investment_id: Option<i64>,
+        status: Option<&str>,
+    ) -> Result<Vec<DividendEvent>> {
+        let query = format!(
+            "{} WHERE (? IS NULL OR InvestmentID = ?) AND (? IS NULL OR Status = ?) ORDER BY Date, ID",
+            SELECT_DIVIDEND
+        );
+        let dividends = sqlx::query_as::<_, DividendEvent>(&query)
+            .bind(investment_id)
+            .bind(investment_id)
+            .bind(status)
+            .bind(status)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(dividends)
+    }
+
+    async fn find_dividend(&self, id: i64) -> Result<Option<DividendEvent>> {
+        let query = format!("{} WHERE ID = ?", SELECT_DIVIDEND);
+        let dividend = sqlx::query_as::<_, DividendEvent>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(dividend)
+    }
+
+    async fn add_dividends(&self, dividends: &[DividendEvent]) -> Result<usize> {
+        retry_busy(|| async move {
+            let mut tx = begin_write(&self.pool).await?;
+            let mut inserted = 0;
+            for dividend in dividends {
+                let result = sqlx::query(
+                "INSERT INTO DividendEvent (InvestmentID, Date, Amount, Currency, Source, Status)
+                 VALUES (?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(InvestmentID, Date, Source) DO NOTHING",
+            )
+            .bind(dividend.investment_id)
+            .bind(dividend.date)
+            .bind(DecimalColumn(Some(dividend.amount)))
+            .bind(&dividend.currency)
+            .bind(&dividend.source)
+            .bind(&dividend.status)
+            .execute(&mut *tx)
+            .await?;
+                inserted += result.rows_affected() as usize;
+            }
+            tx.commit().await?;
+            Ok(inserted)
+        })
+        .await
+    }
+
+    async fn set_dividend_status(
+        &self,
+        id: i64,
+        status: &str,
+        movement_id: Option<i64>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE DividendEvent SET Status = ?, MovementID = ? WHERE ID = ?")
+            .bind(status)
+            .bind(movement_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn find_splits(&self, investment_id: Option<i64>) -> Result<Vec<SplitEvent>> {
+        let splits = sqlx::query_as::<_, SplitEvent>(
+            "SELECT ID, InvestmentID, Date, CAST(Numerator AS REAL) as Numerator, CAST(Denominator AS REAL) as Denominator, Source FROM SplitEvent WHERE (? IS NULL OR InvestmentID = ?) ORDER BY Date, ID",
+        )
+        .bind(investment_id)
+        .bind(investment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(splits)
+    }
+
+    async fn add_splits(&self, splits: &[SplitEvent]) -> Result<usize> {
+        retry_busy(|| async move {
+            let mut tx = begin_write(&self.pool).await?;
+            let mut inserted = 0;
+            for split in splits {
+                let result = sqlx::query(
+                    "INSERT INTO SplitEvent (InvestmentID, Date, Numerator, Denominator, Source)
+                 VALUES (?, ?, ?, ?, ?)
+                 ON CONFLICT(InvestmentID, Date, Source) DO NOTHING",
+                )
+                .bind(split.investment_id)
+                .bind(split.date)
+                .bind(DecimalColumn(Some(split.numerator)))
+                .bind(DecimalColumn(Some(split.denominator)))
+                .bind(&split.source)
+                .execute(&mut *tx)
+                .await?;
+                inserted += result.rows_affected() as usize;
+            }
+            tx.commit().await?;
+            Ok(inserted)
+        })
+        .await
+    }
+}
@@ -0,0 +1,193 @@
+use crate::error::Result;
+use crate::models::{DataExport, DecimalColumn, ImportMode, ImportSummary};
+use crate::repository::sqlite::investment_price::UPSERT_PRICE;
+use crate::repository::sqlite::{begin_write, retry_busy};
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct SqliteDataImportRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteDataImportRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::DataImportRepository for SqliteDataImportRepository {
+    async fn import(&self, data: &DataExport, mode: ImportMode) -> Result<ImportSummary> {
+        retry_busy(|| async move {
+        let mut tx = begin_write(&self.pool).await?;
+        let mut summary = ImportSummary::default();
+        let replace = mode == ImportMode::Replace;
+
+        if replace {
+            for table in [
+                "InvestmentPrice",
+                "CashMovement",
+                "Movement",
+                "Investment",
+                "Portfolio",
+            ] {
+                sqlx::query(&format!("DELETE FROM {}", table))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
+            if let Some(settings) = &data.settings {
+                // IDs are kept on replace, so the benchmark investment ID stays valid
+                sqlx::query(
+                    "UPDATE Settings SET BaseCurrency = ?, CostBasisMethod = ?, \
+                     BenchmarkInvestmentID = ?, BenchmarkTicker = ?, PriceSourcePriority = ?, \
+                     ValueDecimals = ?, PriceDecimals = ?, QuantityDecimals = ?, RoundingMode = ?, \
+                     RejectNegativeHoldings = ?",
+                )
+                .bind(&settings.base_currency)
+                .bind(&settings.cost_basis_method)
+                .bind(settings.benchmark_investment_id)
+                .bind(&settings.benchmark_ticker)
+                .bind(&settings.price_source_priority)
+                .bind(settings.value_decimals)
+                .bind(settings.price_decimals)
+                .bind(settings.quantity_decimals)
+                .bind(&settings.rounding_mode)
+                .bind(settings.reject_negative_holdings)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        for action_type in &data.action_types {
+            sqlx::query("INSERT OR IGNORE INTO ActionType (ID, Name, Effect) VALUES (?, ?, ?)")
+                .bind(action_type.id)
+                .bind(&action_type.name)
+                .bind(&action_type.effect)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // Map IDs of the export to IDs in this database. Without an explicit ID
+        // SQLite assigns a new one.
+        let mut portfolio_ids: HashMap<i64, i64> = HashMap::new();
+        for portfolio in &data.portfolios {
+            if !replace {
+                let existing: Option<(i64,)> =
+                    sqlx::query_as("SELECT ID FROM Portfolio WHERE Name = ? ORDER BY ID LIMIT 1")
+                        .bind(&portfolio.name)
+                        .fetch_optional(&mut *tx)
+                        .await?;
+                if let Some((id,)) = existing {
+                    portfolio_ids.insert(portfolio.id, id);
+                    continue;
+                }
+            }
+
+            let result =
+                sqlx::query("INSERT INTO Portfolio (ID, Name, Description) VALUES (?, ?, ?)")
+                    .bind(replace.then_some(portfolio.id))
+                    .bind(&portfolio.name)
+                    .bind(&portfolio.description)
+                    .execute(&mut *tx)
+                    .await?;
+            portfolio_ids.insert(portfolio.id, result.last_insert_rowid());
+            summary.portfolios += 1;
+        }
+
+        let mut investment_ids: HashMap<i64, i64> = HashMap::new();
+        for investment in &data.investments {
+            if let (false, Some(isin)) = (replace, &investment.isin) {
+                let existing: Option<(i64,)> =
+                    sqlx::query_as("SELECT ID FROM Investment WHERE ISIN = ? ORDER BY ID LIMIT 1")
+                        .bind(isin)
+                        .fetch_optional(&mut *tx)
+                        .await?;
+                if let Some((id,)) = existing {
+                    investment_ids.insert(investment.id, id);
+                    continue;
+                }
+            }
+
+            let result = sqlx::query(
+                "INSERT INTO Investment (ID, Name, ISIN, ShortName, TickerSymbol, QuoteProvider, Currency, AssetClass, Watchlist, PartialExemption, QuoteFetchEnabled, QuoteFetchIntervalDays, Archived) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(replace.then_some(investment.id))
+            .bind(&investment.name)
+            .bind(&investment.isin)
+            .bind(&investment.shortname)
+            .bind(&investment.ticker_symbol)
+            .bind(&investment.quote_provider)
+            .bind(&investment.currency)
+            .bind(&investment.asset_class)
+            .bind(investment.watchlist)
+            .bind(DecimalColumn(investment.partial_exemption))
+            .bind(investment.quote_fetch_enabled)
+            .bind(investment.quote_fetch_interval_days)
+            .bind(investment.archived)
+            .execute(&mut *tx)
+            .await?;
+            investment_ids.insert(investment.id, result.last_insert_rowid());
+            summary.investments += 1;
+        }
+
+        for movement in &data.movements {
+            sqlx::query(
+                "INSERT INTO Movement (ID, Date, ActionID, InvestmentID, Quantity, Amount, Fee, Tax, PortfolioID, ExternalID) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(replace.then_some(movement.id))
+            .bind(movement.date)
+            .bind(movement.action_id)
+            .bind(movement.investment_id.and_then(|id| investment_ids.get(&id).copied()))
+            .bind(DecimalColumn(movement.quantity))
+            .bind(DecimalColumn(movement.amount))
+            .bind(DecimalColumn(movement.fee))
+            .bind(DecimalColumn(movement.tax))
+            .bind(movement.portfolio_id.and_then(|id| portfolio_ids.get(&id).copied()))
+            .bind(&movement.external_id)
+            .execute(&mut *tx)
+            .await?;
+            summary.movements += 1;
+        }
+
+        for cash in &data.cash_movements {
+            sqlx::query(
+                "INSERT INTO CashMovement (ID, Date, ActionID, Amount, PortfolioID, Description) VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(replace.then_some(cash.id))
+            .bind(cash.date)
+            .bind(cash.action_id)
+            .bind(DecimalColumn(Some(cash.amount)))
+            .bind(cash.portfolio_id.and_then(|id| portfolio_ids.get(&id).copied()))
+            .bind(&cash.description)
+            .execute(&mut *tx)
+            .await?;
+            summary.cash_movements += 1;
+        }
+
+        for price in &data.prices {
+            sqlx::query(UPSERT_PRICE)
+                .bind(price.date)
+                .bind(
+                    price
+                        .investment_id
+                        .and_then(|id| investment_ids.get(&id).copied()),
+                )
+                .bind(DecimalColumn(price.price))
+                .bind(price.stored_source())
+                .bind(&price.currency)
+                .bind(DecimalColumn(price.original_price))
+                .execute(&mut *tx)
+                .await?;
+            summary.prices += 1;
+        }
+
+        tx.commit().await?;
+        Ok(summary)
+        })
+        .await
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::Result;
+use crate::models::{DecimalColumn, Development};
+use crate::repository::sqlite::{begin_write, retry_busy};
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteDevelopmentRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteDevelopmentRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::DevelopmentRepository for SqliteDevelopmentRepository {
+    async fn find_all(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>> {
+        let developments = sqlx::query_as::<_, Development>(
+            "SELECT * FROM Development WHERE (? IS NULL OR Date >= ?) AND (? IS NULL OR Date <= ?) ORDER BY InvestmentID, Date",
+        )
+        .bind(start_date)
+        .bind(start_date)
+        .bind(end_date)
+        .bind(end_date)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(developments)
+    }
+
+    async fn replace(
+        &self,
+        investment_id: Option<i64>,
+        from_date: Option<NaiveDate>,
+        developments: &[Development],
+    ) -> Result<()> {
+        retry_busy(|| async move {
+        let mut tx = begin_write(&self.pool).await?;
+
+        sqlx::query(
+            "DELETE FROM Development WHERE (? IS NULL OR InvestmentID = ?) AND (? IS NULL OR Date >= ?)",
+        )
+        .bind(investment_id)
+        .bind(investment_id)
+        .bind(from_date)
+        .bind(from_date)
+        .execute(&mut *tx)
+        .await?;
+
+        for development in developments {
+            sqlx::query(
+                "INSERT INTO Development (InvestmentID, Date, Price, Quantity, Value, Source) VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(development.investment)
+            .bind(development.date)
+            .bind(DecimalColumn(Some(development.price)))
+            .bind(DecimalColumn(Some(development.quantity)))
+            .bind(DecimalColumn(Some(development.value)))
+            .bind(&development.source)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+        })
+        .await
+    }
+}
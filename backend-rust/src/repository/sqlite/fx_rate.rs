@@ -0,0 +1,79 @@
+use crate::error::Result;
+use crate::models::FxRate;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+
+const SELECT_FX_RATE: &str =
+    "SELECT Date, FromCurrency, ToCurrency, CAST(Rate AS REAL) as Rate FROM FxRate";
+
+#[derive(Clone)]
+pub struct SqliteFxRateRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteFxRateRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::FxRateRepository for SqliteFxRateRepository {
+    async fn find(&self, date: NaiveDate, from: &str, to: &str) -> Result<Option<FxRate>> {
+        let query = format!(
+            "{} WHERE Date = ? AND FromCurrency = ? AND ToCurrency = ?",
+            SELECT_FX_RATE
+        );
+        let rate = sqlx::query_as::<_, FxRate>(&query)
+            .bind(date)
+            .bind(from)
+            .bind(to)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(rate)
+    }
+
+    async fn find_all(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<FxRate>> {
+        let query = format!(
+            "{} WHERE (? IS NULL OR FromCurrency = ?) AND (? IS NULL OR ToCurrency = ?) \
+             AND (? IS NULL OR Date >= ?) AND (? IS NULL OR Date <= ?) \
+             ORDER BY Date DESC, FromCurrency, ToCurrency",
+            SELECT_FX_RATE
+        );
+        let rates = sqlx::query_as::<_, FxRate>(&query)
+            .bind(from)
+            .bind(from)
+            .bind(to)
+            .bind(to)
+            .bind(start_date)
+            .bind(start_date)
+            .bind(end_date)
+            .bind(end_date)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rates)
+    }
+
+    async fn upsert(&self, rate: &FxRate) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO FxRate (Date, FromCurrency, ToCurrency, Rate) VALUES (?, ?, ?, ?)
+             ON CONFLICT(Date, FromCurrency, ToCurrency) DO UPDATE SET Rate = excluded.Rate",
+        )
+        .bind(rate.date)
+        .bind(&rate.from_currency)
+        .bind(&rate.to_currency)
+        .bind(rate.rate)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use crate::db::migrations;
+use crate::error::Result;
+use crate::models::MigrationStatus;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteHealthRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteHealthRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::HealthRepository for SqliteHealthRepository {
+    async fn check(&self) -> Result<MigrationStatus> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        let (applied_version,): (Option<i64>,) =
+            sqlx::query_as("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(MigrationStatus {
+            applied_version,
+            latest_version: migrations::latest_version(),
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use crate::error::Result;
+use crate::models::ImportProfile;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteImportProfileRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteImportProfileRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::ImportProfileRepository for SqliteImportProfileRepository {
+    async fn find_all(&self) -> Result<Vec<ImportProfile>> {
+        let profiles =
+            sqlx::query_as::<_, ImportProfile>("SELECT * FROM ImportProfile ORDER BY ID")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(profiles)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<ImportProfile>> {
+        let profile =
+            sqlx::query_as::<_, ImportProfile>("SELECT * FROM ImportProfile WHERE ID = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(profile)
+    }
+
+    async fn create(&self, profile: &ImportProfile) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO ImportProfile (Name, Delimiter, DateFormat, DecimalSeparator, Currency, DateColumn, ActionColumn, IsinColumn, ProductColumn, QuantityColumn, AmountColumn, FeeColumn, ActionKeywords) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&profile.name)
+        .bind(&profile.delimiter)
+        .bind(&profile.date_format)
+        .bind(&profile.decimal_separator)
+        .bind(&profile.currency)
+        .bind(&profile.date_column)
+        .bind(&profile.action_column)
+        .bind(&profile.isin_column)
+        .bind(&profile.product_column)
+        .bind(&profile.quantity_column)
+        .bind(&profile.amount_column)
+        .bind(&profile.fee_column)
+        .bind(&profile.action_keywords)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn update(&self, id: i64, profile: &ImportProfile) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE ImportProfile SET Name = ?, Delimiter = ?, DateFormat = ?, DecimalSeparator = ?, Currency = ?, DateColumn = ?, ActionColumn = ?, IsinColumn = ?, ProductColumn = ?, QuantityColumn = ?, AmountColumn = ?, FeeColumn = ?, ActionKeywords = ? WHERE ID = ?",
+        )
+        .bind(&profile.name)
+        .bind(&profile.delimiter)
+        .bind(&profile.date_format)
+        .bind(&profile.decimal_separator)
+        .bind(&profile.currency)
+        .bind(&profile.date_column)
+        .bind(&profile.action_column)
+        .bind(&profile.isin_column)
+        .bind(&profile.product_column)
+        .bind(&profile.quantity_column)
+        .bind(&profile.amount_column)
+        .bind(&profile.fee_column)
+        .bind(&profile.action_keywords)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM ImportProfile WHERE ID = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
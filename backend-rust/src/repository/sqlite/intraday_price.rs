@@ -0,0 +1,53 @@
+use crate::error::Result;
+use crate::models::{DecimalColumn, IntradayPrice};
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteIntradayPriceRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteIntradayPriceRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::IntradayPriceRepository for SqliteIntradayPriceRepository {
+    async fn find(&self, investment_id: i64) -> Result<Option<IntradayPrice>> {
+        let price = sqlx::query_as::<_, IntradayPrice>(
+            "SELECT InvestmentID, CAST(Price AS REAL) as Price, Currency, CAST(OriginalPrice AS REAL) as OriginalPrice, QuotedAt, FetchedAt, Source FROM IntradayPrice WHERE InvestmentID = ?",
+        )
+        .bind(investment_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(price)
+    }
+
+    async fn upsert(&self, price: &IntradayPrice) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO IntradayPrice (InvestmentID, Price, Currency, OriginalPrice, QuotedAt, FetchedAt, Source)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(InvestmentID) DO UPDATE SET
+                Price = excluded.Price,
+                Currency = excluded.Currency,
+                OriginalPrice = excluded.OriginalPrice,
+                QuotedAt = excluded.QuotedAt,
+                FetchedAt = excluded.FetchedAt,
+                Source = excluded.Source",
+        )
+        .bind(price.investment_id)
+        .bind(DecimalColumn(Some(price.price)))
+        .bind(&price.currency)
+        .bind(DecimalColumn(Some(price.original_price)))
+        .bind(price.quoted_at)
+        .bind(price.fetched_at)
+        .bind(&price.source)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -1,6 +1,11 @@
-use crate::error::Result;
-use crate::models::Investment;
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::{
+    DecimalColumn, Investment, InvestmentDependents, InvestmentFilter, InvestmentMerge,
+    INVESTMENT_MERGED,
+};
+use crate::repository::sqlite::{begin_write, retry_busy};
 use crate::repository::traits;
+use crate::repository::{investment_in_use, merge_details, SearchPatterns};
 use async_trait::async_trait;
 use sqlx::SqlitePool;
 
@@ -32,43 +37,220 @@ impl traits::InvestmentRepository for SqliteInvestmentRepository {
         Ok(investment)
     }
 
+    async fn search(
+        &self,
+        text: &str,
+        filter: &InvestmentFilter,
+        limit: i64,
+    ) -> Result<Vec<Investment>> {
+        let patterns = SearchPatterns::new(text);
+        let investments = sqlx::query_as::<_, Investment>(
+            r"SELECT * FROM Investment
+              WHERE (LOWER(Name) LIKE ?3 ESCAPE '\' OR LOWER(ShortName) LIKE ?3 ESCAPE '\'
+                     OR LOWER(ISIN) LIKE ?3 ESCAPE '\' OR LOWER(TickerSymbol) LIKE ?3 ESCAPE '\')
+                AND (?4 IS NULL OR Watchlist = ?4) AND (?6 OR Archived = 0)
+              ORDER BY
+                CASE
+                  WHEN ?1 IN (LOWER(Name), LOWER(ShortName), LOWER(ISIN), LOWER(TickerSymbol)) THEN 0
+                  WHEN LOWER(Name) LIKE ?2 ESCAPE '\' OR LOWER(ShortName) LIKE ?2 ESCAPE '\'
+                       OR LOWER(ISIN) LIKE ?2 ESCAPE '\' OR LOWER(TickerSymbol) LIKE ?2 ESCAPE '\' THEN 1
+                  ELSE 2
+                END,
+                LOWER(COALESCE(Name, '')), ID
+              LIMIT ?5",
+        )
+        .bind(&patterns.exact)
+        .bind(&patterns.prefix)
+        .bind(&patterns.contains)
+        .bind(filter.watchlist)
+        .bind(limit)
+        .bind(filter.include_archived)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(investments)
+    }
+
     async fn create(&self, investment: &Investment) -> Result<i64> {
         let result = sqlx::query(
-            "INSERT INTO Investment (Name, ISIN, ShortName, TickerSymbol, QuoteProvider) VALUES (?, ?, ?, ?, ?)"
+            "INSERT INTO Investment (Name, ISIN, ShortName, TickerSymbol, QuoteProvider, Currency, AssetClass, Watchlist, PartialExemption, QuoteFetchEnabled, QuoteFetchIntervalDays, Archived) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(&investment.name)
         .bind(&investment.isin)
         .bind(&investment.shortname)
         .bind(&investment.ticker_symbol)
         .bind(&investment.quote_provider)
+        .bind(&investment.currency)
+        .bind(&investment.asset_class)
+        .bind(investment.watchlist)
+        .bind(DecimalColumn(investment.partial_exemption))
+        .bind(investment.quote_fetch_enabled)
+        .bind(investment.quote_fetch_interval_days)
+        .bind(investment.archived)
         .execute(&self.pool)
         .await?;
 
         Ok(result.last_insert_rowid())
     }
 
-    async fn update(&self, id: i64, investment: &Investment) -> Result<()> {
-        sqlx::query(
-            "UPDATE Investment SET Name = ?, ISIN = ?, ShortName = ?, TickerSymbol = ?, QuoteProvider = ? WHERE ID = ?"
+    async fn update(&self, id: i64, investment: &Investment) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE Investment SET Name = ?, ISIN = ?, ShortName = ?, TickerSymbol = ?, QuoteProvider = ?, Currency = ?, AssetClass = ?, Watchlist = ?, PartialExemption = ?, QuoteFetchEnabled = ?, QuoteFetchIntervalDays = ?, Archived = ? WHERE ID = ?"
         )
         .bind(&investment.name)
         .bind(&investment.isin)
         .bind(&investment.shortname)
         .bind(&investment.ticker_symbol)
         .bind(&investment.quote_provider)
+        .bind(&investment.currency)
+        .bind(&investment.asset_class)
+        .bind(investment.watchlist)
+        .bind(DecimalColumn(investment.partial_exemption))
+        .bind(investment.quote_fetch_enabled)
+        .bind(investment.quote_fetch_interval_days)
+        .bind(investment.archived)
         .bind(id)
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected())
     }
 
-    async fn delete(&self, id: i64) -> Result<()> {
-        sqlx::query("DELETE FROM Investment WHERE ID = ?")
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM Investment WHERE ID = ?")
             .bind(id)
             .execute(&self.pool)
             .await?;
 
-        Ok(())
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_with_dependents(&self, id: i64, cascade: bool) -> Result<InvestmentDependents> {
+        retry_busy(|| async move {
+            let mut tx = begin_write(&self.pool).await?;
+
+            let (movements,): (i64,) =
+                sqlx::query_as("SELECT COUNT(*) FROM Movement WHERE InvestmentID = ?")
+                    .bind(id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+            let (prices,): (i64,) =
+                sqlx::query_as("SELECT COUNT(*) FROM InvestmentPrice WHERE InvestmentID = ?")
+                    .bind(id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+            let dependents = InvestmentDependents { movements, prices };
+
+            if !cascade && !dependents.is_empty() {
+                return Err(investment_in_use(id, dependents));
+            }
+
+            sqlx::query("DELETE FROM InvestmentPrice WHERE InvestmentID = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM Movement WHERE InvestmentID = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            let deleted = sqlx::query("DELETE FROM Investment WHERE ID = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            ensure_found(deleted.rows_affected(), "Investment", id)?;
+
+            tx.commit().await?;
+            Ok(dependents)
+        })
+        .await
+    }
+
+    async fn merge_into(&self, id: i64, target_id: i64) -> Result<InvestmentMerge> {
+        retry_busy(|| async move {
+            let mut tx = begin_write(&self.pool).await?;
+
+            let mut names = Vec::with_capacity(2);
+            for investment in [id, target_id] {
+                let name: Option<(Option<String>,)> =
+                    sqlx::query_as("SELECT Name FROM Investment WHERE ID = ?")
+                        .bind(investment)
+                        .fetch_optional(&mut *tx)
+                        .await?;
+                let (name,) = name.ok_or_else(|| AppError::not_found("Investment", investment))?;
+                names.push(name);
+            }
+
+            let duplicate_prices = sqlx::query(
+                "DELETE FROM InvestmentPrice
+                 WHERE InvestmentID = ? AND EXISTS (
+                     SELECT 1 FROM InvestmentPrice AS target
+                     WHERE target.InvestmentID = ?
+                       AND target.Date = InvestmentPrice.Date
+                       AND target.Source = InvestmentPrice.Source
+                 )",
+            )
+            .bind(id)
+            .bind(target_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+            let prices =
+                sqlx::query("UPDATE InvestmentPrice SET InvestmentID = ? WHERE InvestmentID = ?")
+                    .bind(target_id)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+            let movements =
+                sqlx::query("UPDATE Movement SET InvestmentID = ? WHERE InvestmentID = ?")
+                    .bind(target_id)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+            for table in ["PriceAlert", "SavingsPlan"] {
+                sqlx::query(&format!(
+                    "UPDATE {} SET InvestmentID = ? WHERE InvestmentID = ?",
+                    table
+                ))
+                .bind(target_id)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            }
+            sqlx::query(
+                "INSERT OR IGNORE INTO InvestmentTag (InvestmentID, TagID)
+                 SELECT ?, TagID FROM InvestmentTag WHERE InvestmentID = ?",
+            )
+            .bind(target_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("DELETE FROM Investment WHERE ID = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+
+            let merge = InvestmentMerge {
+                source_id: id,
+                target_id,
+                movements: movements as i64,
+                prices: prices as i64,
+                duplicate_prices: duplicate_prices as i64,
+            };
+            sqlx::query("INSERT INTO AuditLog (CreatedAt, Action, Details) VALUES (?, ?, ?)")
+                .bind(chrono::Utc::now())
+                .bind(INVESTMENT_MERGED)
+                .bind(merge_details(
+                    &merge,
+                    names[0].as_deref(),
+                    names[1].as_deref(),
+                ))
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await?;
+            Ok(merge)
+        })
+        .await
     }
 }
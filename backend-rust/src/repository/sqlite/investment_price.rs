@@ -1,10 +1,20 @@
 use crate::error::Result;
-use crate::models::InvestmentPrice;
+use crate::models::{DecimalColumn, InvestmentPrice};
+use crate::repository::sqlite::{begin_write, retry_busy};
 use crate::repository::traits;
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use sqlx::SqlitePool;
 
+/// Shared with the data import, so that prices are merged on the same key
+pub(super) const UPSERT_PRICE: &str =
+    "INSERT INTO InvestmentPrice (Date, InvestmentID, Price, Source, Currency, OriginalPrice)
+     VALUES (?, ?, ?, ?, ?, ?)
+     ON CONFLICT(Date, InvestmentID, Source) DO UPDATE SET
+        Price = excluded.Price,
+        Currency = excluded.Currency,
+        OriginalPrice = excluded.OriginalPrice";
+
 #[derive(Clone)]
 pub struct SqliteInvestmentPriceRepository {
     pool: SqlitePool,
@@ -24,7 +34,7 @@ impl traits::InvestmentPriceRepository for SqliteInvestmentPriceRepository {
         start_date: Option<NaiveDate>,
         end_date: Option<NaiveDate>,
     ) -> Result<Vec<InvestmentPrice>> {
-        let mut query = String::from("SELECT Date, InvestmentID, CAST(Price AS REAL) as Price, Source FROM InvestmentPrice WHERE 1=1");
+        let mut query = String::from("SELECT Date, InvestmentID, CAST(Price AS REAL) as Price, Source, Currency, CAST(OriginalPrice AS REAL) as OriginalPrice FROM InvestmentPrice WHERE 1=1");
 
         if investment_id.is_some() {
             query.push_str(" AND InvestmentID = ?");
@@ -55,12 +65,14 @@ impl traits::InvestmentPriceRepository for SqliteInvestmentPriceRepository {
 
     async fn create(&self, price: &InvestmentPrice) -> Result<()> {
         sqlx::query(
-            "INSERT INTO InvestmentPrice (Date, InvestmentID, Price, Source) VALUES (?, ?, ?, ?)",
+            "INSERT INTO InvestmentPrice (Date, InvestmentID, Price, Source, Currency, OriginalPrice) VALUES (?, ?, ?, ?, ?, ?)",
         )
         .bind(price.date)
         .bind(price.investment_id)
-        .bind(price.price)
-        .bind(&price.source)
+        .bind(DecimalColumn(price.price))
+        .bind(price.stored_source())
+        .bind(&price.currency)
+        .bind(DecimalColumn(price.original_price))
         .execute(&self.pool)
         .await?;
 
@@ -68,19 +80,76 @@ impl traits::InvestmentPriceRepository for SqliteInvestmentPriceRepository {
     }
 
     async fn upsert(&self, price: &InvestmentPrice) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO InvestmentPrice (Date, InvestmentID, Price, Source) 
-             VALUES (?, ?, ?, ?)
-             ON CONFLICT(Date, InvestmentID, Source) DO UPDATE SET Price = ?",
+        sqlx::query(UPSERT_PRICE)
+            .bind(price.date)
+            .bind(price.investment_id)
+            .bind(DecimalColumn(price.price))
+            .bind(price.stored_source())
+            .bind(&price.currency)
+            .bind(DecimalColumn(price.original_price))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_many(&self, prices: &[InvestmentPrice]) -> Result<()> {
+        retry_busy(|| async move {
+            // The statement is prepared once and reused for every price
+            let mut tx = begin_write(&self.pool).await?;
+            for price in prices {
+                sqlx::query(UPSERT_PRICE)
+                    .bind(price.date)
+                    .bind(price.investment_id)
+                    .bind(DecimalColumn(price.price))
+                    .bind(price.stored_source())
+                    .bind(&price.currency)
+                    .bind(DecimalColumn(price.original_price))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn find_latest_date(
+        &self,
+        investment_id: i64,
+        source: &str,
+    ) -> Result<Option<NaiveDate>> {
+        let date = sqlx::query_scalar(
+            "SELECT MAX(Date) FROM InvestmentPrice WHERE InvestmentID = ? AND Source = ?",
+        )
+        .bind(investment_id)
+        .bind(source)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(date)
+    }
+
+    async fn delete_orphaned(&self) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM InvestmentPrice
+             WHERE InvestmentID IS NULL OR InvestmentID NOT IN (SELECT ID FROM Investment)",
         )
-        .bind(price.date)
-        .bind(price.investment_id)
-        .bind(price.price)
-        .bind(&price.source)
-        .bind(price.price)
         .execute(&self.pool)
         .await?;
+        Ok(result.rows_affected())
+    }
 
-        Ok(())
+    async fn delete_duplicates(&self) -> Result<u64> {
+        // The unique key does not apply to rows with a NULL column, GROUP BY does
+        let result = sqlx::query(
+            "DELETE FROM InvestmentPrice
+             WHERE id NOT IN (
+                 SELECT MAX(id) FROM InvestmentPrice
+                 GROUP BY Date, InvestmentID, COALESCE(Source, 'manual')
+             )",
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
     }
 }
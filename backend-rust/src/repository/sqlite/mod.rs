@@ -1,11 +1,88 @@
 pub mod action_type;
+pub mod api_token;
+pub mod audit_log;
+pub mod cash_movement;
+pub mod corporate_event;
+pub mod data_import;
+pub mod development;
+pub mod fx_rate;
+pub mod health;
+pub mod import_profile;
+pub mod intraday_price;
 pub mod investment;
 pub mod investment_price;
 pub mod movement;
+pub mod portfolio;
+pub mod price_alert;
+pub mod provider_config;
+pub mod quote_fetch_log;
+pub mod savings_plan;
 pub mod settings;
+pub mod snapshot;
+pub mod tag;
 
 pub use action_type::SqliteActionTypeRepository;
+pub use api_token::SqliteApiTokenRepository;
+pub use audit_log::SqliteAuditLogRepository;
+pub use cash_movement::SqliteCashMovementRepository;
+pub use corporate_event::SqliteCorporateEventRepository;
+pub use data_import::SqliteDataImportRepository;
+pub use development::SqliteDevelopmentRepository;
+pub use fx_rate::SqliteFxRateRepository;
+pub use health::SqliteHealthRepository;
+pub use import_profile::SqliteImportProfileRepository;
+pub use intraday_price::SqliteIntradayPriceRepository;
 pub use investment::SqliteInvestmentRepository;
 pub use investment_price::SqliteInvestmentPriceRepository;
 pub use movement::SqliteMovementRepository;
+pub use portfolio::SqlitePortfolioRepository;
+pub use price_alert::SqlitePriceAlertRepository;
+pub use provider_config::SqliteProviderConfigRepository;
+pub use quote_fetch_log::SqliteQuoteFetchLogRepository;
+pub use savings_plan::SqliteSavingsPlanRepository;
 pub use settings::SqliteSettingsRepository;
+pub use snapshot::SqliteSnapshotRepository;
+pub use tag::SqliteTagRepository;
+
+use crate::error::Result;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::future::Future;
+use std::time::Duration;
+
+/// Attempts of a write transaction while SQLite reports the database busy
+const BUSY_RETRY_ATTEMPTS: u32 = 4;
+/// Delay before the first retry, doubled for every further one
+const BUSY_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Start a transaction that takes the write lock right away
+///
+/// A deferred transaction that read first fails immediately when another connection
+/// wrote in between, without waiting for the busy timeout.
+pub(crate) async fn begin_write(pool: &SqlitePool) -> Result<Transaction<'static, Sqlite>> {
+    Ok(pool.begin_with("BEGIN IMMEDIATE").await?)
+}
+
+/// Run a write again after a short, growing delay while SQLite reports the database
+/// busy or locked after the busy timeout
+///
+/// The operation must do all its writes in one transaction, so a failed attempt left
+/// nothing behind.
+pub(crate) async fn retry_busy<T, F, Fut>(operation: F) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    let mut delay = BUSY_RETRY_DELAY;
+    loop {
+        match operation().await {
+            Err(e) if e.is_busy() && attempt < BUSY_RETRY_ATTEMPTS => {
+                tracing::warn!("Database busy, retrying in {} ms: {}", delay.as_millis(), e);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+}
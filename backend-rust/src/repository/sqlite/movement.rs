@@ -1,9 +1,16 @@
 use crate::error::Result;
-use crate::models::Movement;
+use crate::models::{DecimalColumn, Movement, MovementListOptions};
+use crate::repository::sqlite::{begin_write, retry_busy};
 use crate::repository::traits;
+use crate::services::cost_basis::SPLIT_ACTION_ID;
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use sqlx::SqlitePool;
 
+/// Optional filters of `MovementListOptions`, bound as parameters 1 to 5
+const FILTER_CLAUSE: &str = "(?1 IS NULL OR PortfolioID = ?1) AND (?2 IS NULL OR InvestmentID = ?2) \
+     AND (?3 IS NULL OR ActionID = ?3) AND (?4 IS NULL OR Date >= ?4) AND (?5 IS NULL OR Date <= ?5)";
+
 #[derive(Clone)]
 pub struct SqliteMovementRepository {
     pool: SqlitePool,
@@ -19,8 +26,111 @@ impl SqliteMovementRepository {
 impl traits::MovementRepository for SqliteMovementRepository {
     async fn find_all(&self) -> Result<Vec<Movement>> {
         let movements = sqlx::query_as::<_, Movement>(
-            "SELECT ID, Date, ActionID, InvestmentID, CAST(Quantity AS REAL) as Quantity, CAST(Amount AS REAL) as Amount, CAST(Fee AS REAL) as Fee FROM Movement",
+            "SELECT ID, Date, ActionID, InvestmentID, CAST(Quantity AS REAL) as Quantity, CAST(Amount AS REAL) as Amount, CAST(Fee AS REAL) as Fee, CAST(Tax AS REAL) as Tax, PortfolioID, ExternalID FROM Movement",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(movements)
+    }
+
+    async fn find_by_portfolio(&self, portfolio_id: i64) -> Result<Vec<Movement>> {
+        let movements = sqlx::query_as::<_, Movement>(
+            "SELECT ID, Date, ActionID, InvestmentID, CAST(Quantity AS REAL) as Quantity, CAST(Amount AS REAL) as Amount, CAST(Fee AS REAL) as Fee, CAST(Tax AS REAL) as Tax, PortfolioID, ExternalID FROM Movement WHERE PortfolioID = ?",
         )
+        .bind(portfolio_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(movements)
+    }
+
+    async fn find_page(&self, options: &MovementListOptions) -> Result<(Vec<Movement>, i64)> {
+        let total: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM Movement WHERE {}",
+            FILTER_CLAUSE
+        ))
+        .bind(options.portfolio_id)
+        .bind(options.investment_id)
+        .bind(options.action_id)
+        .bind(options.start_date)
+        .bind(options.end_date)
+        .fetch_one(&self.pool)
+        .await?;
+
+        // Sort column and direction come from enums, so they are safe to interpolate
+        let query = format!(
+            "SELECT ID, Date, ActionID, InvestmentID, CAST(Quantity AS REAL) as Quantity, CAST(Amount AS REAL) as Amount, CAST(Fee AS REAL) as Fee, CAST(Tax AS REAL) as Tax, PortfolioID, ExternalID FROM Movement \
+             WHERE {} ORDER BY {} {}, ID {} LIMIT ?6 OFFSET ?7",
+            FILTER_CLAUSE,
+            options.sort_by.column(),
+            options.order.as_sql(),
+            options.order.as_sql()
+        );
+        let movements = sqlx::query_as::<_, Movement>(&query)
+            .bind(options.portfolio_id)
+            .bind(options.investment_id)
+            .bind(options.action_id)
+            .bind(options.start_date)
+            .bind(options.end_date)
+            // A negative limit means no limit in SQLite
+            .bind(options.limit.unwrap_or(-1))
+            .bind(options.offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((movements, total.0))
+    }
+
+    async fn find_by_investment_and_range(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Movement>> {
+        let query = format!(
+            "SELECT ID, Date, ActionID, InvestmentID, CAST(Quantity AS REAL) as Quantity, CAST(Amount AS REAL) as Amount, CAST(Fee AS REAL) as Fee, CAST(Tax AS REAL) as Tax, PortfolioID, ExternalID FROM Movement \
+             WHERE {} ORDER BY Date, ID",
+            FILTER_CLAUSE
+        );
+        let movements = sqlx::query_as::<_, Movement>(&query)
+            .bind(portfolio_id)
+            .bind(investment_id)
+            .bind(None::<i64>)
+            .bind(start_date)
+            .bind(end_date)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(movements)
+    }
+
+    async fn find_quantity_totals_before(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        before: NaiveDate,
+    ) -> Result<Vec<Movement>> {
+        // The splits of the investment up to a movement number the period it belongs to
+        let movements = sqlx::query_as::<_, Movement>(
+            "SELECT 0 AS ID, MIN(m.Date) AS Date, m.ActionID, m.InvestmentID, CAST(SUM(m.Quantity) AS REAL) AS Quantity, \
+                    NULL AS Amount, NULL AS Fee, NULL AS Tax, ?2 AS PortfolioID, NULL AS ExternalID \
+             FROM Movement m \
+             WHERE m.Date < ?3 AND m.ActionID <> ?4 AND m.InvestmentID IS NOT NULL AND m.Quantity IS NOT NULL \
+               AND (?1 IS NULL OR m.InvestmentID = ?1) AND (?2 IS NULL OR m.PortfolioID = ?2) \
+             GROUP BY m.InvestmentID, m.ActionID, m.Quantity < 0, \
+               (SELECT COUNT(*) FROM Movement s WHERE s.ActionID = ?4 AND s.InvestmentID = m.InvestmentID \
+                  AND s.Date <= m.Date AND (?2 IS NULL OR s.PortfolioID = ?2)) \
+             UNION ALL \
+             SELECT ID, Date, ActionID, InvestmentID, CAST(Quantity AS REAL) AS Quantity, \
+                    NULL AS Amount, NULL AS Fee, NULL AS Tax, PortfolioID, NULL AS ExternalID \
+             FROM Movement \
+             WHERE Date < ?3 AND ActionID = ?4 \
+               AND (?1 IS NULL OR InvestmentID = ?1) AND (?2 IS NULL OR PortfolioID = ?2) \
+             ORDER BY Date",
+        )
+        .bind(investment_id)
+        .bind(portfolio_id)
+        .bind(before)
+        .bind(SPLIT_ACTION_ID)
         .fetch_all(&self.pool)
         .await?;
         Ok(movements)
@@ -28,7 +138,7 @@ impl traits::MovementRepository for SqliteMovementRepository {
 
     async fn find_by_id(&self, id: i64) -> Result<Option<Movement>> {
         let movement = sqlx::query_as::<_, Movement>(
-            "SELECT ID, Date, ActionID, InvestmentID, CAST(Quantity AS REAL) as Quantity, CAST(Amount AS REAL) as Amount, CAST(Fee AS REAL) as Fee FROM Movement WHERE ID = ?"
+            "SELECT ID, Date, ActionID, InvestmentID, CAST(Quantity AS REAL) as Quantity, CAST(Amount AS REAL) as Amount, CAST(Fee AS REAL) as Fee, CAST(Tax AS REAL) as Tax, PortfolioID, ExternalID FROM Movement WHERE ID = ?"
         )
             .bind(id)
             .fetch_optional(&self.pool)
@@ -36,45 +146,96 @@ impl traits::MovementRepository for SqliteMovementRepository {
         Ok(movement)
     }
 
+    async fn find_by_external_id(
+        &self,
+        portfolio_id: Option<i64>,
+        external_id: &str,
+    ) -> Result<Option<Movement>> {
+        let movement = sqlx::query_as::<_, Movement>(
+            "SELECT ID, Date, ActionID, InvestmentID, CAST(Quantity AS REAL) as Quantity, CAST(Amount AS REAL) as Amount, CAST(Fee AS REAL) as Fee, CAST(Tax AS REAL) as Tax, PortfolioID, ExternalID FROM Movement \
+             WHERE COALESCE(PortfolioID, 0) = COALESCE(?, 0) AND ExternalID = ?",
+        )
+        .bind(portfolio_id)
+        .bind(external_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(movement)
+    }
+
     async fn create(&self, movement: &Movement) -> Result<i64> {
         let result = sqlx::query(
-            "INSERT INTO Movement (Date, ActionID, InvestmentID, Quantity, Amount, Fee) VALUES (?, ?, ?, ?, ?, ?)"
+            "INSERT INTO Movement (Date, ActionID, InvestmentID, Quantity, Amount, Fee, Tax, PortfolioID, ExternalID) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(movement.date)
         .bind(movement.action_id)
         .bind(movement.investment_id)
-        .bind(movement.quantity)
-        .bind(movement.amount)
-        .bind(movement.fee)
+        .bind(DecimalColumn(movement.quantity))
+        .bind(DecimalColumn(movement.amount))
+        .bind(DecimalColumn(movement.fee))
+        .bind(DecimalColumn(movement.tax))
+        .bind(movement.portfolio_id)
+        .bind(&movement.external_id)
         .execute(&self.pool)
         .await?;
 
         Ok(result.last_insert_rowid())
     }
 
-    async fn update(&self, id: i64, movement: &Movement) -> Result<()> {
-        sqlx::query(
-            "UPDATE Movement SET Date = ?, ActionID = ?, InvestmentID = ?, Quantity = ?, Amount = ?, Fee = ? WHERE ID = ?"
+    async fn create_many(&self, movements: &[Movement]) -> Result<Vec<i64>> {
+        retry_busy(|| async move {
+        let mut tx = begin_write(&self.pool).await?;
+        let mut ids = Vec::with_capacity(movements.len());
+
+        for movement in movements {
+            let result = sqlx::query(
+                "INSERT INTO Movement (Date, ActionID, InvestmentID, Quantity, Amount, Fee, Tax, PortfolioID, ExternalID) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(movement.date)
+            .bind(movement.action_id)
+            .bind(movement.investment_id)
+            .bind(DecimalColumn(movement.quantity))
+            .bind(DecimalColumn(movement.amount))
+            .bind(DecimalColumn(movement.fee))
+            .bind(DecimalColumn(movement.tax))
+            .bind(movement.portfolio_id)
+            .bind(&movement.external_id)
+            .execute(&mut *tx)
+            .await?;
+            ids.push(result.last_insert_rowid());
+        }
+
+        tx.commit().await?;
+        Ok(ids)
+        })
+        .await
+    }
+
+    async fn update(&self, id: i64, movement: &Movement) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE Movement SET Date = ?, ActionID = ?, InvestmentID = ?, Quantity = ?, Amount = ?, Fee = ?, Tax = ?, PortfolioID = ?, ExternalID = ? WHERE ID = ?"
         )
         .bind(movement.date)
         .bind(movement.action_id)
         .bind(movement.investment_id)
-        .bind(movement.quantity)
-        .bind(movement.amount)
-        .bind(movement.fee)
+        .bind(DecimalColumn(movement.quantity))
+        .bind(DecimalColumn(movement.amount))
+        .bind(DecimalColumn(movement.fee))
+        .bind(DecimalColumn(movement.tax))
+        .bind(movement.portfolio_id)
+        .bind(&movement.external_id)
         .bind(id)
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected())
     }
 
-    async fn delete(&self, id: i64) -> Result<()> {
-        sqlx::query("DELETE FROM Movement WHERE ID = ?")
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM Movement WHERE ID = ?")
             .bind(id)
             .execute(&self.pool)
             .await?;
 
-        Ok(())
+        Ok(result.rows_affected())
     }
 }
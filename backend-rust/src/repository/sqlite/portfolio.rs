@@ -0,0 +1,64 @@
+use crate::error::Result;
+use crate::models::Portfolio;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqlitePortfolioRepository {
+    pool: SqlitePool,
+}
+
+impl SqlitePortfolioRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::PortfolioRepository for SqlitePortfolioRepository {
+    async fn find_all(&self) -> Result<Vec<Portfolio>> {
+        let portfolios = sqlx::query_as::<_, Portfolio>("SELECT * FROM Portfolio ORDER BY ID")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(portfolios)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Portfolio>> {
+        let portfolio = sqlx::query_as::<_, Portfolio>("SELECT * FROM Portfolio WHERE ID = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(portfolio)
+    }
+
+    async fn create(&self, portfolio: &Portfolio) -> Result<i64> {
+        let result = sqlx::query("INSERT INTO Portfolio (Name, Description) VALUES (?, ?)")
+            .bind(&portfolio.name)
+            .bind(&portfolio.description)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn update(&self, id: i64, portfolio: &Portfolio) -> Result<u64> {
+        let result = sqlx::query("UPDATE Portfolio SET Name = ?, Description = ? WHERE ID = ?")
+            .bind(&portfolio.name)
+            .bind(&portfolio.description)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM Portfolio WHERE ID = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,130 @@
+use crate::error::Result;
+use crate::models::{PriceAlert, TriggeredAlert};
+use crate::repository::sqlite::{begin_write, retry_busy};
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqlitePriceAlertRepository {
+    pool: SqlitePool,
+}
+
+impl SqlitePriceAlertRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::PriceAlertRepository for SqlitePriceAlertRepository {
+    async fn find_all(&self, investment_id: Option<i64>) -> Result<Vec<PriceAlert>> {
+        let alerts = sqlx::query_as::<_, PriceAlert>(
+            "SELECT * FROM PriceAlert WHERE (? IS NULL OR InvestmentID = ?) ORDER BY ID",
+        )
+        .bind(investment_id)
+        .bind(investment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(alerts)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<PriceAlert>> {
+        let alert = sqlx::query_as::<_, PriceAlert>("SELECT * FROM PriceAlert WHERE ID = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(alert)
+    }
+
+    async fn find_active(&self, investment_id: i64) -> Result<Vec<PriceAlert>> {
+        let alerts = sqlx::query_as::<_, PriceAlert>(
+            "SELECT * FROM PriceAlert WHERE InvestmentID = ? AND Active = 1 ORDER BY ID",
+        )
+        .bind(investment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(alerts)
+    }
+
+    async fn create(&self, alert: &PriceAlert) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO PriceAlert (InvestmentID, Threshold, Direction, Active) VALUES (?, ?, ?, ?)",
+        )
+        .bind(alert.investment_id)
+        .bind(alert.threshold)
+        .bind(&alert.direction)
+        .bind(alert.active)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn update(&self, id: i64, alert: &PriceAlert) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE PriceAlert SET InvestmentID = ?, Threshold = ?, Direction = ?, Active = ? WHERE ID = ?",
+        )
+        .bind(alert.investment_id)
+        .bind(alert.threshold)
+        .bind(&alert.direction)
+        .bind(alert.active)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM PriceAlert WHERE ID = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn record_trigger(&self, triggered: &TriggeredAlert) -> Result<i64> {
+        retry_busy(|| async move {
+        let mut tx = begin_write(&self.pool).await?;
+
+        let result = sqlx::query(
+            "INSERT INTO TriggeredAlert (AlertID, InvestmentID, TriggeredAt, PriceDate, Price, Threshold, Direction) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(triggered.alert_id)
+        .bind(triggered.investment_id)
+        .bind(triggered.triggered_at)
+        .bind(triggered.price_date)
+        .bind(triggered.price)
+        .bind(triggered.threshold)
+        .bind(&triggered.direction)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE PriceAlert SET Active = 0 WHERE ID = ?")
+            .bind(triggered.alert_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result.last_insert_rowid())
+        })
+        .await
+    }
+
+    async fn find_triggered(
+        &self,
+        investment_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<TriggeredAlert>> {
+        let triggered = sqlx::query_as::<_, TriggeredAlert>(
+            "SELECT * FROM TriggeredAlert WHERE (? IS NULL OR InvestmentID = ?) ORDER BY TriggeredAt DESC, ID DESC LIMIT ?",
+        )
+        .bind(investment_id)
+        .bind(investment_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(triggered)
+    }
+}
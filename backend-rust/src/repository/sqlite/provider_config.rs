@@ -0,0 +1,52 @@
+use crate::error::Result;
+use crate::models::ProviderConfig;
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteProviderConfigRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteProviderConfigRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::ProviderConfigRepository for SqliteProviderConfigRepository {
+    async fn find_all(&self) -> Result<Vec<ProviderConfig>> {
+        let configs = sqlx::query_as::<_, ProviderConfig>(
+            "SELECT Provider, ApiKey, UpdatedAt FROM ProviderConfig ORDER BY Provider",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(configs)
+    }
+
+    async fn upsert(&self, config: &ProviderConfig) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO ProviderConfig (Provider, ApiKey, UpdatedAt)
+             VALUES (?, ?, ?)
+             ON CONFLICT(Provider) DO UPDATE SET
+                ApiKey = excluded.ApiKey,
+                UpdatedAt = excluded.UpdatedAt",
+        )
+        .bind(&config.provider)
+        .bind(&config.api_key)
+        .bind(config.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, provider: &str) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM ProviderConfig WHERE Provider = ?")
+            .bind(provider)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
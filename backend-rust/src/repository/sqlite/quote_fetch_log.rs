@@ -0,0 +1,61 @@
+use crate::error::Result;
+use crate::models::QuoteFetchLog;
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteQuoteFetchLogRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteQuoteFetchLogRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::QuoteFetchLogRepository for SqliteQuoteFetchLogRepository {
+    async fn find_recent(
+        &self,
+        investment_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<QuoteFetchLog>> {
+        let entries = sqlx::query_as::<_, QuoteFetchLog>(
+            "SELECT * FROM QuoteFetchLog WHERE (? IS NULL OR InvestmentID = ?) ORDER BY FetchedAt DESC, ID DESC LIMIT ?",
+        )
+        .bind(investment_id)
+        .bind(investment_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+
+    async fn create(&self, entry: &QuoteFetchLog) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO QuoteFetchLog (FetchedAt, InvestmentID, Provider, Success, Error, QuotesStored) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(entry.fetched_at)
+        .bind(entry.investment_id)
+        .bind(&entry.provider)
+        .bind(entry.success)
+        .bind(&entry.error)
+        .bind(entry.quotes_stored)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn last_success(&self) -> Result<Option<DateTime<Utc>>> {
+        let fetched_at: Option<(DateTime<Utc>,)> = sqlx::query_as(
+            "SELECT FetchedAt FROM QuoteFetchLog WHERE Success = 1 ORDER BY FetchedAt DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(fetched_at.map(|(at,)| at))
+    }
+}
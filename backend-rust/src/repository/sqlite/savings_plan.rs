@@ -0,0 +1,83 @@
+use crate::error::Result;
+use crate::models::{DecimalColumn, SavingsPlan};
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+const SELECT_SAVINGS_PLAN: &str = "SELECT ID, InvestmentID, CAST(Amount AS REAL) as Amount, Interval, StartDate, EndDate FROM SavingsPlan";
+
+#[derive(Clone)]
+pub struct SqliteSavingsPlanRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteSavingsPlanRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::SavingsPlanRepository for SqliteSavingsPlanRepository {
+    async fn find_all(&self, investment_id: Option<i64>) -> Result<Vec<SavingsPlan>> {
+        let query = format!(
+            "{} WHERE (? IS NULL OR InvestmentID = ?) ORDER BY ID",
+            SELECT_SAVINGS_PLAN
+        );
+        let plans = sqlx::query_as::<_, SavingsPlan>(&query)
+            .bind(investment_id)
+            .bind(investment_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(plans)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<SavingsPlan>> {
+        let query = format!("{} WHERE ID = ?", SELECT_SAVINGS_PLAN);
+        let plan = sqlx::query_as::<_, SavingsPlan>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(plan)
+    }
+
+    async fn create(&self, plan: &SavingsPlan) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO SavingsPlan (InvestmentID, Amount, Interval, StartDate, EndDate) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(plan.investment_id)
+        .bind(DecimalColumn(Some(plan.amount)))
+        .bind(&plan.interval)
+        .bind(plan.start_date)
+        .bind(plan.end_date)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn update(&self, id: i64, plan: &SavingsPlan) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE SavingsPlan SET InvestmentID = ?, Amount = ?, Interval = ?, StartDate = ?, EndDate = ? WHERE ID = ?",
+        )
+        .bind(plan.investment_id)
+        .bind(DecimalColumn(Some(plan.amount)))
+        .bind(&plan.interval)
+        .bind(plan.start_date)
+        .bind(plan.end_date)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM SavingsPlan WHERE ID = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
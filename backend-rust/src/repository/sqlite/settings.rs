@@ -25,10 +25,26 @@ impl traits::SettingsRepository for SqliteSettingsRepository {
     }
 
     async fn update(&self, settings: &Settings) -> Result<()> {
-        sqlx::query("UPDATE Settings SET BaseCurrency = ? WHERE ID = 1")
-            .bind(&settings.base_currency)
-            .execute(&self.pool)
-            .await?;
+        sqlx::query(
+            "UPDATE Settings SET BaseCurrency = ?, CostBasisMethod = ?, BenchmarkInvestmentID = ?, \
+             BenchmarkTicker = ?, WebhookUrls = ?, WebhookSecret = ?, PriceSourcePriority = ?, \
+             ValueDecimals = ?, PriceDecimals = ?, QuantityDecimals = ?, RoundingMode = ?, \
+             RejectNegativeHoldings = ? WHERE ID = 1",
+        )
+        .bind(&settings.base_currency)
+        .bind(&settings.cost_basis_method)
+        .bind(settings.benchmark_investment_id)
+        .bind(&settings.benchmark_ticker)
+        .bind(&settings.webhook_urls)
+        .bind(&settings.webhook_secret)
+        .bind(&settings.price_source_priority)
+        .bind(settings.value_decimals)
+        .bind(settings.price_decimals)
+        .bind(settings.quantity_decimals)
+        .bind(&settings.rounding_mode)
+        .bind(settings.reject_negative_holdings)
+        .execute(&self.pool)
+        .await?;
 
         Ok(())
     }
@@ -0,0 +1,69 @@
+use crate::error::Result;
+use crate::models::{DecimalColumn, SnapshotHolding};
+use crate::repository::sqlite::{begin_write, retry_busy};
+use crate::repository::traits;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteSnapshotRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteSnapshotRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::SnapshotRepository for SqliteSnapshotRepository {
+    async fn find_all(&self, date: Option<NaiveDate>) -> Result<Vec<SnapshotHolding>> {
+        let holdings = sqlx::query_as::<_, SnapshotHolding>(
+            "SELECT * FROM Snapshot WHERE (? IS NULL OR Date = ?) ORDER BY Date, InvestmentID",
+        )
+        .bind(date)
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(holdings)
+    }
+
+    async fn replace(&self, date: NaiveDate, holdings: &[SnapshotHolding]) -> Result<()> {
+        retry_busy(|| async move {
+        let mut tx = begin_write(&self.pool).await?;
+
+        sqlx::query("DELETE FROM Snapshot WHERE Date = ?")
+            .bind(date)
+            .execute(&mut *tx)
+            .await?;
+
+        for holding in holdings {
+            sqlx::query(
+                "INSERT INTO Snapshot (Date, InvestmentID, Price, Quantity, Value) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(date)
+            .bind(holding.investment)
+            .bind(DecimalColumn(Some(holding.price)))
+            .bind(DecimalColumn(Some(holding.quantity)))
+            .bind(DecimalColumn(Some(holding.value)))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+        })
+        .await
+    }
+
+    async fn delete(&self, date: NaiveDate) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM Snapshot WHERE Date = ?")
+            .bind(date)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
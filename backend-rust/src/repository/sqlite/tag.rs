@@ -0,0 +1,116 @@
+use crate::error::Result;
+use crate::models::Tag;
+use crate::repository::sqlite::{begin_write, retry_busy};
+use crate::repository::traits;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Clone)]
+pub struct SqliteTagRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteTagRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl traits::TagRepository for SqliteTagRepository {
+    async fn find_all(&self) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as::<_, Tag>("SELECT * FROM Tag ORDER BY Name")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tags)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Tag>> {
+        let tag = sqlx::query_as::<_, Tag>("SELECT * FROM Tag WHERE ID = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(tag)
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Tag>> {
+        let tag = sqlx::query_as::<_, Tag>("SELECT * FROM Tag WHERE LOWER(Name) = LOWER(?)")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(tag)
+    }
+
+    async fn create(&self, tag: &Tag) -> Result<i64> {
+        let result = sqlx::query("INSERT INTO Tag (Name) VALUES (?)")
+            .bind(&tag.name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn update(&self, id: i64, tag: &Tag) -> Result<u64> {
+        let result = sqlx::query("UPDATE Tag SET Name = ? WHERE ID = ?")
+            .bind(&tag.name)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, id: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM Tag WHERE ID = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn find_by_investment(&self, investment_id: i64) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as::<_, Tag>(
+            "SELECT Tag.* FROM Tag JOIN InvestmentTag ON InvestmentTag.TagID = Tag.ID \
+             WHERE InvestmentTag.InvestmentID = ? ORDER BY Tag.Name",
+        )
+        .bind(investment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tags)
+    }
+
+    async fn set_investment_tags(&self, investment_id: i64, tag_ids: &[i64]) -> Result<()> {
+        retry_busy(|| async move {
+            let mut tx = begin_write(&self.pool).await?;
+
+            sqlx::query("DELETE FROM InvestmentTag WHERE InvestmentID = ?")
+                .bind(investment_id)
+                .execute(&mut *tx)
+                .await?;
+            for tag_id in tag_ids {
+                sqlx::query(
+                    "INSERT OR IGNORE INTO InvestmentTag (InvestmentID, TagID) VALUES (?, ?)",
+                )
+                .bind(investment_id)
+                .bind(tag_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            tx.commit().await?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn find_investment_ids(&self, tag_id: i64) -> Result<Vec<i64>> {
+        let ids: Vec<(i64,)> = sqlx::query_as(
+            "SELECT InvestmentID FROM InvestmentTag WHERE TagID = ? ORDER BY InvestmentID",
+        )
+        .bind(tag_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+}
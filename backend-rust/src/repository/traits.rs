@@ -1,24 +1,106 @@
+//! Repository interfaces, implemented for SQLite, PostgreSQL and in memory
+//!
+//! Updates and deletions of single entities return the number of affected rows, which is
+//! 0 if the entity does not exist.
+
 use crate::error::Result;
-use crate::models::{ActionType, Investment, InvestmentPrice, Movement, Settings};
+use crate::models::{
+    ActionType, ApiToken, AuditLogEntry, CashMovement, DataExport, Development, DividendEvent,
+    FxRate, ImportMode, ImportProfile, ImportSummary, IntradayPrice, Investment,
+    InvestmentDependents, InvestmentFilter, InvestmentMerge, InvestmentPrice, MigrationStatus,
+    Movement, MovementListOptions, MovementSortField, Portfolio, PriceAlert, ProviderConfig,
+    QuoteFetchLog, SavingsPlan, Settings, SnapshotHolding, SplitEvent, Tag, TriggeredAlert,
+};
 use async_trait::async_trait;
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 
 #[async_trait]
 pub trait InvestmentRepository: Send + Sync {
     async fn find_all(&self) -> Result<Vec<Investment>>;
     async fn find_by_id(&self, id: i64) -> Result<Option<Investment>>;
+    /// Investments whose name, short name, ISIN or ticker contains `text`, ignoring case.
+    /// Exact matches of a field come first, then prefix matches, each ordered by name.
+    async fn search(
+        &self,
+        text: &str,
+        filter: &InvestmentFilter,
+        limit: i64,
+    ) -> Result<Vec<Investment>>;
     async fn create(&self, investment: &Investment) -> Result<i64>;
-    async fn update(&self, id: i64, investment: &Investment) -> Result<()>;
-    async fn delete(&self, id: i64) -> Result<()>;
+    async fn update(&self, id: i64, investment: &Investment) -> Result<u64>;
+    async fn delete(&self, id: i64) -> Result<u64>;
+    /// Delete an investment in one transaction, together with its movements and prices
+    /// when `cascade` is set. Without `cascade` the deletion is rejected with
+    /// `AppError::Conflict` if any dependents exist. Returns the deleted dependents, or
+    /// `AppError::NotFound` if the investment does not exist.
+    async fn delete_with_dependents(&self, id: i64, cascade: bool) -> Result<InvestmentDependents>;
+    /// Move the movements, prices, price alerts, savings plans and tags of an investment
+    /// to the target, delete it and record the merge in the audit log, all in one
+    /// transaction. Prices the target already has for the same day and source are dropped.
+    /// `AppError::NotFound` if either investment does not exist.
+    async fn merge_into(&self, id: i64, target_id: i64) -> Result<InvestmentMerge>;
 }
 
 #[async_trait]
 pub trait MovementRepository: Send + Sync {
     async fn find_all(&self) -> Result<Vec<Movement>>;
+    async fn find_by_portfolio(&self, portfolio_id: i64) -> Result<Vec<Movement>>;
+    /// One page of movements and the total number of movements matching the filter
+    async fn find_page(&self, options: &MovementListOptions) -> Result<(Vec<Movement>, i64)>;
+    /// Movements matching all given filters, ordered by date
+    async fn find_filtered(
+        &self,
+        investment_id: Option<i64>,
+        action_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Movement>> {
+        let options = MovementListOptions {
+            investment_id,
+            action_id,
+            start_date,
+            end_date,
+            sort_by: MovementSortField::Date,
+            ..Default::default()
+        };
+        let (movements, _) = self.find_page(&options).await?;
+        Ok(movements)
+    }
+    /// Movements of the investment and portfolio if given, dated within the range and
+    /// ordered by date
+    async fn find_by_investment_and_range(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Movement>>;
+    /// Movements dated before `before`, condensed to what the quantity held depends on
+    ///
+    /// Splits (6) are returned as they are. All other movements with a quantity are summed
+    /// per investment, action type, sign of the quantity and period between two splits of
+    /// the investment, into one movement without ID and amount dated on the first day of
+    /// the period. Applying them in date order gives the quantity held at the start of
+    /// `before`.
+    async fn find_quantity_totals_before(
+        &self,
+        investment_id: Option<i64>,
+        portfolio_id: Option<i64>,
+        before: NaiveDate,
+    ) -> Result<Vec<Movement>>;
     async fn find_by_id(&self, id: i64) -> Result<Option<Movement>>;
+    /// Movement with the external reference in the portfolio, `None` for movements
+    /// without portfolio
+    async fn find_by_external_id(
+        &self,
+        portfolio_id: Option<i64>,
+        external_id: &str,
+    ) -> Result<Option<Movement>>;
     async fn create(&self, movement: &Movement) -> Result<i64>;
-    async fn update(&self, id: i64, movement: &Movement) -> Result<()>;
-    async fn delete(&self, id: i64) -> Result<()>;
+    /// Insert all movements in one transaction and return their IDs in order
+    async fn create_many(&self, movements: &[Movement]) -> Result<Vec<i64>>;
+    async fn update(&self, id: i64, movement: &Movement) -> Result<u64>;
+    async fn delete(&self, id: i64) -> Result<u64>;
 }
 
 #[async_trait]
@@ -31,12 +113,25 @@ pub trait InvestmentPriceRepository: Send + Sync {
     ) -> Result<Vec<InvestmentPrice>>;
     async fn create(&self, price: &InvestmentPrice) -> Result<()>;
     async fn upsert(&self, price: &InvestmentPrice) -> Result<()>;
+    /// Upsert several prices in one transaction, either all or none
+    async fn upsert_many(&self, prices: &[InvestmentPrice]) -> Result<()>;
+    /// Date of the most recent price of an investment from a source
+    async fn find_latest_date(&self, investment_id: i64, source: &str)
+        -> Result<Option<NaiveDate>>;
+    /// Delete prices without investment or of investments that no longer exist
+    async fn delete_orphaned(&self) -> Result<u64>;
+    /// Keep only the latest stored price per (Date, InvestmentID, Source)
+    async fn delete_duplicates(&self) -> Result<u64>;
 }
 
 #[async_trait]
 pub trait ActionTypeRepository: Send + Sync {
     async fn find_all(&self) -> Result<Vec<ActionType>>;
     async fn find_by_id(&self, id: i64) -> Result<Option<ActionType>>;
+    async fn create(&self, action_type: &ActionType) -> Result<i64>;
+    async fn update(&self, id: i64, action_type: &ActionType) -> Result<u64>;
+    /// Rejected with `AppError::Conflict` while movements of the type exist
+    async fn delete(&self, id: i64) -> Result<u64>;
 }
 
 #[async_trait]
@@ -44,3 +139,199 @@ pub trait SettingsRepository: Send + Sync {
     async fn get(&self) -> Result<Option<Settings>>;
     async fn update(&self, settings: &Settings) -> Result<()>;
 }
+
+#[async_trait]
+pub trait PortfolioRepository: Send + Sync {
+    async fn find_all(&self) -> Result<Vec<Portfolio>>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<Portfolio>>;
+    async fn create(&self, portfolio: &Portfolio) -> Result<i64>;
+    async fn update(&self, id: i64, portfolio: &Portfolio) -> Result<u64>;
+    async fn delete(&self, id: i64) -> Result<u64>;
+}
+
+#[async_trait]
+pub trait CashMovementRepository: Send + Sync {
+    async fn find_all(&self, portfolio_id: Option<i64>) -> Result<Vec<CashMovement>>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<CashMovement>>;
+    async fn create(&self, movement: &CashMovement) -> Result<i64>;
+    async fn delete(&self, id: i64) -> Result<u64>;
+}
+
+#[async_trait]
+pub trait QuoteFetchLogRepository: Send + Sync {
+    /// Most recent entries first, optionally for a single investment
+    async fn find_recent(
+        &self,
+        investment_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<QuoteFetchLog>>;
+    async fn create(&self, entry: &QuoteFetchLog) -> Result<i64>;
+    /// Time of the latest successful fetch of any investment
+    async fn last_success(&self) -> Result<Option<DateTime<Utc>>>;
+}
+
+#[async_trait]
+pub trait AuditLogRepository: Send + Sync {
+    /// Most recent entries first
+    async fn find_recent(&self, limit: i64) -> Result<Vec<AuditLogEntry>>;
+}
+
+#[async_trait]
+pub trait ApiTokenRepository: Send + Sync {
+    /// All tokens, oldest first
+    async fn find_all(&self) -> Result<Vec<ApiToken>>;
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>>;
+    async fn count(&self) -> Result<i64>;
+    async fn create(&self, token: &ApiToken) -> Result<i64>;
+    async fn delete(&self, id: i64) -> Result<u64>;
+}
+
+#[async_trait]
+pub trait PriceAlertRepository: Send + Sync {
+    /// All alerts, optionally of a single investment
+    async fn find_all(&self, investment_id: Option<i64>) -> Result<Vec<PriceAlert>>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<PriceAlert>>;
+    async fn find_active(&self, investment_id: i64) -> Result<Vec<PriceAlert>>;
+    async fn create(&self, alert: &PriceAlert) -> Result<i64>;
+    async fn update(&self, id: i64, alert: &PriceAlert) -> Result<u64>;
+    async fn delete(&self, id: i64) -> Result<u64>;
+    /// Store a triggered alert and deactivate its alert in one transaction
+    async fn record_trigger(&self, triggered: &TriggeredAlert) -> Result<i64>;
+    /// Most recent triggers first, optionally for a single investment
+    async fn find_triggered(
+        &self,
+        investment_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<TriggeredAlert>>;
+}
+
+#[async_trait]
+pub trait CorporateEventRepository: Send + Sync {
+    /// Dividends ordered by date, optionally of a single investment or status
+    async fn find_dividends(
+        &self,
+        investment_id: Option<i64>,
+        status: Option<&str>,
+    ) -> Result<Vec<DividendEvent>>;
+    async fn find_dividend(&self, id: i64) -> Result<Option<DividendEvent>>;
+    /// Insert the dividends not stored yet for their (investment, date, source), keeping
+    /// the status of known ones; returns the number inserted
+    async fn add_dividends(&self, dividends: &[DividendEvent]) -> Result<usize>;
+    async fn set_dividend_status(
+        &self,
+        id: i64,
+        status: &str,
+        movement_id: Option<i64>,
+    ) -> Result<()>;
+    /// Splits ordered by date, optionally of a single investment
+    async fn find_splits(&self, investment_id: Option<i64>) -> Result<Vec<SplitEvent>>;
+    /// Insert the splits not stored yet for their (investment, date, source); returns the
+    /// number inserted
+    async fn add_splits(&self, splits: &[SplitEvent]) -> Result<usize>;
+}
+
+#[async_trait]
+pub trait IntradayPriceRepository: Send + Sync {
+    async fn find(&self, investment_id: i64) -> Result<Option<IntradayPrice>>;
+    /// Replace the live quote of the investment
+    async fn upsert(&self, price: &IntradayPrice) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ProviderConfigRepository: Send + Sync {
+    /// Stored API keys ordered by provider
+    async fn find_all(&self) -> Result<Vec<ProviderConfig>>;
+    /// Replace the API key of the provider
+    async fn upsert(&self, config: &ProviderConfig) -> Result<()>;
+    /// Remove the API key of the provider, if stored
+    async fn delete(&self, provider: &str) -> Result<u64>;
+}
+
+#[async_trait]
+pub trait SavingsPlanRepository: Send + Sync {
+    /// All savings plans, optionally of a single investment
+    async fn find_all(&self, investment_id: Option<i64>) -> Result<Vec<SavingsPlan>>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<SavingsPlan>>;
+    async fn create(&self, plan: &SavingsPlan) -> Result<i64>;
+    async fn update(&self, id: i64, plan: &SavingsPlan) -> Result<u64>;
+    async fn delete(&self, id: i64) -> Result<u64>;
+}
+
+#[async_trait]
+pub trait SnapshotRepository: Send + Sync {
+    /// Holdings of all snapshots, optionally of a single date, ordered by date and investment
+    async fn find_all(&self, date: Option<NaiveDate>) -> Result<Vec<SnapshotHolding>>;
+    /// Replace the snapshot of `date` with the holdings in one transaction
+    async fn replace(&self, date: NaiveDate, holdings: &[SnapshotHolding]) -> Result<()>;
+    async fn delete(&self, date: NaiveDate) -> Result<u64>;
+}
+
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    async fn find_all(&self) -> Result<Vec<Tag>>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<Tag>>;
+    /// Tag with the name, ignoring case
+    async fn find_by_name(&self, name: &str) -> Result<Option<Tag>>;
+    async fn create(&self, tag: &Tag) -> Result<i64>;
+    async fn update(&self, id: i64, tag: &Tag) -> Result<u64>;
+    /// Delete a tag and its assignments
+    async fn delete(&self, id: i64) -> Result<u64>;
+    /// Tags assigned to an investment, ordered by name
+    async fn find_by_investment(&self, investment_id: i64) -> Result<Vec<Tag>>;
+    /// Replace the tags of an investment in one transaction
+    async fn set_investment_tags(&self, investment_id: i64, tag_ids: &[i64]) -> Result<()>;
+    /// IDs of the investments with the tag
+    async fn find_investment_ids(&self, tag_id: i64) -> Result<Vec<i64>>;
+}
+
+#[async_trait]
+pub trait FxRateRepository: Send + Sync {
+    async fn find(&self, date: NaiveDate, from: &str, to: &str) -> Result<Option<FxRate>>;
+    async fn find_all(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<FxRate>>;
+    async fn upsert(&self, rate: &FxRate) -> Result<()>;
+}
+
+#[async_trait]
+pub trait DataImportRepository: Send + Sync {
+    /// Import a complete export in a single transaction
+    async fn import(&self, data: &DataExport, mode: ImportMode) -> Result<ImportSummary>;
+}
+
+#[async_trait]
+pub trait ImportProfileRepository: Send + Sync {
+    async fn find_all(&self) -> Result<Vec<ImportProfile>>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<ImportProfile>>;
+    async fn create(&self, profile: &ImportProfile) -> Result<i64>;
+    async fn update(&self, id: i64, profile: &ImportProfile) -> Result<u64>;
+    async fn delete(&self, id: i64) -> Result<u64>;
+}
+
+#[async_trait]
+pub trait DevelopmentRepository: Send + Sync {
+    /// Stored developments in the date range, ordered by investment and date
+    async fn find_all(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>>;
+    /// Replace the stored developments of one investment (`None`: all investments) from
+    /// `from_date` on (`None`: all dates) in one transaction
+    async fn replace(
+        &self,
+        investment_id: Option<i64>,
+        from_date: Option<NaiveDate>,
+        developments: &[Development],
+    ) -> Result<()>;
+}
+
+#[async_trait]
+pub trait HealthRepository: Send + Sync {
+    /// Run `SELECT 1` against the database and read the applied migrations
+    async fn check(&self) -> Result<MigrationStatus>;
+}
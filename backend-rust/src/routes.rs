@@ -1,36 +1,535 @@
 use crate::handlers;
+use crate::models::TokenScope;
 use crate::repository::traits::{
-    ActionTypeRepository, InvestmentPriceRepository, InvestmentRepository, MovementRepository,
-    SettingsRepository,
+    ActionTypeRepository, CorporateEventRepository, FxRateRepository, HealthRepository,
+    ImportProfileRepository, IntradayPriceRepository, InvestmentPriceRepository,
+    InvestmentRepository, MovementRepository, PriceAlertRepository, ProviderConfigRepository,
+    QuoteFetchLogRepository, SavingsPlanRepository, SettingsRepository, TagRepository,
 };
-use crate::services::{PortfolioCalculator, QuoteFetcherService};
+use crate::repository::Repositories;
+use crate::services::quote_fetcher::DEFAULT_FETCH_DAYS;
+use crate::services::quotes::ProviderApiKeys;
+use crate::services::{
+    ApiTokenService, BrokerImportService, CashLedgerService, CorporateEventService,
+    CostBasisCalculator, CurrencyConverter, DashboardService, DataRevision, DataTransferService,
+    DevelopmentCache, DividendService, EmailNotifier, GermanTaxService, HoldingStatsService,
+    IntegrityService, InvestmentSummaryService, PendingDevelopments, PortfolioCalculator,
+    PriceGapService, PriceRecalculationService, QuoteFetchStatusTracker, QuoteFetcherService,
+    ReportService, RiskMetricsService, SavingsPlanService, SnapshotService, SymbolSearchService,
+    WebhookNotifier, XlsxExportService,
+};
+use crate::telemetry;
 use axum::{
-    routing::{get, post},
+    extract::{DefaultBodyLimit, Request, State},
+    http::{header, HeaderName, HeaderValue},
+    middleware::{self, Next},
+    response::Response,
+    routing::{delete, get, post, put},
     Router,
 };
 use std::sync::Arc;
-use tower_http::{cors::CorsLayer, services::ServeDir};
+use std::time::Duration;
+use tower::ServiceBuilder;
+use tower_http::{
+    cors::{Any, CorsLayer},
+    services::ServeDir,
+    trace::TraceLayer,
+};
+
+/// Maximum request body size of data and broker statement imports in MiB
+pub const DEFAULT_IMPORT_BODY_LIMIT_MB: usize = 64;
+
+/// Seconds an API request may take before it is answered with 408
+pub const DEFAULT_REQUEST_TIMEOUT_SECONDS: u64 = 30;
 
 #[derive(Clone)]
 pub struct QuoteFetchState {
     pub investment_repo: Arc<dyn InvestmentRepository>,
     pub price_repo: Arc<dyn InvestmentPriceRepository>,
     pub settings_repo: Arc<dyn SettingsRepository>,
+    pub fetch_log_repo: Arc<dyn QuoteFetchLogRepository>,
+    pub fx_rate_repo: Arc<dyn FxRateRepository>,
+    pub alert_repo: Arc<dyn PriceAlertRepository>,
+    pub event_repo: Arc<dyn CorporateEventRepository>,
+    pub intraday_repo: Arc<dyn IntradayPriceRepository>,
+    pub provider_config_repo: Arc<dyn ProviderConfigRepository>,
+    pub webhooks: WebhookNotifier,
+    /// Emails triggered price alerts if configured
+    pub email: Option<EmailNotifier>,
+    pub api_keys: ProviderApiKeys,
+    /// Days of history requested by a regular fetch
+    pub fetch_days: u32,
 }
 
+#[derive(Clone)]
+pub struct ProviderSettingsState {
+    pub provider_config_repo: Arc<dyn ProviderConfigRepository>,
+    /// Keys of the config file and environment, overridden by the stored ones
+    pub api_keys: ProviderApiKeys,
+}
+
+#[derive(Clone)]
+pub struct PriceAlertState {
+    pub alert_repo: Arc<dyn PriceAlertRepository>,
+    pub investment_repo: Arc<dyn InvestmentRepository>,
+}
+
+#[derive(Clone)]
+pub struct MovementState {
+    pub movement_repo: Arc<dyn MovementRepository>,
+    /// Checks that referenced investments and action types exist
+    pub investment_repo: Arc<dyn InvestmentRepository>,
+    pub action_type_repo: Arc<dyn ActionTypeRepository>,
+    /// Whether sells of more than the holding are rejected
+    pub settings_repo: Arc<dyn SettingsRepository>,
+}
+
+#[derive(Clone)]
+pub struct SavingsPlanState {
+    pub plan_repo: Arc<dyn SavingsPlanRepository>,
+    pub investment_repo: Arc<dyn InvestmentRepository>,
+    pub service: Arc<SavingsPlanService>,
+}
+
+#[derive(Clone)]
+pub struct HealthState {
+    pub health_repo: Arc<dyn HealthRepository>,
+    pub fetch_log_repo: Arc<dyn QuoteFetchLogRepository>,
+}
+
+#[derive(Clone)]
+pub struct ProfileImportState {
+    pub profile_repo: Arc<dyn ImportProfileRepository>,
+    pub import_service: Arc<BrokerImportService>,
+}
+
+#[derive(Clone)]
+pub struct TagState {
+    pub tag_repo: Arc<dyn TagRepository>,
+    pub investment_repo: Arc<dyn InvestmentRepository>,
+}
+
+#[derive(Clone)]
+pub struct CalculatorState {
+    pub calculator: Arc<PortfolioCalculator>,
+    pub tag_repo: Arc<dyn TagRepository>,
+}
+
+#[derive(Clone)]
+pub struct RiskState {
+    pub service: Arc<RiskMetricsService>,
+    pub tag_repo: Arc<dyn TagRepository>,
+}
+
+#[derive(Clone)]
+pub struct HoldingsState {
+    pub service: Arc<HoldingStatsService>,
+    pub tag_repo: Arc<dyn TagRepository>,
+}
+
+#[derive(Clone)]
+pub struct GainsState {
+    pub calculator: Arc<CostBasisCalculator>,
+    pub settings_repo: Arc<dyn SettingsRepository>,
+    pub tag_repo: Arc<dyn TagRepository>,
+}
+
+#[derive(Clone)]
+pub struct BenchmarkState {
+    pub calculator: Arc<PortfolioCalculator>,
+    pub settings_repo: Arc<dyn SettingsRepository>,
+    pub investment_repo: Arc<dyn InvestmentRepository>,
+    pub tag_repo: Arc<dyn TagRepository>,
+}
+
+/// Scope a route group requires from API tokens, see [`handlers::require_token`]
+#[derive(Clone)]
+pub struct TokenGuard {
+    pub service: Arc<ApiTokenService>,
+    /// Scope of every route in the group; `None` requires `read` for GET and HEAD
+    /// requests and `write` for the others
+    pub scope: Option<TokenScope>,
+}
+
+/// Router settings taken from the configuration
+#[derive(Debug, Clone, Default)]
+pub struct RouterSettings {
+    /// Origins allowed to call the API from a browser, any origin if empty
+    pub cors_allowed_origins: Vec<String>,
+    pub api_keys: ProviderApiKeys,
+    /// Emails triggered price alerts, disabled if unset
+    pub email: Option<EmailNotifier>,
+    /// Days of history requested by a regular quote fetch, `DEFAULT_FETCH_DAYS` if unset
+    pub quote_fetch_days: Option<u32>,
+    /// Time limit of API requests, `DEFAULT_REQUEST_TIMEOUT_SECONDS` if unset
+    pub request_timeout: Option<Duration>,
+    /// Body size limit of imports in bytes, `DEFAULT_IMPORT_BODY_LIMIT_MB` if unset
+    pub import_body_limit: Option<usize>,
+}
+
+impl RouterSettings {
+    fn cors_layer(&self) -> CorsLayer {
+        if self.cors_allowed_origins.is_empty() {
+            return CorsLayer::permissive();
+        }
+        // The origins are validated when the configuration is loaded
+        let origins: Vec<HeaderValue> = self
+            .cors_allowed_origins
+            .iter()
+            .filter_map(|origin| HeaderValue::from_str(origin).ok())
+            .collect();
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(Any)
+            .allow_headers(Any)
+    }
+}
+
+/// Prefix of every API version
+pub const API_PREFIX: &str = "/api";
+
+/// Value of the `Deprecation` header of the unversioned paths, the date they were deprecated
+/// (2026-10-16) as structured field date
+const UNVERSIONED_DEPRECATED_SINCE: &str = "@1792108800";
+
+/// Mounts each version of the API under `/api/<version>`, so a breaking change can ship as
+/// a new version while clients still use the previous one
+#[derive(Default)]
+pub struct ApiVersions {
+    versions: Vec<(&'static str, Router)>,
+    deprecated_alias: Option<&'static str>,
+}
+
+impl ApiVersions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `routes` under `/api/<version>`
+    pub fn version(mut self, version: &'static str, routes: Router) -> Self {
+        self.versions.push((version, routes));
+        self
+    }
+
+    /// Also serve a version under the unversioned `/api`, for clients from before the
+    /// versioning. Its responses carry a `Deprecation` header and link the versioned path.
+    pub fn deprecated_alias(mut self, version: &'static str) -> Self {
+        self.deprecated_alias = Some(version);
+        self
+    }
+
+    /// Router with all versions; panics if the alias names a version that was not added
+    pub fn into_router(self) -> Router {
+        let mut router = Router::new();
+        if let Some(alias) = self.deprecated_alias {
+            let (_, routes) = self
+                .versions
+                .iter()
+                .find(|(version, _)| *version == alias)
+                .unwrap_or_else(|| panic!("API version {} of the alias is not mounted", alias));
+            router = router.nest(
+                API_PREFIX,
+                routes
+                    .clone()
+                    .layer(middleware::from_fn_with_state(alias, deprecated_alias)),
+            );
+        }
+        for (version, routes) in self.versions {
+            router = router.nest(&format!("{}/{}", API_PREFIX, version), routes);
+        }
+        router
+    }
+}
+
+/// Mark a response of an unversioned path as deprecated, with the path of `version` as
+/// successor
+async fn deprecated_alias(
+    State(version): State<&'static str>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // The nested router sees the path without the /api prefix
+    let successor = format!(
+        "{}/{}{}",
+        API_PREFIX,
+        version,
+        request
+            .uri()
+            .path_and_query()
+            .map(|path| path.as_str())
+            .unwrap_or_default()
+    );
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(
+        HeaderName::from_static("deprecation"),
+        HeaderValue::from_static(UNVERSIONED_DEPRECATED_SINCE),
+    );
+    if let Ok(link) = HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor))
+    {
+        headers.insert(header::LINK, link);
+    }
+    response
+}
+
+/// Build the API router; `development_cache`, `data_revision` and `pending_developments`
+/// must be change listeners of `repos`. Without `pending_developments` developments are
+/// not stored.
 pub fn create_router(
-    investment_repo: Arc<dyn InvestmentRepository>,
-    movement_repo: Arc<dyn MovementRepository>,
-    investment_price_repo: Arc<dyn InvestmentPriceRepository>,
-    action_type_repo: Arc<dyn ActionTypeRepository>,
-    settings_repo: Arc<dyn SettingsRepository>,
+    repos: Repositories,
+    fetch_status: QuoteFetchStatusTracker,
+    development_cache: DevelopmentCache,
+    data_revision: DataRevision,
+    pending_developments: Option<PendingDevelopments>,
+    settings: RouterSettings,
 ) -> Router {
+    // Movements, prices and developments answer 304 while the data is unchanged
+    let conditional = middleware::from_fn_with_state(data_revision, handlers::conditional_get);
+
+    // Imports read the whole body up to their own limit, which is larger than the default
+    let import_body_limit = settings
+        .import_body_limit
+        .unwrap_or(DEFAULT_IMPORT_BODY_LIMIT_MB * 1024 * 1024);
+    let import_limit = ServiceBuilder::new()
+        .layer(DefaultBodyLimit::max(import_body_limit))
+        .layer(middleware::from_fn_with_state(
+            import_body_limit,
+            handlers::body_limit,
+        ));
+    let request_timeout = settings
+        .request_timeout
+        .unwrap_or(Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECONDS));
+
+    // Create export/import service, which needs every repository
+    let data_transfer = Arc::new(DataTransferService::new(repos.clone()));
+
+    let Repositories {
+        investments: investment_repo,
+        movements: movement_repo,
+        investment_prices: investment_price_repo,
+        action_types: action_type_repo,
+        settings: settings_repo,
+        portfolios: portfolio_repo,
+        cash_movements: cash_movement_repo,
+        quote_fetch_log: fetch_log_repo,
+        audit_log: audit_log_repo,
+        fx_rates: fx_rate_repo,
+        data_import: _,
+        import_profiles: import_profile_repo,
+        developments: development_repo,
+        price_alerts: alert_repo,
+        savings_plans: savings_plan_repo,
+        corporate_events: corporate_event_repo,
+        intraday_prices: intraday_price_repo,
+        provider_configs: provider_config_repo,
+        snapshots: snapshot_repo,
+        tags: tag_repo,
+        api_tokens: api_token_repo,
+        health: health_repo,
+    } = repos;
+
     // Create portfolio calculator service
-    let portfolio_calculator = Arc::new(PortfolioCalculator::new(
+    let mut portfolio_calculator =
+        PortfolioCalculator::new(movement_repo.clone(), investment_price_repo.clone())
+            .with_action_types(action_type_repo.clone())
+            .with_price_sources(settings_repo.clone(), investment_repo.clone())
+            .with_currency_converter(CurrencyConverter::new().with_cache(fx_rate_repo.clone()))
+            .with_cache(development_cache);
+    if let Some(pending) = pending_developments {
+        portfolio_calculator =
+            portfolio_calculator.with_development_store(development_repo, pending);
+    }
+    let portfolio_calculator = Arc::new(portfolio_calculator);
+
+    // Create state for developments and returns, which can be filtered by tag
+    let calculator_state = CalculatorState {
+        calculator: portfolio_calculator.clone(),
+        tag_repo: tag_repo.clone(),
+    };
+
+    // Create state for the benchmark comparison
+    let benchmark_state = BenchmarkState {
+        calculator: portfolio_calculator.clone(),
+        settings_repo: settings_repo.clone(),
+        investment_repo: investment_repo.clone(),
+        tag_repo: tag_repo.clone(),
+    };
+
+    // Create state for tags and their assignment to investments
+    let tag_state = TagState {
+        tag_repo: tag_repo.clone(),
+        investment_repo: investment_repo.clone(),
+    };
+
+    // Create investment summary service
+    let investment_summary = Arc::new(
+        InvestmentSummaryService::new(
+            investment_repo.clone(),
+            movement_repo.clone(),
+            portfolio_calculator.clone(),
+        )
+        .with_action_types(action_type_repo.clone()),
+    );
+
+    // Create state for the movement endpoints
+    let movement_state = MovementState {
+        movement_repo: movement_repo.clone(),
+        investment_repo: investment_repo.clone(),
+        action_type_repo: action_type_repo.clone(),
+        settings_repo: settings_repo.clone(),
+    };
+
+    // Create GraphQL schema on the same repositories as the REST routes
+    let graphql_schema = handlers::build_schema(
+        investment_repo.clone(),
+        movement_state.clone(),
+        investment_price_repo.clone(),
+        investment_summary.clone(),
+    );
+
+    // Create state for the risk metrics
+    let risk_state = RiskState {
+        service: Arc::new(RiskMetricsService::new(portfolio_calculator.clone())),
+        tag_repo: tag_repo.clone(),
+    };
+
+    // Create German tax report service
+    let german_tax = Arc::new(
+        GermanTaxService::new(
+            investment_repo.clone(),
+            movement_repo.clone(),
+            portfolio_calculator.clone(),
+        )
+        .with_action_types(action_type_repo.clone()),
+    );
+
+    // Create state for the savings plans and their projection
+    let savings_plan_state = SavingsPlanState {
+        plan_repo: savings_plan_repo.clone(),
+        investment_repo: investment_repo.clone(),
+        service: Arc::new(
+            SavingsPlanService::new(
+                savings_plan_repo,
+                movement_repo.clone(),
+                portfolio_calculator.clone(),
+            )
+            .with_action_types(action_type_repo.clone()),
+        ),
+    };
+
+    // Create snapshot service to freeze the holdings of a date
+    let snapshots = Arc::new(SnapshotService::new(
+        snapshot_repo,
+        portfolio_calculator.clone(),
+    ));
+
+    // Create state for the holding statistics
+    let holdings_state = HoldingsState {
+        service: Arc::new(
+            HoldingStatsService::new(
+                investment_repo.clone(),
+                movement_repo.clone(),
+                portfolio_calculator.clone(),
+            )
+            .with_action_types(action_type_repo.clone()),
+        ),
+        tag_repo: tag_repo.clone(),
+    };
+
+    // Create spreadsheet export service
+    let xlsx_export = Arc::new(XlsxExportService::new(
+        movement_repo.clone(),
+        investment_repo.clone(),
+        action_type_repo.clone(),
+        portfolio_calculator.clone(),
+    ));
+
+    // Create broker statement import service
+    let broker_import = Arc::new(BrokerImportService::new(
+        investment_repo.clone(),
         movement_repo.clone(),
+        portfolio_repo.clone(),
+        settings_repo.clone(),
+    ));
+
+    // Create state for imports with a user defined profile
+    let profile_import_state = ProfileImportState {
+        profile_repo: import_profile_repo.clone(),
+        import_service: broker_import.clone(),
+    };
+
+    // Create state for cost basis / gains endpoint
+    let gains_state = GainsState {
+        calculator: Arc::new(
+            CostBasisCalculator::new(movement_repo.clone())
+                .with_action_types(action_type_repo.clone()),
+        ),
+        settings_repo: settings_repo.clone(),
+        tag_repo,
+    };
+
+    // Create dividend service
+    let dividend_service = Arc::new(
+        DividendService::new(movement_repo.clone(), settings_repo.clone())
+            .with_action_types(action_type_repo.clone()),
+    );
+
+    // Create service for the dividends and splits reported by quote providers
+    let corporate_events = Arc::new(
+        CorporateEventService::new(
+            corporate_event_repo.clone(),
+            movement_repo.clone(),
+            settings_repo.clone(),
+        )
+        .with_action_types(action_type_repo.clone())
+        .with_fx_rates(fx_rate_repo.clone()),
+    );
+
+    // Create price gap detection service
+    let price_gap_service = Arc::new(
+        PriceGapService::new(movement_repo.clone(), investment_price_repo.clone())
+            .with_action_types(action_type_repo.clone()),
+    );
+
+    // Create price recalculation service for base currency changes
+    let price_recalculation = Arc::new(PriceRecalculationService::new(
+        investment_price_repo.clone(),
+        settings_repo.clone(),
+        fx_rate_repo.clone(),
+    ));
+
+    // Create data integrity checks
+    let integrity = Arc::new(IntegrityService::new(
+        movement_repo.clone(),
+        investment_repo.clone(),
+        action_type_repo.clone(),
+        portfolio_repo.clone(),
         investment_price_repo.clone(),
     ));
 
+    // Create cash ledger service
+    let cash_ledger = Arc::new(
+        CashLedgerService::new(cash_movement_repo.clone(), movement_repo.clone())
+            .with_action_types(action_type_repo.clone()),
+    );
+
+    // Create dashboard service
+    let dashboard = Arc::new(
+        DashboardService::new(portfolio_calculator.clone(), cash_ledger.clone())
+            .with_watchlist(investment_repo.clone(), investment_price_repo.clone()),
+    );
+
+    // Create report service for the weekly summary
+    let reports = Arc::new(
+        ReportService::new(
+            dashboard.clone(),
+            movement_repo.clone(),
+            settings_repo.clone(),
+        )
+        .with_action_types(action_type_repo.clone()),
+    );
+
     // Get base currency from settings (blocking call at startup)
     let base_currency = tokio::task::block_in_place(|| {
         tokio::runtime::Handle::current().block_on(async {
@@ -44,82 +543,449 @@ pub fn create_router(
         })
     });
 
+    // Webhooks for triggered price alerts
+    let webhooks = WebhookNotifier::new(settings_repo.clone());
+
     // Create quote fetcher service
-    let quote_fetcher = Arc::new(QuoteFetcherService::new(
+    let fetch_days = settings.quote_fetch_days.unwrap_or(DEFAULT_FETCH_DAYS);
+    let mut quote_fetcher = QuoteFetcherService::new(
         investment_repo.clone(),
         investment_price_repo.clone(),
         base_currency,
-    ));
+    )
+    .with_fetch_log(fetch_log_repo.clone())
+    .with_fx_rates(fx_rate_repo.clone())
+    .with_price_alerts(alert_repo.clone())
+    .with_webhooks(webhooks.clone())
+    .with_api_keys(settings.api_keys.clone())
+    .with_provider_configs(provider_config_repo.clone())
+    .with_fetch_days(fetch_days);
+    if let Some(email) = &settings.email {
+        quote_fetcher = quote_fetcher.with_email(email.clone());
+    }
+    let quote_fetcher = Arc::new(quote_fetcher);
+
+    let symbol_search = Arc::new(SymbolSearchService::new());
 
     // Create state for quote fetch endpoint
     let quote_fetch_state = QuoteFetchState {
         investment_repo: investment_repo.clone(),
         price_repo: investment_price_repo.clone(),
         settings_repo: settings_repo.clone(),
+        fetch_log_repo: fetch_log_repo.clone(),
+        fx_rate_repo: fx_rate_repo.clone(),
+        alert_repo: alert_repo.clone(),
+        event_repo: corporate_event_repo.clone(),
+        intraday_repo: intraday_price_repo,
+        provider_config_repo: provider_config_repo.clone(),
+        webhooks,
+        email: settings.email.clone(),
+        api_keys: settings.api_keys.clone(),
+        fetch_days,
+    };
+
+    // Create state for the provider API key settings
+    let provider_settings_state = ProviderSettingsState {
+        provider_config_repo,
+        api_keys: settings.api_keys.clone(),
+    };
+
+    // Create state for the price alert endpoints
+    let alert_state = PriceAlertState {
+        alert_repo,
+        investment_repo: investment_repo.clone(),
     };
 
-    Router::new()
-        // Health check
-        .route("/api/health", get(handlers::health))
+    // Create API token service and the scopes of the route groups
+    let api_tokens = Arc::new(ApiTokenService::new(api_token_repo));
+    let data_guard = middleware::from_fn_with_state(
+        TokenGuard {
+            service: api_tokens.clone(),
+            scope: None,
+        },
+        handlers::require_token,
+    );
+    let graphql_guard = middleware::from_fn_with_state(
+        TokenGuard {
+            service: api_tokens.clone(),
+            scope: Some(TokenScope::Read),
+        },
+        handlers::require_token,
+    );
+    let admin_guard = middleware::from_fn_with_state(
+        TokenGuard {
+            service: api_tokens.clone(),
+            scope: Some(TokenScope::Admin),
+        },
+        handlers::require_token,
+    );
+
+    // Create state for the readiness probe
+    let health_state = HealthState {
+        health_repo,
+        fetch_log_repo: fetch_log_repo.clone(),
+    };
+
+    // Portfolio data, readable with a read token and changed with a write token
+    let data_routes = Router::new()
         // Investments
         .route(
-            "/api/investments",
+            "/investments",
             get(handlers::list_investments).post(handlers::create_investment),
         )
         .route(
-            "/api/investments/:id",
+            "/investments/:id",
             get(handlers::get_investment)
                 .put(handlers::update_investment)
                 .delete(handlers::delete_investment),
         )
+        .route(
+            "/investments/:id/merge-into/:target_id",
+            post(handlers::merge_investment),
+        )
         .with_state(investment_repo)
+        .route(
+            "/investments/:id/summary",
+            get(handlers::get_investment_summary),
+        )
+        .route(
+            "/investments/:id/position-history",
+            get(handlers::get_position_history),
+        )
+        .with_state(investment_summary)
+        .route(
+            "/investments/:id/tags",
+            get(handlers::get_investment_tags).put(handlers::set_investment_tags),
+        )
+        // Tags
+        .route("/tags", get(handlers::list_tags).post(handlers::create_tag))
+        .route(
+            "/tags/:id",
+            get(handlers::get_tag)
+                .put(handlers::update_tag)
+                .delete(handlers::delete_tag),
+        )
+        .with_state(tag_state)
         // Movements
         .route(
-            "/api/movements",
-            get(handlers::list_movements).post(handlers::create_movement),
+            "/movements",
+            get(handlers::list_movements)
+                .layer(conditional.clone())
+                .post(handlers::create_movement),
+        )
+        .route("/movements/bulk", post(handlers::create_movements_bulk))
+        .route(
+            "/movements/check-duplicates",
+            post(handlers::check_duplicate_movements),
+        )
+        .route("/movements/transfer", post(handlers::create_transfer))
+        .route(
+            "/movements/by-external-id/:external_id",
+            put(handlers::upsert_movement_by_external_id),
         )
         .route(
-            "/api/movements/:id",
+            "/movements/:id",
             get(handlers::get_movement)
                 .put(handlers::update_movement)
                 .delete(handlers::delete_movement),
         )
-        .with_state(movement_repo)
+        .with_state(movement_state)
+        .route(
+            "/movements/export.xlsx",
+            get(handlers::export_movements_xlsx),
+        )
+        .route(
+            "/developments/export.xlsx",
+            get(handlers::export_developments_xlsx),
+        )
+        .with_state(xlsx_export)
+        // Portfolios
+        .route(
+            "/portfolios",
+            get(handlers::list_portfolios).post(handlers::create_portfolio),
+        )
+        .route(
+            "/portfolios/:id",
+            get(handlers::get_portfolio)
+                .put(handlers::update_portfolio)
+                .delete(handlers::delete_portfolio),
+        )
+        .with_state(portfolio_repo)
+        // Cash
+        .route(
+            "/cash/movements",
+            get(handlers::list_cash_movements).post(handlers::create_cash_movement),
+        )
+        .route(
+            "/cash/movements/:id",
+            delete(handlers::delete_cash_movement),
+        )
+        .with_state(cash_movement_repo)
+        .route("/cash/balance", get(handlers::get_cash_balance))
+        .with_state(cash_ledger)
         // Investment Prices
         .route(
-            "/api/investmentprices",
-            get(handlers::list_investment_prices).post(handlers::create_investment_price),
+            "/investmentprices",
+            get(handlers::list_investment_prices)
+                .layer(conditional.clone())
+                .post(handlers::create_investment_price),
         )
         .route(
-            "/api/investmentprices/upsert",
+            "/investmentprices/upsert",
             post(handlers::upsert_investment_price),
         )
+        .route(
+            "/investmentprices/bulk-upsert",
+            post(handlers::bulk_upsert_investment_prices),
+        )
         .with_state(investment_price_repo)
         // Action Types
-        .route("/api/actiontypes", get(handlers::list_action_types))
-        .route("/api/actiontypes/:id", get(handlers::get_action_type))
+        .route(
+            "/actiontypes",
+            get(handlers::list_action_types).post(handlers::create_action_type),
+        )
+        .route(
+            "/actiontypes/:id",
+            get(handlers::get_action_type)
+                .put(handlers::update_action_type)
+                .delete(handlers::delete_action_type),
+        )
         .with_state(action_type_repo)
         // Settings
         .route(
-            "/api/settings",
+            "/settings",
             get(handlers::get_settings).put(handlers::update_settings),
         )
-        .with_state(settings_repo)
+        .with_state(settings_repo.clone())
+        .route(
+            "/settings/recalculate-prices",
+            post(handlers::recalculate_prices),
+        )
+        .with_state(price_recalculation)
+        // FX rates
+        .route("/fx-rates", get(handlers::list_fx_rates))
+        .with_state(fx_rate_repo)
+        .route(
+            "/import/degiro",
+            post(handlers::import_degiro).layer(import_limit.clone()),
+        )
+        .route(
+            "/import/trade-republic",
+            post(handlers::import_trade_republic).layer(import_limit.clone()),
+        )
+        .with_state(broker_import)
+        .route(
+            "/import-profiles",
+            get(handlers::list_import_profiles).post(handlers::create_import_profile),
+        )
+        .route(
+            "/import-profiles/:id",
+            get(handlers::get_import_profile)
+                .put(handlers::update_import_profile)
+                .delete(handlers::delete_import_profile),
+        )
+        .with_state(import_profile_repo)
+        .route(
+            "/import-profiles/:id/import",
+            post(handlers::import_with_profile).layer(import_limit.clone()),
+        )
+        .with_state(profile_import_state)
         // Developments (Portfolio Calculations)
-        .route("/api/developments", get(handlers::list_developments))
-        .with_state(portfolio_calculator)
+        .route(
+            "/developments",
+            get(handlers::list_developments).layer(conditional.clone()),
+        )
+        .route(
+            "/developments/total",
+            get(handlers::get_total_developments).layer(conditional),
+        )
+        .route(
+            "/developments/recalculate",
+            post(handlers::recalculate_developments),
+        )
+        // Performance
+        .route("/performance/twr", get(handlers::get_time_weighted_return))
+        .with_state(calculator_state)
+        .route("/performance/gains", get(handlers::get_gains))
+        .with_state(gains_state)
+        .route(
+            "/performance/vs-benchmark",
+            get(handlers::get_benchmark_comparison),
+        )
+        .with_state(benchmark_state)
+        .route("/performance/risk", get(handlers::get_risk_metrics))
+        .with_state(risk_state)
+        .route("/performance/holdings", get(handlers::get_holdings))
+        .with_state(holdings_state)
+        // Dashboard
+        .route("/dashboard", get(handlers::get_dashboard))
+        .with_state(dashboard)
+        // Reports
+        .route("/reports/weekly-summary", get(handlers::get_weekly_summary))
+        .route("/reports/periodic", get(handlers::get_periodic_report))
+        .with_state(reports)
+        .route("/reports/tax/de", get(handlers::get_german_tax_report))
+        .with_state(german_tax)
+        // Snapshots
+        .route(
+            "/snapshots",
+            get(handlers::list_snapshots).post(handlers::take_snapshot),
+        )
+        .route("/snapshots/compare", get(handlers::compare_snapshots))
+        .route(
+            "/snapshots/:date",
+            get(handlers::get_snapshot).delete(handlers::delete_snapshot),
+        )
+        .with_state(snapshots)
+        // Dividends
+        .route("/dividends/summary", get(handlers::get_dividend_summary))
+        .with_state(dividend_service)
+        .route("/dividend-events", get(handlers::list_dividend_events))
+        .route(
+            "/dividend-events/:id/confirm",
+            post(handlers::confirm_dividend_event),
+        )
+        .route(
+            "/dividend-events/:id/dismiss",
+            post(handlers::dismiss_dividend_event),
+        )
+        .route("/split-events", get(handlers::list_split_events))
+        .with_state(corporate_events)
         // Quotes
-        .route("/api/quotes/providers", get(handlers::list_providers))
-        .route("/api/quotes/fetch", post(handlers::fetch_quotes))
+        .route("/quotes/providers", get(handlers::list_providers))
+        .route("/quotes/fetch", post(handlers::fetch_quotes))
         .with_state(quote_fetcher)
+        .route("/quotes/fetch-status", get(handlers::get_fetch_status))
+        .with_state(fetch_status)
+        .route("/quotes/fetch-log", get(handlers::get_fetch_log))
+        .with_state(fetch_log_repo)
+        .route("/quotes/gaps", get(handlers::get_price_gaps))
+        .with_state(price_gap_service)
+        .route("/quotes/latest", get(handlers::get_latest_quote))
         // Quote fetch for specific investment
         .route(
-            "/api/quotes/:investment_id/fetch",
+            "/quotes/:investment_id/fetch",
             post(handlers::fetch_latest_quotes),
         )
-        .route("/api/quotes/:investment_id", get(handlers::get_quotes))
+        .route(
+            "/quotes/:investment_id/backfill",
+            post(handlers::backfill_quotes),
+        )
+        .route("/quotes/:investment_id", get(handlers::get_quotes))
+        .route("/investments/:id/enrich", post(handlers::enrich_investment))
+        .route(
+            "/status/integrations",
+            get(handlers::get_integration_status),
+        )
         .with_state(quote_fetch_state)
-        .layer(CorsLayer::permissive())
+        .route("/symbols/search", get(handlers::search_symbols))
+        .with_state(symbol_search)
+        // Price alerts
+        .route(
+            "/alerts",
+            get(handlers::list_alerts).post(handlers::create_alert),
+        )
+        .route("/alerts/triggered", get(handlers::list_triggered_alerts))
+        .route(
+            "/alerts/:id",
+            get(handlers::get_alert)
+                .put(handlers::update_alert)
+                .delete(handlers::delete_alert),
+        )
+        .with_state(alert_state)
+        // Savings plans
+        .route(
+            "/savings-plans",
+            get(handlers::list_savings_plans).post(handlers::create_savings_plan),
+        )
+        .route(
+            "/savings-plans/projection",
+            get(handlers::get_savings_plan_projection),
+        )
+        .route(
+            "/savings-plans/:id",
+            get(handlers::get_savings_plan)
+                .put(handlers::update_savings_plan)
+                .delete(handlers::delete_savings_plan),
+        )
+        .route(
+            "/savings-plans/:id/comparison",
+            get(handlers::get_savings_plan_comparison),
+        )
+        .with_state(savings_plan_state)
+        .route_layer(data_guard);
+
+    // GraphQL facade over investments, movements and prices; queries are sent with POST,
+    // so the handler checks the scope for mutations itself
+    let graphql_routes = Router::new()
+        .route("/graphql", post(handlers::execute_graphql))
+        .with_state(graphql_schema)
+        .route_layer(graphql_guard);
+
+    // Tokens, provider keys, full exports and imports and data fixes need an admin token
+    let admin_routes = Router::new()
+        .route(
+            "/tokens",
+            get(handlers::list_tokens).post(handlers::create_token),
+        )
+        .route("/tokens/:id", delete(handlers::delete_token))
+        .with_state(api_tokens)
+        .route(
+            "/settings/providers",
+            get(handlers::get_provider_settings).put(handlers::update_provider_settings),
+        )
+        .with_state(provider_settings_state)
+        // Export / import
+        .route("/export", get(handlers::export_data))
+        .route(
+            "/import",
+            // Exports with a long price history exceed the default 2 MB body limit
+            post(handlers::import_data).layer(import_limit),
+        )
+        .with_state(data_transfer)
+        .route("/audit-log", get(handlers::get_audit_log))
+        .with_state(audit_log_repo)
+        // Data integrity
+        .route("/admin/integrity", get(handlers::get_integrity_report))
+        .route("/admin/integrity/fix", post(handlers::fix_integrity))
+        .with_state(integrity)
+        .route_layer(admin_guard);
+
+    // Current API, mounted under /api/v1 and the deprecated unversioned /api
+    let api_v1 = Router::new()
+        .route("/health", get(handlers::health))
+        .with_state(health_state.clone())
+        .merge(data_routes)
+        .merge(graphql_routes)
+        .merge(admin_routes)
+        // Amounts, prices and quantities are rounded to the display precision of the settings
+        .layer(middleware::from_fn_with_state(
+            settings_repo,
+            handlers::display_precision,
+        ))
+        .layer(middleware::from_fn_with_state(
+            request_timeout,
+            handlers::request_timeout,
+        ));
+
+    ApiVersions::new()
+        .version("v1", api_v1)
+        .deprecated_alias("v1")
+        .into_router()
+        // Liveness and readiness probes stay unversioned for orchestrators
+        .merge(
+            Router::new()
+                .route("/health/live", get(handlers::health))
+                .route("/health/ready", get(handlers::health_ready))
+                .with_state(health_state),
+        )
+        .layer(settings.cors_layer())
+        // One span per API request with method, route, status and duration
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(telemetry::request_span)
+                .on_response(telemetry::record_response),
+        )
         // Serve static frontend files (must be last to not interfere with API routes)
         .fallback_service(ServeDir::new("static").append_index_html_on_directories(true))
 }
@@ -0,0 +1,93 @@
+use crate::error::Result;
+use crate::models::{ActionEffect, ActionType, CashMovement, Movement, MAX_BUILT_IN_ACTION_ID};
+use crate::repository::traits::ActionTypeRepository;
+use crate::services::cash_ledger::{DEPOSIT_ACTION_ID, WITHDRAWAL_ACTION_ID};
+use crate::services::cost_basis::{
+    OPENING_ACTION_ID, SPLIT_ACTION_ID, TRANSFER_IN_ACTION_ID, TRANSFER_OUT_ACTION_ID,
+};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Effects of all action types, used to calculate movements of custom action types
+#[derive(Debug, Clone, Default)]
+pub struct ActionEffects {
+    effects: HashMap<i64, ActionEffect>,
+}
+
+impl ActionEffects {
+    /// Action types with an unknown effect are left out
+    pub fn new(action_types: &[ActionType]) -> Self {
+        Self {
+            effects: action_types
+                .iter()
+                .filter_map(|t| Some((t.id, t.effect.parse().ok()?)))
+                .collect(),
+        }
+    }
+
+    /// Effects of the stored action types, or none without repository
+    pub async fn load(repo: Option<&Arc<dyn ActionTypeRepository>>) -> Result<Self> {
+        match repo {
+            Some(repo) => Ok(Self::new(&repo.find_all().await?)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn effect(&self, action_id: i64) -> Option<ActionEffect> {
+        self.effects.get(&action_id).copied()
+    }
+
+    /// Give movements the built-in action type with the same effect on the quantity
+    ///
+    /// The calculations only know the built-in types, so a custom type that increases
+    /// the quantity is calculated as buy (1) and one that decreases it as sell (2).
+    /// Cash-only movements keep their type.
+    pub fn normalize(&self, movements: &mut [Movement]) {
+        for movement in movements {
+            let Some(effect) = movement.action_id.and_then(|id| self.effect(id)) else {
+                continue;
+            };
+            let built_in = match effect {
+                ActionEffect::IncreasesQuantity => 1,
+                ActionEffect::DecreasesQuantity => 2,
+                ActionEffect::Split => SPLIT_ACTION_ID,
+                ActionEffect::TransferOut => TRANSFER_OUT_ACTION_ID,
+                ActionEffect::TransferIn => TRANSFER_IN_ACTION_ID,
+                ActionEffect::Opening => OPENING_ACTION_ID,
+                ActionEffect::CashOnly => continue,
+            };
+            movement.action_id = Some(built_in);
+        }
+    }
+
+    /// Cash-only movements of custom action types as deposits and withdrawals
+    ///
+    /// The signed amount less fee and taxes is received if positive and paid otherwise.
+    /// Built-in payouts (3) are left to the cash ledger.
+    pub fn cash_only_movements(&self, movements: &[Movement]) -> Vec<CashMovement> {
+        movements
+            .iter()
+            .filter(|m| {
+                m.action_id.is_some_and(|id| {
+                    id > MAX_BUILT_IN_ACTION_ID && self.effect(id) == Some(ActionEffect::CashOnly)
+                })
+            })
+            .filter_map(|m| {
+                let change = m.amount.unwrap_or_default() - m.costs();
+                Some(CashMovement {
+                    id: m.id,
+                    date: m.date?,
+                    action_id: if change >= Decimal::ZERO {
+                        DEPOSIT_ACTION_ID
+                    } else {
+                        WITHDRAWAL_ACTION_ID
+                    },
+                    amount: change.abs(),
+                    portfolio_id: m.portfolio_id,
+                    description: None,
+                })
+            })
+            .collect()
+    }
+}
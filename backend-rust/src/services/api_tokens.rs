@@ -0,0 +1,125 @@
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::{ApiToken, TokenScope};
+use crate::repository::traits::ApiTokenRepository;
+use chrono::Utc;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Prefix of issued tokens, which makes them easy to recognize in configs and logs
+pub const TOKEN_PREFIX: &str = "pdb_";
+
+/// A newly issued token; `token` is not stored and cannot be shown again
+#[derive(Serialize)]
+pub struct IssuedToken {
+    #[serde(flatten)]
+    pub details: ApiToken,
+    pub token: String,
+}
+
+/// Hex encoded SHA-256 hash under which a token is stored
+///
+/// Tokens are 256 random bits, so they need no salt or slow hash.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// The token of an `Authorization: Bearer` header value; other schemes are ignored
+pub fn bearer_token(header: &str) -> Option<&str> {
+    let (scheme, token) = header.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+/// Issues, lists and checks API tokens
+///
+/// Until the first token is issued the API is open, so existing installations keep working.
+pub struct ApiTokenService {
+    repo: Arc<dyn ApiTokenRepository>,
+}
+
+impl ApiTokenService {
+    pub fn new(repo: Arc<dyn ApiTokenRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list(&self) -> Result<Vec<ApiToken>> {
+        self.repo.find_all().await
+    }
+
+    /// Issue a token with a new random secret
+    ///
+    /// The first token must have the admin scope, so that tokens can still be managed
+    /// once the API requires one.
+    pub async fn issue(&self, name: &str, scope: TokenScope) -> Result<IssuedToken> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidInput(
+                "Token name must not be empty".to_string(),
+            ));
+        }
+        if scope != TokenScope::Admin && self.repo.count().await? == 0 {
+            return Err(AppError::InvalidInput(
+                "The first token must have the admin scope".to_string(),
+            ));
+        }
+
+        let token = format!(
+            "{}{}",
+            TOKEN_PREFIX,
+            hex::encode(rand::random::<[u8; 32]>())
+        );
+        let mut details = ApiToken {
+            id: 0,
+            name: name.to_string(),
+            scope: scope.to_string(),
+            token_hash: hash_token(&token),
+            created_at: Utc::now(),
+        };
+        details.id = self.repo.create(&details).await?;
+        tracing::info!("Issued {} API token {} ({})", scope, details.id, name);
+        Ok(IssuedToken { details, token })
+    }
+
+    pub async fn revoke(&self, id: i64) -> Result<()> {
+        ensure_found(self.repo.delete(id).await?, "API token", id)?;
+        tracing::info!("Revoked API token {}", id);
+        Ok(())
+    }
+
+    /// Scope of the bearer token in `authorization` if it allows `required`
+    ///
+    /// Without stored tokens every request is allowed and no scope is returned.
+    /// Otherwise a missing or unknown token is `Unauthorized` and a token with a
+    /// smaller scope `Forbidden`.
+    pub async fn authorize(
+        &self,
+        authorization: Option<&str>,
+        required: TokenScope,
+    ) -> Result<Option<TokenScope>> {
+        let Some(token) = authorization.and_then(bearer_token) else {
+            if self.repo.count().await? == 0 {
+                return Ok(None);
+            }
+            return Err(AppError::Unauthorized(
+                "An API token is required".to_string(),
+            ));
+        };
+        let stored = self
+            .repo
+            .find_by_hash(&hash_token(token))
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid API token".to_string()))?;
+        // Scopes are validated when a token is issued
+        let scope: TokenScope = stored.scope.parse()?;
+        if !scope.allows(required) {
+            return Err(AppError::Forbidden(format!(
+                "The token has the {} scope, this request needs {}",
+                scope, required
+            )));
+        }
+        Ok(Some(scope))
+    }
+}
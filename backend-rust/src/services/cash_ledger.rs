@@ -0,0 +1,131 @@
+use crate::error::Result;
+use crate::models::{CashMovement, Movement};
+use crate::repository::traits::{ActionTypeRepository, CashMovementRepository, MovementRepository};
+use crate::services::action_effects::ActionEffects;
+use crate::services::display_precision;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Action type of a cash deposit
+pub const DEPOSIT_ACTION_ID: i64 = 4;
+/// Action type of a cash withdrawal
+pub const WITHDRAWAL_ACTION_ID: i64 = 5;
+
+/// Cash position at the end of a day with cash activity
+#[derive(Debug, Clone, Serialize)]
+pub struct CashBalance {
+    pub date: NaiveDate,
+    #[serde(serialize_with = "display_precision::value")]
+    pub change: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub balance: Decimal,
+}
+
+/// Replay cash movements and investment movements into a running cash balance.
+///
+/// Deposits add and withdrawals remove cash. Buys (1) are paid from cash including
+/// their fee and taxes, sells (2) and payouts (3) credit their amount less fee and taxes.
+/// The balance before `start_date` is carried into the first returned day.
+pub fn calculate_cash_balance(
+    cash_movements: &[CashMovement],
+    movements: &[Movement],
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+) -> Vec<CashBalance> {
+    let mut changes: BTreeMap<NaiveDate, Decimal> = BTreeMap::new();
+
+    for cash in cash_movements {
+        let change = match cash.action_id {
+            DEPOSIT_ACTION_ID => cash.amount.abs(),
+            WITHDRAWAL_ACTION_ID => -cash.amount.abs(),
+            _ => continue,
+        };
+        *changes.entry(cash.date).or_default() += change;
+    }
+
+    for movement in movements {
+        let (Some(date), Some(action_id)) = (movement.date, movement.action_id) else {
+            continue;
+        };
+        let amount = movement.amount.unwrap_or_default().abs();
+        let costs = movement.costs();
+        let change = match action_id {
+            1 => -(amount + costs),
+            2 | 3 => amount - costs,
+            _ => continue,
+        };
+        *changes.entry(date).or_default() += change;
+    }
+
+    let mut balance = Decimal::ZERO;
+    let mut balances = Vec::new();
+    for (date, change) in changes {
+        if end_date.map(|end| date > end).unwrap_or(false) {
+            break;
+        }
+        balance += change;
+        if start_date.map(|start| date >= start).unwrap_or(true) {
+            balances.push(CashBalance {
+                date,
+                change,
+                balance,
+            });
+        }
+    }
+
+    balances
+}
+
+pub struct CashLedgerService {
+    cash_repo: Arc<dyn CashMovementRepository>,
+    movement_repo: Arc<dyn MovementRepository>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl CashLedgerService {
+    pub fn new(
+        cash_repo: Arc<dyn CashMovementRepository>,
+        movement_repo: Arc<dyn MovementRepository>,
+    ) -> Self {
+        Self {
+            cash_repo,
+            movement_repo,
+            action_type_repo: None,
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Calculate the cash balance over time, optionally for a single portfolio
+    pub async fn calculate_balance(
+        &self,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<CashBalance>> {
+        let mut cash_movements = self.cash_repo.find_all(portfolio_id).await?;
+        let mut movements = match portfolio_id {
+            Some(id) => self.movement_repo.find_by_portfolio(id).await?,
+            None => self.movement_repo.find_all().await?,
+        };
+
+        // Custom cash-only movements count like deposits and withdrawals
+        let effects = ActionEffects::load(self.action_type_repo.as_ref()).await?;
+        cash_movements.extend(effects.cash_only_movements(&movements));
+        effects.normalize(&mut movements);
+
+        Ok(calculate_cash_balance(
+            &cash_movements,
+            &movements,
+            start_date,
+            end_date,
+        ))
+    }
+}
@@ -0,0 +1,231 @@
+use crate::error::{AppError, Result};
+use crate::models::{DividendEvent, DividendStatus, Movement, SplitEvent};
+use crate::repository::traits::{
+    ActionTypeRepository, CorporateEventRepository, FxRateRepository, MovementRepository,
+    SettingsRepository,
+};
+use crate::services::action_effects::ActionEffects;
+use crate::services::currency_converter::CurrencyConverter;
+use crate::services::display_precision;
+use crate::services::dividends::PAYOUT_ACTION_ID;
+use crate::services::positions::current_quantities;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// A detected dividend with the payout it would book
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DividendPayout {
+    #[serde(flatten)]
+    pub dividend: DividendEvent,
+    /// Quantity held at the end of the day before the ex-dividend date
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub quantity: Decimal,
+    /// Quantity times the amount per share in the base currency, unknown without an
+    /// exchange rate of the ex-dividend date
+    #[serde(serialize_with = "display_precision::value")]
+    pub payout: Option<Decimal>,
+}
+
+/// Quantity of an investment entitled to a dividend with the given ex-dividend date
+pub fn entitled_quantity(
+    movements: &[Movement],
+    investment_id: i64,
+    ex_date: NaiveDate,
+) -> Decimal {
+    let before: Vec<Movement> = movements
+        .iter()
+        .filter(|m| m.date.is_some_and(|date| date < ex_date))
+        .cloned()
+        .collect();
+    current_quantities(&before)
+        .remove(&investment_id)
+        .unwrap_or_default()
+}
+
+/// Turns the dividends detected by the quote fetch into payout movements once confirmed
+pub struct CorporateEventService {
+    event_repo: Arc<dyn CorporateEventRepository>,
+    movement_repo: Arc<dyn MovementRepository>,
+    settings_repo: Arc<dyn SettingsRepository>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+    converter: CurrencyConverter,
+}
+
+impl CorporateEventService {
+    pub fn new(
+        event_repo: Arc<dyn CorporateEventRepository>,
+        movement_repo: Arc<dyn MovementRepository>,
+        settings_repo: Arc<dyn SettingsRepository>,
+    ) -> Self {
+        Self {
+            event_repo,
+            movement_repo,
+            settings_repo,
+            action_type_repo: None,
+            converter: CurrencyConverter::new(),
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Cache exchange rates in the database
+    pub fn with_fx_rates(mut self, fx_rate_repo: Arc<dyn FxRateRepository>) -> Self {
+        self.converter = CurrencyConverter::new().with_cache(fx_rate_repo);
+        self
+    }
+
+    /// Dividends with the payout they would book, optionally of one investment or status
+    pub async fn dividends(
+        &self,
+        investment_id: Option<i64>,
+        status: Option<DividendStatus>,
+    ) -> Result<Vec<DividendPayout>> {
+        let dividends = self
+            .event_repo
+            .find_dividends(investment_id, status.map(|s| s.as_str()))
+            .await?;
+        let movements = self.movements().await?;
+        let base_currency = self.base_currency().await?;
+
+        let mut payouts = Vec::with_capacity(dividends.len());
+        for dividend in dividends {
+            payouts.push(self.payout(dividend, &movements, &base_currency).await?);
+        }
+        Ok(payouts)
+    }
+
+    /// Splits, optionally of one investment
+    pub async fn splits(&self, investment_id: Option<i64>) -> Result<Vec<SplitEvent>> {
+        self.event_repo.find_splits(investment_id).await
+    }
+
+    /// Record the payout of a pending dividend, with `amount` instead of the calculated
+    /// payout if given
+    pub async fn confirm_dividend(
+        &self,
+        id: i64,
+        amount: Option<Decimal>,
+    ) -> Result<DividendPayout> {
+        let dividend = self.pending(id).await?;
+        let movements = self.movements().await?;
+        let base_currency = self.base_currency().await?;
+        let payout = self.payout(dividend, &movements, &base_currency).await?;
+
+        let amount = match (amount, payout.payout) {
+            (Some(amount), _) => amount,
+            (None, Some(payout)) if payout > Decimal::ZERO => payout,
+            (None, Some(_)) => {
+                return Err(AppError::InvalidInput(format!(
+                    "Investment {} was not held before {}; dismiss the dividend or give the amount received",
+                    payout.dividend.investment_id, payout.dividend.date
+                )))
+            }
+            (None, None) => {
+                return Err(AppError::InvalidInput(format!(
+                    "No exchange rate from {} to {} on {}; give the amount received",
+                    payout.dividend.currency.as_deref().unwrap_or("?"),
+                    base_currency,
+                    payout.dividend.date
+                )))
+            }
+        };
+
+        let movement_id = self
+            .movement_repo
+            .create(&Movement {
+                id: 0,
+                date: Some(payout.dividend.date),
+                action_id: Some(PAYOUT_ACTION_ID),
+                investment_id: Some(payout.dividend.investment_id),
+                quantity: None,
+                amount: Some(amount),
+                fee: None,
+                tax: None,
+                portfolio_id: None,
+                external_id: Some(format!("dividend-event-{}", id)),
+            })
+            .await?;
+        self.event_repo
+            .set_dividend_status(id, DividendStatus::Confirmed.as_str(), Some(movement_id))
+            .await?;
+
+        let dividend = self.stored(id).await?;
+        Ok(DividendPayout {
+            dividend,
+            payout: Some(amount),
+            ..payout
+        })
+    }
+
+    /// Mark a pending dividend as not received
+    pub async fn dismiss_dividend(&self, id: i64) -> Result<DividendEvent> {
+        self.pending(id).await?;
+        self.event_repo
+            .set_dividend_status(id, DividendStatus::Dismissed.as_str(), None)
+            .await?;
+        self.stored(id).await
+    }
+
+    async fn payout(
+        &self,
+        dividend: DividendEvent,
+        movements: &[Movement],
+        base_currency: &str,
+    ) -> Result<DividendPayout> {
+        let quantity = entitled_quantity(movements, dividend.investment_id, dividend.date);
+        let per_share = match dividend.currency.as_deref() {
+            Some(currency) => {
+                self.converter
+                    .convert(dividend.amount, currency, base_currency, dividend.date)
+                    .await?
+            }
+            None => Some(dividend.amount),
+        };
+        Ok(DividendPayout {
+            payout: per_share.map(|per_share| per_share * quantity),
+            quantity,
+            dividend,
+        })
+    }
+
+    async fn stored(&self, id: i64) -> Result<DividendEvent> {
+        self.event_repo
+            .find_dividend(id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Dividend", id))
+    }
+
+    async fn pending(&self, id: i64) -> Result<DividendEvent> {
+        let dividend = self.stored(id).await?;
+        if dividend.status != DividendStatus::Pending.as_str() {
+            return Err(AppError::Conflict(format!(
+                "Dividend {} is already {}",
+                id, dividend.status
+            )));
+        }
+        Ok(dividend)
+    }
+
+    async fn movements(&self) -> Result<Vec<Movement>> {
+        let mut movements = self.movement_repo.find_all().await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+        Ok(movements)
+    }
+
+    async fn base_currency(&self) -> Result<String> {
+        Ok(self
+            .settings_repo
+            .get()
+            .await?
+            .map(|s| s.base_currency)
+            .unwrap_or_else(|| "EUR".to_string()))
+    }
+}
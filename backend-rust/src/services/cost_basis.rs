@@ -0,0 +1,436 @@
+use crate::error::{AppError, Result};
+use crate::models::Movement;
+use crate::repository::traits::{ActionTypeRepository, MovementRepository};
+use crate::services::action_effects::ActionEffects;
+use crate::services::display_precision;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Action type of a stock split; the movement quantity is the number of new shares per
+/// old share, e.g. `4` for a 1:4 split or `0.1` for a 10:1 reverse split
+pub const SPLIT_ACTION_ID: i64 = 6;
+
+/// Action type of shares leaving a portfolio for another one
+pub const TRANSFER_OUT_ACTION_ID: i64 = 7;
+
+/// Action type of shares arriving from another portfolio
+pub const TRANSFER_IN_ACTION_ID: i64 = 8;
+
+/// Action type of a position held before the first recorded movement; the amount is its
+/// cost basis, paid before the recorded history
+pub const OPENING_ACTION_ID: i64 = 9;
+
+/// Method used to match sells against earlier buys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostBasisMethod {
+    #[default]
+    Fifo,
+    Lifo,
+    Average,
+}
+
+/// Valid cost basis method identifiers as stored in Settings
+pub const VALID_COST_BASIS_METHODS: &[&str] = &["fifo", "lifo", "average"];
+
+impl CostBasisMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CostBasisMethod::Fifo => "fifo",
+            CostBasisMethod::Lifo => "lifo",
+            CostBasisMethod::Average => "average",
+        }
+    }
+}
+
+impl fmt::Display for CostBasisMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CostBasisMethod {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "fifo" => Ok(CostBasisMethod::Fifo),
+            "lifo" => Ok(CostBasisMethod::Lifo),
+            "average" => Ok(CostBasisMethod::Average),
+            _ => Err(AppError::InvalidInput(format!(
+                "Invalid cost basis method '{}'. Valid methods are: {}",
+                s,
+                VALID_COST_BASIS_METHODS.join(", ")
+            ))),
+        }
+    }
+}
+
+/// A purchased quantity that has not been sold yet
+#[derive(Debug, Clone, Serialize)]
+pub struct Lot {
+    pub date: NaiveDate,
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub quantity: Decimal,
+    #[serde(serialize_with = "display_precision::price")]
+    pub unit_cost: Decimal,
+    /// Total cost of the quantity, kept so taking a whole lot realizes exactly what was paid
+    #[serde(serialize_with = "display_precision::value")]
+    pub cost: Decimal,
+}
+
+impl Lot {
+    fn new(date: NaiveDate, quantity: Decimal, cost: Decimal) -> Self {
+        Self {
+            date,
+            quantity,
+            unit_cost: cost / quantity,
+            cost,
+        }
+    }
+}
+
+/// Gain realized by a single sell movement
+#[derive(Debug, Clone, Serialize)]
+pub struct RealizedGain {
+    pub movement_id: i64,
+    pub date: NaiveDate,
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub quantity: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub proceeds: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub cost: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub gain: Decimal,
+}
+
+/// Cost basis and realized gains of one investment
+#[derive(Debug, Clone, Serialize)]
+pub struct InvestmentGains {
+    pub investment: i64,
+    pub method: CostBasisMethod,
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub quantity: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub cost_basis: Decimal,
+    #[serde(serialize_with = "display_precision::price")]
+    pub average_cost: Option<Decimal>,
+    #[serde(serialize_with = "display_precision::value")]
+    pub realized_gain: Decimal,
+    pub sales: Vec<RealizedGain>,
+    pub open_lots: Vec<Lot>,
+}
+
+/// Open lots of one investment while replaying its movements
+#[derive(Debug, Default)]
+struct LotBook {
+    lots: VecDeque<Lot>,
+    sales: Vec<RealizedGain>,
+}
+
+impl LotBook {
+    fn buy(
+        &mut self,
+        method: CostBasisMethod,
+        date: NaiveDate,
+        quantity: Decimal,
+        amount: Decimal,
+    ) {
+        if quantity <= Decimal::ZERO {
+            return;
+        }
+
+        match method {
+            // Average cost keeps a single pooled lot
+            CostBasisMethod::Average => {
+                if let Some(pool) = self.lots.front_mut() {
+                    pool.cost += amount;
+                    pool.quantity += quantity;
+                    pool.unit_cost = pool.cost / pool.quantity;
+                } else {
+                    self.lots.push_back(Lot::new(date, quantity, amount));
+                }
+            }
+            CostBasisMethod::Fifo | CostBasisMethod::Lifo => {
+                self.lots.push_back(Lot::new(date, quantity, amount))
+            }
+        }
+    }
+
+    /// Multiply the quantity of all open lots by `ratio`, keeping their total cost
+    fn split(&mut self, ratio: Decimal) {
+        if ratio <= Decimal::ZERO {
+            return;
+        }
+        for lot in &mut self.lots {
+            lot.quantity *= ratio;
+            lot.unit_cost /= ratio;
+        }
+    }
+
+    /// Remove up to `quantity` from the lots in the order sells consume them
+    fn take(&mut self, method: CostBasisMethod, quantity: Decimal) -> Vec<Lot> {
+        let mut remaining = quantity;
+        let mut taken_lots = Vec::new();
+
+        while remaining > Decimal::ZERO {
+            let lot = match method {
+                CostBasisMethod::Lifo => self.lots.back_mut(),
+                CostBasisMethod::Fifo | CostBasisMethod::Average => self.lots.front_mut(),
+            };
+            let Some(lot) = lot else { break };
+
+            let taken = remaining.min(lot.quantity);
+            let cost = if taken == lot.quantity {
+                lot.cost
+            } else {
+                lot.cost * taken / lot.quantity
+            };
+            taken_lots.push(Lot {
+                quantity: taken,
+                cost,
+                ..lot.clone()
+            });
+            lot.quantity -= taken;
+            lot.cost -= cost;
+            remaining -= taken;
+
+            if lot.quantity <= Decimal::ZERO {
+                match method {
+                    CostBasisMethod::Lifo => self.lots.pop_back(),
+                    CostBasisMethod::Fifo | CostBasisMethod::Average => self.lots.pop_front(),
+                };
+            }
+        }
+
+        taken_lots
+    }
+
+    /// Add lots transferred from another portfolio, keeping their purchase date and cost
+    fn receive(&mut self, method: CostBasisMethod, lots: Vec<Lot>) {
+        match method {
+            CostBasisMethod::Average => {
+                for lot in lots {
+                    self.buy(method, lot.date, lot.quantity, lot.cost);
+                }
+            }
+            CostBasisMethod::Fifo | CostBasisMethod::Lifo => {
+                self.lots.extend(lots);
+                self.lots.make_contiguous().sort_by_key(|lot| lot.date);
+            }
+        }
+    }
+
+    /// Consume lots for a sell; quantity exceeding the holding is realized at zero cost
+    fn sell(
+        &mut self,
+        method: CostBasisMethod,
+        movement_id: i64,
+        date: NaiveDate,
+        quantity: Decimal,
+        proceeds: Decimal,
+    ) {
+        let cost = self.take(method, quantity).iter().map(|lot| lot.cost).sum();
+
+        self.sales.push(RealizedGain {
+            movement_id,
+            date,
+            quantity,
+            proceeds,
+            cost,
+            gain: proceeds - cost,
+        });
+    }
+
+    fn into_gains(self, investment: i64, method: CostBasisMethod) -> InvestmentGains {
+        let quantity: Decimal = self.lots.iter().map(|l| l.quantity).sum();
+        let cost_basis: Decimal = self.lots.iter().map(|l| l.cost).sum();
+        InvestmentGains {
+            investment,
+            method,
+            quantity,
+            cost_basis,
+            average_cost: (quantity > Decimal::ZERO).then(|| cost_basis / quantity),
+            realized_gain: self.sales.iter().map(|s| s.gain).sum(),
+            sales: self.sales,
+            open_lots: self.lots.into_iter().collect(),
+        }
+    }
+}
+
+/// Replay buy (1), sell (2) and split movements up to `end_date` and match them with `method`
+///
+/// A split on a day applies before the buys and sells of that day. An opening position (9)
+/// is a lot bought on its date for its amount. Transfers between portfolios do not change
+/// the lots of an investment and are ignored.
+pub fn calculate_gains(
+    movements: &[Movement],
+    method: CostBasisMethod,
+    end_date: Option<NaiveDate>,
+) -> Vec<InvestmentGains> {
+    replay(movements, method, end_date, false)
+        .into_iter()
+        .map(|((inv_id, _), book)| book.into_gains(inv_id, method))
+        .collect()
+}
+
+/// Cost basis and realized gains of the lots held in one portfolio
+///
+/// Lots are tracked per portfolio. A transfer out (7) takes lots from its portfolio in
+/// the order a sell would, and the transfer in (8) of the same investment adds them to
+/// the receiving portfolio with their original purchase date and cost, so transfers never
+/// realize a gain. Transfers in apply after the other movements of the day; a transfer in
+/// without matching transfer out brings in shares at zero cost.
+pub fn calculate_portfolio_gains(
+    movements: &[Movement],
+    method: CostBasisMethod,
+    end_date: Option<NaiveDate>,
+    portfolio_id: i64,
+) -> Vec<InvestmentGains> {
+    replay(movements, method, end_date, true)
+        .into_iter()
+        .filter(|((_, portfolio), _)| *portfolio == Some(portfolio_id))
+        .map(|((inv_id, _), book)| book.into_gains(inv_id, method))
+        .collect()
+}
+
+/// Order of movements on the same day: splits first, transfers in last
+fn same_day_rank(movement: &Movement) -> u8 {
+    match movement.action_id {
+        Some(SPLIT_ACTION_ID) => 0,
+        Some(TRANSFER_IN_ACTION_ID) => 2,
+        _ => 1,
+    }
+}
+
+/// Lot books per investment and, if `by_portfolio` is set, per portfolio
+fn replay(
+    movements: &[Movement],
+    method: CostBasisMethod,
+    end_date: Option<NaiveDate>,
+    by_portfolio: bool,
+) -> BTreeMap<(i64, Option<i64>), LotBook> {
+    let mut sorted: Vec<&Movement> = movements
+        .iter()
+        .filter(|m| m.date.is_some() && m.investment_id.is_some())
+        .filter(|m| end_date.map(|end| m.date <= Some(end)).unwrap_or(true))
+        .collect();
+    sorted.sort_by_key(|m| (m.date, same_day_rank(m), m.id));
+
+    let mut books: BTreeMap<(i64, Option<i64>), LotBook> = BTreeMap::new();
+    // Lots that left a portfolio and have not arrived in another one yet
+    let mut in_transit: BTreeMap<i64, LotBook> = BTreeMap::new();
+    for movement in sorted {
+        let (Some(inv_id), Some(date)) = (movement.investment_id, movement.date) else {
+            continue;
+        };
+        let key = (inv_id, movement.portfolio_id.filter(|_| by_portfolio));
+        let quantity = movement.quantity.unwrap_or_default().abs();
+        let amount = movement.amount.unwrap_or_default().abs();
+
+        match movement.action_id {
+            // Fees and taxes are part of the cost of a buy and reduce the proceeds of a sell.
+            // An opening position is one lot bought at its cost basis.
+            Some(1 | OPENING_ACTION_ID) => {
+                books
+                    .entry(key)
+                    .or_default()
+                    .buy(method, date, quantity, amount + movement.costs())
+            }
+            Some(2) => books.entry(key).or_default().sell(
+                method,
+                movement.id,
+                date,
+                quantity,
+                amount - movement.costs(),
+            ),
+            // A split applies to the shares in every portfolio
+            Some(SPLIT_ACTION_ID) => books
+                .range_mut((inv_id, None)..=(inv_id, Some(i64::MAX)))
+                .map(|(_, book)| book)
+                .chain(in_transit.get_mut(&inv_id))
+                .for_each(|book| book.split(quantity)),
+            Some(TRANSFER_OUT_ACTION_ID) if by_portfolio => {
+                let lots = books.entry(key).or_default().take(method, quantity);
+                in_transit
+                    .entry(inv_id)
+                    .or_default()
+                    .receive(CostBasisMethod::Fifo, lots);
+            }
+            Some(TRANSFER_IN_ACTION_ID) if by_portfolio => {
+                let mut lots = in_transit
+                    .entry(inv_id)
+                    .or_default()
+                    .take(CostBasisMethod::Fifo, quantity);
+                let missing = quantity - lots.iter().map(|l| l.quantity).sum::<Decimal>();
+                if missing > Decimal::ZERO {
+                    lots.push(Lot::new(date, missing, Decimal::ZERO));
+                }
+                books.entry(key).or_default().receive(method, lots);
+            }
+            _ => {}
+        }
+    }
+
+    books
+}
+
+pub struct CostBasisCalculator {
+    movement_repo: Arc<dyn MovementRepository>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl CostBasisCalculator {
+    pub fn new(movement_repo: Arc<dyn MovementRepository>) -> Self {
+        Self {
+            movement_repo,
+            action_type_repo: None,
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    async fn movements(&self) -> Result<Vec<Movement>> {
+        let mut movements = self.movement_repo.find_all().await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+        Ok(movements)
+    }
+
+    /// Calculate cost basis and realized gains for all investments
+    pub async fn calculate_gains(
+        &self,
+        method: CostBasisMethod,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<InvestmentGains>> {
+        let movements = self.movements().await?;
+        Ok(calculate_gains(&movements, method, end_date))
+    }
+
+    /// Calculate cost basis and realized gains for the lots held in one portfolio
+    pub async fn calculate_portfolio_gains(
+        &self,
+        portfolio_id: i64,
+        method: CostBasisMethod,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<InvestmentGains>> {
+        let movements = self.movements().await?;
+        Ok(calculate_portfolio_gains(
+            &movements,
+            method,
+            end_date,
+            portfolio_id,
+        ))
+    }
+}
@@ -1,51 +1,292 @@
 use crate::error::{AppError, Result};
-use chrono::NaiveDate;
+use crate::models::FxRate;
+use crate::repository::traits::FxRateRepository;
+use chrono::{Duration, NaiveDate};
 use reqwest::Client;
+use rust_decimal::Decimal;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 struct FrankfurterResponse {
     rates: HashMap<String, f64>,
 }
 
+#[derive(Debug, Deserialize)]
+struct FrankfurterSeriesResponse {
+    rates: BTreeMap<NaiveDate, HashMap<String, f64>>,
+}
+
+/// Days fetched before the first requested date so that weekends and holidays
+/// at the start of a range can fall back to the previous business day
+const SERIES_LOOKBACK_DAYS: i64 = 7;
+
+/// Convert dated values with the most recent rate on or before each date
+///
+/// Values dated before the first available rate cannot be converted and yield `None`.
+pub fn apply_rates(
+    values: &[(NaiveDate, Decimal)],
+    rates: &BTreeMap<NaiveDate, f64>,
+) -> Vec<Option<Decimal>> {
+    values
+        .iter()
+        .map(|(date, value)| {
+            let (_, rate) = rates.range(..=*date).next_back()?;
+            Some(value * Decimal::try_from(*rate).ok()?)
+        })
+        .collect()
+}
+
 pub struct CurrencyConverter {
     client: Client,
+    rate_repo: Option<Arc<dyn FxRateRepository>>,
 }
 
 impl CurrencyConverter {
     pub fn new() -> Self {
         Self {
             client: Client::new(),
+            rate_repo: None,
         }
     }
 
+    /// Look up rates in the given cache first and store every fetched rate there
+    pub fn with_cache(mut self, rate_repo: Arc<dyn FxRateRepository>) -> Self {
+        self.rate_repo = Some(rate_repo);
+        self
+    }
+
     /// Convert amount from one currency to another on a specific date
     /// Uses Frankfurter.app API for historical exchange rates
     pub async fn convert(
         &self,
-        amount: f64,
+        amount: Decimal,
         from_currency: &str,
         to_currency: &str,
         conversion_date: NaiveDate,
-    ) -> Result<Option<f64>> {
+    ) -> Result<Option<Decimal>> {
         // If currencies are the same, no conversion needed
         if from_currency == to_currency {
             return Ok(Some(amount));
         }
 
-        tracing::info!(
-            "Converting {} {} to {} on {}",
+        let Some(rate) = self
+            .get_rate(from_currency, to_currency, conversion_date)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let Ok(decimal_rate) = Decimal::try_from(rate) else {
+            return Ok(None);
+        };
+        let converted = amount * decimal_rate;
+        tracing::debug!(
+            "Converted {} {} to {} {} (rate: {})",
             amount,
             from_currency,
+            converted,
             to_currency,
-            conversion_date
+            rate
+        );
+        Ok(Some(converted))
+    }
+
+    /// Exchange rate from one currency to another on a specific date
+    ///
+    /// Cached rates are used when available, otherwise the rate is fetched and cached.
+    pub async fn get_rate(
+        &self,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+    ) -> Result<Option<f64>> {
+        if from_currency == to_currency {
+            return Ok(Some(1.0));
+        }
+
+        if let Some(repo) = &self.rate_repo {
+            if let Some(cached) = repo.find(date, from_currency, to_currency).await? {
+                return Ok(Some(cached.rate));
+            }
+        }
+
+        let Some(rate) = self.fetch_rate(from_currency, to_currency, date).await? else {
+            return Ok(None);
+        };
+
+        if let Some(repo) = &self.rate_repo {
+            let fx_rate = FxRate {
+                date,
+                from_currency: from_currency.to_string(),
+                to_currency: to_currency.to_string(),
+                rate,
+            };
+            // A failing cache write must not fail the conversion itself
+            if let Err(e) = repo.upsert(&fx_rate).await {
+                tracing::warn!("Failed to cache FX rate: {}", e);
+            }
+        }
+
+        Ok(Some(rate))
+    }
+
+    /// Convert a series of dated values, fetching all rates of the range in one request
+    ///
+    /// Returns the converted values in the order of `values`; `None` marks values
+    /// for which no rate is available.
+    pub async fn convert_series(
+        &self,
+        values: &[(NaiveDate, Decimal)],
+        from_currency: &str,
+        to_currency: &str,
+    ) -> Result<Vec<Option<Decimal>>> {
+        if from_currency == to_currency {
+            return Ok(values.iter().map(|(_, value)| Some(*value)).collect());
+        }
+        let (Some(start), Some(end)) = (
+            values.iter().map(|(date, _)| *date).min(),
+            values.iter().map(|(date, _)| *date).max(),
+        ) else {
+            return Ok(Vec::new());
+        };
+
+        // Use the cache when it already holds a rate for every requested date
+        if let Some(repo) = &self.rate_repo {
+            let cached: BTreeMap<NaiveDate, f64> = repo
+                .find_all(
+                    Some(from_currency),
+                    Some(to_currency),
+                    Some(start),
+                    Some(end),
+                )
+                .await?
+                .into_iter()
+                .map(|r| (r.date, r.rate))
+                .collect();
+            if values.iter().all(|(date, _)| cached.contains_key(date)) {
+                return Ok(apply_rates(values, &cached));
+            }
+        }
+
+        let rates = self
+            .fetch_rate_series(
+                from_currency,
+                to_currency,
+                start - Duration::days(SERIES_LOOKBACK_DAYS),
+                end,
+            )
+            .await?;
+
+        if let Some(repo) = &self.rate_repo {
+            // Cache the rate used for every requested date, including non-business days
+            let mut dates: Vec<NaiveDate> = values.iter().map(|(date, _)| *date).collect();
+            dates.sort();
+            dates.dedup();
+            for date in dates {
+                let Some((_, rate)) = rates.range(..=date).next_back() else {
+                    continue;
+                };
+                let fx_rate = FxRate {
+                    date,
+                    from_currency: from_currency.to_string(),
+                    to_currency: to_currency.to_string(),
+                    rate: *rate,
+                };
+                if let Err(e) = repo.upsert(&fx_rate).await {
+                    tracing::warn!("Failed to cache FX rate: {}", e);
+                }
+            }
+        }
+
+        Ok(apply_rates(values, &rates))
+    }
+
+    /// Request the latest rates from the Frankfurter.app API, for status checks
+    pub async fn probe(&self) -> Result<()> {
+        let response = self
+            .client
+            .get("https://api.frankfurter.app/latest?from=EUR&to=USD")
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Frankfurter request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(AppError::ExternalApi(format!(
+                "Frankfurter returned status: {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fetch the exchange rates of a date range from the Frankfurter.app time-series API
+    async fn fetch_rate_series(
+        &self,
+        from_currency: &str,
+        to_currency: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<BTreeMap<NaiveDate, f64>> {
+        tracing::info!(
+            "Fetching {} to {} exchange rates from {} to {}",
+            from_currency,
+            to_currency,
+            start,
+            end
+        );
+
+        let url = format!(
+            "https://api.frankfurter.app/{}..{}?from={}&to={}",
+            start, end, from_currency, to_currency
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|_e| AppError::CurrencyConversion)?;
+
+        if !response.status().is_success() {
+            tracing::warn!(
+                "Currency conversion failed: {} returned status {}",
+                url,
+                response.status()
+            );
+            return Ok(BTreeMap::new());
+        }
+
+        let data: FrankfurterSeriesResponse = response
+            .json()
+            .await
+            .map_err(|_| AppError::CurrencyConversion)?;
+
+        Ok(data
+            .rates
+            .into_iter()
+            .filter_map(|(date, rates)| rates.get(to_currency).map(|rate| (date, *rate)))
+            .collect())
+    }
+
+    /// Fetch an exchange rate from the Frankfurter.app API
+    async fn fetch_rate(
+        &self,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+    ) -> Result<Option<f64>> {
+        tracing::info!(
+            "Fetching {} to {} exchange rate for {}",
+            from_currency,
+            to_currency,
+            date
         );
 
         // Frankfurter API endpoint
         let url = format!(
             "https://api.frankfurter.app/{}?from={}&to={}",
-            conversion_date, from_currency, to_currency
+            date, from_currency, to_currency
         );
 
         let response = self
@@ -69,25 +310,15 @@ impl CurrencyConverter {
             .await
             .map_err(|_| AppError::CurrencyConversion)?;
 
-        if let Some(&rate) = data.rates.get(to_currency) {
-            let converted = amount * rate;
-            tracing::info!(
-                "Converted {} {} to {} {} (rate: {})",
-                amount,
-                from_currency,
-                converted,
-                to_currency,
-                rate
-            );
-            Ok(Some(converted))
-        } else {
+        let rate = data.rates.get(to_currency).copied();
+        if rate.is_none() {
             tracing::warn!(
                 "No conversion rate found for {} to {}",
                 from_currency,
                 to_currency
             );
-            Ok(None)
         }
+        Ok(rate)
     }
 }
 
@@ -0,0 +1,285 @@
+use crate::error::Result;
+use crate::models::{Investment, InvestmentPrice};
+use crate::repository::traits::{InvestmentPriceRepository, InvestmentRepository};
+use crate::services::display_precision;
+use crate::services::portfolio_calculator::{GrowthPoint, GrowthSeries};
+use crate::services::{CashLedgerService, PortfolioCalculator};
+use chrono::{Duration, Months, NaiveDate};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Number of top gainers and losers unless requested otherwise
+pub const DEFAULT_TOP_MOVERS: usize = 5;
+
+/// Period that changes on the dashboard are calculated over, ending at the latest valuation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DashboardPeriod {
+    #[default]
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl DashboardPeriod {
+    pub const ALL: [DashboardPeriod; 4] = [
+        DashboardPeriod::Day,
+        DashboardPeriod::Week,
+        DashboardPeriod::Month,
+        DashboardPeriod::Year,
+    ];
+
+    /// Day before the period ending on `date`
+    pub fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            DashboardPeriod::Day => date - Duration::days(1),
+            DashboardPeriod::Week => date - Duration::days(7),
+            DashboardPeriod::Month => date.checked_sub_months(Months::new(1)).unwrap_or(date),
+            DashboardPeriod::Year => date.checked_sub_months(Months::new(12)).unwrap_or(date),
+        }
+    }
+}
+
+/// Change of the portfolio over one period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeriodChange {
+    pub period: DashboardPeriod,
+    pub start_date: NaiveDate,
+    /// Change of the total value, including buys and sells
+    #[serde(serialize_with = "display_precision::value")]
+    pub value_change: f64,
+    /// Time-weighted return, which excludes buys and sells
+    pub twr: f64,
+}
+
+/// Time-weighted return of a held investment over the movers period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InvestmentMover {
+    pub investment: i64,
+    #[serde(serialize_with = "display_precision::value")]
+    pub value: f64,
+    pub twr: f64,
+}
+
+/// Latest quote of an investment on the watchlist
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WatchlistQuote {
+    pub investment: i64,
+    pub name: Option<String>,
+    /// Date of the latest price, none without prices
+    pub date: Option<NaiveDate>,
+    #[serde(serialize_with = "display_precision::price")]
+    pub price: Option<Decimal>,
+    /// Relative change from the previous price to the latest one
+    pub change: Option<f64>,
+}
+
+/// Latest quote and its change from the prices of a watchlist investment
+pub fn watchlist_quote(investment: &Investment, prices: &[InvestmentPrice]) -> WatchlistQuote {
+    let mut quotes: Vec<(NaiveDate, Decimal)> = prices
+        .iter()
+        .filter_map(|p| Some((p.date?, p.price?)))
+        .collect();
+    quotes.sort_by_key(|(date, _)| *date);
+
+    let latest = quotes.last().copied();
+    let previous = quotes.iter().rev().nth(1).map(|(_, price)| *price);
+    WatchlistQuote {
+        investment: investment.id,
+        name: investment.name.clone(),
+        date: latest.map(|(date, _)| date),
+        price: latest.map(|(_, price)| price),
+        change: latest
+            .zip(previous)
+            .filter(|(_, previous)| *previous > Decimal::ZERO)
+            .and_then(|((_, price), previous)| (price / previous - Decimal::ONE).to_f64()),
+    }
+}
+
+/// Snapshot for the landing page
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Dashboard {
+    /// Latest valuation date, none without developments
+    pub date: Option<NaiveDate>,
+    #[serde(serialize_with = "display_precision::value")]
+    pub total_value: f64,
+    pub changes: Vec<PeriodChange>,
+    pub top_gainers: Vec<InvestmentMover>,
+    pub top_losers: Vec<InvestmentMover>,
+    #[serde(serialize_with = "display_precision::value")]
+    pub cash_balance: Decimal,
+    /// Quotes of the investments on the watchlist, which are not part of the values above
+    pub watchlist: Vec<WatchlistQuote>,
+}
+
+/// Latest point of a date-sorted series on or before `date`
+fn point_at(points: &[GrowthPoint], date: NaiveDate) -> Option<&GrowthPoint> {
+    points.iter().take_while(|p| p.date <= date).last()
+}
+
+/// Growth from the end of `start` to the end of `end`, before the first point it is 1
+fn twr_between(points: &[GrowthPoint], start: NaiveDate, end: NaiveDate) -> f64 {
+    let growth = |date| point_at(points, date).map(|p| p.growth).unwrap_or(1.0);
+    let start_growth = growth(start);
+    if start_growth > 0.0 {
+        growth(end) / start_growth - 1.0
+    } else {
+        0.0
+    }
+}
+
+/// Build the dashboard from the growth of the portfolio and the cash balance
+///
+/// Gainers and losers are the held investments with the highest positive and lowest
+/// negative return over `movers_period`, at most `top` of each.
+pub fn build_dashboard(
+    growth: &GrowthSeries,
+    cash_balance: Decimal,
+    movers_period: DashboardPeriod,
+    top: usize,
+) -> Dashboard {
+    let Some(latest) = growth.total.last() else {
+        return Dashboard {
+            date: None,
+            total_value: 0.0,
+            changes: Vec::new(),
+            top_gainers: Vec::new(),
+            top_losers: Vec::new(),
+            cash_balance,
+            watchlist: Vec::new(),
+        };
+    };
+    let date = latest.date;
+    let value_at = |day| point_at(&growth.total, day).map(|p| p.value).unwrap_or(0.0);
+
+    let changes = DashboardPeriod::ALL
+        .iter()
+        .map(|&period| {
+            let start_date = period.start(date);
+            PeriodChange {
+                period,
+                start_date,
+                value_change: latest.value - value_at(start_date),
+                twr: twr_between(&growth.total, start_date, date),
+            }
+        })
+        .collect();
+
+    let movers_start = movers_period.start(date);
+    let mut movers: Vec<InvestmentMover> = growth
+        .investments
+        .iter()
+        .filter_map(|(&investment, points)| {
+            let current = point_at(points, date)?;
+            (current.value > 0.0).then(|| InvestmentMover {
+                investment,
+                value: current.value,
+                twr: twr_between(points, movers_start, date),
+            })
+        })
+        .collect();
+    movers.sort_by(|a, b| b.twr.total_cmp(&a.twr));
+
+    let top_gainers = movers
+        .iter()
+        .filter(|m| m.twr > 0.0)
+        .take(top)
+        .cloned()
+        .collect();
+    let top_losers = movers
+        .iter()
+        .rev()
+        .filter(|m| m.twr < 0.0)
+        .take(top)
+        .cloned()
+        .collect();
+
+    Dashboard {
+        date: Some(date),
+        total_value: latest.value,
+        changes,
+        top_gainers,
+        top_losers,
+        cash_balance,
+        watchlist: Vec::new(),
+    }
+}
+
+/// Repositories the watchlist quotes are read from
+struct WatchlistSource {
+    investment_repo: Arc<dyn InvestmentRepository>,
+    price_repo: Arc<dyn InvestmentPriceRepository>,
+}
+
+/// Assembles the dashboard from the portfolio calculator and the cash ledger
+pub struct DashboardService {
+    calculator: Arc<PortfolioCalculator>,
+    cash_ledger: Arc<CashLedgerService>,
+    watchlist: Option<WatchlistSource>,
+}
+
+impl DashboardService {
+    pub fn new(calculator: Arc<PortfolioCalculator>, cash_ledger: Arc<CashLedgerService>) -> Self {
+        Self {
+            calculator,
+            cash_ledger,
+            watchlist: None,
+        }
+    }
+
+    /// Calculator of the developments the dashboard is based on
+    pub fn calculator(&self) -> &Arc<PortfolioCalculator> {
+        &self.calculator
+    }
+
+    /// Show the latest quotes of the investments on the watchlist
+    pub fn with_watchlist(
+        mut self,
+        investment_repo: Arc<dyn InvestmentRepository>,
+        price_repo: Arc<dyn InvestmentPriceRepository>,
+    ) -> Self {
+        self.watchlist = Some(WatchlistSource {
+            investment_repo,
+            price_repo,
+        });
+        self
+    }
+
+    async fn watchlist_quotes(&self) -> Result<Vec<WatchlistQuote>> {
+        let Some(source) = &self.watchlist else {
+            return Ok(Vec::new());
+        };
+        let mut quotes = Vec::new();
+        for investment in source.investment_repo.find_all().await? {
+            if !investment.watchlist || investment.archived {
+                continue;
+            }
+            let prices = source
+                .price_repo
+                .find_all(Some(investment.id), None, None)
+                .await?;
+            quotes.push(watchlist_quote(&investment, &prices));
+        }
+        Ok(quotes)
+    }
+
+    pub async fn dashboard(&self, movers_period: DashboardPeriod, top: usize) -> Result<Dashboard> {
+        let growth = self
+            .calculator
+            .calculate_growth_series(None, None, None)
+            .await?;
+        let cash_balance = self
+            .cash_ledger
+            .calculate_balance(None, None, None)
+            .await?
+            .last()
+            .map(|b| b.balance)
+            .unwrap_or_default();
+        let mut dashboard = build_dashboard(&growth, cash_balance, movers_period, top);
+        dashboard.watchlist = self.watchlist_quotes().await?;
+        Ok(dashboard)
+    }
+}
@@ -0,0 +1,72 @@
+use crate::repository::notifying::{ChangeListener, DataChange};
+use chrono::{DateTime, SubsecRound, Utc};
+use std::sync::{Arc, Mutex};
+
+/// Version of the data behind the read endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Revision {
+    /// Start of the counting, so counters of an earlier server run never match
+    pub started: DateTime<Utc>,
+    /// Number of writes since `started`
+    pub number: u64,
+    /// Time of the last write, `started` before the first one
+    pub modified: DateTime<Utc>,
+}
+
+impl Revision {
+    /// Weak entity tag, since the same data is served as JSON and NDJSON
+    pub fn etag(&self) -> String {
+        format!(
+            "W/\"{:x}-{}\"",
+            self.started.timestamp_nanos_opt().unwrap_or_default(),
+            self.number
+        )
+    }
+}
+
+/// Counts the writes made through the notifying repositories, so clients can skip
+/// downloading data that did not change since their last request
+///
+/// Register it as change listener of the repositories. Changes made directly in the
+/// database are not seen until the server restarts.
+#[derive(Clone)]
+pub struct DataRevision {
+    current: Arc<Mutex<Revision>>,
+}
+
+impl Default for DataRevision {
+    fn default() -> Self {
+        // HTTP dates have whole seconds
+        let now = Utc::now().trunc_subsecs(0);
+        Self {
+            current: Arc::new(Mutex::new(Revision {
+                started: now,
+                number: 0,
+                modified: now,
+            })),
+        }
+    }
+}
+
+impl DataRevision {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn current(&self) -> Revision {
+        *self.lock()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Revision> {
+        // The revision stays consistent even if a holder panicked
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ChangeListener for DataRevision {
+    fn data_changed(&self, _change: DataChange) {
+        let mut revision = self.lock();
+        revision.number += 1;
+        revision.modified = Utc::now().trunc_subsecs(0);
+    }
+}
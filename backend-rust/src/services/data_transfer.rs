@@ -0,0 +1,48 @@
+use crate::error::{AppError, Result};
+use crate::models::{DataExport, ImportMode, ImportSummary, DATA_EXPORT_VERSION};
+use crate::repository::Repositories;
+use chrono::Utc;
+
+/// Export and import of the complete database as one JSON document
+pub struct DataTransferService {
+    repos: Repositories,
+}
+
+impl DataTransferService {
+    pub fn new(repos: Repositories) -> Self {
+        Self { repos }
+    }
+
+    /// Collect all user data into one document
+    ///
+    /// Quote fetch logs and cached exchange rates are left out, as they can be recreated.
+    pub async fn export(&self) -> Result<DataExport> {
+        Ok(DataExport {
+            version: DATA_EXPORT_VERSION,
+            exported_at: Utc::now(),
+            settings: self.repos.settings.get().await?,
+            action_types: self.repos.action_types.find_all().await?,
+            portfolios: self.repos.portfolios.find_all().await?,
+            investments: self.repos.investments.find_all().await?,
+            movements: self.repos.movements.find_all().await?,
+            cash_movements: self.repos.cash_movements.find_all(None).await?,
+            prices: self
+                .repos
+                .investment_prices
+                .find_all(None, None, None)
+                .await?,
+        })
+    }
+
+    /// Restore a document created by [`export`](Self::export) in a single transaction
+    pub async fn import(&self, data: &DataExport, mode: ImportMode) -> Result<ImportSummary> {
+        if data.version != DATA_EXPORT_VERSION {
+            return Err(AppError::InvalidInput(format!(
+                "Unsupported export version {}, expected {}",
+                data.version, DATA_EXPORT_VERSION
+            )));
+        }
+
+        self.repos.data_import.import(data, mode).await
+    }
+}
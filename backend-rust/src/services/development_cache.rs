@@ -0,0 +1,75 @@
+use crate::repository::notifying::{ChangeListener, DataChange};
+use crate::services::portfolio_calculator::Development;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Number of date ranges kept before the cache is emptied
+const MAX_CACHED_RANGES: usize = 32;
+
+/// Portfolio and date range of a development calculation
+pub type DevelopmentKey = (Option<i64>, Option<NaiveDate>, Option<NaiveDate>);
+
+#[derive(Default)]
+struct CacheState {
+    /// Increased on every invalidation, so results calculated from older data are not stored
+    generation: u64,
+    developments: HashMap<DevelopmentKey, Arc<Vec<Development>>>,
+}
+
+/// In-memory cache of calculated developments, emptied whenever movements or prices change
+///
+/// Register it as change listener of the repositories the calculator reads from.
+#[derive(Clone, Default)]
+pub struct DevelopmentCache {
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl DevelopmentCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generation to pass to `insert` for a calculation started now
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    pub fn get(&self, key: &DevelopmentKey) -> Option<Arc<Vec<Development>>> {
+        self.lock().developments.get(key).cloned()
+    }
+
+    /// Store developments unless the data changed since `generation`
+    pub fn insert(
+        &self,
+        key: DevelopmentKey,
+        generation: u64,
+        developments: Arc<Vec<Development>>,
+    ) {
+        let mut state = self.lock();
+        if state.generation != generation {
+            return;
+        }
+        if state.developments.len() >= MAX_CACHED_RANGES {
+            state.developments.clear();
+        }
+        state.developments.insert(key, developments);
+    }
+
+    pub fn invalidate(&self) {
+        let mut state = self.lock();
+        state.generation += 1;
+        state.developments.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        // The state stays consistent even if a holder panicked
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ChangeListener for DevelopmentCache {
+    fn data_changed(&self, _change: DataChange) {
+        self.invalidate();
+    }
+}
@@ -0,0 +1,103 @@
+use crate::repository::notifying::{ChangeListener, DataChange};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Stored developments that are outdated since they were last written
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PendingChanges {
+    /// Every stored development has to be calculated again
+    pub all: bool,
+    /// Earliest outdated date per investment, `None` for its whole history
+    pub investments: HashMap<i64, Option<NaiveDate>>,
+}
+
+impl PendingChanges {
+    pub fn is_empty(&self) -> bool {
+        !self.all && self.investments.is_empty()
+    }
+
+    pub fn add(&mut self, change: DataChange) {
+        if self.all {
+            return;
+        }
+        let Some(investment_id) = change.investment_id else {
+            self.all = true;
+            self.investments.clear();
+            return;
+        };
+        self.investments
+            .entry(investment_id)
+            .and_modify(|from| {
+                *from = match (*from, change.from_date) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    _ => None,
+                }
+            })
+            .or_insert(change.from_date);
+    }
+
+    pub fn merge(&mut self, other: PendingChanges) {
+        if other.all {
+            self.add(DataChange::ALL);
+        }
+        for (investment_id, from_date) in other.investments {
+            self.add(DataChange {
+                investment_id: Some(investment_id),
+                from_date,
+            });
+        }
+    }
+}
+
+/// Collects the changes to movements and prices until the stored developments are updated
+///
+/// Register it as change listener of the repositories the calculator reads from. A new
+/// tracker reports everything as outdated, since the data may have changed while the
+/// server was not running.
+#[derive(Clone)]
+pub struct PendingDevelopments {
+    changes: Arc<Mutex<PendingChanges>>,
+}
+
+impl Default for PendingDevelopments {
+    fn default() -> Self {
+        Self {
+            changes: Arc::new(Mutex::new(PendingChanges {
+                all: true,
+                investments: HashMap::new(),
+            })),
+        }
+    }
+}
+
+impl PendingDevelopments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove and return the changes collected so far
+    pub fn take(&self) -> PendingChanges {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// Put back changes that could not be applied
+    pub fn restore(&self, changes: PendingChanges) {
+        self.lock().merge(changes);
+    }
+
+    pub fn mark_all(&self) {
+        self.lock().add(DataChange::ALL);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PendingChanges> {
+        // The changes stay consistent even if a holder panicked
+        self.changes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ChangeListener for PendingDevelopments {
+    fn data_changed(&self, change: DataChange) {
+        self.lock().add(change);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::error::{AppError, Result};
+use crate::models::Settings;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+
+/// Most decimal places a display precision may have
+pub const MAX_DISPLAY_DECIMALS: u32 = 12;
+
+/// Rule for rounding a number to its display precision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// Ties to the even neighbour, also known as banker's rounding
+    #[default]
+    HalfEven,
+    /// Ties away from zero, as usually taught in school
+    HalfUp,
+    /// Ties towards zero
+    HalfDown,
+    /// Away from zero
+    Up,
+    /// Towards zero, which truncates
+    Down,
+    /// Towards positive infinity
+    Ceiling,
+    /// Towards negative infinity
+    Floor,
+}
+
+/// Valid rounding mode identifiers as stored in Settings
+pub const VALID_ROUNDING_MODES: &[&str] = &[
+    "half_even",
+    "half_up",
+    "half_down",
+    "up",
+    "down",
+    "ceiling",
+    "floor",
+];
+
+impl RoundingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundingMode::HalfEven => "half_even",
+            RoundingMode::HalfUp => "half_up",
+            RoundingMode::HalfDown => "half_down",
+            RoundingMode::Up => "up",
+            RoundingMode::Down => "down",
+            RoundingMode::Ceiling => "ceiling",
+            RoundingMode::Floor => "floor",
+        }
+    }
+
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::HalfDown => RoundingStrategy::MidpointTowardZero,
+            RoundingMode::Up => RoundingStrategy::AwayFromZero,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+            RoundingMode::Ceiling => RoundingStrategy::ToPositiveInfinity,
+            RoundingMode::Floor => RoundingStrategy::ToNegativeInfinity,
+        }
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RoundingMode {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "half_even" => Ok(RoundingMode::HalfEven),
+            "half_up" => Ok(RoundingMode::HalfUp),
+            "half_down" => Ok(RoundingMode::HalfDown),
+            "up" => Ok(RoundingMode::Up),
+            "down" => Ok(RoundingMode::Down),
+            "ceiling" => Ok(RoundingMode::Ceiling),
+            "floor" => Ok(RoundingMode::Floor),
+            _ => Err(AppError::InvalidInput(format!(
+                "Invalid rounding mode '{}'. Valid modes are: {}",
+                s,
+                VALID_ROUNDING_MODES.join(", ")
+            ))),
+        }
+    }
+}
+
+tokio::task_local! {
+    /// Precision of the request whose response is being serialized
+    static CURRENT: DisplayPrecision;
+}
+
+/// Decimal places that amounts in API responses are rounded to; `None` keeps all places
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DisplayPrecision {
+    /// Amounts, fees, costs, market values and balances
+    pub value_decimals: Option<u32>,
+    /// Prices per unit
+    pub price_decimals: Option<u32>,
+    pub quantity_decimals: Option<u32>,
+    pub rounding_mode: RoundingMode,
+}
+
+impl DisplayPrecision {
+    /// Precision configured in the settings; an unknown rounding mode rounds half to even
+    pub fn from_settings(settings: &Settings) -> Self {
+        let decimals = |places: Option<i32>| places.and_then(|p| u32::try_from(p).ok());
+        Self {
+            value_decimals: decimals(settings.value_decimals),
+            price_decimals: decimals(settings.price_decimals),
+            quantity_decimals: decimals(settings.quantity_decimals),
+            rounding_mode: settings.rounding_mode.parse().unwrap_or_default(),
+        }
+    }
+
+    /// Precision of the response being serialized; outside of [`scope`](Self::scope)
+    /// nothing is rounded
+    pub fn current() -> Self {
+        CURRENT.try_with(|precision| *precision).unwrap_or_default()
+    }
+
+    /// Serialize the responses built by `future` with this precision
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Serialize with this precision in `f`, for bodies serialized after the handler returned
+    pub fn sync_scope<R>(self, f: impl FnOnce() -> R) -> R {
+        CURRENT.sync_scope(self, f)
+    }
+
+    /// `value` rounded to `decimals` places, padded with zeros so that every value shows
+    /// all of them, e.g. `10.50`
+    pub fn round(&self, value: Decimal, decimals: Option<u32>) -> Decimal {
+        let Some(decimals) = decimals else {
+            return value;
+        };
+        let mut rounded = value.round_dp_with_strategy(decimals, self.rounding_mode.strategy());
+        rounded.rescale(decimals);
+        rounded
+    }
+}
+
+/// Number in a response that can be rounded to a display precision
+pub trait DisplayRound: Serialize + Sized {
+    fn display_round(&self, precision: &DisplayPrecision, decimals: Option<u32>) -> Self;
+}
+
+impl DisplayRound for Decimal {
+    fn display_round(&self, precision: &DisplayPrecision, decimals: Option<u32>) -> Self {
+        precision.round(*self, decimals)
+    }
+}
+
+impl DisplayRound for f64 {
+    fn display_round(&self, precision: &DisplayPrecision, decimals: Option<u32>) -> Self {
+        decimals
+            .and_then(|_| Decimal::from_f64(*self))
+            .and_then(|value| precision.round(value, decimals).to_f64())
+            .unwrap_or(*self)
+    }
+}
+
+impl<T: DisplayRound> DisplayRound for Option<T> {
+    fn display_round(&self, precision: &DisplayPrecision, decimals: Option<u32>) -> Self {
+        self.as_ref()
+            .map(|value| value.display_round(precision, decimals))
+    }
+}
+
+/// Serialize an amount, fee, cost or balance with the current value precision,
+/// for `#[serde(serialize_with = "...")]`
+pub fn value<T, S>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: DisplayRound,
+    S: Serializer,
+{
+    let precision = DisplayPrecision::current();
+    value
+        .display_round(&precision, precision.value_decimals)
+        .serialize(serializer)
+}
+
+/// Serialize a price per unit with the current price precision
+pub fn price<T, S>(price: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: DisplayRound,
+    S: Serializer,
+{
+    let precision = DisplayPrecision::current();
+    price
+        .display_round(&precision, precision.price_decimals)
+        .serialize(serializer)
+}
+
+/// Serialize a quantity with the current quantity precision
+pub fn quantity<T, S>(quantity: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: DisplayRound,
+    S: Serializer,
+{
+    let precision = DisplayPrecision::current();
+    quantity
+        .display_round(&precision, precision.quantity_decimals)
+        .serialize(serializer)
+}
@@ -0,0 +1,199 @@
+use crate::error::Result;
+use crate::models::Movement;
+use crate::repository::traits::{ActionTypeRepository, MovementRepository, SettingsRepository};
+use crate::services::action_effects::ActionEffects;
+use crate::services::cost_basis::{calculate_gains, CostBasisMethod};
+use crate::services::display_precision;
+use chrono::{Datelike, NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Action type of a dividend or interest payout
+pub const PAYOUT_ACTION_ID: i64 = 3;
+
+/// Payouts received in one month of the year
+#[derive(Debug, Clone, Serialize)]
+pub struct MonthlyDividends {
+    pub month: u32,
+    #[serde(serialize_with = "display_precision::value")]
+    pub amount: Decimal,
+}
+
+/// Payouts received in one calendar year
+#[derive(Debug, Clone, Serialize)]
+pub struct YearlyDividends {
+    pub year: i32,
+    #[serde(serialize_with = "display_precision::value")]
+    pub amount: Decimal,
+}
+
+/// Payouts of one investment in the reported year
+#[derive(Debug, Clone, Serialize)]
+pub struct InvestmentDividends {
+    pub investment: i64,
+    #[serde(serialize_with = "display_precision::value")]
+    pub total: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub cost_basis: Decimal,
+    pub yield_on_cost: Option<f64>,
+    pub months: Vec<MonthlyDividends>,
+}
+
+/// Dividend report for one year, plus the yearly totals of the whole history
+#[derive(Debug, Clone, Serialize)]
+pub struct DividendSummary {
+    pub year: i32,
+    #[serde(serialize_with = "display_precision::value")]
+    pub total: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub cost_basis: Decimal,
+    pub yield_on_cost: Option<f64>,
+    pub months: Vec<MonthlyDividends>,
+    pub investments: Vec<InvestmentDividends>,
+    pub years: Vec<YearlyDividends>,
+}
+
+/// Sum payout amounts into a January..December breakdown
+fn monthly_breakdown<'a>(
+    payouts: impl Iterator<Item = &'a (NaiveDate, Decimal)>,
+) -> Vec<MonthlyDividends> {
+    let mut amounts = [Decimal::ZERO; 12];
+    for (date, amount) in payouts {
+        amounts[date.month0() as usize] += amount;
+    }
+
+    amounts
+        .iter()
+        .enumerate()
+        .map(|(i, &amount)| MonthlyDividends {
+            month: i as u32 + 1,
+            amount,
+        })
+        .collect()
+}
+
+/// Payouts as share of the cost basis, a ratio and therefore a floating point number
+fn yield_on_cost(total: Decimal, cost_basis: Decimal) -> Option<f64> {
+    (cost_basis > Decimal::ZERO)
+        .then(|| (total / cost_basis).to_f64())
+        .flatten()
+}
+
+/// Aggregate payouts (action 3) per investment and month of `year`.
+///
+/// Yield-on-cost divides the payouts of the year by the cost basis of the
+/// holding at the end of that year, calculated with `method`.
+pub fn summarize_dividends(
+    movements: &[Movement],
+    year: i32,
+    method: CostBasisMethod,
+) -> DividendSummary {
+    let mut yearly: BTreeMap<i32, Decimal> = BTreeMap::new();
+    let mut payouts_by_investment: BTreeMap<i64, Vec<(NaiveDate, Decimal)>> = BTreeMap::new();
+
+    for movement in movements {
+        if movement.action_id != Some(PAYOUT_ACTION_ID) {
+            continue;
+        }
+        let (Some(inv_id), Some(date)) = (movement.investment_id, movement.date) else {
+            continue;
+        };
+        let amount = movement.amount.unwrap_or_default().abs();
+
+        *yearly.entry(date.year()).or_default() += amount;
+        if date.year() == year {
+            payouts_by_investment
+                .entry(inv_id)
+                .or_default()
+                .push((date, amount));
+        }
+    }
+
+    let year_end = NaiveDate::from_ymd_opt(year, 12, 31);
+    let cost_basis_by_investment: HashMap<i64, Decimal> =
+        calculate_gains(movements, method, year_end)
+            .into_iter()
+            .map(|g| (g.investment, g.cost_basis))
+            .collect();
+
+    let investments: Vec<InvestmentDividends> = payouts_by_investment
+        .iter()
+        .map(|(&investment, payouts)| {
+            let total = payouts.iter().map(|(_, amount)| amount).sum();
+            let cost_basis = cost_basis_by_investment
+                .get(&investment)
+                .copied()
+                .unwrap_or_default();
+            InvestmentDividends {
+                investment,
+                total,
+                cost_basis,
+                yield_on_cost: yield_on_cost(total, cost_basis),
+                months: monthly_breakdown(payouts.iter()),
+            }
+        })
+        .collect();
+
+    let total = investments.iter().map(|i| i.total).sum();
+    // Only holdings that paid out contribute to the portfolio yield-on-cost
+    let cost_basis = investments.iter().map(|i| i.cost_basis).sum();
+
+    DividendSummary {
+        year,
+        total,
+        cost_basis,
+        yield_on_cost: yield_on_cost(total, cost_basis),
+        months: monthly_breakdown(payouts_by_investment.values().flatten()),
+        investments,
+        years: yearly
+            .into_iter()
+            .map(|(year, amount)| YearlyDividends { year, amount })
+            .collect(),
+    }
+}
+
+pub struct DividendService {
+    movement_repo: Arc<dyn MovementRepository>,
+    settings_repo: Arc<dyn SettingsRepository>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl DividendService {
+    pub fn new(
+        movement_repo: Arc<dyn MovementRepository>,
+        settings_repo: Arc<dyn SettingsRepository>,
+    ) -> Self {
+        Self {
+            movement_repo,
+            settings_repo,
+            action_type_repo: None,
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Dividend summary for `year` (default: current year) using the configured cost basis method
+    pub async fn summary(&self, year: Option<i32>) -> Result<DividendSummary> {
+        let year = year.unwrap_or_else(|| Utc::now().year());
+        let method = self
+            .settings_repo
+            .get()
+            .await?
+            .map(|s| s.cost_basis_method.parse())
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut movements = self.movement_repo.find_all().await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+        Ok(summarize_dividends(&movements, year, method))
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::Result;
+use crate::models::Movement;
+use crate::repository::traits::MovementRepository;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Largest difference in quantity and amount for movements to count as duplicates
+pub const DEFAULT_DUPLICATE_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// A movement that matches one or more stored movements
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateMovement {
+    /// Position of the movement in the checked list
+    pub index: usize,
+    /// IDs of the stored movements it matches
+    pub existing_ids: Vec<i64>,
+}
+
+/// Whether two movements of the same investment, date and action are duplicates
+fn is_duplicate(a: &Movement, b: &Movement, tolerance: Decimal) -> bool {
+    let close = |x: Option<Decimal>, y: Option<Decimal>| {
+        (x.unwrap_or_default().abs() - y.unwrap_or_default().abs()).abs() <= tolerance
+    };
+    close(a.quantity, b.quantity) && close(a.amount, b.amount)
+}
+
+/// Finds movements that were already recorded, e.g. when a statement is imported twice
+pub struct DuplicateDetector {
+    movement_repo: Arc<dyn MovementRepository>,
+}
+
+impl DuplicateDetector {
+    pub fn new(movement_repo: Arc<dyn MovementRepository>) -> Self {
+        Self { movement_repo }
+    }
+
+    /// Compare movements with the stored ones
+    ///
+    /// A movement is a duplicate of a stored movement with the same investment, date
+    /// and action whose quantity and amount differ by at most `tolerance`. Signs are
+    /// ignored, as sells are recorded with positive or negative quantities. Movements
+    /// without date are never duplicates. Only movements with a match are returned.
+    pub async fn find_duplicates(
+        &self,
+        movements: &[Movement],
+        tolerance: Decimal,
+    ) -> Result<Vec<DuplicateMovement>> {
+        let dates = movements.iter().filter_map(|m| m.date);
+        let (Some(start), Some(end)) = (dates.clone().min(), dates.max()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut stored: HashMap<(Option<i64>, NaiveDate, Option<i64>), Vec<Movement>> =
+            HashMap::new();
+        for movement in self
+            .movement_repo
+            .find_filtered(None, None, Some(start), Some(end))
+            .await?
+        {
+            if let Some(date) = movement.date {
+                stored
+                    .entry((movement.investment_id, date, movement.action_id))
+                    .or_default()
+                    .push(movement);
+            }
+        }
+
+        Ok(movements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, movement)| {
+                let key = (movement.investment_id, movement.date?, movement.action_id);
+                let existing_ids: Vec<i64> = stored
+                    .get(&key)?
+                    .iter()
+                    .filter(|m| is_duplicate(movement, m, tolerance))
+                    .map(|m| m.id)
+                    .collect();
+                (!existing_ids.is_empty()).then_some(DuplicateMovement {
+                    index,
+                    existing_ids,
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,211 @@
+use crate::config::{EmailConfig, SmtpTls};
+use crate::error::{AppError, Result};
+use crate::services::reports::{ReportService, WeeklySummary};
+use crate::services::webhooks::PriceAlertNotification;
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Subject and plain text body of a notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub subject: String,
+    pub body: String,
+}
+
+/// Email for a triggered price alert
+pub fn alert_email(notification: &PriceAlertNotification) -> Email {
+    let alert = &notification.alert;
+    let name = notification
+        .investment_name
+        .clone()
+        .unwrap_or_else(|| format!("Investment {}", alert.investment_id));
+    let movement = match alert.direction.as_str() {
+        "below" => "fell below",
+        _ => "rose above",
+    };
+
+    Email {
+        subject: format!("Price alert: {} {} {:.2}", name, movement, alert.threshold),
+        body: format!(
+            "{} {} the threshold of {:.2}.\n\nPrice: {:.2} on {}\nTriggered at: {}\n\nThe alert is inactive now; reactivate it to be notified again.\n",
+            name,
+            movement,
+            alert.threshold,
+            alert.price,
+            alert.price_date,
+            alert.triggered_at.format("%Y-%m-%d %H:%M UTC"),
+        ),
+    }
+}
+
+/// Email for the weekly portfolio summary
+pub fn weekly_summary_email(summary: &WeeklySummary) -> Email {
+    let currency = &summary.base_currency;
+    Email {
+        subject: format!(
+            "Weekly portfolio summary: {:.2} {} ({:+.2}%)",
+            summary.total_value,
+            currency,
+            summary.twr * 100.0
+        ),
+        body: format!(
+            "Portfolio summary for the week ending {}\n\n\
+             Total value:        {:.2} {currency}\n\
+             Change:             {:+.2} {currency} ({:+.2}%)\n\
+             Dividends received: {:.2} {currency}\n\
+             Cash balance:       {:.2} {currency}\n\n\
+             The change includes buys and sells, the percentage is the time-weighted return.\n",
+            summary.end_date,
+            summary.total_value,
+            summary.value_change,
+            summary.twr * 100.0,
+            summary.dividends,
+            summary.cash_balance,
+        ),
+    }
+}
+
+/// Sends plain text emails to the configured recipients over SMTP
+#[derive(Debug, Clone)]
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailNotifier {
+    pub fn new(config: &EmailConfig) -> Result<Self> {
+        let host = config.smtp_host.as_str();
+        let builder = match config.smtp_tls {
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                host,
+            )),
+        }
+        .map_err(|e| AppError::InvalidInput(format!("Invalid SMTP host '{}': {}", host, e)))?;
+
+        let mut builder = builder.port(config.smtp_port).timeout(Some(SMTP_TIMEOUT));
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        let mailbox = |address: &str| {
+            address.parse::<Mailbox>().map_err(|e| {
+                AppError::InvalidInput(format!("Invalid email address '{}': {}", address, e))
+            })
+        };
+
+        Ok(Self {
+            transport: builder.build(),
+            from: mailbox(&config.from)?,
+            to: config
+                .to
+                .iter()
+                .map(|address| mailbox(address))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Send an email to all recipients and wait for the SMTP server to accept it
+    pub async fn send(&self, email: &Email) -> Result<()> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(email.subject.as_str())
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        let message = builder
+            .body(email.body.clone())
+            .map_err(|e| AppError::Internal(e.into()))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("SMTP: {}", e)))?;
+        Ok(())
+    }
+
+    /// Send an email and log a failure instead of returning it
+    pub async fn send_logged(&self, email: &Email) {
+        if let Err(e) = self.send(email).await {
+            tracing::warn!("Failed to send email '{}': {}", email.subject, e);
+        }
+    }
+
+    /// Send an email in the background, so a slow SMTP server does not hold up the caller
+    pub fn notify(&self, email: Email) {
+        let notifier = self.clone();
+        tokio::spawn(async move { notifier.send_logged(&email).await });
+    }
+}
+
+/// Background task that emails the weekly summary according to a cron expression
+/// (with seconds, e.g. `0 0 8 * * Mon`)
+pub struct WeeklySummaryScheduler {
+    schedule: Schedule,
+    expression: String,
+    reports: Arc<ReportService>,
+    email: EmailNotifier,
+}
+
+impl WeeklySummaryScheduler {
+    pub fn new(
+        expression: &str,
+        reports: Arc<ReportService>,
+        email: EmailNotifier,
+    ) -> Result<Self> {
+        let schedule = Schedule::from_str(expression).map_err(|e| {
+            AppError::InvalidInput(format!("Invalid cron expression '{}': {}", expression, e))
+        })?;
+
+        Ok(Self {
+            schedule,
+            expression: expression.to_string(),
+            reports,
+            email,
+        })
+    }
+
+    /// Next scheduled run after now
+    pub fn next_run(&self) -> Option<DateTime<Utc>> {
+        self.schedule.upcoming(Utc).next()
+    }
+
+    /// Spawn the scheduler loop on the tokio runtime
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        tracing::info!("Weekly summary email scheduled with '{}'", self.expression);
+
+        while let Some(next) = self.next_run() {
+            let wait = (next - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            if let Err(e) = self.run_once().await {
+                tracing::error!("Failed to send the weekly summary email: {}", e);
+            }
+        }
+
+        tracing::warn!("Weekly summary schedule has no upcoming runs, scheduler stopped");
+    }
+
+    /// Generate the summary and email it
+    pub async fn run_once(&self) -> Result<()> {
+        let summary = self.reports.weekly_summary().await?;
+        self.email.send(&weekly_summary_email(&summary)).await
+    }
+}
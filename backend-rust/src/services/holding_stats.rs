@@ -0,0 +1,209 @@
+use crate::error::Result;
+use crate::models::Movement;
+use crate::repository::traits::{ActionTypeRepository, InvestmentRepository, MovementRepository};
+use crate::services::action_effects::ActionEffects;
+use crate::services::cost_basis::OPENING_ACTION_ID;
+use crate::services::display_precision;
+use crate::services::investment_summary::summarize_investment;
+use crate::services::portfolio_calculator::Development;
+use crate::services::PortfolioCalculator;
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+/// Average number of days per year, used to annualize returns
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// Holding period and returns of one investment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HoldingStats {
+    pub investment: i64,
+    pub name: Option<String>,
+    pub first_purchase_date: NaiveDate,
+    /// Latest valuation while held, the date of the last movement once sold
+    pub end_date: NaiveDate,
+    pub holding_days: i64,
+    /// Whether the investment is still held at `end_date`
+    pub open: bool,
+    /// Time-weighted return over the holding period
+    pub twr: f64,
+    /// Time-weighted return per year (CAGR), only for holdings of at least a year
+    pub annualized_return: Option<f64>,
+    /// `market value + sold + dividends - invested - fees - taxes`
+    #[serde(serialize_with = "display_precision::value")]
+    pub gain: Decimal,
+    /// Gain as a fraction of the amount invested in all holdings, so that the contributions
+    /// add up to the portfolio return
+    pub contribution: Option<f64>,
+}
+
+/// Holding statistics of all investments that were bought
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HoldingsReport {
+    #[serde(serialize_with = "display_precision::value")]
+    pub total_invested: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub total_gain: Decimal,
+    /// `total_gain / total_invested`, if anything was invested
+    pub total_return: Option<f64>,
+    pub holdings: Vec<HoldingStats>,
+}
+
+/// Annualize a return over a number of days, `None` for periods shorter than a year
+///
+/// Shorter periods are not extrapolated, as a few good days would show as an
+/// unrealistic yearly return.
+pub fn annualize(total_return: f64, days: i64) -> Option<f64> {
+    if (days as f64) < DAYS_PER_YEAR.floor() || total_return <= -1.0 {
+        return None;
+    }
+    Some((1.0 + total_return).powf(DAYS_PER_YEAR / days as f64) - 1.0)
+}
+
+/// Calculate the holding statistics from normalized movements, the latest development
+/// and the time-weighted return of every investment
+pub fn calculate_holdings(
+    investments: &[(i64, Option<String>)],
+    movements: &[Movement],
+    latest: &HashMap<i64, &Development>,
+    twr: &HashMap<i64, f64>,
+) -> HoldingsReport {
+    let mut by_investment: BTreeMap<i64, Vec<&Movement>> = BTreeMap::new();
+    for movement in movements {
+        if let Some(investment) = movement.investment_id {
+            by_investment.entry(investment).or_default().push(movement);
+        }
+    }
+
+    let mut holdings = Vec::new();
+    let mut total_invested = Decimal::ZERO;
+    let mut total_gain = Decimal::ZERO;
+    for (investment, name) in investments {
+        let Some(own) = by_investment.get(investment) else {
+            continue;
+        };
+        let Some(first_purchase_date) = own
+            .iter()
+            .filter(|m| matches!(m.action_id, Some(1 | OPENING_ACTION_ID)))
+            .filter_map(|m| m.date)
+            .min()
+        else {
+            continue;
+        };
+
+        let own: Vec<Movement> = own.iter().map(|&m| m.clone()).collect();
+        let development = latest.get(investment).copied();
+        let summary = summarize_investment(*investment, name.clone(), &own, development);
+        let open = summary.quantity > Decimal::ZERO;
+        let last_movement = own.iter().filter_map(|m| m.date).max();
+        let end_date = match (open, development) {
+            (true, Some(dev)) => dev.date,
+            _ => last_movement.unwrap_or(first_purchase_date),
+        }
+        .max(first_purchase_date);
+        let gain = summary.market_value + summary.total_sold + summary.dividends_received
+            - summary.total_invested
+            - summary.fees_paid
+            - summary.taxes_paid;
+        let holding_days = (end_date - first_purchase_date).num_days();
+        let twr = twr.get(investment).copied().unwrap_or(0.0);
+
+        total_invested += summary.total_invested;
+        total_gain += gain;
+        holdings.push(HoldingStats {
+            investment: *investment,
+            name: name.clone(),
+            first_purchase_date,
+            end_date,
+            holding_days,
+            open,
+            twr,
+            annualized_return: annualize(twr, holding_days),
+            gain,
+            contribution: None,
+        });
+    }
+
+    if total_invested > Decimal::ZERO {
+        for holding in &mut holdings {
+            holding.contribution = (holding.gain / total_invested).to_f64();
+        }
+    }
+
+    HoldingsReport {
+        total_invested,
+        total_gain,
+        total_return: (total_invested > Decimal::ZERO)
+            .then(|| (total_gain / total_invested).to_f64())
+            .flatten(),
+        holdings,
+    }
+}
+
+/// Calculates holding periods and returns per investment
+pub struct HoldingStatsService {
+    investment_repo: Arc<dyn InvestmentRepository>,
+    movement_repo: Arc<dyn MovementRepository>,
+    calculator: Arc<PortfolioCalculator>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl HoldingStatsService {
+    pub fn new(
+        investment_repo: Arc<dyn InvestmentRepository>,
+        movement_repo: Arc<dyn MovementRepository>,
+        calculator: Arc<PortfolioCalculator>,
+    ) -> Self {
+        Self {
+            investment_repo,
+            movement_repo,
+            calculator,
+            action_type_repo: None,
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Statistics of every investment that was bought, only of the given investments if
+    /// `investments` is set; contributions then refer to those investments
+    pub async fn holdings(&self, investments: Option<&HashSet<i64>>) -> Result<HoldingsReport> {
+        let names: Vec<(i64, Option<String>)> = self
+            .investment_repo
+            .find_all()
+            .await?
+            .into_iter()
+            .filter(|inv| investments.is_none_or(|ids| ids.contains(&inv.id)))
+            .map(|inv| (inv.id, inv.name))
+            .collect();
+
+        let mut movements = self.movement_repo.find_all().await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+
+        // Developments are ordered by date, so the last one is the current position
+        let developments = self.calculator.calculate_developments(None, None).await?;
+        let mut latest = HashMap::new();
+        for dev in &developments {
+            latest.insert(dev.investment, dev);
+        }
+
+        let twr = self
+            .calculator
+            .calculate_time_weighted_return(investments, None, None, None)
+            .await?
+            .investments
+            .into_iter()
+            .map(|r| (r.investment, r.twr))
+            .collect();
+
+        Ok(calculate_holdings(&names, &movements, &latest, &twr))
+    }
+}
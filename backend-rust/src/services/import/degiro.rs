@@ -0,0 +1,213 @@
+use super::statement_parser::{
+    parse_number, BrokerTransaction, StatementParser, BUY_ACTION_ID, SELL_ACTION_ID,
+};
+use crate::error::{AppError, Result};
+use crate::isin;
+use chrono::NaiveDate;
+use csv::StringRecord;
+use rust_decimal::Decimal;
+
+/// Date format of Degiro exports, e.g. `28-04-2023`
+const DATE_FORMAT: &str = "%d-%m-%Y";
+
+/// Parser for the transaction export of Degiro ("Transactions" → "Export" → CSV)
+///
+/// Columns are located by their English header names, so both the older layout,
+/// where currencies follow in unnamed columns, and the newer one, with the
+/// currency in the header (e.g. `Value EUR`), are supported.
+#[derive(Debug, Default, Clone)]
+pub struct DegiroParser;
+
+impl DegiroParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Where the currency of a value column is given
+#[derive(Debug)]
+enum Currency {
+    /// In the header, e.g. `Value EUR`
+    Header(String),
+    /// In the (unnamed) column with this index
+    Column(usize),
+}
+
+impl Currency {
+    fn get(&self, record: &StringRecord) -> Option<String> {
+        match self {
+            Currency::Header(currency) => Some(currency.clone()),
+            Currency::Column(index) => record
+                .get(*index)
+                .map(|c| c.trim().to_uppercase())
+                .filter(|c| !c.is_empty()),
+        }
+    }
+}
+
+/// Column indices of the fields used for movements
+#[derive(Debug)]
+struct Columns {
+    date: usize,
+    product: usize,
+    isin: usize,
+    quantity: usize,
+    local_value: usize,
+    value: usize,
+    value_currency: Currency,
+    exchange_rate: Option<usize>,
+    fees: Vec<usize>,
+}
+
+impl Columns {
+    fn from_headers(headers: &StringRecord) -> Result<Self> {
+        let names: Vec<String> = headers
+            .iter()
+            .map(|h| h.trim().trim_start_matches('\u{feff}').to_lowercase())
+            .collect();
+        let find = |name: &str| names.iter().position(|h| h == name);
+        let require = |name: &str| {
+            find(name).ok_or_else(|| {
+                AppError::InvalidInput(format!(
+                    "Missing column '{}' in Degiro export, expected the English CSV export of transactions",
+                    name
+                ))
+            })
+        };
+        let date = require("date")?;
+        let product = require("product")?;
+        let isin = require("isin")?;
+        let quantity = require("quantity")?;
+        let local_value = require("local value")?;
+
+        let (value, value_currency) = match find("value") {
+            Some(index) => (index, Currency::Column(index + 1)),
+            None => names
+                .iter()
+                .enumerate()
+                .find_map(|(index, h)| {
+                    let currency = h.strip_prefix("value ")?;
+                    Some((index, Currency::Header(currency.trim().to_uppercase())))
+                })
+                .ok_or_else(|| {
+                    AppError::InvalidInput("Missing column 'value' in Degiro export".to_string())
+                })?,
+        };
+
+        // "Transaction and/or third party fees" (older exports: "Transaction costs")
+        // and, since 2023, "AutoFX Fee" for the currency exchange
+        let fees = names
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| h.starts_with("transaction") || h.starts_with("autofx"))
+            .map(|(index, _)| index)
+            .collect();
+
+        Ok(Self {
+            date,
+            product,
+            isin,
+            quantity,
+            local_value,
+            value,
+            value_currency,
+            exchange_rate: find("exchange rate"),
+            fees,
+        })
+    }
+}
+
+impl StatementParser for DegiroParser {
+    fn parse(&self, content: &str) -> Result<Vec<BrokerTransaction>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(content.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid Degiro export: {}", e)))?;
+        let columns = Columns::from_headers(headers)?;
+
+        let mut transactions = Vec::new();
+        for record in reader.records() {
+            let record = record
+                .map_err(|e| AppError::InvalidInput(format!("Invalid Degiro export: {}", e)))?;
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+
+            let line = record.position().map(|p| p.line()).unwrap_or_default();
+            let transaction = parse_record(&columns, &record)
+                .map_err(|msg| AppError::InvalidInput(format!("Line {}: {}", line, msg)))?;
+            transactions.push(transaction);
+        }
+
+        Ok(transactions)
+    }
+}
+
+fn parse_record(
+    columns: &Columns,
+    record: &StringRecord,
+) -> std::result::Result<BrokerTransaction, String> {
+    let field = |index: usize| record.get(index).unwrap_or("").trim();
+
+    let date = NaiveDate::parse_from_str(field(columns.date), DATE_FORMAT)
+        .map_err(|_| format!("Invalid date '{}'", field(columns.date)))?;
+    let isin = isin::normalize(field(columns.isin));
+    if isin.is_empty() {
+        return Err("Missing ISIN".to_string());
+    }
+    if !isin::is_valid(&isin) {
+        return Err(format!("Invalid ISIN '{}'", field(columns.isin)));
+    }
+    let quantity = parse_number(field(columns.quantity))?
+        .filter(|q| !q.is_zero())
+        .ok_or_else(|| "Missing quantity".to_string())?;
+
+    // The value in account currency is missing in some older exports, in which
+    // case it is derived from the local value and the exchange rate
+    let (amount, currency) = match parse_number(field(columns.value))? {
+        Some(value) => (value, columns.value_currency.get(record)),
+        None => {
+            let local_value = parse_number(field(columns.local_value))?
+                .ok_or_else(|| "Missing value".to_string())?;
+            let rate = match columns.exchange_rate {
+                Some(index) => parse_number(field(index))?,
+                None => None,
+            };
+            match rate {
+                Some(rate) if !rate.is_zero() => (local_value / rate, None),
+                _ => (
+                    local_value,
+                    Currency::Column(columns.local_value + 1).get(record),
+                ),
+            }
+        }
+    };
+    let currency = currency
+        .or_else(|| columns.value_currency.get(record))
+        .ok_or_else(|| "Missing currency of value".to_string())?;
+
+    let mut fee = None;
+    for index in &columns.fees {
+        if let Some(value) = parse_number(field(*index))? {
+            *fee.get_or_insert(Decimal::ZERO) += value.abs();
+        }
+    }
+
+    Ok(BrokerTransaction {
+        date,
+        isin,
+        product: field(columns.product).to_string(),
+        action_id: if quantity > Decimal::ZERO {
+            BUY_ACTION_ID
+        } else {
+            SELL_ACTION_ID
+        },
+        quantity: Some(quantity.abs()),
+        amount: amount.abs(),
+        fee,
+        tax: None,
+        currency,
+    })
+}
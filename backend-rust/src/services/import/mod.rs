@@ -0,0 +1,277 @@
+pub mod degiro;
+pub mod profile;
+pub mod statement_parser;
+pub mod trade_republic;
+
+pub use degiro::DegiroParser;
+pub use profile::ProfileParser;
+pub use statement_parser::{BrokerTransaction, StatementParser};
+pub use trade_republic::TradeRepublicParser;
+
+use crate::error::{AppError, Result};
+use crate::isin;
+use crate::models::{Investment, Movement};
+use crate::repository::traits::{
+    InvestmentRepository, MovementRepository, PortfolioRepository, SettingsRepository,
+};
+use crate::services::duplicates::{DuplicateDetector, DEFAULT_DUPLICATE_TOLERANCE};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// How a statement is imported
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrokerImportOptions {
+    /// Portfolio the movements are assigned to
+    pub portfolio_id: Option<i64>,
+    /// Create investments for unknown ISINs instead of reporting them as unmatched
+    pub create_investments: bool,
+    /// Import nothing if a transaction was already recorded as a movement
+    pub reject_duplicates: bool,
+}
+
+/// A security of the statement without investment of the same ISIN
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnmatchedSecurity {
+    pub isin: String,
+    /// Product name as given by the broker
+    pub product: String,
+    /// Number of transactions of this security in the statement
+    pub transactions: usize,
+}
+
+/// A transaction of the statement that matches already recorded movements
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateTransaction {
+    pub date: NaiveDate,
+    pub isin: String,
+    pub product: String,
+    pub action_id: i64,
+    /// IDs of the movements it matches
+    pub existing_ids: Vec<i64>,
+}
+
+/// Outcome of importing a broker statement
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokerImportResult {
+    /// IDs of the created movements
+    pub movements: Vec<i64>,
+    /// Investments created because no investment with their ISIN existed
+    pub created_investments: Vec<Investment>,
+    /// Securities that could not be matched; if not empty, nothing was imported
+    pub unmatched: Vec<UnmatchedSecurity>,
+    /// Transactions that were already recorded; if `reject_duplicates` is set and this
+    /// is not empty, nothing was imported
+    pub duplicates: Vec<DuplicateTransaction>,
+}
+
+/// Imports transactions from broker statements as movements
+pub struct BrokerImportService {
+    investment_repo: Arc<dyn InvestmentRepository>,
+    movement_repo: Arc<dyn MovementRepository>,
+    portfolio_repo: Arc<dyn PortfolioRepository>,
+    settings_repo: Arc<dyn SettingsRepository>,
+    duplicates: DuplicateDetector,
+}
+
+impl BrokerImportService {
+    pub fn new(
+        investment_repo: Arc<dyn InvestmentRepository>,
+        movement_repo: Arc<dyn MovementRepository>,
+        portfolio_repo: Arc<dyn PortfolioRepository>,
+        settings_repo: Arc<dyn SettingsRepository>,
+    ) -> Self {
+        Self {
+            investment_repo,
+            duplicates: DuplicateDetector::new(movement_repo.clone()),
+            movement_repo,
+            portfolio_repo,
+            settings_repo,
+        }
+    }
+
+    /// Parse a statement and store its transactions as movements
+    ///
+    /// The whole statement is parsed and validated before anything is written, and the
+    /// movements are created in a single transaction. Transactions are matched to
+    /// investments by ISIN. Unknown ISINs either get a new investment named after the
+    /// product, or are returned as unmatched without importing anything, so they can be
+    /// assigned to investments before importing the statement again. Transactions that
+    /// match recorded movements are reported as duplicates and, with `reject_duplicates`,
+    /// prevent the import, so a statement can safely be imported again.
+    pub async fn import(
+        &self,
+        parser: &dyn StatementParser,
+        content: &str,
+        options: BrokerImportOptions,
+    ) -> Result<BrokerImportResult> {
+        let transactions = parser.parse(content)?;
+        let portfolio_id = options.portfolio_id;
+
+        if let Some(id) = portfolio_id {
+            if self.portfolio_repo.find_by_id(id).await?.is_none() {
+                return Err(AppError::InvalidInput(format!(
+                    "Portfolio {} does not exist",
+                    id
+                )));
+            }
+        }
+
+        let base_currency = self
+            .settings_repo
+            .get()
+            .await?
+            .map(|s| s.base_currency)
+            .unwrap_or_else(|| "EUR".to_string());
+        if let Some(t) = transactions
+            .iter()
+            .find(|t| !t.currency.eq_ignore_ascii_case(&base_currency))
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Transaction of {} on {} is in {}, but the base currency is {}",
+                t.product, t.date, t.currency, base_currency
+            )));
+        }
+
+        let mut investment_ids: HashMap<String, i64> = self
+            .investment_repo
+            .find_all()
+            .await?
+            .into_iter()
+            // Investments stored before ISINs were normalized may contain whitespace
+            .filter_map(|i| Some((isin::normalize(&i.isin?), i.id)))
+            .collect();
+
+        if !options.create_investments {
+            let unmatched = unmatched_securities(&transactions, &investment_ids);
+            if !unmatched.is_empty() {
+                return Ok(BrokerImportResult {
+                    movements: Vec::new(),
+                    created_investments: Vec::new(),
+                    unmatched,
+                    duplicates: Vec::new(),
+                });
+            }
+        }
+
+        // Only transactions of known investments can have been recorded before
+        let known: Vec<(&BrokerTransaction, i64)> = transactions
+            .iter()
+            .filter_map(|t| Some((t, *investment_ids.get(&t.isin.to_uppercase())?)))
+            .collect();
+        let candidates: Vec<Movement> = known
+            .iter()
+            .map(|(t, investment_id)| to_movement(t, Some(*investment_id), portfolio_id))
+            .collect();
+        let duplicates: Vec<DuplicateTransaction> = self
+            .duplicates
+            .find_duplicates(&candidates, DEFAULT_DUPLICATE_TOLERANCE)
+            .await?
+            .into_iter()
+            .map(|d| {
+                let t = known[d.index].0;
+                DuplicateTransaction {
+                    date: t.date,
+                    isin: t.isin.to_uppercase(),
+                    product: t.product.clone(),
+                    action_id: t.action_id,
+                    existing_ids: d.existing_ids,
+                }
+            })
+            .collect();
+        if options.reject_duplicates && !duplicates.is_empty() {
+            return Ok(BrokerImportResult {
+                movements: Vec::new(),
+                created_investments: Vec::new(),
+                unmatched: Vec::new(),
+                duplicates,
+            });
+        }
+
+        let mut created_investments = Vec::new();
+        for transaction in &transactions {
+            let isin = transaction.isin.to_uppercase();
+            if investment_ids.contains_key(&isin) {
+                continue;
+            }
+
+            let mut investment = Investment {
+                id: 0,
+                name: Some(transaction.product.clone()),
+                isin: Some(isin.clone()),
+                shortname: None,
+                ticker_symbol: None,
+                quote_provider: None,
+                currency: None,
+                asset_class: None,
+                watchlist: false,
+                partial_exemption: None,
+                quote_fetch_enabled: true,
+                quote_fetch_interval_days: None,
+                archived: false,
+            };
+            investment.id = self.investment_repo.create(&investment).await?;
+            investment_ids.insert(isin, investment.id);
+            created_investments.push(investment);
+        }
+
+        let movements: Vec<Movement> = transactions
+            .iter()
+            .map(|t| {
+                let investment_id = investment_ids.get(&t.isin.to_uppercase()).copied();
+                to_movement(t, investment_id, portfolio_id)
+            })
+            .collect();
+        let movements = self.movement_repo.create_many(&movements).await?;
+
+        Ok(BrokerImportResult {
+            movements,
+            created_investments,
+            unmatched: Vec::new(),
+            duplicates,
+        })
+    }
+}
+
+fn to_movement(
+    transaction: &BrokerTransaction,
+    investment_id: Option<i64>,
+    portfolio_id: Option<i64>,
+) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(transaction.date),
+        action_id: Some(transaction.action_id),
+        investment_id,
+        quantity: transaction.quantity,
+        amount: Some(transaction.amount),
+        fee: transaction.fee,
+        tax: transaction.tax,
+        portfolio_id,
+        external_id: None,
+    }
+}
+
+/// Securities of the transactions without investment, ordered by ISIN
+fn unmatched_securities(
+    transactions: &[BrokerTransaction],
+    investment_ids: &HashMap<String, i64>,
+) -> Vec<UnmatchedSecurity> {
+    let mut unmatched: BTreeMap<String, UnmatchedSecurity> = BTreeMap::new();
+    for transaction in transactions {
+        let isin = transaction.isin.to_uppercase();
+        if investment_ids.contains_key(&isin) {
+            continue;
+        }
+        unmatched
+            .entry(isin.clone())
+            .or_insert_with(|| UnmatchedSecurity {
+                isin,
+                product: transaction.product.clone(),
+                transactions: 0,
+            })
+            .transactions += 1;
+    }
+    unmatched.into_values().collect()
+}
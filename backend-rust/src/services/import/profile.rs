@@ -0,0 +1,193 @@
+use super::statement_parser::{BrokerTransaction, StatementParser, BUY_ACTION_ID, SELL_ACTION_ID};
+use crate::error::{AppError, Result};
+use crate::isin;
+use crate::models::ImportProfile;
+use crate::services::dividends::PAYOUT_ACTION_ID;
+use chrono::{NaiveDate, NaiveDateTime};
+use csv::StringRecord;
+use rust_decimal::Decimal;
+
+/// Action types a profile may map keywords to
+pub const PROFILE_ACTION_IDS: [i64; 3] = [BUY_ACTION_ID, SELL_ACTION_ID, PAYOUT_ACTION_ID];
+
+/// Generic CSV parser driven by an [`ImportProfile`]
+#[derive(Debug, Clone)]
+pub struct ProfileParser {
+    profile: ImportProfile,
+}
+
+impl ProfileParser {
+    pub fn new(profile: ImportProfile) -> Self {
+        Self { profile }
+    }
+}
+
+/// Column indices of the profile's columns in one file
+#[derive(Debug)]
+struct Columns {
+    date: usize,
+    action: usize,
+    isin: usize,
+    product: Option<usize>,
+    quantity: Option<usize>,
+    amount: usize,
+    fee: Option<usize>,
+}
+
+impl Columns {
+    fn from_headers(profile: &ImportProfile, headers: &StringRecord) -> Result<Self> {
+        let names: Vec<String> = headers
+            .iter()
+            .map(|h| h.trim().trim_start_matches('\u{feff}').to_lowercase())
+            .collect();
+        let find = |column: &str| {
+            names
+                .iter()
+                .position(|h| *h == column.trim().to_lowercase())
+                .ok_or_else(|| {
+                    AppError::InvalidInput(format!(
+                        "Column '{}' of import profile '{}' not found",
+                        column, profile.name
+                    ))
+                })
+        };
+        let find_optional = |column: &Option<String>| column.as_deref().map(find).transpose();
+
+        Ok(Self {
+            date: find(&profile.date_column)?,
+            action: find(&profile.action_column)?,
+            isin: find(&profile.isin_column)?,
+            product: find_optional(&profile.product_column)?,
+            quantity: find_optional(&profile.quantity_column)?,
+            amount: find(&profile.amount_column)?,
+            fee: find_optional(&profile.fee_column)?,
+        })
+    }
+}
+
+impl StatementParser for ProfileParser {
+    fn parse(&self, content: &str) -> Result<Vec<BrokerTransaction>> {
+        let delimiter = self.profile.delimiter.bytes().next().unwrap_or(b',');
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(content.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid CSV file: {}", e)))?;
+        let columns = Columns::from_headers(&self.profile, headers)?;
+
+        let mut transactions = Vec::new();
+        for record in reader.records() {
+            let record =
+                record.map_err(|e| AppError::InvalidInput(format!("Invalid CSV file: {}", e)))?;
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+
+            let line = record.position().map(|p| p.line()).unwrap_or_default();
+            let transaction = self
+                .parse_record(&columns, &record)
+                .map_err(|msg| AppError::InvalidInput(format!("Line {}: {}", line, msg)))?;
+            transactions.extend(transaction);
+        }
+
+        Ok(transactions)
+    }
+}
+
+impl ProfileParser {
+    fn parse_record(
+        &self,
+        columns: &Columns,
+        record: &StringRecord,
+    ) -> std::result::Result<Option<BrokerTransaction>, String> {
+        let field = |index: usize| record.get(index).unwrap_or("").trim();
+        let number = |index: usize| parse_decimal(field(index), &self.profile.decimal_separator);
+
+        let keyword = field(columns.action).to_lowercase();
+        let Some(&action_id) = self.profile.action_keywords.get(&keyword) else {
+            return Ok(None);
+        };
+
+        let date = self
+            .parse_date(field(columns.date))
+            .ok_or_else(|| format!("Invalid date '{}'", field(columns.date)))?;
+        let isin = isin::normalize(field(columns.isin));
+        if isin.is_empty() {
+            return Err("Missing ISIN".to_string());
+        }
+        if !isin::is_valid(&isin) {
+            return Err(format!("Invalid ISIN '{}'", field(columns.isin)));
+        }
+        let amount = number(columns.amount)?
+            .ok_or_else(|| "Missing amount".to_string())?
+            .abs();
+        let quantity = match columns.quantity {
+            Some(index) => number(index)?.map(|q| q.abs()).filter(|q| !q.is_zero()),
+            None => None,
+        };
+        if quantity.is_none() && action_id != PAYOUT_ACTION_ID {
+            return Err("Missing quantity".to_string());
+        }
+        let fee = match columns.fee {
+            Some(index) => number(index)?.map(|fee| fee.abs()),
+            None => None,
+        };
+        let product = columns
+            .product
+            .map(|index| field(index).to_string())
+            .filter(|product| !product.is_empty())
+            .unwrap_or_else(|| isin.clone());
+
+        Ok(Some(BrokerTransaction {
+            date,
+            isin,
+            product,
+            action_id,
+            quantity,
+            amount,
+            fee,
+            tax: None,
+            currency: self.profile.currency.clone(),
+        }))
+    }
+
+    /// Parse a date, also accepting formats that include a time
+    fn parse_date(&self, value: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(value, &self.profile.date_format)
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(value, &self.profile.date_format)
+                    .ok()
+                    .map(|dt| dt.date())
+            })
+    }
+}
+
+/// Parse a number with the given decimal separator, ignoring thousands separators
+fn parse_decimal(
+    value: &str,
+    decimal_separator: &str,
+) -> std::result::Result<Option<Decimal>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let normalized: String = if decimal_separator == "," {
+        value
+            .chars()
+            .filter(|c| *c != '.' && !c.is_whitespace())
+            .map(|c| if c == ',' { '.' } else { c })
+            .collect()
+    } else {
+        value
+            .chars()
+            .filter(|c| *c != ',' && !c.is_whitespace())
+            .collect()
+    };
+    normalized
+        .parse::<Decimal>()
+        .map(Some)
+        .map_err(|_| format!("Invalid number '{}'", value))
+}
@@ -0,0 +1,62 @@
+use crate::error::Result;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+/// Action type of purchases
+pub const BUY_ACTION_ID: i64 = 1;
+
+/// Action type of sales
+pub const SELL_ACTION_ID: i64 = 2;
+
+/// A buy, sell or payout read from a broker statement
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BrokerTransaction {
+    pub date: NaiveDate,
+    pub isin: String,
+    /// Product name as given by the broker
+    pub product: String,
+    /// Buy, sell or payout action type
+    pub action_id: i64,
+    /// Number of shares, always positive; not set for payouts
+    pub quantity: Option<Decimal>,
+    /// Value of the trade in `currency` without fees, always positive
+    pub amount: Decimal,
+    /// Fees in `currency`, always positive
+    pub fee: Option<Decimal>,
+    /// Taxes in `currency` paid on top of a buy or deducted from a sale, always positive
+    pub tax: Option<Decimal>,
+    /// Currency of the account, i.e. of `amount`, `fee` and `tax`
+    pub currency: String,
+}
+
+/// Trait for parsers of broker transaction exports
+pub trait StatementParser: Send + Sync {
+    /// Parse the content of an export into transactions
+    ///
+    /// Fails on the first row that cannot be read, so a statement is imported
+    /// completely or not at all.
+    fn parse(&self, content: &str) -> Result<Vec<BrokerTransaction>>;
+}
+
+/// Parse a number with `.` or `,` as decimal separator
+///
+/// Depending on the language setting brokers write `-1005.00` or `-1.005,00`.
+/// If both separators appear, the last one is the decimal separator.
+pub(super) fn parse_number(value: &str) -> std::result::Result<Option<Decimal>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let normalized = match (value.rfind('.'), value.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => value.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => value.replace(',', ""),
+        (None, Some(_)) => value.replace(',', "."),
+        _ => value.to_string(),
+    };
+    normalized
+        .parse::<Decimal>()
+        .map(Some)
+        .map_err(|_| format!("Invalid number '{}'", value))
+}
@@ -0,0 +1,202 @@
+use super::statement_parser::{
+    parse_number, BrokerTransaction, StatementParser, BUY_ACTION_ID, SELL_ACTION_ID,
+};
+use crate::error::{AppError, Result};
+use crate::isin;
+use crate::services::dividends::PAYOUT_ACTION_ID;
+use chrono::{NaiveDate, NaiveDateTime};
+use csv::StringRecord;
+use rust_decimal::Decimal;
+
+/// Trade Republic accounts are held in EUR
+const ACCOUNT_CURRENCY: &str = "EUR";
+
+/// Parser for the transaction CSV export of Trade Republic
+///
+/// Expects the columns `Datum;Typ;Wert;Notiz;ISIN;Stück;Gebühren;Steuern` (or their
+/// English names `Date;Type;Value;Note;ISIN;Shares;Fees;Taxes`), separated by `;` or `,`.
+/// `Wert` is the booked cash amount including fees and taxes, negative for purchases.
+///
+/// Purchases (`Kauf`, `Sparplan`), sales (`Verkauf`) and payouts (`Dividende`,
+/// `Ausschüttung`) are read; other rows such as deposits or interest are skipped.
+#[derive(Debug, Default, Clone)]
+pub struct TradeRepublicParser;
+
+impl TradeRepublicParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Column indices of the fields used for movements
+#[derive(Debug)]
+struct Columns {
+    date: usize,
+    kind: usize,
+    value: usize,
+    note: Option<usize>,
+    isin: usize,
+    shares: Option<usize>,
+    fees: Option<usize>,
+    taxes: Option<usize>,
+}
+
+impl Columns {
+    fn from_headers(headers: &StringRecord) -> Result<Self> {
+        let names: Vec<String> = headers
+            .iter()
+            .map(|h| h.trim().trim_start_matches('\u{feff}').to_lowercase())
+            .collect();
+        let find =
+            |candidates: &[&str]| names.iter().position(|h| candidates.contains(&h.as_str()));
+        let require = |candidates: &[&str]| {
+            find(candidates).ok_or_else(|| {
+                AppError::InvalidInput(format!(
+                    "Missing column '{}' in Trade Republic export",
+                    candidates[0]
+                ))
+            })
+        };
+
+        Ok(Self {
+            date: require(&["datum", "date"])?,
+            kind: require(&["typ", "type"])?,
+            value: require(&["wert", "value"])?,
+            note: find(&["notiz", "note"]),
+            isin: require(&["isin"])?,
+            shares: find(&["stück", "shares"]),
+            fees: find(&["gebühren", "fees"]),
+            taxes: find(&["steuern", "taxes"]),
+        })
+    }
+}
+
+impl StatementParser for TradeRepublicParser {
+    fn parse(&self, content: &str) -> Result<Vec<BrokerTransaction>> {
+        let header_line = content.lines().next().unwrap_or("");
+        let delimiter = if header_line.contains(';') {
+            b';'
+        } else {
+            b','
+        };
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(content.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid Trade Republic export: {}", e)))?;
+        let columns = Columns::from_headers(headers)?;
+
+        let mut transactions = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|e| {
+                AppError::InvalidInput(format!("Invalid Trade Republic export: {}", e))
+            })?;
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+
+            let line = record.position().map(|p| p.line()).unwrap_or_default();
+            let transaction = parse_record(&columns, &record)
+                .map_err(|msg| AppError::InvalidInput(format!("Line {}: {}", line, msg)))?;
+            transactions.extend(transaction);
+        }
+
+        Ok(transactions)
+    }
+}
+
+/// Action type of a transaction type, `None` for rows that are not movements
+fn action_id(kind: &str) -> Option<i64> {
+    match kind.trim().to_lowercase().as_str() {
+        "kauf" | "buy" | "sparplan" | "savings plan" => Some(BUY_ACTION_ID),
+        "verkauf" | "sell" => Some(SELL_ACTION_ID),
+        "dividende" | "dividend" | "ausschüttung" | "distribution" => Some(PAYOUT_ACTION_ID),
+        _ => None,
+    }
+}
+
+/// Dates are written as `2024-01-15`, optionally with time, or as `15.01.2024`
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%d.%m.%Y"))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .ok()
+                .map(|dt| dt.date())
+        })
+}
+
+fn parse_record(
+    columns: &Columns,
+    record: &StringRecord,
+) -> std::result::Result<Option<BrokerTransaction>, String> {
+    let field = |index: usize| record.get(index).unwrap_or("").trim();
+    let optional = |index: Option<usize>| -> std::result::Result<Decimal, String> {
+        match index {
+            Some(index) => Ok(parse_number(field(index))?.unwrap_or_default().abs()),
+            None => Ok(Decimal::ZERO),
+        }
+    };
+
+    let Some(action_id) = action_id(field(columns.kind)) else {
+        return Ok(None);
+    };
+
+    let date = parse_date(field(columns.date))
+        .ok_or_else(|| format!("Invalid date '{}'", field(columns.date)))?;
+    let isin = isin::normalize(field(columns.isin));
+    if isin.is_empty() {
+        return Err("Missing ISIN".to_string());
+    }
+    if !isin::is_valid(&isin) {
+        return Err(format!("Invalid ISIN '{}'", field(columns.isin)));
+    }
+    let value = parse_number(field(columns.value))?
+        .ok_or_else(|| "Missing value".to_string())?
+        .abs();
+    let fee = optional(columns.fees)?;
+    let taxes = optional(columns.taxes)?;
+
+    let (quantity, amount, tax) = if action_id == PAYOUT_ACTION_ID {
+        // Payouts are booked net of taxes, which are therefore not recorded again
+        (None, value, Decimal::ZERO)
+    } else {
+        let quantity = match columns.shares {
+            Some(index) => parse_number(field(index))?,
+            None => None,
+        }
+        .map(|q| q.abs())
+        .filter(|q| !q.is_zero())
+        .ok_or_else(|| "Missing number of shares".to_string())?;
+
+        // The booked value includes fees and taxes, the movement amount does not
+        let amount = if action_id == BUY_ACTION_ID {
+            value - fee - taxes
+        } else {
+            value + fee + taxes
+        };
+        (Some(quantity), amount, taxes)
+    };
+
+    let product = columns
+        .note
+        .map(|index| field(index).to_string())
+        .filter(|note| !note.is_empty())
+        .unwrap_or_else(|| isin.clone());
+
+    Ok(Some(BrokerTransaction {
+        date,
+        isin,
+        product,
+        action_id,
+        quantity,
+        amount,
+        fee: (!fee.is_zero()).then_some(fee),
+        tax: (!tax.is_zero()).then_some(tax),
+        currency: ACCOUNT_CURRENCY.to_string(),
+    }))
+}
@@ -0,0 +1,129 @@
+use crate::error::{AppError, Result};
+use crate::repository::traits::InvestmentRepository;
+use crate::services::currency_converter::CurrencyConverter;
+use crate::services::quote_fetcher::{
+    parse_provider_chain, QuoteFetcherService, AVAILABLE_PROVIDERS,
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Seconds a status check waits for an integration before reporting it unreachable
+pub const PROBE_TIMEOUT_SECONDS: u64 = 5;
+
+/// Result of the status check of one external API
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrationStatus {
+    /// Provider ID, or `frankfurter` for the exchange rates
+    pub id: String,
+    pub name: String,
+    /// `quote_provider` or `fx_rates`
+    pub kind: String,
+    pub reachable: bool,
+    /// Time until the check answered or failed
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Checks whether the quote providers in use and the exchange rate API answer, to tell
+/// a provider outage from a configuration problem
+pub struct IntegrationStatusService {
+    investment_repo: Arc<dyn InvestmentRepository>,
+    quote_fetcher: QuoteFetcherService,
+    currency_converter: CurrencyConverter,
+    timeout: Duration,
+}
+
+impl IntegrationStatusService {
+    pub fn new(
+        investment_repo: Arc<dyn InvestmentRepository>,
+        quote_fetcher: QuoteFetcherService,
+    ) -> Self {
+        Self {
+            investment_repo,
+            quote_fetcher,
+            currency_converter: CurrencyConverter::new(),
+            timeout: Duration::from_secs(PROBE_TIMEOUT_SECONDS),
+        }
+    }
+
+    /// Report an integration unreachable if it does not answer within the given time
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Providers named in the quote provider chain of an unarchived investment with quote
+    /// fetching enabled, including unknown ones
+    async fn configured_providers(&self) -> Result<BTreeSet<String>> {
+        let investments = self.investment_repo.find_all().await?;
+        Ok(investments
+            .iter()
+            .filter(|investment| investment.quote_fetch_enabled && !investment.archived)
+            .filter_map(|investment| investment.quote_provider.as_deref())
+            .flat_map(parse_provider_chain)
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Check all configured providers and the exchange rate API concurrently
+    pub async fn check(&self) -> Result<Vec<IntegrationStatus>> {
+        let providers = self.configured_providers().await?;
+
+        let provider_checks = futures::future::join_all(providers.iter().map(|id| {
+            let name = AVAILABLE_PROVIDERS
+                .iter()
+                .find(|(provider, _)| provider == id)
+                .map_or(id.as_str(), |(_, name)| name);
+            self.probe(
+                id,
+                name,
+                "quote_provider",
+                self.quote_fetcher.probe_provider(id),
+            )
+        }));
+        let fx_check = self.probe(
+            "frankfurter",
+            "Frankfurter",
+            "fx_rates",
+            self.currency_converter.probe(),
+        );
+        let (mut statuses, fx_status) = futures::future::join(provider_checks, fx_check).await;
+
+        statuses.push(fx_status);
+        Ok(statuses)
+    }
+
+    async fn probe(
+        &self,
+        id: &str,
+        name: &str,
+        kind: &str,
+        request: impl Future<Output = Result<()>>,
+    ) -> IntegrationStatus {
+        let started = Instant::now();
+        let result = match tokio::time::timeout(self.timeout, request).await {
+            Ok(result) => result,
+            Err(_) => Err(AppError::ExternalApi(format!(
+                "No answer within {} ms",
+                self.timeout.as_millis()
+            ))),
+        };
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        if let Err(e) = &result {
+            tracing::warn!("Status check of {} failed: {}", name, e);
+        }
+        IntegrationStatus {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind: kind.to_string(),
+            reachable: result.is_ok(),
+            latency_ms,
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+}
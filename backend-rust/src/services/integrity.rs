@@ -0,0 +1,286 @@
+use crate::error::Result;
+use crate::models::{InvestmentPrice, Movement};
+use crate::repository::traits::{
+    ActionTypeRepository, InvestmentPriceRepository, InvestmentRepository, MovementRepository,
+    PortfolioRepository,
+};
+use crate::services::action_effects::ActionEffects;
+use crate::services::cost_basis::TRANSFER_OUT_ACTION_ID;
+use crate::services::positions::negative_quantity_dates;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+/// Number of affected rows listed per check
+pub const SAMPLE_SIZE: usize = 10;
+
+/// Key of a price, which has no ID in the API
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct PriceKey {
+    pub date: Option<NaiveDate>,
+    pub investment_id: Option<i64>,
+    pub source: String,
+}
+
+impl PriceKey {
+    fn of(price: &InvestmentPrice) -> Self {
+        Self {
+            date: price.date,
+            investment_id: price.investment_id,
+            source: price.stored_source().to_string(),
+        }
+    }
+}
+
+/// Result of one check of the stored data
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityCheck {
+    /// Name of the check, e.g. `orphaned_movements`
+    pub check: String,
+    pub description: String,
+    /// Number of affected rows
+    pub count: usize,
+    /// IDs of the first affected movements
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sample_ids: Vec<i64>,
+    /// Keys of the first affected prices
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sample_prices: Vec<PriceKey>,
+    /// Whether the fix repairs the affected rows without losing information
+    pub fixable: bool,
+}
+
+impl IntegrityCheck {
+    fn movements(check: &str, description: &str, mut ids: Vec<i64>) -> Self {
+        ids.sort_unstable();
+        Self {
+            check: check.to_string(),
+            description: description.to_string(),
+            count: ids.len(),
+            sample_ids: ids.into_iter().take(SAMPLE_SIZE).collect(),
+            sample_prices: Vec::new(),
+            fixable: false,
+        }
+    }
+
+    fn prices(check: &str, description: &str, count: usize, mut keys: Vec<PriceKey>) -> Self {
+        keys.sort();
+        Self {
+            check: check.to_string(),
+            description: description.to_string(),
+            count,
+            sample_ids: Vec::new(),
+            sample_prices: keys.into_iter().take(SAMPLE_SIZE).collect(),
+            fixable: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    /// `ok` if no check found affected rows, else `issues_found`
+    pub status: String,
+    pub checks: Vec<IntegrityCheck>,
+}
+
+/// Prices deleted by the fix and the report of the repaired data
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityFix {
+    /// Prices without investment or of a deleted investment
+    pub deleted_orphaned_prices: u64,
+    /// Older prices with the same date, investment and source as a later one
+    pub deleted_duplicate_prices: u64,
+    pub report: IntegrityReport,
+}
+
+/// Finds stored data the calculations cannot use or would count wrongly, e.g. left over
+/// from older versions or direct database edits
+pub struct IntegrityService {
+    movement_repo: Arc<dyn MovementRepository>,
+    investment_repo: Arc<dyn InvestmentRepository>,
+    action_type_repo: Arc<dyn ActionTypeRepository>,
+    portfolio_repo: Arc<dyn PortfolioRepository>,
+    price_repo: Arc<dyn InvestmentPriceRepository>,
+}
+
+impl IntegrityService {
+    pub fn new(
+        movement_repo: Arc<dyn MovementRepository>,
+        investment_repo: Arc<dyn InvestmentRepository>,
+        action_type_repo: Arc<dyn ActionTypeRepository>,
+        portfolio_repo: Arc<dyn PortfolioRepository>,
+        price_repo: Arc<dyn InvestmentPriceRepository>,
+    ) -> Self {
+        Self {
+            movement_repo,
+            investment_repo,
+            action_type_repo,
+            portfolio_repo,
+            price_repo,
+        }
+    }
+
+    /// Run all checks
+    pub async fn check(&self) -> Result<IntegrityReport> {
+        let movements = self.movement_repo.find_all().await?;
+        let prices = self.price_repo.find_all(None, None, None).await?;
+        let investments: HashSet<i64> = self
+            .investment_repo
+            .find_all()
+            .await?
+            .iter()
+            .map(|investment| investment.id)
+            .collect();
+        let action_types = self.action_type_repo.find_all().await?;
+        let portfolios: HashSet<i64> = self
+            .portfolio_repo
+            .find_all()
+            .await?
+            .iter()
+            .map(|portfolio| portfolio.id)
+            .collect();
+
+        let action_ids: HashSet<i64> = action_types.iter().map(|t| t.id).collect();
+        let missing =
+            |id: Option<i64>, existing: &HashSet<i64>| id.is_some_and(|id| !existing.contains(&id));
+        let orphaned_movements = movements
+            .iter()
+            .filter(|m| {
+                missing(m.investment_id, &investments)
+                    || missing(m.action_id, &action_ids)
+                    || missing(m.portfolio_id, &portfolios)
+            })
+            .map(|m| m.id)
+            .collect();
+        let undated_movements = movements
+            .iter()
+            .filter(|m| m.date.is_none())
+            .map(|m| m.id)
+            .collect();
+
+        let mut normalized = movements;
+        ActionEffects::new(&action_types).normalize(&mut normalized);
+
+        let orphaned_prices: Vec<PriceKey> = prices
+            .iter()
+            .filter(|p| missing(p.investment_id, &investments))
+            .map(PriceKey::of)
+            .collect();
+        let unassigned_prices: Vec<PriceKey> = prices
+            .iter()
+            .filter(|p| p.investment_id.is_none())
+            .map(PriceKey::of)
+            .collect();
+        let (duplicate_count, duplicate_keys) = duplicate_prices(&prices);
+
+        let checks = vec![
+            IntegrityCheck::movements(
+                "orphaned_movements",
+                "Movements referring to an investment, action type or portfolio that does not exist",
+                orphaned_movements,
+            ),
+            IntegrityCheck::movements(
+                "movements_without_date",
+                "Movements without date, which the calculations skip",
+                undated_movements,
+            ),
+            IntegrityCheck::movements(
+                "negative_holdings",
+                "Sells and outgoing transfers after which less than nothing is held",
+                oversold_movements(&normalized),
+            ),
+            IntegrityCheck::prices(
+                "orphaned_prices",
+                "Prices of investments that do not exist",
+                orphaned_prices.len(),
+                orphaned_prices,
+            ),
+            IntegrityCheck::prices(
+                "prices_without_investment",
+                "Prices without investment",
+                unassigned_prices.len(),
+                unassigned_prices,
+            ),
+            IntegrityCheck::prices(
+                "duplicate_prices",
+                "Prices with the same date, investment and source as another price",
+                duplicate_count,
+                duplicate_keys,
+            ),
+        ];
+
+        let clean = checks.iter().all(|check| check.count == 0);
+        Ok(IntegrityReport {
+            status: if clean { "ok" } else { "issues_found" }.to_string(),
+            checks,
+        })
+    }
+
+    /// Delete orphaned and duplicate prices, then check again
+    ///
+    /// Movements are never changed, since only the user knows how to correct them.
+    pub async fn fix(&self) -> Result<IntegrityFix> {
+        let deleted_orphaned_prices = self.price_repo.delete_orphaned().await?;
+        let deleted_duplicate_prices = self.price_repo.delete_duplicates().await?;
+        if deleted_orphaned_prices + deleted_duplicate_prices > 0 {
+            tracing::info!(
+                "Integrity fix deleted {} orphaned and {} duplicate prices",
+                deleted_orphaned_prices,
+                deleted_duplicate_prices
+            );
+        }
+        Ok(IntegrityFix {
+            deleted_orphaned_prices,
+            deleted_duplicate_prices,
+            report: self.check().await?,
+        })
+    }
+}
+
+/// IDs of the sells and outgoing transfers on days at whose end the quantity held in
+/// their portfolio is negative
+fn oversold_movements(movements: &[Movement]) -> Vec<i64> {
+    let mut by_portfolio: HashMap<Option<i64>, Vec<Movement>> = HashMap::new();
+    for movement in movements {
+        by_portfolio
+            .entry(movement.portfolio_id)
+            .or_default()
+            .push(movement.clone());
+    }
+
+    let mut ids = Vec::new();
+    for portfolio_movements in by_portfolio.values() {
+        let negative = negative_quantity_dates(portfolio_movements);
+        ids.extend(
+            portfolio_movements
+                .iter()
+                .filter(|m| matches!(m.action_id, Some(2) | Some(TRANSFER_OUT_ACTION_ID)))
+                .filter(|m| {
+                    let (Some(investment), Some(date)) = (m.investment_id, m.date) else {
+                        return false;
+                    };
+                    negative
+                        .get(&investment)
+                        .is_some_and(|dates| dates.contains(&date))
+                })
+                .map(|m| m.id),
+        );
+    }
+    ids
+}
+
+/// Number of prices beyond the first per (Date, InvestmentID, Source) and their keys
+fn duplicate_prices(prices: &[InvestmentPrice]) -> (usize, Vec<PriceKey>) {
+    let mut counts: BTreeMap<PriceKey, usize> = BTreeMap::new();
+    for price in prices {
+        *counts.entry(PriceKey::of(price)).or_default() += 1;
+    }
+    counts.into_iter().filter(|(_, count)| *count > 1).fold(
+        (0, Vec::new()),
+        |(total, mut keys), (key, count)| {
+            keys.push(key);
+            (total + count - 1, keys)
+        },
+    )
+}
@@ -0,0 +1,195 @@
+use crate::error::{AppError, Result};
+use crate::models::Movement;
+use crate::repository::traits::{ActionTypeRepository, InvestmentRepository, MovementRepository};
+use crate::services::action_effects::ActionEffects;
+use crate::services::cost_basis::OPENING_ACTION_ID;
+use crate::services::display_precision;
+use crate::services::dividends::PAYOUT_ACTION_ID;
+use crate::services::portfolio_calculator::Development;
+use crate::services::positions::{current_quantities, PositionPoint};
+use crate::services::{PortfolioCalculator, PositionCalculator};
+use async_graphql::SimpleObject;
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Current position and totals of one investment
+#[derive(Debug, Clone, PartialEq, Serialize, SimpleObject)]
+pub struct InvestmentSummary {
+    pub investment: i64,
+    pub name: Option<String>,
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub quantity: Decimal,
+    /// Latest quote, or transaction price if more recent
+    #[serde(serialize_with = "display_precision::price")]
+    pub price: Option<Decimal>,
+    pub price_date: Option<NaiveDate>,
+    #[serde(serialize_with = "display_precision::value")]
+    pub market_value: Decimal,
+    /// Amounts paid for buys
+    #[serde(serialize_with = "display_precision::value")]
+    pub total_invested: Decimal,
+    /// Amounts received for sells
+    #[serde(serialize_with = "display_precision::value")]
+    pub total_sold: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub fees_paid: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub taxes_paid: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub dividends_received: Decimal,
+    /// `(market value + sold + dividends - invested - fees - taxes) / invested`, if anything
+    /// was invested
+    pub simple_return: Option<f64>,
+}
+
+/// Summarize buys (1), sells (2), payouts (3) and openings (9) of one investment at its latest development
+pub fn summarize_investment(
+    investment: i64,
+    name: Option<String>,
+    movements: &[Movement],
+    latest: Option<&Development>,
+) -> InvestmentSummary {
+    let mut total_invested = Decimal::ZERO;
+    let mut total_sold = Decimal::ZERO;
+    let mut fees_paid = Decimal::ZERO;
+    let mut taxes_paid = Decimal::ZERO;
+    let mut dividends_received = Decimal::ZERO;
+
+    for movement in movements
+        .iter()
+        .filter(|m| m.investment_id == Some(investment))
+    {
+        let amount = movement.amount.unwrap_or_default().abs();
+        match movement.action_id {
+            // An opening position counts as invested at its cost basis
+            Some(1 | OPENING_ACTION_ID) => total_invested += amount,
+            Some(2) => total_sold += amount,
+            Some(PAYOUT_ACTION_ID) => dividends_received += amount,
+            _ => {}
+        }
+        fees_paid += movement.fee.unwrap_or_default().abs();
+        taxes_paid += movement.tax.unwrap_or_default().abs();
+    }
+
+    let quantity = latest.map(|dev| dev.quantity).unwrap_or_default();
+    let market_value = latest.map(|dev| dev.value).unwrap_or_default();
+    let simple_return = (total_invested > Decimal::ZERO)
+        .then(|| {
+            ((market_value + total_sold + dividends_received
+                - total_invested
+                - fees_paid
+                - taxes_paid)
+                / total_invested)
+                .to_f64()
+        })
+        .flatten();
+
+    InvestmentSummary {
+        investment,
+        name,
+        quantity,
+        price: latest.map(|dev| dev.price),
+        price_date: latest.map(|dev| dev.date),
+        market_value,
+        total_invested,
+        total_sold,
+        fees_paid,
+        taxes_paid,
+        dividends_received,
+        simple_return,
+    }
+}
+
+/// Assembles the summary of an investment from its movements and developments
+pub struct InvestmentSummaryService {
+    investment_repo: Arc<dyn InvestmentRepository>,
+    movement_repo: Arc<dyn MovementRepository>,
+    calculator: Arc<PortfolioCalculator>,
+    positions: PositionCalculator,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl InvestmentSummaryService {
+    pub fn new(
+        investment_repo: Arc<dyn InvestmentRepository>,
+        movement_repo: Arc<dyn MovementRepository>,
+        calculator: Arc<PortfolioCalculator>,
+    ) -> Self {
+        Self {
+            investment_repo,
+            positions: PositionCalculator::new(movement_repo.clone()),
+            movement_repo,
+            calculator,
+            action_type_repo: None,
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.positions = self.positions.with_action_types(action_type_repo.clone());
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Summary of one investment, `AppError::NotFound` if it does not exist
+    pub async fn summary(&self, investment_id: i64) -> Result<InvestmentSummary> {
+        let investment = self
+            .investment_repo
+            .find_by_id(investment_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Investment", investment_id))?;
+
+        let mut movements = self
+            .movement_repo
+            .find_filtered(Some(investment_id), None, None, None)
+            .await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+
+        // Developments are ordered by date, so the last one is the current position
+        let developments = self.calculator.calculate_developments(None, None).await?;
+        let latest = developments
+            .iter()
+            .rev()
+            .find(|dev| dev.investment == investment_id);
+
+        let mut summary = summarize_investment(investment_id, investment.name, &movements, latest);
+        // The position follows from the movements alone, like for the quote fetch
+        summary.quantity = current_quantities(&movements)
+            .remove(&investment_id)
+            .unwrap_or_default();
+        Ok(summary)
+    }
+
+    /// Quantity held of an investment after every day its position changed,
+    /// `AppError::NotFound` if it does not exist
+    ///
+    /// Earlier movements still count towards the quantities from `start_date` on.
+    pub async fn position_history(
+        &self,
+        investment_id: i64,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<PositionPoint>> {
+        if self
+            .investment_repo
+            .find_by_id(investment_id)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::not_found("Investment", investment_id));
+        }
+
+        let mut history = self.positions.history(investment_id, portfolio_id).await?;
+        history.retain(|point| {
+            start_date.is_none_or(|start| point.date >= start)
+                && end_date.is_none_or(|end| point.date <= end)
+        });
+        Ok(history)
+    }
+}
@@ -1,8 +1,73 @@
+pub mod action_effects;
+pub mod api_tokens;
+pub mod cash_ledger;
+pub mod corporate_events;
+pub mod cost_basis;
 pub mod currency_converter;
+pub mod dashboard;
+pub mod data_revision;
+pub mod data_transfer;
+pub mod development_cache;
+pub mod development_store;
+pub mod display_precision;
+pub mod dividends;
+pub mod duplicates;
+pub mod email;
+pub mod holding_stats;
+pub mod import;
+pub mod integration_status;
+pub mod integrity;
+pub mod investment_summary;
 pub mod portfolio_calculator;
+pub mod positions;
+pub mod price_alerts;
+pub mod price_gaps;
+pub mod price_recalculation;
+pub mod price_sources;
 pub mod quote_fetcher;
+pub mod quote_scheduler;
 pub mod quotes;
+pub mod reports;
+pub mod risk_metrics;
+pub mod savings_plans;
+pub mod snapshots;
+pub mod symbol_search;
+pub mod tax;
+pub mod webhooks;
+pub mod xlsx_export;
 
+pub use api_tokens::ApiTokenService;
+pub use cash_ledger::CashLedgerService;
+pub use corporate_events::CorporateEventService;
+pub use cost_basis::CostBasisCalculator;
 pub use currency_converter::CurrencyConverter;
+pub use dashboard::DashboardService;
+pub use data_revision::DataRevision;
+pub use data_transfer::DataTransferService;
+pub use development_cache::DevelopmentCache;
+pub use development_store::PendingDevelopments;
+pub use display_precision::DisplayPrecision;
+pub use dividends::DividendService;
+pub use duplicates::DuplicateDetector;
+pub use email::{EmailNotifier, WeeklySummaryScheduler};
+pub use holding_stats::HoldingStatsService;
+pub use import::BrokerImportService;
+pub use integration_status::IntegrationStatusService;
+pub use integrity::IntegrityService;
+pub use investment_summary::InvestmentSummaryService;
 pub use portfolio_calculator::PortfolioCalculator;
+pub use positions::PositionCalculator;
+pub use price_alerts::PriceAlertService;
+pub use price_gaps::PriceGapService;
+pub use price_recalculation::PriceRecalculationService;
+pub use price_sources::PriceSourcePriority;
 pub use quote_fetcher::QuoteFetcherService;
+pub use quote_scheduler::{QuoteFetchStatusTracker, QuoteScheduler};
+pub use reports::ReportService;
+pub use risk_metrics::RiskMetricsService;
+pub use savings_plans::SavingsPlanService;
+pub use snapshots::SnapshotService;
+pub use symbol_search::SymbolSearchService;
+pub use tax::GermanTaxService;
+pub use webhooks::WebhookNotifier;
+pub use xlsx_export::XlsxExportService;
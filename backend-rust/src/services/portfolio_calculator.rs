@@ -1,23 +1,149 @@
-use crate::error::Result;
+use crate::error::{AppError, Result};
 use crate::models::{InvestmentPrice, Movement};
-use crate::repository::traits::{InvestmentPriceRepository, MovementRepository};
-use chrono::NaiveDate;
-use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use crate::repository::traits::{
+    ActionTypeRepository, DevelopmentRepository, InvestmentPriceRepository, InvestmentRepository,
+    MovementRepository, SettingsRepository,
+};
+use crate::services::action_effects::ActionEffects;
+use crate::services::cost_basis::{
+    OPENING_ACTION_ID, SPLIT_ACTION_ID, TRANSFER_IN_ACTION_ID, TRANSFER_OUT_ACTION_ID,
+};
+use crate::services::currency_converter::CurrencyConverter;
+use crate::services::development_cache::DevelopmentCache;
+use crate::services::development_store::{PendingChanges, PendingDevelopments};
+use crate::services::display_precision;
+use crate::services::positions::{
+    collect_quantity_changes, QuantityChange, QuantityChanges, QuantityCursor,
+};
+use crate::services::price_sources::PriceSourcePriority;
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 
+pub use crate::models::Development;
+
+/// Which days developments are calculated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DevelopmentFill {
+    /// Only days with a transaction or quote
+    #[default]
+    None,
+    /// Every calendar day while an investment is held
+    Daily,
+}
+
+/// Period that developments are downsampled to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    #[default]
+    Daily,
+    /// Weeks starting on Monday
+    Weekly,
+    Monthly,
+}
+
+impl Granularity {
+    /// First day of the period containing `date`
+    pub fn period_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Daily => date,
+            Granularity::Weekly => {
+                date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            Granularity::Monthly => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// Value of all investments on one day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TotalDevelopment {
+    pub date: NaiveDate,
+    #[serde(serialize_with = "display_precision::value")]
+    pub value: Decimal,
+}
+
+/// Time-weighted return of a single investment over a period
 #[derive(Debug, Clone, Serialize)]
-pub struct Development {
+pub struct InvestmentReturn {
     pub investment: i64,
+    pub twr: f64,
+}
+
+/// Time-weighted returns per investment and for the whole portfolio
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeWeightedReturn {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub total: f64,
+    pub investments: Vec<InvestmentReturn>,
+}
+
+/// Value and cumulative time-weighted growth factor on one valuation date
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GrowthPoint {
     pub date: NaiveDate,
-    pub price: f64,
-    pub quantity: f64,
     pub value: f64,
+    pub growth: f64,
+}
+
+/// Time-weighted growth per investment and for the total portfolio
+#[derive(Debug, Clone, Default)]
+pub struct GrowthSeries {
+    pub total: Vec<GrowthPoint>,
+    pub investments: BTreeMap<i64, Vec<GrowthPoint>>,
+}
+
+/// Portfolio and benchmark on one day, both indexed to 100 on the first day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkPoint {
+    pub date: NaiveDate,
+    pub portfolio: f64,
+    pub benchmark: f64,
+}
+
+/// Indexed performance of the portfolio next to a benchmark investment
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkComparison {
+    pub benchmark_investment_id: i64,
+    pub series: Vec<BenchmarkPoint>,
+}
+
+/// External cash flows of one (investment, date): money put in and taken out
+#[derive(Debug, Clone, Copy, Default)]
+struct CashFlow {
+    inflow: f64,
+    outflow: f64,
 }
 
 pub struct PortfolioCalculator {
     movement_repo: Arc<dyn MovementRepository>,
     price_repo: Arc<dyn InvestmentPriceRepository>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+    settings_repo: Option<Arc<dyn SettingsRepository>>,
+    investment_repo: Option<Arc<dyn InvestmentRepository>>,
+    converter: Option<CurrencyConverter>,
+    cache: Option<DevelopmentCache>,
+    store: Option<DevelopmentStore>,
+}
+
+/// Persisted developments and the changes not yet written to them
+struct DevelopmentStore {
+    repo: Arc<dyn DevelopmentRepository>,
+    pending: PendingDevelopments,
+    /// Held while updating and reading, so no reader sees a half applied update
+    lock: tokio::sync::Mutex<()>,
+}
+
+/// Result of a forced recalculation
+#[derive(Debug, Clone, Serialize)]
+pub struct DevelopmentRecalculation {
+    pub developments: usize,
 }
 
 impl PortfolioCalculator {
@@ -28,40 +154,334 @@ impl PortfolioCalculator {
         Self {
             movement_repo,
             price_repo,
+            action_type_repo: None,
+            settings_repo: None,
+            investment_repo: None,
+            converter: None,
+            cache: None,
+            store: None,
+        }
+    }
+
+    /// Reuse developments calculated for the same portfolio and date range until the
+    /// cache is invalidated
+    pub fn with_cache(mut self, cache: DevelopmentCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Keep developments across all portfolios in `repo` and only calculate again what
+    /// `pending` reports as changed, from the earliest changed date of each investment on
+    ///
+    /// The stored developments are brought up to date on the next read.
+    pub fn with_development_store(
+        mut self,
+        repo: Arc<dyn DevelopmentRepository>,
+        pending: PendingDevelopments,
+    ) -> Self {
+        self.store = Some(DevelopmentStore {
+            repo,
+            pending,
+            lock: tokio::sync::Mutex::new(()),
+        });
+        self
+    }
+
+    /// Drop all cached and stored developments and calculate the full history again
+    pub async fn recalculate(&self) -> Result<DevelopmentRecalculation> {
+        if let Some(store) = &self.store {
+            store.pending.mark_all();
         }
+        if let Some(cache) = &self.cache {
+            cache.invalidate();
+        }
+        let developments = self.calculate_developments(None, None).await?;
+        Ok(DevelopmentRecalculation {
+            developments: developments.len(),
+        })
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Prefer quote sources by the priority in the settings and the quote provider chain
+    /// of each investment
+    ///
+    /// Without them the sources are still chosen deterministically, in alphabetical order.
+    pub fn with_price_sources(
+        mut self,
+        settings_repo: Arc<dyn SettingsRepository>,
+        investment_repo: Arc<dyn InvestmentRepository>,
+    ) -> Self {
+        self.settings_repo = Some(settings_repo);
+        self.investment_repo = Some(investment_repo);
+        self
+    }
+
+    /// Report values in other currencies than the base currency of the settings, with the
+    /// exchange rates of `converter`
+    ///
+    /// Developments are calculated and stored in the base currency and only converted when
+    /// another currency is requested.
+    pub fn with_currency_converter(mut self, converter: CurrencyConverter) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    /// Exchange rates from the base currency to `currency` on each of `dates`
+    ///
+    /// `None` if no currency is requested or it is the base currency, so that nothing needs
+    /// to be converted. Days without a rate use the rate of the previous business day.
+    async fn display_rates(
+        &self,
+        currency: Option<&str>,
+        dates: impl IntoIterator<Item = NaiveDate>,
+    ) -> Result<Option<BTreeMap<NaiveDate, Decimal>>> {
+        let Some(currency) = currency else {
+            return Ok(None);
+        };
+        let base_currency = match &self.settings_repo {
+            Some(repo) => repo.get().await?.map(|s| s.base_currency),
+            None => None,
+        }
+        .unwrap_or_else(|| "EUR".to_string());
+        if currency == base_currency {
+            return Ok(None);
+        }
+        let Some(converter) = &self.converter else {
+            return Err(AppError::InvalidInput(format!(
+                "Values cannot be converted from {} to {}",
+                base_currency, currency
+            )));
+        };
+
+        let dates: BTreeSet<NaiveDate> = dates.into_iter().collect();
+        let ones: Vec<(NaiveDate, Decimal)> = dates.iter().map(|d| (*d, Decimal::ONE)).collect();
+        let rates = converter
+            .convert_series(&ones, &base_currency, currency)
+            .await?;
+        dates
+            .into_iter()
+            .zip(rates)
+            .map(|(date, rate)| {
+                rate.map(|rate| (date, rate)).ok_or_else(|| {
+                    AppError::InvalidInput(format!(
+                        "No exchange rate from {} to {} on {}",
+                        base_currency, currency, date
+                    ))
+                })
+            })
+            .collect::<Result<_>>()
+            .map(Some)
+    }
+
+    /// Prices and values of `developments` in `currency` instead of the base currency,
+    /// converted with the exchange rate of each development's date
+    pub async fn convert_developments(
+        &self,
+        mut developments: Vec<Development>,
+        currency: Option<&str>,
+    ) -> Result<Vec<Development>> {
+        let dates = developments.iter().map(|dev| dev.date);
+        if let Some(rates) = self.display_rates(currency, dates).await? {
+            Self::apply_display_rates(&mut developments, &rates);
+        }
+        Ok(developments)
+    }
+
+    fn apply_display_rates(developments: &mut [Development], rates: &BTreeMap<NaiveDate, Decimal>) {
+        for dev in developments {
+            let rate = rates[&dev.date];
+            dev.price *= rate;
+            dev.value *= rate;
+        }
+    }
+
+    /// Total values in `currency` instead of the base currency, converted with the exchange
+    /// rate of each day
+    pub async fn convert_totals(
+        &self,
+        mut totals: Vec<TotalDevelopment>,
+        currency: Option<&str>,
+    ) -> Result<Vec<TotalDevelopment>> {
+        let dates = totals.iter().map(|total| total.date);
+        let Some(rates) = self.display_rates(currency, dates).await? else {
+            return Ok(totals);
+        };
+        for total in &mut totals {
+            total.value *= rates[&total.date];
+        }
+        Ok(totals)
     }
 
     /// Calculate portfolio developments combining movement data and fetched quotes.
     ///
     /// For each investment and date, we calculate:
-    /// - quantity: cumulative quantity held (from movements)
-    /// - price: market price from InvestmentPrice if available, otherwise transaction price
+    /// - quantity: cumulative quantity held (from movements), adjusted by stock splits
+    /// - price: market price from InvestmentPrice if available, otherwise transaction price,
+    ///   otherwise the last known price adjusted by splits since then
     /// - value: quantity * price
     pub async fn calculate_developments(
         &self,
         start_date: Option<NaiveDate>,
         end_date: Option<NaiveDate>,
     ) -> Result<Vec<Development>> {
-        // Get all movements and prices
-        let movements = self.movement_repo.find_all().await?;
-        let prices = self.price_repo.find_all(None, start_date, end_date).await?;
+        self.calculate_portfolio_developments(None, start_date, end_date)
+            .await
+    }
+
+    /// Calculate developments restricted to the movements of one portfolio.
+    ///
+    /// Quotes are only considered for investments that have movements in the portfolio.
+    /// Passing `None` calculates developments across all movements, still skipping the
+    /// quotes of investments without any movements.
+    #[tracing::instrument(skip(self))]
+    pub async fn calculate_portfolio_developments(
+        &self,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>> {
+        let Some(cache) = &self.cache else {
+            return self
+                .compute_developments(portfolio_id, start_date, end_date)
+                .await;
+        };
+
+        let key = (portfolio_id, start_date, end_date);
+        if let Some(developments) = cache.get(&key) {
+            return Ok(developments.as_ref().clone());
+        }
+        let generation = cache.generation();
+        let developments = self
+            .compute_developments(portfolio_id, start_date, end_date)
+            .await?;
+        cache.insert(key, generation, Arc::new(developments.clone()));
+        Ok(developments)
+    }
+
+    async fn compute_developments(
+        &self,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>> {
+        match (&self.store, portfolio_id) {
+            (Some(store), None) => self.stored_developments(store, start_date, end_date).await,
+            _ => {
+                self.calculate_from_movements(portfolio_id, None, start_date, end_date)
+                    .await
+            }
+        }
+    }
+
+    /// Apply the pending changes to the stored developments and read them
+    #[tracing::instrument(skip(self, store))]
+    async fn stored_developments(
+        &self,
+        store: &DevelopmentStore,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>> {
+        let _guard = store.lock.lock().await;
+        let changes = store.pending.take();
+        if let Err(e) = self.apply_changes(store.repo.as_ref(), &changes).await {
+            store.pending.restore(changes);
+            return Err(e);
+        }
+        store.repo.find_all(start_date, end_date).await
+    }
+
+    #[tracing::instrument(skip_all, fields(all = changes.all, investments = changes.investments.len()))]
+    async fn apply_changes(
+        &self,
+        repo: &dyn DevelopmentRepository,
+        changes: &PendingChanges,
+    ) -> Result<()> {
+        if changes.all {
+            let developments = self
+                .calculate_from_movements(None, None, None, None)
+                .await?;
+            return repo.replace(None, None, &developments).await;
+        }
+        for (&investment_id, &from_date) in &changes.investments {
+            // Quantities and fallback prices depend on the earlier history, so the
+            // investment is calculated in full and only the outdated part is written
+            let mut developments = self
+                .calculate_from_movements(None, Some(investment_id), None, None)
+                .await?;
+            if let Some(from) = from_date {
+                developments.retain(|d| d.date >= from);
+            }
+            repo.replace(Some(investment_id), from_date, &developments)
+                .await?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn calculate_from_movements(
+        &self,
+        portfolio_id: Option<i64>,
+        investment_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>> {
+        // Earlier movements only matter for the quantities held at the start, so they
+        // are read summed up
+        let mut movements = match start_date {
+            Some(start) => {
+                self.movement_repo
+                    .find_quantity_totals_before(investment_id, portfolio_id, start)
+                    .await?
+            }
+            None => Vec::new(),
+        };
+        movements.extend(
+            self.movement_repo
+                .find_by_investment_and_range(investment_id, portfolio_id, start_date, end_date)
+                .await?,
+        );
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+        let mut prices = self
+            .price_repo
+            .find_all(investment_id, start_date, end_date)
+            .await?;
+
+        // Investments without movements, like those only on the watchlist, are not held
+        let investment_ids: HashSet<i64> =
+            movements.iter().filter_map(|m| m.investment_id).collect();
+        prices.retain(|p| {
+            p.investment_id
+                .map(|id| investment_ids.contains(&id))
+                .unwrap_or(false)
+        });
 
         // Calculate transaction days with average transaction price
         let transaction_days = self.calculate_transaction_days(&movements);
 
-        // Create a mapping of (investment, date) -> quote price
-        let quote_prices = self.create_quote_price_map(&prices);
+        // Create a mapping of (investment, date) -> quote price of the preferred source
+        let priority =
+            PriceSourcePriority::load(self.settings_repo.as_ref(), self.investment_repo.as_ref())
+                .await?;
+        let quote_prices = self.create_quote_price_map(&prices, &priority);
 
-        // Combine all unique (investment, date) pairs
-        let all_dates = self.collect_all_dates(&transaction_days, &prices);
+        // Pre-calculate buys, sells and splits per investment
+        let quantity_changes = collect_quantity_changes(&movements);
 
-        // Pre-calculate buy/sell aggregates
-        let buy_movements = self.aggregate_movements(&movements, 1);
-        let sell_movements = self.aggregate_movements(&movements, 2);
+        // Combine all unique (investment, date) pairs
+        let all_dates = self.collect_all_dates(&transaction_days, &prices, &quantity_changes);
 
-        // Build developments for all dates
+        // Build developments for all dates. The dates are sorted by investment and date,
+        // so one cursor per investment walks its quantity changes once.
         let mut developments = Vec::new();
-        let mut last_price_by_investment: HashMap<i64, f64> = HashMap::new();
+        let mut last_price: Option<(Decimal, Decimal, Option<&str>)> = None;
+        let mut cursor: Option<(i64, QuantityCursor)> = None;
 
         for (investment_id, date) in all_dates {
             // Apply date filtering
@@ -76,35 +496,48 @@ impl PortfolioCalculator {
                 }
             }
 
+            if cursor.as_ref().map(|(id, _)| *id) != Some(investment_id) {
+                let changes = quantity_changes
+                    .get(&investment_id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                cursor = Some((investment_id, QuantityCursor::new(changes)));
+                last_price = None;
+            }
+            let Some((_, held)) = cursor.as_mut() else {
+                continue;
+            };
+
             // Calculate quantity held on this date
-            let quantity_bought = self.sum_quantities(&buy_movements, investment_id, date);
-            let quantity_sold = self.sum_quantities(&sell_movements, investment_id, date);
-            let quantity = quantity_bought - quantity_sold;
+            held.advance_to(date);
+            let quantity = held.quantity;
 
             // Determine price: prefer quote price, fallback to transaction price, then last known price
-            let mut price: Option<f64> = None;
+            let mut price: Option<(Decimal, Option<&str>)> = None;
 
             // 1. Try to get quote price for this date
-            if let Some(&quote_price) = quote_prices.get(&(investment_id, date)) {
-                price = Some(quote_price);
+            if let Some(&quote) = quote_prices.get(&(investment_id, date)) {
+                price = Some(quote);
             }
 
             // 2. If no quote, try to get transaction price for this date
             if price.is_none() {
                 if let Some(transaction_price) = transaction_days.get(&(investment_id, date)) {
-                    price = Some(*transaction_price);
+                    price = Some((*transaction_price, None));
                 }
             }
 
-            // 3. If still no price, use last known price for this investment
+            // 3. If still no price, use last known price adjusted by the splits since then
             if price.is_none() {
-                price = last_price_by_investment.get(&investment_id).copied();
+                price = last_price.map(|(last_price, split_product, source)| {
+                    (last_price * split_product / held.split_product, source)
+                });
             }
 
             // Only add development if we have a price
-            if let Some(price_value) = price {
+            if let Some((price_value, source)) = price {
                 // Update last known price
-                last_price_by_investment.insert(investment_id, price_value);
+                last_price = Some((price_value, held.split_product, source));
 
                 developments.push(Development {
                     investment: investment_id,
@@ -112,6 +545,7 @@ impl PortfolioCalculator {
                     price: price_value,
                     quantity,
                     value: quantity * price_value,
+                    source: source.map(str::to_string),
                 });
             }
         }
@@ -119,22 +553,586 @@ impl PortfolioCalculator {
         Ok(developments)
     }
 
+    /// Calculate the developments of a single investment, in one portfolio or across all
+    ///
+    /// Only its movements and prices are read, and neither the cache nor the stored
+    /// developments are used.
+    #[tracing::instrument(skip(self))]
+    pub async fn calculate_investment_developments(
+        &self,
+        investment_id: i64,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>> {
+        self.calculate_from_movements(portfolio_id, Some(investment_id), start_date, end_date)
+            .await
+    }
+
+    /// Developments of one investment if given, else of all investments
+    async fn scoped_developments(
+        &self,
+        portfolio_id: Option<i64>,
+        investment_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Development>> {
+        match investment_id {
+            Some(id) => {
+                self.calculate_investment_developments(id, portfolio_id, start_date, end_date)
+                    .await
+            }
+            None => {
+                self.calculate_portfolio_developments(portfolio_id, start_date, end_date)
+                    .await
+            }
+        }
+    }
+
+    /// Calculate developments, optionally for every day an investment is held.
+    ///
+    /// With `DevelopmentFill::Daily` the days between two developments of an investment
+    /// are filled with its last quantity and price, as long as the quantity is not zero.
+    /// Positions still held at the end are filled up to `end_date` (default: the last
+    /// development of any calculated investment). With `investment_id` only that
+    /// investment is calculated.
+    pub async fn calculate_filled_developments(
+        &self,
+        portfolio_id: Option<i64>,
+        investment_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        fill: DevelopmentFill,
+    ) -> Result<Vec<Development>> {
+        if fill == DevelopmentFill::None {
+            return self
+                .scoped_developments(portfolio_id, investment_id, start_date, end_date)
+                .await;
+        }
+
+        // Positions held before the start date are filled into the period
+        let developments = self
+            .scoped_developments(portfolio_id, investment_id, None, end_date)
+            .await?;
+        let mut filled = Self::fill_daily(developments, end_date);
+        if let Some(start) = start_date {
+            filled.retain(|dev| dev.date >= start);
+        }
+        Ok(filled)
+    }
+
+    /// Repeat each development of an investment on the following days until its next
+    /// development, skipping periods in which nothing was held
+    fn fill_daily(developments: Vec<Development>, end_date: Option<NaiveDate>) -> Vec<Development> {
+        let Some(last_date) = end_date.or_else(|| developments.iter().map(|d| d.date).max()) else {
+            return developments;
+        };
+
+        let mut filled = Vec::with_capacity(developments.len());
+        let mut iter = developments.into_iter().peekable();
+        while let Some(dev) = iter.next() {
+            let fill_until = match iter.peek() {
+                Some(next) if next.investment == dev.investment => next.date.pred_opt(),
+                _ => Some(last_date),
+            };
+            let held = !dev.quantity.is_zero();
+            let fill_until = fill_until.filter(|_| held).unwrap_or(dev.date);
+
+            let mut date = dev.date;
+            filled.push(dev.clone());
+            while let Some(next_date) = date.succ_opt().filter(|d| *d <= fill_until) {
+                date = next_date;
+                filled.push(Development {
+                    date,
+                    ..dev.clone()
+                });
+            }
+        }
+
+        filled
+    }
+
+    /// Calculate the total value of all investments for every day.
+    ///
+    /// Between two developments of an investment its last value is carried forward, so
+    /// every day from the first development up to `end_date` (default: the last
+    /// development) has a value. Investments held before `start_date` are included.
+    /// With `investments` only those investments are summed.
+    pub async fn calculate_total_developments(
+        &self,
+        portfolio_id: Option<i64>,
+        investments: Option<&HashSet<i64>>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<TotalDevelopment>> {
+        // The full history is needed to know the values at the start of the period
+        let mut developments = self
+            .calculate_portfolio_developments(portfolio_id, None, end_date)
+            .await?;
+        Self::retain_investments(&mut developments, investments);
+        Ok(Self::sum_daily_values(&developments, start_date, end_date))
+    }
+
+    /// Sum the values of all investments per day, forward-filling missing days
+    fn sum_daily_values(
+        developments: &[Development],
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Vec<TotalDevelopment> {
+        let mut by_date: BTreeMap<NaiveDate, Vec<&Development>> = BTreeMap::new();
+        for dev in developments {
+            by_date.entry(dev.date).or_default().push(dev);
+        }
+        let (Some(&first), Some(&last)) = (by_date.keys().next(), by_date.keys().next_back())
+        else {
+            return Vec::new();
+        };
+
+        let mut last_values: HashMap<i64, Decimal> = HashMap::new();
+        let mut totals = Vec::new();
+        for date in first
+            .iter_days()
+            .take_while(|d| *d <= end_date.unwrap_or(last))
+        {
+            for dev in by_date.get(&date).into_iter().flatten() {
+                last_values.insert(dev.investment, dev.value);
+            }
+            if start_date.map(|start| date >= start).unwrap_or(true) {
+                totals.push(TotalDevelopment {
+                    date,
+                    value: last_values.values().sum(),
+                });
+            }
+        }
+
+        totals
+    }
+
+    /// Keep the last development of each investment per period
+    ///
+    /// Expects developments ordered by investment and date, as returned by the calculations.
+    pub fn resample_developments(
+        developments: Vec<Development>,
+        granularity: Granularity,
+    ) -> Vec<Development> {
+        let mut iter = developments.into_iter().peekable();
+        let mut resampled = Vec::new();
+        while let Some(dev) = iter.next() {
+            let period_ends = iter.peek().is_none_or(|next| {
+                next.investment != dev.investment
+                    || granularity.period_start(next.date) != granularity.period_start(dev.date)
+            });
+            if period_ends {
+                resampled.push(dev);
+            }
+        }
+        resampled
+    }
+
+    /// Keep the last total value per period
+    pub fn resample_totals(
+        totals: Vec<TotalDevelopment>,
+        granularity: Granularity,
+    ) -> Vec<TotalDevelopment> {
+        let mut iter = totals.into_iter().peekable();
+        let mut resampled = Vec::new();
+        while let Some(total) = iter.next() {
+            let period_ends = iter.peek().is_none_or(|next| {
+                granularity.period_start(next.date) != granularity.period_start(total.date)
+            });
+            if period_ends {
+                resampled.push(total);
+            }
+        }
+        resampled
+    }
+
+    /// Calculate the time-weighted return (TWR) per investment and for the total portfolio.
+    ///
+    /// The period is split at every valuation date and the sub-period returns are chained.
+    /// Cash flows happen at the end of a day, so each sub-period return is
+    /// `(value + outflow - inflow) / previous value - 1`. On the day a position is
+    /// opened the return is measured against the invested amount instead; an opening
+    /// position counts as invested at its value on its first valuation date. With
+    /// `investments` the total covers only those investments. With `currency` values and
+    /// cash flows are converted into that currency first, so the return includes the
+    /// exchange rate changes.
+    pub async fn calculate_time_weighted_return(
+        &self,
+        investments: Option<&HashSet<i64>>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        currency: Option<&str>,
+    ) -> Result<TimeWeightedReturn> {
+        // The full history is needed to know the value at the start of the period
+        let mut developments = self.calculate_developments(None, end_date).await?;
+        Self::retain_investments(&mut developments, investments);
+        let mut cash_flows = self.load_cash_flows(investments, &developments).await?;
+
+        let dates = developments
+            .iter()
+            .map(|dev| dev.date)
+            .chain(cash_flows.keys().map(|(_, date)| *date));
+        if let Some(rates) = self.display_rates(currency, dates).await? {
+            Self::apply_display_rates(&mut developments, &rates);
+            for (&(_, date), flow) in cash_flows.iter_mut() {
+                let rate = rates[&date].to_f64().unwrap_or_default();
+                flow.inflow *= rate;
+                flow.outflow *= rate;
+            }
+        }
+
+        let investments = Self::values_by_investment(&developments)
+            .iter()
+            .map(|(&investment_id, values)| {
+                let flows = |date: NaiveDate| {
+                    cash_flows
+                        .get(&(investment_id, date))
+                        .copied()
+                        .unwrap_or_default()
+                };
+                InvestmentReturn {
+                    investment: investment_id,
+                    twr: Self::chain_returns(values.iter().copied(), flows, start_date),
+                }
+            })
+            .collect();
+
+        let flows_by_date = Self::sum_cash_flows_by_date(&cash_flows);
+        let total_flows = |date: NaiveDate| flows_by_date.get(&date).copied().unwrap_or_default();
+        let total_values = Self::total_values(&developments);
+
+        Ok(TimeWeightedReturn {
+            start_date,
+            end_date,
+            total: Self::chain_returns(total_values.into_iter(), total_flows, start_date),
+            investments,
+        })
+    }
+
+    /// Cumulative time-weighted growth on every valuation date from `start_date` on
+    ///
+    /// Growth is chained like the time-weighted return over the full history, so the
+    /// first point of the period need not be 1.
+    pub async fn calculate_growth_series(
+        &self,
+        investments: Option<&HashSet<i64>>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<GrowthSeries> {
+        let mut developments = self.calculate_developments(None, end_date).await?;
+        Self::retain_investments(&mut developments, investments);
+        let cash_flows = self.load_cash_flows(investments, &developments).await?;
+        let in_period = |point: &GrowthPoint| start_date.is_none_or(|start| point.date >= start);
+
+        let investments = Self::values_by_investment(&developments)
+            .into_iter()
+            .map(|(investment_id, values)| {
+                let flows = |date: NaiveDate| {
+                    cash_flows
+                        .get(&(investment_id, date))
+                        .copied()
+                        .unwrap_or_default()
+                };
+                let mut points = Self::growth_series(values.into_iter(), flows);
+                points.retain(in_period);
+                (investment_id, points)
+            })
+            .collect();
+
+        let flows_by_date = Self::sum_cash_flows_by_date(&cash_flows);
+        let total_flows = |date: NaiveDate| flows_by_date.get(&date).copied().unwrap_or_default();
+        let mut total =
+            Self::growth_series(Self::total_values(&developments).into_iter(), total_flows);
+        total.retain(in_period);
+
+        Ok(GrowthSeries { total, investments })
+    }
+
+    /// Compare the portfolio with the stored prices of a benchmark investment
+    ///
+    /// The portfolio is indexed by its time-weighted growth, so buys and sells do not
+    /// move the index, and the benchmark by its price. Both are 100 on the first day
+    /// from `start_date` on with a portfolio value and a benchmark price; days without
+    /// a benchmark quote use the last known price. With `investments` the portfolio
+    /// consists of only those investments.
+    pub async fn calculate_benchmark_comparison(
+        &self,
+        benchmark_investment_id: i64,
+        investments: Option<&HashSet<i64>>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<BenchmarkComparison> {
+        // The full history is needed to know the growth up to the start of the period
+        let mut developments = self.calculate_developments(None, end_date).await?;
+        Self::retain_investments(&mut developments, investments);
+        let flows_by_date =
+            Self::sum_cash_flows_by_date(&self.load_cash_flows(investments, &developments).await?);
+        let total_flows = |date: NaiveDate| flows_by_date.get(&date).copied().unwrap_or_default();
+
+        let totals = Self::sum_daily_values(&developments, None, end_date);
+        let growth = Self::growth_series(
+            totals
+                .iter()
+                .map(|t| (t.date, t.value.to_f64().unwrap_or_default())),
+            total_flows,
+        );
+
+        let benchmark_prices: BTreeMap<NaiveDate, f64> = self
+            .price_repo
+            .find_all(Some(benchmark_investment_id), None, end_date)
+            .await?
+            .into_iter()
+            .filter_map(|p| Some((p.date?, p.price?.to_f64()?)))
+            .collect();
+
+        let mut base: Option<(f64, f64)> = None;
+        let mut series = Vec::new();
+        for GrowthPoint {
+            date,
+            growth: portfolio_growth,
+            ..
+        } in growth
+        {
+            if start_date.is_some_and(|start| date < start) {
+                continue;
+            }
+            let Some((_, &price)) = benchmark_prices.range(..=date).next_back() else {
+                continue;
+            };
+            let (base_growth, base_price) = *base.get_or_insert((portfolio_growth, price));
+            series.push(BenchmarkPoint {
+                date,
+                portfolio: 100.0 * portfolio_growth / base_growth,
+                benchmark: 100.0 * price / base_price,
+            });
+        }
+
+        Ok(BenchmarkComparison {
+            benchmark_investment_id,
+            series,
+        })
+    }
+
+    /// Growth of a value over one sub-period with the cash flows at its end
+    fn period_growth(previous_value: f64, value: f64, flow: CashFlow) -> f64 {
+        if previous_value > 0.0 {
+            (value + flow.outflow - flow.inflow) / previous_value
+        } else if flow.inflow > 0.0 {
+            (value + flow.outflow) / flow.inflow
+        } else {
+            1.0
+        }
+    }
+
+    /// Chain sub-period returns of a date-sorted value series, starting after `start_date`
+    fn chain_returns(
+        values: impl Iterator<Item = (NaiveDate, f64)>,
+        flows: impl Fn(NaiveDate) -> CashFlow,
+        start_date: Option<NaiveDate>,
+    ) -> f64 {
+        let mut growth = 1.0;
+        let mut previous_value = 0.0;
+
+        for (date, value) in values {
+            let in_period = start_date.map(|start| date > start).unwrap_or(true);
+            if in_period {
+                growth *= Self::period_growth(previous_value, value, flows(date));
+            }
+            previous_value = value;
+        }
+
+        growth - 1.0
+    }
+
+    /// Cumulative growth factor after each date of a date-sorted value series
+    fn growth_series(
+        values: impl Iterator<Item = (NaiveDate, f64)>,
+        flows: impl Fn(NaiveDate) -> CashFlow,
+    ) -> Vec<GrowthPoint> {
+        let mut growth = 1.0;
+        let mut previous_value = 0.0;
+
+        values
+            .map(|(date, value)| {
+                growth *= Self::period_growth(previous_value, value, flows(date));
+                previous_value = value;
+                GrowthPoint {
+                    date,
+                    value,
+                    growth,
+                }
+            })
+            .collect()
+    }
+
+    /// Value series of each investment, ordered by date
+    ///
+    /// Returns are ratios, so they are chained as floating point numbers.
+    fn values_by_investment(developments: &[Development]) -> BTreeMap<i64, Vec<(NaiveDate, f64)>> {
+        let mut values: BTreeMap<i64, Vec<(NaiveDate, f64)>> = BTreeMap::new();
+        for dev in developments {
+            values
+                .entry(dev.investment)
+                .or_default()
+                .push((dev.date, dev.value.to_f64().unwrap_or_default()));
+        }
+        values
+    }
+
+    /// Total portfolio value per valuation date, carrying each investment's last value forward
+    fn total_values(developments: &[Development]) -> Vec<(NaiveDate, f64)> {
+        let mut sorted_devs: Vec<&Development> = developments.iter().collect();
+        sorted_devs.sort_by_key(|d| d.date);
+        let mut last_values: HashMap<i64, Decimal> = HashMap::new();
+        let mut total_values: Vec<(NaiveDate, f64)> = Vec::new();
+        for dev in sorted_devs {
+            last_values.insert(dev.investment, dev.value);
+            let total = last_values
+                .values()
+                .sum::<Decimal>()
+                .to_f64()
+                .unwrap_or_default();
+            match total_values.last_mut() {
+                Some((date, value)) if *date == dev.date => *value = total,
+                _ => total_values.push((dev.date, total)),
+            }
+        }
+        total_values
+    }
+
+    /// Keep the developments of the given investments, all if `None`
+    fn retain_investments(developments: &mut Vec<Development>, investments: Option<&HashSet<i64>>) {
+        if let Some(ids) = investments {
+            developments.retain(|dev| ids.contains(&dev.investment));
+        }
+    }
+
+    /// Cash flows of all movements or those of the given investments, with custom action
+    /// types calculated by their effect and opening positions valued by the developments
+    async fn load_cash_flows(
+        &self,
+        investments: Option<&HashSet<i64>>,
+        developments: &[Development],
+    ) -> Result<HashMap<(i64, NaiveDate), CashFlow>> {
+        let mut movements = self.movement_repo.find_all().await?;
+        if let Some(ids) = investments {
+            movements.retain(|m| m.investment_id.is_some_and(|id| ids.contains(&id)));
+        }
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+        let mut flows = self.aggregate_cash_flows(&movements);
+        Self::add_opening_flows(&mut flows, &movements, developments);
+        Ok(flows)
+    }
+
+    /// Opening positions as inflows of their value on the first valuation date from their
+    /// date on
+    ///
+    /// The value an opening position starts with was not earned in the recorded history,
+    /// so it is treated like money put in. Its cost basis only matters for gains.
+    fn add_opening_flows(
+        flows: &mut HashMap<(i64, NaiveDate), CashFlow>,
+        movements: &[Movement],
+        developments: &[Development],
+    ) {
+        let prices: BTreeMap<(i64, NaiveDate), Decimal> = developments
+            .iter()
+            .map(|dev| ((dev.investment, dev.date), dev.price))
+            .collect();
+        for movement in movements {
+            let (Some(OPENING_ACTION_ID), Some(inv_id), Some(date), Some(quantity)) = (
+                movement.action_id,
+                movement.investment_id,
+                movement.date,
+                movement.quantity,
+            ) else {
+                continue;
+            };
+            let Some((&key, price)) = prices
+                .range((inv_id, date)..=(inv_id, NaiveDate::MAX))
+                .next()
+            else {
+                continue;
+            };
+            flows.entry(key).or_default().inflow +=
+                (quantity.abs() * price).to_f64().unwrap_or_default();
+        }
+    }
+
+    /// Cash flows of all investments per date
+    fn sum_cash_flows_by_date(
+        cash_flows: &HashMap<(i64, NaiveDate), CashFlow>,
+    ) -> HashMap<NaiveDate, CashFlow> {
+        let mut flows_by_date: HashMap<NaiveDate, CashFlow> = HashMap::new();
+        for (&(_, date), flow) in cash_flows {
+            let total = flows_by_date.entry(date).or_default();
+            total.inflow += flow.inflow;
+            total.outflow += flow.outflow;
+        }
+        flows_by_date
+    }
+
+    /// Aggregate buy amounts as inflows and sell/payout amounts as outflows
+    ///
+    /// Fees and taxes add to what a buy puts in and reduce what a sell or payout takes out,
+    /// so they lower the return.
+    fn aggregate_cash_flows(&self, movements: &[Movement]) -> HashMap<(i64, NaiveDate), CashFlow> {
+        let mut flows: HashMap<(i64, NaiveDate), CashFlow> = HashMap::new();
+
+        for movement in movements {
+            if let (Some(inv_id), Some(date), Some(amount), Some(action_id)) = (
+                movement.investment_id,
+                movement.date,
+                movement.amount,
+                movement.action_id,
+            ) {
+                let flow = flows.entry((inv_id, date)).or_default();
+                let amount = amount.abs();
+                let costs = movement.costs();
+                match action_id {
+                    1 => flow.inflow += (amount + costs).to_f64().unwrap_or_default(),
+                    2 | 3 => flow.outflow += (amount - costs).to_f64().unwrap_or_default(),
+                    _ => {}
+                }
+            }
+        }
+
+        flows
+    }
+
     /// Calculate average transaction price for each (investment, date) pair
-    fn calculate_transaction_days(&self, movements: &[Movement]) -> HashMap<(i64, NaiveDate), f64> {
-        let mut transaction_map: HashMap<(i64, NaiveDate), Vec<f64>> = HashMap::new();
+    fn calculate_transaction_days(
+        &self,
+        movements: &[Movement],
+    ) -> HashMap<(i64, NaiveDate), Decimal> {
+        let mut transaction_map: HashMap<(i64, NaiveDate), Vec<Decimal>> = HashMap::new();
 
         for movement in movements {
+            // Splits, transfers and openings do not happen at a market price
+            if matches!(
+                movement.action_id,
+                Some(
+                    SPLIT_ACTION_ID
+                        | TRANSFER_IN_ACTION_ID
+                        | TRANSFER_OUT_ACTION_ID
+                        | OPENING_ACTION_ID
+                )
+            ) {
+                continue;
+            }
             if let (Some(inv_id), Some(date), Some(amount), Some(quantity)) = (
                 movement.investment_id,
                 movement.date,
                 movement.amount,
                 movement.quantity,
             ) {
-                if quantity != 0.0 {
+                if !quantity.is_zero() {
                     let transaction_price = (amount / quantity).abs();
                     transaction_map
                         .entry((inv_id, date))
-                        .or_insert_with(Vec::new)
+                        .or_default()
                         .push(transaction_price);
                 }
             }
@@ -144,32 +1142,32 @@ impl PortfolioCalculator {
         transaction_map
             .into_iter()
             .map(|(key, prices)| {
-                let avg = prices.iter().sum::<f64>() / prices.len() as f64;
+                let avg = prices.iter().sum::<Decimal>() / Decimal::from(prices.len());
                 (key, avg)
             })
             .collect()
     }
 
-    /// Create a mapping of (investment, date) -> quote price
-    fn create_quote_price_map(&self, prices: &[InvestmentPrice]) -> HashMap<(i64, NaiveDate), f64> {
-        prices
-            .iter()
-            .filter_map(|p| {
-                if let (Some(inv_id), Some(date), Some(price)) = (p.investment_id, p.date, p.price)
-                {
-                    Some(((inv_id, date), price))
-                } else {
-                    None
-                }
-            })
+    /// Create a mapping of (investment, date) -> quote price and source, taking the
+    /// preferred source where several have a price
+    fn create_quote_price_map<'a>(
+        &self,
+        prices: &'a [InvestmentPrice],
+        priority: &PriceSourcePriority,
+    ) -> HashMap<(i64, NaiveDate), (Decimal, Option<&'a str>)> {
+        priority
+            .select(prices)
+            .into_iter()
+            .filter_map(|(key, p)| Some((key, (p.price?, p.source.as_deref()))))
             .collect()
     }
 
-    /// Collect all unique (investment, date) pairs from transactions and quotes
+    /// Collect all unique (investment, date) pairs from transactions, quotes and splits
     fn collect_all_dates(
         &self,
-        transaction_days: &HashMap<(i64, NaiveDate), f64>,
+        transaction_days: &HashMap<(i64, NaiveDate), Decimal>,
         prices: &[InvestmentPrice],
+        quantity_changes: &HashMap<i64, QuantityChanges>,
     ) -> Vec<(i64, NaiveDate)> {
         let mut all_dates: HashSet<(i64, NaiveDate)> = HashSet::new();
 
@@ -185,44 +1183,18 @@ impl PortfolioCalculator {
             }
         }
 
-        // Sort by investment and date
-        let mut sorted_dates: Vec<_> = all_dates.into_iter().collect();
-        sorted_dates.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
-        sorted_dates
-    }
-
-    /// Aggregate movements by action type (1=Buy, 2=Sell)
-    fn aggregate_movements(
-        &self,
-        movements: &[Movement],
-        action_id: i64,
-    ) -> HashMap<(i64, NaiveDate), f64> {
-        let mut aggregates: HashMap<(i64, NaiveDate), f64> = HashMap::new();
-
-        for movement in movements {
-            if movement.action_id == Some(action_id) {
-                if let (Some(inv_id), Some(date), Some(quantity)) =
-                    (movement.investment_id, movement.date, movement.quantity)
-                {
-                    *aggregates.entry((inv_id, date)).or_insert(0.0) += quantity;
+        // Add split dates, so the quantity change shows on the day of the split
+        for (&inv_id, changes) in quantity_changes {
+            for (date, change) in changes {
+                if let QuantityChange::Split(_) = change {
+                    all_dates.insert((inv_id, *date));
                 }
             }
         }
 
-        aggregates
-    }
-
-    /// Sum quantities up to and including a specific date
-    fn sum_quantities(
-        &self,
-        aggregates: &HashMap<(i64, NaiveDate), f64>,
-        investment_id: i64,
-        up_to_date: NaiveDate,
-    ) -> f64 {
-        aggregates
-            .iter()
-            .filter(|((inv_id, date), _)| *inv_id == investment_id && *date <= up_to_date)
-            .map(|(_, quantity)| quantity)
-            .sum()
+        // Sort by investment and date
+        let mut sorted_dates: Vec<_> = all_dates.into_iter().collect();
+        sorted_dates.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+        sorted_dates
     }
 }
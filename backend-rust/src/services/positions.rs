@@ -0,0 +1,251 @@
+use crate::error::Result;
+use crate::models::{Movement, MovementListOptions};
+use crate::repository::traits::{ActionTypeRepository, MovementRepository};
+use crate::services::action_effects::ActionEffects;
+use crate::services::cost_basis::{
+    OPENING_ACTION_ID, SPLIT_ACTION_ID, TRANSFER_IN_ACTION_ID, TRANSFER_OUT_ACTION_ID,
+};
+use crate::services::display_precision;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Quantity held of an investment at the end of a day
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PositionPoint {
+    pub date: NaiveDate,
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub quantity: Decimal,
+}
+
+/// A change of the quantity held in an investment
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum QuantityChange {
+    /// Shares bought or transferred in (positive), sold or transferred out (negative)
+    Trade(Decimal),
+    /// Stock split with the number of new shares per old share
+    Split(Decimal),
+}
+
+/// Quantity changes of one investment, ordered by date with splits before trades
+pub(crate) type QuantityChanges = Vec<(NaiveDate, QuantityChange)>;
+
+/// Running totals of the quantity changes of one investment up to a date
+///
+/// Dates are visited in ascending order, so every change is applied once instead of
+/// summing all earlier changes again for every date.
+pub(crate) struct QuantityCursor<'a> {
+    changes: &'a [(NaiveDate, QuantityChange)],
+    next: usize,
+    pub(crate) quantity: Decimal,
+    /// Product of all split ratios applied so far
+    pub(crate) split_product: Decimal,
+}
+
+impl<'a> QuantityCursor<'a> {
+    pub(crate) fn new(changes: &'a [(NaiveDate, QuantityChange)]) -> Self {
+        Self {
+            changes,
+            next: 0,
+            quantity: Decimal::ZERO,
+            split_product: Decimal::ONE,
+        }
+    }
+
+    /// Apply all changes up to and including `date`, which must not be before earlier calls
+    pub(crate) fn advance_to(&mut self, date: NaiveDate) {
+        while let Some((change_date, change)) = self.changes.get(self.next) {
+            if *change_date > date {
+                break;
+            }
+            match change {
+                QuantityChange::Trade(traded) => self.quantity += traded,
+                QuantityChange::Split(ratio) => {
+                    self.quantity *= ratio;
+                    self.split_product *= ratio;
+                }
+            }
+            self.next += 1;
+        }
+    }
+}
+
+/// Buys (1), sells (2), openings, transfers and splits per investment, in the order they apply
+///
+/// Transfers between portfolios cancel out unless the movements are restricted to
+/// one portfolio.
+pub(crate) fn collect_quantity_changes(movements: &[Movement]) -> HashMap<i64, QuantityChanges> {
+    let mut changes: HashMap<i64, QuantityChanges> = HashMap::new();
+
+    for movement in movements {
+        let (Some(inv_id), Some(date), Some(quantity)) =
+            (movement.investment_id, movement.date, movement.quantity)
+        else {
+            continue;
+        };
+        let change = match movement.action_id {
+            Some(1 | OPENING_ACTION_ID) => QuantityChange::Trade(quantity),
+            Some(2) => QuantityChange::Trade(-quantity),
+            Some(TRANSFER_IN_ACTION_ID) => QuantityChange::Trade(quantity.abs()),
+            Some(TRANSFER_OUT_ACTION_ID) => QuantityChange::Trade(-quantity.abs()),
+            Some(SPLIT_ACTION_ID) if quantity > Decimal::ZERO => QuantityChange::Split(quantity),
+            _ => continue,
+        };
+        changes.entry(inv_id).or_default().push((date, change));
+    }
+
+    for investment_changes in changes.values_mut() {
+        investment_changes
+            .sort_by_key(|(date, change)| (*date, matches!(change, QuantityChange::Trade(_))));
+    }
+
+    changes
+}
+
+/// Quantity currently held per investment after all buys, sells, transfers and splits
+///
+/// Movements of custom action types need to be normalized with [`ActionEffects`] first.
+pub fn current_quantities(movements: &[Movement]) -> HashMap<i64, Decimal> {
+    collect_quantity_changes(movements)
+        .into_iter()
+        .map(|(investment, changes)| {
+            let mut cursor = QuantityCursor::new(&changes);
+            if let Some((last, _)) = changes.last() {
+                cursor.advance_to(*last);
+            }
+            (investment, cursor.quantity)
+        })
+        .collect()
+}
+
+/// Quantity of an investment held at the end of `date`, including the changes of that day
+///
+/// Movements of custom action types need to be normalized with [`ActionEffects`] first.
+pub fn quantity_at(movements: &[Movement], investment_id: i64, date: NaiveDate) -> Decimal {
+    let changes = collect_quantity_changes(movements)
+        .remove(&investment_id)
+        .unwrap_or_default();
+    let mut cursor = QuantityCursor::new(&changes);
+    cursor.advance_to(date);
+    cursor.quantity
+}
+
+/// Quantity of an investment held at the end of every day with a buy, sell, transfer or
+/// split, ordered by date
+///
+/// Movements of custom action types need to be normalized with [`ActionEffects`] first.
+pub fn position_history(movements: &[Movement], investment_id: i64) -> Vec<PositionPoint> {
+    let changes = collect_quantity_changes(movements)
+        .remove(&investment_id)
+        .unwrap_or_default();
+    let mut dates: Vec<NaiveDate> = changes.iter().map(|(date, _)| *date).collect();
+    dates.dedup();
+    let mut cursor = QuantityCursor::new(&changes);
+    dates
+        .into_iter()
+        .map(|date| {
+            cursor.advance_to(date);
+            PositionPoint {
+                date,
+                quantity: cursor.quantity,
+            }
+        })
+        .collect()
+}
+
+/// Days at whose end the quantity held of an investment is below zero, per investment
+///
+/// Movements of custom action types need to be normalized with [`ActionEffects`] first.
+pub fn negative_quantity_dates(movements: &[Movement]) -> HashMap<i64, Vec<NaiveDate>> {
+    collect_quantity_changes(movements)
+        .into_iter()
+        .filter_map(|(investment, changes)| {
+            let mut cursor = QuantityCursor::new(&changes);
+            let mut dates: Vec<NaiveDate> = changes.iter().map(|(date, _)| *date).collect();
+            dates.dedup();
+            dates.retain(|date| {
+                cursor.advance_to(*date);
+                cursor.quantity < Decimal::ZERO
+            });
+            (!dates.is_empty()).then_some((investment, dates))
+        })
+        .collect()
+}
+
+/// Quantities held of the stored movements, without looking at any price, shared by the developments, summaries, quote fetch
+/// and sell validation
+pub struct PositionCalculator {
+    movement_repo: Arc<dyn MovementRepository>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl PositionCalculator {
+    pub fn new(movement_repo: Arc<dyn MovementRepository>) -> Self {
+        Self {
+            movement_repo,
+            action_type_repo: None,
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Normalized movements, of one portfolio and investment if given
+    async fn movements(
+        &self,
+        portfolio_id: Option<i64>,
+        investment_id: Option<i64>,
+    ) -> Result<Vec<Movement>> {
+        let mut movements = if portfolio_id.is_none() && investment_id.is_none() {
+            self.movement_repo.find_all().await?
+        } else {
+            let options = MovementListOptions {
+                portfolio_id,
+                investment_id,
+                ..Default::default()
+            };
+            let (movements, _) = self.movement_repo.find_page(&options).await?;
+            movements
+        };
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+        Ok(movements)
+    }
+
+    /// Quantity currently held per investment, in one portfolio or across all
+    pub async fn current_quantities(
+        &self,
+        portfolio_id: Option<i64>,
+    ) -> Result<HashMap<i64, Decimal>> {
+        let movements = self.movements(portfolio_id, None).await?;
+        Ok(current_quantities(&movements))
+    }
+
+    /// Quantity of an investment held at the end of `date`, in one portfolio or across all
+    pub async fn quantity_at(
+        &self,
+        investment_id: i64,
+        portfolio_id: Option<i64>,
+        date: NaiveDate,
+    ) -> Result<Decimal> {
+        let movements = self.movements(portfolio_id, Some(investment_id)).await?;
+        Ok(quantity_at(&movements, investment_id, date))
+    }
+
+    /// Quantity of an investment held after every day its position changed, in one
+    /// portfolio or across all
+    pub async fn history(
+        &self,
+        investment_id: i64,
+        portfolio_id: Option<i64>,
+    ) -> Result<Vec<PositionPoint>> {
+        let movements = self.movements(portfolio_id, Some(investment_id)).await?;
+        Ok(position_history(&movements, investment_id))
+    }
+}
@@ -0,0 +1,62 @@
+use crate::error::Result;
+use crate::models::{AlertDirection, TriggeredAlert};
+use crate::repository::traits::PriceAlertRepository;
+use chrono::{NaiveDate, Utc};
+use std::sync::Arc;
+
+/// Checks the active price alerts of an investment against a new price
+#[derive(Clone)]
+pub struct PriceAlertService {
+    alert_repo: Arc<dyn PriceAlertRepository>,
+}
+
+impl PriceAlertService {
+    pub fn new(alert_repo: Arc<dyn PriceAlertRepository>) -> Self {
+        Self { alert_repo }
+    }
+
+    /// Record a trigger for every active alert whose threshold the price crossed and
+    /// deactivate these alerts. Returns the recorded triggers.
+    pub async fn evaluate(
+        &self,
+        investment_id: i64,
+        price_date: NaiveDate,
+        price: f64,
+    ) -> Result<Vec<TriggeredAlert>> {
+        let mut triggered = Vec::new();
+        for alert in self.alert_repo.find_active(investment_id).await? {
+            let direction = match alert.direction.parse::<AlertDirection>() {
+                Ok(direction) => direction,
+                Err(e) => {
+                    tracing::warn!("Skipping price alert {}: {}", alert.id, e);
+                    continue;
+                }
+            };
+            if !direction.is_crossed(price, alert.threshold) {
+                continue;
+            }
+
+            let mut trigger = TriggeredAlert {
+                id: 0,
+                alert_id: alert.id,
+                investment_id,
+                triggered_at: Utc::now(),
+                price_date,
+                price,
+                threshold: alert.threshold,
+                direction: alert.direction,
+            };
+            trigger.id = self.alert_repo.record_trigger(&trigger).await?;
+            tracing::info!(
+                "Price alert {} triggered: investment {} at {} is {} {}",
+                alert.id,
+                investment_id,
+                price,
+                direction,
+                alert.threshold
+            );
+            triggered.push(trigger);
+        }
+        Ok(triggered)
+    }
+}
@@ -0,0 +1,191 @@
+use crate::error::Result;
+use crate::models::{InvestmentPrice, Movement};
+use crate::repository::traits::{
+    ActionTypeRepository, InvestmentPriceRepository, MovementRepository,
+};
+use crate::services::action_effects::ActionEffects;
+use crate::services::cost_basis::OPENING_ACTION_ID;
+use chrono::{Datelike, NaiveDate, Utc, Weekday};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+/// Consecutive days without a stored price
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceGap {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Number of missing days, excluding weekends unless they are checked
+    pub missing_days: usize,
+}
+
+/// Missing prices of one investment while it was held
+#[derive(Debug, Clone, Serialize)]
+pub struct InvestmentPriceGaps {
+    pub investment: i64,
+    pub missing_days: usize,
+    pub gaps: Vec<PriceGap>,
+}
+
+/// Which days to check for missing prices
+#[derive(Debug, Clone, Copy)]
+pub struct GapOptions {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: NaiveDate,
+    pub include_weekends: bool,
+}
+
+/// Periods in which the quantity held (openings and buys minus sells) was positive.
+/// A position that is still open ends at `end_date`.
+fn holding_periods(
+    movements: &[Movement],
+    end_date: NaiveDate,
+) -> BTreeMap<i64, Vec<(NaiveDate, NaiveDate)>> {
+    let mut by_investment: BTreeMap<i64, Vec<&Movement>> = BTreeMap::new();
+    for movement in movements {
+        if let (Some(inv_id), Some(_)) = (movement.investment_id, movement.date) {
+            by_investment.entry(inv_id).or_default().push(movement);
+        }
+    }
+
+    let mut periods: BTreeMap<i64, Vec<(NaiveDate, NaiveDate)>> = BTreeMap::new();
+    for (inv_id, mut inv_movements) in by_investment {
+        inv_movements.sort_by_key(|m| (m.date, m.id));
+
+        let mut quantity = Decimal::ZERO;
+        let mut opened: Option<NaiveDate> = None;
+        for movement in inv_movements {
+            let Some(date) = movement.date else { continue };
+            let change = movement.quantity.unwrap_or_default().abs();
+            match movement.action_id {
+                Some(1 | OPENING_ACTION_ID) => quantity += change,
+                Some(2) => quantity -= change,
+                _ => continue,
+            }
+
+            if quantity > Decimal::ZERO {
+                opened.get_or_insert(date);
+            } else if let Some(start) = opened.take() {
+                periods.entry(inv_id).or_default().push((start, date));
+            }
+        }
+
+        if let Some(start) = opened {
+            periods.entry(inv_id).or_default().push((start, end_date));
+        }
+    }
+
+    periods
+}
+
+/// Report date ranges without a price for every investment while it was held.
+///
+/// Weekends are skipped unless `include_weekends` is set; a gap spanning a
+/// weekend is reported as one range.
+pub fn find_price_gaps(
+    movements: &[Movement],
+    prices: &[InvestmentPrice],
+    options: GapOptions,
+) -> Vec<InvestmentPriceGaps> {
+    let mut priced_dates: HashMap<i64, HashSet<NaiveDate>> = HashMap::new();
+    for price in prices {
+        if let (Some(inv_id), Some(date), Some(_)) = (price.investment_id, price.date, price.price)
+        {
+            priced_dates.entry(inv_id).or_default().insert(date);
+        }
+    }
+
+    let is_expected = |date: NaiveDate| {
+        options.include_weekends || !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+    };
+
+    let mut reports = Vec::new();
+    for (inv_id, periods) in holding_periods(movements, options.end_date) {
+        let dates = priced_dates.get(&inv_id);
+        let mut gaps: Vec<PriceGap> = Vec::new();
+
+        for (start, end) in periods {
+            let start = options.start_date.map_or(start, |s| start.max(s));
+            let end = end.min(options.end_date);
+            let mut current: Option<PriceGap> = None;
+
+            for date in start.iter_days().take_while(|d| *d <= end) {
+                if !is_expected(date) {
+                    continue;
+                }
+                if dates.map(|d| d.contains(&date)).unwrap_or(false) {
+                    gaps.extend(current.take());
+                    continue;
+                }
+                let gap = current.get_or_insert(PriceGap {
+                    start_date: date,
+                    end_date: date,
+                    missing_days: 0,
+                });
+                gap.end_date = date;
+                gap.missing_days += 1;
+            }
+            gaps.extend(current);
+        }
+
+        if !gaps.is_empty() {
+            reports.push(InvestmentPriceGaps {
+                investment: inv_id,
+                missing_days: gaps.iter().map(|g| g.missing_days).sum(),
+                gaps,
+            });
+        }
+    }
+
+    reports
+}
+
+pub struct PriceGapService {
+    movement_repo: Arc<dyn MovementRepository>,
+    price_repo: Arc<dyn InvestmentPriceRepository>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl PriceGapService {
+    pub fn new(
+        movement_repo: Arc<dyn MovementRepository>,
+        price_repo: Arc<dyn InvestmentPriceRepository>,
+    ) -> Self {
+        Self {
+            movement_repo,
+            price_repo,
+            action_type_repo: None,
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Find missing prices of held investments up to `end_date` (default: today)
+    pub async fn find_gaps(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        include_weekends: bool,
+    ) -> Result<Vec<InvestmentPriceGaps>> {
+        let mut movements = self.movement_repo.find_all().await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+        let prices = self.price_repo.find_all(None, start_date, end_date).await?;
+
+        Ok(find_price_gaps(
+            &movements,
+            &prices,
+            GapOptions {
+                start_date,
+                end_date: end_date.unwrap_or_else(|| Utc::now().date_naive()),
+                include_weekends,
+            },
+        ))
+    }
+}
@@ -0,0 +1,105 @@
+use crate::error::{AppError, Result};
+use crate::models::InvestmentPrice;
+use crate::repository::traits::{FxRateRepository, InvestmentPriceRepository, SettingsRepository};
+use crate::services::currency_converter::CurrencyConverter;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Outcome of re-deriving stored prices in the current base currency
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceRecalculationResult {
+    pub base_currency: String,
+    pub updated: usize,
+    /// Prices without original currency and price, or without an exchange rate
+    pub skipped: usize,
+}
+
+pub struct PriceRecalculationService {
+    price_repo: Arc<dyn InvestmentPriceRepository>,
+    settings_repo: Arc<dyn SettingsRepository>,
+    currency_converter: CurrencyConverter,
+}
+
+impl PriceRecalculationService {
+    pub fn new(
+        price_repo: Arc<dyn InvestmentPriceRepository>,
+        settings_repo: Arc<dyn SettingsRepository>,
+        fx_rate_repo: Arc<dyn FxRateRepository>,
+    ) -> Self {
+        Self {
+            price_repo,
+            settings_repo,
+            currency_converter: CurrencyConverter::new().with_cache(fx_rate_repo),
+        }
+    }
+
+    /// Convert every stored price from its original currency into the current base currency
+    ///
+    /// Prices stored before the original quote was kept cannot be re-derived and are skipped.
+    pub async fn recalculate(&self) -> Result<PriceRecalculationResult> {
+        let base_currency = self
+            .settings_repo
+            .get()
+            .await?
+            .map(|s| s.base_currency)
+            .ok_or_else(|| AppError::not_found("Settings", 1))?;
+
+        let prices = self.price_repo.find_all(None, None, None).await?;
+        let total = prices.len();
+
+        // Group re-derivable prices by their original currency
+        let mut by_currency: BTreeMap<String, Vec<InvestmentPrice>> = BTreeMap::new();
+        for price in prices {
+            if price.date.is_none() || price.source.is_none() || price.original_price.is_none() {
+                continue;
+            }
+            if let Some(currency) = price.currency.clone() {
+                by_currency.entry(currency).or_default().push(price);
+            }
+        }
+
+        let mut updated = 0;
+        for (currency, prices) in by_currency {
+            let values: Vec<(NaiveDate, Decimal)> = prices
+                .iter()
+                .filter_map(|p| Some((p.date?, p.original_price?)))
+                .collect();
+            let converted = self
+                .currency_converter
+                .convert_series(&values, &currency, &base_currency)
+                .await?;
+
+            for (mut price, converted) in prices.into_iter().zip(converted) {
+                let Some(converted) = converted else {
+                    tracing::warn!(
+                        "No {} to {} rate for price of investment {:?} on {:?}",
+                        currency,
+                        base_currency,
+                        price.investment_id,
+                        price.date
+                    );
+                    continue;
+                };
+                price.price = Some(converted);
+                self.price_repo.upsert(&price).await?;
+                updated += 1;
+            }
+        }
+
+        tracing::info!(
+            "Recalculated {} of {} prices in {}",
+            updated,
+            total,
+            base_currency
+        );
+
+        Ok(PriceRecalculationResult {
+            base_currency,
+            updated,
+            skipped: total - updated,
+        })
+    }
+}
@@ -0,0 +1,106 @@
+use crate::error::Result;
+use crate::models::{Investment, InvestmentPrice};
+use crate::repository::traits::{InvestmentRepository, SettingsRepository};
+use crate::services::quote_fetcher::parse_provider_chain;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Order in which quote sources are preferred when several sources have a price for the
+/// same investment and date
+///
+/// The quote provider chain of an investment comes first, then the global priority from
+/// the settings. Sources in neither list follow in alphabetical order and prices without
+/// source come last, so the choice never depends on the order prices are loaded in.
+#[derive(Debug, Clone, Default)]
+pub struct PriceSourcePriority {
+    global: Vec<String>,
+    investments: HashMap<i64, Vec<String>>,
+}
+
+/// Position of a source, smaller is preferred
+type Rank = (usize, usize, bool, String);
+
+impl PriceSourcePriority {
+    pub fn new(global: &[String], investments: &[Investment]) -> Self {
+        let normalize = |source: &str| source.trim().to_lowercase();
+        Self {
+            global: global.iter().map(|s| normalize(s)).collect(),
+            investments: investments
+                .iter()
+                .filter_map(|inv| {
+                    let chain = parse_provider_chain(inv.quote_provider.as_deref()?);
+                    Some((inv.id, chain.into_iter().map(normalize).collect()))
+                })
+                .collect(),
+        }
+    }
+
+    /// Priority from the settings and the investments' quote providers, or only the
+    /// deterministic fallback order without repositories
+    pub async fn load(
+        settings_repo: Option<&Arc<dyn SettingsRepository>>,
+        investment_repo: Option<&Arc<dyn InvestmentRepository>>,
+    ) -> Result<Self> {
+        let global = match settings_repo {
+            Some(repo) => repo
+                .get()
+                .await?
+                .map(|s| s.price_source_priority.0)
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        let investments = match investment_repo {
+            Some(repo) => repo.find_all().await?,
+            None => Vec::new(),
+        };
+        Ok(Self::new(&global, &investments))
+    }
+
+    fn rank(&self, investment_id: i64, source: Option<&str>) -> Rank {
+        let Some(source) = source else {
+            return (usize::MAX, usize::MAX, true, String::new());
+        };
+        let source = source.to_lowercase();
+        let position = |order: &[String]| {
+            order
+                .iter()
+                .position(|s| *s == source)
+                .unwrap_or(usize::MAX)
+        };
+        let investment = self
+            .investments
+            .get(&investment_id)
+            .map(|order| position(order))
+            .unwrap_or(usize::MAX);
+        (investment, position(&self.global), false, source)
+    }
+
+    /// The preferred price for each (investment, date)
+    pub fn select<'a>(
+        &self,
+        prices: &'a [InvestmentPrice],
+    ) -> HashMap<(i64, NaiveDate), &'a InvestmentPrice> {
+        let mut selected: HashMap<(i64, NaiveDate), (Rank, &InvestmentPrice)> = HashMap::new();
+        for price in prices {
+            let (Some(investment_id), Some(date), Some(_)) =
+                (price.investment_id, price.date, price.price)
+            else {
+                continue;
+            };
+            let rank = self.rank(investment_id, price.source.as_deref());
+            match selected.get(&(investment_id, date)) {
+                // Sources differing only in case fall back to the exact name
+                Some((best, current))
+                    if (best, current.source.as_deref()) <= (&rank, price.source.as_deref()) => {}
+                _ => {
+                    selected.insert((investment_id, date), (rank, price));
+                }
+            }
+        }
+        selected
+            .into_iter()
+            .map(|(key, (_, price))| (key, price))
+            .collect()
+    }
+}
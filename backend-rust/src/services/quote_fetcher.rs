@@ -1,8 +1,24 @@
-use crate::error::Result;
-use crate::models::{Investment, InvestmentPrice};
-use crate::repository::traits::{InvestmentPriceRepository, InvestmentRepository};
+use crate::error::{AppError, Result};
+use crate::models::{
+    DividendEvent, DividendStatus, IntradayPrice, Investment, InvestmentPrice, QuoteFetchLog,
+    SplitEvent,
+};
+use crate::repository::traits::{
+    CorporateEventRepository, FxRateRepository, IntradayPriceRepository, InvestmentPriceRepository,
+    InvestmentRepository, PriceAlertRepository, ProviderConfigRepository, QuoteFetchLogRepository,
+};
 use crate::services::currency_converter::CurrencyConverter;
-use crate::services::quotes::{JustETFProvider, QuoteProvider, YahooFinanceProvider};
+use crate::services::email::{alert_email, EmailNotifier};
+use crate::services::positions::PositionCalculator;
+use crate::services::price_alerts::PriceAlertService;
+use crate::services::quotes::{
+    CoinGeckoProvider, InstrumentInfo, JustETFProvider, ProviderApiKeys, QuoteData, QuoteProvider,
+    YahooFinanceProvider,
+};
+use crate::services::webhooks::{PriceAlertNotification, WebhookEvent, WebhookNotifier};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -12,6 +28,54 @@ pub struct QuoteFetchResult {
     pub success: bool,
     pub error: Option<String>,
     pub quotes_stored: usize,
+    /// Provider that delivered the quotes
+    pub provider: Option<String>,
+}
+
+impl QuoteFetchResult {
+    fn failed(investment_id: i64, error: String) -> Self {
+        Self {
+            investment_id,
+            success: false,
+            error: Some(error),
+            quotes_stored: 0,
+            provider: None,
+        }
+    }
+}
+
+/// Result of filling in investment fields from a provider
+#[derive(Debug, Clone, Serialize)]
+pub struct InvestmentEnrichment {
+    pub investment: Investment,
+    /// Provider that knew the instrument
+    pub provider: String,
+    /// Fields that were empty and have been filled in
+    pub updated_fields: Vec<String>,
+}
+
+/// Fill the empty fields of an investment from instrument data and return their names
+///
+/// Fields that are already set are kept, so manual changes are never overwritten.
+pub fn fill_missing_fields(investment: &mut Investment, info: &InstrumentInfo) -> Vec<String> {
+    let mut updated = Vec::new();
+    for (name, field, value) in [
+        ("name", &mut investment.name, &info.name),
+        ("shortname", &mut investment.shortname, &info.shortname),
+        ("currency", &mut investment.currency, &info.currency),
+        (
+            "asset_class",
+            &mut investment.asset_class,
+            &info.asset_class,
+        ),
+    ] {
+        let missing = field.as_deref().is_none_or(|v| v.trim().is_empty());
+        if let (true, Some(value)) = (missing, value) {
+            *field = Some(value.clone());
+            updated.push(name.to_string());
+        }
+    }
+    updated
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,17 +85,70 @@ pub struct ProviderInfo {
 }
 
 /// Centralized list of available quote providers (id, name)
-pub const AVAILABLE_PROVIDERS: &[(&str, &str)] =
-    &[("yahoo", "Yahoo Finance"), ("justetf", "JustETF")];
+pub const AVAILABLE_PROVIDERS: &[(&str, &str)] = &[
+    ("yahoo", "Yahoo Finance"),
+    ("justetf", "JustETF"),
+    ("coingecko", "CoinGecko"),
+];
 
 /// Valid quote provider IDs (derived from AVAILABLE_PROVIDERS)
-pub const VALID_PROVIDER_IDS: &[&str] = &["yahoo", "justetf"];
+pub const VALID_PROVIDER_IDS: &[&str] = &["yahoo", "justetf", "coingecko"];
+
+/// Split a comma separated provider fallback chain (e.g. `"yahoo,justetf"`) into provider IDs
+pub fn parse_provider_chain(value: &str) -> Vec<&str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Days of history requested by a regular quote fetch, enough to close gaps of a few
+/// missed runs without downloading the full history every time
+pub const DEFAULT_FETCH_DAYS: u32 = 31;
+
+/// Seconds a stored live quote is returned before it is requested again
+pub const LIVE_QUOTE_MAX_AGE_SECONDS: i64 = 60;
+
+/// Which quotes to request from a provider
+#[derive(Debug, Clone, Copy)]
+enum FetchMode {
+    All,
+    Latest,
+    Range(NaiveDate, NaiveDate),
+    /// Quotes of an investment since the latest price stored from the provider, but
+    /// not before the first date
+    Missing(i64, NaiveDate, NaiveDate),
+}
+
+impl FetchMode {
+    /// Dates to look for dividends and splits in, none for the latest quote only
+    fn event_range(self) -> Option<(NaiveDate, NaiveDate)> {
+        match self {
+            FetchMode::All => Some((NaiveDate::default(), Utc::now().date_naive())),
+            FetchMode::Latest => None,
+            FetchMode::Range(date_from, date_to) | FetchMode::Missing(_, date_from, date_to) => {
+                Some((date_from, date_to))
+            }
+        }
+    }
+}
 
 pub struct QuoteFetcherService {
     investment_repo: Arc<dyn InvestmentRepository>,
     price_repo: Arc<dyn InvestmentPriceRepository>,
     base_currency: String,
     currency_converter: CurrencyConverter,
+    fetch_log_repo: Option<Arc<dyn QuoteFetchLogRepository>>,
+    price_alerts: Option<PriceAlertService>,
+    webhooks: Option<WebhookNotifier>,
+    email: Option<EmailNotifier>,
+    api_keys: ProviderApiKeys,
+    provider_config_repo: Option<Arc<dyn ProviderConfigRepository>>,
+    fetch_days: u32,
+    positions: Option<Arc<PositionCalculator>>,
+    event_repo: Option<Arc<dyn CorporateEventRepository>>,
+    intraday_repo: Option<Arc<dyn IntradayPriceRepository>>,
 }
 
 impl QuoteFetcherService {
@@ -45,6 +162,169 @@ impl QuoteFetcherService {
             price_repo,
             base_currency,
             currency_converter: CurrencyConverter::new(),
+            fetch_log_repo: None,
+            price_alerts: None,
+            webhooks: None,
+            email: None,
+            api_keys: ProviderApiKeys::default(),
+            provider_config_repo: None,
+            fetch_days: DEFAULT_FETCH_DAYS,
+            positions: None,
+            event_repo: None,
+            intraday_repo: None,
+        }
+    }
+
+    /// Request the given number of days up to today in a regular fetch
+    pub fn with_fetch_days(mut self, fetch_days: u32) -> Self {
+        self.fetch_days = fetch_days.max(1);
+        self
+    }
+
+    /// Skip investments that are neither held nor on the watchlist when fetching all
+    /// investments
+    pub fn with_held_only(mut self, positions: Arc<PositionCalculator>) -> Self {
+        self.positions = Some(positions);
+        self
+    }
+
+    /// Store the dividends and splits reported along with the quotes
+    pub fn with_corporate_events(mut self, event_repo: Arc<dyn CorporateEventRepository>) -> Self {
+        self.event_repo = Some(event_repo);
+        self
+    }
+
+    /// Cache live quotes, so repeated requests within a minute do not reach the provider
+    pub fn with_intraday_prices(mut self, intraday_repo: Arc<dyn IntradayPriceRepository>) -> Self {
+        self.intraday_repo = Some(intraday_repo);
+        self
+    }
+
+    /// Authenticate with the providers that have a key configured
+    pub fn with_api_keys(mut self, api_keys: ProviderApiKeys) -> Self {
+        self.api_keys = api_keys;
+        self
+    }
+
+    /// Prefer the API keys stored through the settings API over the configured ones
+    pub fn with_provider_configs(
+        mut self,
+        provider_config_repo: Arc<dyn ProviderConfigRepository>,
+    ) -> Self {
+        self.provider_config_repo = Some(provider_config_repo);
+        self
+    }
+
+    /// Configured API keys overridden by the stored ones, read on every use so that
+    /// changed keys apply without restart
+    async fn api_keys(&self) -> ProviderApiKeys {
+        let Some(repo) = &self.provider_config_repo else {
+            return self.api_keys.clone();
+        };
+        match repo.find_all().await {
+            Ok(configs) => self.api_keys.clone().with_stored(&configs),
+            Err(e) => {
+                tracing::warn!("Failed to read the stored provider API keys: {}", e);
+                self.api_keys.clone()
+            }
+        }
+    }
+
+    /// Record the result of every fetch in the given fetch log
+    pub fn with_fetch_log(mut self, fetch_log_repo: Arc<dyn QuoteFetchLogRepository>) -> Self {
+        self.fetch_log_repo = Some(fetch_log_repo);
+        self
+    }
+
+    /// Evaluate the price alerts of an investment after its latest quotes are stored
+    pub fn with_price_alerts(mut self, alert_repo: Arc<dyn PriceAlertRepository>) -> Self {
+        self.price_alerts = Some(PriceAlertService::new(alert_repo));
+        self
+    }
+
+    /// Send triggered price alerts to the webhooks
+    pub fn with_webhooks(mut self, webhooks: WebhookNotifier) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Email triggered price alerts
+    pub fn with_email(mut self, email: EmailNotifier) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Cache exchange rates used for currency conversion in the given repository
+    pub fn with_fx_rates(mut self, fx_rate_repo: Arc<dyn FxRateRepository>) -> Self {
+        self.currency_converter = CurrencyConverter::new().with_cache(fx_rate_repo);
+        self
+    }
+
+    /// Persist a fetch result; logging failures must not fail the fetch itself
+    async fn log_result(&self, result: &QuoteFetchResult) {
+        let Some(repo) = &self.fetch_log_repo else {
+            return;
+        };
+
+        let entry = QuoteFetchLog {
+            id: 0,
+            fetched_at: Utc::now(),
+            investment_id: result.investment_id,
+            provider: result.provider.clone(),
+            success: result.success,
+            error: result.error.clone(),
+            quotes_stored: result.quotes_stored as i64,
+        };
+
+        if let Err(e) = repo.create(&entry).await {
+            tracing::warn!(
+                "Failed to record quote fetch result for investment {}: {}",
+                result.investment_id,
+                e
+            );
+        }
+    }
+
+    /// Check the alerts against the most recent of the stored prices; alert failures must
+    /// not fail the fetch itself
+    async fn evaluate_alerts(&self, investment: &Investment, prices: &[InvestmentPrice]) {
+        let Some(price_alerts) = &self.price_alerts else {
+            return;
+        };
+        let investment_id = investment.id;
+        let Some((date, price)) = prices
+            .iter()
+            .filter_map(|p| Some((p.date?, p.price?)))
+            .max_by_key(|(date, _)| *date)
+        else {
+            return;
+        };
+
+        // Alert thresholds are compared as floating point numbers
+        let price = price.to_f64().unwrap_or_default();
+        let triggered = match price_alerts.evaluate(investment_id, date, price).await {
+            Ok(triggered) => triggered,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to evaluate price alerts for investment {}: {}",
+                    investment_id,
+                    e
+                );
+                return;
+            }
+        };
+
+        for alert in triggered {
+            let notification = PriceAlertNotification {
+                alert,
+                investment_name: investment.name.clone(),
+            };
+            if let Some(email) = &self.email {
+                email.notify(alert_email(&notification));
+            }
+            if let Some(webhooks) = &self.webhooks {
+                webhooks.notify(WebhookEvent::PriceAlertTriggered(notification));
+            }
         }
     }
 
@@ -60,46 +340,51 @@ impl QuoteFetcherService {
     }
 
     /// Create a provider instance on-demand based on provider name
-    fn create_provider(&self, provider_name: &str) -> Option<Arc<dyn QuoteProvider>> {
+    async fn create_provider(&self, provider_name: &str) -> Option<Arc<dyn QuoteProvider>> {
         match provider_name {
             "yahoo" => Some(Arc::new(YahooFinanceProvider::new())),
             "justetf" => Some(Arc::new(JustETFProvider::new())),
+            "coingecko" => Some(Arc::new(
+                CoinGeckoProvider::new(&self.base_currency)
+                    .with_api_key(self.api_keys().await.coingecko_api_key),
+            )),
             _ => None,
         }
     }
 
+    /// Send the status check request of a provider
+    pub async fn probe_provider(&self, provider_name: &str) -> Result<()> {
+        let provider = self.create_provider(provider_name).await.ok_or_else(|| {
+            AppError::InvalidInput(format!("Unknown provider: {}", provider_name))
+        })?;
+        provider.probe().await
+    }
+
     /// Fetch quotes for a single investment
     pub async fn fetch_quotes_for_investment(
         &self,
         investment: &Investment,
     ) -> Result<QuoteFetchResult> {
+        let result = self.fetch_and_store_quotes(investment).await?;
+        self.log_result(&result).await;
+        Ok(result)
+    }
+
+    async fn fetch_and_store_quotes(&self, investment: &Investment) -> Result<QuoteFetchResult> {
         let investment_id = investment.id;
 
         // Validate investment has required configuration
-        let quote_provider = match &investment.quote_provider {
-            Some(provider) if !provider.is_empty() => provider,
-            _ => {
-                return Ok(QuoteFetchResult {
-                    investment_id,
-                    success: false,
-                    error: Some("No quote provider configured".to_string()),
-                    quotes_stored: 0,
-                });
-            }
-        };
-
-        // Get provider (create on-demand)
-        let provider = match self.create_provider(quote_provider) {
-            Some(p) => p,
-            None => {
-                return Ok(QuoteFetchResult {
-                    investment_id,
-                    success: false,
-                    error: Some(format!("Unknown provider: {}", quote_provider)),
-                    quotes_stored: 0,
-                });
-            }
-        };
+        let providers = investment
+            .quote_provider
+            .as_deref()
+            .map(parse_provider_chain)
+            .unwrap_or_default();
+        if providers.is_empty() {
+            return Ok(QuoteFetchResult::failed(
+                investment_id,
+                "No quote provider configured".to_string(),
+            ));
+        }
 
         // Determine ticker to use
         let ticker = investment
@@ -110,75 +395,31 @@ impl QuoteFetcherService {
                 crate::error::AppError::InvalidInput("Investment has no ticker or ISIN".to_string())
             })?;
 
-        // Fetch quotes from provider
-        let quotes_data = match provider.get_quotes(ticker).await {
-            Ok(quotes) if !quotes.is_empty() => quotes,
-            Ok(_) => {
-                return Ok(QuoteFetchResult {
-                    investment_id,
-                    success: false,
-                    error: Some("No quote data returned from provider".to_string()),
-                    quotes_stored: 0,
-                });
-            }
-            Err(e) => {
-                return Ok(QuoteFetchResult {
-                    investment_id,
-                    success: false,
-                    error: Some(format!("Provider error: {}", e)),
-                    quotes_stored: 0,
-                });
-            }
-        };
-
-        // Process and store quotes
-        let mut stored_count = 0;
-        for quote_data in quotes_data {
-            // Convert to base currency if needed
-            let price_in_base_currency = if quote_data.currency != self.base_currency {
-                match self
-                    .currency_converter
-                    .convert(
-                        quote_data.price,
-                        &quote_data.currency,
-                        &self.base_currency,
-                        quote_data.date,
-                    )
-                    .await?
-                {
-                    Some(converted) => converted,
-                    None => {
-                        tracing::warn!(
-                            "Currency conversion failed for {} on {}: {} to {}",
-                            ticker,
-                            quote_data.date,
-                            quote_data.currency,
-                            self.base_currency
-                        );
-                        continue;
-                    }
-                }
-            } else {
-                quote_data.price
-            };
-
-            // Store in database (upsert)
-            let price = InvestmentPrice {
-                date: Some(quote_data.date),
-                investment_id: Some(investment_id),
-                price: Some(price_in_base_currency),
-                source: Some(quote_data.source.clone()),
+        // Fetch the quotes missing from the recent days from the first provider in the
+        // chain that delivers data
+        let date_to = Utc::now().date_naive();
+        let date_from = date_to - chrono::Duration::days(i64::from(self.fetch_days) - 1);
+        let mode = FetchMode::Missing(investment_id, date_from, date_to);
+        let (provider_name, quotes_data) =
+            match self.fetch_from_providers(&providers, ticker, mode).await {
+                Ok(fetched) => fetched,
+                Err(error) => return Ok(QuoteFetchResult::failed(investment_id, error)),
             };
 
-            self.price_repo.upsert(&price).await?;
-            stored_count += 1;
-        }
+        let stored = self
+            .store_quotes(investment_id, ticker, &provider_name, quotes_data)
+            .await?;
+        let stored_count = stored.len();
+        self.evaluate_alerts(investment, &stored).await;
+        self.store_events(investment_id, ticker, &provider_name, mode)
+            .await;
 
         tracing::info!(
-            "Successfully fetched {} quotes for {} ({})",
+            "Successfully fetched {} quotes for {} ({}) from {}",
             stored_count,
             investment.name.as_deref().unwrap_or("Unknown"),
-            ticker
+            ticker,
+            provider_name
         );
 
         Ok(QuoteFetchResult {
@@ -186,6 +427,7 @@ impl QuoteFetcherService {
             success: true,
             error: None,
             quotes_stored: stored_count,
+            provider: Some(provider_name),
         })
     }
 
@@ -193,45 +435,35 @@ impl QuoteFetcherService {
     pub async fn fetch_latest_quote_for_investment(
         &self,
         investment_id: i64,
+    ) -> Result<(QuoteFetchResult, Option<InvestmentPrice>)> {
+        let (result, price) = self.fetch_and_store_latest_quote(investment_id).await?;
+        self.log_result(&result).await;
+        Ok((result, price))
+    }
+
+    async fn fetch_and_store_latest_quote(
+        &self,
+        investment_id: i64,
     ) -> Result<(QuoteFetchResult, Option<InvestmentPrice>)> {
         // Get investment
         let investment = self
             .investment_repo
             .find_by_id(investment_id)
             .await?
-            .ok_or_else(|| crate::error::AppError::NotFound)?;
+            .ok_or_else(|| AppError::not_found("Investment", investment_id))?;
 
         // Validate investment has required configuration
-        let quote_provider = match &investment.quote_provider {
-            Some(provider) if !provider.is_empty() => provider,
-            _ => {
-                return Ok((
-                    QuoteFetchResult {
-                        investment_id,
-                        success: false,
-                        error: Some("No quote provider configured".to_string()),
-                        quotes_stored: 0,
-                    },
-                    None,
-                ));
-            }
-        };
-
-        // Get provider (create on-demand)
-        let provider = match self.create_provider(quote_provider) {
-            Some(p) => p,
-            None => {
-                return Ok((
-                    QuoteFetchResult {
-                        investment_id,
-                        success: false,
-                        error: Some(format!("Unknown provider: {}", quote_provider)),
-                        quotes_stored: 0,
-                    },
-                    None,
-                ));
-            }
-        };
+        let providers = investment
+            .quote_provider
+            .as_deref()
+            .map(parse_provider_chain)
+            .unwrap_or_default();
+        if providers.is_empty() {
+            return Ok((
+                QuoteFetchResult::failed(investment_id, "No quote provider configured".to_string()),
+                None,
+            ));
+        }
 
         // Determine ticker to use
         let ticker = investment
@@ -242,31 +474,27 @@ impl QuoteFetcherService {
                 crate::error::AppError::InvalidInput("Investment has no ticker or ISIN".to_string())
             })?;
 
-        // Fetch latest quote from provider (None = latest)
-        let quote_data = match provider.get_quote(ticker, None).await {
-            Ok(Some(quote)) => quote,
-            Ok(None) => {
-                return Ok((
-                    QuoteFetchResult {
-                        investment_id,
-                        success: false,
-                        error: Some("No quote data returned from provider".to_string()),
-                        quotes_stored: 0,
-                    },
-                    None,
-                ));
+        // Fetch latest quote from the first provider in the chain that delivers one
+        let (provider_name, quote_data) = match self
+            .fetch_from_providers(&providers, ticker, FetchMode::Latest)
+            .await
+        {
+            Ok((provider_name, mut quotes)) => (provider_name, quotes.remove(0)),
+            Err(error) => {
+                return Ok((QuoteFetchResult::failed(investment_id, error), None));
             }
-            Err(e) => {
-                return Ok((
-                    QuoteFetchResult {
+        };
+        let Some(quote_price) = decimal_price(&quote_data) else {
+            return Ok((
+                QuoteFetchResult {
+                    provider: Some(provider_name),
+                    ..QuoteFetchResult::failed(
                         investment_id,
-                        success: false,
-                        error: Some(format!("Provider error: {}", e)),
-                        quotes_stored: 0,
-                    },
-                    None,
-                ));
-            }
+                        format!("Invalid price {}", quote_data.price),
+                    )
+                },
+                None,
+            ));
         };
 
         // Convert to base currency if needed
@@ -274,7 +502,7 @@ impl QuoteFetcherService {
             match self
                 .currency_converter
                 .convert(
-                    quote_data.price,
+                    quote_price,
                     &quote_data.currency,
                     &self.base_currency,
                     quote_data.date,
@@ -292,33 +520,39 @@ impl QuoteFetcherService {
                     );
                     return Ok((
                         QuoteFetchResult {
-                            investment_id,
-                            success: false,
-                            error: Some("Currency conversion failed".to_string()),
-                            quotes_stored: 0,
+                            provider: Some(provider_name),
+                            ..QuoteFetchResult::failed(
+                                investment_id,
+                                "Currency conversion failed".to_string(),
+                            )
                         },
                         None,
                     ));
                 }
             }
         } else {
-            quote_data.price
+            quote_price
         };
 
-        // Store in database (upsert)
+        // Store in database (upsert), recording the provider that delivered the quote
         let price = InvestmentPrice {
             date: Some(quote_data.date),
             investment_id: Some(investment_id),
             price: Some(price_in_base_currency),
-            source: Some(quote_data.source.clone()),
+            source: Some(provider_name.clone()),
+            currency: Some(quote_data.currency.clone()),
+            original_price: Some(quote_price),
         };
 
         self.price_repo.upsert(&price).await?;
+        self.evaluate_alerts(&investment, std::slice::from_ref(&price))
+            .await;
 
         tracing::info!(
-            "Successfully fetched latest quote for {} ({}): {} {} on {}",
+            "Successfully fetched latest quote for {} ({}) from {}: {} {} on {}",
             investment.name.as_deref().unwrap_or("Unknown"),
             ticker,
+            provider_name,
             price_in_base_currency,
             self.base_currency,
             quote_data.date
@@ -330,11 +564,513 @@ impl QuoteFetcherService {
                 success: true,
                 error: None,
                 quotes_stored: 1,
+                provider: Some(provider_name),
             },
             Some(price),
         ))
     }
 
+    /// Fetch quotes between `date_from` and `date_to` for a single investment,
+    /// e.g. to load history older than a regular fetch returns
+    pub async fn backfill_quotes_for_investment(
+        &self,
+        investment_id: i64,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> Result<QuoteFetchResult> {
+        if date_from > date_to {
+            return Err(AppError::InvalidInput(
+                "start_date must not be after end_date".to_string(),
+            ));
+        }
+        let result = self
+            .backfill_and_store_quotes(investment_id, FetchMode::Range(date_from, date_to))
+            .await?;
+        self.log_result(&result).await;
+        Ok(result)
+    }
+
+    /// Fetch all quotes a provider has for a single investment
+    pub async fn backfill_full_history_for_investment(
+        &self,
+        investment_id: i64,
+    ) -> Result<QuoteFetchResult> {
+        let result = self
+            .backfill_and_store_quotes(investment_id, FetchMode::All)
+            .await?;
+        self.log_result(&result).await;
+        Ok(result)
+    }
+
+    async fn backfill_and_store_quotes(
+        &self,
+        investment_id: i64,
+        mode: FetchMode,
+    ) -> Result<QuoteFetchResult> {
+        let investment = self
+            .investment_repo
+            .find_by_id(investment_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Investment", investment_id))?;
+
+        let providers = investment
+            .quote_provider
+            .as_deref()
+            .map(parse_provider_chain)
+            .unwrap_or_default();
+        if providers.is_empty() {
+            return Ok(QuoteFetchResult::failed(
+                investment_id,
+                "No quote provider configured".to_string(),
+            ));
+        }
+
+        let ticker = investment
+            .ticker_symbol
+            .as_ref()
+            .or(investment.isin.as_ref())
+            .ok_or_else(|| {
+                AppError::InvalidInput("Investment has no ticker or ISIN".to_string())
+            })?;
+
+        let (provider_name, quotes_data) =
+            match self.fetch_from_providers(&providers, ticker, mode).await {
+                Ok(fetched) => fetched,
+                Err(error) => return Ok(QuoteFetchResult::failed(investment_id, error)),
+            };
+
+        // Backfilled history is not checked against the alerts, which concern current prices
+        let stored_count = self
+            .store_quotes(investment_id, ticker, &provider_name, quotes_data)
+            .await?
+            .len();
+        self.store_events(investment_id, ticker, &provider_name, mode)
+            .await;
+
+        tracing::info!(
+            "Backfilled {} quotes for {} ({}) from {}",
+            stored_count,
+            investment.name.as_deref().unwrap_or("Unknown"),
+            ticker,
+            provider_name
+        );
+
+        Ok(QuoteFetchResult {
+            investment_id,
+            success: true,
+            error: None,
+            quotes_stored: stored_count,
+            provider: Some(provider_name),
+        })
+    }
+
+    /// Store the dividends and splits the provider reports for the fetched period
+    ///
+    /// Dividends are stored as pending until a payout is confirmed for them. Failures
+    /// are only logged, as the quotes are stored already.
+    async fn store_events(
+        &self,
+        investment_id: i64,
+        ticker: &str,
+        provider_name: &str,
+        mode: FetchMode,
+    ) {
+        let (Some(event_repo), Some((date_from, date_to))) = (&self.event_repo, mode.event_range())
+        else {
+            return;
+        };
+        let Some(provider) = self.create_provider(provider_name).await else {
+            return;
+        };
+
+        let events = match provider.get_events(ticker, date_from, date_to).await {
+            Ok(events) if events.is_empty() => return,
+            Ok(events) => events,
+            Err(e) => {
+                tracing::warn!("Failed to fetch dividends and splits of {}: {}", ticker, e);
+                return;
+            }
+        };
+        let dividends: Vec<DividendEvent> = events
+            .dividends
+            .iter()
+            .filter_map(|dividend| {
+                Some(DividendEvent {
+                    id: 0,
+                    investment_id,
+                    date: dividend.date,
+                    amount: Decimal::try_from(dividend.amount).ok()?,
+                    currency: Some(dividend.currency.clone()),
+                    source: provider_name.to_string(),
+                    status: DividendStatus::Pending.to_string(),
+                    movement_id: None,
+                })
+            })
+            .collect();
+        let splits: Vec<SplitEvent> = events
+            .splits
+            .iter()
+            .filter_map(|split| {
+                Some(SplitEvent {
+                    id: 0,
+                    investment_id,
+                    date: split.date,
+                    numerator: Decimal::try_from(split.numerator).ok()?,
+                    denominator: Decimal::try_from(split.denominator).ok()?,
+                    source: provider_name.to_string(),
+                })
+            })
+            .collect();
+
+        match event_repo.add_dividends(&dividends).await {
+            Ok(0) => {}
+            Ok(added) => tracing::info!("Detected {} new dividend(s) of {}", added, ticker),
+            Err(e) => tracing::warn!("Failed to store dividends of {}: {}", ticker, e),
+        }
+        match event_repo.add_splits(&splits).await {
+            Ok(0) => {}
+            Ok(added) => tracing::info!("Detected {} new split(s) of {}", added, ticker),
+            Err(e) => tracing::warn!("Failed to store splits of {}: {}", ticker, e),
+        }
+    }
+
+    /// Convert quotes to the base currency and upsert them, returning the stored prices
+    async fn store_quotes(
+        &self,
+        investment_id: i64,
+        ticker: &str,
+        provider_name: &str,
+        quotes_data: Vec<QuoteData>,
+    ) -> Result<Vec<InvestmentPrice>> {
+        // Convert each currency in one bulk request instead of one request per quote
+        let prices: Vec<Option<Decimal>> = quotes_data.iter().map(decimal_price).collect();
+        let mut converted = prices.clone();
+        let mut currencies: Vec<&str> = quotes_data
+            .iter()
+            .map(|q| q.currency.as_str())
+            .filter(|c| *c != self.base_currency)
+            .collect();
+        currencies.sort();
+        currencies.dedup();
+
+        for currency in currencies {
+            let (indices, values): (Vec<usize>, Vec<(NaiveDate, Decimal)>) = quotes_data
+                .iter()
+                .zip(&prices)
+                .enumerate()
+                .filter(|(_, (q, _))| q.currency == currency)
+                .filter_map(|(i, (q, price))| Some((i, (q.date, (*price)?))))
+                .unzip();
+            let series = self
+                .currency_converter
+                .convert_series(&values, currency, &self.base_currency)
+                .await?;
+            for (i, value) in indices.into_iter().zip(series) {
+                converted[i] = value;
+            }
+        }
+
+        // Process and store quotes
+        let mut stored = Vec::new();
+        for ((quote_data, quote_price), price_in_base_currency) in
+            quotes_data.into_iter().zip(prices).zip(converted)
+        {
+            let Some(quote_price) = quote_price else {
+                tracing::warn!(
+                    "Invalid price {} for {} on {}",
+                    quote_data.price,
+                    ticker,
+                    quote_data.date
+                );
+                continue;
+            };
+            let Some(price_in_base_currency) = price_in_base_currency else {
+                tracing::warn!(
+                    "Currency conversion failed for {} on {}: {} to {}",
+                    ticker,
+                    quote_data.date,
+                    quote_data.currency,
+                    self.base_currency
+                );
+                continue;
+            };
+
+            // Store in database (upsert), recording the provider that delivered the quote
+            let price = InvestmentPrice {
+                date: Some(quote_data.date),
+                investment_id: Some(investment_id),
+                price: Some(price_in_base_currency),
+                source: Some(provider_name.to_string()),
+                currency: Some(quote_data.currency),
+                original_price: Some(quote_price),
+            };
+
+            self.price_repo.upsert(&price).await?;
+            stored.push(price);
+        }
+
+        Ok(stored)
+    }
+
+    /// First date of the quotes of an investment that are missing from a source.
+    ///
+    /// The day of the latest stored price is fetched again, as its quote may have been
+    /// taken before the market closed.
+    async fn missing_quotes_start(
+        &self,
+        investment_id: i64,
+        source: &str,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> Result<NaiveDate> {
+        let latest = self
+            .price_repo
+            .find_latest_date(investment_id, source)
+            .await?;
+        Ok(latest.map_or(date_from, |latest| latest.clamp(date_from, date_to)))
+    }
+
+    /// Try the providers in order until one returns quotes.
+    ///
+    /// Returns the name of the delivering provider with its quotes, or the
+    /// collected errors of all providers.
+    async fn fetch_from_providers(
+        &self,
+        providers: &[&str],
+        ticker: &str,
+        mode: FetchMode,
+    ) -> std::result::Result<(String, Vec<QuoteData>), String> {
+        let mut errors = Vec::new();
+
+        for &provider_name in providers {
+            // Get provider (create on-demand)
+            let Some(provider) = self.create_provider(provider_name).await else {
+                errors.push(format!("Unknown provider: {}", provider_name));
+                continue;
+            };
+
+            let fetched = match mode {
+                FetchMode::All => provider.get_quotes(ticker).await,
+                FetchMode::Latest => provider
+                    .get_quote(ticker, None)
+                    .await
+                    .map(|quote| quote.into_iter().collect()),
+                FetchMode::Range(date_from, date_to) => {
+                    provider.get_quotes_range(ticker, date_from, date_to).await
+                }
+                FetchMode::Missing(investment_id, date_from, date_to) => {
+                    let source = provider.get_provider_name();
+                    let date_from = match self
+                        .missing_quotes_start(investment_id, source, date_from, date_to)
+                        .await
+                    {
+                        Ok(date_from) => date_from,
+                        Err(e) => {
+                            errors.push(format!("Provider error ({}): {}", provider_name, e));
+                            continue;
+                        }
+                    };
+                    tracing::debug!(
+                        "Fetching quotes for {} from {} between {} and {}",
+                        ticker,
+                        source,
+                        date_from,
+                        date_to
+                    );
+                    provider
+                        .get_quotes_range(ticker, date_from, date_to)
+                        .await
+                        .map(|quotes| quotes.into_iter().filter(|q| q.date >= date_from).collect())
+                }
+            };
+
+            match fetched {
+                Ok(quotes) if !quotes.is_empty() => {
+                    return Ok((provider.get_provider_name().to_string(), quotes));
+                }
+                Ok(_) => errors.push(format!(
+                    "No quote data returned from provider {}",
+                    provider_name
+                )),
+                Err(e) => errors.push(format!("Provider error ({}): {}", provider_name, e)),
+            }
+
+            if providers.len() > 1 {
+                tracing::warn!(
+                    "Provider {} failed for {}, trying next provider",
+                    provider_name,
+                    ticker
+                );
+            }
+        }
+
+        Err(errors.join("; "))
+    }
+
+    /// Fill in missing name, shortname, currency and asset class of an investment from
+    /// the first provider of its chain that knows the ticker (or ISIN)
+    pub async fn enrich_investment(&self, investment_id: i64) -> Result<InvestmentEnrichment> {
+        let mut investment = self
+            .investment_repo
+            .find_by_id(investment_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Investment", investment_id))?;
+
+        let chain = investment.quote_provider.clone().unwrap_or_default();
+        let providers = parse_provider_chain(&chain);
+        if providers.is_empty() {
+            return Err(AppError::InvalidInput(
+                "No quote provider configured".to_string(),
+            ));
+        }
+        let ticker = investment
+            .ticker_symbol
+            .clone()
+            .or_else(|| investment.isin.clone())
+            .ok_or_else(|| {
+                AppError::InvalidInput("Investment has no ticker or ISIN".to_string())
+            })?;
+
+        let mut errors = Vec::new();
+        for &provider_name in &providers {
+            let Some(provider) = self.create_provider(provider_name).await else {
+                errors.push(format!("Unknown provider: {}", provider_name));
+                continue;
+            };
+
+            match provider.get_instrument_info(&ticker).await {
+                Ok(Some(info)) => {
+                    let updated_fields = fill_missing_fields(&mut investment, &info);
+                    if !updated_fields.is_empty() {
+                        self.investment_repo
+                            .update(investment_id, &investment)
+                            .await?;
+                    }
+                    return Ok(InvestmentEnrichment {
+                        investment,
+                        provider: provider_name.to_string(),
+                        updated_fields,
+                    });
+                }
+                Ok(None) => errors.push(format!(
+                    "No instrument data from provider {}",
+                    provider_name
+                )),
+                Err(e) => errors.push(format!("Provider error ({}): {}", provider_name, e)),
+            }
+        }
+
+        Err(AppError::ExternalApi(errors.join("; ")))
+    }
+
+    /// Current price of an investment during the trading day, from the first provider of
+    /// its chain that offers live quotes
+    ///
+    /// A stored live quote younger than [`LIVE_QUOTE_MAX_AGE_SECONDS`] is returned without
+    /// a request, and an older one if no provider delivers a price.
+    pub async fn live_quote(&self, investment_id: i64) -> Result<IntradayPrice> {
+        let investment = self
+            .investment_repo
+            .find_by_id(investment_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Investment", investment_id))?;
+
+        let cached = match &self.intraday_repo {
+            Some(repo) => repo.find(investment_id).await?,
+            None => None,
+        };
+        if let Some(cached) = &cached {
+            let age = Utc::now() - cached.fetched_at;
+            if age < chrono::Duration::seconds(LIVE_QUOTE_MAX_AGE_SECONDS) {
+                return Ok(cached.clone());
+            }
+        }
+
+        match self.fetch_live_quote(&investment).await {
+            Ok(price) => {
+                if let Some(repo) = &self.intraday_repo {
+                    repo.upsert(&price).await?;
+                }
+                Ok(price)
+            }
+            Err(e) => match cached {
+                Some(cached) => {
+                    tracing::warn!(
+                        "Live quote of investment {} not refreshed, returning the one of {}: {}",
+                        investment_id,
+                        cached.quoted_at,
+                        e
+                    );
+                    Ok(cached)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    async fn fetch_live_quote(&self, investment: &Investment) -> Result<IntradayPrice> {
+        let chain = investment.quote_provider.clone().unwrap_or_default();
+        let providers = parse_provider_chain(&chain);
+        if providers.is_empty() {
+            return Err(AppError::InvalidInput(
+                "No quote provider configured".to_string(),
+            ));
+        }
+        let ticker = investment
+            .ticker_symbol
+            .as_ref()
+            .or(investment.isin.as_ref())
+            .ok_or_else(|| {
+                AppError::InvalidInput("Investment has no ticker or ISIN".to_string())
+            })?;
+
+        let mut errors = Vec::new();
+        for &provider_name in &providers {
+            let Some(provider) = self.create_provider(provider_name).await else {
+                errors.push(format!("Unknown provider: {}", provider_name));
+                continue;
+            };
+
+            let quote = match provider.get_live_quote(ticker).await {
+                Ok(Some(quote)) => quote,
+                Ok(None) => {
+                    errors.push(format!("No live quote from provider {}", provider_name));
+                    continue;
+                }
+                Err(e) => {
+                    errors.push(format!("Provider error ({}): {}", provider_name, e));
+                    continue;
+                }
+            };
+            let Ok(original_price) = Decimal::try_from(quote.price) else {
+                errors.push(format!("Invalid price {}", quote.price));
+                continue;
+            };
+            let price = self
+                .currency_converter
+                .convert(
+                    original_price,
+                    &quote.currency,
+                    &self.base_currency,
+                    quote.time.date_naive(),
+                )
+                .await?
+                .ok_or(AppError::CurrencyConversion)?;
+
+            return Ok(IntradayPrice {
+                investment_id: investment.id,
+                price,
+                currency: quote.currency,
+                original_price,
+                quoted_at: quote.time,
+                fetched_at: Utc::now(),
+                source: provider.get_provider_name().to_string(),
+            });
+        }
+
+        Err(AppError::ExternalApi(errors.join("; ")))
+    }
+
     /// Fetch quotes for multiple investments
     pub async fn fetch_quotes(
         &self,
@@ -351,18 +1087,39 @@ impl QuoteFetcherService {
             }
             inv_list
         } else {
-            // Fetch all investments with quote provider configured
-            self.investment_repo
+            // Fetch all investments with quote provider configured and fetching enabled,
+            // except archived ones
+            let mut configured: Vec<Investment> = self
+                .investment_repo
                 .find_all()
                 .await?
                 .into_iter()
                 .filter(|inv| {
-                    inv.quote_provider
-                        .as_ref()
-                        .map(|p| !p.is_empty())
-                        .unwrap_or(false)
+                    inv.quote_fetch_enabled
+                        && !inv.archived
+                        && inv
+                            .quote_provider
+                            .as_ref()
+                            .map(|p| !p.is_empty())
+                            .unwrap_or(false)
                 })
-                .collect()
+                .collect();
+            if let Some(positions) = &self.positions {
+                let quantities = positions.current_quantities(None).await?;
+                configured.retain(|inv| {
+                    inv.watchlist
+                        || quantities
+                            .get(&inv.id)
+                            .is_some_and(|quantity| *quantity > Decimal::ZERO)
+                });
+            }
+            let mut due = Vec::with_capacity(configured.len());
+            for investment in configured {
+                if self.fetch_due(&investment).await? {
+                    due.push(investment);
+                }
+            }
+            due
         };
 
         let mut results = Vec::new();
@@ -381,4 +1138,39 @@ impl QuoteFetcherService {
 
         Ok(results)
     }
+
+    /// Whether the fetch interval of an investment has passed since its last fetch attempt;
+    /// investments without interval are due on every run
+    async fn fetch_due(&self, investment: &Investment) -> Result<bool> {
+        let (Some(days), Some(fetch_log_repo)) =
+            (investment.quote_fetch_interval_days, &self.fetch_log_repo)
+        else {
+            return Ok(true);
+        };
+        let Some(last) = fetch_log_repo
+            .find_recent(Some(investment.id), 1)
+            .await?
+            .into_iter()
+            .next()
+        else {
+            return Ok(true);
+        };
+
+        // Compared by date, so a daily run at a fixed time does not drift by the fetch time
+        let due = last.fetched_at.date_naive() + chrono::Duration::days(days);
+        if due > Utc::now().date_naive() {
+            tracing::debug!(
+                "Skipping quote fetch of investment {} until {}",
+                investment.id,
+                due
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+}
+
+/// Price of a quote as decimal, `None` if the provider delivered no finite number
+fn decimal_price(quote: &QuoteData) -> Option<Decimal> {
+    Decimal::try_from(quote.price).ok()
 }
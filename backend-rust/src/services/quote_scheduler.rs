@@ -0,0 +1,295 @@
+use crate::error::{AppError, Result};
+use crate::repository::traits::{
+    CorporateEventRepository, FxRateRepository, InvestmentPriceRepository, InvestmentRepository,
+    PriceAlertRepository, ProviderConfigRepository, QuoteFetchLogRepository, SettingsRepository,
+};
+use crate::services::email::EmailNotifier;
+use crate::services::positions::PositionCalculator;
+use crate::services::quote_fetcher::{QuoteFetcherService, DEFAULT_FETCH_DAYS};
+use crate::services::quotes::ProviderApiKeys;
+use crate::services::webhooks::{QuoteFetchSummary, WebhookEvent, WebhookNotifier};
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+/// Status of the scheduled quote fetch, as reported by `/api/quotes/fetch-status`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QuoteFetchStatus {
+    pub enabled: bool,
+    pub schedule: Option<String>,
+    pub next_run: Option<DateTime<Utc>>,
+    pub running: bool,
+    pub last_run_started: Option<DateTime<Utc>>,
+    pub last_run_finished: Option<DateTime<Utc>>,
+    pub last_run_success: Option<bool>,
+    pub last_run_error: Option<String>,
+    pub total: usize,
+    pub successful: usize,
+    pub failed: usize,
+}
+
+/// Shared, cloneable handle to the scheduler status
+#[derive(Clone, Default)]
+pub struct QuoteFetchStatusTracker {
+    inner: Arc<RwLock<QuoteFetchStatus>>,
+}
+
+impl QuoteFetchStatusTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn get(&self) -> QuoteFetchStatus {
+        self.inner.read().await.clone()
+    }
+
+    async fn update<F: FnOnce(&mut QuoteFetchStatus)>(&self, f: F) {
+        let mut status = self.inner.write().await;
+        f(&mut status);
+    }
+}
+
+/// Background task that periodically fetches quotes for all investments
+/// according to a cron expression (with seconds, e.g. `0 0 18 * * Mon-Fri`).
+pub struct QuoteScheduler {
+    schedule: Schedule,
+    expression: String,
+    investment_repo: Arc<dyn InvestmentRepository>,
+    price_repo: Arc<dyn InvestmentPriceRepository>,
+    settings_repo: Arc<dyn SettingsRepository>,
+    fetch_log_repo: Arc<dyn QuoteFetchLogRepository>,
+    fx_rate_repo: Arc<dyn FxRateRepository>,
+    alert_repo: Option<Arc<dyn PriceAlertRepository>>,
+    webhooks: Option<WebhookNotifier>,
+    email: Option<EmailNotifier>,
+    status: QuoteFetchStatusTracker,
+    api_keys: ProviderApiKeys,
+    provider_config_repo: Option<Arc<dyn ProviderConfigRepository>>,
+    fetch_days: u32,
+    positions: Option<Arc<PositionCalculator>>,
+    event_repo: Option<Arc<dyn CorporateEventRepository>>,
+}
+
+impl QuoteScheduler {
+    pub fn new(
+        expression: &str,
+        investment_repo: Arc<dyn InvestmentRepository>,
+        price_repo: Arc<dyn InvestmentPriceRepository>,
+        settings_repo: Arc<dyn SettingsRepository>,
+        fetch_log_repo: Arc<dyn QuoteFetchLogRepository>,
+        fx_rate_repo: Arc<dyn FxRateRepository>,
+        status: QuoteFetchStatusTracker,
+    ) -> Result<Self> {
+        let schedule = Schedule::from_str(expression).map_err(|e| {
+            AppError::InvalidInput(format!("Invalid cron expression '{}': {}", expression, e))
+        })?;
+
+        Ok(Self {
+            schedule,
+            expression: expression.to_string(),
+            investment_repo,
+            price_repo,
+            settings_repo,
+            fetch_log_repo,
+            fx_rate_repo,
+            alert_repo: None,
+            webhooks: None,
+            email: None,
+            status,
+            api_keys: ProviderApiKeys::default(),
+            provider_config_repo: None,
+            fetch_days: DEFAULT_FETCH_DAYS,
+            positions: None,
+            event_repo: None,
+        })
+    }
+
+    /// Request the given number of days up to today in every run
+    pub fn with_fetch_days(mut self, fetch_days: u32) -> Self {
+        self.fetch_days = fetch_days;
+        self
+    }
+
+    /// Skip investments that are neither held nor on the watchlist
+    pub fn with_held_only(mut self, positions: Arc<PositionCalculator>) -> Self {
+        self.positions = Some(positions);
+        self
+    }
+
+    /// Store the dividends and splits reported along with the quotes
+    pub fn with_corporate_events(mut self, event_repo: Arc<dyn CorporateEventRepository>) -> Self {
+        self.event_repo = Some(event_repo);
+        self
+    }
+
+    /// Authenticate with the providers that have a key configured
+    pub fn with_api_keys(mut self, api_keys: ProviderApiKeys) -> Self {
+        self.api_keys = api_keys;
+        self
+    }
+
+    /// Prefer the API keys stored through the settings API over the configured ones
+    pub fn with_provider_configs(
+        mut self,
+        provider_config_repo: Arc<dyn ProviderConfigRepository>,
+    ) -> Self {
+        self.provider_config_repo = Some(provider_config_repo);
+        self
+    }
+
+    /// Evaluate price alerts after every fetch
+    pub fn with_price_alerts(mut self, alert_repo: Arc<dyn PriceAlertRepository>) -> Self {
+        self.alert_repo = Some(alert_repo);
+        self
+    }
+
+    /// Send a summary after every run and triggered price alerts to the webhooks
+    pub fn with_webhooks(mut self, webhooks: WebhookNotifier) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Email triggered price alerts
+    pub fn with_email(mut self, email: EmailNotifier) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Next scheduled run after now
+    pub fn next_run(&self) -> Option<DateTime<Utc>> {
+        self.schedule.upcoming(Utc).next()
+    }
+
+    /// Spawn the scheduler loop on the tokio runtime
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        tracing::info!(
+            "Quote fetch scheduler started with schedule '{}'",
+            self.expression
+        );
+
+        let expression = self.expression.clone();
+        self.status
+            .update(|s| {
+                s.enabled = true;
+                s.schedule = Some(expression);
+            })
+            .await;
+
+        while let Some(next) = self.next_run() {
+            self.status.update(|s| s.next_run = Some(next)).await;
+
+            let wait = (next - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            self.run_once().await;
+        }
+
+        tracing::warn!("Quote fetch schedule has no upcoming runs, scheduler stopped");
+        self.status.update(|s| s.next_run = None).await;
+    }
+
+    /// Execute a single quote fetch for all investments and record its status
+    pub async fn run_once(&self) {
+        tracing::info!("Starting scheduled quote fetch");
+
+        let started_at = Utc::now();
+        self.status
+            .update(|s| {
+                s.running = true;
+                s.last_run_started = Some(started_at);
+            })
+            .await;
+
+        let outcome = self.fetch_all().await;
+        let finished_at = Utc::now();
+
+        if let Some(webhooks) = &self.webhooks {
+            let (total, successful) = outcome.as_ref().map_or((0, 0), |counts| *counts);
+            let summary = QuoteFetchSummary {
+                started_at,
+                finished_at,
+                total,
+                successful,
+                failed: total - successful,
+                error: outcome.as_ref().err().map(ToString::to_string),
+            };
+            webhooks
+                .send_logged(&WebhookEvent::QuoteFetchCompleted(summary))
+                .await;
+        }
+
+        self.status
+            .update(|s| {
+                s.running = false;
+                s.last_run_finished = Some(finished_at);
+                match outcome {
+                    Ok((total, successful)) => {
+                        s.last_run_success = Some(true);
+                        s.last_run_error = None;
+                        s.total = total;
+                        s.successful = successful;
+                        s.failed = total - successful;
+                    }
+                    Err(e) => {
+                        tracing::error!("Scheduled quote fetch failed: {}", e);
+                        s.last_run_success = Some(false);
+                        s.last_run_error = Some(e.to_string());
+                        s.total = 0;
+                        s.successful = 0;
+                        s.failed = 0;
+                    }
+                }
+            })
+            .await;
+    }
+
+    async fn fetch_all(&self) -> Result<(usize, usize)> {
+        // Read base currency on every run so settings changes are picked up
+        let base_currency = self
+            .settings_repo
+            .get()
+            .await?
+            .map(|s| s.base_currency)
+            .unwrap_or_else(|| "EUR".to_string());
+
+        let mut service = QuoteFetcherService::new(
+            self.investment_repo.clone(),
+            self.price_repo.clone(),
+            base_currency,
+        )
+        .with_fetch_log(self.fetch_log_repo.clone())
+        .with_fx_rates(self.fx_rate_repo.clone())
+        .with_api_keys(self.api_keys.clone())
+        .with_fetch_days(self.fetch_days);
+        if let Some(provider_config_repo) = &self.provider_config_repo {
+            service = service.with_provider_configs(provider_config_repo.clone());
+        }
+        if let Some(alert_repo) = &self.alert_repo {
+            service = service.with_price_alerts(alert_repo.clone());
+        }
+        if let Some(webhooks) = &self.webhooks {
+            service = service.with_webhooks(webhooks.clone());
+        }
+        if let Some(email) = &self.email {
+            service = service.with_email(email.clone());
+        }
+        if let Some(positions) = &self.positions {
+            service = service.with_held_only(positions.clone());
+        }
+        if let Some(event_repo) = &self.event_repo {
+            service = service.with_corporate_events(event_repo.clone());
+        }
+
+        let results = service.fetch_quotes(None).await?;
+        let successful = results.iter().filter(|r| r.success).count();
+        Ok((results.len(), successful))
+    }
+}
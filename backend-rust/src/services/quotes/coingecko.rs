@@ -0,0 +1,265 @@
+use crate::error::{AppError, Result};
+use crate::services::quotes::{status_error, InstrumentInfo, QuoteData, QuoteProvider};
+use chrono::{DateTime, NaiveDate};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Days of history requested for a backfill (the public API limit)
+const BACKFILL_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize)]
+struct MarketChartResponse {
+    /// `[unix timestamp in milliseconds, price]` pairs
+    prices: Vec<[f64; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinResponse {
+    name: String,
+    symbol: String,
+}
+
+/// Reduce market chart points to one close price per day.
+///
+/// Daily points are stamped at 00:00 UTC and hold the close of the previous day,
+/// so every point is attributed to the day that ends at its timestamp. The most
+/// recent point (the current price) therefore counts for today.
+pub fn daily_closes(points: &[[f64; 2]]) -> Vec<(NaiveDate, f64)> {
+    let mut closes: BTreeMap<NaiveDate, (i64, f64)> = BTreeMap::new();
+
+    for &[timestamp, price] in points {
+        let millis = timestamp as i64;
+        let Some(time) = DateTime::from_timestamp_millis(millis - 1) else {
+            continue;
+        };
+        let entry = closes.entry(time.date_naive()).or_insert((millis, price));
+        if millis >= entry.0 {
+            *entry = (millis, price);
+        }
+    }
+
+    closes
+        .into_iter()
+        .map(|(date, (_, price))| (date, price))
+        .collect()
+}
+
+/// Quotes for cryptocurrencies from CoinGecko.
+///
+/// The ticker is the CoinGecko coin id (e.g. `bitcoin`, `ethereum`) and prices
+/// are requested directly in the given currency.
+pub struct CoinGeckoProvider {
+    client: Client,
+    currency: String,
+    api_key: Option<String>,
+}
+
+impl CoinGeckoProvider {
+    pub fn new(currency: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+                .build()
+                .unwrap_or_default(),
+            currency: currency.to_uppercase(),
+            api_key: None,
+        }
+    }
+
+    /// Send a demo API key with every request
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+
+    async fn fetch_market_chart(&self, coin_id: &str, days: i64) -> Result<Vec<QuoteData>> {
+        tracing::info!(
+            "Fetching {} days of quotes from CoinGecko for coin: {}",
+            days,
+            coin_id
+        );
+
+        let url = format!(
+            "https://api.coingecko.com/api/v3/coins/{}/market_chart?vs_currency={}&days={}&interval=daily",
+            coin_id,
+            self.currency.to_lowercase(),
+            days
+        );
+        self.fetch_prices(coin_id, &url).await
+    }
+
+    /// Fetch a date range via `market_chart/range`; short ranges return hourly points
+    async fn fetch_market_chart_range(
+        &self,
+        coin_id: &str,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> Result<Vec<QuoteData>> {
+        tracing::info!(
+            "Fetching quotes from CoinGecko for coin: {} ({} to {})",
+            coin_id,
+            date_from,
+            date_to
+        );
+
+        // Include the midnight point that closes `date_to`
+        let url = format!(
+            "https://api.coingecko.com/api/v3/coins/{}/market_chart/range?vs_currency={}&from={}&to={}",
+            coin_id,
+            self.currency.to_lowercase(),
+            date_from.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp(),
+            (date_to + chrono::Duration::days(1))
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+                .timestamp()
+        );
+        let quotes = self.fetch_prices(coin_id, &url).await?;
+        Ok(quotes
+            .into_iter()
+            .filter(|q| q.date >= date_from && q.date <= date_to)
+            .collect())
+    }
+
+    /// GET request carrying the API key, if one is configured
+    fn request(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.get(url);
+        match &self.api_key {
+            Some(api_key) => request.header("x-cg-demo-api-key", api_key),
+            None => request,
+        }
+    }
+
+    async fn fetch_prices(&self, coin_id: &str, url: &str) -> Result<Vec<QuoteData>> {
+        let response = self
+            .request(url)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("CoinGecko request failed: {}", e)))?;
+
+        if response.status() == 404 {
+            tracing::warn!("Coin {} not found on CoinGecko", coin_id);
+            return Ok(vec![]);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error("CoinGecko", response.status()));
+        }
+
+        let data: MarketChartResponse = response.json().await.map_err(|e| {
+            AppError::ExternalApi(format!("Failed to parse CoinGecko response: {}", e))
+        })?;
+
+        let quotes: Vec<QuoteData> = daily_closes(&data.prices)
+            .into_iter()
+            .map(|(date, price)| {
+                QuoteData::new(
+                    coin_id.to_string(),
+                    date,
+                    price,
+                    self.currency.clone(),
+                    "coingecko".to_string(),
+                )
+            })
+            .collect();
+
+        tracing::info!(
+            "Fetched {} quotes from CoinGecko for {}",
+            quotes.len(),
+            coin_id
+        );
+        Ok(quotes)
+    }
+}
+
+#[async_trait::async_trait]
+impl QuoteProvider for CoinGeckoProvider {
+    /// Also rejects an invalid API key
+    async fn probe(&self) -> Result<()> {
+        let response = self
+            .request("https://api.coingecko.com/api/v3/ping")
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("CoinGecko request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(status_error("CoinGecko", response.status()));
+        }
+        Ok(())
+    }
+
+    async fn get_quote(
+        &self,
+        ticker: &str,
+        quote_date: Option<NaiveDate>,
+    ) -> Result<Option<QuoteData>> {
+        let today = chrono::Utc::now().date_naive();
+        match quote_date {
+            Some(target_date) => {
+                let days = (today - target_date).num_days() + 1;
+                if !(1..=BACKFILL_DAYS).contains(&days) {
+                    return Ok(None);
+                }
+                let quotes = self.fetch_market_chart(ticker, days).await?;
+                Ok(quotes.into_iter().find(|q| q.date == target_date))
+            }
+            None => {
+                let quotes = self.fetch_market_chart(ticker, 2).await?;
+                Ok(quotes.into_iter().max_by_key(|q| q.date))
+            }
+        }
+    }
+
+    async fn get_quotes(&self, ticker: &str) -> Result<Vec<QuoteData>> {
+        self.fetch_market_chart(ticker, BACKFILL_DAYS).await
+    }
+
+    async fn get_quotes_range(
+        &self,
+        ticker: &str,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> Result<Vec<QuoteData>> {
+        self.fetch_market_chart_range(ticker, date_from, date_to)
+            .await
+    }
+
+    /// Name and symbol of the coin; quotes are delivered in the configured currency
+    async fn get_instrument_info(&self, ticker: &str) -> Result<Option<InstrumentInfo>> {
+        tracing::info!("Fetching coin info from CoinGecko for: {}", ticker);
+
+        let url = format!(
+            "https://api.coingecko.com/api/v3/coins/{}?localization=false&tickers=false&market_data=false&community_data=false&developer_data=false",
+            ticker
+        );
+        let response = self
+            .request(&url)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("CoinGecko request failed: {}", e)))?;
+
+        if response.status() == 404 {
+            tracing::warn!("Coin {} not found on CoinGecko", ticker);
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error("CoinGecko", response.status()));
+        }
+
+        let coin: CoinResponse = response.json().await.map_err(|e| {
+            AppError::ExternalApi(format!("Failed to parse CoinGecko response: {}", e))
+        })?;
+
+        Ok(Some(InstrumentInfo {
+            name: Some(coin.name),
+            shortname: Some(coin.symbol.to_uppercase()),
+            currency: Some(self.currency.clone()),
+            asset_class: Some("crypto".to_string()),
+        }))
+    }
+
+    fn get_provider_name(&self) -> &str {
+        "coingecko"
+    }
+}
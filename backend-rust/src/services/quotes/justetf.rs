@@ -1,7 +1,7 @@
 use crate::error::{AppError, Result};
-use crate::services::quotes::{QuoteData, QuoteProvider};
+use crate::services::quotes::{status_error, QuoteData, QuoteProvider};
 use chrono::NaiveDate;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -12,7 +12,8 @@ struct JustETFResponse {
 #[derive(Debug, Deserialize)]
 struct JustETFDataPoint {
     date: String,
-    value: JustETFValue,
+    // null on days without a price
+    value: Option<JustETFValue>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -20,6 +21,9 @@ struct JustETFValue {
     raw: f64,
 }
 
+/// ETF that always has a price history, requested by status checks
+const PROBE_ISIN: &str = "IE00B4L5Y983";
+
 pub struct JustETFProvider {
     client: Client,
 }
@@ -61,34 +65,14 @@ impl JustETFProvider {
             .await
             .map_err(|e| AppError::ExternalApi(format!("JustETF API request failed: {}", e)))?;
 
-        if response.status() == 404 {
+        if response.status() == StatusCode::NOT_FOUND {
             tracing::warn!("ISIN {} not found on JustETF", ticker);
-            return Ok(vec![]);
-        }
-
-        if !response.status().is_success() {
-            return Err(AppError::ExternalApi(format!(
-                "JustETF API returned status: {}",
-                response.status()
-            )));
         }
-
-        let data: JustETFResponse = response.json().await.map_err(|e| {
-            AppError::ExternalApi(format!("Failed to parse JustETF API response: {}", e))
+        let status = response.status();
+        let body = response.text().await.map_err(|e| {
+            AppError::ExternalApi(format!("Failed to read JustETF API response: {}", e))
         })?;
-
-        let mut quotes = Vec::new();
-        for point in data.series {
-            if let Ok(date) = NaiveDate::parse_from_str(&point.date, "%Y-%m-%d") {
-                quotes.push(QuoteData::new(
-                    ticker.to_string(),
-                    date,
-                    point.value.raw,
-                    "EUR".to_string(),
-                    "justetf".to_string(),
-                ));
-            }
-        }
+        let quotes = quotes_from_performance_chart(ticker, status, &body)?;
 
         tracing::info!(
             "Fetched {} quotes from JustETF API for {}",
@@ -99,6 +83,41 @@ impl JustETFProvider {
     }
 }
 
+/// Quotes of a performance chart response with the given status; unknown ISINs have none
+///
+/// Prices are requested in EUR. Points without value or with an invalid date are skipped.
+pub fn quotes_from_performance_chart(
+    isin: &str,
+    status: StatusCode,
+    body: &str,
+) -> Result<Vec<QuoteData>> {
+    if status == StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !status.is_success() {
+        return Err(status_error("JustETF API", status));
+    }
+
+    let data: JustETFResponse = serde_json::from_str(body).map_err(|e| {
+        AppError::ExternalApi(format!("Failed to parse JustETF API response: {}", e))
+    })?;
+
+    Ok(data
+        .series
+        .into_iter()
+        .filter_map(|point| {
+            let date = NaiveDate::parse_from_str(&point.date, "%Y-%m-%d").ok()?;
+            Some(QuoteData::new(
+                isin.to_string(),
+                date,
+                point.value?.raw,
+                "EUR".to_string(),
+                "justetf".to_string(),
+            ))
+        })
+        .collect())
+}
+
 impl Default for JustETFProvider {
     fn default() -> Self {
         Self::new()
@@ -107,6 +126,14 @@ impl Default for JustETFProvider {
 
 #[async_trait::async_trait]
 impl QuoteProvider for JustETFProvider {
+    async fn probe(&self) -> Result<()> {
+        let date_to = chrono::Utc::now().date_naive();
+        let date_from = date_to - chrono::Duration::days(7);
+        self.fetch_quotes_range(PROBE_ISIN, date_from, date_to)
+            .await
+            .map(|_| ())
+    }
+
     async fn get_quote(
         &self,
         ticker: &str,
@@ -131,6 +158,15 @@ impl QuoteProvider for JustETFProvider {
         self.fetch_quotes_range(ticker, date_from, date_to).await
     }
 
+    async fn get_quotes_range(
+        &self,
+        ticker: &str,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> Result<Vec<QuoteData>> {
+        self.fetch_quotes_range(ticker, date_from, date_to).await
+    }
+
     fn get_provider_name(&self) -> &str {
         "justetf"
     }
@@ -1,7 +1,23 @@
+pub mod coingecko;
 pub mod justetf;
 pub mod provider_trait;
 pub mod yahoo_finance;
 
+pub use coingecko::CoinGeckoProvider;
 pub use justetf::JustETFProvider;
-pub use provider_trait::{QuoteData, QuoteProvider};
+pub use provider_trait::{
+    CorporateEvents, DividendData, InstrumentInfo, LiveQuote, ProviderApiKeys, QuoteData,
+    QuoteProvider, SplitData, SymbolMatch,
+};
 pub use yahoo_finance::YahooFinanceProvider;
+
+use crate::error::AppError;
+use reqwest::StatusCode;
+
+/// Error for an unsuccessful response status of a provider
+pub(crate) fn status_error(provider: &str, status: StatusCode) -> AppError {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return AppError::ExternalApi(format!("{} rate limit exceeded, try again later", provider));
+    }
+    AppError::ExternalApi(format!("{} returned status: {}", provider, status))
+}
@@ -1,7 +1,52 @@
 use crate::error::Result;
-use chrono::NaiveDate;
+use crate::models::ProviderConfig;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+/// API keys for the providers that accept one
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProviderApiKeys {
+    /// CoinGecko demo API key, raises the rate limit of the public API
+    pub coingecko_api_key: Option<String>,
+}
+
+impl std::fmt::Debug for ProviderApiKeys {
+    // Keys must not end up in logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderApiKeys")
+            .field(
+                "coingecko_api_key",
+                &self.coingecko_api_key.as_ref().map(|_| "***"),
+            )
+            .finish()
+    }
+}
+
+impl ProviderApiKeys {
+    /// IDs of the providers that accept an API key
+    pub const PROVIDERS: &'static [&'static str] = &["coingecko"];
+
+    /// Key of a provider, `None` if it has none or accepts none
+    pub fn get(&self, provider: &str) -> Option<&str> {
+        match provider {
+            "coingecko" => self.coingecko_api_key.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Override the configured keys with the ones stored through the settings API
+    pub fn with_stored(mut self, configs: &[ProviderConfig]) -> Self {
+        for config in configs {
+            match config.provider.as_str() {
+                "coingecko" => self.coingecko_api_key = Some(config.api_key.clone()),
+                provider => tracing::warn!("Ignoring stored API key of provider {}", provider),
+            }
+        }
+        self
+    }
+}
+
 /// Quote data returned by providers
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuoteData {
@@ -10,6 +55,9 @@ pub struct QuoteData {
     pub price: f64,
     pub currency: String,
     pub source: String,
+    /// Close adjusted for later dividends and splits, if the provider reports it
+    #[serde(default)]
+    pub adjusted_price: Option<f64>,
 }
 
 impl QuoteData {
@@ -26,8 +74,78 @@ impl QuoteData {
             price,
             currency,
             source,
+            adjusted_price: None,
         }
     }
+
+    pub fn with_adjusted_price(mut self, adjusted_price: Option<f64>) -> Self {
+        self.adjusted_price = adjusted_price;
+        self
+    }
+}
+
+/// Current price of an instrument during the trading day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveQuote {
+    pub ticker: String,
+    pub price: f64,
+    pub currency: String,
+    /// Time of the last trade
+    pub time: DateTime<Utc>,
+    pub source: String,
+}
+
+/// Dividend per share paid by an instrument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DividendData {
+    /// Ex-dividend date
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub currency: String,
+}
+
+/// Stock split of an instrument, `numerator` new shares for `denominator` old ones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitData {
+    pub date: NaiveDate,
+    pub numerator: f64,
+    pub denominator: f64,
+}
+
+/// Dividends and splits reported by a provider
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CorporateEvents {
+    pub dividends: Vec<DividendData>,
+    pub splits: Vec<SplitData>,
+}
+
+impl CorporateEvents {
+    pub fn is_empty(&self) -> bool {
+        self.dividends.is_empty() && self.splits.is_empty()
+    }
+}
+
+/// Instrument found by a provider's symbol search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolMatch {
+    /// Symbol to use as ticker of an investment with this provider, e.g. `EUNL.DE`
+    pub ticker: String,
+    pub name: Option<String>,
+    pub exchange: Option<String>,
+    pub currency: Option<String>,
+    /// Kind of instrument as named by the provider, e.g. `ETF` or `EQUITY`
+    pub quote_type: Option<String>,
+    pub provider: String,
+}
+
+/// Descriptive data of an instrument, as far as the provider knows it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentInfo {
+    pub name: Option<String>,
+    pub shortname: Option<String>,
+    pub currency: Option<String>,
+    /// `stock`, `etf`, `fund` or `crypto`
+    pub asset_class: Option<String>,
 }
 
 /// Trait for quote providers
@@ -44,6 +162,60 @@ pub trait QuoteProvider: Send + Sync {
     /// Fetch all available historical quotes for the given ticker
     async fn get_quotes(&self, ticker: &str) -> Result<Vec<QuoteData>>;
 
+    /// Fetch historical quotes between `date_from` and `date_to` (inclusive)
+    ///
+    /// The default implementation filters the result of `get_quotes`, providers
+    /// should override it when their API can request a date range.
+    async fn get_quotes_range(
+        &self,
+        ticker: &str,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> Result<Vec<QuoteData>> {
+        let quotes = self.get_quotes(ticker).await?;
+        Ok(quotes
+            .into_iter()
+            .filter(|q| q.date >= date_from && q.date <= date_to)
+            .collect())
+    }
+
+    /// Find instruments matching a name, ticker or ISIN, best match first
+    ///
+    /// Providers without a search API find nothing.
+    async fn search_symbols(&self, _query: &str) -> Result<Vec<SymbolMatch>> {
+        Ok(Vec::new())
+    }
+
+    /// Dividends and splits between `date_from` and `date_to` (inclusive)
+    ///
+    /// Providers without event data report none.
+    async fn get_events(
+        &self,
+        _ticker: &str,
+        _date_from: NaiveDate,
+        _date_to: NaiveDate,
+    ) -> Result<CorporateEvents> {
+        Ok(CorporateEvents::default())
+    }
+
+    /// Current price without the daily history, for a live value during the trading day
+    ///
+    /// Providers without a lightweight quote API return `None`.
+    async fn get_live_quote(&self, _ticker: &str) -> Result<Option<LiveQuote>> {
+        Ok(None)
+    }
+
+    /// Look up name, currency and asset class of the instrument with the given ticker
+    ///
+    /// Providers without instrument data return `None`.
+    async fn get_instrument_info(&self, _ticker: &str) -> Result<Option<InstrumentInfo>> {
+        Ok(None)
+    }
+
+    /// Send a cheap request that succeeds while the provider's API is available, for
+    /// status checks
+    async fn probe(&self) -> Result<()>;
+
     /// Get the name/ID of this provider
     fn get_provider_name(&self) -> &str;
 }
@@ -1,8 +1,12 @@
 use crate::error::{AppError, Result};
-use crate::services::quotes::{QuoteData, QuoteProvider};
-use chrono::NaiveDate;
-use reqwest::Client;
+use crate::services::quotes::{
+    status_error, CorporateEvents, DividendData, InstrumentInfo, LiveQuote, QuoteData,
+    QuoteProvider, SplitData, SymbolMatch,
+};
+use chrono::{DateTime, NaiveDate};
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
+use std::collections::HashMap;
 
 #[derive(Debug, Deserialize)]
 struct YahooQuoteResponse {
@@ -11,31 +15,127 @@ struct YahooQuoteResponse {
 
 #[derive(Debug, Deserialize)]
 struct YahooChart {
-    result: Vec<YahooResult>,
+    // null when Yahoo reports an error
+    result: Option<Vec<YahooResult>>,
+    error: Option<YahooError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooError {
+    code: String,
+    description: String,
 }
 
 #[derive(Debug, Deserialize)]
 struct YahooResult {
+    // Missing when the requested period has no trading days
+    #[serde(default)]
     timestamp: Vec<i64>,
     indicators: YahooIndicators,
     meta: YahooMeta,
+    // Only present if the period has dividends or splits
+    events: Option<YahooEvents>,
 }
 
 #[derive(Debug, Deserialize)]
 struct YahooIndicators {
     quote: Vec<YahooQuote>,
+    #[serde(default)]
+    adjclose: Vec<YahooAdjClose>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooAdjClose {
+    #[serde(default)]
+    adjclose: Vec<Option<f64>>,
+}
+
+/// Events keyed by the timestamp of their date
+#[derive(Debug, Deserialize)]
+struct YahooEvents {
+    #[serde(default)]
+    dividends: HashMap<String, YahooDividend>,
+    #[serde(default)]
+    splits: HashMap<String, YahooSplit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooDividend {
+    amount: f64,
+    date: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooSplit {
+    date: i64,
+    numerator: f64,
+    denominator: f64,
 }
 
 #[derive(Debug, Deserialize)]
 struct YahooQuote {
+    // The quote is an empty object when the period has no trading days
+    #[serde(default)]
     close: Vec<Option<f64>>,
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct YahooMeta {
-    currency: String,
+    // Missing for some delisted symbols
+    currency: Option<String>,
+    long_name: Option<String>,
+    short_name: Option<String>,
+    instrument_type: Option<String>,
 }
 
+/// Response of the quote endpoint, which reports the current price without history
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YahooLiveQuoteResponse {
+    quote_response: YahooLiveQuotes,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooLiveQuotes {
+    // null when Yahoo reports an error
+    result: Option<Vec<YahooLiveQuote>>,
+    error: Option<YahooError>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YahooLiveQuote {
+    currency: Option<String>,
+    // Missing for symbols that never traded
+    regular_market_price: Option<f64>,
+    regular_market_time: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooSearchResponse {
+    #[serde(default)]
+    quotes: Vec<YahooSearchQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YahooSearchQuote {
+    // Missing for some entries that are no tradable instruments
+    symbol: Option<String>,
+    longname: Option<String>,
+    shortname: Option<String>,
+    exch_disp: Option<String>,
+    exchange: Option<String>,
+    quote_type: Option<String>,
+}
+
+/// Number of matches requested from the symbol search
+const SEARCH_RESULT_COUNT: usize = 10;
+
+/// Ticker that always has a chart, requested by status checks
+const PROBE_TICKER: &str = "SPY";
+
 pub struct YahooFinanceProvider {
     client: Client,
 }
@@ -50,10 +150,12 @@ impl YahooFinanceProvider {
         }
     }
 
-    async fn fetch_yahoo_data(&self, ticker: &str) -> Result<YahooQuoteResponse> {
+    /// Request daily chart data with adjusted close, dividends and splits, `period`
+    /// selects the time span (e.g. `range=max`)
+    async fn fetch_chart(&self, ticker: &str, period: &str) -> Result<Option<YahooResult>> {
         let url = format!(
-            "https://query1.finance.yahoo.com/v8/finance/chart/{}?range=max&interval=1d",
-            ticker
+            "https://query1.finance.yahoo.com/v8/finance/chart/{}?{}&interval=1d&events=div%2Csplits&includeAdjustedClose=true",
+            ticker, period
         );
 
         let response =
@@ -61,17 +163,330 @@ impl YahooFinanceProvider {
                 AppError::ExternalApi(format!("Yahoo Finance request failed: {}", e))
             })?;
 
+        let status = response.status();
+        let body = response.text().await.map_err(|e| {
+            AppError::ExternalApi(format!("Failed to read Yahoo Finance response: {}", e))
+        })?;
+        let result = chart_result(status, &body)?;
+        if result.is_none() {
+            tracing::warn!("Ticker {} not found on Yahoo Finance", ticker);
+        }
+        Ok(result)
+    }
+
+    /// Request the current price from the quote endpoint
+    async fn fetch_live_quote(&self, ticker: &str) -> Result<Option<LiveQuote>> {
+        let response = self
+            .client
+            .get("https://query1.finance.yahoo.com/v7/finance/quote")
+            .query(&[("symbols", ticker)])
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Yahoo Finance request failed: {}", e)))?;
+
+        let status = response.status();
+        let body = response.text().await.map_err(|e| {
+            AppError::ExternalApi(format!("Failed to read Yahoo Finance response: {}", e))
+        })?;
+        live_quote_from_quote_response(ticker, status, &body)
+    }
+
+    async fn search(&self, query: &str) -> Result<String> {
+        let count = SEARCH_RESULT_COUNT.to_string();
+        let response = self
+            .client
+            .get("https://query1.finance.yahoo.com/v1/finance/search")
+            .query(&[("q", query), ("quotesCount", &count), ("newsCount", "0")])
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Yahoo Finance search failed: {}", e)))?;
+
         if !response.status().is_success() {
-            return Err(AppError::ExternalApi(format!(
-                "Yahoo Finance returned status: {}",
-                response.status()
-            )));
+            return Err(status_error("Yahoo Finance search", response.status()));
         }
 
-        response.json::<YahooQuoteResponse>().await.map_err(|e| {
-            AppError::ExternalApi(format!("Failed to parse Yahoo Finance response: {}", e))
+        response.text().await.map_err(|e| {
+            AppError::ExternalApi(format!(
+                "Failed to read Yahoo Finance search response: {}",
+                e
+            ))
         })
     }
+
+    /// Trading currency of a symbol, from the metadata of its latest chart
+    async fn fetch_currency(&self, ticker: &str) -> Option<String> {
+        match self.fetch_chart(ticker, "range=1d").await {
+            Ok(result) => result.and_then(|result| result.meta.currency),
+            Err(e) => {
+                tracing::debug!("No currency for {}: {}", ticker, e);
+                None
+            }
+        }
+    }
+}
+
+/// Matches of a symbol search response, without currency as the search does not report it
+pub fn symbols_from_search_response(body: &str) -> Result<Vec<SymbolMatch>> {
+    let response: YahooSearchResponse = serde_json::from_str(body).map_err(|e| {
+        AppError::ExternalApi(format!(
+            "Failed to parse Yahoo Finance search response: {}",
+            e
+        ))
+    })?;
+
+    Ok(response
+        .quotes
+        .into_iter()
+        .filter_map(|quote| {
+            Some(SymbolMatch {
+                ticker: quote.symbol?,
+                name: quote.longname.or(quote.shortname),
+                exchange: quote.exch_disp.or(quote.exchange),
+                currency: None,
+                quote_type: quote.quote_type,
+                provider: "yahoo".to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Current price of a quote endpoint response with the given status; unknown symbols and
+/// symbols without trades have none
+pub fn live_quote_from_quote_response(
+    ticker: &str,
+    status: StatusCode,
+    body: &str,
+) -> Result<Option<LiveQuote>> {
+    if status == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(status_error("Yahoo Finance", status));
+    }
+
+    let response: YahooLiveQuoteResponse = serde_json::from_str(body).map_err(|e| {
+        AppError::ExternalApi(format!("Failed to parse Yahoo Finance response: {}", e))
+    })?;
+    if let Some(error) = response.quote_response.error {
+        return Err(AppError::ExternalApi(format!(
+            "Yahoo Finance reported {}: {}",
+            error.code, error.description
+        )));
+    }
+
+    let Some(quote) = response
+        .quote_response
+        .result
+        .and_then(|r| r.into_iter().next())
+    else {
+        return Ok(None);
+    };
+    let (Some(price), Some(timestamp)) = (quote.regular_market_price, quote.regular_market_time)
+    else {
+        return Ok(None);
+    };
+    let currency = quote.currency.ok_or_else(|| {
+        AppError::ExternalApi(format!("Yahoo Finance reported no currency for {}", ticker))
+    })?;
+
+    Ok(Some(LiveQuote {
+        ticker: ticker.to_string(),
+        price,
+        currency,
+        time: DateTime::from_timestamp(timestamp, 0)
+            .ok_or_else(|| AppError::ExternalApi(format!("Invalid timestamp: {}", timestamp)))?,
+        source: "yahoo".to_string(),
+    }))
+}
+
+/// Asset class of a Yahoo instrument type, `None` for indices, currencies and futures
+pub fn asset_class_from_instrument_type(instrument_type: &str) -> Option<&'static str> {
+    match instrument_type {
+        "EQUITY" => Some("stock"),
+        "ETF" => Some("etf"),
+        "MUTUALFUND" => Some("fund"),
+        "CRYPTOCURRENCY" => Some("crypto"),
+        _ => None,
+    }
+}
+
+/// Chart data of a response, `None` for symbols unknown to Yahoo Finance.
+///
+/// Yahoo answers unknown symbols with 404 and reports other problems in the `error`
+/// field of the chart.
+fn chart_result(status: StatusCode, body: &str) -> Result<Option<YahooResult>> {
+    if status == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(status_error("Yahoo Finance", status));
+    }
+
+    let response: YahooQuoteResponse = serde_json::from_str(body).map_err(|e| {
+        AppError::ExternalApi(format!("Failed to parse Yahoo Finance response: {}", e))
+    })?;
+    if let Some(error) = response.chart.error {
+        return Err(AppError::ExternalApi(format!(
+            "Yahoo Finance reported {}: {}",
+            error.code, error.description
+        )));
+    }
+    Ok(response.chart.result.and_then(|r| r.into_iter().next()))
+}
+
+/// Instrument data from the metadata of a chart response
+pub fn instrument_info_from_chart_response(body: &str) -> Result<Option<InstrumentInfo>> {
+    Ok(chart_result(StatusCode::OK, body)?.map(instrument_info))
+}
+
+/// Daily close prices of a chart response with the given status; unknown symbols have none
+pub fn quotes_from_chart_response(
+    ticker: &str,
+    status: StatusCode,
+    body: &str,
+) -> Result<Vec<QuoteData>> {
+    match chart_result(status, body)? {
+        Some(result) => quotes_from_result(ticker, &result),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Dividends and splits of a chart response with the given status; unknown symbols have
+/// none
+pub fn events_from_chart_response(
+    ticker: &str,
+    status: StatusCode,
+    body: &str,
+) -> Result<CorporateEvents> {
+    match chart_result(status, body)? {
+        Some(result) => events_from_result(ticker, &result),
+        None => Ok(CorporateEvents::default()),
+    }
+}
+
+fn instrument_info(result: YahooResult) -> InstrumentInfo {
+    let meta = result.meta;
+    InstrumentInfo {
+        name: meta.long_name.or_else(|| meta.short_name.clone()),
+        shortname: meta.short_name,
+        currency: meta.currency,
+        asset_class: meta
+            .instrument_type
+            .as_deref()
+            .and_then(asset_class_from_instrument_type)
+            .map(str::to_string),
+    }
+}
+
+/// Extract daily close prices from chart data; days without close are skipped
+fn quotes_from_result(ticker: &str, result: &YahooResult) -> Result<Vec<QuoteData>> {
+    let timestamps = &result.timestamp;
+    let closes = &result
+        .indicators
+        .quote
+        .first()
+        .ok_or_else(|| {
+            AppError::ExternalApi("No quote data in Yahoo Finance response".to_string())
+        })?
+        .close;
+
+    // Prices in an unknown currency cannot be converted to the base currency
+    let currency = match (&result.meta.currency, timestamps.is_empty()) {
+        (Some(currency), _) => currency.clone(),
+        (None, true) => String::new(),
+        (None, false) => {
+            return Err(AppError::ExternalApi(format!(
+                "Yahoo Finance reported no currency for {}",
+                ticker
+            )))
+        }
+    };
+
+    let adjusted_closes = result
+        .indicators
+        .adjclose
+        .first()
+        .map(|a| a.adjclose.as_slice())
+        .unwrap_or_default();
+
+    let mut quotes = Vec::new();
+
+    for (i, &timestamp) in timestamps.iter().enumerate() {
+        if let Some(Some(close_price)) = closes.get(i) {
+            quotes.push(
+                QuoteData::new(
+                    ticker.to_string(),
+                    date_of(timestamp)?,
+                    *close_price,
+                    currency.clone(),
+                    "yahoo".to_string(),
+                )
+                .with_adjusted_price(adjusted_closes.get(i).copied().flatten()),
+            );
+        }
+    }
+
+    Ok(quotes)
+}
+
+/// Extract dividends and splits from chart data, ordered by date
+fn events_from_result(ticker: &str, result: &YahooResult) -> Result<CorporateEvents> {
+    let Some(events) = &result.events else {
+        return Ok(CorporateEvents::default());
+    };
+
+    let mut dividends = Vec::new();
+    if !events.dividends.is_empty() {
+        // Amounts in an unknown currency cannot be booked as payout
+        let currency = result.meta.currency.clone().ok_or_else(|| {
+            AppError::ExternalApi(format!("Yahoo Finance reported no currency for {}", ticker))
+        })?;
+        for dividend in events.dividends.values() {
+            dividends.push(DividendData {
+                date: date_of(dividend.date)?,
+                amount: dividend.amount,
+                currency: currency.clone(),
+            });
+        }
+    }
+    dividends.sort_by_key(|d| d.date);
+
+    let mut splits = Vec::new();
+    for split in events.splits.values() {
+        splits.push(SplitData {
+            date: date_of(split.date)?,
+            numerator: split.numerator,
+            denominator: split.denominator,
+        });
+    }
+    splits.sort_by_key(|s| s.date);
+
+    Ok(CorporateEvents { dividends, splits })
+}
+
+/// Date of a Unix timestamp
+fn date_of(timestamp: i64) -> Result<NaiveDate> {
+    Ok(chrono::DateTime::from_timestamp(timestamp, 0)
+        .ok_or_else(|| AppError::ExternalApi(format!("Invalid timestamp: {}", timestamp)))?
+        .date_naive())
+}
+
+/// Chart period from the start of `date_from` to the end of `date_to`
+fn range_period(date_from: NaiveDate, date_to: NaiveDate) -> String {
+    // period2 is exclusive, so request up to the start of the following day
+    format!(
+        "period1={}&period2={}",
+        date_from
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp(),
+        (date_to + chrono::Duration::days(1))
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp()
+    )
 }
 
 impl Default for YahooFinanceProvider {
@@ -82,6 +497,16 @@ impl Default for YahooFinanceProvider {
 
 #[async_trait::async_trait]
 impl QuoteProvider for YahooFinanceProvider {
+    async fn probe(&self) -> Result<()> {
+        match self.fetch_chart(PROBE_TICKER, "range=1d").await? {
+            Some(_) => Ok(()),
+            None => Err(AppError::ExternalApi(format!(
+                "Yahoo Finance returned no chart for {}",
+                PROBE_TICKER
+            ))),
+        }
+    }
+
     async fn get_quote(
         &self,
         ticker: &str,
@@ -101,43 +526,40 @@ impl QuoteProvider for YahooFinanceProvider {
     async fn get_quotes(&self, ticker: &str) -> Result<Vec<QuoteData>> {
         tracing::info!("Fetching quotes from Yahoo Finance for ticker: {}", ticker);
 
-        let response = self.fetch_yahoo_data(ticker).await?;
+        let quotes = match self.fetch_chart(ticker, "range=max").await? {
+            Some(result) => quotes_from_result(ticker, &result)?,
+            None => Vec::new(),
+        };
 
-        let result = response.chart.result.first().ok_or_else(|| {
-            AppError::ExternalApi("No data in Yahoo Finance response".to_string())
-        })?;
+        tracing::info!(
+            "Fetched {} quotes from Yahoo Finance for {}",
+            quotes.len(),
+            ticker
+        );
+        Ok(quotes)
+    }
 
-        let currency = result.meta.currency.clone();
-        let timestamps = &result.timestamp;
-        let closes = &result
-            .indicators
-            .quote
-            .first()
-            .ok_or_else(|| {
-                AppError::ExternalApi("No quote data in Yahoo Finance response".to_string())
-            })?
-            .close;
-
-        let mut quotes = Vec::new();
-
-        for (i, &timestamp) in timestamps.iter().enumerate() {
-            if let Some(Some(close_price)) = closes.get(i) {
-                // Convert Unix timestamp to NaiveDate
-                let date = chrono::DateTime::from_timestamp(timestamp, 0)
-                    .ok_or_else(|| {
-                        AppError::ExternalApi(format!("Invalid timestamp: {}", timestamp))
-                    })?
-                    .date_naive();
-
-                quotes.push(QuoteData::new(
-                    ticker.to_string(),
-                    date,
-                    *close_price,
-                    currency.clone(),
-                    "yahoo".to_string(),
-                ));
-            }
+    async fn get_quotes_range(
+        &self,
+        ticker: &str,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> Result<Vec<QuoteData>> {
+        tracing::info!(
+            "Fetching quotes from Yahoo Finance for ticker: {} ({} to {})",
+            ticker,
+            date_from,
+            date_to
+        );
+
+        let period = range_period(date_from, date_to);
+        let quotes: Vec<QuoteData> = match self.fetch_chart(ticker, &period).await? {
+            Some(result) => quotes_from_result(ticker, &result)?,
+            None => Vec::new(),
         }
+        .into_iter()
+        .filter(|q| q.date >= date_from && q.date <= date_to)
+        .collect();
 
         tracing::info!(
             "Fetched {} quotes from Yahoo Finance for {}",
@@ -147,6 +569,73 @@ impl QuoteProvider for YahooFinanceProvider {
         Ok(quotes)
     }
 
+    async fn get_events(
+        &self,
+        ticker: &str,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> Result<CorporateEvents> {
+        tracing::info!(
+            "Fetching dividends and splits from Yahoo Finance for ticker: {} ({} to {})",
+            ticker,
+            date_from,
+            date_to
+        );
+
+        let period = range_period(date_from, date_to);
+        let mut events = match self.fetch_chart(ticker, &period).await? {
+            Some(result) => events_from_result(ticker, &result)?,
+            None => CorporateEvents::default(),
+        };
+        events
+            .dividends
+            .retain(|d| d.date >= date_from && d.date <= date_to);
+        events
+            .splits
+            .retain(|s| s.date >= date_from && s.date <= date_to);
+        Ok(events)
+    }
+
+    async fn get_live_quote(&self, ticker: &str) -> Result<Option<LiveQuote>> {
+        tracing::info!(
+            "Fetching live quote from Yahoo Finance for ticker: {}",
+            ticker
+        );
+
+        let quote = self.fetch_live_quote(ticker).await?;
+        if quote.is_none() {
+            tracing::warn!("No live quote for {} on Yahoo Finance", ticker);
+        }
+        Ok(quote)
+    }
+
+    async fn search_symbols(&self, query: &str) -> Result<Vec<SymbolMatch>> {
+        tracing::info!("Searching Yahoo Finance symbols for: {}", query);
+
+        let mut matches = symbols_from_search_response(&self.search(query).await?)?;
+
+        // The search does not report currencies, so they are looked up concurrently
+        let currencies =
+            futures::future::join_all(matches.iter().map(|m| self.fetch_currency(&m.ticker))).await;
+        for (symbol, currency) in matches.iter_mut().zip(currencies) {
+            symbol.currency = currency;
+        }
+
+        Ok(matches)
+    }
+
+    async fn get_instrument_info(&self, ticker: &str) -> Result<Option<InstrumentInfo>> {
+        tracing::info!(
+            "Fetching instrument info from Yahoo Finance for: {}",
+            ticker
+        );
+
+        Ok(self
+            .fetch_chart(ticker, "range=1d")
+            .await?
+            .map(instrument_info))
+    }
+
     fn get_provider_name(&self) -> &str {
         "yahoo"
     }
@@ -0,0 +1,272 @@
+use crate::error::Result;
+use crate::models::Movement;
+use crate::repository::traits::{ActionTypeRepository, MovementRepository, SettingsRepository};
+use crate::services::action_effects::ActionEffects;
+use crate::services::dashboard::{Dashboard, DashboardPeriod};
+use crate::services::display_precision;
+use crate::services::dividends::PAYOUT_ACTION_ID;
+use crate::services::portfolio_calculator::{GrowthPoint, TotalDevelopment};
+use crate::services::DashboardService;
+use chrono::{Datelike, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Portfolio summary for the week ending at the latest valuation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeeklySummary {
+    /// Day before the week
+    pub start_date: NaiveDate,
+    /// Latest valuation date, today without developments
+    pub end_date: NaiveDate,
+    pub base_currency: String,
+    #[serde(serialize_with = "display_precision::value")]
+    pub total_value: f64,
+    /// Change of the total value, including buys and sells
+    #[serde(serialize_with = "display_precision::value")]
+    pub value_change: f64,
+    /// Time-weighted return, which excludes buys and sells
+    pub twr: f64,
+    /// Payouts (action 3) received after `start_date` up to `end_date`
+    #[serde(serialize_with = "display_precision::value")]
+    pub dividends: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub cash_balance: Decimal,
+}
+
+/// Sum of the payouts dated after `start` up to and including `end`
+pub fn payouts_between(movements: &[Movement], start: NaiveDate, end: NaiveDate) -> Decimal {
+    movements
+        .iter()
+        .filter(|m| m.action_id == Some(PAYOUT_ACTION_ID))
+        .filter(|m| m.date.is_some_and(|date| date > start && date <= end))
+        .map(|m| m.amount.unwrap_or_default().abs())
+        .sum()
+}
+
+/// Build the weekly summary from the dashboard and the normalized movements
+pub fn build_weekly_summary(
+    dashboard: &Dashboard,
+    movements: &[Movement],
+    base_currency: String,
+    today: NaiveDate,
+) -> WeeklySummary {
+    let end_date = dashboard.date.unwrap_or(today);
+    let start_date = DashboardPeriod::Week.start(end_date);
+    let week = dashboard
+        .changes
+        .iter()
+        .find(|c| c.period == DashboardPeriod::Week);
+
+    WeeklySummary {
+        start_date,
+        end_date,
+        base_currency,
+        total_value: dashboard.total_value,
+        value_change: week.map(|c| c.value_change).unwrap_or(0.0),
+        twr: week.map(|c| c.twr).unwrap_or(0.0),
+        dividends: payouts_between(movements, start_date, end_date),
+        cash_balance: dashboard.cash_balance,
+    }
+}
+
+/// Length of the periods of the periodic report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    #[default]
+    Month,
+    Year,
+}
+
+impl ReportPeriod {
+    /// First day of the period containing `date`
+    pub fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            ReportPeriod::Month => date.with_day(1),
+            ReportPeriod::Year => date.with_ordinal(1),
+        }
+        .unwrap_or(date)
+    }
+
+    /// First day of the period after the one containing `date`
+    pub fn next_start(self, date: NaiveDate) -> NaiveDate {
+        let start = self.start(date);
+        match self {
+            ReportPeriod::Month => start.checked_add_months(chrono::Months::new(1)),
+            ReportPeriod::Year => start.checked_add_months(chrono::Months::new(12)),
+        }
+        .unwrap_or(NaiveDate::MAX)
+    }
+}
+
+/// Values and cash flows of the investments in one month or year
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeriodPerformance {
+    pub start_date: NaiveDate,
+    /// Last day of the period, or the latest valuation in the current period
+    pub end_date: NaiveDate,
+    /// Value at the end of the previous period
+    #[serde(serialize_with = "display_precision::value")]
+    pub opening_value: Decimal,
+    /// Amounts paid for buys
+    #[serde(serialize_with = "display_precision::value")]
+    pub contributions: Decimal,
+    /// Amounts received for sells
+    #[serde(serialize_with = "display_precision::value")]
+    pub withdrawals: Decimal,
+    /// Payouts (action 3) received
+    #[serde(serialize_with = "display_precision::value")]
+    pub dividends: Decimal,
+    /// Fees and taxes of all movements
+    #[serde(serialize_with = "display_precision::value")]
+    pub fees: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub closing_value: Decimal,
+    /// Time-weighted return of the period, which excludes buys and sells
+    pub twr: f64,
+}
+
+/// Value on the last day before `date`, zero before the first development
+fn value_before(totals: &[TotalDevelopment], date: NaiveDate) -> Decimal {
+    let index = totals.partition_point(|t| t.date < date);
+    index
+        .checked_sub(1)
+        .map(|i| totals[i].value)
+        .unwrap_or_default()
+}
+
+/// Growth on the last valuation date before `date`, 1 before the first valuation
+fn growth_before(growth: &[GrowthPoint], date: NaiveDate) -> f64 {
+    let index = growth.partition_point(|p| p.date < date);
+    index
+        .checked_sub(1)
+        .map(|i| growth[i].growth)
+        .unwrap_or(1.0)
+}
+
+/// Split the history into periods from the first development or movement to the last
+///
+/// Expects normalized movements and date-sorted daily totals and growth points.
+pub fn build_periodic_report(
+    totals: &[TotalDevelopment],
+    growth: &[GrowthPoint],
+    movements: &[Movement],
+    period: ReportPeriod,
+) -> Vec<PeriodPerformance> {
+    let dates = totals
+        .iter()
+        .map(|t| t.date)
+        .chain(movements.iter().filter_map(|m| m.date));
+    let (Some(first), Some(last)) = (dates.clone().min(), dates.max()) else {
+        return Vec::new();
+    };
+
+    let mut report = Vec::new();
+    let mut start = period.start(first);
+    while start <= last {
+        let next_start = period.next_start(start);
+        let end_date = next_start.pred_opt().unwrap_or(next_start).min(last);
+        let in_period: Vec<&Movement> = movements
+            .iter()
+            .filter(|m| {
+                m.date
+                    .is_some_and(|date| date >= start && date < next_start)
+            })
+            .collect();
+        let sum = |action_id: i64| -> Decimal {
+            in_period
+                .iter()
+                .filter(|m| m.action_id == Some(action_id))
+                .map(|m| m.amount.unwrap_or_default().abs())
+                .sum()
+        };
+
+        report.push(PeriodPerformance {
+            start_date: start,
+            end_date,
+            opening_value: value_before(totals, start),
+            contributions: sum(1),
+            withdrawals: sum(2),
+            dividends: sum(PAYOUT_ACTION_ID),
+            fees: in_period.iter().map(|m| m.costs()).sum(),
+            closing_value: value_before(totals, next_start),
+            twr: growth_before(growth, next_start) / growth_before(growth, start) - 1.0,
+        });
+        start = next_start;
+    }
+    report
+}
+
+/// Periodic reports on the whole portfolio
+pub struct ReportService {
+    dashboard: Arc<DashboardService>,
+    movement_repo: Arc<dyn MovementRepository>,
+    settings_repo: Arc<dyn SettingsRepository>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl ReportService {
+    pub fn new(
+        dashboard: Arc<DashboardService>,
+        movement_repo: Arc<dyn MovementRepository>,
+        settings_repo: Arc<dyn SettingsRepository>,
+    ) -> Self {
+        Self {
+            dashboard,
+            movement_repo,
+            settings_repo,
+            action_type_repo: None,
+        }
+    }
+
+    /// Count payouts of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Opening and closing value, cash flows and return of every month or year
+    pub async fn periodic_report(&self, period: ReportPeriod) -> Result<Vec<PeriodPerformance>> {
+        let calculator = self.dashboard.calculator();
+        let totals = calculator
+            .calculate_total_developments(None, None, None, None)
+            .await?;
+        let growth = calculator.calculate_growth_series(None, None, None).await?;
+
+        let mut movements = self.movement_repo.find_all().await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+
+        Ok(build_periodic_report(
+            &totals,
+            &growth.total,
+            &movements,
+            period,
+        ))
+    }
+
+    /// Value, change and dividends of the week ending at the latest valuation
+    pub async fn weekly_summary(&self) -> Result<WeeklySummary> {
+        let base_currency = self
+            .settings_repo
+            .get()
+            .await?
+            .map(|s| s.base_currency)
+            .unwrap_or_else(|| "EUR".to_string());
+        let dashboard = self.dashboard.dashboard(DashboardPeriod::Week, 0).await?;
+
+        let mut movements = self.movement_repo.find_all().await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+
+        Ok(build_weekly_summary(
+            &dashboard,
+            &movements,
+            base_currency,
+            Utc::now().date_naive(),
+        ))
+    }
+}
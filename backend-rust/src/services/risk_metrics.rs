@@ -0,0 +1,129 @@
+use crate::error::Result;
+use crate::services::portfolio_calculator::GrowthPoint;
+use crate::services::PortfolioCalculator;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Average number of days per year, used to annualize volatility
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// Risk of a value series over a period
+///
+/// Drawdowns are the loss from the highest growth reached so far as a fraction,
+/// e.g. `0.2` for 20% below the peak.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct RiskMetrics {
+    pub max_drawdown: f64,
+    pub current_drawdown: f64,
+    /// Standard deviation of the returns between valuation dates, annualized
+    pub volatility: f64,
+}
+
+/// Risk metrics of a single investment
+#[derive(Debug, Clone, Serialize)]
+pub struct InvestmentRisk {
+    pub investment: i64,
+    #[serde(flatten)]
+    pub metrics: RiskMetrics,
+}
+
+/// Risk metrics per investment and for the whole portfolio
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskReport {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub total: RiskMetrics,
+    pub investments: Vec<InvestmentRisk>,
+}
+
+/// Calculate drawdowns and volatility of a date-sorted growth series
+///
+/// Returns are taken from the time-weighted growth, so buys and sells do not count as
+/// gains or losses. Periods in which nothing was held are skipped. The volatility is
+/// annualized with the average number of valuation dates per year, so it fits both
+/// daily quotes and sparse manual prices.
+pub fn calculate_risk_metrics(points: &[GrowthPoint]) -> RiskMetrics {
+    let mut peak = f64::NEG_INFINITY;
+    let mut max_drawdown: f64 = 0.0;
+    let mut current_drawdown = 0.0;
+    for point in points {
+        peak = peak.max(point.growth);
+        current_drawdown = if peak > 0.0 {
+            1.0 - point.growth / peak
+        } else {
+            0.0
+        };
+        max_drawdown = max_drawdown.max(current_drawdown);
+    }
+
+    let held: Vec<(&GrowthPoint, &GrowthPoint)> = points
+        .windows(2)
+        .map(|pair| (&pair[0], &pair[1]))
+        .filter(|(previous, _)| previous.value > 0.0 && previous.growth > 0.0)
+        .collect();
+    let returns: Vec<f64> = held
+        .iter()
+        .map(|(previous, point)| point.growth / previous.growth - 1.0)
+        .collect();
+    let days: i64 = held
+        .iter()
+        .map(|(previous, point)| (point.date - previous.date).num_days())
+        .sum();
+
+    let volatility = if returns.len() < 2 || days <= 0 {
+        0.0
+    } else {
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let periods_per_year = DAYS_PER_YEAR * n / days as f64;
+        (variance * periods_per_year).sqrt()
+    };
+
+    RiskMetrics {
+        max_drawdown,
+        current_drawdown,
+        volatility,
+    }
+}
+
+/// Calculates risk metrics from the portfolio developments
+pub struct RiskMetricsService {
+    calculator: Arc<PortfolioCalculator>,
+}
+
+impl RiskMetricsService {
+    pub fn new(calculator: Arc<PortfolioCalculator>) -> Self {
+        Self { calculator }
+    }
+
+    /// Maximum and current drawdown and volatility per investment and in total, the
+    /// total of only the given investments if `investments` is set
+    pub async fn calculate(
+        &self,
+        investments: Option<&HashSet<i64>>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<RiskReport> {
+        let growth = self
+            .calculator
+            .calculate_growth_series(investments, start_date, end_date)
+            .await?;
+
+        Ok(RiskReport {
+            start_date,
+            end_date,
+            total: calculate_risk_metrics(&growth.total),
+            investments: growth
+                .investments
+                .iter()
+                .map(|(&investment, points)| InvestmentRisk {
+                    investment,
+                    metrics: calculate_risk_metrics(points),
+                })
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,223 @@
+use crate::error::{AppError, Result};
+use crate::models::{Movement, SavingsPlan};
+use crate::repository::traits::{ActionTypeRepository, MovementRepository, SavingsPlanRepository};
+use crate::services::action_effects::ActionEffects;
+use crate::services::display_precision;
+use crate::services::PortfolioCalculator;
+use chrono::{Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Projected value at the end of one month
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectionPoint {
+    pub date: NaiveDate,
+    /// Amount paid into the savings plans since the start of the projection
+    #[serde(serialize_with = "display_precision::value")]
+    pub contributions: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub value: Decimal,
+}
+
+/// Simulated portfolio value under a constant yearly return
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SavingsPlanProjection {
+    pub start_date: NaiveDate,
+    pub annual_return: f64,
+    /// Current total value the projection starts from
+    #[serde(serialize_with = "display_precision::value")]
+    pub start_value: Decimal,
+    pub points: Vec<ProjectionPoint>,
+}
+
+/// One planned execution and the amount actually bought for it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanExecution {
+    pub date: NaiveDate,
+    #[serde(serialize_with = "display_precision::value")]
+    pub expected_amount: Decimal,
+    /// Buys of the investment from this execution up to the next one
+    #[serde(serialize_with = "display_precision::value")]
+    pub actual_amount: Decimal,
+}
+
+/// Executions of a savings plan up to today compared with the recorded buys
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanComparison {
+    pub plan_id: i64,
+    pub investment_id: i64,
+    #[serde(serialize_with = "display_precision::value")]
+    pub expected_total: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub actual_total: Decimal,
+    pub executions: Vec<PlanExecution>,
+}
+
+/// Grow `start_value` month by month with `annual_return` and add the executions of the
+/// plans after `start_date`
+///
+/// Executions are added at the end of the month they fall into, so they earn no return
+/// in that month.
+pub fn project_savings_plans(
+    plans: &[SavingsPlan],
+    start_value: Decimal,
+    start_date: NaiveDate,
+    months: u32,
+    annual_return: f64,
+) -> SavingsPlanProjection {
+    let monthly_factor =
+        Decimal::try_from((1.0 + annual_return).powf(1.0 / 12.0)).unwrap_or(Decimal::ONE);
+
+    let mut points = Vec::new();
+    let mut value = start_value;
+    let mut contributions = Decimal::ZERO;
+    let mut previous = start_date;
+    for month in 1..=months {
+        let Some(date) = start_date.checked_add_months(Months::new(month)) else {
+            break;
+        };
+        let paid: Decimal = plans
+            .iter()
+            .map(|plan| {
+                let executions = plan
+                    .executions(date)
+                    .into_iter()
+                    .filter(|execution| *execution > previous)
+                    .count();
+                plan.amount * Decimal::from(executions)
+            })
+            .sum();
+        contributions += paid;
+        value = value * monthly_factor + paid;
+        points.push(ProjectionPoint {
+            date,
+            contributions,
+            value,
+        });
+        previous = date;
+    }
+
+    SavingsPlanProjection {
+        start_date,
+        annual_return,
+        start_value,
+        points,
+    }
+}
+
+/// Match the executions of `plan` up to `today` with the buys (1) of its investment
+///
+/// Buys before the first execution are not counted.
+pub fn compare_savings_plan(
+    plan: &SavingsPlan,
+    movements: &[Movement],
+    today: NaiveDate,
+) -> PlanComparison {
+    let dates = plan.executions(today);
+    let executions: Vec<PlanExecution> = dates
+        .iter()
+        .enumerate()
+        .map(|(i, &date)| {
+            let next = dates.get(i + 1).copied();
+            let actual_amount = movements
+                .iter()
+                .filter(|m| m.investment_id == Some(plan.investment_id))
+                .filter(|m| m.action_id == Some(1))
+                .filter(|m| {
+                    m.date
+                        .is_some_and(|d| d >= date && next.is_none_or(|next| d < next))
+                })
+                .map(|m| m.amount.unwrap_or_default().abs())
+                .sum();
+            PlanExecution {
+                date,
+                expected_amount: plan.amount,
+                actual_amount,
+            }
+        })
+        .collect();
+
+    PlanComparison {
+        plan_id: plan.id,
+        investment_id: plan.investment_id,
+        expected_total: executions.iter().map(|e| e.expected_amount).sum(),
+        actual_total: executions.iter().map(|e| e.actual_amount).sum(),
+        executions,
+    }
+}
+
+/// Projects savings plans into the future and compares them with the recorded buys
+pub struct SavingsPlanService {
+    plan_repo: Arc<dyn SavingsPlanRepository>,
+    movement_repo: Arc<dyn MovementRepository>,
+    calculator: Arc<PortfolioCalculator>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl SavingsPlanService {
+    pub fn new(
+        plan_repo: Arc<dyn SavingsPlanRepository>,
+        movement_repo: Arc<dyn MovementRepository>,
+        calculator: Arc<PortfolioCalculator>,
+    ) -> Self {
+        Self {
+            plan_repo,
+            movement_repo,
+            calculator,
+            action_type_repo: None,
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Value of the portfolio over the next `months` with all savings plans
+    pub async fn projection(
+        &self,
+        months: u32,
+        annual_return: f64,
+    ) -> Result<SavingsPlanProjection> {
+        let plans = self.plan_repo.find_all(None).await?;
+        let start_value = self
+            .calculator
+            .calculate_total_developments(None, None, None, None)
+            .await?
+            .last()
+            .map(|total| total.value)
+            .unwrap_or_default();
+
+        Ok(project_savings_plans(
+            &plans,
+            start_value,
+            Utc::now().date_naive(),
+            months,
+            annual_return,
+        ))
+    }
+
+    /// Planned executions of a savings plan up to today and the buys recorded for them
+    pub async fn comparison(&self, id: i64) -> Result<PlanComparison> {
+        let plan = self
+            .plan_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::not_found("SavingsPlan", id))?;
+        let mut movements = self
+            .movement_repo
+            .find_filtered(Some(plan.investment_id), None, None, None)
+            .await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+
+        Ok(compare_savings_plan(
+            &plan,
+            &movements,
+            Utc::now().date_naive(),
+        ))
+    }
+}
@@ -0,0 +1,218 @@
+use crate::error::{ensure_found, AppError, Result};
+use crate::models::{Development, SnapshotHolding};
+use crate::repository::traits::SnapshotRepository;
+use crate::services::display_precision;
+use crate::services::PortfolioCalculator;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Holdings frozen at a date
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snapshot {
+    pub date: NaiveDate,
+    #[serde(serialize_with = "display_precision::value")]
+    pub total_value: Decimal,
+    pub holdings: Vec<SnapshotHolding>,
+}
+
+/// Date and total value of a stored snapshot
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotSummary {
+    pub date: NaiveDate,
+    #[serde(serialize_with = "display_precision::value")]
+    pub total_value: Decimal,
+    pub holdings: usize,
+}
+
+/// A holding in two snapshots; quantity and value are zero where it is missing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HoldingChange {
+    pub investment: i64,
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub from_quantity: Decimal,
+    #[serde(serialize_with = "display_precision::quantity")]
+    pub to_quantity: Decimal,
+    pub from_price: Option<Decimal>,
+    pub to_price: Option<Decimal>,
+    #[serde(serialize_with = "display_precision::value")]
+    pub from_value: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub to_value: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub change: Decimal,
+}
+
+/// Difference between the snapshots of two dates
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotComparison {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    #[serde(serialize_with = "display_precision::value")]
+    pub from_value: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub to_value: Decimal,
+    #[serde(serialize_with = "display_precision::value")]
+    pub change: Decimal,
+    pub holdings: Vec<HoldingChange>,
+}
+
+/// Latest development of every investment held at `date`
+pub fn holdings_at(date: NaiveDate, developments: &[Development]) -> Vec<SnapshotHolding> {
+    let mut latest: BTreeMap<i64, &Development> = BTreeMap::new();
+    for dev in developments.iter().filter(|dev| dev.date <= date) {
+        if latest
+            .get(&dev.investment)
+            .is_none_or(|l| l.date <= dev.date)
+        {
+            latest.insert(dev.investment, dev);
+        }
+    }
+    latest
+        .into_values()
+        .filter(|dev| !dev.quantity.is_zero())
+        .map(|dev| SnapshotHolding {
+            date,
+            investment: dev.investment,
+            price: dev.price,
+            quantity: dev.quantity,
+            value: dev.value,
+        })
+        .collect()
+}
+
+/// Compare the holdings of two snapshots by investment
+pub fn compare_snapshots(
+    from: NaiveDate,
+    from_holdings: &[SnapshotHolding],
+    to: NaiveDate,
+    to_holdings: &[SnapshotHolding],
+) -> SnapshotComparison {
+    let mut by_investment: BTreeMap<i64, (Option<&SnapshotHolding>, Option<&SnapshotHolding>)> =
+        BTreeMap::new();
+    for holding in from_holdings {
+        by_investment.entry(holding.investment).or_default().0 = Some(holding);
+    }
+    for holding in to_holdings {
+        by_investment.entry(holding.investment).or_default().1 = Some(holding);
+    }
+
+    let holdings: Vec<HoldingChange> = by_investment
+        .into_iter()
+        .map(|(investment, (before, after))| {
+            let from_value = before.map(|h| h.value).unwrap_or_default();
+            let to_value = after.map(|h| h.value).unwrap_or_default();
+            HoldingChange {
+                investment,
+                from_quantity: before.map(|h| h.quantity).unwrap_or_default(),
+                to_quantity: after.map(|h| h.quantity).unwrap_or_default(),
+                from_price: before.map(|h| h.price),
+                to_price: after.map(|h| h.price),
+                from_value,
+                to_value,
+                change: to_value - from_value,
+            }
+        })
+        .collect();
+    let from_value: Decimal = holdings.iter().map(|h| h.from_value).sum();
+    let to_value: Decimal = holdings.iter().map(|h| h.to_value).sum();
+
+    SnapshotComparison {
+        from,
+        to,
+        from_value,
+        to_value,
+        change: to_value - from_value,
+        holdings,
+    }
+}
+
+/// Freezes the calculated holdings of a date so later corrections leave them unchanged
+pub struct SnapshotService {
+    snapshot_repo: Arc<dyn SnapshotRepository>,
+    calculator: Arc<PortfolioCalculator>,
+}
+
+impl SnapshotService {
+    pub fn new(
+        snapshot_repo: Arc<dyn SnapshotRepository>,
+        calculator: Arc<PortfolioCalculator>,
+    ) -> Self {
+        Self {
+            snapshot_repo,
+            calculator,
+        }
+    }
+
+    /// Store the holdings at `date`; an existing snapshot of the date is only replaced
+    /// if `replace` is set
+    pub async fn take(&self, date: NaiveDate, replace: bool) -> Result<Snapshot> {
+        if !replace && !self.snapshot_repo.find_all(Some(date)).await?.is_empty() {
+            return Err(AppError::Conflict(format!(
+                "A snapshot of {} already exists; take it with replace=true to overwrite it",
+                date
+            )));
+        }
+        let developments = self
+            .calculator
+            .calculate_developments(None, Some(date))
+            .await?;
+        let holdings = holdings_at(date, &developments);
+        if holdings.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "Nothing was held on {}",
+                date
+            )));
+        }
+
+        self.snapshot_repo.replace(date, &holdings).await?;
+        self.get(date).await
+    }
+
+    /// Dates of the stored snapshots with their total value
+    pub async fn list(&self) -> Result<Vec<SnapshotSummary>> {
+        let mut by_date: BTreeMap<NaiveDate, SnapshotSummary> = BTreeMap::new();
+        for holding in self.snapshot_repo.find_all(None).await? {
+            let summary = by_date.entry(holding.date).or_insert(SnapshotSummary {
+                date: holding.date,
+                total_value: Decimal::ZERO,
+                holdings: 0,
+            });
+            summary.total_value += holding.value;
+            summary.holdings += 1;
+        }
+        Ok(by_date.into_values().collect())
+    }
+
+    /// Stored snapshot of `date`
+    pub async fn get(&self, date: NaiveDate) -> Result<Snapshot> {
+        let holdings = self.stored(date).await?;
+        Ok(Snapshot {
+            date,
+            total_value: holdings.iter().map(|h| h.value).sum(),
+            holdings,
+        })
+    }
+
+    pub async fn delete(&self, date: NaiveDate) -> Result<()> {
+        let deleted = self.snapshot_repo.delete(date).await?;
+        ensure_found(deleted, "Snapshot", date)
+    }
+
+    /// Change of the holdings between the stored snapshots of two dates
+    pub async fn compare(&self, from: NaiveDate, to: NaiveDate) -> Result<SnapshotComparison> {
+        let from_holdings = self.stored(from).await?;
+        let to_holdings = self.stored(to).await?;
+        Ok(compare_snapshots(from, &from_holdings, to, &to_holdings))
+    }
+
+    async fn stored(&self, date: NaiveDate) -> Result<Vec<SnapshotHolding>> {
+        let holdings = self.snapshot_repo.find_all(Some(date)).await?;
+        if holdings.is_empty() {
+            return Err(AppError::not_found("Snapshot", date));
+        }
+        Ok(holdings)
+    }
+}
@@ -0,0 +1,81 @@
+use crate::error::{AppError, Result};
+use crate::services::quotes::{QuoteProvider, SymbolMatch, YahooFinanceProvider};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Largest and default number of matches returned by a search
+pub const MAX_SEARCH_LIMIT: usize = 10;
+
+/// Searches the quote providers for instruments, e.g. to suggest tickers when an
+/// investment is created
+pub struct SymbolSearchService {
+    providers: Vec<Arc<dyn QuoteProvider>>,
+}
+
+impl SymbolSearchService {
+    /// Search Yahoo Finance, the only provider with a search API
+    pub fn new() -> Self {
+        Self::with_providers(vec![Arc::new(YahooFinanceProvider::new())])
+    }
+
+    /// Search the given providers, matches of earlier providers come first
+    pub fn with_providers(providers: Vec<Arc<dyn QuoteProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Matches for a name, ticker or ISIN of all providers
+    ///
+    /// A failing provider is skipped as long as another one answers.
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SymbolMatch>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(AppError::InvalidInput(
+                "Search query must not be empty".to_string(),
+            ));
+        }
+
+        let results = futures::future::join_all(
+            self.providers
+                .iter()
+                .map(|provider| provider.search_symbols(query)),
+        )
+        .await;
+
+        let mut matches = Vec::new();
+        let mut seen = HashSet::new();
+        let mut first_error = None;
+        let mut answered = false;
+        for (provider, result) in self.providers.iter().zip(results) {
+            match result {
+                Ok(found) => {
+                    answered = true;
+                    for symbol in found {
+                        if seen.insert((symbol.provider.clone(), symbol.ticker.clone())) {
+                            matches.push(symbol);
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Symbol search of {} failed: {}",
+                        provider.get_provider_name(),
+                        e
+                    );
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if let (false, Some(e)) = (answered, first_error) {
+            return Err(e);
+        }
+        matches.truncate(limit);
+        Ok(matches)
+    }
+}
+
+impl Default for SymbolSearchService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,319 @@
+use crate::error::Result;
+use crate::models::{Development, Investment, Movement};
+use crate::repository::traits::{ActionTypeRepository, InvestmentRepository, MovementRepository};
+use crate::services::action_effects::ActionEffects;
+use crate::services::cost_basis::{calculate_gains, CostBasisMethod, InvestmentGains};
+use crate::services::display_precision;
+use crate::services::dividends::PAYOUT_ACTION_ID;
+use crate::services::PortfolioCalculator;
+use chrono::{Datelike, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Basiszins published by the Federal Ministry of Finance per year, in basis points
+const BASE_RATES: &[(i32, i64)] = &[
+    (2018, 87),
+    (2019, 52),
+    (2020, 7),
+    (2021, -45),
+    (2022, -5),
+    (2023, 255),
+    (2024, 229),
+    (2025, 253),
+];
+
+/// Share of the Basiszins that makes up the Basisertrag of a fund
+const BASE_YIELD_FACTOR: Decimal = Decimal::from_parts(7, 0, 0, false, 1);
+
+/// Basiszins of `year` as a fraction, if it has been published
+pub fn base_rate(year: i32) -> Option<Decimal> {
+    BASE_RATES
+        .iter()
+        .find(|(y, _)| *y == year)
+        .map(|(_, basis_points)| Decimal::new(*basis_points, 4))
+}
+
+/// How the income of an investment is taxed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxCategory {
+    /// Shares, whose sale losses only offset sale gains of shares
+    Stock,
+    /// Investment funds with Teilfreistellung and Vorabpauschale
+    Fund,
+    /// Any other capital investment
+    Other,
+}
+
+impl TaxCategory {
+    /// Category from the asset class; crypto is no capital investment and has none
+    pub fn of(investment: &Investment) -> Option<Self> {
+        match investment.asset_class.as_deref() {
+            Some("crypto") => None,
+            Some("stock") => Some(TaxCategory::Stock),
+            Some("etf") | Some("fund") => Some(TaxCategory::Fund),
+            _ if investment.partial_exemption.is_some() => Some(TaxCategory::Fund),
+            _ => Some(TaxCategory::Other),
+        }
+    }
+}
+
+/// Taxable income of one investment in a year
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InvestmentTax {
+    pub investment: i64,
+    pub name: Option<String>,
+    pub category: TaxCategory,
+    /// Teilfreistellung in percent
+    pub partial_exemption: Decimal,
+    /// Payouts (action 3) received in the year
+    #[serde(serialize_with = "display_precision::value")]
+    pub dividends: Decimal,
+    /// Sum of the sales with a gain, matched FIFO
+    #[serde(serialize_with = "display_precision::value")]
+    pub realized_gains: Decimal,
+    /// Sum of the sales with a loss, as a positive amount
+    #[serde(serialize_with = "display_precision::value")]
+    pub realized_losses: Decimal,
+    /// Vorabpauschale of the year, deemed received on the first working day of the next
+    #[serde(serialize_with = "display_precision::value")]
+    pub vorabpauschale: Decimal,
+    /// Dividends, gains less losses and Vorabpauschale after the Teilfreistellung
+    #[serde(serialize_with = "display_precision::value")]
+    pub taxable_income: Decimal,
+}
+
+/// Numbers of a calendar year for the Anlage KAP
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GermanTaxReport {
+    pub year: i32,
+    /// Basiszins of the year, `None` if not published (no Vorabpauschale is calculated)
+    pub base_rate: Option<Decimal>,
+    /// Dividends, gains and Vorabpauschale after the Teilfreistellung, including the gains
+    /// from selling shares
+    #[serde(serialize_with = "display_precision::value")]
+    pub capital_income: Decimal,
+    /// Gains from selling shares contained in `capital_income`
+    #[serde(serialize_with = "display_precision::value")]
+    pub stock_gains: Decimal,
+    /// Losses other than from selling shares, after the Teilfreistellung
+    #[serde(serialize_with = "display_precision::value")]
+    pub losses: Decimal,
+    /// Losses from selling shares
+    #[serde(serialize_with = "display_precision::value")]
+    pub stock_losses: Decimal,
+    /// Taxes recorded on the movements of the year
+    #[serde(serialize_with = "display_precision::value")]
+    pub taxes_withheld: Decimal,
+    pub investments: Vec<InvestmentTax>,
+}
+
+/// Vorabpauschale of a fund from the developments up to the end of the year
+///
+/// The Basisertrag is 70% of the Basiszins on the price at the start of the year, limited
+/// to the price increase plus the distributions, less the distributions. Units bought in
+/// the year count one twelfth less for every full month before their purchase.
+fn vorabpauschale(
+    year: i32,
+    rate: Decimal,
+    developments: &[&Development],
+    gains: Option<&InvestmentGains>,
+    distributions: Decimal,
+) -> Decimal {
+    let year_start = NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or(NaiveDate::MIN);
+    let start_price = developments
+        .iter()
+        .rev()
+        .find(|dev| dev.date < year_start)
+        .or_else(|| developments.iter().find(|dev| dev.date >= year_start))
+        .map(|dev| dev.price);
+    let end_price = developments.last().map(|dev| dev.price);
+    let (Some(start_price), Some(end_price), Some(gains)) = (start_price, end_price, gains) else {
+        return Decimal::ZERO;
+    };
+
+    let units: Decimal = gains
+        .open_lots
+        .iter()
+        .map(|lot| {
+            let months = if lot.date.year() < year {
+                12
+            } else {
+                13 - lot.date.month()
+            };
+            lot.quantity * Decimal::from(months) / Decimal::from(12)
+        })
+        .sum();
+    let base_yield = start_price * rate * BASE_YIELD_FACTOR * units;
+    let increase = (end_price - start_price) * units + distributions;
+    (base_yield.min(increase) - distributions).max(Decimal::ZERO)
+}
+
+/// Build the tax report of `year` from normalized movements and developments up to its end
+pub fn build_german_tax_report(
+    year: i32,
+    investments: &[Investment],
+    movements: &[Movement],
+    developments: &[Development],
+) -> GermanTaxReport {
+    let year_end = NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or(NaiveDate::MAX);
+    let in_year = |m: &&Movement| m.date.is_some_and(|date| date.year() == year);
+    let rate = base_rate(year);
+    // German law prescribes FIFO for the lots sold
+    let gains: HashMap<i64, InvestmentGains> =
+        calculate_gains(movements, CostBasisMethod::Fifo, Some(year_end))
+            .into_iter()
+            .map(|g| (g.investment, g))
+            .collect();
+    let taxes_by_movement: HashMap<i64, Decimal> = movements
+        .iter()
+        .map(|m| (m.id, m.tax.unwrap_or_default().abs()))
+        .collect();
+    let mut by_investment: HashMap<i64, Vec<&Development>> = HashMap::new();
+    for dev in developments.iter().filter(|dev| dev.date <= year_end) {
+        by_investment.entry(dev.investment).or_default().push(dev);
+    }
+
+    let mut report = GermanTaxReport {
+        year,
+        base_rate: rate,
+        capital_income: Decimal::ZERO,
+        stock_gains: Decimal::ZERO,
+        losses: Decimal::ZERO,
+        stock_losses: Decimal::ZERO,
+        taxes_withheld: Decimal::ZERO,
+        investments: Vec::new(),
+    };
+    for investment in investments {
+        let Some(category) = TaxCategory::of(investment) else {
+            continue;
+        };
+        let own: Vec<&Movement> = movements
+            .iter()
+            .filter(|m| m.investment_id == Some(investment.id))
+            .filter(in_year)
+            .collect();
+        let gains = gains.get(&investment.id);
+        let sales = gains
+            .into_iter()
+            .flat_map(|g| &g.sales)
+            .filter(|sale| sale.date.year() == year);
+        let (realized_gains, realized_losses) =
+            sales.fold((Decimal::ZERO, Decimal::ZERO), |(up, down), sale| {
+                // Taxes withheld on a sale are no selling costs
+                let gain = sale.gain
+                    + taxes_by_movement
+                        .get(&sale.movement_id)
+                        .copied()
+                        .unwrap_or_default();
+                if gain >= Decimal::ZERO {
+                    (up + gain, down)
+                } else {
+                    (up, down - gain)
+                }
+            });
+        let dividends: Decimal = own
+            .iter()
+            .filter(|m| m.action_id == Some(PAYOUT_ACTION_ID))
+            .map(|m| m.amount.unwrap_or_default().abs())
+            .sum();
+        let vorabpauschale = match (category, rate) {
+            (TaxCategory::Fund, Some(rate)) if rate > Decimal::ZERO => vorabpauschale(
+                year,
+                rate,
+                by_investment
+                    .get(&investment.id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                gains,
+                dividends,
+            ),
+            _ => Decimal::ZERO,
+        };
+        let taxes: Decimal = own.iter().map(|m| m.tax.unwrap_or_default().abs()).sum();
+        if own.is_empty() && vorabpauschale.is_zero() {
+            continue;
+        }
+
+        let partial_exemption = investment.partial_exemption.unwrap_or_default();
+        let taxable = Decimal::ONE - partial_exemption / Decimal::ONE_HUNDRED;
+        let income = (dividends + vorabpauschale) * taxable;
+        match category {
+            TaxCategory::Stock => {
+                report.capital_income += income + realized_gains;
+                report.stock_gains += realized_gains;
+                report.stock_losses += realized_losses;
+            }
+            TaxCategory::Fund | TaxCategory::Other => {
+                report.capital_income += income + realized_gains * taxable;
+                report.losses += realized_losses * taxable;
+            }
+        }
+        report.taxes_withheld += taxes;
+        report.investments.push(InvestmentTax {
+            investment: investment.id,
+            name: investment.name.clone(),
+            category,
+            partial_exemption,
+            dividends,
+            realized_gains,
+            realized_losses,
+            vorabpauschale,
+            taxable_income: income + (realized_gains - realized_losses) * taxable,
+        });
+    }
+    report
+}
+
+/// Calculates the German capital income tax figures of a year
+pub struct GermanTaxService {
+    investment_repo: Arc<dyn InvestmentRepository>,
+    movement_repo: Arc<dyn MovementRepository>,
+    calculator: Arc<PortfolioCalculator>,
+    action_type_repo: Option<Arc<dyn ActionTypeRepository>>,
+}
+
+impl GermanTaxService {
+    pub fn new(
+        investment_repo: Arc<dyn InvestmentRepository>,
+        movement_repo: Arc<dyn MovementRepository>,
+        calculator: Arc<PortfolioCalculator>,
+    ) -> Self {
+        Self {
+            investment_repo,
+            movement_repo,
+            calculator,
+            action_type_repo: None,
+        }
+    }
+
+    /// Calculate movements of custom action types by their effect
+    pub fn with_action_types(mut self, action_type_repo: Arc<dyn ActionTypeRepository>) -> Self {
+        self.action_type_repo = Some(action_type_repo);
+        self
+    }
+
+    /// Tax report for `year` (default: current year)
+    pub async fn report(&self, year: Option<i32>) -> Result<GermanTaxReport> {
+        let year = year.unwrap_or_else(|| Utc::now().year());
+        let investments = self.investment_repo.find_all().await?;
+        let mut movements = self.movement_repo.find_all().await?;
+        ActionEffects::load(self.action_type_repo.as_ref())
+            .await?
+            .normalize(&mut movements);
+        let year_end = NaiveDate::from_ymd_opt(year, 12, 31);
+        let developments = self
+            .calculator
+            .calculate_developments(None, year_end)
+            .await?;
+
+        Ok(build_german_tax_report(
+            year,
+            &investments,
+            &movements,
+            &developments,
+        ))
+    }
+}
@@ -0,0 +1,5 @@
+//! Tax reports following the rules of a country
+
+pub mod germany;
+
+pub use germany::GermanTaxService;
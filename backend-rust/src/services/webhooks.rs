@@ -0,0 +1,221 @@
+use crate::error::{AppError, Result};
+use crate::models::TriggeredAlert;
+use crate::repository::traits::SettingsRepository;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::Serialize;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Header with the HMAC-SHA256 of the request body, e.g. `sha256=9f86d0...`
+pub const SIGNATURE_HEADER: &str = "X-PortfolioDB-Signature";
+
+/// Header with the event name, e.g. `price_alert_triggered`
+pub const EVENT_HEADER: &str = "X-PortfolioDB-Event";
+
+/// Attempts per webhook URL, including the first one
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled for every further one
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of a scheduled quote fetch
+#[derive(Debug, Clone, Serialize)]
+pub struct QuoteFetchSummary {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub total: usize,
+    pub successful: usize,
+    pub failed: usize,
+    /// Set if the run failed as a whole, e.g. because the database was unavailable
+    pub error: Option<String>,
+}
+
+/// A triggered price alert with the name of its investment
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceAlertNotification {
+    #[serde(flatten)]
+    pub alert: TriggeredAlert,
+    pub investment_name: Option<String>,
+}
+
+/// Event sent to the webhook URLs, serialized as `{"event": ..., "data": ...}`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    QuoteFetchCompleted(QuoteFetchSummary),
+    PriceAlertTriggered(PriceAlertNotification),
+}
+
+impl WebhookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookEvent::QuoteFetchCompleted(_) => "quote_fetch_completed",
+            WebhookEvent::PriceAlertTriggered(_) => "price_alert_triggered",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    #[serde(flatten)]
+    event: &'a WebhookEvent,
+    sent_at: DateTime<Utc>,
+}
+
+/// Result of sending an event to one URL
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub url: String,
+    pub success: bool,
+    pub attempts: u32,
+    /// Error of the last attempt
+    pub error: Option<String>,
+}
+
+/// Value of the signature header for a request body
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Posts events as signed JSON to the webhook URLs of the settings
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    settings_repo: Arc<dyn SettingsRepository>,
+    client: Client,
+    max_attempts: u32,
+    retry_delay: Duration,
+}
+
+impl WebhookNotifier {
+    pub fn new(settings_repo: Arc<dyn SettingsRepository>) -> Self {
+        Self {
+            settings_repo,
+            client: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_delay: DEFAULT_RETRY_DELAY,
+        }
+    }
+
+    /// Try every URL up to `max_attempts` times, waiting `retry_delay` before the first
+    /// retry and twice as long before each further one
+    pub fn with_retry(mut self, max_attempts: u32, retry_delay: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Send an event to all webhook URLs and wait for the deliveries
+    ///
+    /// The settings are read on every call, so changed URLs apply immediately.
+    pub async fn send(&self, event: &WebhookEvent) -> Result<Vec<WebhookDelivery>> {
+        let Some(settings) = self.settings_repo.get().await? else {
+            return Ok(Vec::new());
+        };
+        if settings.webhook_urls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let body = serde_json::to_vec(&WebhookPayload {
+            event,
+            sent_at: Utc::now(),
+        })
+        .map_err(|e| AppError::Internal(e.into()))?;
+        let signature = settings
+            .webhook_secret
+            .as_deref()
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| sign(secret, &body));
+
+        let deliveries = futures::future::join_all(
+            settings
+                .webhook_urls
+                .iter()
+                .map(|url| self.deliver(url, event.name(), &body, signature.as_deref())),
+        )
+        .await;
+        Ok(deliveries)
+    }
+
+    /// Send an event and log failed deliveries instead of returning them
+    pub async fn send_logged(&self, event: &WebhookEvent) {
+        match self.send(event).await {
+            Ok(deliveries) => {
+                for delivery in deliveries.iter().filter(|d| !d.success) {
+                    tracing::warn!(
+                        "Webhook {} to {} failed after {} attempt(s): {}",
+                        event.name(),
+                        delivery.url,
+                        delivery.attempts,
+                        delivery.error.as_deref().unwrap_or("unknown error")
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("Failed to send webhook {}: {}", event.name(), e),
+        }
+    }
+
+    /// Send an event in the background, so retries do not hold up the caller
+    pub fn notify(&self, event: WebhookEvent) {
+        let notifier = self.clone();
+        tokio::spawn(async move { notifier.send_logged(&event).await });
+    }
+
+    async fn deliver(
+        &self,
+        url: &str,
+        event: &str,
+        body: &[u8],
+        signature: Option<&str>,
+    ) -> WebhookDelivery {
+        let mut delay = self.retry_delay;
+        let mut error = None;
+
+        for attempt in 1..=self.max_attempts {
+            if attempt > 1 {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+
+            let mut request = self
+                .client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, event)
+                .body(body.to_vec());
+            if let Some(signature) = signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    return WebhookDelivery {
+                        url: url.to_string(),
+                        success: true,
+                        attempts: attempt,
+                        error: None,
+                    };
+                }
+                Ok(response) => error = Some(format!("HTTP {}", response.status())),
+                Err(e) => error = Some(e.to_string()),
+            }
+        }
+
+        WebhookDelivery {
+            url: url.to_string(),
+            success: false,
+            attempts: self.max_attempts,
+            error,
+        }
+    }
+}
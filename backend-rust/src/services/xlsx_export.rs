@@ -0,0 +1,303 @@
+use crate::error::Result;
+use crate::models::{ActionType, Investment, Movement, MovementListOptions, MovementSortField};
+use crate::repository::traits::{ActionTypeRepository, InvestmentRepository, MovementRepository};
+use crate::services::portfolio_calculator::{Development, PortfolioCalculator};
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+/// Content type of the generated workbooks
+pub const XLSX_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// Excel limits sheet names to 31 characters
+const MAX_SHEET_NAME_LEN: usize = 31;
+
+/// Sheet for movements that do not belong to an investment
+const UNASSIGNED_SHEET_NAME: &str = "Unassigned";
+
+const DATE_FORMAT: &str = "yyyy-mm-dd";
+const AMOUNT_FORMAT: &str = "#,##0.00";
+const QUANTITY_FORMAT: &str = "#,##0.0000";
+
+/// Spreadsheet exports of movements and developments, one sheet per investment
+pub struct XlsxExportService {
+    movement_repo: Arc<dyn MovementRepository>,
+    investment_repo: Arc<dyn InvestmentRepository>,
+    action_type_repo: Arc<dyn ActionTypeRepository>,
+    calculator: Arc<PortfolioCalculator>,
+}
+
+impl XlsxExportService {
+    pub fn new(
+        movement_repo: Arc<dyn MovementRepository>,
+        investment_repo: Arc<dyn InvestmentRepository>,
+        action_type_repo: Arc<dyn ActionTypeRepository>,
+        calculator: Arc<PortfolioCalculator>,
+    ) -> Self {
+        Self {
+            movement_repo,
+            investment_repo,
+            action_type_repo,
+            calculator,
+        }
+    }
+
+    /// Workbook with the movements matching `options`, sorted by date
+    ///
+    /// Sorting and paging of `options` are ignored.
+    pub async fn movements(&self, options: MovementListOptions) -> Result<Vec<u8>> {
+        let options = MovementListOptions {
+            sort_by: MovementSortField::Date,
+            limit: None,
+            offset: 0,
+            ..options
+        };
+        let (movements, _) = self.movement_repo.find_page(&options).await?;
+        let investments = self.investment_repo.find_all().await?;
+        let action_types = self.action_type_repo.find_all().await?;
+
+        movements_workbook(&movements, &investments, &action_types)
+    }
+
+    /// Workbook with the daily developments, as returned by `GET /api/developments`
+    pub async fn developments(
+        &self,
+        portfolio_id: Option<i64>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<u8>> {
+        let developments = self
+            .calculator
+            .calculate_portfolio_developments(portfolio_id, start_date, end_date)
+            .await?;
+        let investments = self.investment_repo.find_all().await?;
+
+        developments_workbook(&developments, &investments)
+    }
+}
+
+/// Build a workbook with one sheet of movements per investment
+///
+/// Sheets are ordered by investment ID; movements without an investment are
+/// written to a last sheet named "Unassigned".
+pub fn movements_workbook(
+    movements: &[Movement],
+    investments: &[Investment],
+    action_types: &[ActionType],
+) -> Result<Vec<u8>> {
+    let action_names: HashMap<i64, &str> = action_types
+        .iter()
+        .map(|a| (a.id, a.name.as_str()))
+        .collect();
+
+    // BTreeMap puts `None` first, so unassigned movements are moved to the end
+    let mut by_investment: BTreeMap<Option<i64>, Vec<&Movement>> = BTreeMap::new();
+    for movement in movements {
+        by_investment
+            .entry(movement.investment_id)
+            .or_default()
+            .push(movement);
+    }
+    let unassigned = by_investment.remove(&None);
+    let sheets = by_investment
+        .into_iter()
+        .chain(unassigned.map(|movements| (None, movements)));
+
+    let write = || -> std::result::Result<Vec<u8>, XlsxError> {
+        let formats = Formats::new();
+        let mut names = SheetNames::new(investments);
+        let mut workbook = Workbook::new();
+
+        for (investment_id, movements) in sheets {
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name(names.next(investment_id))?;
+            write_header(
+                worksheet,
+                &formats,
+                &["Date", "Action", "Quantity", "Amount", "Fee", "Tax"],
+            )?;
+
+            for (row, movement) in (1u32..).zip(movements) {
+                if let Some(date) = movement.date {
+                    worksheet.write_datetime_with_format(row, 0, date, &formats.date)?;
+                }
+                if let Some(action_id) = movement.action_id {
+                    match action_names.get(&action_id) {
+                        Some(name) => worksheet.write_string(row, 1, *name)?,
+                        None => worksheet.write_number(row, 1, action_id as f64)?,
+                    };
+                }
+                write_optional(worksheet, row, 2, movement.quantity, &formats.quantity)?;
+                write_optional(worksheet, row, 3, movement.amount, &formats.amount)?;
+                write_optional(worksheet, row, 4, movement.fee, &formats.amount)?;
+                write_optional(worksheet, row, 5, movement.tax, &formats.amount)?;
+            }
+        }
+
+        workbook.save_to_buffer()
+    };
+
+    Ok(write().map_err(anyhow::Error::from)?)
+}
+
+/// Build a workbook with one sheet of daily developments per investment
+pub fn developments_workbook(
+    developments: &[Development],
+    investments: &[Investment],
+) -> Result<Vec<u8>> {
+    let mut by_investment: BTreeMap<i64, Vec<&Development>> = BTreeMap::new();
+    for development in developments {
+        by_investment
+            .entry(development.investment)
+            .or_default()
+            .push(development);
+    }
+
+    let write = || -> std::result::Result<Vec<u8>, XlsxError> {
+        let formats = Formats::new();
+        let mut names = SheetNames::new(investments);
+        let mut workbook = Workbook::new();
+
+        for (investment_id, developments) in by_investment {
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name(names.next(Some(investment_id)))?;
+            write_header(worksheet, &formats, &["Date", "Price", "Quantity", "Value"])?;
+
+            for (row, development) in (1u32..).zip(developments) {
+                worksheet.write_datetime_with_format(row, 0, development.date, &formats.date)?;
+                worksheet.write_number_with_format(
+                    row,
+                    1,
+                    number(development.price),
+                    &formats.amount,
+                )?;
+                worksheet.write_number_with_format(
+                    row,
+                    2,
+                    number(development.quantity),
+                    &formats.quantity,
+                )?;
+                worksheet.write_number_with_format(
+                    row,
+                    3,
+                    number(development.value),
+                    &formats.amount,
+                )?;
+            }
+        }
+
+        workbook.save_to_buffer()
+    };
+
+    Ok(write().map_err(anyhow::Error::from)?)
+}
+
+struct Formats {
+    header: Format,
+    date: Format,
+    amount: Format,
+    quantity: Format,
+}
+
+impl Formats {
+    fn new() -> Self {
+        Self {
+            header: Format::new().set_bold(),
+            date: Format::new().set_num_format(DATE_FORMAT),
+            amount: Format::new().set_num_format(AMOUNT_FORMAT),
+            quantity: Format::new().set_num_format(QUANTITY_FORMAT),
+        }
+    }
+}
+
+/// Write a bold, frozen header row and widen the columns
+fn write_header(
+    worksheet: &mut Worksheet,
+    formats: &Formats,
+    titles: &[&str],
+) -> std::result::Result<(), XlsxError> {
+    for (col, title) in (0u16..).zip(titles) {
+        worksheet.write_string_with_format(0, col, *title, &formats.header)?;
+        worksheet.set_column_width(col, 14)?;
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+    Ok(())
+}
+
+fn write_optional(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    value: Option<Decimal>,
+    format: &Format,
+) -> std::result::Result<(), XlsxError> {
+    if let Some(value) = value {
+        worksheet.write_number_with_format(row, col, number(value), format)?;
+    }
+    Ok(())
+}
+
+/// Unique, valid sheet names derived from the investments
+struct SheetNames<'a> {
+    investments: HashMap<i64, &'a Investment>,
+    used: HashSet<String>,
+}
+
+impl<'a> SheetNames<'a> {
+    fn new(investments: &'a [Investment]) -> Self {
+        Self {
+            investments: investments.iter().map(|i| (i.id, i)).collect(),
+            used: HashSet::new(),
+        }
+    }
+
+    /// Name for the sheet of an investment: its short name, name or ID
+    fn next(&mut self, investment_id: Option<i64>) -> String {
+        let base = match investment_id {
+            Some(id) => self
+                .investments
+                .get(&id)
+                .and_then(|i| i.shortname.as_deref().or(i.name.as_deref()))
+                .map(sanitize_sheet_name)
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("Investment {}", id)),
+            None => UNASSIGNED_SHEET_NAME.to_string(),
+        };
+
+        // Excel compares sheet names case-insensitively
+        let mut name = base.clone();
+        let mut counter = 2;
+        while !self.used.insert(name.to_lowercase()) {
+            let suffix = format!(" ({})", counter);
+            let prefix: String = base
+                .chars()
+                .take(MAX_SHEET_NAME_LEN - suffix.len())
+                .collect();
+            name = format!("{}{}", prefix, suffix);
+            counter += 1;
+        }
+        name
+    }
+}
+
+/// Replace characters Excel does not allow in sheet names and truncate to 31 characters
+pub fn sanitize_sheet_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            c => c,
+        })
+        .take(MAX_SHEET_NAME_LEN)
+        .collect();
+    name.trim().trim_matches('\'').to_string()
+}
+
+/// Spreadsheet cells hold floating point numbers
+fn number(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
@@ -0,0 +1,150 @@
+use crate::error::{AppError, Result};
+use crate::isin;
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// Dates further ahead than this many days are rejected as typos
+pub const MAX_FUTURE_DAYS: i64 = 366;
+
+/// Active ISO 4217 currency codes, including precious metals and fund codes
+pub const CURRENCY_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+    "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+    "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+    "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+    "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+    "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+    "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+    "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XCD", "XCG", "XDR", "XOF", "XPD", "XPF", "XPT",
+    "YER", "ZAR", "ZMW", "ZWG", "ZWL",
+];
+
+/// Problem with a single field of a request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    /// Field name, prefixed with the position for lists, e.g. `[2].quantity`
+    pub field: String,
+    pub message: String,
+}
+
+/// Collects the problems of all fields, so a client can show them at once
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Add the errors of a list element with its position as prefix
+    pub fn add_nested(&mut self, index: usize, nested: ValidationErrors) {
+        for error in nested.errors {
+            self.errors.push(FieldError {
+                field: format!("[{}].{}", index, error.field),
+                message: error.message,
+            });
+        }
+    }
+
+    /// Checks the normalized ISIN, as stored by the handlers
+    pub fn isin(&mut self, field: &str, value: Option<&str>) {
+        if let Some(isin) = value {
+            if !isin::is_valid(&isin::normalize(isin)) {
+                self.add(field, format!("'{}' is not a valid ISIN", isin));
+            }
+        }
+    }
+
+    pub fn currency(&mut self, field: &str, value: Option<&str>) {
+        if let Some(currency) = value {
+            if !CURRENCY_CODES.contains(&currency) {
+                self.add(
+                    field,
+                    format!("'{}' is not an ISO 4217 currency code", currency),
+                );
+            }
+        }
+    }
+
+    /// Reject negative numbers, and NaN, which compares to nothing
+    pub fn non_negative<T: PartialOrd + Default>(&mut self, field: &str, value: Option<T>) {
+        if value.is_some_and(|v| {
+            !matches!(
+                v.partial_cmp(&T::default()),
+                Some(Ordering::Greater | Ordering::Equal)
+            )
+        }) {
+            self.add(field, "must not be negative");
+        }
+    }
+
+    pub fn positive<T: PartialOrd + Default>(&mut self, field: &str, value: Option<T>) {
+        if value.is_some_and(|v| v.partial_cmp(&T::default()) != Some(Ordering::Greater)) {
+            self.add(field, "must be positive");
+        }
+    }
+
+    /// Reject dates more than `MAX_FUTURE_DAYS` after today
+    pub fn date(&mut self, field: &str, value: Option<NaiveDate>) {
+        let latest = Utc::now().date_naive() + Duration::days(MAX_FUTURE_DAYS);
+        if value.is_some_and(|date| date > latest) {
+            self.add(field, format!("must not be after {}", latest));
+        }
+    }
+
+    pub fn into_result(self) -> Result<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self))
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields: Vec<String> = self
+            .errors
+            .iter()
+            .map(|e| format!("{} {}", e.field, e.message))
+            .collect();
+        write!(f, "{}", fields.join(", "))
+    }
+}
+
+/// Field checks of a request body, run by the handler before anything is stored
+pub trait Validate {
+    /// Add an error for every invalid field
+    fn check(&self, errors: &mut ValidationErrors);
+
+    fn validate(&self) -> Result<()> {
+        let mut errors = ValidationErrors::default();
+        self.check(&mut errors);
+        errors.into_result()
+    }
+}
+
+impl<T: Validate> Validate for [T] {
+    fn check(&self, errors: &mut ValidationErrors) {
+        for (index, item) in self.iter().enumerate() {
+            let mut nested = ValidationErrors::default();
+            item.check(&mut nested);
+            errors.add_nested(index, nested);
+        }
+    }
+}
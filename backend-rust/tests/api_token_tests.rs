@@ -0,0 +1,295 @@
+mod test_helpers;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Method, Request, StatusCode};
+use axum::response::Response;
+use axum::Router;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::TokenScope;
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::{create_router, RouterSettings};
+use portfoliodb_rust::services::{
+    ApiTokenService, DataRevision, DevelopmentCache, QuoteFetchStatusTracker,
+};
+use serde_json::{json, Value};
+use test_helpers::setup_test_db;
+use tower::ServiceExt;
+
+fn router(repos: Repositories) -> Router {
+    create_router(
+        repos,
+        QuoteFetchStatusTracker::new(),
+        DevelopmentCache::new(),
+        DataRevision::new(),
+        None,
+        RouterSettings::default(),
+    )
+}
+
+async fn send(
+    router: &Router,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<Value>,
+) -> Response {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let body = match body {
+        Some(body) => {
+            request = request.header(header::CONTENT_TYPE, "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    router
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn json_body(response: Response) -> Value {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Issue a token through the API and return its secret
+async fn issue(router: &Router, admin: Option<&str>, name: &str, scope: &str) -> String {
+    let response = send(
+        router,
+        Method::POST,
+        "/api/v1/tokens",
+        admin,
+        Some(json!({ "name": name, "scope": scope })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    json_body(response).await["token"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_api_is_open_until_the_first_token() {
+    let router = router(Repositories::sqlite(setup_test_db().await));
+
+    let response = send(&router, Method::GET, "/api/v1/investments", None, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Without an admin token nobody could manage the others
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/v1/tokens",
+        None,
+        Some(json!({ "name": "Dashboard", "scope": "read" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/v1/tokens",
+        None,
+        Some(json!({ "name": "Admin", "scope": "admin" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let issued = json_body(response).await;
+    assert!(issued["token"].as_str().unwrap().starts_with("pdb_"));
+    assert_eq!(issued["scope"], "admin");
+    assert!(issued.get("token_hash").is_none());
+
+    let response = send(&router, Method::GET, "/api/v1/investments", None, None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+    let response = send(
+        &router,
+        Method::GET,
+        "/api/v1/investments",
+        Some("pdb_unknown"),
+        None,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(json_body(response).await["error"]["code"], "UNAUTHORIZED");
+
+    // Probes keep working without a token
+    let response = send(&router, Method::GET, "/api/v1/health", None, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scopes_limit_the_routes() {
+    let router = router(Repositories::sqlite(setup_test_db().await));
+    let admin = issue(&router, None, "Admin", "admin").await;
+    let read = issue(&router, Some(&admin), "Dashboard", "read").await;
+    let write = issue(&router, Some(&admin), "Importer", "write").await;
+    let investment = json!({ "name": "World ETF" });
+
+    let response = send(
+        &router,
+        Method::GET,
+        "/api/v1/investments",
+        Some(&read),
+        None,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/v1/investments",
+        Some(&read),
+        Some(investment.clone()),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/v1/investments",
+        Some(&write),
+        Some(investment),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Token management and full exports need the admin scope
+    for uri in ["/api/v1/tokens", "/api/v1/export"] {
+        let response = send(&router, Method::GET, uri, Some(&write), None).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = send(&router, Method::GET, uri, Some(&admin), None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = send(&router, Method::GET, "/api/v1/tokens", Some(&admin), None).await;
+    let tokens = json_body(response).await;
+    let names: Vec<&str> = tokens
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Admin", "Dashboard", "Importer"]);
+    assert!(tokens[0].get("token").is_none());
+
+    // Revoked tokens are rejected
+    let id = tokens[1]["id"].as_i64().unwrap();
+    let uri = format!("/api/v1/tokens/{}", id);
+    let response = send(&router, Method::DELETE, &uri, Some(&admin), None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send(
+        &router,
+        Method::GET,
+        "/api/v1/investments",
+        Some(&read),
+        None,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_token_cannot_run_graphql_mutations() {
+    let router = router(Repositories::sqlite(setup_test_db().await));
+    let admin = issue(&router, None, "Admin", "admin").await;
+    let read = issue(&router, Some(&admin), "Dashboard", "read").await;
+    let graphql = |query: &str| Some(json!({ "query": query }));
+
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/v1/graphql",
+        Some(&read),
+        graphql("{ investments { id } }"),
+    )
+    .await;
+    let result = json_body(response).await;
+    assert!(result.get("errors").is_none());
+    assert_eq!(result["data"]["investments"], json!([]));
+
+    let mutation = r#"mutation { createInvestment(input: { name: "World ETF" }) { id } }"#;
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/v1/graphql",
+        Some(&read),
+        graphql(mutation),
+    )
+    .await;
+    let result = json_body(response).await;
+    assert_eq!(result["errors"][0]["extensions"]["status"], 403);
+    assert_eq!(result["data"], Value::Null);
+
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/v1/graphql",
+        Some(&admin),
+        graphql(mutation),
+    )
+    .await;
+    let result = json_body(response).await;
+    assert!(result.get("errors").is_none());
+}
+
+async fn check_authorize(repos: Repositories) {
+    let service = ApiTokenService::new(repos.api_tokens.clone());
+    assert_eq!(
+        service.authorize(None, TokenScope::Admin).await.unwrap(),
+        None
+    );
+
+    let issued = service.issue("Admin", TokenScope::Admin).await.unwrap();
+    let header = format!("Bearer {}", issued.token);
+    assert_eq!(
+        service
+            .authorize(Some(&header), TokenScope::Write)
+            .await
+            .unwrap(),
+        Some(TokenScope::Admin)
+    );
+    assert!(matches!(
+        service.authorize(None, TokenScope::Read).await,
+        Err(AppError::Unauthorized(_))
+    ));
+    // Only the bearer scheme is accepted
+    let basic = format!("Basic {}", issued.token);
+    assert!(matches!(
+        service.authorize(Some(&basic), TokenScope::Read).await,
+        Err(AppError::Unauthorized(_))
+    ));
+
+    let read = service.issue("Dashboard", TokenScope::Read).await.unwrap();
+    let header = format!("Bearer {}", read.token);
+    assert!(matches!(
+        service.authorize(Some(&header), TokenScope::Write).await,
+        Err(AppError::Forbidden(_))
+    ));
+
+    // Only the hash is stored
+    let stored = repos.api_tokens.find_all().await.unwrap();
+    assert_eq!(stored.len(), 2);
+    assert!(stored.iter().all(|t| t.token_hash != issued.token));
+
+    service.revoke(read.details.id).await.unwrap();
+    assert!(matches!(
+        service.revoke(read.details.id).await,
+        Err(AppError::NotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_authorize_sqlite() {
+    check_authorize(Repositories::sqlite(setup_test_db().await)).await;
+}
+
+#[tokio::test]
+async fn test_authorize_in_memory() {
+    check_authorize(Repositories::in_memory()).await;
+}
@@ -0,0 +1,86 @@
+mod test_helpers;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::{create_router, ApiVersions, RouterSettings};
+use portfoliodb_rust::services::{DataRevision, DevelopmentCache, QuoteFetchStatusTracker};
+use test_helpers::setup_test_db;
+use tower::ServiceExt;
+
+async fn get_response(router: &Router, uri: &str) -> Response {
+    router
+        .clone()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn body(response: Response) -> String {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn versions() -> Router {
+    ApiVersions::new()
+        .version("v1", Router::new().route("/items", get(|| async { "v1" })))
+        .version("v2", Router::new().route("/items", get(|| async { "v2" })))
+        .deprecated_alias("v1")
+        .into_router()
+}
+
+#[tokio::test]
+async fn test_versions_are_served_side_by_side() {
+    let router = versions();
+
+    let response = get_response(&router, "/api/v1/items").await;
+    assert!(response.headers().get("deprecation").is_none());
+    assert_eq!(body(response).await, "v1");
+
+    let response = get_response(&router, "/api/v2/items").await;
+    assert!(response.headers().get("deprecation").is_none());
+    assert_eq!(body(response).await, "v2");
+}
+
+#[tokio::test]
+async fn test_unversioned_alias_is_deprecated() {
+    let router = versions();
+
+    let response = get_response(&router, "/api/items?limit=2").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["deprecation"], "@1792108800");
+    assert_eq!(
+        response.headers()[header::LINK],
+        "</api/v1/items?limit=2>; rel=\"successor-version\""
+    );
+    assert_eq!(body(response).await, "v1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_api_is_mounted_under_v1() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let router = create_router(
+        repos,
+        QuoteFetchStatusTracker::new(),
+        DevelopmentCache::new(),
+        DataRevision::new(),
+        None,
+        RouterSettings::default(),
+    );
+
+    let response = get_response(&router, "/api/v1/investments").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("deprecation").is_none());
+
+    let response = get_response(&router, "/api/investments").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("deprecation").is_some());
+
+    // Probes stay unversioned
+    let response = get_response(&router, "/health/live").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("deprecation").is_none());
+}
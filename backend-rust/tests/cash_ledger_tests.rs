@@ -0,0 +1,173 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{CashMovement, Movement, Portfolio};
+use portfoliodb_rust::repository::traits::{
+    CashMovementRepository, MovementRepository, PortfolioRepository,
+};
+use portfoliodb_rust::repository::{
+    SqliteCashMovementRepository, SqliteMovementRepository, SqlitePortfolioRepository,
+};
+use portfoliodb_rust::services::cash_ledger::{
+    calculate_cash_balance, DEPOSIT_ACTION_ID, WITHDRAWAL_ACTION_ID,
+};
+use portfoliodb_rust::services::CashLedgerService;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+fn cash(
+    date: NaiveDate,
+    action_id: i64,
+    amount: Decimal,
+    portfolio_id: Option<i64>,
+) -> CashMovement {
+    CashMovement {
+        id: 0,
+        date,
+        action_id,
+        amount,
+        portfolio_id,
+        description: None,
+    }
+}
+
+fn trade(date: NaiveDate, action_id: i64, amount: Decimal, fee: Decimal) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(action_id),
+        investment_id: Some(1),
+        quantity: Some(dec!(1.0)),
+        amount: Some(amount),
+        fee: Some(fee),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+#[test]
+fn test_cash_balance_includes_trades_and_fees() {
+    let cash_movements = vec![
+        cash(day(1), DEPOSIT_ACTION_ID, dec!(1000.0), None),
+        cash(day(5), WITHDRAWAL_ACTION_ID, dec!(100.0), None),
+    ];
+    let movements = vec![
+        trade(day(2), 1, dec!(500.0), dec!(5.0)), // buy
+        trade(day(3), 2, dec!(200.0), dec!(2.0)), // sell
+        trade(day(4), 3, dec!(10.0), dec!(0.0)),  // payout
+    ];
+
+    let balances = calculate_cash_balance(&cash_movements, &movements, None, None);
+
+    let values: Vec<(NaiveDate, Decimal)> = balances.iter().map(|b| (b.date, b.balance)).collect();
+    assert_eq!(
+        values,
+        vec![
+            (day(1), dec!(1000.0)),
+            (day(2), dec!(495.0)),
+            (day(3), dec!(693.0)),
+            (day(4), dec!(703.0)),
+            (day(5), dec!(603.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_cash_balance_includes_taxes() {
+    let cash_movements = vec![cash(day(1), DEPOSIT_ACTION_ID, dec!(1000.0), None)];
+    let movements = vec![
+        Movement {
+            tax: Some(dec!(3.0)),
+            ..trade(day(2), 1, dec!(500.0), dec!(5.0))
+        },
+        Movement {
+            tax: Some(dec!(20.0)),
+            ..trade(day(3), 2, dec!(600.0), dec!(5.0))
+        },
+    ];
+
+    let balances = calculate_cash_balance(&cash_movements, &movements, None, None);
+
+    assert_eq!(balances[1].balance, dec!(492.0));
+    assert_eq!(balances[2].balance, dec!(1067.0));
+}
+
+#[test]
+fn test_cash_balance_date_range_carries_opening_balance() {
+    let cash_movements = vec![
+        cash(day(1), DEPOSIT_ACTION_ID, dec!(1000.0), None),
+        cash(day(10), DEPOSIT_ACTION_ID, dec!(500.0), None),
+        cash(day(20), WITHDRAWAL_ACTION_ID, dec!(300.0), None),
+    ];
+
+    let balances = calculate_cash_balance(&cash_movements, &[], Some(day(5)), Some(day(15)));
+
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances[0].date, day(10));
+    assert_eq!(balances[0].change, dec!(500.0));
+    assert_eq!(balances[0].balance, dec!(1500.0));
+}
+
+#[tokio::test]
+async fn test_cash_ledger_per_portfolio() {
+    let pool = setup_test_db().await;
+    let portfolio_repo = SqlitePortfolioRepository::new(pool.clone());
+    let cash_repo = Arc::new(SqliteCashMovementRepository::new(pool.clone()));
+    let movement_repo = Arc::new(SqliteMovementRepository::new(pool));
+
+    let portfolio_id = portfolio_repo
+        .create(&Portfolio {
+            id: 0,
+            name: "Brokerage".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+
+    cash_repo
+        .create(&cash(
+            day(1),
+            DEPOSIT_ACTION_ID,
+            dec!(1000.0),
+            Some(portfolio_id),
+        ))
+        .await
+        .unwrap();
+    cash_repo
+        .create(&cash(day(1), DEPOSIT_ACTION_ID, dec!(50.0), None))
+        .await
+        .unwrap();
+    movement_repo
+        .create(&Movement {
+            investment_id: None,
+            portfolio_id: Some(portfolio_id),
+            ..trade(day(2), 1, dec!(400.0), dec!(0.0))
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(cash_repo.find_all(None).await.unwrap().len(), 2);
+    assert_eq!(
+        cash_repo.find_all(Some(portfolio_id)).await.unwrap().len(),
+        1
+    );
+
+    let ledger = CashLedgerService::new(cash_repo, movement_repo);
+    let balances = ledger
+        .calculate_balance(Some(portfolio_id), None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(balances.len(), 2);
+    assert_eq!(balances[1].balance, dec!(600.0));
+
+    let total = ledger.calculate_balance(None, None, None).await.unwrap();
+    assert_eq!(total.last().unwrap().balance, dec!(650.0));
+}
@@ -0,0 +1,109 @@
+mod test_helpers;
+
+use clap::Parser;
+use portfoliodb_rust::cli::{self, Cli, Command, ImportModeArg, TokenScopeArg};
+use portfoliodb_rust::models::{ImportMode, Investment};
+use portfoliodb_rust::repository::Repositories;
+use std::path::PathBuf;
+use test_helpers::setup_test_db;
+
+fn parse(args: &[&str]) -> Cli {
+    Cli::try_parse_from(std::iter::once("portfoliodb-rust").chain(args.iter().copied())).unwrap()
+}
+
+#[test]
+fn test_parse_subcommands() {
+    assert_eq!(parse(&[]).command, None);
+    assert_eq!(parse(&["serve"]).command, Some(Command::Serve));
+    assert_eq!(parse(&["migrate"]).command, Some(Command::Migrate));
+    assert_eq!(
+        parse(&["import", "backup.json", "--mode", "replace"]).command,
+        Some(Command::Import {
+            file: PathBuf::from("backup.json"),
+            mode: ImportModeArg::Replace,
+        })
+    );
+    assert_eq!(
+        parse(&["fetch-quotes", "--investment", "1", "--investment", "3"]).command,
+        Some(Command::FetchQuotes {
+            investments: vec![1, 3],
+        })
+    );
+    assert_eq!(
+        parse(&["export", "-o", "backup.json"]).command,
+        Some(Command::Export {
+            output: Some(PathBuf::from("backup.json")),
+        })
+    );
+    assert_eq!(
+        parse(&["create-token", "--name", "Recovery"]).command,
+        Some(Command::CreateToken {
+            name: "Recovery".to_string(),
+            scope: TokenScopeArg::Admin,
+        })
+    );
+
+    // The config file can be given before or after the subcommand
+    let cli = parse(&["export", "--config", "prod.toml"]);
+    assert_eq!(cli.config, Some(PathBuf::from("prod.toml")));
+    let cli = parse(&["--data-dir", "/var/lib/portfoliodb", "migrate"]);
+    assert_eq!(cli.data_dir, Some(PathBuf::from("/var/lib/portfoliodb")));
+
+    assert!(Cli::try_parse_from(["portfoliodb-rust", "import"]).is_err());
+    assert!(Cli::try_parse_from(["portfoliodb-rust", "backup"]).is_err());
+}
+
+#[tokio::test]
+async fn test_export_and_import_roundtrip() {
+    let source = Repositories::sqlite(setup_test_db().await);
+    source
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("World ETF".to_string()),
+            isin: Some("IE00B4L5Y983".to_string()),
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    let mut exported = Vec::new();
+    cli::export(&source, &mut exported).await.unwrap();
+    let path = std::env::temp_dir().join(format!("portfoliodb-cli-{}.json", std::process::id()));
+    std::fs::write(&path, &exported).unwrap();
+
+    let target = Repositories::sqlite(setup_test_db().await);
+    let summary = cli::import(&target, &path, ImportMode::Merge).await;
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(summary.unwrap().investments, 1);
+    let investments = target.investments.find_all().await.unwrap();
+    assert_eq!(investments[0].isin.as_deref(), Some("IE00B4L5Y983"));
+}
+
+#[tokio::test]
+async fn test_import_rejects_invalid_file() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let path = std::env::temp_dir().join(format!(
+        "portfoliodb-cli-invalid-{}.json",
+        std::process::id()
+    ));
+    std::fs::write(&path, "not json").unwrap();
+
+    let error = cli::import(&repos, &path, ImportMode::Merge).await;
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(error
+        .unwrap_err()
+        .to_string()
+        .contains("Invalid export file"));
+}
@@ -0,0 +1,96 @@
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::routing::get;
+use axum::{middleware, Router};
+use portfoliodb_rust::handlers::conditional::conditional_get;
+use portfoliodb_rust::repository::notifying::{ChangeListener, DataChange};
+use portfoliodb_rust::services::DataRevision;
+use tower::ServiceExt;
+
+fn router(revision: &DataRevision) -> Router {
+    Router::new().route(
+        "/rows",
+        get(|| async { "[1, 2, 3]" })
+            .layer(middleware::from_fn_with_state(
+                revision.clone(),
+                conditional_get,
+            ))
+            .post(|| async { "created" }),
+    )
+}
+
+async fn request(
+    revision: &DataRevision,
+    method: &str,
+    header: Option<(header::HeaderName, &str)>,
+) -> axum::response::Response {
+    let mut builder = Request::builder().method(method).uri("/rows");
+    if let Some((name, value)) = header {
+        builder = builder.header(name, value);
+    }
+    router(revision)
+        .oneshot(builder.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_unchanged_data_is_not_modified() {
+    let revision = DataRevision::new();
+
+    let response = request(&revision, "GET", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[header::ETAG]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let last_modified = response.headers()[header::LAST_MODIFIED]
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(etag.starts_with("W/\""));
+
+    let response = request(&revision, "GET", Some((header::IF_NONE_MATCH, &etag))).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[header::ETAG], etag.as_str());
+
+    let response = request(
+        &revision,
+        "GET",
+        Some((header::IF_MODIFIED_SINCE, &last_modified)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    // A write changes the tag
+    revision.data_changed(DataChange::ALL);
+    let response = request(&revision, "GET", Some((header::IF_NONE_MATCH, &etag))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()[header::ETAG], etag.as_str());
+}
+
+#[tokio::test]
+async fn test_other_tags_and_old_dates_are_modified() {
+    let revision = DataRevision::new();
+
+    let response = request(
+        &revision,
+        "GET",
+        Some((header::IF_NONE_MATCH, "W/\"0-0\", \"other\"")),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request(
+        &revision,
+        "GET",
+        Some((header::IF_MODIFIED_SINCE, "Sun, 06 Nov 1994 08:49:37 GMT")),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Writes are never answered from the client's copy
+    let response = request(&revision, "POST", Some((header::IF_NONE_MATCH, "*"))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(header::ETAG).is_none());
+}
@@ -0,0 +1,266 @@
+use portfoliodb_rust::config::{
+    Config, LogFormat, SmtpTls, TlsConfig, DEFAULT_WEEKLY_SUMMARY_SCHEDULE,
+};
+use portfoliodb_rust::db::PoolSettings;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[test]
+fn test_parse_log_format() {
+    assert_eq!("text".parse::<LogFormat>().unwrap(), LogFormat::Text);
+    assert_eq!(" JSON ".parse::<LogFormat>().unwrap(), LogFormat::Json);
+    assert!("xml".parse::<LogFormat>().is_err());
+}
+
+#[test]
+fn test_tls_requires_certificate_and_key() {
+    let tls = TlsConfig::from_paths(Some("cert.pem".to_string()), Some("key.pem".to_string()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(tls.cert_path, PathBuf::from("cert.pem"));
+    assert_eq!(tls.key_path, PathBuf::from("key.pem"));
+
+    assert!(TlsConfig::from_paths(None, None).unwrap().is_none());
+    assert!(TlsConfig::from_paths(Some("cert.pem".to_string()), None).is_err());
+    assert!(TlsConfig::from_paths(None, Some("key.pem".to_string())).is_err());
+}
+
+const CONFIG_FILE: &str = r#"
+[server]
+host = "0.0.0.0"
+port = 9000
+
+[database]
+url = "sqlite:portfolio.db"
+incremental_developments = true
+max_connections = 4
+log_statements = true
+
+[providers]
+coingecko_api_key = "secret"
+
+[scheduler]
+quote_fetch_schedule = "0 0 18 * * Mon-Fri"
+quote_fetch_days = 7
+quote_fetch_held_only = false
+
+[cors]
+allowed_origins = ["https://portfolio.example.com"]
+"#;
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn test_defaults_without_file_or_environment() {
+    let config = Config::from_sources(None, env(&[])).unwrap();
+
+    assert_eq!(config.host, "127.0.0.1");
+    assert_eq!(config.port, 8001);
+    assert!(config.quote_fetch_schedule.is_none());
+    assert_eq!(config.quote_fetch_days, 31);
+    assert!(config.quote_fetch_held_only);
+    assert!(!config.incremental_developments);
+    assert!(config.cors_allowed_origins.is_empty());
+    assert!(config.api_keys.coingecko_api_key.is_none());
+    assert!(config.email.is_none());
+    assert_eq!(config.request_timeout_seconds, 30);
+    assert_eq!(config.import_body_limit_mb, 64);
+    assert_eq!(config.database_pool, PoolSettings::default());
+}
+
+#[test]
+fn test_config_file_values() {
+    let config = Config::from_sources(Some(CONFIG_FILE), env(&[])).unwrap();
+
+    assert_eq!(config.host, "0.0.0.0");
+    assert_eq!(config.port, 9000);
+    assert_eq!(config.database_url, "sqlite:portfolio.db");
+    assert!(config.incremental_developments);
+    assert_eq!(config.database_pool.max_connections, 4);
+    assert!(config.database_pool.log_statements);
+    assert_eq!(
+        config.quote_fetch_schedule.as_deref(),
+        Some("0 0 18 * * Mon-Fri")
+    );
+    assert_eq!(config.quote_fetch_days, 7);
+    assert!(!config.quote_fetch_held_only);
+    assert_eq!(
+        config.cors_allowed_origins,
+        vec!["https://portfolio.example.com"]
+    );
+    assert_eq!(config.api_keys.coingecko_api_key.as_deref(), Some("secret"));
+    // Keys are not printed
+    assert!(!format!("{:?}", config).contains("secret"));
+}
+
+#[test]
+fn test_environment_overrides_config_file() {
+    let config = Config::from_sources(
+        Some(CONFIG_FILE),
+        env(&[
+            ("PORT", "9100"),
+            ("DATABASE_URL", "postgres://localhost/portfolio"),
+            ("INCREMENTAL_DEVELOPMENTS", "false"),
+            ("QUOTE_FETCH_DAYS", "90"),
+            ("QUOTE_FETCH_HELD_ONLY", "true"),
+            ("REQUEST_TIMEOUT_SECONDS", "120"),
+            ("IMPORT_BODY_LIMIT_MB", "256"),
+            ("DATABASE_MAX_CONNECTIONS", "20"),
+            ("DATABASE_ACQUIRE_TIMEOUT_SECONDS", "5"),
+            ("DATABASE_LOG_STATEMENTS", "false"),
+            (
+                "CORS_ALLOWED_ORIGINS",
+                "http://localhost:3000, https://other.example.com",
+            ),
+        ]),
+    )
+    .unwrap();
+
+    assert_eq!(config.host, "0.0.0.0");
+    assert_eq!(config.port, 9100);
+    assert_eq!(config.database_url, "postgres://localhost/portfolio");
+    assert!(!config.incremental_developments);
+    assert_eq!(config.quote_fetch_days, 90);
+    assert!(config.quote_fetch_held_only);
+    assert_eq!(config.request_timeout_seconds, 120);
+    assert_eq!(config.import_body_limit_mb, 256);
+    assert_eq!(
+        config.database_pool,
+        PoolSettings {
+            max_connections: 20,
+            acquire_timeout: Duration::from_secs(5),
+            log_statements: false,
+        }
+    );
+    assert_eq!(
+        config.cors_allowed_origins,
+        vec!["http://localhost:3000", "https://other.example.com"]
+    );
+}
+
+#[test]
+fn test_data_dir_sets_default_database() {
+    let config = Config::from_sources(None, env(&[("DATA_DIR", "/var/lib/portfoliodb")])).unwrap();
+    assert_eq!(
+        config.database_url,
+        "sqlite:/var/lib/portfoliodb/portfoliodb.db"
+    );
+    assert_eq!(config.data_dir, Some(PathBuf::from("/var/lib/portfoliodb")));
+
+    // An explicit database URL wins
+    let config = Config::from_sources(Some(CONFIG_FILE), env(&[("DATA_DIR", "/data")])).unwrap();
+    assert_eq!(config.database_url, "sqlite:portfolio.db");
+    assert_eq!(config.data_dir, None);
+}
+
+#[test]
+fn test_validation_errors_name_the_field() {
+    let error = |file: &str, vars: &[(&str, &str)]| {
+        Config::from_sources(Some(file), env(vars))
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert!(error("[server]\nprot = 80\n", &[]).contains("prot"));
+    assert!(error("[server]\nport = \"eighty\"\n", &[]).contains("port"));
+    assert!(error("", &[("PORT", "eighty")]).contains("PORT"));
+    assert!(
+        error("[scheduler]\nquote_fetch_schedule = \"daily\"\n", &[])
+            .contains("scheduler.quote_fetch_schedule")
+    );
+    assert!(
+        error("[scheduler]\nquote_fetch_days = 0\n", &[]).contains("scheduler.quote_fetch_days")
+    );
+    assert!(error("", &[("QUOTE_FETCH_DAYS", "a month")]).contains("QUOTE_FETCH_DAYS"));
+    assert!(error("[cors]\nallowed_origins = [\"example.com\"]\n", &[])
+        .contains("cors.allowed_origins"));
+    assert!(error("[server]\nrequest_timeout_seconds = 0\n", &[])
+        .contains("server.request_timeout_seconds"));
+    assert!(error("", &[("IMPORT_BODY_LIMIT_MB", "0")]).contains("server.import_body_limit_mb"));
+    assert!(error("[database]\nmax_connections = 0\n", &[])
+        .contains("database.max_connections (DATABASE_MAX_CONNECTIONS)"));
+    assert!(error("", &[("DATABASE_LOG_STATEMENTS", "yes")]).contains("DATABASE_LOG_STATEMENTS"));
+    assert!(error("[server]\ntls_cert_path = \"cert.pem\"\n", &[]).contains("server.tls_key_path"));
+}
+
+#[test]
+fn test_parse_smtp_tls() {
+    assert_eq!("STARTTLS".parse::<SmtpTls>().unwrap(), SmtpTls::Starttls);
+    assert_eq!(" tls ".parse::<SmtpTls>().unwrap(), SmtpTls::Tls);
+    assert_eq!("none".parse::<SmtpTls>().unwrap(), SmtpTls::None);
+    assert!("ssl".parse::<SmtpTls>().is_err());
+}
+
+#[test]
+fn test_email_from_config_file_and_environment() {
+    let file = r#"
+[email]
+smtp_host = "smtp.example.com"
+smtp_tls = "tls"
+smtp_username = "portfolio"
+from = "PortfolioDB <portfolio@example.com>"
+to = ["me@example.com"]
+"#;
+    let config = Config::from_sources(
+        Some(file),
+        env(&[
+            ("SMTP_PASSWORD", "hunter2"),
+            ("EMAIL_TO", "me@example.com, you@example.com"),
+        ]),
+    )
+    .unwrap();
+
+    let email = config.email.clone().unwrap();
+    assert_eq!(email.smtp_host, "smtp.example.com");
+    assert_eq!(email.smtp_tls, SmtpTls::Tls);
+    // The port follows from the TLS mode unless given
+    assert_eq!(email.smtp_port, 465);
+    assert_eq!(email.smtp_password.as_deref(), Some("hunter2"));
+    assert_eq!(email.to, vec!["me@example.com", "you@example.com"]);
+    assert_eq!(
+        email.weekly_summary_schedule.as_deref(),
+        Some(DEFAULT_WEEKLY_SUMMARY_SCHEDULE)
+    );
+    // The password is not printed
+    assert!(!format!("{:?}", config).contains("hunter2"));
+
+    // An empty schedule sends only price alerts
+    let file = format!("{}weekly_summary_schedule = \"\"\n", file);
+    let config = Config::from_sources(Some(&file), env(&[("SMTP_PASSWORD", "hunter2")])).unwrap();
+    assert!(config.email.unwrap().weekly_summary_schedule.is_none());
+}
+
+#[test]
+fn test_email_validation_errors_name_the_field() {
+    let error = |vars: &[(&str, &str)]| {
+        let mut all = vec![
+            ("SMTP_HOST", "smtp.example.com"),
+            ("EMAIL_FROM", "portfolio@example.com"),
+            ("EMAIL_TO", "me@example.com"),
+        ];
+        all.extend_from_slice(vars);
+        Config::from_sources(None, env(&all))
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert!(error(&[("EMAIL_FROM", "not an address")]).contains("EMAIL_FROM"));
+    assert!(error(&[("EMAIL_TO", "me@example.com, nobody")]).contains("EMAIL_TO"));
+    assert!(error(&[("SMTP_TLS", "ssl")]).contains("SMTP_TLS"));
+    assert!(error(&[("SMTP_PORT", "smtp")]).contains("SMTP_PORT"));
+    assert!(error(&[("SMTP_USERNAME", "portfolio")]).contains("SMTP_PASSWORD"));
+    assert!(error(&[("EMAIL_WEEKLY_SUMMARY_SCHEDULE", "weekly")])
+        .contains("email.weekly_summary_schedule"));
+
+    let missing = Config::from_sources(None, env(&[("SMTP_HOST", "smtp.example.com")]))
+        .unwrap_err()
+        .to_string();
+    assert!(missing.contains("email.from"));
+}
@@ -0,0 +1,230 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::{DividendEvent, DividendStatus, Investment, Movement, SplitEvent};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::dividends::PAYOUT_ACTION_ID;
+use portfoliodb_rust::services::CorporateEventService;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn date(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 6, d).unwrap()
+}
+
+fn dividend(investment_id: i64, day: u32, amount: Decimal) -> DividendEvent {
+    DividendEvent {
+        id: 0,
+        investment_id,
+        date: date(day),
+        amount,
+        currency: Some("EUR".to_string()),
+        source: "yahoo".to_string(),
+        status: DividendStatus::Pending.to_string(),
+        movement_id: None,
+    }
+}
+
+fn purchase(investment_id: i64, day: u32, quantity: Decimal) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date(day)),
+        action_id: Some(1),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(quantity * dec!(100)),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+async fn setup() -> (Repositories, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Dividend Stock".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: Some("DIV.DE".to_string()),
+            quote_provider: Some("yahoo".to_string()),
+            currency: Some("EUR".to_string()),
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    (repos, investment_id)
+}
+
+fn service(repos: &Repositories) -> CorporateEventService {
+    CorporateEventService::new(
+        repos.corporate_events.clone(),
+        repos.movements.clone(),
+        repos.settings.clone(),
+    )
+    .with_action_types(repos.action_types.clone())
+}
+
+#[tokio::test]
+async fn test_events_are_stored_once() {
+    let (repos, investment_id) = setup().await;
+    let events = repos.corporate_events.clone();
+
+    let dividends = vec![
+        dividend(investment_id, 4, dec!(0.25)),
+        dividend(investment_id, 10, dec!(0.3)),
+    ];
+    assert_eq!(events.add_dividends(&dividends).await.unwrap(), 2);
+    // A later fetch of an overlapping period reports the same dividends again
+    assert_eq!(events.add_dividends(&dividends).await.unwrap(), 0);
+
+    let stored = events
+        .find_dividends(Some(investment_id), None)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 2);
+    assert_eq!(stored[0].date, date(4));
+    assert_eq!(stored[0].amount, dec!(0.25));
+    assert_eq!(stored[0].status, "pending");
+    assert!(events
+        .find_dividends(Some(investment_id + 1), None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let split = SplitEvent {
+        id: 0,
+        investment_id,
+        date: date(5),
+        numerator: dec!(4),
+        denominator: dec!(1),
+        source: "yahoo".to_string(),
+    };
+    assert_eq!(
+        events
+            .add_splits(std::slice::from_ref(&split))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(events.add_splits(&[split]).await.unwrap(), 0);
+    let splits = events.find_splits(None).await.unwrap();
+    assert_eq!(splits.len(), 1);
+    assert_eq!(splits[0].numerator, dec!(4));
+}
+
+#[tokio::test]
+async fn test_confirm_dividend_records_payout() {
+    let (repos, investment_id) = setup().await;
+    repos
+        .movements
+        .create(&purchase(investment_id, 1, dec!(10)))
+        .await
+        .unwrap();
+    // Bought on the ex-dividend date, not entitled to the dividend
+    repos
+        .movements
+        .create(&purchase(investment_id, 4, dec!(5)))
+        .await
+        .unwrap();
+    repos
+        .corporate_events
+        .add_dividends(&[dividend(investment_id, 4, dec!(0.25))])
+        .await
+        .unwrap();
+    let service = service(&repos);
+
+    let pending = service
+        .dividends(None, Some(DividendStatus::Pending))
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].quantity, dec!(10));
+    assert_eq!(pending[0].payout, Some(dec!(2.5)));
+
+    let confirmed = service
+        .confirm_dividend(pending[0].dividend.id, None)
+        .await
+        .unwrap();
+    assert_eq!(confirmed.dividend.status, "confirmed");
+    let movement_id = confirmed.dividend.movement_id.unwrap();
+
+    let payout = repos
+        .movements
+        .find_by_id(movement_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(payout.action_id, Some(PAYOUT_ACTION_ID));
+    assert_eq!(payout.date, Some(date(4)));
+    assert_eq!(payout.amount, Some(dec!(2.5)));
+    assert_eq!(
+        payout.external_id,
+        Some(format!("dividend-event-{}", confirmed.dividend.id))
+    );
+
+    // A dividend is booked only once
+    let err = service
+        .confirm_dividend(confirmed.dividend.id, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::Conflict(_)), "{:?}", err);
+    assert!(service
+        .dividends(None, Some(DividendStatus::Pending))
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_dividend_without_holding() {
+    let (repos, investment_id) = setup().await;
+    repos
+        .corporate_events
+        .add_dividends(&[
+            dividend(investment_id, 4, dec!(0.25)),
+            dividend(investment_id, 10, dec!(0.3)),
+        ])
+        .await
+        .unwrap();
+    let service = service(&repos);
+    let dividends = service.dividends(Some(investment_id), None).await.unwrap();
+    assert_eq!(dividends[0].payout, Some(Decimal::ZERO));
+
+    // Nothing to book without the amount actually received
+    let err = service
+        .confirm_dividend(dividends[0].dividend.id, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)), "{:?}", err);
+    let confirmed = service
+        .confirm_dividend(dividends[0].dividend.id, Some(dec!(1.2)))
+        .await
+        .unwrap();
+    assert_eq!(confirmed.payout, Some(dec!(1.2)));
+
+    let dismissed = service
+        .dismiss_dividend(dividends[1].dividend.id)
+        .await
+        .unwrap();
+    assert_eq!(dismissed.status, "dismissed");
+    assert_eq!(dismissed.movement_id, None);
+    let err = service
+        .dismiss_dividend(dividends[1].dividend.id)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::Conflict(_)), "{:?}", err);
+
+    let err = service.dismiss_dividend(99).await.unwrap_err();
+    assert!(matches!(err, AppError::NotFound { .. }));
+}
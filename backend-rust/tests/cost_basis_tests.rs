@@ -0,0 +1,210 @@
+use chrono::NaiveDate;
+use portfoliodb_rust::models::Movement;
+use portfoliodb_rust::services::cost_basis::{
+    calculate_gains, calculate_portfolio_gains, CostBasisMethod, SPLIT_ACTION_ID,
+    TRANSFER_IN_ACTION_ID, TRANSFER_OUT_ACTION_ID,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn movement(id: i64, day: u32, action_id: i64, quantity: Decimal, amount: Decimal) -> Movement {
+    Movement {
+        id,
+        date: Some(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
+        action_id: Some(action_id),
+        investment_id: Some(1),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: Some(dec!(0.0)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+/// Buy 10 @ 10, buy 10 @ 20, sell 10 @ 30
+fn two_lots_and_a_sale() -> Vec<Movement> {
+    vec![
+        movement(1, 1, 1, dec!(10.0), dec!(100.0)),
+        movement(2, 2, 1, dec!(10.0), dec!(200.0)),
+        movement(3, 3, 2, dec!(10.0), dec!(300.0)),
+    ]
+}
+
+#[test]
+fn test_fifo_sells_oldest_lot_first() {
+    let gains = calculate_gains(&two_lots_and_a_sale(), CostBasisMethod::Fifo, None);
+
+    assert_eq!(gains.len(), 1);
+    assert_eq!(gains[0].realized_gain, dec!(200.0)); // 300 - 100
+    assert_eq!(gains[0].quantity, dec!(10.0));
+    assert_eq!(gains[0].cost_basis, dec!(200.0));
+    assert_eq!(gains[0].open_lots.len(), 1);
+    assert_eq!(gains[0].open_lots[0].unit_cost, dec!(20.0));
+}
+
+#[test]
+fn test_fees_and_taxes_count_as_cost_and_reduce_proceeds() {
+    let buy = Movement {
+        fee: Some(dec!(1.0)),
+        tax: Some(dec!(0.5)),
+        ..movement(1, 1, 1, dec!(10.0), dec!(100.0))
+    };
+    let sell = Movement {
+        fee: Some(dec!(1.0)),
+        tax: Some(dec!(12.5)),
+        ..movement(2, 2, 2, dec!(5.0), dec!(150.0))
+    };
+
+    let gains = calculate_gains(&[buy, sell], CostBasisMethod::Fifo, None);
+
+    assert_eq!(gains[0].sales[0].proceeds, dec!(136.5)); // 150 - 1 - 12.5
+    assert_eq!(gains[0].sales[0].cost, dec!(50.75)); // half of 100 + 1 + 0.5
+    assert_eq!(gains[0].realized_gain, dec!(85.75));
+    assert_eq!(gains[0].cost_basis, dec!(50.75));
+}
+
+#[test]
+fn test_lifo_sells_newest_lot_first() {
+    let gains = calculate_gains(&two_lots_and_a_sale(), CostBasisMethod::Lifo, None);
+
+    assert_eq!(gains[0].realized_gain, dec!(100.0)); // 300 - 200
+    assert_eq!(gains[0].cost_basis, dec!(100.0));
+    assert_eq!(gains[0].open_lots[0].unit_cost, dec!(10.0));
+}
+
+#[test]
+fn test_average_cost_pools_lots() {
+    let gains = calculate_gains(&two_lots_and_a_sale(), CostBasisMethod::Average, None);
+
+    assert_eq!(gains[0].realized_gain, dec!(150.0)); // 300 - 10 * 15
+    assert_eq!(gains[0].cost_basis, dec!(150.0));
+    assert_eq!(gains[0].average_cost, Some(dec!(15.0)));
+}
+
+#[test]
+fn test_partial_lot_sale_and_end_date() {
+    let movements = vec![
+        movement(1, 1, 1, dec!(10.0), dec!(100.0)),
+        movement(2, 2, 2, dec!(4.0), dec!(60.0)),
+        movement(3, 5, 2, dec!(6.0), dec!(120.0)),
+    ];
+
+    let gains = calculate_gains(
+        &movements,
+        CostBasisMethod::Fifo,
+        NaiveDate::from_ymd_opt(2024, 1, 3),
+    );
+
+    assert_eq!(gains[0].sales.len(), 1);
+    assert_eq!(gains[0].sales[0].cost, dec!(40.0));
+    assert_eq!(gains[0].realized_gain, dec!(20.0));
+    assert_eq!(gains[0].quantity, dec!(6.0));
+}
+
+#[test]
+fn test_split_adjusts_lots_without_changing_cost() {
+    // Buy 10 @ 100, 1:4 split, sell 8 @ 30
+    let movements = vec![
+        movement(1, 1, 1, dec!(10.0), dec!(1000.0)),
+        movement(2, 5, SPLIT_ACTION_ID, dec!(4.0), dec!(0.0)),
+        movement(3, 6, 2, dec!(8.0), dec!(240.0)),
+    ];
+
+    let gains = calculate_gains(&movements, CostBasisMethod::Fifo, None);
+
+    assert_eq!(gains[0].sales[0].cost, dec!(200.0));
+    assert_eq!(gains[0].realized_gain, dec!(40.0));
+    assert_eq!(gains[0].quantity, dec!(32.0));
+    assert_eq!(gains[0].cost_basis, dec!(800.0));
+    assert_eq!(gains[0].open_lots.len(), 1);
+    assert_eq!(gains[0].open_lots[0].unit_cost, dec!(25.0));
+}
+
+#[test]
+fn test_split_applies_before_trades_of_the_same_day() {
+    let movements = vec![
+        movement(1, 1, 1, dec!(10.0), dec!(1000.0)),
+        // Recorded after the buy of the same day, which is already at the new ratio
+        movement(2, 5, 1, dec!(2.0), dec!(50.0)),
+        movement(3, 5, SPLIT_ACTION_ID, dec!(4.0), dec!(0.0)),
+    ];
+
+    let gains = calculate_gains(&movements, CostBasisMethod::Average, None);
+
+    assert_eq!(gains[0].quantity, dec!(42.0));
+    assert_eq!(gains[0].cost_basis, dec!(1050.0));
+}
+
+/// Two lots in portfolio 1, 15 shares moved to portfolio 2 on day 5 and 5 of them sold
+fn transfer_between_portfolios() -> Vec<Movement> {
+    let in_portfolio = |portfolio_id: i64, movement: Movement| Movement {
+        portfolio_id: Some(portfolio_id),
+        ..movement
+    };
+    vec![
+        in_portfolio(1, movement(1, 1, 1, dec!(10.0), dec!(100.0))),
+        in_portfolio(1, movement(2, 2, 1, dec!(10.0), dec!(200.0))),
+        in_portfolio(
+            2,
+            movement(4, 5, TRANSFER_IN_ACTION_ID, dec!(15.0), dec!(0.0)),
+        ),
+        in_portfolio(
+            1,
+            movement(3, 5, TRANSFER_OUT_ACTION_ID, dec!(15.0), dec!(0.0)),
+        ),
+        in_portfolio(2, movement(5, 6, 2, dec!(5.0), dec!(150.0))),
+    ]
+}
+
+#[test]
+fn test_transfer_carries_lots_to_other_portfolio() {
+    let movements = transfer_between_portfolios();
+
+    let source = calculate_portfolio_gains(&movements, CostBasisMethod::Fifo, None, 1);
+    assert_eq!(source[0].quantity, dec!(5.0));
+    assert_eq!(source[0].cost_basis, dec!(100.0));
+    assert!(source[0].sales.is_empty());
+
+    let target = calculate_portfolio_gains(&movements, CostBasisMethod::Fifo, None, 2);
+    // The oldest lot arrives first and is sold first
+    assert_eq!(target[0].sales[0].cost, dec!(50.0));
+    assert_eq!(target[0].realized_gain, dec!(100.0));
+    assert_eq!(target[0].quantity, dec!(10.0));
+    assert_eq!(target[0].cost_basis, dec!(150.0));
+    assert_eq!(
+        target[0].open_lots[0].date,
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+    );
+}
+
+#[test]
+fn test_transfer_does_not_change_total_gains() {
+    let movements = transfer_between_portfolios();
+    let without_transfer: Vec<Movement> = movements
+        .iter()
+        .filter(|m| m.action_id == Some(1) || m.action_id == Some(2))
+        .cloned()
+        .collect();
+
+    let gains = calculate_gains(&movements, CostBasisMethod::Fifo, None);
+    let expected = calculate_gains(&without_transfer, CostBasisMethod::Fifo, None);
+
+    assert_eq!(gains.len(), 1);
+    assert_eq!(gains[0].realized_gain, expected[0].realized_gain);
+    assert_eq!(gains[0].cost_basis, expected[0].cost_basis);
+    assert_eq!(gains[0].quantity, dec!(15.0));
+}
+
+#[test]
+fn test_parse_cost_basis_method() {
+    assert_eq!(
+        "LIFO".parse::<CostBasisMethod>().unwrap(),
+        CostBasisMethod::Lifo
+    );
+    assert_eq!(
+        "average".parse::<CostBasisMethod>().unwrap(),
+        CostBasisMethod::Average
+    );
+    assert!("hifo".parse::<CostBasisMethod>().is_err());
+}
@@ -0,0 +1,167 @@
+mod test_helpers;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::action_types::{
+    create_action_type, delete_action_type, update_action_type, ActionTypeRequest,
+};
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::cost_basis::CostBasisMethod;
+use portfoliodb_rust::services::{CashLedgerService, CostBasisCalculator, PortfolioCalculator};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn request(name: &str, effect: &str) -> ActionTypeRequest {
+    ActionTypeRequest {
+        name: name.to_string(),
+        effect: effect.to_string(),
+    }
+}
+
+fn day(d: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(2024, 1, d)
+}
+
+fn movement(action_id: i64, date: u32, quantity: Option<Decimal>, amount: Decimal) -> Movement {
+    Movement {
+        id: 0,
+        date: day(date),
+        action_id: Some(action_id),
+        investment_id: Some(1),
+        quantity,
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_action_types_are_rejected() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let repo = repos.action_types.clone();
+
+    for request in [
+        request("", "cash_only"),
+        request("Fee", "costs_money"),
+        request("Spin-off", "split"),
+    ] {
+        let err = create_action_type(State(repo.clone()), Json(request))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidInput(_)), "{:?}", err);
+    }
+
+    let err = update_action_type(
+        State(repo.clone()),
+        Path(1),
+        Json(request("Purchase", "increases_quantity")),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::Conflict(_)));
+
+    let err = delete_action_type(State(repo.clone()), Path(2))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::Conflict(_)));
+
+    let err = update_action_type(State(repo), Path(99), Json(request("Fee", "cash_only")))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        AppError::NotFound {
+            entity: "ActionType",
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_custom_action_types_are_calculated_by_effect() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Test".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let Json(bonus) = create_action_type(
+        State(repos.action_types.clone()),
+        Json(request("Bonus shares", "increases_quantity")),
+    )
+    .await
+    .unwrap();
+    let Json(fee) = create_action_type(
+        State(repos.action_types.clone()),
+        Json(request("Custody fee", "cash_only")),
+    )
+    .await
+    .unwrap();
+    repos
+        .movements
+        .create_many(&[
+            movement(1, 1, Some(dec!(10.0)), dec!(100.0)),
+            movement(bonus.id, 2, Some(dec!(1.0)), dec!(0.0)),
+            movement(fee.id, 3, None, dec!(-4.0)),
+        ])
+        .await
+        .unwrap();
+    repos
+        .investment_prices
+        .create(&InvestmentPrice {
+            date: day(3),
+            investment_id: Some(1),
+            price: Some(dec!(12.0)),
+            source: None,
+            currency: None,
+            original_price: None,
+        })
+        .await
+        .unwrap();
+
+    let developments =
+        PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone())
+            .with_action_types(repos.action_types.clone())
+            .calculate_developments(None, None)
+            .await
+            .unwrap();
+    let last = developments.last().unwrap();
+    assert_eq!(last.quantity, dec!(11.0));
+    assert_eq!(last.value, dec!(132.0));
+
+    let gains = CostBasisCalculator::new(repos.movements.clone())
+        .with_action_types(repos.action_types.clone())
+        .calculate_gains(CostBasisMethod::Fifo, None)
+        .await
+        .unwrap();
+    assert_eq!(gains[0].quantity, dec!(11.0));
+    assert_eq!(gains[0].cost_basis, dec!(100.0));
+
+    let balance = CashLedgerService::new(repos.cash_movements.clone(), repos.movements.clone())
+        .with_action_types(repos.action_types.clone())
+        .calculate_balance(None, None, None)
+        .await
+        .unwrap();
+    let balances: Vec<Decimal> = balance.iter().map(|b| b.balance).collect();
+    assert_eq!(balances, [dec!(-100.0), dec!(-100.0), dec!(-104.0)]);
+}
@@ -0,0 +1,244 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::dashboard::{
+    build_dashboard, watchlist_quote, DashboardPeriod, WatchlistQuote,
+};
+use portfoliodb_rust::services::portfolio_calculator::{GrowthPoint, GrowthSeries};
+use portfoliodb_rust::services::{CashLedgerService, DashboardService, PortfolioCalculator};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn point(month: u32, day: u32, value: f64, growth: f64) -> GrowthPoint {
+    GrowthPoint {
+        date: NaiveDate::from_ymd_opt(2024, month, day).unwrap(),
+        value,
+        growth,
+    }
+}
+
+fn growth_series() -> GrowthSeries {
+    let mut investments = BTreeMap::new();
+    // Gains 10% on the last day
+    investments.insert(
+        1,
+        vec![
+            point(1, 1, 100.0, 1.0),
+            point(1, 31, 100.0, 1.0),
+            point(2, 1, 110.0, 1.1),
+        ],
+    );
+    // Loses 10% on the last day
+    investments.insert(
+        2,
+        vec![
+            point(1, 25, 50.0, 1.0),
+            point(1, 31, 60.0, 1.2),
+            point(2, 1, 54.0, 1.08),
+        ],
+    );
+    // Sold before the last day
+    investments.insert(3, vec![point(1, 1, 20.0, 1.0), point(1, 31, 0.0, 1.5)]);
+    // Gains 5% on the last day
+    investments.insert(4, vec![point(1, 31, 10.0, 1.0), point(2, 1, 10.5, 1.05)]);
+
+    GrowthSeries {
+        total: vec![
+            point(1, 1, 100.0, 1.0),
+            point(1, 25, 150.0, 1.2),
+            point(1, 31, 160.0, 1.28),
+            point(2, 1, 170.0, 1.36),
+        ],
+        investments,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn test_dashboard_changes() {
+    let dashboard = build_dashboard(&growth_series(), dec!(25.0), DashboardPeriod::Day, 5);
+
+    assert_eq!(dashboard.date, NaiveDate::from_ymd_opt(2024, 2, 1));
+    assert_eq!(dashboard.total_value, 170.0);
+    assert_eq!(dashboard.cash_balance, dec!(25.0));
+
+    let changes: Vec<(DashboardPeriod, f64)> = dashboard
+        .changes
+        .iter()
+        .map(|c| (c.period, c.value_change))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            (DashboardPeriod::Day, 10.0),
+            (DashboardPeriod::Week, 20.0),
+            (DashboardPeriod::Month, 70.0),
+            // The history is shorter than a year
+            (DashboardPeriod::Year, 170.0),
+        ]
+    );
+    for (change, twr) in dashboard
+        .changes
+        .iter()
+        .zip([0.0625, 1.36 / 1.2 - 1.0, 0.36, 0.36])
+    {
+        assert_close(change.twr, twr);
+    }
+}
+
+#[test]
+fn test_dashboard_top_movers() {
+    let dashboard = build_dashboard(&growth_series(), dec!(0.0), DashboardPeriod::Day, 5);
+
+    let gainers: Vec<i64> = dashboard.top_gainers.iter().map(|m| m.investment).collect();
+    let losers: Vec<i64> = dashboard.top_losers.iter().map(|m| m.investment).collect();
+    assert_eq!(gainers, vec![1, 4]);
+    assert_eq!(losers, vec![2]);
+    assert_close(dashboard.top_losers[0].twr, -0.1);
+
+    let dashboard = build_dashboard(&growth_series(), dec!(0.0), DashboardPeriod::Day, 1);
+    assert_eq!(dashboard.top_gainers.len(), 1);
+    assert_eq!(dashboard.top_gainers[0].investment, 1);
+}
+
+#[test]
+fn test_empty_dashboard() {
+    let dashboard = build_dashboard(
+        &GrowthSeries::default(),
+        dec!(10.0),
+        DashboardPeriod::Week,
+        5,
+    );
+
+    assert_eq!(dashboard.date, None);
+    assert_eq!(dashboard.total_value, 0.0);
+    assert!(dashboard.changes.is_empty());
+    assert_eq!(dashboard.cash_balance, dec!(10.0));
+}
+
+fn investment(name: &str, watchlist: bool) -> Investment {
+    Investment {
+        id: 0,
+        name: Some(name.to_string()),
+        isin: None,
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    }
+}
+
+fn price(investment_id: i64, month: u32, day: u32, price: Decimal) -> InvestmentPrice {
+    InvestmentPrice {
+        date: NaiveDate::from_ymd_opt(2024, month, day),
+        investment_id: Some(investment_id),
+        price: Some(price),
+        source: None,
+        currency: None,
+        original_price: None,
+    }
+}
+
+#[test]
+fn test_watchlist_quote() {
+    let watched = Investment {
+        id: 7,
+        ..investment("Nvidia", true)
+    };
+    // Out of order on purpose
+    let prices = vec![price(7, 2, 1, dec!(110.0)), price(7, 1, 31, dec!(100.0))];
+
+    let quote = watchlist_quote(&watched, &prices);
+    assert_eq!(quote.date, NaiveDate::from_ymd_opt(2024, 2, 1));
+    assert_eq!(quote.price, Some(dec!(110.0)));
+    assert_close(quote.change.unwrap(), 0.1);
+
+    assert_eq!(
+        watchlist_quote(&watched, &[]),
+        WatchlistQuote {
+            investment: 7,
+            name: Some("Nvidia".to_string()),
+            date: None,
+            price: None,
+            change: None,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_dashboard_shows_watchlist_apart_from_holdings() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let held = repos
+        .investments
+        .create(&investment("Apple", false))
+        .await
+        .unwrap();
+    let watched = repos
+        .investments
+        .create(&investment("Nvidia", true))
+        .await
+        .unwrap();
+    repos
+        .movements
+        .create(&Movement {
+            id: 0,
+            date: NaiveDate::from_ymd_opt(2024, 1, 31),
+            action_id: Some(1),
+            investment_id: Some(held),
+            quantity: Some(dec!(10.0)),
+            amount: Some(dec!(1000.0)),
+            fee: None,
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        })
+        .await
+        .unwrap();
+    for p in [
+        price(held, 2, 1, dec!(105.0)),
+        price(watched, 2, 1, dec!(500.0)),
+        price(watched, 2, 2, dec!(550.0)),
+    ] {
+        repos.investment_prices.create(&p).await.unwrap();
+    }
+
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    let cash_ledger = Arc::new(CashLedgerService::new(
+        repos.cash_movements.clone(),
+        repos.movements.clone(),
+    ));
+    let service = DashboardService::new(calculator, cash_ledger)
+        .with_watchlist(repos.investments.clone(), repos.investment_prices.clone());
+
+    let dashboard = service.dashboard(DashboardPeriod::Day, 5).await.unwrap();
+
+    // The watchlist prices neither add value nor a later valuation date
+    assert_eq!(dashboard.date, NaiveDate::from_ymd_opt(2024, 2, 1));
+    assert_close(dashboard.total_value, 1050.0);
+    assert_eq!(dashboard.watchlist.len(), 1);
+    assert_eq!(dashboard.watchlist[0].investment, watched);
+    assert_eq!(dashboard.watchlist[0].price, Some(dec!(550.0)));
+    assert_close(dashboard.watchlist[0].change.unwrap(), 0.1);
+}
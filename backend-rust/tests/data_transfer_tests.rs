@@ -0,0 +1,281 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::{
+    CashMovement, ImportMode, Investment, InvestmentPrice, Movement, Portfolio, Settings,
+};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::DataTransferService;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+async fn setup() -> (Repositories, DataTransferService) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let service = DataTransferService::new(repos.clone());
+    (repos, service)
+}
+
+/// Create one portfolio, investment, movement, cash movement and price
+async fn seed(repos: &Repositories) -> (i64, i64) {
+    let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+    let portfolio_id = repos
+        .portfolios
+        .create(&Portfolio {
+            id: 0,
+            name: "Retirement".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("World ETF".to_string()),
+            isin: Some("IE00B4L5Y983".to_string()),
+            shortname: Some("IWDA".to_string()),
+            ticker_symbol: Some("IWDA.AS".to_string()),
+            quote_provider: Some("yahoo".to_string()),
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    repos
+        .movements
+        .create(&Movement {
+            id: 0,
+            date: Some(date),
+            action_id: Some(1),
+            investment_id: Some(investment_id),
+            quantity: Some(dec!(10.0)),
+            amount: Some(dec!(800.0)),
+            fee: Some(dec!(1.0)),
+            tax: None,
+            portfolio_id: Some(portfolio_id),
+            external_id: None,
+        })
+        .await
+        .unwrap();
+    repos
+        .cash_movements
+        .create(&CashMovement {
+            id: 0,
+            date,
+            action_id: 4,
+            amount: dec!(1000.0),
+            portfolio_id: Some(portfolio_id),
+            description: Some("Initial deposit".to_string()),
+        })
+        .await
+        .unwrap();
+    repos
+        .investment_prices
+        .upsert(&InvestmentPrice {
+            date: Some(date),
+            investment_id: Some(investment_id),
+            price: Some(dec!(80.0)),
+            source: Some("yahoo".to_string()),
+            currency: Some("USD".to_string()),
+            original_price: Some(dec!(88.0)),
+        })
+        .await
+        .unwrap();
+
+    (portfolio_id, investment_id)
+}
+
+#[tokio::test]
+async fn test_export_contains_all_data() {
+    let (repos, service) = setup().await;
+    seed(&repos).await;
+
+    let export = service.export().await.unwrap();
+
+    assert_eq!(export.version, 1);
+    assert_eq!(export.settings.unwrap().base_currency, "EUR");
+    assert_eq!(export.action_types.len(), 9);
+    assert_eq!(export.portfolios.len(), 1);
+    assert_eq!(export.investments.len(), 1);
+    assert_eq!(export.movements.len(), 1);
+    assert_eq!(export.cash_movements.len(), 1);
+    assert_eq!(export.prices.len(), 1);
+    assert_eq!(export.prices[0].original_price, Some(dec!(88.0)));
+}
+
+#[tokio::test]
+async fn test_replace_import_restores_export() {
+    let (repos, service) = setup().await;
+    let (portfolio_id, investment_id) = seed(&repos).await;
+    let mut export = service.export().await.unwrap();
+    export.settings = Some(Settings {
+        id: 1,
+        base_currency: "USD".to_string(),
+        cost_basis_method: "average".to_string(),
+        benchmark_investment_id: None,
+        benchmark_ticker: None,
+        webhook_urls: Default::default(),
+        webhook_secret: None,
+        price_source_priority: Default::default(),
+        value_decimals: None,
+        price_decimals: None,
+        quantity_decimals: None,
+        rounding_mode: "half_even".to_string(),
+        reject_negative_holdings: false,
+    });
+
+    // Restore into a different database, which already contains other data
+    let (target_repos, target) = setup().await;
+    target_repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Removed".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    let json = serde_json::to_string(&export).unwrap();
+    let summary = target
+        .import(&serde_json::from_str(&json).unwrap(), ImportMode::Replace)
+        .await
+        .unwrap();
+
+    assert_eq!(summary.investments, 1);
+    assert_eq!(summary.movements, 1);
+    assert_eq!(summary.prices, 1);
+
+    let investments = target_repos.investments.find_all().await.unwrap();
+    assert_eq!(investments.len(), 1);
+    assert_eq!(investments[0].id, investment_id);
+    let movements = target_repos.movements.find_all().await.unwrap();
+    assert_eq!(movements[0].investment_id, Some(investment_id));
+    assert_eq!(movements[0].portfolio_id, Some(portfolio_id));
+    let settings = target_repos.settings.get().await.unwrap().unwrap();
+    assert_eq!(settings.base_currency, "USD");
+    assert_eq!(settings.cost_basis_method, "average");
+}
+
+#[tokio::test]
+async fn test_merge_import_matches_by_isin_and_name() {
+    let (repos, service) = setup().await;
+    let (portfolio_id, investment_id) = seed(&repos).await;
+    let mut export = service.export().await.unwrap();
+    export.settings = Some(Settings {
+        id: 1,
+        base_currency: "USD".to_string(),
+        cost_basis_method: "fifo".to_string(),
+        benchmark_investment_id: None,
+        benchmark_ticker: None,
+        webhook_urls: Default::default(),
+        webhook_secret: None,
+        price_source_priority: Default::default(),
+        value_decimals: None,
+        price_decimals: None,
+        quantity_decimals: None,
+        rounding_mode: "half_even".to_string(),
+        reject_negative_holdings: false,
+    });
+
+    // Merging into the same database reuses the investment and portfolio
+    let summary = service.import(&export, ImportMode::Merge).await.unwrap();
+
+    assert_eq!(summary.investments, 0);
+    assert_eq!(summary.portfolios, 0);
+    assert_eq!(summary.movements, 1);
+    assert_eq!(summary.cash_movements, 1);
+
+    assert_eq!(repos.investments.find_all().await.unwrap().len(), 1);
+    let movements = repos.movements.find_all().await.unwrap();
+    assert_eq!(movements.len(), 2);
+    assert!(movements
+        .iter()
+        .all(|m| m.investment_id == Some(investment_id) && m.portfolio_id == Some(portfolio_id)));
+    // Same date, investment and source, so the price is updated in place
+    assert_eq!(
+        repos
+            .investment_prices
+            .find_all(None, None, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    // Settings are kept when merging
+    let settings = repos.settings.get().await.unwrap().unwrap();
+    assert_eq!(settings.base_currency, "EUR");
+}
+
+#[tokio::test]
+async fn test_merge_import_remaps_new_ids() {
+    let (repos, service) = setup().await;
+    seed(&repos).await;
+    let export = service.export().await.unwrap();
+
+    let (target_repos, target) = setup().await;
+    // Occupy the exported IDs with unrelated rows
+    let other_id = target_repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Other".to_string()),
+            isin: Some("US0378331005".to_string()),
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    let summary = target.import(&export, ImportMode::Merge).await.unwrap();
+    assert_eq!(summary.investments, 1);
+
+    let imported = target_repos
+        .investments
+        .find_all()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|i| i.isin.as_deref() == Some("IE00B4L5Y983"))
+        .unwrap();
+    assert_ne!(imported.id, other_id);
+    let movements = target_repos.movements.find_all().await.unwrap();
+    assert_eq!(movements[0].investment_id, Some(imported.id));
+}
+
+#[tokio::test]
+async fn test_import_rejects_unknown_version() {
+    let (_, service) = setup().await;
+    let mut export = service.export().await.unwrap();
+    export.version = 99;
+
+    let err = service
+        .import(&export, ImportMode::Merge)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+}
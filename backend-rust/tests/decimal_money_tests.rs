@@ -0,0 +1,145 @@
+mod test_helpers;
+
+use chrono::{Duration, NaiveDate};
+use portfoliodb_rust::handlers::movements::MovementResponse;
+use portfoliodb_rust::models::{CashMovement, Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::traits::{
+    InvestmentPriceRepository, InvestmentRepository, MovementRepository,
+};
+use portfoliodb_rust::repository::{
+    SqliteInvestmentPriceRepository, SqliteInvestmentRepository, SqliteMovementRepository,
+};
+use portfoliodb_rust::services::cash_ledger::{calculate_cash_balance, DEPOSIT_ACTION_ID};
+use portfoliodb_rust::services::cost_basis::{calculate_gains, CostBasisMethod};
+use portfoliodb_rust::services::PortfolioCalculator;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+/// Transactions repeated this often would accumulate rounding errors as `f64`
+const CHAIN_LENGTH: i64 = 1000;
+
+fn start() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()
+}
+
+fn movement(id: i64, action_id: i64, quantity: Decimal, amount: Decimal) -> Movement {
+    Movement {
+        id,
+        date: Some(start() + Duration::days(id)),
+        action_id: Some(action_id),
+        investment_id: Some(1),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: Some(dec!(0.01)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+#[test]
+fn test_cost_basis_of_long_chain_is_exact() {
+    // Buy 0.1 for 0.7 each day, sell 0.1 for 0.8 on all but the last, 0.01 fee each
+    let mut movements: Vec<Movement> = (1..=CHAIN_LENGTH)
+        .map(|id| movement(id, 1, dec!(0.1), dec!(0.7)))
+        .collect();
+    movements
+        .extend((1..CHAIN_LENGTH).map(|id| movement(CHAIN_LENGTH + id, 2, dec!(0.1), dec!(0.8))));
+
+    for method in [
+        CostBasisMethod::Fifo,
+        CostBasisMethod::Lifo,
+        CostBasisMethod::Average,
+    ] {
+        let gains = calculate_gains(&movements, method, None);
+
+        assert_eq!(gains[0].quantity, dec!(0.1));
+        assert_eq!(gains[0].cost_basis, dec!(0.71));
+        assert_eq!(gains[0].realized_gain, dec!(79.92));
+    }
+}
+
+#[test]
+fn test_cash_balance_of_long_chain_is_exact() {
+    let deposits: Vec<CashMovement> = (0..CHAIN_LENGTH)
+        .map(|i| CashMovement {
+            id: i,
+            date: start() + Duration::days(i),
+            action_id: DEPOSIT_ACTION_ID,
+            amount: dec!(0.1),
+            portfolio_id: None,
+            description: None,
+        })
+        .collect();
+
+    let balances = calculate_cash_balance(&deposits, &[], None, None);
+
+    assert_eq!(balances.last().unwrap().balance, dec!(100));
+}
+
+#[tokio::test]
+async fn test_developments_of_stored_chain_are_exact() {
+    let pool = setup_test_db().await;
+    SqliteInvestmentRepository::new(pool.clone())
+        .create(&Investment {
+            id: 1,
+            name: Some("Fund".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let movement_repo = Arc::new(SqliteMovementRepository::new(pool.clone()));
+    let price_repo = Arc::new(SqliteInvestmentPriceRepository::new(pool));
+
+    let buys: Vec<Movement> = (1..=CHAIN_LENGTH)
+        .map(|id| movement(id, 1, dec!(0.1), dec!(0.3)))
+        .collect();
+    movement_repo.create_many(&buys).await.unwrap();
+    price_repo
+        .upsert(&InvestmentPrice {
+            date: Some(start() + Duration::days(CHAIN_LENGTH)),
+            investment_id: Some(1),
+            price: Some(dec!(3.3)),
+            source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
+        })
+        .await
+        .unwrap();
+
+    // Decimals read back from SQLite's REAL columns are the ones written
+    let stored = movement_repo.find_by_id(1).await.unwrap().unwrap();
+    assert_eq!(stored.amount, Some(dec!(0.3)));
+    assert_eq!(stored.fee, Some(dec!(0.01)));
+
+    let calculator = PortfolioCalculator::new(movement_repo, price_repo);
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+    let latest = developments.last().unwrap();
+
+    assert_eq!(latest.quantity, dec!(100));
+    assert_eq!(latest.price, dec!(3.3));
+    assert_eq!(latest.value, dec!(330));
+}
+
+#[test]
+fn test_amounts_are_serialized_as_strings() {
+    let response = MovementResponse::from(movement(1, 1, dec!(0.1), dec!(105.75)));
+
+    let json = serde_json::to_value(&response).unwrap();
+
+    assert_eq!(json["quantity"], "0.1");
+    assert_eq!(json["amount"], "105.75");
+    assert_eq!(json["fee"], "0.01");
+}
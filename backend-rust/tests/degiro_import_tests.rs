@@ -0,0 +1,227 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::Investment;
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::import::{BrokerImportOptions, DegiroParser, StatementParser};
+use portfoliodb_rust::services::BrokerImportService;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+/// Layout with the currencies in unnamed columns after the values
+const DEGIRO_CSV: &str = "\
+Date,Time,Product,ISIN,Reference exchange,Venue,Quantity,Price,,Local value,,Value,,Exchange rate,Transaction and/or third,,Total,,Order ID
+28-04-2023,15:33,VANGUARD FTSE ALL-WORLD UCITS ETF,IE00BK5BQT80,EAM,XAMS,10,100.5000,EUR,-1005.00,EUR,-1005.00,EUR,,-2.00,EUR,-1007.00,EUR,3c5a8f9e-0000-0000-0000-000000000001
+02-05-2023,16:10,APPLE INC. - COMMON STOCK,US0378331005,NDQ,XNAS,-3,170.0000,USD,510.00,USD,463.64,EUR,1.1000,-0.50,EUR,463.14,EUR,3c5a8f9e-0000-0000-0000-000000000002
+";
+
+/// Layout since 2023 with the currency in the header and a separate AutoFX fee
+const DEGIRO_CSV_2023: &str = "\
+Date,Time,Product,ISIN,Reference exchange,Venue,Quantity,Price,,Local value,,Value EUR,Exchange rate,AutoFX Fee,Transaction and/or third party fees EUR,Total EUR,Order ID
+15-01-2024,09:05,APPLE INC. - COMMON STOCK,US0378331005,NDQ,XNAS,2,\"185,00\",USD,\"-370,00\",USD,\"-1.336,36\",\"1,1000\",\"-0,84\",\"-1,00\",\"-1.338,20\",abc
+";
+
+fn setup_service(repos: &Repositories) -> BrokerImportService {
+    BrokerImportService::new(
+        repos.investments.clone(),
+        repos.movements.clone(),
+        repos.portfolios.clone(),
+        repos.settings.clone(),
+    )
+}
+
+fn create_investments() -> BrokerImportOptions {
+    BrokerImportOptions {
+        portfolio_id: None,
+        create_investments: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_parse_degiro_export() {
+    let transactions = DegiroParser::new().parse(DEGIRO_CSV).unwrap();
+
+    assert_eq!(transactions.len(), 2);
+
+    let buy = &transactions[0];
+    assert_eq!(buy.date, NaiveDate::from_ymd_opt(2023, 4, 28).unwrap());
+    assert_eq!(buy.isin, "IE00BK5BQT80");
+    assert_eq!(buy.product, "VANGUARD FTSE ALL-WORLD UCITS ETF");
+    assert_eq!(buy.action_id, 1);
+    assert_eq!(buy.quantity, Some(dec!(10.0)));
+    assert_eq!(buy.amount, dec!(1005.0));
+    assert_eq!(buy.fee, Some(dec!(2.0)));
+    assert_eq!(buy.currency, "EUR");
+
+    // Sells have a negative quantity, the value is already converted to EUR
+    let sell = &transactions[1];
+    assert_eq!(sell.date, NaiveDate::from_ymd_opt(2023, 5, 2).unwrap());
+    assert_eq!(sell.action_id, 2);
+    assert_eq!(sell.quantity, Some(dec!(3.0)));
+    assert_eq!(sell.amount, dec!(463.64));
+    assert_eq!(sell.fee, Some(dec!(0.5)));
+    assert_eq!(sell.currency, "EUR");
+}
+
+#[test]
+fn test_parse_degiro_export_with_currency_in_header() {
+    let transactions = DegiroParser::new().parse(DEGIRO_CSV_2023).unwrap();
+
+    assert_eq!(transactions.len(), 1);
+    let buy = &transactions[0];
+    assert_eq!(buy.amount, dec!(1336.36));
+    // Transaction fee and AutoFX fee are combined
+    assert_eq!(buy.fee, Some(dec!(1.84)));
+    assert_eq!(buy.currency, "EUR");
+}
+
+#[test]
+fn test_parse_degiro_export_errors() {
+    let parser = DegiroParser::new();
+
+    let err = parser.parse("Datum,Produkt\n01-01-2024,Foo\n").unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(msg) if msg.contains("'date'")));
+
+    let invalid_date = DEGIRO_CSV.replace("28-04-2023", "2023-04-28");
+    let err = parser.parse(&invalid_date).unwrap_err();
+    assert!(
+        matches!(err, AppError::InvalidInput(ref msg) if msg.contains("Line 2") && msg.contains("2023-04-28")),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn test_parse_degiro_export_rejects_isin_typo() {
+    let typo = DEGIRO_CSV.replace("IE00BK5BQT80", "IE00BK5BQT08");
+    let err = DegiroParser::new().parse(&typo).unwrap_err();
+    assert!(
+        matches!(err, AppError::InvalidInput(ref msg) if msg.contains("Invalid ISIN 'IE00BK5BQT08'")),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_import_creates_missing_investments() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let existing_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Apple".to_string()),
+            isin: Some("US0378331005".to_string()),
+            shortname: Some("AAPL".to_string()),
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    let result = setup_service(&repos)
+        .import(&DegiroParser::new(), DEGIRO_CSV, create_investments())
+        .await
+        .unwrap();
+
+    assert_eq!(result.movements.len(), 2);
+    assert_eq!(result.created_investments.len(), 1);
+    let created = &result.created_investments[0];
+    assert_eq!(created.isin.as_deref(), Some("IE00BK5BQT80"));
+    assert_eq!(
+        created.name.as_deref(),
+        Some("VANGUARD FTSE ALL-WORLD UCITS ETF")
+    );
+
+    let movements = repos.movements.find_all().await.unwrap();
+    assert_eq!(movements.len(), 2);
+    let buy = movements.iter().find(|m| m.action_id == Some(1)).unwrap();
+    assert_eq!(buy.investment_id, Some(created.id));
+    assert_eq!(buy.amount, Some(dec!(1005.0)));
+    assert_eq!(buy.fee, Some(dec!(2.0)));
+    let sell = movements.iter().find(|m| m.action_id == Some(2)).unwrap();
+    assert_eq!(sell.investment_id, Some(existing_id));
+    assert_eq!(sell.quantity, Some(dec!(3.0)));
+}
+
+#[tokio::test]
+async fn test_import_rejects_other_account_currency() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let usd_account = DEGIRO_CSV_2023.replace("Value EUR", "Value USD");
+
+    let err = setup_service(&repos)
+        .import(&DegiroParser::new(), &usd_account, create_investments())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, AppError::InvalidInput(msg) if msg.contains("base currency is EUR")));
+    assert!(repos.investments.find_all().await.unwrap().is_empty());
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_import_rejects_unknown_portfolio() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let err = setup_service(&repos)
+        .import(
+            &DegiroParser::new(),
+            DEGIRO_CSV,
+            BrokerImportOptions {
+                portfolio_id: Some(42),
+                create_investments: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, AppError::InvalidInput(_)));
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_reimport_reports_duplicates() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let service = setup_service(&repos);
+    service
+        .import(&DegiroParser::new(), DEGIRO_CSV, create_investments())
+        .await
+        .unwrap();
+
+    let result = service
+        .import(
+            &DegiroParser::new(),
+            DEGIRO_CSV,
+            BrokerImportOptions {
+                reject_duplicates: true,
+                ..create_investments()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(result.movements.is_empty());
+    assert_eq!(result.duplicates.len(), 2);
+    assert_eq!(result.duplicates[0].isin, "IE00BK5BQT80");
+    assert_eq!(result.duplicates[0].existing_ids.len(), 1);
+    assert_eq!(repos.movements.find_all().await.unwrap().len(), 2);
+
+    // Without rejecting, duplicates are imported but still reported
+    let result = service
+        .import(&DegiroParser::new(), DEGIRO_CSV, create_investments())
+        .await
+        .unwrap();
+
+    assert_eq!(result.movements.len(), 2);
+    assert_eq!(result.duplicates.len(), 2);
+    assert_eq!(repos.movements.find_all().await.unwrap().len(), 4);
+}
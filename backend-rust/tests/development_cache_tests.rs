@@ -0,0 +1,170 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::{DevelopmentCache, PortfolioCalculator};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+fn price(investment_id: i64, date: NaiveDate, price: Decimal) -> InvestmentPrice {
+    InvestmentPrice {
+        date: Some(date),
+        investment_id: Some(investment_id),
+        price: Some(price),
+        source: Some("test".to_string()),
+        currency: None,
+        original_price: None,
+    }
+}
+
+/// Plain repositories, repositories reporting to the cache and an investment with one buy
+async fn setup() -> (Repositories, Repositories, DevelopmentCache, i64) {
+    let plain = Repositories::sqlite(setup_test_db().await);
+    let cache = DevelopmentCache::new();
+    let notifying = plain.clone().with_change_listener(Arc::new(cache.clone()));
+
+    let investment_id = notifying
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Cached".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    notifying
+        .movements
+        .create(&Movement {
+            id: 0,
+            date: Some(day(1)),
+            action_id: Some(1),
+            investment_id: Some(investment_id),
+            quantity: Some(dec!(10.0)),
+            amount: Some(dec!(100.0)),
+            fee: None,
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        })
+        .await
+        .unwrap();
+
+    (plain, notifying, cache, investment_id)
+}
+
+fn calculator(repos: &Repositories, cache: &DevelopmentCache) -> PortfolioCalculator {
+    PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone())
+        .with_cache(cache.clone())
+}
+
+#[tokio::test]
+async fn test_developments_are_cached_until_data_changes() {
+    let (plain, notifying, cache, investment_id) = setup().await;
+    let calculator = calculator(&notifying, &cache);
+
+    assert_eq!(
+        calculator
+            .calculate_developments(None, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Writes that bypass the notifying repositories are not seen
+    plain
+        .investment_prices
+        .create(&price(investment_id, day(2), dec!(11.0)))
+        .await
+        .unwrap();
+    assert_eq!(
+        calculator
+            .calculate_developments(None, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Writes through them invalidate the cache
+    notifying
+        .investment_prices
+        .create(&price(investment_id, day(3), dec!(12.0)))
+        .await
+        .unwrap();
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+    assert_eq!(developments.len(), 3);
+    assert_eq!(developments[2].value, dec!(120.0));
+}
+
+#[tokio::test]
+async fn test_date_ranges_are_cached_separately() {
+    let (_, notifying, cache, investment_id) = setup().await;
+    notifying
+        .investment_prices
+        .create(&price(investment_id, day(5), dec!(12.0)))
+        .await
+        .unwrap();
+    let calculator = calculator(&notifying, &cache);
+
+    let all = calculator.calculate_developments(None, None).await.unwrap();
+    let later = calculator
+        .calculate_developments(Some(day(2)), None)
+        .await
+        .unwrap();
+
+    assert_eq!(all.len(), 2);
+    assert_eq!(later.len(), 1);
+}
+
+#[tokio::test]
+async fn test_recalculate_refreshes_cache() {
+    let (plain, notifying, cache, investment_id) = setup().await;
+    let calculator = calculator(&notifying, &cache);
+    calculator.calculate_developments(None, None).await.unwrap();
+
+    plain
+        .investment_prices
+        .create(&price(investment_id, day(2), dec!(11.0)))
+        .await
+        .unwrap();
+    let result = calculator.recalculate().await.unwrap();
+
+    assert_eq!(result.developments, 2);
+    assert_eq!(
+        calculator
+            .calculate_developments(None, None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn test_results_from_before_an_invalidation_are_not_stored() {
+    let cache = DevelopmentCache::new();
+    let generation = cache.generation();
+    cache.invalidate();
+
+    cache.insert((None, None, None), generation, Arc::new(Vec::new()));
+
+    assert!(cache.get(&(None, None, None)).is_none());
+}
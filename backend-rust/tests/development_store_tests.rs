@@ -0,0 +1,302 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Development, Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::notifying::{ChangeListener, DataChange};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::development_store::PendingChanges;
+use portfoliodb_rust::services::{PendingDevelopments, PortfolioCalculator};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+fn price(investment_id: i64, date: NaiveDate, price: Decimal) -> InvestmentPrice {
+    InvestmentPrice {
+        date: Some(date),
+        investment_id: Some(investment_id),
+        price: Some(price),
+        source: Some("test".to_string()),
+        currency: None,
+        original_price: None,
+    }
+}
+
+fn buy(investment_id: i64, date: NaiveDate, quantity: Decimal, amount: Decimal) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(1),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn development(investment: i64, date: NaiveDate, price: Decimal, quantity: Decimal) -> Development {
+    Development {
+        investment,
+        date,
+        price,
+        quantity,
+        value: price * quantity,
+        source: None,
+    }
+}
+
+/// Development valued with a quote of the test source
+fn quoted(investment: i64, date: NaiveDate, price: Decimal, quantity: Decimal) -> Development {
+    Development {
+        source: Some("test".to_string()),
+        ..development(investment, date, price, quantity)
+    }
+}
+
+/// Plain repositories, repositories reporting to the tracker and two investments with a buy
+async fn setup() -> (Repositories, Repositories, PendingDevelopments, Vec<i64>) {
+    let plain = Repositories::sqlite(setup_test_db().await);
+    let pending = PendingDevelopments::new();
+    let notifying = plain
+        .clone()
+        .with_change_listener(Arc::new(pending.clone()));
+
+    let mut investment_ids = Vec::new();
+    for name in ["First", "Second"] {
+        let id = notifying
+            .investments
+            .create(&Investment {
+                id: 0,
+                name: Some(name.to_string()),
+                isin: None,
+                shortname: None,
+                ticker_symbol: None,
+                quote_provider: None,
+                currency: None,
+                asset_class: None,
+                watchlist: false,
+                partial_exemption: None,
+                quote_fetch_enabled: true,
+                quote_fetch_interval_days: None,
+                archived: false,
+            })
+            .await
+            .unwrap();
+        notifying
+            .movements
+            .create(&buy(id, day(1), dec!(10.0), dec!(100.0)))
+            .await
+            .unwrap();
+        investment_ids.push(id);
+    }
+
+    (plain, notifying, pending, investment_ids)
+}
+
+fn calculator(repos: &Repositories, pending: &PendingDevelopments) -> PortfolioCalculator {
+    PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone())
+        .with_development_store(repos.developments.clone(), pending.clone())
+}
+
+#[tokio::test]
+async fn test_replace_developments_from_date() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    repos
+        .developments
+        .replace(
+            None,
+            None,
+            &[
+                development(1, day(1), dec!(10.0), dec!(1.0)),
+                development(1, day(2), dec!(11.0), dec!(1.0)),
+                development(2, day(2), dec!(20.0), dec!(1.0)),
+            ],
+        )
+        .await
+        .unwrap();
+
+    // Only the second investment from the given date on is replaced
+    repos
+        .developments
+        .replace(
+            Some(2),
+            Some(day(2)),
+            &[development(2, day(3), dec!(21.0), dec!(2.0))],
+        )
+        .await
+        .unwrap();
+
+    let developments = repos.developments.find_all(None, None).await.unwrap();
+    assert_eq!(
+        developments,
+        vec![
+            development(1, day(1), dec!(10.0), dec!(1.0)),
+            development(1, day(2), dec!(11.0), dec!(1.0)),
+            development(2, day(3), dec!(21.0), dec!(2.0)),
+        ]
+    );
+
+    let range = repos
+        .developments
+        .find_all(Some(day(2)), Some(day(2)))
+        .await
+        .unwrap();
+    assert_eq!(range, vec![development(1, day(2), dec!(11.0), dec!(1.0))]);
+}
+
+#[tokio::test]
+async fn test_developments_are_stored_on_first_read() {
+    let (_, notifying, pending, _) = setup().await;
+    let calculator = calculator(&notifying, &pending);
+
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+
+    assert_eq!(developments.len(), 2);
+    assert_eq!(
+        notifying.developments.find_all(None, None).await.unwrap(),
+        developments
+    );
+    assert!(pending.take().is_empty());
+}
+
+#[tokio::test]
+async fn test_new_data_recalculates_only_affected_investment_from_its_date() {
+    let (plain, notifying, pending, ids) = setup().await;
+    let calculator = calculator(&notifying, &pending);
+    calculator.calculate_developments(None, None).await.unwrap();
+
+    // A write bypassing the tracker shows which investments were recalculated
+    plain
+        .investment_prices
+        .create(&price(ids[0], day(2), dec!(99.0)))
+        .await
+        .unwrap();
+    notifying
+        .investment_prices
+        .create(&price(ids[1], day(3), dec!(12.0)))
+        .await
+        .unwrap();
+    notifying
+        .movements
+        .create(&buy(ids[1], day(4), dec!(5.0), dec!(65.0)))
+        .await
+        .unwrap();
+
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+
+    assert_eq!(
+        developments,
+        vec![
+            development(ids[0], day(1), dec!(10.0), dec!(10.0)),
+            development(ids[1], day(1), dec!(10.0), dec!(10.0)),
+            quoted(ids[1], day(3), dec!(12.0), dec!(10.0)),
+            development(ids[1], day(4), dec!(13.0), dec!(15.0)),
+        ]
+    );
+
+    // After a recalculation the stored developments match a full calculation again
+    calculator.recalculate().await.unwrap();
+    let full = PortfolioCalculator::new(plain.movements.clone(), plain.investment_prices.clone())
+        .calculate_developments(None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        calculator.calculate_developments(None, None).await.unwrap(),
+        full
+    );
+    assert_eq!(full.len(), 5);
+}
+
+#[tokio::test]
+async fn test_stored_developments_use_earlier_history() {
+    let (_, notifying, pending, ids) = setup().await;
+    let calculator = calculator(&notifying, &pending);
+    calculator.calculate_developments(None, None).await.unwrap();
+
+    // The quantity bought before the changed date is still counted
+    notifying
+        .investment_prices
+        .create(&price(ids[0], day(5), dec!(15.0)))
+        .await
+        .unwrap();
+
+    let developments = calculator
+        .calculate_developments(Some(day(5)), None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        developments,
+        vec![quoted(ids[0], day(5), dec!(15.0), dec!(10.0))]
+    );
+}
+
+#[tokio::test]
+async fn test_portfolio_developments_are_not_stored() {
+    let (_, notifying, pending, _) = setup().await;
+    let calculator = calculator(&notifying, &pending);
+
+    let developments = calculator
+        .calculate_portfolio_developments(Some(1), None, None)
+        .await
+        .unwrap();
+
+    assert!(developments.is_empty());
+    assert!(notifying
+        .developments
+        .find_all(None, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_pending_changes_keep_earliest_date() {
+    let mut changes = PendingChanges::default();
+    let change = |investment_id, from_date| DataChange {
+        investment_id,
+        from_date,
+    };
+
+    changes.add(change(Some(1), Some(day(5))));
+    changes.add(change(Some(1), Some(day(3))));
+    changes.add(change(Some(1), Some(day(4))));
+    changes.add(change(Some(2), Some(day(2))));
+    changes.add(change(Some(2), None));
+
+    assert!(!changes.all);
+    assert_eq!(
+        changes.investments,
+        HashMap::from([(1, Some(day(3))), (2, None)])
+    );
+
+    changes.add(change(None, Some(day(1))));
+    assert!(changes.all);
+    assert!(changes.investments.is_empty());
+}
+
+#[test]
+fn test_new_tracker_reports_everything() {
+    let pending = PendingDevelopments::new();
+    assert!(pending.take().all);
+
+    pending.data_changed(DataChange {
+        investment_id: Some(1),
+        from_date: Some(day(1)),
+    });
+    let changes = pending.take();
+    assert!(!changes.all);
+    assert!(pending.take().is_empty());
+
+    // Changes that could not be applied are kept
+    pending.restore(changes.clone());
+    assert_eq!(pending.take(), changes);
+}
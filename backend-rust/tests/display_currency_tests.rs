@@ -0,0 +1,175 @@
+mod test_helpers;
+
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, Uri};
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::developments::{list_developments, DevelopmentQuery};
+use portfoliodb_rust::models::{FxRate, Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::CalculatorState;
+use portfoliodb_rust::services::{CurrencyConverter, PortfolioCalculator};
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+/// Buys 10 shares for 1000 EUR on day 1, quoted at 110 EUR on day 2, with cached EUR to
+/// USD rates of 1.1 and 1.2
+async fn setup() -> (Repositories, PortfolioCalculator) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("World ETF".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: Some("USD".to_string()),
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    repos
+        .movements
+        .create(&Movement {
+            id: 0,
+            date: Some(day(1)),
+            action_id: Some(1),
+            investment_id: Some(investment_id),
+            quantity: Some(dec!(10)),
+            amount: Some(dec!(1000)),
+            fee: None,
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        })
+        .await
+        .unwrap();
+    repos
+        .investment_prices
+        .create(&InvestmentPrice {
+            date: Some(day(2)),
+            investment_id: Some(investment_id),
+            price: Some(dec!(110)),
+            source: None,
+            currency: None,
+            original_price: None,
+        })
+        .await
+        .unwrap();
+    for (d, rate) in [(1, 1.1), (2, 1.2)] {
+        repos
+            .fx_rates
+            .upsert(&FxRate {
+                date: day(d),
+                from_currency: "EUR".to_string(),
+                to_currency: "USD".to_string(),
+                rate,
+            })
+            .await
+            .unwrap();
+    }
+
+    let calculator =
+        PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone())
+            .with_price_sources(repos.settings.clone(), repos.investments.clone())
+            .with_currency_converter(CurrencyConverter::new().with_cache(repos.fx_rates.clone()));
+    (repos, calculator)
+}
+
+#[tokio::test]
+async fn test_developments_in_display_currency() {
+    let (_, calculator) = setup().await;
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+
+    let converted = calculator
+        .convert_developments(developments.clone(), Some("USD"))
+        .await
+        .unwrap();
+
+    assert_eq!(converted.len(), 2);
+    assert_eq!(converted[0].price, dec!(110));
+    assert_eq!(converted[0].value, dec!(1100));
+    assert_eq!(converted[0].quantity, dec!(10));
+    assert_eq!(converted[1].price, dec!(132));
+    assert_eq!(converted[1].value, dec!(1320));
+
+    // The base currency needs no conversion
+    let unchanged = calculator
+        .convert_developments(developments.clone(), Some("EUR"))
+        .await
+        .unwrap();
+    assert_eq!(unchanged, developments);
+}
+
+#[tokio::test]
+async fn test_total_developments_in_display_currency() {
+    let (_, calculator) = setup().await;
+    let totals = calculator
+        .calculate_total_developments(None, None, None, None)
+        .await
+        .unwrap();
+
+    let converted = calculator
+        .convert_totals(totals, Some("USD"))
+        .await
+        .unwrap();
+
+    let values: Vec<_> = converted.iter().map(|t| t.value).collect();
+    assert_eq!(values, vec![dec!(1100), dec!(1320)]);
+}
+
+#[tokio::test]
+async fn test_time_weighted_return_in_display_currency() {
+    let (_, calculator) = setup().await;
+
+    let in_base = calculator
+        .calculate_time_weighted_return(None, None, None, None)
+        .await
+        .unwrap();
+    let in_usd = calculator
+        .calculate_time_weighted_return(None, None, None, Some("USD"))
+        .await
+        .unwrap();
+
+    // 10% gain on the price, plus the dollar rising from 1.1 to 1.2 per euro
+    assert!((in_base.total - 0.1).abs() < 1e-9);
+    assert!((in_usd.total - 0.2).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_invalid_display_currency_is_rejected() {
+    let (repos, calculator) = setup().await;
+    let state = CalculatorState {
+        calculator: Arc::new(calculator),
+        tag_repo: repos.tags.clone(),
+    };
+    let uri: Uri = "/api/developments?currency=DOLLAR".parse().unwrap();
+    let query: Query<DevelopmentQuery> = Query::try_from_uri(&uri).unwrap();
+
+    let result = list_developments(State(state), query, HeaderMap::new()).await;
+
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_display_currency_without_converter_is_rejected() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let calculator =
+        PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone());
+
+    let result = calculator.convert_totals(Vec::new(), Some("USD")).await;
+
+    assert!(matches!(result, Err(AppError::InvalidInput(_))));
+}
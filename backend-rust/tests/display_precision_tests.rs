@@ -0,0 +1,225 @@
+mod test_helpers;
+
+use axum::body::to_bytes;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, HeaderValue, Uri};
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::movements::{list_movements, MovementQuery, MovementResponse};
+use portfoliodb_rust::handlers::ndjson::NDJSON_CONTENT_TYPE;
+use portfoliodb_rust::handlers::{update_settings, UpdateSettingsRequest};
+use portfoliodb_rust::models::Movement;
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::MovementState;
+use portfoliodb_rust::services::display_precision::RoundingMode;
+use portfoliodb_rust::services::DisplayPrecision;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn movement() -> Movement {
+    Movement {
+        id: 1,
+        date: NaiveDate::from_ymd_opt(2024, 1, 2),
+        action_id: Some(1),
+        investment_id: None,
+        quantity: Some(dec!(1.234567)),
+        amount: Some(dec!(10.125)),
+        fee: Some(dec!(1.5)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn broker_precision(rounding_mode: RoundingMode) -> DisplayPrecision {
+    DisplayPrecision {
+        value_decimals: Some(2),
+        price_decimals: Some(4),
+        quantity_decimals: Some(4),
+        rounding_mode,
+    }
+}
+
+fn request() -> UpdateSettingsRequest {
+    UpdateSettingsRequest {
+        base_currency: None,
+        cost_basis_method: None,
+        benchmark_investment_id: None,
+        benchmark_ticker: None,
+        webhook_urls: None,
+        webhook_secret: None,
+        price_source_priority: None,
+        value_decimals: None,
+        price_decimals: None,
+        quantity_decimals: None,
+        rounding_mode: None,
+        reject_negative_holdings: None,
+    }
+}
+
+#[test]
+fn test_rounding_modes() {
+    let cases = [
+        (RoundingMode::HalfEven, dec!(2.12), dec!(-2.12)),
+        (RoundingMode::HalfUp, dec!(2.13), dec!(-2.13)),
+        (RoundingMode::HalfDown, dec!(2.12), dec!(-2.12)),
+        (RoundingMode::Up, dec!(2.13), dec!(-2.13)),
+        (RoundingMode::Down, dec!(2.12), dec!(-2.12)),
+        (RoundingMode::Ceiling, dec!(2.13), dec!(-2.12)),
+        (RoundingMode::Floor, dec!(2.12), dec!(-2.13)),
+    ];
+    for (mode, positive, negative) in cases {
+        let precision = broker_precision(mode);
+        assert_eq!(precision.round(dec!(2.125), Some(2)), positive, "{}", mode);
+        assert_eq!(precision.round(dec!(-2.125), Some(2)), negative, "{}", mode);
+    }
+}
+
+#[test]
+fn test_round_pads_to_all_places() {
+    let precision = broker_precision(RoundingMode::HalfEven);
+
+    assert_eq!(precision.round(dec!(10.5), Some(2)).to_string(), "10.50");
+    assert_eq!(precision.round(dec!(10.5), None).to_string(), "10.5");
+}
+
+#[test]
+fn test_rounding_mode_parsing() {
+    assert_eq!(
+        "HALF_UP".parse::<RoundingMode>().unwrap(),
+        RoundingMode::HalfUp
+    );
+    assert!(matches!(
+        "bankers".parse::<RoundingMode>(),
+        Err(AppError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn test_responses_are_rounded_in_scope() {
+    let response = MovementResponse::from(movement());
+
+    let unrounded = serde_json::to_value(&response).unwrap();
+    let rounded = broker_precision(RoundingMode::HalfEven)
+        .sync_scope(|| serde_json::to_value(&response).unwrap());
+
+    assert_eq!(
+        unrounded["amount"],
+        serde_json::to_value(dec!(10.125)).unwrap()
+    );
+    assert_eq!(
+        rounded["amount"],
+        serde_json::to_value(dec!(10.12)).unwrap()
+    );
+    assert_eq!(rounded["fee"], serde_json::to_value(dec!(1.50)).unwrap());
+    assert_eq!(
+        rounded["quantity"],
+        serde_json::to_value(dec!(1.2346)).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_ndjson_rows_keep_the_precision_of_the_request() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    repos.movements.create(&movement()).await.unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::ACCEPT,
+        HeaderValue::from_static(NDJSON_CONTENT_TYPE),
+    );
+    let uri: Uri = "/api/movements".parse().unwrap();
+    let query: Query<MovementQuery> = Query::try_from_uri(&uri).unwrap();
+
+    // The rows are serialized while the body is read, after the scope has ended
+    let response = broker_precision(RoundingMode::HalfUp)
+        .scope(list_movements(
+            State(MovementState {
+                movement_repo: repos.movements.clone(),
+                investment_repo: repos.investments.clone(),
+                action_type_repo: repos.action_types.clone(),
+                settings_repo: repos.settings.clone(),
+            }),
+            query,
+            headers,
+        ))
+        .await
+        .unwrap();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let row: serde_json::Value = serde_json::from_slice(bytes.trim_ascii_end()).unwrap();
+
+    assert_eq!(row["amount"], serde_json::to_value(dec!(10.13)).unwrap());
+}
+
+#[tokio::test]
+async fn test_update_display_precision_settings() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let response = update_settings(
+        State(repos.settings.clone()),
+        Json(UpdateSettingsRequest {
+            value_decimals: Some(Some(2)),
+            quantity_decimals: Some(Some(4)),
+            rounding_mode: Some("Half_Up".to_string()),
+            reject_negative_holdings: None,
+            ..request()
+        }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(response.0.value_decimals, Some(2));
+    assert_eq!(response.0.price_decimals, None);
+    assert_eq!(response.0.quantity_decimals, Some(4));
+    assert_eq!(response.0.rounding_mode, "half_up");
+
+    let settings = repos.settings.get().await.unwrap().unwrap();
+    assert_eq!(
+        DisplayPrecision::from_settings(&settings),
+        DisplayPrecision {
+            value_decimals: Some(2),
+            price_decimals: None,
+            quantity_decimals: Some(4),
+            rounding_mode: RoundingMode::HalfUp,
+        }
+    );
+
+    // Null resets a precision to all places
+    let response = update_settings(
+        State(repos.settings.clone()),
+        Json(UpdateSettingsRequest {
+            value_decimals: Some(None),
+            ..request()
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.0.value_decimals, None);
+    assert_eq!(response.0.quantity_decimals, Some(4));
+}
+
+#[tokio::test]
+async fn test_update_display_precision_rejects_invalid_values() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let result = update_settings(
+        State(repos.settings.clone()),
+        Json(UpdateSettingsRequest {
+            price_decimals: Some(Some(-1)),
+            ..request()
+        }),
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let result = update_settings(
+        State(repos.settings.clone()),
+        Json(UpdateSettingsRequest {
+            rounding_mode: Some("bankers".to_string()),
+            reject_negative_holdings: None,
+            ..request()
+        }),
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::InvalidInput(_))));
+}
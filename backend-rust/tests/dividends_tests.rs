@@ -0,0 +1,108 @@
+use chrono::NaiveDate;
+use portfoliodb_rust::models::Movement;
+use portfoliodb_rust::services::cost_basis::CostBasisMethod;
+use portfoliodb_rust::services::dividends::summarize_dividends;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn movement(
+    id: i64,
+    action_id: i64,
+    investment_id: i64,
+    date: NaiveDate,
+    quantity: Decimal,
+    amount: Decimal,
+) -> Movement {
+    Movement {
+        id,
+        date: Some(date),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn test_dividend_summary_monthly_and_yearly() {
+    let movements = vec![
+        movement(1, 1, 1, date(2023, 1, 10), dec!(10.0), dec!(1000.0)),
+        movement(2, 3, 1, date(2023, 6, 15), dec!(0.0), dec!(20.0)),
+        movement(3, 3, 1, date(2024, 3, 15), dec!(0.0), dec!(25.0)),
+        movement(4, 3, 1, date(2024, 3, 28), dec!(0.0), dec!(5.0)),
+        movement(5, 3, 1, date(2024, 9, 15), dec!(0.0), dec!(30.0)),
+    ];
+
+    let summary = summarize_dividends(&movements, 2024, CostBasisMethod::Fifo);
+
+    assert_eq!(summary.year, 2024);
+    assert_eq!(summary.total, dec!(60.0));
+    assert_eq!(summary.months.len(), 12);
+    assert_eq!(summary.months[2].month, 3);
+    assert_eq!(summary.months[2].amount, dec!(30.0));
+    assert_eq!(summary.months[8].amount, dec!(30.0));
+
+    assert_eq!(summary.years.len(), 2);
+    assert_eq!(summary.years[0].year, 2023);
+    assert_eq!(summary.years[0].amount, dec!(20.0));
+    assert_eq!(summary.years[1].amount, dec!(60.0));
+
+    assert_eq!(summary.investments.len(), 1);
+    assert_eq!(summary.investments[0].cost_basis, dec!(1000.0));
+    assert_eq!(summary.investments[0].yield_on_cost, Some(0.06));
+    assert_eq!(summary.yield_on_cost, Some(0.06));
+}
+
+#[test]
+fn test_dividend_yield_uses_cost_basis_at_year_end() {
+    let movements = vec![
+        movement(1, 1, 1, date(2024, 1, 1), dec!(10.0), dec!(1000.0)),
+        movement(2, 1, 1, date(2024, 6, 1), dec!(10.0), dec!(1500.0)),
+        // Sold after the reported year, must not affect its cost basis
+        movement(3, 2, 1, date(2025, 2, 1), dec!(10.0), dec!(1400.0)),
+        movement(4, 3, 1, date(2024, 12, 1), dec!(0.0), dec!(50.0)),
+        movement(5, 3, 2, date(2024, 12, 1), dec!(0.0), dec!(10.0)),
+    ];
+
+    let summary = summarize_dividends(&movements, 2024, CostBasisMethod::Fifo);
+
+    let first = &summary.investments[0];
+    assert_eq!(first.cost_basis, dec!(2500.0));
+    assert_eq!(first.yield_on_cost, Some(0.02));
+
+    // Payouts without any purchase have no yield-on-cost
+    let second = &summary.investments[1];
+    assert_eq!(second.total, dec!(10.0));
+    assert_eq!(second.yield_on_cost, None);
+
+    assert_eq!(summary.total, dec!(60.0));
+    assert_eq!(summary.yield_on_cost, Some(60.0 / 2500.0));
+}
+
+#[test]
+fn test_dividend_summary_without_payouts() {
+    let movements = vec![movement(
+        1,
+        1,
+        1,
+        date(2024, 1, 1),
+        dec!(10.0),
+        dec!(1000.0),
+    )];
+
+    let summary = summarize_dividends(&movements, 2024, CostBasisMethod::Average);
+
+    assert_eq!(summary.total, dec!(0.0));
+    assert!(summary.investments.is_empty());
+    assert!(summary.years.is_empty());
+    assert_eq!(summary.yield_on_cost, None);
+    assert!(summary.months.iter().all(|m| m.amount == dec!(0.0)));
+}
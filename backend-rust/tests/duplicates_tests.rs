@@ -0,0 +1,222 @@
+mod test_helpers;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::movements::{
+    check_duplicate_movements, create_movements_bulk, BulkCreateQuery, CreateMovementRequest,
+    DuplicateCheckQuery,
+};
+use portfoliodb_rust::models::{Investment, Movement};
+use portfoliodb_rust::repository::traits::InvestmentRepository;
+use portfoliodb_rust::repository::{Repositories, SqliteInvestmentRepository};
+use portfoliodb_rust::routes::MovementState;
+use portfoliodb_rust::services::duplicates::{DuplicateMovement, DEFAULT_DUPLICATE_TOLERANCE};
+use portfoliodb_rust::services::DuplicateDetector;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn date(day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(2024, 3, day)
+}
+
+fn movement(
+    investment_id: i64,
+    day: u32,
+    action_id: i64,
+    quantity: Decimal,
+    amount: Decimal,
+) -> Movement {
+    Movement {
+        id: 0,
+        date: date(day),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn request(
+    investment_id: i64,
+    day: u32,
+    quantity: Decimal,
+    amount: Decimal,
+) -> CreateMovementRequest {
+    CreateMovementRequest {
+        date: date(day),
+        action_id: Some(1),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: Some(dec!(1.0)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+/// Movement endpoints with one investment, bought on March 1 and sold on March 5
+async fn setup() -> (MovementState, i64, Vec<i64>) {
+    let pool = setup_test_db().await;
+    let investment_id = SqliteInvestmentRepository::new(pool.clone())
+        .create(&Investment {
+            id: 0,
+            name: Some("Test".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let repos = Repositories::sqlite(pool);
+    let ids = repos
+        .movements
+        .create_many(&[
+            movement(investment_id, 1, 1, dec!(10.0), dec!(1000.0)),
+            movement(investment_id, 5, 2, dec!(-4.0), dec!(420.0)),
+        ])
+        .await
+        .unwrap();
+    let state = MovementState {
+        movement_repo: repos.movements,
+        investment_repo: repos.investments,
+        action_type_repo: repos.action_types,
+        settings_repo: repos.settings.clone(),
+    };
+    (state, investment_id, ids)
+}
+
+#[tokio::test]
+async fn test_find_duplicates() {
+    let (state, investment_id, ids) = setup().await;
+    let detector = DuplicateDetector::new(state.movement_repo);
+
+    let duplicates = detector
+        .find_duplicates(
+            &[
+                // Rounded amount
+                movement(investment_id, 1, 1, dec!(10.0), dec!(1000.004)),
+                // Different action on the same day
+                movement(investment_id, 1, 2, dec!(10.0), dec!(1000.0)),
+                // Different amount
+                movement(investment_id, 1, 1, dec!(10.0), dec!(1001.0)),
+                // Sell recorded with a positive quantity
+                movement(investment_id, 5, 2, dec!(4.0), dec!(420.0)),
+                // Other investment
+                movement(investment_id + 1, 5, 2, dec!(4.0), dec!(420.0)),
+            ],
+            DEFAULT_DUPLICATE_TOLERANCE,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        duplicates,
+        vec![
+            DuplicateMovement {
+                index: 0,
+                existing_ids: vec![ids[0]],
+            },
+            DuplicateMovement {
+                index: 3,
+                existing_ids: vec![ids[1]],
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_find_duplicates_without_dates() {
+    let (state, investment_id, _) = setup().await;
+    let mut undated = movement(investment_id, 1, 1, dec!(10.0), dec!(1000.0));
+    undated.date = None;
+
+    let duplicates = DuplicateDetector::new(state.movement_repo)
+        .find_duplicates(&[undated], DEFAULT_DUPLICATE_TOLERANCE)
+        .await
+        .unwrap();
+
+    assert!(duplicates.is_empty());
+}
+
+#[tokio::test]
+async fn test_check_duplicates_endpoint() {
+    let (state, investment_id, ids) = setup().await;
+
+    let Json(duplicates) = check_duplicate_movements(
+        State(state.clone()),
+        Query(DuplicateCheckQuery {
+            tolerance: Some(dec!(5.0)),
+        }),
+        Json(vec![
+            request(investment_id, 2, dec!(10.0), dec!(1000.0)),
+            request(investment_id, 1, dec!(10.0), dec!(1003.0)),
+        ]),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].index, 1);
+    assert_eq!(duplicates[0].existing_ids, vec![ids[0]]);
+
+    let err = check_duplicate_movements(
+        State(state),
+        Query(DuplicateCheckQuery {
+            tolerance: Some(dec!(-1.0)),
+        }),
+        Json(vec![]),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+}
+
+#[tokio::test]
+async fn test_bulk_create_rejects_duplicates() {
+    let (state, investment_id, _) = setup().await;
+    let repo = state.movement_repo.clone();
+
+    let err = create_movements_bulk(
+        State(state.clone()),
+        Query(BulkCreateQuery {
+            reject_duplicates: true,
+        }),
+        Json(vec![
+            request(investment_id, 2, dec!(3.0), dec!(300.0)),
+            request(investment_id, 1, dec!(10.0), dec!(1000.0)),
+        ]),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(matches!(err, AppError::Conflict(_)));
+    assert_eq!(repo.find_all().await.unwrap().len(), 2);
+
+    // Duplicates are allowed unless rejected
+    let Json(response) = create_movements_bulk(
+        State(state),
+        Query(BulkCreateQuery::default()),
+        Json(vec![request(investment_id, 1, dec!(10.0), dec!(1000.0))]),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(response.ids.len(), 1);
+    assert_eq!(repo.find_all().await.unwrap().len(), 3);
+}
@@ -0,0 +1,286 @@
+mod test_helpers;
+
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use portfoliodb_rust::config::{EmailConfig, SmtpTls};
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::{Investment, Movement, TriggeredAlert};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::dashboard::{Dashboard, DashboardPeriod, PeriodChange};
+use portfoliodb_rust::services::email::{alert_email, weekly_summary_email, Email};
+use portfoliodb_rust::services::reports::{build_weekly_summary, WeeklySummary};
+use portfoliodb_rust::services::webhooks::PriceAlertNotification;
+use portfoliodb_rust::services::{
+    CashLedgerService, DashboardService, EmailNotifier, PortfolioCalculator, ReportService,
+    WeeklySummaryScheduler,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::{Arc, Mutex};
+use test_helpers::setup_test_db;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// Start an SMTP server on a free local port that accepts every message and records
+/// the message data
+async fn start_smtp_server() -> (u16, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+
+    let received = messages.clone();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let messages = received.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+
+                let mut data: Option<String> = None;
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let reply = match data.as_mut() {
+                        Some(_) if line == "." => {
+                            messages.lock().unwrap().push(data.take().unwrap());
+                            "250 Queued"
+                        }
+                        Some(message) => {
+                            message.push_str(&line);
+                            message.push('\n');
+                            continue;
+                        }
+                        None => match line.to_uppercase().as_str() {
+                            "DATA" => {
+                                data = Some(String::new());
+                                "354 End data with <CR><LF>.<CR><LF>"
+                            }
+                            "QUIT" => "221 Bye",
+                            _ => "250 OK",
+                        },
+                    };
+                    writer
+                        .write_all(format!("{}\r\n", reply).as_bytes())
+                        .await
+                        .unwrap();
+                    if reply.starts_with("221") {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    (port, messages)
+}
+
+fn email_config(port: u16) -> EmailConfig {
+    EmailConfig {
+        smtp_host: "127.0.0.1".to_string(),
+        smtp_port: port,
+        smtp_tls: SmtpTls::None,
+        smtp_username: None,
+        smtp_password: None,
+        from: "PortfolioDB <portfolio@example.com>".to_string(),
+        to: vec!["me@example.com".to_string(), "you@example.com".to_string()],
+        weekly_summary_schedule: None,
+    }
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn payout(id: i64, date: NaiveDate, amount: Decimal) -> Movement {
+    Movement {
+        id,
+        date: Some(date),
+        action_id: Some(3),
+        investment_id: Some(1),
+        quantity: None,
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+#[test]
+fn test_alert_email() {
+    let notification = PriceAlertNotification {
+        alert: TriggeredAlert {
+            id: 1,
+            alert_id: 2,
+            investment_id: 3,
+            triggered_at: Utc.with_ymd_and_hms(2024, 3, 15, 18, 5, 0).unwrap(),
+            price_date: date(2024, 3, 15),
+            price: 95.5,
+            threshold: 100.0,
+            direction: "below".to_string(),
+        },
+        investment_name: Some("Apple".to_string()),
+    };
+
+    let email = alert_email(&notification);
+
+    assert_eq!(email.subject, "Price alert: Apple fell below 100.00");
+    assert!(email.body.contains("Price: 95.50 on 2024-03-15"));
+    assert!(email.body.contains("Triggered at: 2024-03-15 18:05 UTC"));
+}
+
+#[test]
+fn test_build_weekly_summary() {
+    let dashboard = Dashboard {
+        date: Some(date(2024, 3, 15)),
+        total_value: 10500.0,
+        changes: vec![PeriodChange {
+            period: DashboardPeriod::Week,
+            start_date: date(2024, 3, 8),
+            value_change: 500.0,
+            twr: 0.0125,
+        }],
+        top_gainers: Vec::new(),
+        top_losers: Vec::new(),
+        cash_balance: dec!(250.0),
+        watchlist: Vec::new(),
+    };
+    let movements = vec![
+        // The day before the week is not included, the last day is
+        payout(1, date(2024, 3, 8), dec!(100.0)),
+        payout(2, date(2024, 3, 9), dec!(12.5)),
+        payout(3, date(2024, 3, 15), dec!(7.5)),
+        payout(4, date(2024, 3, 16), dec!(100.0)),
+    ];
+
+    let summary =
+        build_weekly_summary(&dashboard, &movements, "EUR".to_string(), date(2024, 3, 18));
+
+    assert_eq!(
+        summary,
+        WeeklySummary {
+            start_date: date(2024, 3, 8),
+            end_date: date(2024, 3, 15),
+            base_currency: "EUR".to_string(),
+            total_value: 10500.0,
+            value_change: 500.0,
+            twr: 0.0125,
+            dividends: dec!(20.0),
+            cash_balance: dec!(250.0),
+        }
+    );
+
+    let email = weekly_summary_email(&summary);
+    assert_eq!(
+        email.subject,
+        "Weekly portfolio summary: 10500.00 EUR (+1.25%)"
+    );
+    assert!(email.body.contains("week ending 2024-03-15"));
+    assert!(email
+        .body
+        .contains("Change:             +500.00 EUR (+1.25%)"));
+    assert!(email.body.contains("Dividends received: 20.00 EUR"));
+}
+
+#[tokio::test]
+async fn test_send_email() {
+    let (port, messages) = start_smtp_server().await;
+    let notifier = EmailNotifier::new(&email_config(port)).unwrap();
+
+    notifier
+        .send(&Email {
+            subject: "Hello".to_string(),
+            body: "Portfolio is up.\n".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let messages = messages.lock().unwrap();
+    assert_eq!(messages.len(), 1);
+    let message = &messages[0];
+    assert!(message.contains("From: PortfolioDB <portfolio@example.com>"));
+    assert!(message.contains("To: me@example.com, you@example.com"));
+    assert!(message.contains("Subject: Hello"));
+    assert!(message.contains("Portfolio is up."));
+}
+
+#[tokio::test]
+async fn test_send_email_without_server_fails() {
+    // Bind and drop a listener to get a port nobody listens on
+    let port = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let notifier = EmailNotifier::new(&email_config(port)).unwrap();
+
+    let err = notifier
+        .send(&Email {
+            subject: "Hello".to_string(),
+            body: String::new(),
+        })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, AppError::ExternalApi(_)));
+}
+
+#[tokio::test]
+async fn test_scheduler_emails_weekly_summary() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Apple".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let yesterday = Utc::now().date_naive() - Duration::days(1);
+    repos
+        .movements
+        .create(&Movement {
+            investment_id: Some(investment_id),
+            ..payout(0, yesterday, dec!(12.5))
+        })
+        .await
+        .unwrap();
+
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    let cash_ledger = Arc::new(CashLedgerService::new(
+        repos.cash_movements.clone(),
+        repos.movements.clone(),
+    ));
+    let reports = Arc::new(ReportService::new(
+        Arc::new(DashboardService::new(calculator, cash_ledger)),
+        repos.movements.clone(),
+        repos.settings.clone(),
+    ));
+
+    let (port, messages) = start_smtp_server().await;
+    let notifier = EmailNotifier::new(&email_config(port)).unwrap();
+    WeeklySummaryScheduler::new("0 0 8 * * Mon", reports, notifier)
+        .unwrap()
+        .run_once()
+        .await
+        .unwrap();
+
+    let messages = messages.lock().unwrap();
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].contains("Subject: Weekly portfolio summary"));
+    assert!(messages[0].contains("Dividends received: 12.50 EUR"));
+}
@@ -0,0 +1,176 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::tax::germany::{base_rate, TaxCategory};
+use portfoliodb_rust::services::{GermanTaxService, PortfolioCalculator};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn investment(name: &str, asset_class: &str, partial_exemption: Option<Decimal>) -> Investment {
+    Investment {
+        id: 0,
+        name: Some(name.to_string()),
+        isin: None,
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: None,
+        asset_class: Some(asset_class.to_string()),
+        watchlist: false,
+        partial_exemption,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    }
+}
+
+fn movement(
+    date: NaiveDate,
+    action_id: i64,
+    investment_id: i64,
+    quantity: Decimal,
+    amount: Decimal,
+) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn service(repos: &Repositories) -> GermanTaxService {
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    GermanTaxService::new(
+        repos.investments.clone(),
+        repos.movements.clone(),
+        calculator,
+    )
+    .with_action_types(repos.action_types.clone())
+}
+
+/// A stock sold with a gain and a loss in 2024, an equity ETF with 30% Teilfreistellung
+/// rising from 100 to 110 in 2024 and a coin sold with a gain
+async fn setup() -> (Repositories, i64, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let stock = repos
+        .investments
+        .create(&investment("Stock", "stock", None))
+        .await
+        .unwrap();
+    let fund = repos
+        .investments
+        .create(&investment("ETF", "etf", Some(dec!(30))))
+        .await
+        .unwrap();
+    let coin = repos
+        .investments
+        .create(&investment("Coin", "crypto", None))
+        .await
+        .unwrap();
+
+    for m in [
+        movement(date(2023, 3, 1), 1, stock, dec!(10), dec!(1000)),
+        Movement {
+            tax: Some(dec!(25)),
+            ..movement(date(2024, 5, 1), 2, stock, dec!(5), dec!(600))
+        },
+        movement(date(2024, 6, 1), 3, stock, dec!(0), dec!(20)),
+        movement(date(2024, 8, 1), 2, stock, dec!(5), dec!(400)),
+        movement(date(2023, 1, 10), 1, fund, dec!(10), dec!(1000)),
+        movement(date(2024, 7, 1), 3, fund, dec!(0), dec!(5)),
+        movement(date(2024, 2, 1), 1, coin, dec!(1), dec!(100)),
+        movement(date(2024, 9, 1), 2, coin, dec!(1), dec!(300)),
+    ] {
+        repos.movements.create(&m).await.unwrap();
+    }
+    for (day, price) in [
+        (date(2023, 12, 29), dec!(100)),
+        (date(2024, 12, 30), dec!(110)),
+    ] {
+        repos
+            .investment_prices
+            .create(&InvestmentPrice {
+                date: Some(day),
+                investment_id: Some(fund),
+                price: Some(price),
+                source: Some("test".to_string()),
+                currency: None,
+                original_price: None,
+            })
+            .await
+            .unwrap();
+    }
+    (repos, stock, fund)
+}
+
+#[tokio::test]
+async fn test_german_tax_report() {
+    let (repos, stock, fund) = setup().await;
+
+    let report = service(&repos).report(Some(2024)).await.unwrap();
+
+    assert_eq!(report.year, 2024);
+    assert_eq!(report.base_rate, Some(dec!(0.0229)));
+    // Crypto is no capital income
+    assert_eq!(report.investments.len(), 2);
+
+    let shares = &report.investments[0];
+    assert_eq!(shares.investment, stock);
+    assert_eq!(shares.category, TaxCategory::Stock);
+    assert_eq!(shares.dividends, dec!(20));
+    // The tax withheld on the sale does not lower the gain
+    assert_eq!(shares.realized_gains, dec!(100));
+    assert_eq!(shares.realized_losses, dec!(100));
+    assert_eq!(shares.vorabpauschale, dec!(0));
+    assert_eq!(shares.taxable_income, dec!(20));
+
+    // Basisertrag 100 * 2.29% * 70% * 10 = 16.03, less the distribution of 5
+    let etf = &report.investments[1];
+    assert_eq!(etf.investment, fund);
+    assert_eq!(etf.category, TaxCategory::Fund);
+    assert_eq!(etf.partial_exemption, dec!(30));
+    assert_eq!(etf.dividends, dec!(5));
+    assert_eq!(etf.vorabpauschale, dec!(11.03));
+    assert_eq!(etf.taxable_income, dec!(11.221));
+
+    assert_eq!(report.capital_income, dec!(131.221));
+    assert_eq!(report.stock_gains, dec!(100));
+    assert_eq!(report.stock_losses, dec!(100));
+    assert_eq!(report.losses, dec!(0));
+    assert_eq!(report.taxes_withheld, dec!(25));
+}
+
+#[tokio::test]
+async fn test_german_tax_report_of_year_without_income() {
+    let (repos, _, _) = setup().await;
+
+    let report = service(&repos).report(Some(2022)).await.unwrap();
+
+    assert!(report.investments.is_empty());
+    assert_eq!(report.capital_income, dec!(0));
+}
+
+#[test]
+fn test_base_rate() {
+    assert_eq!(base_rate(2023), Some(dec!(0.0255)));
+    assert_eq!(base_rate(2021), Some(dec!(-0.0045)));
+    assert_eq!(base_rate(1990), None);
+}
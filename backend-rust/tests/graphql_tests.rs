@@ -0,0 +1,186 @@
+mod test_helpers;
+
+use async_graphql::Request;
+use portfoliodb_rust::handlers::graphql::{build_schema, PortfolioSchema};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::MovementState;
+use portfoliodb_rust::services::{InvestmentSummaryService, PortfolioCalculator};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+async fn schema() -> PortfolioSchema {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    let summary = Arc::new(
+        InvestmentSummaryService::new(
+            repos.investments.clone(),
+            repos.movements.clone(),
+            calculator,
+        )
+        .with_action_types(repos.action_types.clone()),
+    );
+    let movements = MovementState {
+        movement_repo: repos.movements,
+        investment_repo: repos.investments.clone(),
+        action_type_repo: repos.action_types,
+        settings_repo: repos.settings.clone(),
+    };
+    build_schema(
+        repos.investments,
+        movements,
+        repos.investment_prices,
+        summary,
+    )
+}
+
+/// Data of a request expected to succeed
+async fn execute(schema: &PortfolioSchema, query: &str) -> Value {
+    let response = schema.execute(Request::new(query)).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    response.data.into_json().unwrap()
+}
+
+/// Extensions of the only error of a request expected to fail
+async fn error(schema: &PortfolioSchema, query: &str) -> Value {
+    let response = schema.execute(Request::new(query)).await;
+    assert_eq!(response.errors.len(), 1, "{:?}", response.errors);
+    serde_json::to_value(&response.errors[0].extensions).unwrap()
+}
+
+#[tokio::test]
+async fn test_investment_with_movements_prices_and_summary() {
+    let schema = schema().await;
+
+    let created = execute(
+        &schema,
+        r#"mutation { createInvestment(input: { name: "World ETF", isin: " ie00b4l5y983 " }) { id isin watchlist } }"#,
+    )
+    .await;
+    assert_eq!(created["createInvestment"]["isin"], "IE00B4L5Y983");
+    assert_eq!(created["createInvestment"]["watchlist"], false);
+    let id = created["createInvestment"]["id"].as_i64().unwrap();
+
+    execute(
+        &schema,
+        &format!(
+            r#"mutation {{
+                createMovement(input: {{ date: "2024-01-10", actionId: 1, investmentId: {id}, quantity: 10, amount: 1000, fee: 5 }}) {{ id }}
+                upsertPrice(input: {{ date: "2024-02-01", investmentId: {id}, price: 120 }}) {{ source }}
+            }}"#
+        ),
+    )
+    .await;
+
+    let data = execute(
+        &schema,
+        &format!(
+            "{{ investment(id: {id}) {{ name movements {{ quantity amount }} prices {{ date price source }} summary {{ quantity price marketValue totalInvested }} }} }}"
+        ),
+    )
+    .await;
+    let investment = &data["investment"];
+    assert_eq!(investment["name"], "World ETF");
+    assert_eq!(
+        investment["movements"],
+        json!([{ "quantity": "10", "amount": "1000" }])
+    );
+    assert_eq!(
+        investment["prices"],
+        json!([{ "date": "2024-02-01", "price": "120", "source": "manual" }])
+    );
+    assert_eq!(investment["summary"]["quantity"], "10");
+    assert_eq!(investment["summary"]["marketValue"], "1200");
+    assert_eq!(investment["summary"]["totalInvested"], "1000");
+
+    let data = execute(&schema, "{ investments(watchlist: true) { id } }").await;
+    assert_eq!(data["investments"], json!([]));
+}
+
+#[tokio::test]
+async fn test_update_and_delete() {
+    let schema = schema().await;
+    let created = execute(
+        &schema,
+        r#"mutation { createInvestment(input: { name: "Old", currency: "USD" }) { id } }"#,
+    )
+    .await;
+    let id = created["createInvestment"]["id"].as_i64().unwrap();
+
+    // Omitted currency is kept
+    let updated = execute(
+        &schema,
+        &format!(
+            r#"mutation {{ updateInvestment(id: {id}, input: {{ name: "New" }}) {{ name currency }} }}"#
+        ),
+    )
+    .await;
+    assert_eq!(
+        updated["updateInvestment"],
+        json!({ "name": "New", "currency": "USD" })
+    );
+
+    let movement = execute(
+        &schema,
+        &format!(
+            r#"mutation {{ createMovement(input: {{ date: "2024-01-10", actionId: 1, investmentId: {id}, quantity: 1, amount: 50 }}) {{ id }} }}"#
+        ),
+    )
+    .await;
+    let movement_id = movement["createMovement"]["id"].as_i64().unwrap();
+
+    // Dependents need cascade
+    let extensions = error(
+        &schema,
+        &format!("mutation {{ deleteInvestment(id: {id}) {{ movements }} }}"),
+    )
+    .await;
+    assert_eq!(extensions["status"], 409);
+
+    let deleted = execute(
+        &schema,
+        &format!(
+            "mutation {{ deleteMovement(id: {movement_id}) deleteInvestment(id: {id}) {{ movements prices }} }}"
+        ),
+    )
+    .await;
+    assert_eq!(deleted["deleteMovement"], true);
+    assert_eq!(
+        deleted["deleteInvestment"],
+        json!({ "movements": 0, "prices": 0 })
+    );
+
+    let data = execute(&schema, &format!("{{ investment(id: {id}) {{ id }} }}")).await;
+    assert_eq!(data["investment"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_errors_carry_status_and_fields() {
+    let schema = schema().await;
+
+    let extensions = error(
+        &schema,
+        r#"mutation { createInvestment(input: { isin: "XX123", currency: "XYZ" }) { id } }"#,
+    )
+    .await;
+    assert_eq!(extensions["status"], 422);
+    assert_eq!(extensions["code"], "VALIDATION_FAILED");
+    let fields: Vec<&str> = extensions["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["isin", "currency"]);
+
+    let extensions = error(
+        &schema,
+        r#"mutation { createInvestment(input: { quoteProvider: "unknown" }) { id } }"#,
+    )
+    .await;
+    assert_eq!(extensions["status"], 400);
+    assert_eq!(extensions["code"], "INVALID_PROVIDER");
+}
@@ -0,0 +1,133 @@
+mod test_helpers;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use portfoliodb_rust::db;
+use portfoliodb_rust::handlers::health_ready;
+use portfoliodb_rust::models::{Investment, QuoteFetchLog};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::HealthState;
+use portfoliodb_rust::services::{IntegrationStatusService, QuoteFetcherService};
+use sqlx::SqlitePool;
+use test_helpers::setup_test_db;
+
+fn state(repos: &Repositories) -> HealthState {
+    HealthState {
+        health_repo: repos.health.clone(),
+        fetch_log_repo: repos.quote_fetch_log.clone(),
+    }
+}
+
+fn fetch(minutes_ago: i64, success: bool) -> QuoteFetchLog {
+    QuoteFetchLog {
+        id: 0,
+        fetched_at: Utc::now() - Duration::minutes(minutes_ago),
+        investment_id: 1,
+        provider: Some("yahoo".to_string()),
+        success,
+        error: None,
+        quotes_stored: 1,
+    }
+}
+
+#[tokio::test]
+async fn test_ready_with_migrated_database() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let succeeded = fetch(30, true);
+    repos.quote_fetch_log.create(&succeeded).await.unwrap();
+    repos
+        .quote_fetch_log
+        .create(&fetch(10, false))
+        .await
+        .unwrap();
+
+    let (status, response) = health_ready(State(state(&repos))).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response.status, "ready");
+    assert_eq!(response.database, "ok");
+    let migrations = response.migrations.clone().unwrap();
+    assert_eq!(
+        migrations.applied_version,
+        Some(db::migrations::latest_version())
+    );
+    assert_eq!(response.last_quote_fetch, Some(succeeded.fetched_at));
+}
+
+#[tokio::test]
+async fn test_not_ready_with_pending_migration() {
+    let pool = setup_test_db().await;
+    sqlx::query("DELETE FROM _sqlx_migrations WHERE version = ?")
+        .bind(db::migrations::latest_version())
+        .execute(&pool)
+        .await
+        .unwrap();
+    let repos = Repositories::sqlite(pool);
+
+    let (status, response) = health_ready(State(state(&repos))).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.status, "not_ready");
+    assert_eq!(response.database, "ok");
+    assert!(response.error.is_some());
+}
+
+#[tokio::test]
+async fn test_not_ready_without_database() {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.close().await;
+    let repos = Repositories::sqlite(pool);
+
+    let (status, response) = health_ready(State(state(&repos))).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.database, "unavailable");
+    assert!(response.migrations.is_none());
+    assert!(response.last_quote_fetch.is_none());
+}
+
+/// Test that the providers in use are checked, and unknown or unanswering ones reported
+/// unreachable
+#[tokio::test]
+async fn test_integration_status() {
+    let repos = Repositories::in_memory();
+    for (provider, enabled) in [("yahoo,bogus", true), ("yahoo", true), ("justetf", false)] {
+        let investment = Investment {
+            id: 0,
+            name: Some(provider.to_string()),
+            isin: None,
+            shortname: None,
+            quote_provider: Some(provider.to_string()),
+            ticker_symbol: Some("TICKER".to_string()),
+            currency: None,
+            asset_class: None,
+            watchlist: true,
+            partial_exemption: None,
+            quote_fetch_enabled: enabled,
+            quote_fetch_interval_days: None,
+            archived: false,
+        };
+        repos.investments.create(&investment).await.unwrap();
+    }
+
+    let quote_fetcher = QuoteFetcherService::new(
+        repos.investments.clone(),
+        repos.investment_prices.clone(),
+        "EUR".to_string(),
+    );
+    let service = IntegrationStatusService::new(repos.investments.clone(), quote_fetcher)
+        .with_timeout(std::time::Duration::from_millis(1));
+    let statuses = service.check().await.unwrap();
+
+    let ids: Vec<&str> = statuses.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, vec!["bogus", "yahoo", "frankfurter"]);
+    assert_eq!(statuses[1].name, "Yahoo Finance");
+    assert_eq!(statuses[2].kind, "fx_rates");
+    assert!(statuses.iter().all(|s| !s.reachable && s.error.is_some()));
+    assert!(statuses[0]
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("Unknown provider: bogus"));
+}
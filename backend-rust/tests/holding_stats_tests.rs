@@ -0,0 +1,157 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::holding_stats::annualize;
+use portfoliodb_rust::services::{HoldingStatsService, PortfolioCalculator};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashSet;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn movement(
+    date: NaiveDate,
+    action_id: i64,
+    investment_id: i64,
+    quantity: Decimal,
+    amount: Decimal,
+) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn service(repos: &Repositories) -> HoldingStatsService {
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    HoldingStatsService::new(
+        repos.investments.clone(),
+        repos.movements.clone(),
+        calculator,
+    )
+    .with_action_types(repos.action_types.clone())
+}
+
+/// A fund held for two years with a 21% gain and a stock sold at a loss after three months
+async fn setup() -> (Repositories, i64, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let mut ids = Vec::new();
+    for name in ["Fund", "Stock", "Watched"] {
+        let investment = Investment {
+            id: 0,
+            name: Some(name.to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        };
+        ids.push(repos.investments.create(&investment).await.unwrap());
+    }
+    let (fund, stock) = (ids[0], ids[1]);
+
+    for m in [
+        movement(date(2022, 1, 1), 1, fund, dec!(10.0), dec!(1000.0)),
+        movement(date(2023, 6, 1), 1, stock, dec!(5.0), dec!(500.0)),
+        movement(date(2023, 9, 1), 2, stock, dec!(5.0), dec!(450.0)),
+    ] {
+        repos.movements.create(&m).await.unwrap();
+    }
+    repos
+        .investment_prices
+        .create(&InvestmentPrice {
+            date: Some(date(2024, 1, 1)),
+            investment_id: Some(fund),
+            price: Some(dec!(121.0)),
+            source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
+        })
+        .await
+        .unwrap();
+
+    (repos, fund, stock)
+}
+
+#[tokio::test]
+async fn test_holding_stats() {
+    let (repos, fund, stock) = setup().await;
+
+    let report = service(&repos).holdings(None).await.unwrap();
+
+    // Investments that were never bought are left out
+    assert_eq!(report.holdings.len(), 2);
+    assert_eq!(report.total_invested, dec!(1500.0));
+    assert_eq!(report.total_gain, dec!(160.0));
+
+    let held = &report.holdings[0];
+    assert_eq!(held.investment, fund);
+    assert_eq!(held.first_purchase_date, date(2022, 1, 1));
+    assert_eq!(held.end_date, date(2024, 1, 1));
+    assert_eq!(held.holding_days, 730);
+    assert!(held.open);
+    assert!((held.twr - 0.21).abs() < 1e-9);
+    assert!((held.annualized_return.unwrap() - 0.1).abs() < 1e-3);
+    assert_eq!(held.gain, dec!(210.0));
+
+    let sold = &report.holdings[1];
+    assert_eq!(sold.investment, stock);
+    assert_eq!(sold.end_date, date(2023, 9, 1));
+    assert_eq!(sold.holding_days, 92);
+    assert!(!sold.open);
+    assert!((sold.twr + 0.1).abs() < 1e-9);
+    assert_eq!(sold.annualized_return, None);
+    assert_eq!(sold.gain, dec!(-50.0));
+
+    // The contributions add up to the portfolio return
+    assert!((held.contribution.unwrap() - 0.14).abs() < 1e-9);
+    assert!((sold.contribution.unwrap() + 0.05 / 1.5).abs() < 1e-9);
+    let total: f64 = report.holdings.iter().filter_map(|h| h.contribution).sum();
+    assert!((total - report.total_return.unwrap()).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_holding_stats_of_selected_investments() {
+    let (repos, _, stock) = setup().await;
+
+    let report = service(&repos)
+        .holdings(Some(&HashSet::from([stock])))
+        .await
+        .unwrap();
+
+    assert_eq!(report.holdings.len(), 1);
+    assert_eq!(report.total_invested, dec!(500.0));
+    assert!((report.holdings[0].contribution.unwrap() + 0.1).abs() < 1e-9);
+}
+
+#[test]
+fn test_annualize() {
+    assert!((annualize(0.21, 730).unwrap() - 0.1).abs() < 1e-3);
+    assert!((annualize(0.1, 365).unwrap() - 0.1).abs() < 1e-3);
+    // Shorter periods and total losses are not annualized
+    assert_eq!(annualize(0.1, 364), None);
+    assert_eq!(annualize(-1.0, 730), None);
+}
@@ -0,0 +1,194 @@
+mod test_helpers;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::import_profiles::{
+    create_import_profile, update_import_profile, ImportProfileRequest,
+};
+use portfoliodb_rust::models::Investment;
+use portfoliodb_rust::repository::traits::ImportProfileRepository;
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::import::{BrokerImportOptions, ProfileParser, StatementParser};
+use portfoliodb_rust::services::BrokerImportService;
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+const COMDIRECT_CSV: &str = "\
+Buchungstag;Geschäftsart;WKN/ISIN;Bezeichnung;Stück;Kurswert;Provision
+15.01.2024;Kauf;IE00B4L5Y983;iShares Core MSCI World;10;-1.005,50;4,90
+16.01.2024;Depotgebühr;;;;-1,00;
+20.03.2024;Ertrag;IE00B4L5Y983;iShares Core MSCI World;;12,34;
+02.04.2024;Verkauf;IE00B4L5Y983;iShares Core MSCI World;2;210,00;4,90
+";
+
+fn request() -> ImportProfileRequest {
+    ImportProfileRequest {
+        name: "comdirect".to_string(),
+        delimiter: Some(";".to_string()),
+        date_format: "%d.%m.%Y".to_string(),
+        decimal_separator: Some(",".to_string()),
+        currency: "eur".to_string(),
+        date_column: "Buchungstag".to_string(),
+        action_column: "Geschäftsart".to_string(),
+        isin_column: "WKN/ISIN".to_string(),
+        product_column: Some("Bezeichnung".to_string()),
+        quantity_column: Some("Stück".to_string()),
+        amount_column: "Kurswert".to_string(),
+        fee_column: Some("Provision".to_string()),
+        action_keywords: BTreeMap::from([
+            ("Kauf".to_string(), 1),
+            ("Verkauf".to_string(), 2),
+            ("Ertrag".to_string(), 3),
+        ]),
+    }
+}
+
+async fn setup() -> (Repositories, Arc<dyn ImportProfileRepository>) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let profile_repo = repos.import_profiles.clone();
+    (repos, profile_repo)
+}
+
+#[tokio::test]
+async fn test_create_and_update_profile() {
+    let (_, repo) = setup().await;
+
+    let Json(created) = create_import_profile(State(repo.clone()), Json(request()))
+        .await
+        .unwrap();
+
+    assert!(created.id > 0);
+    assert_eq!(created.currency, "EUR");
+    assert_eq!(created.action_keywords.get("kauf"), Some(&1));
+    assert_eq!(created.fee_column.as_deref(), Some("Provision"));
+
+    let mut changed = request();
+    changed.name = "comdirect Depot".to_string();
+    changed.fee_column = Some(" ".to_string());
+    let Json(updated) = update_import_profile(State(repo.clone()), Path(created.id), Json(changed))
+        .await
+        .unwrap();
+
+    assert_eq!(updated.name, "comdirect Depot");
+    assert_eq!(updated.fee_column, None);
+    assert_eq!(repo.find_all().await.unwrap().len(), 1);
+
+    repo.delete(created.id).await.unwrap();
+    assert!(repo.find_by_id(created.id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_invalid_profiles_are_rejected() {
+    let (_, repo) = setup().await;
+
+    let mut cash_action = request();
+    cash_action.action_keywords = BTreeMap::from([("Einzahlung".to_string(), 4)]);
+    let mut bad_date_format = request();
+    bad_date_format.date_format = "%d.%m.%Q".to_string();
+    let mut bad_separator = request();
+    bad_separator.decimal_separator = Some(";".to_string());
+    let mut no_keywords = request();
+    no_keywords.action_keywords.clear();
+
+    for request in [cash_action, bad_date_format, bad_separator, no_keywords] {
+        let err = create_import_profile(State(repo.clone()), Json(request))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidInput(_)), "{:?}", err);
+    }
+    assert!(repo.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_profile_parser() {
+    let (_, repo) = setup().await;
+    let Json(profile) = create_import_profile(State(repo), Json(request()))
+        .await
+        .unwrap();
+
+    let transactions = ProfileParser::new(profile).parse(COMDIRECT_CSV).unwrap();
+
+    // The custody fee has no mapped keyword and is skipped
+    assert_eq!(transactions.len(), 3);
+    let buy = &transactions[0];
+    assert_eq!(buy.date, NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+    assert_eq!(buy.action_id, 1);
+    assert_eq!(buy.isin, "IE00B4L5Y983");
+    assert_eq!(buy.quantity, Some(dec!(10.0)));
+    assert_eq!(buy.amount, dec!(1005.5));
+    assert_eq!(buy.fee, Some(dec!(4.9)));
+    assert_eq!(buy.currency, "EUR");
+
+    let payout = &transactions[1];
+    assert_eq!(payout.action_id, 3);
+    assert_eq!(payout.quantity, None);
+    assert_eq!(payout.amount, dec!(12.34));
+
+    assert_eq!(transactions[2].action_id, 2);
+}
+
+#[tokio::test]
+async fn test_profile_parser_reports_missing_column() {
+    let (_, repo) = setup().await;
+    let Json(profile) = create_import_profile(State(repo), Json(request()))
+        .await
+        .unwrap();
+
+    let err = ProfileParser::new(profile)
+        .parse("Datum;Typ\n15.01.2024;Kauf\n")
+        .unwrap_err();
+
+    assert!(matches!(err, AppError::InvalidInput(msg) if msg.contains("Buchungstag")));
+}
+
+#[tokio::test]
+async fn test_import_with_profile() {
+    let (repos, repo) = setup().await;
+    let Json(profile) = create_import_profile(State(repo), Json(request()))
+        .await
+        .unwrap();
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("MSCI World".to_string()),
+            isin: Some("IE00B4L5Y983".to_string()),
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let service = BrokerImportService::new(
+        repos.investments.clone(),
+        repos.movements.clone(),
+        repos.portfolios.clone(),
+        repos.settings.clone(),
+    );
+
+    let result = service
+        .import(
+            &ProfileParser::new(profile),
+            COMDIRECT_CSV,
+            BrokerImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.movements.len(), 3);
+    let movements = repos.movements.find_all().await.unwrap();
+    assert!(movements
+        .iter()
+        .all(|m| m.investment_id == Some(investment_id)));
+}
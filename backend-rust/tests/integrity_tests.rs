@@ -0,0 +1,208 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::integrity::{IntegrityCheck, IntegrityReport, PriceKey};
+use portfoliodb_rust::services::IntegrityService;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use sqlx::SqlitePool;
+use test_helpers::setup_test_db;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+fn service(repos: &Repositories) -> IntegrityService {
+    IntegrityService::new(
+        repos.movements.clone(),
+        repos.investments.clone(),
+        repos.action_types.clone(),
+        repos.portfolios.clone(),
+        repos.investment_prices.clone(),
+    )
+}
+
+async fn create_investment(repos: &Repositories) -> i64 {
+    repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Test".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap()
+}
+
+async fn create_movement(
+    repos: &Repositories,
+    investment_id: i64,
+    action_id: i64,
+    date: Option<NaiveDate>,
+    quantity: Decimal,
+) -> i64 {
+    repos
+        .movements
+        .create(&Movement {
+            id: 0,
+            date,
+            action_id: Some(action_id),
+            investment_id: Some(investment_id),
+            quantity: Some(quantity),
+            amount: Some(quantity * dec!(10.0)),
+            fee: None,
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        })
+        .await
+        .unwrap()
+}
+
+/// Insert a price bypassing the repository, which would merge prices with the same key
+async fn insert_price(pool: &SqlitePool, date: Option<NaiveDate>, investment_id: Option<i64>) {
+    sqlx::query(
+        "INSERT INTO InvestmentPrice (Date, InvestmentID, Price, Source) VALUES (?, ?, 10, 'manual')",
+    )
+    .bind(date)
+    .bind(investment_id)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+fn check<'a>(report: &'a IntegrityReport, name: &str) -> &'a IntegrityCheck {
+    report
+        .checks
+        .iter()
+        .find(|check| check.check == name)
+        .unwrap_or_else(|| panic!("no check {}", name))
+}
+
+#[tokio::test]
+async fn test_clean_data_passes_all_checks() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = create_investment(&repos).await;
+    create_movement(&repos, investment_id, 1, Some(day(1)), dec!(5.0)).await;
+    create_movement(&repos, investment_id, 2, Some(day(2)), dec!(5.0)).await;
+    repos
+        .investment_prices
+        .create(&InvestmentPrice {
+            date: Some(day(1)),
+            investment_id: Some(investment_id),
+            price: Some(dec!(10.0)),
+            source: None,
+            currency: None,
+            original_price: None,
+        })
+        .await
+        .unwrap();
+
+    let report = service(&repos).check().await.unwrap();
+
+    assert_eq!(report.status, "ok");
+    assert_eq!(report.checks.len(), 6);
+    assert!(report.checks.iter().all(|check| check.count == 0));
+}
+
+#[tokio::test]
+async fn test_movement_checks_report_sample_ids() {
+    let pool = setup_test_db().await;
+    let repos = Repositories::sqlite(pool.clone());
+    let investment_id = create_investment(&repos).await;
+    create_movement(&repos, investment_id, 1, Some(day(1)), dec!(5.0)).await;
+    let oversold = create_movement(&repos, investment_id, 2, Some(day(2)), dec!(8.0)).await;
+    create_movement(&repos, investment_id, 1, Some(day(3)), dec!(3.0)).await;
+    // Buying back on the same day leaves nothing negative
+    create_movement(&repos, investment_id, 2, Some(day(4)), dec!(1.0)).await;
+    create_movement(&repos, investment_id, 1, Some(day(4)), dec!(1.0)).await;
+    let undated = create_movement(&repos, investment_id, 1, None, dec!(1.0)).await;
+
+    // Older databases did not enforce the foreign keys
+    let mut conn = pool.acquire().await.unwrap();
+    sqlx::query("PRAGMA foreign_keys = OFF")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+    let orphaned = sqlx::query(
+        "INSERT INTO Movement (Date, Quantity, Amount, ActionID, InvestmentID) VALUES ('2024-01-05', 1, 10, 1, 999)",
+    )
+    .execute(&mut *conn)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+    drop(conn);
+
+    let report = service(&repos).check().await.unwrap();
+
+    assert_eq!(report.status, "issues_found");
+    let orphaned_movements = check(&report, "orphaned_movements");
+    assert_eq!(orphaned_movements.sample_ids, vec![orphaned]);
+    assert!(!orphaned_movements.fixable);
+    assert_eq!(
+        check(&report, "movements_without_date").sample_ids,
+        vec![undated]
+    );
+    assert_eq!(
+        check(&report, "negative_holdings").sample_ids,
+        vec![oversold]
+    );
+}
+
+#[tokio::test]
+async fn test_fix_deletes_orphaned_and_duplicate_prices() {
+    let pool = setup_test_db().await;
+    let repos = Repositories::sqlite(pool.clone());
+    let investment_id = create_investment(&repos).await;
+    insert_price(&pool, Some(day(1)), Some(investment_id)).await;
+    insert_price(&pool, Some(day(1)), Some(999)).await;
+    insert_price(&pool, Some(day(1)), None).await;
+    // The unique key does not apply to prices without date
+    for _ in 0..3 {
+        insert_price(&pool, None, Some(investment_id)).await;
+    }
+
+    let service = service(&repos);
+    let report = service.check().await.unwrap();
+    assert_eq!(check(&report, "orphaned_prices").count, 1);
+    assert_eq!(check(&report, "prices_without_investment").count, 1);
+    let duplicates = check(&report, "duplicate_prices");
+    assert_eq!(duplicates.count, 2);
+    assert_eq!(
+        duplicates.sample_prices,
+        vec![PriceKey {
+            date: None,
+            investment_id: Some(investment_id),
+            source: "manual".to_string(),
+        }]
+    );
+    assert!(duplicates.fixable);
+
+    let fix = service.fix().await.unwrap();
+
+    assert_eq!(fix.deleted_orphaned_prices, 2);
+    assert_eq!(fix.deleted_duplicate_prices, 2);
+    assert_eq!(fix.report.status, "ok");
+    let remaining = repos
+        .investment_prices
+        .find_all(Some(investment_id), None, None)
+        .await
+        .unwrap();
+    assert_eq!(remaining.len(), 2);
+}
@@ -0,0 +1,185 @@
+mod test_helpers;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::handlers::{
+    list_investments, update_investment, CreateInvestmentRequest, ListInvestmentsQuery,
+};
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::traits::InvestmentRepository;
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::{PortfolioCalculator, QuoteFetcherService};
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+async fn create(repo: &Arc<dyn InvestmentRepository>, name: &str, archived: bool) -> i64 {
+    repo.create(&Investment {
+        id: 0,
+        name: Some(name.to_string()),
+        isin: None,
+        shortname: None,
+        ticker_symbol: Some(name.to_uppercase()),
+        quote_provider: Some("unknown_provider".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: true,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived,
+    })
+    .await
+    .unwrap()
+}
+
+async fn names(repo: &Arc<dyn InvestmentRepository>, query: ListInvestmentsQuery) -> Vec<String> {
+    let Json(investments) = list_investments(State(repo.clone()), Query(query))
+        .await
+        .unwrap();
+    investments
+        .into_iter()
+        .map(|i| i.name.unwrap_or_default())
+        .collect()
+}
+
+async fn check_list_hides_archived(repo: Arc<dyn InvestmentRepository>) {
+    create(&repo, "Active", false).await;
+    let sold = create(&repo, "Sold", true).await;
+
+    assert_eq!(
+        names(&repo, ListInvestmentsQuery::default()).await,
+        vec!["Active"]
+    );
+    assert_eq!(
+        names(
+            &repo,
+            ListInvestmentsQuery {
+                include_archived: true,
+                ..Default::default()
+            }
+        )
+        .await,
+        vec!["Active", "Sold"]
+    );
+    assert!(names(
+        &repo,
+        ListInvestmentsQuery {
+            q: Some("sold".to_string()),
+            ..Default::default()
+        }
+    )
+    .await
+    .is_empty());
+    assert_eq!(
+        names(
+            &repo,
+            ListInvestmentsQuery {
+                q: Some("sold".to_string()),
+                include_archived: true,
+                ..Default::default()
+            }
+        )
+        .await,
+        vec!["Sold"]
+    );
+
+    // The flag is kept when an update omits it
+    let Json(updated) = update_investment(
+        State(repo.clone()),
+        Path(sold),
+        Json(CreateInvestmentRequest {
+            name: Some("Sold in 2019".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: None,
+            partial_exemption: None,
+            quote_fetch_enabled: None,
+            quote_fetch_interval_days: None,
+            archived: None,
+        }),
+    )
+    .await
+    .unwrap();
+    assert!(updated.archived);
+}
+
+#[tokio::test]
+async fn test_list_hides_archived_sqlite() {
+    check_list_hides_archived(Repositories::sqlite(setup_test_db().await).investments).await;
+}
+
+#[tokio::test]
+async fn test_list_hides_archived_in_memory() {
+    check_list_hides_archived(Repositories::in_memory().investments).await;
+}
+
+#[tokio::test]
+async fn test_scheduled_fetch_skips_archived() {
+    let repos = Repositories::in_memory();
+    let active = create(&repos.investments, "Active", false).await;
+    let archived = create(&repos.investments, "Sold", true).await;
+    let service = QuoteFetcherService::new(
+        repos.investments.clone(),
+        repos.investment_prices.clone(),
+        "EUR".to_string(),
+    );
+
+    let results = service.fetch_quotes(None).await.unwrap();
+    let fetched: Vec<i64> = results.iter().map(|r| r.investment_id).collect();
+    assert_eq!(fetched, vec![active]);
+
+    // Explicitly requested investments are fetched even when archived
+    let results = service.fetch_quotes(Some(vec![archived])).await.unwrap();
+    assert_eq!(results.len(), 1);
+}
+
+#[tokio::test]
+async fn test_developments_include_archived() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let id = create(&repos.investments, "Sold", true).await;
+    let day = |d: u32| NaiveDate::from_ymd_opt(2019, 3, d).unwrap();
+    for (date, action_id, amount) in [(day(1), 1, dec!(100.0)), (day(5), 2, dec!(120.0))] {
+        repos
+            .movements
+            .create(&Movement {
+                id: 0,
+                date: Some(date),
+                action_id: Some(action_id),
+                investment_id: Some(id),
+                quantity: Some(dec!(10.0)),
+                amount: Some(amount),
+                fee: None,
+                tax: None,
+                portfolio_id: None,
+                external_id: None,
+            })
+            .await
+            .unwrap();
+    }
+    repos
+        .investment_prices
+        .create(&InvestmentPrice {
+            date: Some(day(3)),
+            investment_id: Some(id),
+            price: Some(dec!(11.0)),
+            source: None,
+            currency: None,
+            original_price: None,
+        })
+        .await
+        .unwrap();
+
+    let calculator =
+        PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone());
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+
+    assert!(developments.iter().all(|d| d.investment == id));
+    let held = developments.iter().find(|d| d.date == day(3)).unwrap();
+    assert_eq!(held.value, dec!(110.0));
+}
@@ -0,0 +1,179 @@
+mod test_helpers;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::merge_investment;
+use portfoliodb_rust::models::{
+    Investment, InvestmentMerge, InvestmentPrice, Movement, Tag, INVESTMENT_MERGED,
+};
+use portfoliodb_rust::repository::Repositories;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+async fn create_investment(repos: &Repositories, name: &str) -> i64 {
+    repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some(name.to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap()
+}
+
+async fn create_price(repos: &Repositories, investment_id: i64, date: NaiveDate, price: Decimal) {
+    repos
+        .investment_prices
+        .create(&InvestmentPrice {
+            date: Some(date),
+            investment_id: Some(investment_id),
+            price: Some(price),
+            source: None,
+            currency: None,
+            original_price: None,
+        })
+        .await
+        .unwrap();
+}
+
+async fn create_buy(repos: &Repositories, investment_id: i64, date: NaiveDate) {
+    repos
+        .movements
+        .create(&Movement {
+            id: 0,
+            date: Some(date),
+            action_id: Some(1),
+            investment_id: Some(investment_id),
+            quantity: Some(dec!(1.0)),
+            amount: Some(dec!(100.0)),
+            fee: None,
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        })
+        .await
+        .unwrap();
+}
+
+/// Merges a duplicate into the target and checks what ended up where
+async fn check_merge(repos: Repositories) {
+    let target = create_investment(&repos, "MSCI World").await;
+    let duplicate = create_investment(&repos, "MSCI World (typo)").await;
+    create_buy(&repos, target, day(1)).await;
+    create_buy(&repos, duplicate, day(2)).await;
+    create_buy(&repos, duplicate, day(3)).await;
+    create_price(&repos, target, day(1), dec!(100.0)).await;
+    create_price(&repos, duplicate, day(1), dec!(99.0)).await;
+    create_price(&repos, duplicate, day(2), dec!(101.0)).await;
+    let tag = repos
+        .tags
+        .create(&Tag {
+            id: 0,
+            name: "ETF".to_string(),
+        })
+        .await
+        .unwrap();
+    repos
+        .tags
+        .set_investment_tags(duplicate, &[tag])
+        .await
+        .unwrap();
+
+    let Json(merge) = merge_investment(State(repos.investments.clone()), Path((duplicate, target)))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        merge,
+        InvestmentMerge {
+            source_id: duplicate,
+            target_id: target,
+            movements: 2,
+            prices: 1,
+            duplicate_prices: 1,
+        }
+    );
+    assert!(repos
+        .investments
+        .find_by_id(duplicate)
+        .await
+        .unwrap()
+        .is_none());
+    let movements = repos.movements.find_all().await.unwrap();
+    assert_eq!(movements.len(), 3);
+    assert!(movements.iter().all(|m| m.investment_id == Some(target)));
+    // The price of the target is kept for the day both had one
+    let prices = repos
+        .investment_prices
+        .find_all(Some(target), None, None)
+        .await
+        .unwrap();
+    let prices: Vec<_> = prices.iter().map(|p| (p.date, p.price)).collect();
+    assert_eq!(
+        prices,
+        vec![
+            (Some(day(2)), Some(dec!(101.0))),
+            (Some(day(1)), Some(dec!(100.0)))
+        ]
+    );
+    assert_eq!(
+        repos.tags.find_investment_ids(tag).await.unwrap(),
+        vec![target]
+    );
+
+    let log = repos.audit_log.find_recent(10).await.unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].action, INVESTMENT_MERGED);
+    assert!(log[0].details.contains("MSCI World (typo)"));
+}
+
+#[tokio::test]
+async fn test_merge_moves_dependents_sqlite() {
+    check_merge(Repositories::sqlite(setup_test_db().await)).await;
+}
+
+#[tokio::test]
+async fn test_merge_moves_dependents_in_memory() {
+    check_merge(Repositories::in_memory()).await;
+}
+
+#[tokio::test]
+async fn test_merge_into_missing_or_same_investment_is_rejected() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let id = create_investment(&repos, "Test").await;
+    create_buy(&repos, id, day(1)).await;
+
+    let err = merge_investment(State(repos.investments.clone()), Path((id, id)))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+
+    let err = merge_investment(State(repos.investments.clone()), Path((id, 999)))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::NotFound { .. }));
+    assert!(repos.investments.find_by_id(id).await.unwrap().is_some());
+    assert_eq!(
+        repos.movements.find_all().await.unwrap()[0].investment_id,
+        Some(id)
+    );
+    assert!(repos.audit_log.find_recent(10).await.unwrap().is_empty());
+}
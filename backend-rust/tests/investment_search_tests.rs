@@ -0,0 +1,104 @@
+mod test_helpers;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use portfoliodb_rust::handlers::{list_investments, ListInvestmentsQuery};
+use portfoliodb_rust::models::Investment;
+use portfoliodb_rust::repository::traits::InvestmentRepository;
+use portfoliodb_rust::repository::Repositories;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+async fn create(repo: &Arc<dyn InvestmentRepository>, name: &str, ticker: &str, watchlist: bool) {
+    repo.create(&Investment {
+        id: 0,
+        name: Some(name.to_string()),
+        isin: None,
+        shortname: None,
+        ticker_symbol: Some(ticker.to_string()),
+        quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    })
+    .await
+    .unwrap();
+}
+
+async fn search(repo: &Arc<dyn InvestmentRepository>, q: &str) -> Vec<String> {
+    names(repo, q, None, None).await
+}
+
+async fn names(
+    repo: &Arc<dyn InvestmentRepository>,
+    q: &str,
+    watchlist: Option<bool>,
+    limit: Option<i64>,
+) -> Vec<String> {
+    let Json(investments) = list_investments(
+        State(repo.clone()),
+        Query(ListInvestmentsQuery {
+            watchlist,
+            q: Some(q.to_string()),
+            limit,
+            include_archived: false,
+        }),
+    )
+    .await
+    .unwrap();
+    investments
+        .into_iter()
+        .map(|i| i.name.unwrap_or_default())
+        .collect()
+}
+
+async fn check_search(repo: Arc<dyn InvestmentRepository>) {
+    create(&repo, "Vanguard FTSE All-World", "VWRL", false).await;
+    create(&repo, "Apple Inc.", "AAPL", false).await;
+    create(&repo, "Pineapple Farms", "PINE", true).await;
+    create(&repo, "Appian 100% Growth", "APPN", false).await;
+    create(&repo, "AAPL Tracker", "TRK", false).await;
+
+    // Exact ticker first, then prefixes, then substrings, each by name
+    assert_eq!(
+        search(&repo, "aapl").await,
+        vec!["Apple Inc.", "AAPL Tracker"]
+    );
+    assert_eq!(
+        search(&repo, " APP ").await,
+        vec!["Appian 100% Growth", "Apple Inc.", "Pineapple Farms"]
+    );
+    assert_eq!(
+        search(&repo, "world").await,
+        vec!["Vanguard FTSE All-World"]
+    );
+
+    // Wildcards in the search text match literally
+    assert_eq!(search(&repo, "0%").await, vec!["Appian 100% Growth"]);
+    assert!(search(&repo, "a_p").await.is_empty());
+
+    assert_eq!(
+        names(&repo, "app", Some(true), None).await,
+        vec!["Pineapple Farms"]
+    );
+    assert_eq!(
+        names(&repo, "app", None, Some(1)).await,
+        vec!["Appian 100% Growth"]
+    );
+    // An empty search lists everything
+    assert_eq!(search(&repo, "").await.len(), 5);
+}
+
+#[tokio::test]
+async fn test_search_sqlite() {
+    check_search(Repositories::sqlite(setup_test_db().await).investments).await;
+}
+
+#[tokio::test]
+async fn test_search_in_memory() {
+    check_search(Repositories::in_memory().investments).await;
+}
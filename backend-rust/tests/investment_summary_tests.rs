@@ -0,0 +1,300 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement, Portfolio};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::cost_basis::{SPLIT_ACTION_ID, TRANSFER_OUT_ACTION_ID};
+use portfoliodb_rust::services::positions::PositionPoint;
+use portfoliodb_rust::services::{InvestmentSummaryService, PortfolioCalculator};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn movement(
+    date: NaiveDate,
+    action_id: i64,
+    investment_id: i64,
+    quantity: Decimal,
+    amount: Decimal,
+    fee: Decimal,
+) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: Some(fee),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn service(repos: &Repositories) -> InvestmentSummaryService {
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    InvestmentSummaryService::new(
+        repos.investments.clone(),
+        repos.movements.clone(),
+        calculator,
+    )
+    .with_action_types(repos.action_types.clone())
+}
+
+#[tokio::test]
+async fn test_investment_summary() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let mut ids = Vec::new();
+    for name in ["Summarized", "Other"] {
+        let investment = Investment {
+            id: 0,
+            name: Some(name.to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        };
+        ids.push(repos.investments.create(&investment).await.unwrap());
+    }
+    let (id, other) = (ids[0], ids[1]);
+    let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+
+    for m in [
+        movement(date(1, 10), 1, id, dec!(10.0), dec!(1000.0), dec!(5.0)),
+        movement(date(3, 1), 2, id, dec!(4.0), dec!(480.0), dec!(5.0)),
+        movement(date(4, 1), 3, id, dec!(0.0), dec!(30.0), dec!(0.0)),
+        movement(date(1, 10), 1, other, dec!(1.0), dec!(50.0), dec!(1.0)),
+    ] {
+        repos.movements.create(&m).await.unwrap();
+    }
+    repos
+        .investment_prices
+        .create(&InvestmentPrice {
+            date: Some(date(5, 2)),
+            investment_id: Some(id),
+            price: Some(dec!(110.0)),
+            source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
+        })
+        .await
+        .unwrap();
+
+    let summary = service(&repos).summary(id).await.unwrap();
+
+    assert_eq!(summary.name.as_deref(), Some("Summarized"));
+    assert_eq!(summary.quantity, dec!(6.0));
+    assert_eq!(summary.price, Some(dec!(110.0)));
+    assert_eq!(summary.price_date, Some(date(5, 2)));
+    assert_eq!(summary.market_value, dec!(660.0));
+    assert_eq!(summary.total_invested, dec!(1000.0));
+    assert_eq!(summary.total_sold, dec!(480.0));
+    assert_eq!(summary.fees_paid, dec!(10.0));
+    assert_eq!(summary.dividends_received, dec!(30.0));
+    // (660 + 480 + 30 - 1000 - 10) / 1000
+    let simple_return = summary.simple_return.unwrap();
+    assert!((simple_return - 0.16).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_investment_summary_with_taxes() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Taxed".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+
+    for m in [
+        movement(date(1, 10), 1, id, dec!(10.0), dec!(1000.0), dec!(5.0)),
+        Movement {
+            tax: Some(dec!(20.0)),
+            ..movement(date(3, 1), 2, id, dec!(10.0), dec!(1100.0), dec!(5.0))
+        },
+    ] {
+        repos.movements.create(&m).await.unwrap();
+    }
+
+    let summary = service(&repos).summary(id).await.unwrap();
+
+    assert_eq!(summary.fees_paid, dec!(10.0));
+    assert_eq!(summary.taxes_paid, dec!(20.0));
+    // (0 + 1100 - 1000 - 10 - 20) / 1000
+    let simple_return = summary.simple_return.unwrap();
+    assert!((simple_return - 0.07).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_investment_summary_without_movements() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Watchlist".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    let summary = service(&repos).summary(id).await.unwrap();
+
+    assert_eq!(summary.quantity, dec!(0.0));
+    assert_eq!(summary.price, None);
+    assert_eq!(summary.simple_return, None);
+}
+
+#[tokio::test]
+async fn test_investment_summary_unknown_investment() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let err = service(&repos).summary(999).await.unwrap_err();
+
+    assert!(matches!(err, AppError::NotFound { .. }));
+    assert_eq!(err.to_string(), "Investment 999 not found");
+}
+
+#[tokio::test]
+async fn test_position_history() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Split".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let broker = repos
+        .portfolios
+        .create(&Portfolio {
+            id: 0,
+            name: "Broker".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+    let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+
+    for m in [
+        movement(date(1, 10), 1, id, dec!(10.0), dec!(1000.0), dec!(0.0)),
+        // Bought and partly sold on the same day: one point with the end of day quantity
+        movement(date(2, 1), 1, id, dec!(5.0), dec!(500.0), dec!(0.0)),
+        movement(date(2, 1), 2, id, dec!(3.0), dec!(300.0), dec!(0.0)),
+        // 1:2 split, applied before the sell of that day
+        movement(
+            date(3, 1),
+            SPLIT_ACTION_ID,
+            id,
+            dec!(2.0),
+            dec!(0.0),
+            dec!(0.0),
+        ),
+        movement(date(3, 1), 2, id, dec!(4.0), dec!(200.0), dec!(0.0)),
+        // Dividends leave the quantity alone
+        movement(date(4, 1), 3, id, dec!(0.0), dec!(30.0), dec!(0.0)),
+        Movement {
+            portfolio_id: Some(broker),
+            ..movement(date(5, 1), 1, id, dec!(1.0), dec!(50.0), dec!(0.0))
+        },
+        Movement {
+            portfolio_id: Some(broker),
+            ..movement(
+                date(6, 1),
+                TRANSFER_OUT_ACTION_ID,
+                id,
+                dec!(1.0),
+                dec!(0.0),
+                dec!(0.0),
+            )
+        },
+    ] {
+        repos.movements.create(&m).await.unwrap();
+    }
+    let point = |date, quantity| PositionPoint { date, quantity };
+    let service = service(&repos);
+
+    assert_eq!(
+        service
+            .position_history(id, None, None, None)
+            .await
+            .unwrap(),
+        vec![
+            point(date(1, 10), dec!(10.0)),
+            point(date(2, 1), dec!(12.0)),
+            point(date(3, 1), dec!(20.0)),
+            point(date(5, 1), dec!(21.0)),
+            point(date(6, 1), dec!(20.0)),
+        ]
+    );
+    // Quantities before the start still count
+    assert_eq!(
+        service
+            .position_history(id, None, Some(date(2, 15)), Some(date(5, 31)))
+            .await
+            .unwrap(),
+        vec![point(date(3, 1), dec!(20.0)), point(date(5, 1), dec!(21.0))]
+    );
+    assert_eq!(
+        service
+            .position_history(id, Some(broker), None, None)
+            .await
+            .unwrap(),
+        vec![point(date(5, 1), dec!(1.0)), point(date(6, 1), dec!(0.0))]
+    );
+
+    let err = service
+        .position_history(999, None, None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::NotFound { .. }));
+}
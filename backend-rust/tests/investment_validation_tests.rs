@@ -1,6 +1,9 @@
-use axum::extract::State;
+use axum::extract::{Path, State};
 use axum::Json;
-use portfoliodb_rust::handlers::investments::{create_investment, CreateInvestmentRequest};
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::investments::{
+    create_investment, update_investment, CreateInvestmentRequest,
+};
 use portfoliodb_rust::repository::sqlite::SqliteInvestmentRepository;
 use std::sync::Arc;
 
@@ -20,6 +23,13 @@ async fn test_create_investment_with_invalid_provider() {
         shortname: Some("TEST".to_string()),
         ticker_symbol: Some("TEST".to_string()),
         quote_provider: Some("invalid_provider".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
     };
 
     let result = create_investment(State(repo), Json(request)).await;
@@ -45,6 +55,13 @@ async fn test_create_investment_with_valid_provider() {
         shortname: Some("AAPL".to_string()),
         ticker_symbol: Some("AAPL".to_string()),
         quote_provider: Some("yahoo".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
     };
 
     let result = create_investment(State(repo), Json(request)).await;
@@ -70,6 +87,13 @@ async fn test_create_investment_with_no_provider() {
         shortname: Some("TEST".to_string()),
         ticker_symbol: Some("TEST".to_string()),
         quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
     };
 
     let result = create_investment(State(repo), Json(request)).await;
@@ -78,3 +102,264 @@ async fn test_create_investment_with_no_provider() {
     let response = result.unwrap();
     assert_eq!(response.0.quote_provider, None);
 }
+
+#[tokio::test]
+async fn test_create_investment_with_provider_chain() {
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+    portfoliodb_rust::db::migrations::run_migrations(&pool)
+        .await
+        .unwrap();
+
+    let repo = Arc::new(SqliteInvestmentRepository::new(pool))
+        as Arc<dyn portfoliodb_rust::repository::traits::InvestmentRepository>;
+
+    let request = CreateInvestmentRequest {
+        name: Some("Test Investment".to_string()),
+        isin: Some("IE00B4L5Y983".to_string()),
+        shortname: Some("EUNL".to_string()),
+        ticker_symbol: Some("EUNL.DE".to_string()),
+        quote_provider: Some("yahoo, justetf".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
+    };
+
+    let response = create_investment(State(repo.clone()), Json(request))
+        .await
+        .unwrap();
+    assert_eq!(response.0.quote_provider, Some("yahoo,justetf".to_string()));
+
+    let request = CreateInvestmentRequest {
+        name: Some("Test Investment".to_string()),
+        isin: None,
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: Some("yahoo,invalid_provider".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
+    };
+
+    let err = create_investment(State(repo), Json(request))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid_provider"));
+}
+
+#[tokio::test]
+async fn test_create_investment_normalizes_isin() {
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+    portfoliodb_rust::db::migrations::run_migrations(&pool)
+        .await
+        .unwrap();
+
+    let repo = Arc::new(SqliteInvestmentRepository::new(pool))
+        as Arc<dyn portfoliodb_rust::repository::traits::InvestmentRepository>;
+
+    let request = |isin: &str| CreateInvestmentRequest {
+        name: Some("Test Investment".to_string()),
+        isin: Some(isin.to_string()),
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
+    };
+
+    let response = create_investment(State(repo.clone()), Json(request(" ie00 b4l5 y983 ")))
+        .await
+        .unwrap();
+    assert_eq!(response.0.isin, Some("IE00B4L5Y983".to_string()));
+
+    let response = create_investment(State(repo.clone()), Json(request("")))
+        .await
+        .unwrap();
+    assert_eq!(response.0.isin, None);
+
+    let err = create_investment(State(repo), Json(request("IE00B4L5Y993")))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("IE00B4L5Y993"));
+}
+
+#[tokio::test]
+async fn test_update_investment_keeps_omitted_metadata() {
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+    portfoliodb_rust::db::migrations::run_migrations(&pool)
+        .await
+        .unwrap();
+
+    let repo = Arc::new(SqliteInvestmentRepository::new(pool))
+        as Arc<dyn portfoliodb_rust::repository::traits::InvestmentRepository>;
+
+    let request = |currency: Option<&str>| CreateInvestmentRequest {
+        name: Some("Test Investment".to_string()),
+        isin: None,
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: currency.map(str::to_string),
+        asset_class: currency.map(|_| "etf".to_string()),
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
+    };
+
+    let created = create_investment(State(repo.clone()), Json(request(Some("USD"))))
+        .await
+        .unwrap();
+    assert_eq!(created.0.currency, Some("USD".to_string()));
+
+    let updated = update_investment(State(repo.clone()), Path(created.0.id), Json(request(None)))
+        .await
+        .unwrap();
+    assert_eq!(updated.0.currency, Some("USD".to_string()));
+    assert_eq!(updated.0.asset_class, Some("etf".to_string()));
+
+    let err = create_investment(State(repo), Json(request(Some("usd"))))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::Validation(_)));
+}
+
+#[tokio::test]
+async fn test_partial_exemption_is_kept_and_validated() {
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+    portfoliodb_rust::db::migrations::run_migrations(&pool)
+        .await
+        .unwrap();
+
+    let repo = Arc::new(SqliteInvestmentRepository::new(pool))
+        as Arc<dyn portfoliodb_rust::repository::traits::InvestmentRepository>;
+
+    let request = || CreateInvestmentRequest {
+        name: Some("World ETF".to_string()),
+        isin: None,
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: None,
+        asset_class: Some("etf".to_string()),
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
+    };
+
+    let created = create_investment(
+        State(repo.clone()),
+        Json(CreateInvestmentRequest {
+            partial_exemption: Some(rust_decimal_macros::dec!(30)),
+            ..request()
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        created.0.partial_exemption,
+        Some(rust_decimal_macros::dec!(30))
+    );
+
+    // Omitted on update keeps the stored value
+    let updated = update_investment(State(repo.clone()), Path(created.0.id), Json(request()))
+        .await
+        .unwrap();
+    assert_eq!(
+        updated.0.partial_exemption,
+        Some(rust_decimal_macros::dec!(30))
+    );
+
+    let result = update_investment(
+        State(repo),
+        Path(created.0.id),
+        Json(CreateInvestmentRequest {
+            partial_exemption: Some(rust_decimal_macros::dec!(101)),
+            ..request()
+        }),
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_quote_fetch_schedule_is_kept_and_validated() {
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+    portfoliodb_rust::db::migrations::run_migrations(&pool)
+        .await
+        .unwrap();
+
+    let repo = Arc::new(SqliteInvestmentRepository::new(pool))
+        as Arc<dyn portfoliodb_rust::repository::traits::InvestmentRepository>;
+
+    let request = || CreateInvestmentRequest {
+        name: Some("Rarely Traded".to_string()),
+        isin: None,
+        shortname: None,
+        ticker_symbol: Some("RARE.F".to_string()),
+        quote_provider: Some("yahoo".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
+    };
+
+    // Fetched on every run unless configured otherwise
+    let created = create_investment(State(repo.clone()), Json(request()))
+        .await
+        .unwrap();
+    assert!(created.0.quote_fetch_enabled);
+    assert_eq!(created.0.quote_fetch_interval_days, None);
+
+    let updated = update_investment(
+        State(repo.clone()),
+        Path(created.0.id),
+        Json(CreateInvestmentRequest {
+            quote_fetch_enabled: Some(false),
+            quote_fetch_interval_days: Some(7),
+            archived: None,
+            ..request()
+        }),
+    )
+    .await
+    .unwrap();
+    assert!(!updated.0.quote_fetch_enabled);
+    assert_eq!(updated.0.quote_fetch_interval_days, Some(7));
+
+    // Omitted on update keeps the stored values
+    let updated = update_investment(State(repo.clone()), Path(created.0.id), Json(request()))
+        .await
+        .unwrap();
+    assert!(!updated.0.quote_fetch_enabled);
+    assert_eq!(updated.0.quote_fetch_interval_days, Some(7));
+
+    let result = update_investment(
+        State(repo),
+        Path(created.0.id),
+        Json(CreateInvestmentRequest {
+            quote_fetch_interval_days: Some(0),
+            archived: None,
+            ..request()
+        }),
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
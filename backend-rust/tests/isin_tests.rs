@@ -0,0 +1,35 @@
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::isin;
+
+#[test]
+fn test_isin_checksum() {
+    for valid in [
+        "US0378331005",
+        "IE00B4L5Y983",
+        "DE0007164600",
+        "AU0000XVGZA3",
+    ] {
+        assert!(isin::is_valid(valid), "{}", valid);
+    }
+    for invalid in [
+        "US0378331006",
+        "IE00B4L5Y993",
+        "us0378331005",
+        "US037833100",
+        "US03783310055",
+        "1E00B4L5Y983",
+        "IE00B4L5Y98X",
+        "",
+    ] {
+        assert!(!isin::is_valid(invalid), "{}", invalid);
+    }
+}
+
+#[test]
+fn test_normalize_removes_whitespace_and_upper_cases() {
+    assert_eq!(isin::normalize(" ie00 b4l5 y983\t"), "IE00B4L5Y983");
+    assert_eq!(isin::parse("us0378331005 ").unwrap(), "US0378331005");
+
+    let err = isin::parse("IE00B4L5Y993").unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(msg) if msg.contains("IE00B4L5Y993")));
+}
@@ -42,7 +42,7 @@ async fn test_justetf_get_quote_online() {
     let today = chrono::Utc::now().date_naive();
     let days_diff = (today - quote.date).num_days();
     assert!(
-        days_diff >= 0 && days_diff <= 7,
+        (0..=7).contains(&days_diff),
         "Latest quote date {} should be within last 7 days (today: {})",
         quote.date,
         today
@@ -0,0 +1,165 @@
+use portfoliodb_rust::db;
+use sqlx::SqlitePool;
+
+async fn column_names(pool: &SqlitePool, table: &str) -> Vec<String> {
+    sqlx::query_as::<_, (String,)>(&format!("SELECT name FROM pragma_table_info('{}')", table))
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(name,)| name)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_migrations_are_idempotent() {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+    db::run_migrations(&pool).await.unwrap();
+    db::run_migrations(&pool).await.unwrap();
+
+    let (applied,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM _sqlx_migrations")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(applied >= 1);
+
+    let (action_types,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM ActionType")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(action_types, 9);
+
+    let (settings,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM Settings")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(settings, 1);
+}
+
+#[tokio::test]
+async fn test_unversioned_database_is_upgraded() {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+    // Schema and data of a database created before versioned migrations
+    for statement in [
+        "CREATE TABLE ActionType (ID INTEGER PRIMARY KEY AUTOINCREMENT, Name VARCHAR(10) NOT NULL)",
+        "CREATE TABLE Investment (ID INTEGER PRIMARY KEY AUTOINCREMENT, Name TEXT, ISIN VARCHAR(20), ShortName VARCHAR(30), QuoteProvider VARCHAR(20), TickerSymbol VARCHAR(20))",
+        "CREATE TABLE Movement (ID INTEGER PRIMARY KEY AUTOINCREMENT, Date DATE, Quantity DECIMAL, Amount DECIMAL, Fee DECIMAL, ActionID INTEGER REFERENCES ActionType(ID), InvestmentID INTEGER REFERENCES Investment(ID))",
+        "CREATE TABLE InvestmentPrice (id INTEGER PRIMARY KEY AUTOINCREMENT, Date DATE, InvestmentID INTEGER, Price DECIMAL, Source VARCHAR(20), UNIQUE(Date, InvestmentID, Source))",
+        "CREATE TABLE Settings (ID INTEGER PRIMARY KEY AUTOINCREMENT, BaseCurrency VARCHAR(3) NOT NULL)",
+        "INSERT INTO ActionType (ID, Name) VALUES (1, 'Buy'), (2, 'Sell'), (3, 'Payout')",
+        "INSERT INTO Settings (ID, BaseCurrency) VALUES (1, 'USD')",
+        "INSERT INTO Movement (Date, Quantity, Amount, Fee, ActionID) VALUES ('2024-01-15', 1, 10, 0, 1)",
+        "INSERT INTO InvestmentPrice (Date, InvestmentID, Price, Source) VALUES ('2024-01-15', 1, 10, NULL), ('2024-01-15', 1, 11, NULL), ('2024-01-15', 1, 12, 'yahoo')",
+    ] {
+        sqlx::query(statement).execute(&pool).await.unwrap();
+    }
+
+    db::run_migrations(&pool).await.unwrap();
+
+    assert!(column_names(&pool, "Movement")
+        .await
+        .contains(&"PortfolioID".to_string()));
+    let price_columns = column_names(&pool, "InvestmentPrice").await;
+    assert!(price_columns.contains(&"Currency".to_string()));
+    assert!(price_columns.contains(&"OriginalPrice".to_string()));
+
+    // Existing data is kept
+    let (base_currency, method): (String, String) =
+        sqlx::query_as("SELECT BaseCurrency, CostBasisMethod FROM Settings WHERE ID = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(base_currency, "USD");
+    assert_eq!(method, "fifo");
+
+    let (movements,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM Movement")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(movements, 1);
+
+    // Prices without source become one manual price per day, the latest one
+    let prices: Vec<(f64, String)> =
+        sqlx::query_as("SELECT CAST(Price AS REAL), Source FROM InvestmentPrice ORDER BY Source")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        prices,
+        [(11.0, "manual".to_string()), (12.0, "yahoo".to_string())]
+    );
+
+    let (action_types,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM ActionType")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(action_types, 9);
+}
+
+#[tokio::test]
+async fn test_custom_action_type_with_opening_id_is_moved() {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+    for statement in [
+        "CREATE TABLE ActionType (ID INTEGER PRIMARY KEY AUTOINCREMENT, Name VARCHAR(10) NOT NULL)",
+        "CREATE TABLE Movement (ID INTEGER PRIMARY KEY AUTOINCREMENT, Date DATE, Quantity DECIMAL, Amount DECIMAL, Fee DECIMAL, ActionID INTEGER REFERENCES ActionType(ID), InvestmentID INTEGER)",
+        "INSERT INTO ActionType (ID, Name) VALUES (1, 'Buy'), (2, 'Sell'), (3, 'Payout'), (9, 'Interest')",
+        "INSERT INTO Movement (Date, Quantity, Amount, Fee, ActionID) VALUES ('2024-01-15', 0, 10, 0, 9)",
+    ] {
+        sqlx::query(statement).execute(&pool).await.unwrap();
+    }
+
+    db::run_migrations(&pool).await.unwrap();
+
+    let action_types: Vec<(i64, String, String)> =
+        sqlx::query_as("SELECT ID, Name, Effect FROM ActionType WHERE ID >= 9 ORDER BY ID")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        action_types,
+        [
+            (9, "Opening".to_string(), "opening".to_string()),
+            (10, "Interest".to_string(), "cash_only".to_string())
+        ]
+    );
+    let (action_id,): (i64,) = sqlx::query_as("SELECT ActionID FROM Movement")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(action_id, 10);
+}
+
+#[tokio::test]
+async fn test_connect_creates_missing_database() {
+    let dir = std::env::temp_dir().join(format!("portfoliodb-data-{}", std::process::id()));
+    let path = dir.join("nested").join("portfoliodb.db");
+    let url = format!("sqlite:{}", path.display());
+    assert_eq!(db::sqlite_file(&url).unwrap(), Some(path.clone()));
+    assert_eq!(db::sqlite_file("sqlite::memory:").unwrap(), None);
+    assert_eq!(
+        db::sqlite_file("sqlite:file::memory:?cache=shared").unwrap(),
+        None
+    );
+
+    let repos = db::connect(&url).await.unwrap();
+    assert!(path.exists());
+    assert!(repos
+        .health
+        .check()
+        .await
+        .unwrap()
+        .applied_version
+        .is_some());
+
+    // A file in place of the directory cannot be opened; the error names the path
+    let blocked = dir.join("blocked");
+    std::fs::write(&blocked, "").unwrap();
+    let url = format!("sqlite:{}", blocked.join("portfoliodb.db").display());
+    let error = db::connect(&url).await.err().unwrap().to_string();
+    assert!(error.contains(&blocked.display().to_string()), "{}", error);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,140 @@
+mod test_helpers;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::movements::{
+    create_movement, delete_movement, update_movement, upsert_movement_by_external_id,
+    CreateMovementRequest,
+};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::MovementState;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn state(repos: &Repositories) -> MovementState {
+    MovementState {
+        movement_repo: repos.movements.clone(),
+        investment_repo: repos.investments.clone(),
+        action_type_repo: repos.action_types.clone(),
+        settings_repo: repos.settings.clone(),
+    }
+}
+
+fn request(amount: Decimal, external_id: Option<&str>) -> CreateMovementRequest {
+    CreateMovementRequest {
+        date: NaiveDate::from_ymd_opt(2024, 1, 15),
+        action_id: Some(1),
+        investment_id: None,
+        quantity: Some(dec!(1.0)),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: external_id.map(str::to_string),
+    }
+}
+
+#[tokio::test]
+async fn test_upsert_by_external_id_is_idempotent() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let upsert = |amount: Decimal| {
+        upsert_movement_by_external_id(
+            State(state(&repos)),
+            Path("order-1".to_string()),
+            Json(request(amount, None)),
+        )
+    };
+
+    let (status, created) = upsert(dec!(100.0)).await.unwrap();
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created.external_id.as_deref(), Some("order-1"));
+
+    let (status, updated) = upsert(dec!(120.0)).await.unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated.id, created.id);
+    assert_eq!(updated.amount, Some(dec!(120.0)));
+
+    let (status, _) = upsert(dec!(120.0)).await.unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(repos.movements.find_all().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_upsert_rejects_invalid_external_id() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let err = upsert_movement_by_external_id(
+        State(state(&repos)),
+        Path("order-1".to_string()),
+        Json(request(dec!(100.0), Some("order-2"))),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+
+    let err = upsert_movement_by_external_id(
+        State(state(&repos)),
+        Path("x".repeat(256)),
+        Json(request(dec!(100.0), None)),
+    )
+    .await
+    .unwrap_err();
+    match err {
+        AppError::Validation(errors) => assert_eq!(errors.errors[0].field, "external_id"),
+        other => panic!("expected validation error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_duplicate_external_id_conflicts() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let created = create_movement(
+        State(state(&repos)),
+        Json(request(dec!(100.0), Some("order-1"))),
+    )
+    .await
+    .unwrap();
+
+    let err = create_movement(
+        State(state(&repos)),
+        Json(request(dec!(100.0), Some("order-1"))),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.into_response().status(), StatusCode::CONFLICT);
+
+    // Updating without external_id keeps the reference
+    let updated = update_movement(
+        State(state(&repos)),
+        Path(created.id),
+        Json(request(dec!(110.0), None)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.external_id.as_deref(), Some("order-1"));
+}
+
+#[tokio::test]
+async fn test_update_and_delete_of_missing_movement_are_not_found() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let err = update_movement(
+        State(state(&repos)),
+        Path(999),
+        Json(request(dec!(100.0), None)),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Movement 999 not found");
+
+    let err = delete_movement(State(state(&repos)), Path(999))
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Movement 999 not found");
+    assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+}
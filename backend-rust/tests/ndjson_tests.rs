@@ -0,0 +1,98 @@
+mod test_helpers;
+
+use axum::body::to_bytes;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, HeaderValue, Uri};
+use axum::response::Response;
+use chrono::NaiveDate;
+use portfoliodb_rust::handlers::movements::{list_movements, MovementQuery};
+use portfoliodb_rust::handlers::ndjson::{accepts_ndjson, NDJSON_CONTENT_TYPE};
+use portfoliodb_rust::models::Movement;
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::MovementState;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn accept(value: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+    headers
+}
+
+async fn body(response: Response) -> String {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+async fn movements_response(headers: HeaderMap) -> Response {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    for day in 1..=3 {
+        repos
+            .movements
+            .create(&Movement {
+                id: 0,
+                date: NaiveDate::from_ymd_opt(2024, 1, day),
+                action_id: Some(1),
+                investment_id: None,
+                quantity: Some(dec!(1.0)),
+                amount: Some(Decimal::from(10 * day)),
+                fee: None,
+                tax: None,
+                portfolio_id: None,
+                external_id: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let uri: Uri = "/api/movements?sort_by=date&order=asc&limit=2"
+        .parse()
+        .unwrap();
+    let query: Query<MovementQuery> = Query::try_from_uri(&uri).unwrap();
+    let state = MovementState {
+        movement_repo: repos.movements.clone(),
+        investment_repo: repos.investments.clone(),
+        action_type_repo: repos.action_types.clone(),
+        settings_repo: repos.settings.clone(),
+    };
+    list_movements(State(state), query, headers).await.unwrap()
+}
+
+#[test]
+fn test_accepts_ndjson() {
+    assert!(accepts_ndjson(&accept("application/x-ndjson")));
+    assert!(accepts_ndjson(&accept(
+        "application/json;q=0.5, Application/X-NDJSON; q=1"
+    )));
+    assert!(!accepts_ndjson(&accept("application/json")));
+    assert!(!accepts_ndjson(&HeaderMap::new()));
+}
+
+#[tokio::test]
+async fn test_movements_stream_as_ndjson() {
+    let response = movements_response(accept(NDJSON_CONTENT_TYPE)).await;
+
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        NDJSON_CONTENT_TYPE
+    );
+    assert_eq!(response.headers()["x-total-count"], "3");
+
+    let body = body(response).await;
+    let amounts: Vec<serde_json::Value> = body
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["amount"].clone())
+        .collect();
+    assert_eq!(amounts, ["10", "20"]);
+    assert!(body.ends_with('\n'));
+}
+
+#[tokio::test]
+async fn test_movements_default_to_json_array() {
+    let response = movements_response(HeaderMap::new()).await;
+
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+    let rows: Vec<serde_json::Value> = serde_json::from_str(&body(response).await).unwrap();
+    assert_eq!(rows.len(), 2);
+}
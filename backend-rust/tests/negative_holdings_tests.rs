@@ -0,0 +1,202 @@
+mod test_helpers;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::movements::{
+    create_movement, create_movements_bulk, update_movement, BulkCreateQuery, CreateMovementRequest,
+};
+use portfoliodb_rust::models::{Investment, Portfolio};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::MovementState;
+use portfoliodb_rust::validation::FieldError;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+/// Repositories with one investment, rejecting negative holdings if `reject` is set
+async fn setup(reject: bool) -> (Repositories, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Test".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    let mut settings = repos.settings.get().await.unwrap().unwrap();
+    settings.reject_negative_holdings = reject;
+    repos.settings.update(&settings).await.unwrap();
+    (repos, investment_id)
+}
+
+fn state(repos: &Repositories) -> MovementState {
+    MovementState {
+        movement_repo: repos.movements.clone(),
+        investment_repo: repos.investments.clone(),
+        action_type_repo: repos.action_types.clone(),
+        settings_repo: repos.settings.clone(),
+    }
+}
+
+fn trade(investment_id: i64, action_id: i64, day: u32, quantity: Decimal) -> CreateMovementRequest {
+    CreateMovementRequest {
+        date: NaiveDate::from_ymd_opt(2024, 3, day),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(quantity * dec!(10.0)),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn errors(err: AppError) -> Vec<FieldError> {
+    match err {
+        AppError::Validation(errors) => errors.errors,
+        other => panic!("expected validation error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_sell_of_more_than_the_holding_is_rejected() {
+    let (repos, investment_id) = setup(true).await;
+    let create = |req| create_movement(State(state(&repos)), Json(req));
+    for (day, quantity) in [(1, dec!(10.0)), (10, dec!(5.0))] {
+        let Json(buy) = create(trade(investment_id, 1, day, quantity))
+            .await
+            .unwrap();
+        assert_eq!(buy.quantity, Some(quantity));
+    }
+
+    // Later buys do not count
+    let err = create(trade(investment_id, 2, 5, dec!(12.0)))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        errors(err),
+        vec![FieldError {
+            field: "quantity".to_string(),
+            message: "sells 12 but only 10 are held on 2024-03-05".to_string(),
+        }]
+    );
+
+    let Json(sell) = create(trade(investment_id, 2, 5, dec!(10.0)))
+        .await
+        .unwrap();
+    assert_eq!(repos.movements.find_all().await.unwrap().len(), 3);
+
+    // An update replaces the stored quantity instead of adding to it
+    let Json(updated) = update_movement(
+        State(state(&repos)),
+        Path(sell.id),
+        Json(trade(investment_id, 2, 5, dec!(8.0))),
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.quantity, Some(dec!(8.0)));
+    let err = update_movement(
+        State(state(&repos)),
+        Path(sell.id),
+        Json(trade(investment_id, 2, 5, dec!(11.0))),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(errors(err)[0].field, "quantity");
+}
+
+#[tokio::test]
+async fn test_holding_is_checked_per_portfolio() {
+    let (repos, investment_id) = setup(true).await;
+    let portfolio_id = repos
+        .portfolios
+        .create(&Portfolio {
+            id: 0,
+            name: "Broker".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+    let Json(buy) = create_movement(
+        State(state(&repos)),
+        Json(trade(investment_id, 1, 1, dec!(10.0))),
+    )
+    .await
+    .unwrap();
+    assert_eq!(buy.portfolio_id, None);
+
+    let mut sell = trade(investment_id, 2, 2, dec!(1.0));
+    sell.portfolio_id = Some(portfolio_id);
+    let err = create_movement(State(state(&repos)), Json(sell))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        errors(err)[0].message,
+        "sells 1 but only 0 are held on 2024-03-02"
+    );
+}
+
+#[tokio::test]
+async fn test_bulk_may_sell_what_it_buys() {
+    let (repos, investment_id) = setup(true).await;
+    let bulk = |reqs| {
+        create_movements_bulk(
+            State(state(&repos)),
+            Query(BulkCreateQuery::default()),
+            Json(reqs),
+        )
+    };
+
+    let err = bulk(vec![
+        trade(investment_id, 1, 1, dec!(5.0)),
+        trade(investment_id, 2, 2, dec!(3.0)),
+        trade(investment_id, 2, 3, dec!(3.0)),
+    ])
+    .await
+    .unwrap_err();
+    assert_eq!(
+        errors(err),
+        vec![FieldError {
+            field: "[2].quantity".to_string(),
+            message: "sells 3 but only 2 are held on 2024-03-03".to_string(),
+        }]
+    );
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+
+    let Json(created) = bulk(vec![
+        trade(investment_id, 2, 2, dec!(5.0)),
+        trade(investment_id, 1, 1, dec!(5.0)),
+    ])
+    .await
+    .unwrap();
+    assert_eq!(created.ids.len(), 2);
+}
+
+#[tokio::test]
+async fn test_negative_holdings_are_allowed_by_default() {
+    let (repos, investment_id) = setup(false).await;
+
+    let Json(sell) = create_movement(
+        State(state(&repos)),
+        Json(trade(investment_id, 2, 1, dec!(1.0))),
+    )
+    .await
+    .unwrap();
+    assert_eq!(sell.quantity, Some(dec!(1.0)));
+}
@@ -0,0 +1,133 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::cash_ledger::calculate_cash_balance;
+use portfoliodb_rust::services::cost_basis::{CostBasisMethod, OPENING_ACTION_ID};
+use portfoliodb_rust::services::{CostBasisCalculator, PortfolioCalculator};
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn day(month: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2019, month, d).unwrap()
+}
+
+/// Holding of 10 shares bought before 2019 for 500, with a buy and a sell afterwards
+async fn setup() -> (Repositories, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Held since 2010".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let movement = |date, action_id, quantity, amount| Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    };
+    repos
+        .movements
+        .create_many(&[
+            movement(day(1, 2), OPENING_ACTION_ID, dec!(10.0), dec!(500.0)),
+            movement(day(3, 1), 1, dec!(5.0), dec!(420.0)),
+            movement(day(7, 1), 2, dec!(12.0), dec!(1080.0)),
+        ])
+        .await
+        .unwrap();
+    for (date, price) in [
+        (day(1, 2), dec!(80.0)),
+        (day(6, 1), dec!(88.0)),
+        (day(7, 1), dec!(90.0)),
+    ] {
+        repos
+            .investment_prices
+            .create(&InvestmentPrice {
+                date: Some(date),
+                investment_id: Some(investment_id),
+                price: Some(price),
+                source: None,
+                currency: None,
+                original_price: None,
+            })
+            .await
+            .unwrap();
+    }
+    (repos, investment_id)
+}
+
+#[tokio::test]
+async fn test_opening_position_is_valued_at_the_market_price() {
+    let (repos, _) = setup().await;
+    let calculator =
+        PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone());
+
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+
+    let opening = &developments[0];
+    assert_eq!(opening.date, day(1, 2));
+    assert_eq!(opening.quantity, dec!(10.0));
+    assert_eq!(opening.value, dec!(800.0));
+    assert_eq!(developments.last().unwrap().quantity, dec!(3.0));
+}
+
+#[tokio::test]
+async fn test_opening_position_return_starts_at_its_value() {
+    let (repos, investment_id) = setup().await;
+    let calculator =
+        PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone());
+
+    let twr = calculator
+        .calculate_time_weighted_return(None, None, None, None)
+        .await
+        .unwrap();
+
+    // The price went from 80 to 90; the cost basis of 500 does not count as return
+    assert!((twr.total - 0.125).abs() < 1e-9);
+    assert_eq!(twr.investments[0].investment, investment_id);
+    assert!((twr.investments[0].twr - 0.125).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_opening_position_seeds_the_cost_basis() {
+    let (repos, _) = setup().await;
+
+    let gains = CostBasisCalculator::new(repos.movements.clone())
+        .calculate_gains(CostBasisMethod::Fifo, None)
+        .await
+        .unwrap();
+
+    // The sale takes the 10 opening shares at 50 and 2 bought at 84
+    assert_eq!(
+        gains[0].realized_gain,
+        dec!(1080.0) - dec!(500.0) - dec!(168.0)
+    );
+    assert_eq!(gains[0].quantity, dec!(3.0));
+    assert_eq!(gains[0].cost_basis, dec!(252.0));
+
+    // Only the buy and the sell moved cash
+    let movements = repos.movements.find_all().await.unwrap();
+    let balance = calculate_cash_balance(&[], &movements, None, None);
+    assert_eq!(balance.last().map(|b| b.balance), Some(dec!(660.0)));
+}
@@ -0,0 +1,169 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::reports::ReportPeriod;
+use portfoliodb_rust::services::{
+    CashLedgerService, DashboardService, PortfolioCalculator, ReportService,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn date(m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, m, d).unwrap()
+}
+
+fn movement(
+    date: NaiveDate,
+    action_id: i64,
+    investment_id: i64,
+    quantity: Decimal,
+    amount: Decimal,
+) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn service(repos: &Repositories) -> ReportService {
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    let cash_ledger = Arc::new(CashLedgerService::new(
+        repos.cash_movements.clone(),
+        repos.movements.clone(),
+    ));
+    ReportService::new(
+        Arc::new(DashboardService::new(calculator, cash_ledger)),
+        repos.movements.clone(),
+        repos.settings.clone(),
+    )
+}
+
+/// Buys in January, gains 10% by the end of the month, sells half in February and
+/// receives a payout
+async fn setup() -> Repositories {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Fund".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    for m in [
+        Movement {
+            fee: Some(dec!(1.0)),
+            ..movement(date(1, 10), 1, id, dec!(10.0), dec!(1000.0))
+        },
+        movement(date(2, 20), 2, id, dec!(5.0), dec!(600.0)),
+        movement(date(2, 25), 3, id, dec!(0.0), dec!(10.0)),
+    ] {
+        repos.movements.create(&m).await.unwrap();
+    }
+    for (day, price) in [(date(1, 31), dec!(110.0)), (date(2, 15), dec!(120.0))] {
+        repos
+            .investment_prices
+            .create(&InvestmentPrice {
+                date: Some(day),
+                investment_id: Some(id),
+                price: Some(price),
+                source: Some("test".to_string()),
+                currency: None,
+                original_price: None,
+            })
+            .await
+            .unwrap();
+    }
+    repos
+}
+
+#[tokio::test]
+async fn test_monthly_report() {
+    let repos = setup().await;
+
+    let report = service(&repos)
+        .periodic_report(ReportPeriod::Month)
+        .await
+        .unwrap();
+
+    assert_eq!(report.len(), 2);
+    let january = &report[0];
+    assert_eq!(january.start_date, date(1, 1));
+    assert_eq!(january.end_date, date(1, 31));
+    assert_eq!(january.opening_value, dec!(0));
+    assert_eq!(january.contributions, dec!(1000.0));
+    assert_eq!(january.withdrawals, dec!(0));
+    assert_eq!(january.fees, dec!(1.0));
+    assert_eq!(january.closing_value, dec!(1100.0));
+    // The fee is part of the invested amount
+    assert!((january.twr - (1100.0 / 1001.0 - 1.0)).abs() < 1e-9);
+
+    let february = &report[1];
+    assert_eq!(february.start_date, date(2, 1));
+    assert_eq!(february.end_date, date(2, 25));
+    assert_eq!(february.opening_value, dec!(1100.0));
+    assert_eq!(february.contributions, dec!(0));
+    assert_eq!(february.withdrawals, dec!(600.0));
+    assert_eq!(february.dividends, dec!(10.0));
+    assert_eq!(february.closing_value, dec!(600.0));
+}
+
+#[tokio::test]
+async fn test_yearly_report() {
+    let repos = setup().await;
+
+    let report = service(&repos)
+        .periodic_report(ReportPeriod::Year)
+        .await
+        .unwrap();
+
+    assert_eq!(report.len(), 1);
+    let year = &report[0];
+    assert_eq!(year.start_date, date(1, 1));
+    assert_eq!(year.end_date, date(2, 25));
+    assert_eq!(year.opening_value, dec!(0));
+    assert_eq!(year.contributions, dec!(1000.0));
+    assert_eq!(year.withdrawals, dec!(600.0));
+    assert_eq!(year.dividends, dec!(10.0));
+    assert_eq!(year.fees, dec!(1.0));
+    assert_eq!(year.closing_value, dec!(600.0));
+}
+
+#[tokio::test]
+async fn test_periodic_report_without_movements() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let report = service(&repos)
+        .periodic_report(ReportPeriod::Month)
+        .await
+        .unwrap();
+
+    assert!(report.is_empty());
+}
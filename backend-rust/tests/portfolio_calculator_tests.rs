@@ -1,74 +1,16 @@
 use chrono::NaiveDate;
 use portfoliodb_rust::models::{InvestmentPrice, Movement};
-use portfoliodb_rust::repository::traits::{InvestmentPriceRepository, MovementRepository};
+use portfoliodb_rust::repository::{InMemoryInvestmentPriceRepository, InMemoryMovementRepository};
+use portfoliodb_rust::services::cost_basis::{
+    SPLIT_ACTION_ID, TRANSFER_IN_ACTION_ID, TRANSFER_OUT_ACTION_ID,
+};
+use portfoliodb_rust::services::portfolio_calculator::{DevelopmentFill, Granularity};
 use portfoliodb_rust::services::PortfolioCalculator;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashSet;
 use std::sync::Arc;
 
-// Mock repository for movements
-struct MockMovementRepository {
-    movements: Vec<Movement>,
-}
-
-impl MockMovementRepository {
-    fn new(movements: Vec<Movement>) -> Self {
-        Self { movements }
-    }
-}
-
-#[async_trait::async_trait]
-impl MovementRepository for MockMovementRepository {
-    async fn find_all(&self) -> portfoliodb_rust::error::Result<Vec<Movement>> {
-        Ok(self.movements.clone())
-    }
-
-    async fn find_by_id(&self, _id: i64) -> portfoliodb_rust::error::Result<Option<Movement>> {
-        unimplemented!()
-    }
-
-    async fn create(&self, _movement: &Movement) -> portfoliodb_rust::error::Result<i64> {
-        unimplemented!()
-    }
-
-    async fn update(&self, _id: i64, _movement: &Movement) -> portfoliodb_rust::error::Result<()> {
-        unimplemented!()
-    }
-
-    async fn delete(&self, _id: i64) -> portfoliodb_rust::error::Result<()> {
-        unimplemented!()
-    }
-}
-
-// Mock repository for investment prices
-struct MockInvestmentPriceRepository {
-    prices: Vec<InvestmentPrice>,
-}
-
-impl MockInvestmentPriceRepository {
-    fn new(prices: Vec<InvestmentPrice>) -> Self {
-        Self { prices }
-    }
-}
-
-#[async_trait::async_trait]
-impl InvestmentPriceRepository for MockInvestmentPriceRepository {
-    async fn find_all(
-        &self,
-        _investment_id: Option<i64>,
-        _start_date: Option<NaiveDate>,
-        _end_date: Option<NaiveDate>,
-    ) -> portfoliodb_rust::error::Result<Vec<InvestmentPrice>> {
-        Ok(self.prices.clone())
-    }
-
-    async fn create(&self, _price: &InvestmentPrice) -> portfoliodb_rust::error::Result<()> {
-        unimplemented!()
-    }
-
-    async fn upsert(&self, _price: &InvestmentPrice) -> portfoliodb_rust::error::Result<()> {
-        unimplemented!()
-    }
-}
-
 #[tokio::test]
 async fn test_portfolio_calculator_simple_buy() {
     // Arrange: One buy transaction
@@ -77,15 +19,18 @@ async fn test_portfolio_calculator_simple_buy() {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
         action_id: Some(1), // Buy
         investment_id: Some(1),
-        quantity: Some(10.0),
-        amount: Some(100.0), // 10 shares at $10 each
-        fee: Some(0.0),
+        quantity: Some(dec!(10.0)),
+        amount: Some(dec!(100.0)), // 10 shares at $10 each
+        fee: Some(dec!(0.0)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     }];
 
     let prices = vec![];
 
-    let movement_repo = Arc::new(MockMovementRepository::new(movements));
-    let price_repo = Arc::new(MockInvestmentPriceRepository::new(prices));
+    let movement_repo = Arc::new(InMemoryMovementRepository::with_movements(movements));
+    let price_repo = Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices));
 
     let calculator = PortfolioCalculator::new(movement_repo, price_repo);
 
@@ -95,9 +40,9 @@ async fn test_portfolio_calculator_simple_buy() {
     // Assert
     assert_eq!(developments.len(), 1);
     assert_eq!(developments[0].investment, 1);
-    assert_eq!(developments[0].quantity, 10.0);
-    assert_eq!(developments[0].price, 10.0); // Transaction price
-    assert_eq!(developments[0].value, 100.0);
+    assert_eq!(developments[0].quantity, dec!(10.0));
+    assert_eq!(developments[0].price, dec!(10.0)); // Transaction price
+    assert_eq!(developments[0].value, dec!(100.0));
 }
 
 #[tokio::test]
@@ -109,25 +54,31 @@ async fn test_portfolio_calculator_buy_and_sell() {
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
             action_id: Some(1), // Buy
             investment_id: Some(1),
-            quantity: Some(10.0),
-            amount: Some(100.0),
-            fee: Some(0.0),
+            quantity: Some(dec!(10.0)),
+            amount: Some(dec!(100.0)),
+            fee: Some(dec!(0.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
         Movement {
             id: 2,
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
             action_id: Some(2), // Sell
             investment_id: Some(1),
-            quantity: Some(3.0),
-            amount: Some(36.0), // 3 shares at $12 each
-            fee: Some(0.0),
+            quantity: Some(dec!(3.0)),
+            amount: Some(dec!(36.0)), // 3 shares at $12 each
+            fee: Some(dec!(0.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
     ];
 
     let prices = vec![];
 
-    let movement_repo = Arc::new(MockMovementRepository::new(movements));
-    let price_repo = Arc::new(MockInvestmentPriceRepository::new(prices));
+    let movement_repo = Arc::new(InMemoryMovementRepository::with_movements(movements));
+    let price_repo = Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices));
 
     let calculator = PortfolioCalculator::new(movement_repo, price_repo);
 
@@ -138,12 +89,12 @@ async fn test_portfolio_calculator_buy_and_sell() {
     assert_eq!(developments.len(), 2);
 
     // First development: after buy
-    assert_eq!(developments[0].quantity, 10.0);
-    assert_eq!(developments[0].price, 10.0);
+    assert_eq!(developments[0].quantity, dec!(10.0));
+    assert_eq!(developments[0].price, dec!(10.0));
 
     // Second development: after sell
-    assert_eq!(developments[1].quantity, 7.0); // 10 - 3
-    assert_eq!(developments[1].price, 12.0); // Transaction price from sell
+    assert_eq!(developments[1].quantity, dec!(7.0)); // 10 - 3
+    assert_eq!(developments[1].price, dec!(12.0)); // Transaction price from sell
 }
 
 #[tokio::test]
@@ -154,28 +105,35 @@ async fn test_portfolio_calculator_with_quote_prices() {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
         action_id: Some(1), // Buy
         investment_id: Some(1),
-        quantity: Some(10.0),
-        amount: Some(100.0),
-        fee: Some(0.0),
+        quantity: Some(dec!(10.0)),
+        amount: Some(dec!(100.0)),
+        fee: Some(dec!(0.0)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     }];
 
     let prices = vec![
         InvestmentPrice {
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
             investment_id: Some(1),
-            price: Some(10.5), // Quote price slightly higher
+            price: Some(dec!(10.5)), // Quote price slightly higher
             source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
         },
         InvestmentPrice {
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
             investment_id: Some(1),
-            price: Some(11.0), // Price went up
+            price: Some(dec!(11.0)), // Price went up
             source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
         },
     ];
 
-    let movement_repo = Arc::new(MockMovementRepository::new(movements));
-    let price_repo = Arc::new(MockInvestmentPriceRepository::new(prices));
+    let movement_repo = Arc::new(InMemoryMovementRepository::with_movements(movements));
+    let price_repo = Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices));
 
     let calculator = PortfolioCalculator::new(movement_repo, price_repo);
 
@@ -186,12 +144,12 @@ async fn test_portfolio_calculator_with_quote_prices() {
     assert_eq!(developments.len(), 2);
 
     // First development: quote price preferred over transaction price
-    assert_eq!(developments[0].price, 10.5);
-    assert_eq!(developments[0].value, 105.0); // 10 * 10.5
+    assert_eq!(developments[0].price, dec!(10.5));
+    assert_eq!(developments[0].value, dec!(105.0)); // 10 * 10.5
 
     // Second development: only quote price available
-    assert_eq!(developments[1].price, 11.0);
-    assert_eq!(developments[1].value, 110.0); // 10 * 11.0
+    assert_eq!(developments[1].price, dec!(11.0));
+    assert_eq!(developments[1].value, dec!(110.0)); // 10 * 11.0
 }
 
 #[tokio::test]
@@ -203,25 +161,31 @@ async fn test_portfolio_calculator_date_filtering() {
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
             action_id: Some(1),
             investment_id: Some(1),
-            quantity: Some(10.0),
-            amount: Some(100.0),
-            fee: Some(0.0),
+            quantity: Some(dec!(10.0)),
+            amount: Some(dec!(100.0)),
+            fee: Some(dec!(0.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
         Movement {
             id: 2,
             date: Some(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()),
             action_id: Some(1),
             investment_id: Some(1),
-            quantity: Some(5.0),
-            amount: Some(55.0),
-            fee: Some(0.0),
+            quantity: Some(dec!(5.0)),
+            amount: Some(dec!(55.0)),
+            fee: Some(dec!(0.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
     ];
 
     let prices = vec![];
 
-    let movement_repo = Arc::new(MockMovementRepository::new(movements));
-    let price_repo = Arc::new(MockInvestmentPriceRepository::new(prices));
+    let movement_repo = Arc::new(InMemoryMovementRepository::with_movements(movements));
+    let price_repo = Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices));
 
     let calculator = PortfolioCalculator::new(movement_repo, price_repo);
 
@@ -250,25 +214,31 @@ async fn test_portfolio_calculator_multiple_investments() {
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
             action_id: Some(1),
             investment_id: Some(1),
-            quantity: Some(10.0),
-            amount: Some(100.0),
-            fee: Some(0.0),
+            quantity: Some(dec!(10.0)),
+            amount: Some(dec!(100.0)),
+            fee: Some(dec!(0.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
         Movement {
             id: 2,
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
             action_id: Some(1),
             investment_id: Some(2),
-            quantity: Some(5.0),
-            amount: Some(50.0),
-            fee: Some(0.0),
+            quantity: Some(dec!(5.0)),
+            amount: Some(dec!(50.0)),
+            fee: Some(dec!(0.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
     ];
 
     let prices = vec![];
 
-    let movement_repo = Arc::new(MockMovementRepository::new(movements));
-    let price_repo = Arc::new(MockInvestmentPriceRepository::new(prices));
+    let movement_repo = Arc::new(InMemoryMovementRepository::with_movements(movements));
+    let price_repo = Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices));
 
     let calculator = PortfolioCalculator::new(movement_repo, price_repo);
 
@@ -279,10 +249,10 @@ async fn test_portfolio_calculator_multiple_investments() {
     assert_eq!(developments.len(), 2);
 
     let inv1_dev = developments.iter().find(|d| d.investment == 1).unwrap();
-    assert_eq!(inv1_dev.quantity, 10.0);
+    assert_eq!(inv1_dev.quantity, dec!(10.0));
 
     let inv2_dev = developments.iter().find(|d| d.investment == 2).unwrap();
-    assert_eq!(inv2_dev.quantity, 5.0);
+    assert_eq!(inv2_dev.quantity, dec!(5.0));
 }
 
 #[tokio::test]
@@ -293,28 +263,35 @@ async fn test_portfolio_calculator_last_known_price() {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
         action_id: Some(1),
         investment_id: Some(1),
-        quantity: Some(10.0),
-        amount: Some(100.0),
-        fee: Some(0.0),
+        quantity: Some(dec!(10.0)),
+        amount: Some(dec!(100.0)),
+        fee: Some(dec!(0.0)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     }];
 
     let prices = vec![
         InvestmentPrice {
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
             investment_id: Some(1),
-            price: Some(11.0),
+            price: Some(dec!(11.0)),
             source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
         },
         InvestmentPrice {
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 3).unwrap()),
             investment_id: Some(1),
-            price: Some(12.0),
+            price: Some(dec!(12.0)),
             source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
         },
     ];
 
-    let movement_repo = Arc::new(MockMovementRepository::new(movements));
-    let price_repo = Arc::new(MockInvestmentPriceRepository::new(prices));
+    let movement_repo = Arc::new(InMemoryMovementRepository::with_movements(movements));
+    let price_repo = Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices));
 
     let calculator = PortfolioCalculator::new(movement_repo, price_repo);
 
@@ -325,13 +302,13 @@ async fn test_portfolio_calculator_last_known_price() {
     assert_eq!(developments.len(), 3);
 
     // Day 1: transaction price
-    assert_eq!(developments[0].price, 10.0);
+    assert_eq!(developments[0].price, dec!(10.0));
 
     // Day 2: quote price
-    assert_eq!(developments[1].price, 11.0);
+    assert_eq!(developments[1].price, dec!(11.0));
 
     // Day 3: quote price (not last known from day 2)
-    assert_eq!(developments[2].price, 12.0);
+    assert_eq!(developments[2].price, dec!(12.0));
 }
 
 #[tokio::test]
@@ -345,9 +322,12 @@ async fn test_portfolio_calculator_realistic_scenario() {
             date: Some(NaiveDate::from_ymd_opt(2025, 4, 1).unwrap()),
             action_id: Some(1), // Buy
             investment_id: Some(1),
-            quantity: Some(10.0),
-            amount: Some(1000.0),
-            fee: Some(1.0),
+            quantity: Some(dec!(10.0)),
+            amount: Some(dec!(1000.0)),
+            fee: Some(dec!(1.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
         // Day 2: Sell 3 shares at $110 each
         Movement {
@@ -355,9 +335,12 @@ async fn test_portfolio_calculator_realistic_scenario() {
             date: Some(NaiveDate::from_ymd_opt(2025, 4, 2).unwrap()),
             action_id: Some(2), // Sell
             investment_id: Some(1),
-            quantity: Some(3.0),
-            amount: Some(330.0), // Positive amount for sell
-            fee: Some(0.5),
+            quantity: Some(dec!(3.0)),
+            amount: Some(dec!(330.0)), // Positive amount for sell
+            fee: Some(dec!(0.5)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
         // Day 3: Buy 5 more shares at $105 each
         Movement {
@@ -365,9 +348,12 @@ async fn test_portfolio_calculator_realistic_scenario() {
             date: Some(NaiveDate::from_ymd_opt(2025, 4, 3).unwrap()),
             action_id: Some(1), // Buy
             investment_id: Some(1),
-            quantity: Some(5.0),
-            amount: Some(525.0),
-            fee: Some(1.0),
+            quantity: Some(dec!(5.0)),
+            amount: Some(dec!(525.0)),
+            fee: Some(dec!(1.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
         // Day 4: Payout (dividend) - should not affect quantity
         Movement {
@@ -375,9 +361,12 @@ async fn test_portfolio_calculator_realistic_scenario() {
             date: Some(NaiveDate::from_ymd_opt(2025, 4, 4).unwrap()),
             action_id: Some(3), // Payout
             investment_id: Some(1),
-            quantity: Some(0.0),
-            amount: Some(50.0),
-            fee: Some(0.0),
+            quantity: Some(dec!(0.0)),
+            amount: Some(dec!(50.0)),
+            fee: Some(dec!(0.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
         },
     ];
 
@@ -386,25 +375,31 @@ async fn test_portfolio_calculator_realistic_scenario() {
         InvestmentPrice {
             date: Some(NaiveDate::from_ymd_opt(2025, 4, 2).unwrap()),
             investment_id: Some(1),
-            price: Some(110.0),
+            price: Some(dec!(110.0)),
             source: Some("market".to_string()),
+            currency: None,
+            original_price: None,
         },
         InvestmentPrice {
             date: Some(NaiveDate::from_ymd_opt(2025, 4, 3).unwrap()),
             investment_id: Some(1),
-            price: Some(105.0),
+            price: Some(dec!(105.0)),
             source: Some("market".to_string()),
+            currency: None,
+            original_price: None,
         },
         InvestmentPrice {
             date: Some(NaiveDate::from_ymd_opt(2025, 4, 4).unwrap()),
             investment_id: Some(1),
-            price: Some(108.0),
+            price: Some(dec!(108.0)),
             source: Some("market".to_string()),
+            currency: None,
+            original_price: None,
         },
     ];
 
-    let movement_repo = Arc::new(MockMovementRepository::new(movements));
-    let price_repo = Arc::new(MockInvestmentPriceRepository::new(prices));
+    let movement_repo = Arc::new(InMemoryMovementRepository::with_movements(movements));
+    let price_repo = Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices));
 
     let calculator = PortfolioCalculator::new(movement_repo, price_repo);
 
@@ -420,15 +415,18 @@ async fn test_portfolio_calculator_realistic_scenario() {
         NaiveDate::from_ymd_opt(2025, 4, 1).unwrap()
     );
     assert_eq!(
-        developments[0].quantity, 10.0,
+        developments[0].quantity,
+        dec!(10.0),
         "Day 1: Should have 10 shares"
     );
     assert_eq!(
-        developments[0].price, 100.0,
+        developments[0].price,
+        dec!(100.0),
         "Day 1: Transaction price should be $100"
     );
     assert_eq!(
-        developments[0].value, 1000.0,
+        developments[0].value,
+        dec!(1000.0),
         "Day 1: Portfolio value should be $1000"
     );
 
@@ -438,15 +436,18 @@ async fn test_portfolio_calculator_realistic_scenario() {
         NaiveDate::from_ymd_opt(2025, 4, 2).unwrap()
     );
     assert_eq!(
-        developments[1].quantity, 7.0,
+        developments[1].quantity,
+        dec!(7.0),
         "Day 2: Should have 7 shares (10 - 3)"
     );
     assert_eq!(
-        developments[1].price, 110.0,
+        developments[1].price,
+        dec!(110.0),
         "Day 2: Market price should be $110"
     );
     assert_eq!(
-        developments[1].value, 770.0,
+        developments[1].value,
+        dec!(770.0),
         "Day 2: Portfolio value should be $770 (7 * 110)"
     );
 
@@ -456,15 +457,18 @@ async fn test_portfolio_calculator_realistic_scenario() {
         NaiveDate::from_ymd_opt(2025, 4, 3).unwrap()
     );
     assert_eq!(
-        developments[2].quantity, 12.0,
+        developments[2].quantity,
+        dec!(12.0),
         "Day 3: Should have 12 shares (7 + 5)"
     );
     assert_eq!(
-        developments[2].price, 105.0,
+        developments[2].price,
+        dec!(105.0),
         "Day 3: Market price should be $105"
     );
     assert_eq!(
-        developments[2].value, 1260.0,
+        developments[2].value,
+        dec!(1260.0),
         "Day 3: Portfolio value should be $1260 (12 * 105)"
     );
 
@@ -474,25 +478,631 @@ async fn test_portfolio_calculator_realistic_scenario() {
         NaiveDate::from_ymd_opt(2025, 4, 4).unwrap()
     );
     assert_eq!(
-        developments[3].quantity, 12.0,
+        developments[3].quantity,
+        dec!(12.0),
         "Day 4: Should still have 12 shares (payout doesn't change quantity)"
     );
     assert_eq!(
-        developments[3].price, 108.0,
+        developments[3].price,
+        dec!(108.0),
         "Day 4: Market price should be $108"
     );
     assert_eq!(
-        developments[3].value, 1296.0,
+        developments[3].value,
+        dec!(1296.0),
         "Day 4: Portfolio value should be $1296 (12 * 108)"
     );
 
     // Verify portfolio value is always positive
     for dev in &developments {
         assert!(
-            dev.value >= 0.0,
+            dev.value >= dec!(0.0),
             "Portfolio value should never be negative, got {} on {:?}",
             dev.value,
             dev.date
         );
     }
 }
+
+fn buy(
+    id: i64,
+    investment_id: i64,
+    date: NaiveDate,
+    quantity: Decimal,
+    amount: Decimal,
+) -> Movement {
+    Movement {
+        id,
+        date: Some(date),
+        action_id: Some(1),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: Some(dec!(0.0)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn quote(investment_id: i64, date: NaiveDate, price: Decimal) -> InvestmentPrice {
+    InvestmentPrice {
+        date: Some(date),
+        investment_id: Some(investment_id),
+        price: Some(price),
+        source: Some("test".to_string()),
+        currency: None,
+        original_price: None,
+    }
+}
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+#[tokio::test]
+async fn test_time_weighted_return_simple_growth() {
+    // Arrange: Buy at 10, price rises 10% twice
+    let movements = vec![buy(1, 1, day(1), dec!(10.0), dec!(100.0))];
+    let prices = vec![
+        quote(1, day(1), dec!(10.0)),
+        quote(1, day(2), dec!(11.0)),
+        quote(1, day(3), dec!(12.1)),
+    ];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    // Act
+    let twr = calculator
+        .calculate_time_weighted_return(None, None, None, None)
+        .await
+        .unwrap();
+
+    // Assert
+    assert!((twr.total - 0.21).abs() < 1e-9);
+    assert_eq!(twr.investments.len(), 1);
+    assert!((twr.investments[0].twr - 0.21).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_time_weighted_return_includes_fees_and_taxes() {
+    // Arrange: Paying 10 in fees and taxes on a buy of 100 eats up the 10% price gain
+    let movements = vec![Movement {
+        fee: Some(dec!(4.0)),
+        tax: Some(dec!(6.0)),
+        ..buy(1, 1, day(1), dec!(10.0), dec!(100.0))
+    }];
+    let prices = vec![quote(1, day(1), dec!(10.0)), quote(1, day(2), dec!(11.0))];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    // Act
+    let twr = calculator
+        .calculate_time_weighted_return(None, None, None, None)
+        .await
+        .unwrap();
+
+    // Assert
+    assert!(twr.total.abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_time_weighted_return_ignores_additional_contributions() {
+    // Arrange: A second buy at the day's price must not count as performance
+    let movements = vec![
+        buy(1, 1, day(1), dec!(10.0), dec!(100.0)),
+        buy(2, 1, day(2), dec!(10.0), dec!(110.0)),
+    ];
+    let prices = vec![quote(1, day(1), dec!(10.0)), quote(1, day(2), dec!(11.0))];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    // Act
+    let twr = calculator
+        .calculate_time_weighted_return(None, None, None, None)
+        .await
+        .unwrap();
+
+    // Assert: 10% price gain regardless of the doubled position
+    assert!((twr.total - 0.10).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_time_weighted_return_with_start_date_and_two_investments() {
+    // Arrange: Investment 1 gains 10% after the start date, investment 2 stays flat
+    let movements = vec![
+        buy(1, 1, day(1), dec!(10.0), dec!(100.0)),
+        buy(2, 2, day(1), dec!(10.0), dec!(100.0)),
+    ];
+    let prices = vec![
+        quote(1, day(1), dec!(10.0)),
+        quote(1, day(2), dec!(20.0)),
+        quote(1, day(3), dec!(22.0)),
+        quote(2, day(1), dec!(10.0)),
+        quote(2, day(3), dec!(10.0)),
+    ];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    // Act
+    let twr = calculator
+        .calculate_time_weighted_return(None, Some(day(2)), None, None)
+        .await
+        .unwrap();
+
+    // Assert
+    let inv1 = twr.investments.iter().find(|r| r.investment == 1).unwrap();
+    let inv2 = twr.investments.iter().find(|r| r.investment == 2).unwrap();
+    assert!((inv1.twr - 0.10).abs() < 1e-9);
+    assert!(inv2.twr.abs() < 1e-9);
+    // Portfolio goes from 200 + 100 = 300 to 220 + 100 = 320
+    assert!((twr.total - 20.0 / 300.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_returns_and_totals_filtered_by_investments() {
+    // Investment 1 doubles, investment 2 stays flat
+    let movements = vec![
+        buy(1, 1, day(1), dec!(10.0), dec!(100.0)),
+        buy(2, 2, day(1), dec!(10.0), dec!(100.0)),
+    ];
+    let prices = vec![
+        quote(1, day(1), dec!(10.0)),
+        quote(1, day(2), dec!(20.0)),
+        quote(2, day(1), dec!(10.0)),
+        quote(2, day(2), dec!(10.0)),
+    ];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+    let only_first = HashSet::from([1]);
+
+    let twr = calculator
+        .calculate_time_weighted_return(Some(&only_first), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(twr.investments.len(), 1);
+    assert!((twr.total - 1.0).abs() < 1e-9);
+
+    let totals = calculator
+        .calculate_total_developments(None, Some(&only_first), None, None)
+        .await
+        .unwrap();
+    let values: Vec<Decimal> = totals.iter().map(|t| t.value).collect();
+    assert_eq!(values, vec![dec!(100.0), dec!(200.0)]);
+}
+
+#[tokio::test]
+async fn test_portfolio_developments_filter_by_portfolio() {
+    let movements = vec![
+        Movement {
+            portfolio_id: Some(1),
+            ..buy(1, 1, day(1), dec!(10.0), dec!(100.0))
+        },
+        Movement {
+            portfolio_id: Some(2),
+            ..buy(2, 2, day(1), dec!(5.0), dec!(250.0))
+        },
+    ];
+    let prices = vec![quote(1, day(2), dec!(11.0)), quote(2, day(2), dec!(55.0))];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    let developments = calculator
+        .calculate_portfolio_developments(Some(1), None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(developments.len(), 2);
+    assert!(developments.iter().all(|d| d.investment == 1));
+    assert_eq!(developments[1].value, dec!(110.0));
+
+    let all = calculator
+        .calculate_portfolio_developments(None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 4);
+}
+
+#[tokio::test]
+async fn test_developments_skip_investments_without_movements() {
+    // Investment 2 is only on the watchlist: quotes are fetched, but it is never held
+    let movements = vec![buy(1, 1, day(1), dec!(10.0), dec!(100.0))];
+    let prices = vec![
+        quote(1, day(2), dec!(11.0)),
+        quote(2, day(2), dec!(55.0)),
+        quote(2, day(3), dec!(60.0)),
+    ];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+    assert_eq!(developments.len(), 2);
+    assert!(developments.iter().all(|d| d.investment == 1));
+
+    // No valuation date of the watchlist investment enters the totals
+    let totals = calculator
+        .calculate_total_developments(None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(totals.last().unwrap().date, day(2));
+}
+
+#[tokio::test]
+async fn test_investment_developments_match_the_full_calculation() {
+    let movements = vec![
+        Movement {
+            portfolio_id: Some(1),
+            ..buy(1, 1, day(1), dec!(10.0), dec!(100.0))
+        },
+        Movement {
+            portfolio_id: Some(2),
+            ..buy(2, 1, day(3), dec!(5.0), dec!(60.0))
+        },
+        buy(3, 2, day(2), dec!(1.0), dec!(50.0)),
+    ];
+    let prices = vec![
+        quote(1, day(2), dec!(11.0)),
+        quote(1, day(4), dec!(13.0)),
+        quote(2, day(4), dec!(55.0)),
+    ];
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    let mut expected = calculator.calculate_developments(None, None).await.unwrap();
+    expected.retain(|d| d.investment == 1);
+    let single = calculator
+        .calculate_investment_developments(1, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(single, expected);
+    assert_eq!(single.last().unwrap().value, dec!(195.0));
+
+    let in_portfolio = calculator
+        .calculate_investment_developments(1, Some(2), Some(day(4)), None)
+        .await
+        .unwrap();
+    assert_eq!(in_portfolio.len(), 1);
+    assert_eq!(in_portfolio[0].quantity, dec!(5.0));
+
+    let filled = calculator
+        .calculate_filled_developments(
+            None,
+            Some(1),
+            Some(day(2)),
+            Some(day(5)),
+            DevelopmentFill::Daily,
+        )
+        .await
+        .unwrap();
+    assert!(filled.iter().all(|d| d.investment == 1));
+    assert_eq!(filled.first().unwrap().date, day(2));
+    assert_eq!(filled.last().unwrap().date, day(5));
+    assert_eq!(filled.len(), 4);
+}
+
+#[tokio::test]
+async fn test_portfolio_developments_with_split() {
+    // Buy 10 @ 100, 1:4 split on day 5, sell 8 on day 8
+    let movements = vec![
+        buy(1, 1, day(1), dec!(10.0), dec!(1000.0)),
+        Movement {
+            action_id: Some(SPLIT_ACTION_ID),
+            amount: None,
+            ..buy(2, 1, day(5), dec!(4.0), dec!(0.0))
+        },
+        Movement {
+            action_id: Some(2),
+            ..buy(3, 1, day(8), dec!(8.0), dec!(240.0))
+        },
+    ];
+    let prices = vec![quote(1, day(3), dec!(110.0)), quote(1, day(6), dec!(28.0))];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+    let on = |d: u32| developments.iter().find(|dev| dev.date == day(d)).unwrap();
+
+    assert_eq!(developments.len(), 5);
+    assert_eq!(on(3).quantity, dec!(10.0));
+    // Without a quote on the split day, the last price is adjusted by the split
+    assert_eq!(on(5).quantity, dec!(40.0));
+    assert_eq!(on(5).price, dec!(27.5));
+    assert_eq!(on(5).value, dec!(1100.0));
+    assert_eq!(on(6).value, dec!(1120.0));
+    assert_eq!(on(8).quantity, dec!(32.0));
+    assert_eq!(on(8).price, dec!(30.0));
+}
+
+#[tokio::test]
+async fn test_portfolio_developments_with_transfer() {
+    // Buy 10 in portfolio 1, move 4 to portfolio 2 on day 3
+    let transfer = |id: i64, action_id: i64, portfolio_id: i64| Movement {
+        action_id: Some(action_id),
+        amount: None,
+        portfolio_id: Some(portfolio_id),
+        ..buy(id, 1, day(3), dec!(4.0), dec!(0.0))
+    };
+    let movements = vec![
+        Movement {
+            portfolio_id: Some(1),
+            ..buy(1, 1, day(1), dec!(10.0), dec!(100.0))
+        },
+        transfer(2, TRANSFER_OUT_ACTION_ID, 1),
+        transfer(3, TRANSFER_IN_ACTION_ID, 2),
+    ];
+    let prices = vec![quote(1, day(4), dec!(12.0))];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    let all = calculator.calculate_developments(None, None).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[1].quantity, dec!(10.0));
+    assert_eq!(all[1].value, dec!(120.0));
+
+    let source = calculator
+        .calculate_portfolio_developments(Some(1), None, None)
+        .await
+        .unwrap();
+    assert_eq!(source.last().unwrap().quantity, dec!(6.0));
+
+    let target = calculator
+        .calculate_portfolio_developments(Some(2), None, None)
+        .await
+        .unwrap();
+    assert_eq!(target.len(), 1);
+    assert_eq!(target[0].quantity, dec!(4.0));
+    assert_eq!(target[0].value, dec!(48.0));
+
+    // Transfers are no cash flows, so the return is the price change only
+    let twr = calculator
+        .calculate_time_weighted_return(None, None, None, None)
+        .await
+        .unwrap();
+    assert!((twr.total - 0.2).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_total_developments_forward_fill_prices() {
+    // Investment 1 is quoted on days 1 and 4, investment 2 on days 2 and 3
+    let movements = vec![
+        buy(1, 1, day(1), dec!(10.0), dec!(100.0)),
+        buy(2, 2, day(2), dec!(5.0), dec!(100.0)),
+    ];
+    let prices = vec![quote(1, day(4), dec!(12.0)), quote(2, day(3), dec!(22.0))];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    let totals = calculator
+        .calculate_total_developments(None, None, None, Some(day(5)))
+        .await
+        .unwrap();
+    let values: Vec<(NaiveDate, Decimal)> = totals.iter().map(|t| (t.date, t.value)).collect();
+
+    assert_eq!(
+        values,
+        vec![
+            (day(1), dec!(100.0)),
+            (day(2), dec!(200.0)),
+            (day(3), dec!(210.0)),
+            (day(4), dec!(230.0)),
+            (day(5), dec!(230.0)),
+        ]
+    );
+
+    // Values from before the start date are carried into the period
+    let totals = calculator
+        .calculate_total_developments(None, None, Some(day(3)), None)
+        .await
+        .unwrap();
+    assert_eq!(totals.len(), 2);
+    assert_eq!(totals[0].value, dec!(210.0));
+}
+
+#[tokio::test]
+async fn test_daily_filled_developments() {
+    // Investment 1 is held until the end, investment 2 is sold on day 3
+    let movements = vec![
+        buy(1, 1, day(1), dec!(10.0), dec!(100.0)),
+        buy(2, 2, day(2), dec!(5.0), dec!(100.0)),
+        Movement {
+            id: 3,
+            date: Some(day(3)),
+            action_id: Some(2),
+            investment_id: Some(2),
+            quantity: Some(dec!(5.0)),
+            amount: Some(dec!(110.0)),
+            fee: Some(dec!(0.0)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        },
+    ];
+    let prices = vec![quote(1, day(3), dec!(12.0))];
+
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    let developments = calculator
+        .calculate_filled_developments(None, None, None, Some(day(5)), DevelopmentFill::Daily)
+        .await
+        .unwrap();
+    let points: Vec<(i64, NaiveDate, Decimal, Decimal)> = developments
+        .iter()
+        .map(|d| (d.investment, d.date, d.quantity, d.price))
+        .collect();
+
+    assert_eq!(
+        points,
+        vec![
+            (1, day(1), dec!(10.0), dec!(10.0)),
+            (1, day(2), dec!(10.0), dec!(10.0)),
+            (1, day(3), dec!(10.0), dec!(12.0)),
+            (1, day(4), dec!(10.0), dec!(12.0)),
+            (1, day(5), dec!(10.0), dec!(12.0)),
+            (2, day(2), dec!(5.0), dec!(20.0)),
+            (2, day(3), dec!(0.0), dec!(22.0)),
+        ]
+    );
+
+    // Positions held before the start date are filled into the period
+    let developments = calculator
+        .calculate_filled_developments(
+            None,
+            None,
+            Some(day(4)),
+            Some(day(5)),
+            DevelopmentFill::Daily,
+        )
+        .await
+        .unwrap();
+    assert_eq!(developments.len(), 2);
+    assert!(developments.iter().all(|d| d.investment == 1));
+}
+
+#[tokio::test]
+async fn test_resample_developments_weekly_and_monthly() {
+    // 2024-01-01 is a Monday; quotes on Jan 3, Jan 10, Jan 31 and Feb 2
+    let movements = vec![buy(1, 1, day(1), dec!(10.0), dec!(100.0))];
+    let prices = vec![
+        quote(1, day(3), dec!(11.0)),
+        quote(1, day(10), dec!(12.0)),
+        quote(1, day(31), dec!(13.0)),
+        quote(1, NaiveDate::from_ymd_opt(2024, 2, 2).unwrap(), dec!(14.0)),
+    ];
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+    let developments = calculator.calculate_developments(None, None).await.unwrap();
+
+    let weekly =
+        PortfolioCalculator::resample_developments(developments.clone(), Granularity::Weekly);
+    let dates: Vec<NaiveDate> = weekly.iter().map(|d| d.date).collect();
+    assert_eq!(
+        dates,
+        vec![
+            day(3),
+            day(10),
+            NaiveDate::from_ymd_opt(2024, 2, 2).unwrap()
+        ]
+    );
+
+    let monthly = PortfolioCalculator::resample_developments(developments, Granularity::Monthly);
+    let prices: Vec<Decimal> = monthly.iter().map(|d| d.price).collect();
+    assert_eq!(prices, vec![dec!(13.0), dec!(14.0)]);
+
+    let totals = calculator
+        .calculate_total_developments(None, None, None, None)
+        .await
+        .unwrap();
+    let monthly = PortfolioCalculator::resample_totals(totals, Granularity::Monthly);
+    let values: Vec<(NaiveDate, Decimal)> = monthly.iter().map(|t| (t.date, t.value)).collect();
+    assert_eq!(
+        values,
+        vec![
+            (day(31), dec!(130.0)),
+            (NaiveDate::from_ymd_opt(2024, 2, 2).unwrap(), dec!(140.0))
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_benchmark_comparison_ignores_contributions() {
+    // Investment 9 is the benchmark and is not held
+    let movements = vec![
+        buy(1, 1, day(1), dec!(10.0), dec!(100.0)),
+        buy(2, 1, day(5), dec!(10.0), dec!(120.0)),
+    ];
+    let prices = vec![
+        quote(1, day(3), dec!(12.0)),
+        quote(1, day(6), dec!(13.2)),
+        quote(9, day(1), dec!(50.0)),
+        quote(9, day(4), dec!(55.0)),
+    ];
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    let comparison = calculator
+        .calculate_benchmark_comparison(9, None, Some(day(2)), None)
+        .await
+        .unwrap();
+
+    assert_eq!(comparison.benchmark_investment_id, 9);
+    let expected = [
+        (day(2), 100.0, 100.0),
+        (day(3), 120.0, 100.0),
+        (day(4), 120.0, 110.0),
+        // Buying more shares does not change the index
+        (day(5), 120.0, 110.0),
+        (day(6), 132.0, 110.0),
+    ];
+    assert_eq!(comparison.series.len(), expected.len());
+    for (point, (date, portfolio, benchmark)) in comparison.series.iter().zip(expected) {
+        assert_eq!(point.date, date);
+        assert!((point.portfolio - portfolio).abs() < 1e-9, "{:?}", point);
+        assert!((point.benchmark - benchmark).abs() < 1e-9, "{:?}", point);
+    }
+}
+
+#[tokio::test]
+async fn test_growth_series_with_start_date() {
+    let movements = vec![
+        buy(1, 1, day(1), dec!(10.0), dec!(100.0)),
+        buy(2, 1, day(3), dec!(10.0), dec!(120.0)),
+    ];
+    let prices = vec![quote(1, day(2), dec!(12.0)), quote(1, day(4), dec!(9.0))];
+    let calculator = PortfolioCalculator::new(
+        Arc::new(InMemoryMovementRepository::with_movements(movements)),
+        Arc::new(InMemoryInvestmentPriceRepository::with_prices(prices)),
+    );
+
+    let series = calculator
+        .calculate_growth_series(None, Some(day(2)), None)
+        .await
+        .unwrap();
+
+    // Growth from before the start date is kept; the buy on day 3 does not change it
+    let dates: Vec<NaiveDate> = series.total.iter().map(|p| p.date).collect();
+    assert_eq!(dates, vec![day(2), day(3), day(4)]);
+    for (point, growth) in series.total.iter().zip([1.2, 1.2, 0.9]) {
+        assert!((point.growth - growth).abs() < 1e-9, "{:?}", point);
+    }
+    assert_eq!(series.investments[&1].len(), 3);
+    assert_eq!(series.investments[&1][2].value, 180.0);
+}
@@ -0,0 +1,140 @@
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{ActionType, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::cost_basis::{
+    SPLIT_ACTION_ID, TRANSFER_IN_ACTION_ID, TRANSFER_OUT_ACTION_ID,
+};
+use portfoliodb_rust::services::positions::{
+    current_quantities, negative_quantity_dates, quantity_at, PositionPoint,
+};
+use portfoliodb_rust::services::PositionCalculator;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+fn movement(
+    investment_id: i64,
+    date: u32,
+    action_id: i64,
+    quantity: Decimal,
+    portfolio_id: Option<i64>,
+) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(day(date)),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(dec!(100.0)),
+        fee: None,
+        tax: None,
+        portfolio_id,
+        external_id: None,
+    }
+}
+
+#[test]
+fn test_quantities_follow_trades_transfers_and_splits() {
+    let movements = vec![
+        movement(1, 1, 1, dec!(10.0), Some(1)),
+        // Splits apply before the trades of the same day
+        movement(1, 5, 2, dec!(4.0), Some(1)),
+        movement(1, 5, SPLIT_ACTION_ID, dec!(3.0), Some(1)),
+        movement(1, 8, TRANSFER_OUT_ACTION_ID, dec!(6.0), Some(1)),
+        movement(1, 8, TRANSFER_IN_ACTION_ID, dec!(6.0), Some(2)),
+        // Payouts leave the quantity alone
+        movement(1, 9, 3, dec!(0.0), Some(1)),
+        movement(2, 3, 1, dec!(2.5), None),
+    ];
+
+    assert_eq!(
+        current_quantities(&movements),
+        HashMap::from([(1, dec!(26.0)), (2, dec!(2.5))])
+    );
+    assert_eq!(quantity_at(&movements, 1, day(4)), dec!(10.0));
+    assert_eq!(quantity_at(&movements, 1, day(5)), dec!(26.0));
+    assert_eq!(quantity_at(&movements, 2, day(2)), Decimal::ZERO);
+    assert_eq!(quantity_at(&movements, 3, day(9)), Decimal::ZERO);
+}
+
+#[test]
+fn test_negative_quantity_dates() {
+    let movements = vec![
+        movement(1, 1, 1, dec!(5.0), None),
+        movement(1, 2, 2, dec!(8.0), None),
+        movement(1, 3, 1, dec!(1.0), None),
+        movement(1, 4, 1, dec!(2.0), None),
+        // Selling and buying back on the same day leaves nothing negative
+        movement(2, 1, 2, dec!(1.0), None),
+        movement(2, 1, 1, dec!(1.0), None),
+    ];
+
+    assert_eq!(
+        negative_quantity_dates(&movements),
+        HashMap::from([(1, vec![day(2), day(3)])])
+    );
+}
+
+#[tokio::test]
+async fn test_position_calculator_reads_the_stored_movements() {
+    let repos = Repositories::in_memory();
+    let bonus = repos
+        .action_types
+        .create(&ActionType {
+            id: 0,
+            name: "Bonus shares".to_string(),
+            effect: "increases_quantity".to_string(),
+        })
+        .await
+        .unwrap();
+    repos
+        .movements
+        .create_many(&[
+            movement(1, 1, 1, dec!(10.0), Some(1)),
+            movement(1, 2, bonus, dec!(1.0), Some(1)),
+            movement(1, 3, 1, dec!(5.0), Some(2)),
+            movement(1, 6, 2, dec!(4.0), Some(2)),
+            movement(2, 4, 1, dec!(3.0), Some(2)),
+        ])
+        .await
+        .unwrap();
+    let positions = PositionCalculator::new(repos.movements.clone())
+        .with_action_types(repos.action_types.clone());
+
+    assert_eq!(
+        positions.current_quantities(None).await.unwrap(),
+        HashMap::from([(1, dec!(12.0)), (2, dec!(3.0))])
+    );
+    assert_eq!(
+        positions.current_quantities(Some(1)).await.unwrap(),
+        HashMap::from([(1, dec!(11.0))])
+    );
+    assert_eq!(
+        positions.quantity_at(1, Some(2), day(5)).await.unwrap(),
+        dec!(5.0)
+    );
+    let point = |date, quantity| PositionPoint {
+        date: day(date),
+        quantity,
+    };
+    assert_eq!(
+        positions.history(1, None).await.unwrap(),
+        vec![
+            point(1, dec!(10.0)),
+            point(2, dec!(11.0)),
+            point(3, dec!(16.0)),
+            point(6, dec!(12.0)),
+        ]
+    );
+
+    // Without the action types the custom movement is ignored
+    let positions = PositionCalculator::new(repos.movements.clone());
+    assert_eq!(
+        positions.quantity_at(1, None, day(2)).await.unwrap(),
+        dec!(10.0)
+    );
+}
@@ -0,0 +1,292 @@
+mod test_helpers;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::alerts::{
+    create_alert, list_triggered_alerts, update_alert, PriceAlertRequest, TriggeredAlertQuery,
+};
+use portfoliodb_rust::models::{AlertDirection, Investment, PriceAlert};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::PriceAlertState;
+use portfoliodb_rust::services::PriceAlertService;
+use test_helpers::setup_test_db;
+
+async fn setup() -> (Repositories, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Apple".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: Some("AAPL".to_string()),
+            quote_provider: Some("yahoo".to_string()),
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    (repos, investment_id)
+}
+
+fn state(repos: &Repositories) -> PriceAlertState {
+    PriceAlertState {
+        alert_repo: repos.price_alerts.clone(),
+        investment_repo: repos.investments.clone(),
+    }
+}
+
+fn alert(investment_id: i64, threshold: f64, direction: &str) -> PriceAlert {
+    PriceAlert {
+        id: 0,
+        investment_id,
+        threshold,
+        direction: direction.to_string(),
+        active: true,
+    }
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+}
+
+#[test]
+fn test_alert_direction() {
+    assert!(AlertDirection::Above.is_crossed(100.0, 100.0));
+    assert!(!AlertDirection::Above.is_crossed(99.9, 100.0));
+    assert!(AlertDirection::Below.is_crossed(100.0, 100.0));
+    assert!(!AlertDirection::Below.is_crossed(100.1, 100.0));
+    assert_eq!(
+        "Below".parse::<AlertDirection>().unwrap(),
+        AlertDirection::Below
+    );
+    assert!("sideways".parse::<AlertDirection>().is_err());
+}
+
+#[tokio::test]
+async fn test_price_alert_repository_crud() {
+    let (repos, investment_id) = setup().await;
+
+    let id = repos
+        .price_alerts
+        .create(&alert(investment_id, 150.0, "above"))
+        .await
+        .unwrap();
+    let mut stored = repos.price_alerts.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(stored.threshold, 150.0);
+    assert!(stored.active);
+
+    stored.threshold = 160.0;
+    stored.active = false;
+    repos.price_alerts.update(id, &stored).await.unwrap();
+    assert!(repos
+        .price_alerts
+        .find_active(investment_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        repos
+            .price_alerts
+            .find_all(Some(investment_id))
+            .await
+            .unwrap()[0]
+            .threshold,
+        160.0
+    );
+    assert!(repos
+        .price_alerts
+        .find_all(Some(999))
+        .await
+        .unwrap()
+        .is_empty());
+
+    repos.price_alerts.delete(id).await.unwrap();
+    assert!(repos.price_alerts.find_by_id(id).await.unwrap().is_none());
+}
+
+/// Crossed alerts are recorded once and deactivated, others stay active
+#[tokio::test]
+async fn test_evaluate_triggers_crossed_alerts_once() {
+    let (repos, investment_id) = setup().await;
+    let above = repos
+        .price_alerts
+        .create(&alert(investment_id, 150.0, "above"))
+        .await
+        .unwrap();
+    let below = repos
+        .price_alerts
+        .create(&alert(investment_id, 100.0, "below"))
+        .await
+        .unwrap();
+    let service = PriceAlertService::new(repos.price_alerts.clone());
+
+    let triggered = service
+        .evaluate(investment_id, date(1), 120.0)
+        .await
+        .unwrap();
+    assert!(triggered.is_empty());
+
+    let triggered = service
+        .evaluate(investment_id, date(2), 155.5)
+        .await
+        .unwrap();
+    assert_eq!(triggered.len(), 1);
+    assert_eq!(triggered[0].alert_id, above);
+    assert_eq!(triggered[0].price, 155.5);
+    assert_eq!(triggered[0].price_date, date(2));
+
+    let active: Vec<i64> = repos
+        .price_alerts
+        .find_active(investment_id)
+        .await
+        .unwrap()
+        .iter()
+        .map(|a| a.id)
+        .collect();
+    assert_eq!(active, vec![below]);
+
+    // The deactivated alert does not trigger again
+    let triggered = service
+        .evaluate(investment_id, date(3), 160.0)
+        .await
+        .unwrap();
+    assert!(triggered.is_empty());
+
+    let history = repos
+        .price_alerts
+        .find_triggered(Some(investment_id), 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].direction, "above");
+}
+
+#[tokio::test]
+async fn test_create_alert_is_validated() {
+    let (repos, investment_id) = setup().await;
+
+    let err = create_alert(
+        State(state(&repos)),
+        Json(PriceAlertRequest {
+            investment_id,
+            threshold: -1.0,
+            direction: "sideways".to_string(),
+            active: None,
+        }),
+    )
+    .await
+    .unwrap_err();
+    match err {
+        AppError::Validation(errors) => {
+            let fields: Vec<&str> = errors.errors.iter().map(|e| e.field.as_str()).collect();
+            assert_eq!(fields, vec!["threshold", "direction"]);
+        }
+        other => panic!("expected validation error, got {:?}", other),
+    }
+
+    let err = create_alert(
+        State(state(&repos)),
+        Json(PriceAlertRequest {
+            investment_id: 999,
+            threshold: 100.0,
+            direction: "above".to_string(),
+            active: None,
+        }),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+}
+
+/// A triggered alert is reported and can be reactivated by an update
+#[tokio::test]
+async fn test_triggered_alert_endpoints() {
+    let (repos, investment_id) = setup().await;
+
+    let created = create_alert(
+        State(state(&repos)),
+        Json(PriceAlertRequest {
+            investment_id,
+            threshold: 80.0,
+            direction: "BELOW".to_string(),
+            active: None,
+        }),
+    )
+    .await
+    .unwrap()
+    .0;
+    assert_eq!(created.direction, "below");
+    assert!(created.active);
+
+    PriceAlertService::new(repos.price_alerts.clone())
+        .evaluate(investment_id, date(4), 79.0)
+        .await
+        .unwrap();
+
+    let triggered = list_triggered_alerts(
+        State(state(&repos)),
+        Query(TriggeredAlertQuery {
+            investment_id: None,
+            limit: None,
+        }),
+    )
+    .await
+    .unwrap()
+    .0;
+    assert_eq!(triggered.len(), 1);
+    assert_eq!(triggered[0].alert_id, created.id);
+
+    let reactivated = update_alert(
+        State(state(&repos)),
+        Path(created.id),
+        Json(PriceAlertRequest {
+            investment_id,
+            threshold: 70.0,
+            direction: "below".to_string(),
+            active: Some(true),
+        }),
+    )
+    .await
+    .unwrap()
+    .0;
+    assert!(reactivated.active);
+    assert_eq!(reactivated.threshold, 70.0);
+}
+
+/// Alerts and their triggers are removed with the investment
+#[tokio::test]
+async fn test_alerts_are_deleted_with_investment() {
+    let (repos, investment_id) = setup().await;
+    repos
+        .price_alerts
+        .create(&alert(investment_id, 150.0, "above"))
+        .await
+        .unwrap();
+    PriceAlertService::new(repos.price_alerts.clone())
+        .evaluate(investment_id, date(5), 151.0)
+        .await
+        .unwrap();
+
+    repos
+        .investments
+        .delete_with_dependents(investment_id, false)
+        .await
+        .unwrap();
+
+    assert!(repos.price_alerts.find_all(None).await.unwrap().is_empty());
+    assert!(repos
+        .price_alerts
+        .find_triggered(None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,128 @@
+mod test_helpers;
+
+use axum::extract::State;
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::prices::{bulk_upsert_investment_prices, CreatePriceRequest};
+use portfoliodb_rust::models::Investment;
+use portfoliodb_rust::repository::Repositories;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn request(
+    investment_id: i64,
+    day: u32,
+    price: Decimal,
+    source: Option<&str>,
+) -> CreatePriceRequest {
+    CreatePriceRequest {
+        date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+        investment_id,
+        price,
+        source: source.map(str::to_string),
+        currency: None,
+        original_price: None,
+    }
+}
+
+async fn setup() -> (Repositories, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Fund".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    (repos, investment_id)
+}
+
+#[tokio::test]
+async fn test_bulk_upsert_inserts_and_updates() {
+    let (repos, id) = setup().await;
+
+    let response = bulk_upsert_investment_prices(
+        State(repos.investment_prices.clone()),
+        Json(vec![
+            request(id, 1, dec!(10.0), None),
+            request(id, 2, dec!(11.0), None),
+            request(id, 2, dec!(11.5), Some("yahoo")),
+        ]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.0.len(), 3);
+    assert_eq!(response.0[0].source.as_deref(), Some("manual"));
+
+    // Entering the prices again updates them instead of adding duplicates
+    let response = bulk_upsert_investment_prices(
+        State(repos.investment_prices.clone()),
+        Json(vec![
+            request(id, 1, dec!(10.5), None),
+            request(id, 3, dec!(12.0), None),
+        ]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.0.len(), 2);
+
+    let mut stored: Vec<(NaiveDate, Decimal, String)> = repos
+        .investment_prices
+        .find_all(Some(id), None, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|p| (p.date.unwrap(), p.price.unwrap(), p.source.unwrap()))
+        .collect();
+    stored.sort_by(|a, b| (a.0, &a.2).cmp(&(b.0, &b.2)));
+    let day = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+    assert_eq!(
+        stored,
+        [
+            (day(1), dec!(10.5), "manual".to_string()),
+            (day(2), dec!(11.0), "manual".to_string()),
+            (day(2), dec!(11.5), "yahoo".to_string()),
+            (day(3), dec!(12.0), "manual".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_bulk_upsert_stores_nothing_if_one_price_is_invalid() {
+    let (repos, id) = setup().await;
+
+    let err = bulk_upsert_investment_prices(
+        State(repos.investment_prices.clone()),
+        Json(vec![
+            request(id, 1, dec!(10.0), None),
+            request(id, 2, dec!(-1.0), None),
+        ]),
+    )
+    .await
+    .unwrap_err();
+
+    match err {
+        AppError::Validation(errors) => assert_eq!(errors.errors[0].field, "[1].price"),
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+    assert!(repos
+        .investment_prices
+        .find_all(Some(id), None, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,121 @@
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{InvestmentPrice, Movement};
+use portfoliodb_rust::services::price_gaps::{find_price_gaps, GapOptions, PriceGap};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn day(d: u32) -> NaiveDate {
+    // January 2024 starts on a Monday
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+fn movement(
+    id: i64,
+    action_id: i64,
+    investment_id: i64,
+    date: NaiveDate,
+    quantity: Decimal,
+) -> Movement {
+    Movement {
+        id,
+        date: Some(date),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(quantity * dec!(10.0)),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn prices(investment_id: i64, days: &[u32]) -> Vec<InvestmentPrice> {
+    days.iter()
+        .map(|&d| InvestmentPrice {
+            date: Some(day(d)),
+            investment_id: Some(investment_id),
+            price: Some(dec!(10.0)),
+            source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
+        })
+        .collect()
+}
+
+fn options(end: u32, include_weekends: bool) -> GapOptions {
+    GapOptions {
+        start_date: None,
+        end_date: day(end),
+        include_weekends,
+    }
+}
+
+#[test]
+fn test_gap_spanning_weekend_is_one_range() {
+    let movements = vec![movement(1, 1, 1, day(1), dec!(10.0))];
+    // Missing Thursday 4th to Tuesday 9th, weekend 6th/7th is not counted
+    let prices = prices(1, &[1, 2, 3, 10, 11, 12]);
+
+    let report = find_price_gaps(&movements, &prices, options(12, false));
+
+    assert_eq!(report.len(), 1);
+    assert_eq!(
+        report[0].gaps,
+        vec![PriceGap {
+            start_date: day(4),
+            end_date: day(9),
+            missing_days: 4,
+        }]
+    );
+    assert_eq!(report[0].missing_days, 4);
+}
+
+#[test]
+fn test_weekends_checked_when_requested() {
+    let movements = vec![movement(1, 1, 1, day(5), dec!(10.0))];
+    let prices = prices(1, &[5, 8]);
+
+    let report = find_price_gaps(&movements, &prices, options(8, true));
+
+    assert_eq!(report[0].gaps.len(), 1);
+    assert_eq!(report[0].gaps[0].start_date, day(6));
+    assert_eq!(report[0].gaps[0].end_date, day(7));
+    assert_eq!(report[0].missing_days, 2);
+}
+
+#[test]
+fn test_only_holding_periods_are_checked() {
+    let movements = vec![
+        movement(1, 1, 1, day(1), dec!(10.0)),
+        movement(2, 2, 1, day(3), dec!(10.0)),
+        movement(3, 1, 1, day(15), dec!(5.0)),
+        // Never bought, only a stray sell
+        movement(4, 2, 2, day(2), dec!(1.0)),
+    ];
+    let prices = prices(1, &[1, 2, 3, 15]);
+
+    let report = find_price_gaps(&movements, &prices, options(17, false));
+
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].investment, 1);
+    assert_eq!(
+        report[0].gaps,
+        vec![PriceGap {
+            start_date: day(16),
+            end_date: day(17),
+            missing_days: 2,
+        }]
+    );
+}
+
+#[test]
+fn test_no_report_without_gaps() {
+    let movements = vec![movement(1, 1, 1, day(1), dec!(10.0))];
+    let prices = prices(1, &[1, 2, 3, 4, 5]);
+
+    let mut options = options(7, false);
+    options.start_date = Some(day(2));
+
+    assert!(find_price_gaps(&movements, &prices, options).is_empty());
+}
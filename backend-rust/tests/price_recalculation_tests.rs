@@ -0,0 +1,113 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{FxRate, InvestmentPrice};
+use portfoliodb_rust::repository::traits::{
+    FxRateRepository, InvestmentPriceRepository, SettingsRepository,
+};
+use portfoliodb_rust::repository::{
+    SqliteFxRateRepository, SqliteInvestmentPriceRepository, SqliteSettingsRepository,
+};
+use portfoliodb_rust::services::PriceRecalculationService;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn price(
+    day: u32,
+    price: Decimal,
+    currency: Option<&str>,
+    original_price: Option<Decimal>,
+) -> InvestmentPrice {
+    InvestmentPrice {
+        date: Some(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
+        investment_id: Some(1),
+        price: Some(price),
+        source: Some("yahoo".to_string()),
+        currency: currency.map(str::to_string),
+        original_price,
+    }
+}
+
+#[tokio::test]
+async fn test_recalculate_prices_after_base_currency_change() {
+    let pool = setup_test_db().await;
+    let price_repo: Arc<dyn InvestmentPriceRepository> =
+        Arc::new(SqliteInvestmentPriceRepository::new(pool.clone()));
+    let settings_repo: Arc<dyn SettingsRepository> =
+        Arc::new(SqliteSettingsRepository::new(pool.clone()));
+    let fx_rate_repo: Arc<dyn FxRateRepository> = Arc::new(SqliteFxRateRepository::new(pool));
+
+    // Prices stored while the base currency was EUR
+    price_repo
+        .upsert(&price(2, dec!(90.0), Some("USD"), Some(dec!(100.0))))
+        .await
+        .unwrap();
+    price_repo
+        .upsert(&price(3, dec!(50.0), Some("EUR"), Some(dec!(50.0))))
+        .await
+        .unwrap();
+    price_repo
+        .upsert(&price(4, dec!(70.0), None, None))
+        .await
+        .unwrap();
+
+    // Cached rates so no request to the exchange rate API is needed
+    for (day, from, to, rate) in [(2, "USD", "CHF", 0.85), (3, "EUR", "CHF", 0.95)] {
+        fx_rate_repo
+            .upsert(&FxRate {
+                date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+                from_currency: from.to_string(),
+                to_currency: to.to_string(),
+                rate,
+            })
+            .await
+            .unwrap();
+    }
+
+    let mut settings = settings_repo.get().await.unwrap().unwrap();
+    settings.base_currency = "CHF".to_string();
+    settings_repo.update(&settings).await.unwrap();
+
+    let service = PriceRecalculationService::new(price_repo.clone(), settings_repo, fx_rate_repo);
+    let result = service.recalculate().await.unwrap();
+
+    assert_eq!(result.base_currency, "CHF");
+    assert_eq!(result.updated, 2);
+    assert_eq!(result.skipped, 1);
+
+    let prices = price_repo.find_all(Some(1), None, None).await.unwrap();
+    let price_on = |day: u32| {
+        let date = NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        prices.iter().find(|p| p.date == Some(date)).unwrap().price
+    };
+    assert_eq!(price_on(2), Some(dec!(85.0)));
+    assert_eq!(price_on(3), Some(dec!(47.5)));
+    // Without the original quote the price is left untouched
+    assert_eq!(price_on(4), Some(dec!(70.0)));
+    assert_eq!(prices.len(), 3);
+}
+
+#[tokio::test]
+async fn test_recalculate_in_original_currency_restores_original_price() {
+    let pool = setup_test_db().await;
+    let price_repo: Arc<dyn InvestmentPriceRepository> =
+        Arc::new(SqliteInvestmentPriceRepository::new(pool.clone()));
+    let settings_repo: Arc<dyn SettingsRepository> =
+        Arc::new(SqliteSettingsRepository::new(pool.clone()));
+    let fx_rate_repo: Arc<dyn FxRateRepository> = Arc::new(SqliteFxRateRepository::new(pool));
+
+    // Converted from EUR to USD earlier, base currency is back to EUR now
+    price_repo
+        .upsert(&price(2, dec!(110.0), Some("EUR"), Some(dec!(100.0))))
+        .await
+        .unwrap();
+
+    let service = PriceRecalculationService::new(price_repo.clone(), settings_repo, fx_rate_repo);
+    let result = service.recalculate().await.unwrap();
+    assert_eq!(result.updated, 1);
+
+    let prices = price_repo.find_all(Some(1), None, None).await.unwrap();
+    assert_eq!(prices[0].price, Some(dec!(100.0)));
+}
@@ -0,0 +1,179 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::{DevelopmentCache, PortfolioCalculator, PriceSourcePriority};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use sqlx::types::Json;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+fn price(
+    investment_id: i64,
+    date: NaiveDate,
+    price: Decimal,
+    source: Option<&str>,
+) -> InvestmentPrice {
+    InvestmentPrice {
+        date: Some(date),
+        investment_id: Some(investment_id),
+        price: Some(price),
+        source: source.map(str::to_string),
+        currency: None,
+        original_price: None,
+    }
+}
+
+fn investment(id: i64, quote_provider: Option<&str>) -> Investment {
+    Investment {
+        id,
+        name: Some(format!("Investment {}", id)),
+        isin: None,
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: quote_provider.map(str::to_string),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    }
+}
+
+/// Source of the price selected for investment 1 on day 1
+fn selected(priority: &PriceSourcePriority, prices: &[InvestmentPrice]) -> Option<String> {
+    priority.select(prices)[&(1, day(1))].source.clone()
+}
+
+#[test]
+fn test_select_is_independent_of_price_order() {
+    let mut prices = vec![
+        price(1, day(1), dec!(10.0), None),
+        price(1, day(1), dec!(11.0), Some("yahoo")),
+        price(1, day(1), dec!(12.0), Some("justetf")),
+    ];
+    let priority = PriceSourcePriority::default();
+
+    // Without priority the sources are taken alphabetically, prices without source last
+    assert_eq!(selected(&priority, &prices).as_deref(), Some("justetf"));
+    prices.reverse();
+    assert_eq!(selected(&priority, &prices).as_deref(), Some("justetf"));
+
+    prices.retain(|p| p.source.is_some());
+    prices.push(price(1, day(1), dec!(13.0), Some("JustETF")));
+    let first = selected(&priority, &prices);
+    prices.reverse();
+    assert_eq!(selected(&priority, &prices), first);
+}
+
+#[test]
+fn test_select_by_global_and_investment_priority() {
+    let prices = vec![
+        price(1, day(1), dec!(10.0), Some("justetf")),
+        price(1, day(1), dec!(11.0), Some("Yahoo")),
+        price(1, day(1), dec!(12.0), Some("manual")),
+        price(2, day(1), dec!(20.0), Some("justetf")),
+        price(2, day(1), dec!(21.0), Some("yahoo")),
+    ];
+
+    let priority = PriceSourcePriority::new(&["YAHOO".to_string()], &[]);
+    assert_eq!(selected(&priority, &prices).as_deref(), Some("Yahoo"));
+
+    // The quote provider chain of an investment comes before the global priority
+    let priority = PriceSourcePriority::new(
+        &["yahoo".to_string()],
+        &[investment(1, Some("manual, justetf"))],
+    );
+    let selection = priority.select(&prices);
+    assert_eq!(selection[&(1, day(1))].price, Some(dec!(12.0)));
+    assert_eq!(selection[&(2, day(1))].price, Some(dec!(21.0)));
+}
+
+#[tokio::test]
+async fn test_developments_use_preferred_source() {
+    let plain = Repositories::sqlite(setup_test_db().await);
+    let cache = DevelopmentCache::new();
+    let repos = plain.with_change_listener(Arc::new(cache.clone()));
+
+    let investment_id = repos
+        .investments
+        .create(&investment(0, None))
+        .await
+        .unwrap();
+    repos
+        .movements
+        .create(&Movement {
+            id: 0,
+            date: Some(day(1)),
+            action_id: Some(1),
+            investment_id: Some(investment_id),
+            quantity: Some(dec!(10.0)),
+            amount: Some(dec!(100.0)),
+            fee: None,
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        })
+        .await
+        .unwrap();
+    for (date, value, source) in [
+        (day(2), dec!(11.0), "yahoo"),
+        (day(2), dec!(12.0), "justetf"),
+        (day(3), dec!(13.0), "yahoo"),
+    ] {
+        repos
+            .investment_prices
+            .create(&price(investment_id, date, value, Some(source)))
+            .await
+            .unwrap();
+    }
+
+    let calculator =
+        PortfolioCalculator::new(repos.movements.clone(), repos.investment_prices.clone())
+            .with_price_sources(repos.settings.clone(), repos.investments.clone())
+            .with_cache(cache);
+    let sources = || async {
+        calculator
+            .calculate_developments(None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|d| (d.price, d.source))
+            .collect::<Vec<_>>()
+    };
+
+    // The buy is valued with its transaction price
+    assert_eq!(
+        sources().await,
+        [
+            (dec!(10.0), None),
+            (dec!(12.0), Some("justetf".to_string())),
+            (dec!(13.0), Some("yahoo".to_string())),
+        ]
+    );
+
+    // Changing the priority invalidates the cached developments
+    let mut settings = repos.settings.get().await.unwrap().unwrap();
+    settings.price_source_priority = Json(vec!["yahoo".to_string()]);
+    repos.settings.update(&settings).await.unwrap();
+    assert_eq!(sources().await[1], (dec!(11.0), Some("yahoo".to_string())));
+
+    // So does changing the quote provider chain of the investment
+    repos
+        .investments
+        .update(investment_id, &investment(investment_id, Some("justetf")))
+        .await
+        .unwrap();
+    assert_eq!(
+        sources().await[1],
+        (dec!(12.0), Some("justetf".to_string()))
+    );
+}
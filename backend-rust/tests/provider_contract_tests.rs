@@ -0,0 +1,281 @@
+//! Contract tests of the quote providers against recorded responses in `tests/fixtures`
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::services::quotes::justetf::quotes_from_performance_chart;
+use portfoliodb_rust::services::quotes::yahoo_finance::{
+    events_from_chart_response, instrument_info_from_chart_response,
+    live_quote_from_quote_response, quotes_from_chart_response,
+};
+use portfoliodb_rust::services::quotes::{DividendData, QuoteData, SplitData};
+use reqwest::StatusCode;
+
+fn fixture(path: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), path);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Fixture {}: {}", path, e))
+}
+
+fn date(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 6, d).unwrap()
+}
+
+fn summary(quotes: &[QuoteData]) -> Vec<(NaiveDate, f64, &str)> {
+    quotes
+        .iter()
+        .map(|q| (q.date, q.price, q.currency.as_str()))
+        .collect()
+}
+
+fn assert_external_api_error(err: AppError, expected: &str) {
+    match err {
+        AppError::ExternalApi(message) => assert!(
+            message.contains(expected),
+            "expected '{}' in '{}'",
+            expected,
+            message
+        ),
+        other => panic!("expected external API error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_yahoo_daily_chart() {
+    let quotes = quotes_from_chart_response(
+        "EUNL.DE",
+        StatusCode::OK,
+        &fixture("yahoo/chart_daily.json"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        summary(&quotes),
+        vec![
+            (date(3), 95.12, "EUR"),
+            (date(4), 95.282, "EUR"),
+            (date(5), 95.876, "EUR"),
+        ]
+    );
+    assert!(quotes
+        .iter()
+        .all(|q| q.ticker == "EUNL.DE" && q.source == "yahoo"));
+
+    let info = instrument_info_from_chart_response(&fixture("yahoo/chart_daily.json"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.currency.as_deref(), Some("EUR"));
+    assert_eq!(info.asset_class.as_deref(), Some("etf"));
+}
+
+#[test]
+fn test_yahoo_days_without_close_are_skipped() {
+    let quotes = quotes_from_chart_response(
+        "AAPL",
+        StatusCode::OK,
+        &fixture("yahoo/chart_null_closes.json"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        summary(&quotes),
+        vec![(date(3), 194.03, "USD"), (date(5), 195.87, "USD")]
+    );
+}
+
+#[test]
+fn test_yahoo_adjusted_close_and_events() {
+    let body = fixture("yahoo/chart_events.json");
+
+    let quotes = quotes_from_chart_response("AAPL", StatusCode::OK, &body).unwrap();
+    assert_eq!(
+        summary(&quotes),
+        vec![
+            (date(3), 194.03, "USD"),
+            (date(4), 194.35, "USD"),
+            (date(5), 195.87, "USD"),
+        ]
+    );
+    let adjusted: Vec<Option<f64>> = quotes.iter().map(|q| q.adjusted_price).collect();
+    assert_eq!(adjusted, vec![Some(193.02), Some(193.34), Some(195.87)]);
+
+    let events = events_from_chart_response("AAPL", StatusCode::OK, &body).unwrap();
+    assert_eq!(
+        events.dividends,
+        vec![DividendData {
+            date: date(4),
+            amount: 0.25,
+            currency: "USD".to_string(),
+        }]
+    );
+    assert_eq!(
+        events.splits,
+        vec![SplitData {
+            date: date(5),
+            numerator: 4.0,
+            denominator: 1.0,
+        }]
+    );
+
+    // Charts without events section have none
+    let events = events_from_chart_response(
+        "EUNL.DE",
+        StatusCode::OK,
+        &fixture("yahoo/chart_daily.json"),
+    )
+    .unwrap();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn test_yahoo_period_without_trading_days() {
+    let quotes = quotes_from_chart_response(
+        "EUNL.DE",
+        StatusCode::OK,
+        &fixture("yahoo/chart_no_trading_days.json"),
+    )
+    .unwrap();
+
+    assert!(quotes.is_empty());
+}
+
+#[test]
+fn test_yahoo_missing_currency() {
+    let body = fixture("yahoo/chart_missing_currency.json");
+
+    // Prices in an unknown currency are not stored
+    let err = quotes_from_chart_response("XYZ.F", StatusCode::OK, &body).unwrap_err();
+    assert_external_api_error(err, "no currency for XYZ.F");
+
+    let info = instrument_info_from_chart_response(&body).unwrap().unwrap();
+    assert_eq!(info.currency, None);
+    assert_eq!(info.asset_class.as_deref(), Some("stock"));
+}
+
+#[test]
+fn test_yahoo_unknown_symbol() {
+    let quotes = quotes_from_chart_response(
+        "INVALID",
+        StatusCode::NOT_FOUND,
+        &fixture("yahoo/chart_not_found.json"),
+    )
+    .unwrap();
+
+    assert!(quotes.is_empty());
+}
+
+#[test]
+fn test_yahoo_chart_error() {
+    let err = quotes_from_chart_response(
+        "EUNL.DE",
+        StatusCode::OK,
+        &fixture("yahoo/chart_error.json"),
+    )
+    .unwrap_err();
+
+    assert_external_api_error(err, "Bad Request: Invalid input");
+}
+
+#[test]
+fn test_yahoo_rate_limit() {
+    let err = quotes_from_chart_response(
+        "EUNL.DE",
+        StatusCode::TOO_MANY_REQUESTS,
+        &fixture("yahoo/rate_limited.txt"),
+    )
+    .unwrap_err();
+
+    assert_external_api_error(err, "Yahoo Finance rate limit exceeded");
+}
+
+#[test]
+fn test_yahoo_live_quote() {
+    let quote =
+        live_quote_from_quote_response("AAPL", StatusCode::OK, &fixture("yahoo/quote_live.json"))
+            .unwrap()
+            .unwrap();
+
+    assert_eq!(quote.price, 196.45);
+    assert_eq!(quote.currency, "USD");
+    assert_eq!(
+        quote.time,
+        Utc.with_ymd_and_hms(2024, 6, 5, 16, 25, 0).unwrap()
+    );
+    assert_eq!(quote.source, "yahoo");
+
+    let unknown = live_quote_from_quote_response(
+        "INVALID",
+        StatusCode::OK,
+        &fixture("yahoo/quote_unknown.json"),
+    )
+    .unwrap();
+    assert_eq!(unknown, None);
+
+    let err = live_quote_from_quote_response(
+        "AAPL",
+        StatusCode::TOO_MANY_REQUESTS,
+        &fixture("yahoo/rate_limited.txt"),
+    )
+    .unwrap_err();
+    assert_external_api_error(err, "Yahoo Finance rate limit exceeded");
+}
+
+#[test]
+fn test_justetf_performance_chart() {
+    let quotes = quotes_from_performance_chart(
+        "IE00B4L5Y983",
+        StatusCode::OK,
+        &fixture("justetf/performance_chart.json"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        summary(&quotes),
+        vec![
+            (date(3), 95.12, "EUR"),
+            (date(4), 95.28, "EUR"),
+            (date(5), 95.88, "EUR"),
+        ]
+    );
+    assert!(quotes
+        .iter()
+        .all(|q| q.ticker == "IE00B4L5Y983" && q.source == "justetf"));
+}
+
+#[test]
+fn test_justetf_points_without_value_or_date_are_skipped() {
+    let quotes = quotes_from_performance_chart(
+        "IE00B4L5Y983",
+        StatusCode::OK,
+        &fixture("justetf/performance_chart_gaps.json"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        summary(&quotes),
+        vec![(date(3), 95.12, "EUR"), (date(5), 95.88, "EUR")]
+    );
+}
+
+#[test]
+fn test_justetf_unknown_isin() {
+    let quotes = quotes_from_performance_chart(
+        "XX0000000000",
+        StatusCode::NOT_FOUND,
+        &fixture("justetf/not_found.json"),
+    )
+    .unwrap();
+
+    assert!(quotes.is_empty());
+}
+
+#[test]
+fn test_justetf_rate_limit() {
+    let body = fixture("justetf/rate_limited.html");
+
+    let err = quotes_from_performance_chart("IE00B4L5Y983", StatusCode::TOO_MANY_REQUESTS, &body)
+        .unwrap_err();
+    assert_external_api_error(err, "JustETF API rate limit exceeded");
+
+    // An HTML page with success status is no valid response
+    let err = quotes_from_performance_chart("IE00B4L5Y983", StatusCode::OK, &body).unwrap_err();
+    assert_external_api_error(err, "Failed to parse JustETF API response");
+}
@@ -1,11 +1,18 @@
 mod test_helpers;
 
-use portfoliodb_rust::models::Investment;
+use chrono::{Duration, NaiveDate, Utc};
+use portfoliodb_rust::error::AppError;
+
+use portfoliodb_rust::models::{IntradayPrice, Investment, Movement, QuoteFetchLog};
 use portfoliodb_rust::repository::sqlite::{
     SqliteInvestmentPriceRepository, SqliteInvestmentRepository,
 };
 use portfoliodb_rust::repository::traits::{InvestmentPriceRepository, InvestmentRepository};
-use portfoliodb_rust::services::QuoteFetcherService;
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::quote_fetcher::{fill_missing_fields, parse_provider_chain};
+use portfoliodb_rust::services::quotes::{InstrumentInfo, QuoteData, QuoteProvider};
+use portfoliodb_rust::services::{PositionCalculator, QuoteFetcherService};
+use rust_decimal_macros::dec;
 use std::sync::Arc;
 use test_helpers::setup_test_db;
 
@@ -24,13 +31,14 @@ async fn test_quote_fetcher_creation() {
     let providers = service.get_available_providers();
     assert_eq!(
         providers.len(),
-        2,
-        "Should have 2 providers (yahoo, justetf)"
+        3,
+        "Should have 3 providers (yahoo, justetf, coingecko)"
     );
 
     let provider_ids: Vec<String> = providers.iter().map(|p| p.id.clone()).collect();
     assert!(provider_ids.contains(&"yahoo".to_string()));
     assert!(provider_ids.contains(&"justetf".to_string()));
+    assert!(provider_ids.contains(&"coingecko".to_string()));
 }
 
 /// Test fetching quotes for investment without provider configured
@@ -51,6 +59,13 @@ async fn test_fetch_quotes_no_provider() {
         shortname: None,
         quote_provider: None, // No provider
         ticker_symbol: Some("AAPL".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     let created_id = investment_repo.create(&investment).await.unwrap();
@@ -90,6 +105,13 @@ async fn test_fetch_quotes_unknown_provider() {
         shortname: None,
         quote_provider: Some("unknown_provider".to_string()),
         ticker_symbol: Some("AAPL".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     let created_id = investment_repo.create(&investment).await.unwrap();
@@ -108,6 +130,277 @@ async fn test_fetch_quotes_unknown_provider() {
     assert!(result.error.unwrap().contains("Unknown provider"));
 }
 
+/// Test that every provider of a fallback chain is tried before failing
+#[tokio::test]
+async fn test_fetch_quotes_provider_chain_falls_through() {
+    let pool = setup_test_db().await;
+
+    let investment_repo: Arc<dyn InvestmentRepository> =
+        Arc::new(SqliteInvestmentRepository::new(pool.clone()));
+    let price_repo: Arc<dyn InvestmentPriceRepository> =
+        Arc::new(SqliteInvestmentPriceRepository::new(pool.clone()));
+
+    let investment = Investment {
+        id: 0,
+        name: Some("Test Investment".to_string()),
+        isin: Some("US0378331005".to_string()),
+        shortname: None,
+        quote_provider: Some("first_unknown, second_unknown".to_string()),
+        ticker_symbol: Some("AAPL".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    };
+
+    let created_id = investment_repo.create(&investment).await.unwrap();
+    let created = investment_repo
+        .find_by_id(created_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let service = QuoteFetcherService::new(investment_repo, price_repo, "EUR".to_string());
+
+    let result = service.fetch_quotes_for_investment(&created).await.unwrap();
+
+    assert!(!result.success);
+    assert!(result.provider.is_none());
+    let error = result.error.unwrap();
+    assert!(error.contains("Unknown provider: first_unknown"));
+    assert!(error.contains("Unknown provider: second_unknown"));
+}
+
+/// Test that only held and watched investments are fetched with `with_held_only`
+#[tokio::test]
+async fn test_fetch_quotes_held_only() {
+    let repos = Repositories::in_memory();
+    let mut ids = Vec::new();
+    for (name, watchlist) in [("Held", false), ("Sold", false), ("Watched", true)] {
+        let investment = Investment {
+            id: 0,
+            name: Some(name.to_string()),
+            isin: None,
+            shortname: None,
+            quote_provider: Some("unknown_provider".to_string()),
+            ticker_symbol: Some(name.to_uppercase()),
+            currency: None,
+            asset_class: None,
+            watchlist,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        };
+        ids.push(repos.investments.create(&investment).await.unwrap());
+    }
+    let date = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+    for (investment_id, action_id) in [(ids[0], 1), (ids[1], 1), (ids[1], 2)] {
+        repos
+            .movements
+            .create(&Movement {
+                id: 0,
+                date: Some(date),
+                action_id: Some(action_id),
+                investment_id: Some(investment_id),
+                quantity: Some(dec!(5)),
+                amount: Some(dec!(500)),
+                fee: None,
+                tax: None,
+                portfolio_id: None,
+                external_id: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let service = || {
+        QuoteFetcherService::new(
+            repos.investments.clone(),
+            repos.investment_prices.clone(),
+            "EUR".to_string(),
+        )
+    };
+    let all = service().fetch_quotes(None).await.unwrap();
+    assert_eq!(all.len(), 3);
+
+    let positions = Arc::new(PositionCalculator::new(repos.movements.clone()));
+    let held = service()
+        .with_held_only(positions)
+        .fetch_quotes(None)
+        .await
+        .unwrap();
+    let fetched: Vec<i64> = held.iter().map(|r| r.investment_id).collect();
+    assert_eq!(fetched, vec![ids[0], ids[2]]);
+}
+
+#[test]
+fn test_parse_provider_chain() {
+    assert_eq!(
+        parse_provider_chain("yahoo, justetf,,coingecko "),
+        vec!["yahoo", "justetf", "coingecko"]
+    );
+    assert!(parse_provider_chain(" , ").is_empty());
+}
+
+/// Test backfill argument validation
+#[tokio::test]
+async fn test_backfill_quotes_validation() {
+    let pool = setup_test_db().await;
+
+    let investment_repo: Arc<dyn InvestmentRepository> =
+        Arc::new(SqliteInvestmentRepository::new(pool.clone()));
+    let price_repo: Arc<dyn InvestmentPriceRepository> =
+        Arc::new(SqliteInvestmentPriceRepository::new(pool.clone()));
+
+    let service = QuoteFetcherService::new(investment_repo, price_repo, "EUR".to_string());
+
+    let from = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+    let to = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    let result = service.backfill_quotes_for_investment(1, from, to).await;
+    assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+    let result = service.backfill_quotes_for_investment(999, to, from).await;
+    assert!(matches!(result, Err(AppError::NotFound { .. })));
+    let result = service.backfill_full_history_for_investment(999).await;
+    assert!(matches!(result, Err(AppError::NotFound { .. })));
+}
+
+struct StaticProvider;
+
+#[async_trait::async_trait]
+impl QuoteProvider for StaticProvider {
+    async fn get_quote(
+        &self,
+        _ticker: &str,
+        _quote_date: Option<NaiveDate>,
+    ) -> portfoliodb_rust::error::Result<Option<QuoteData>> {
+        unimplemented!()
+    }
+
+    async fn get_quotes(&self, ticker: &str) -> portfoliodb_rust::error::Result<Vec<QuoteData>> {
+        Ok((1..=5)
+            .map(|day| {
+                QuoteData::new(
+                    ticker.to_string(),
+                    NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+                    100.0 + day as f64,
+                    "EUR".to_string(),
+                    "static".to_string(),
+                )
+            })
+            .collect())
+    }
+
+    async fn probe(&self) -> portfoliodb_rust::error::Result<()> {
+        Ok(())
+    }
+
+    fn get_provider_name(&self) -> &str {
+        "static"
+    }
+}
+
+/// Providers without instrument data report none
+#[tokio::test]
+async fn test_default_get_instrument_info_is_none() {
+    let info = StaticProvider.get_instrument_info("TEST").await.unwrap();
+    assert_eq!(info, None);
+}
+
+/// Only empty fields are filled, values entered by the user are kept
+#[test]
+fn test_fill_missing_fields() {
+    let mut investment = Investment {
+        id: 1,
+        name: Some("My World ETF".to_string()),
+        isin: Some("IE00B4L5Y983".to_string()),
+        shortname: Some("  ".to_string()),
+        ticker_symbol: Some("EUNL.DE".to_string()),
+        quote_provider: Some("yahoo".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    };
+    let info = InstrumentInfo {
+        name: Some("iShares Core MSCI World UCITS ETF USD (Acc)".to_string()),
+        shortname: Some("ISHSIII-CORE MSCI WORLD U.ETF".to_string()),
+        currency: Some("EUR".to_string()),
+        asset_class: None,
+    };
+
+    let updated = fill_missing_fields(&mut investment, &info);
+
+    assert_eq!(updated, vec!["shortname", "currency"]);
+    assert_eq!(investment.name.as_deref(), Some("My World ETF"));
+    assert_eq!(
+        investment.shortname.as_deref(),
+        Some("ISHSIII-CORE MSCI WORLD U.ETF")
+    );
+    assert_eq!(investment.currency.as_deref(), Some("EUR"));
+    assert_eq!(investment.asset_class, None);
+
+    assert!(fill_missing_fields(&mut investment, &info).is_empty());
+}
+
+/// Enrichment needs an existing investment with a provider
+#[tokio::test]
+async fn test_enrich_investment_errors() {
+    let pool = setup_test_db().await;
+    let investment_repo: Arc<dyn InvestmentRepository> =
+        Arc::new(SqliteInvestmentRepository::new(pool.clone()));
+    let price_repo: Arc<dyn InvestmentPriceRepository> =
+        Arc::new(SqliteInvestmentPriceRepository::new(pool.clone()));
+    let id = investment_repo
+        .create(&Investment {
+            id: 0,
+            name: Some("No provider".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: Some("TEST".to_string()),
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let service = QuoteFetcherService::new(investment_repo, price_repo, "EUR".to_string());
+
+    let result = service.enrich_investment(id).await;
+    assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+    let result = service.enrich_investment(999).await;
+    assert!(matches!(result, Err(AppError::NotFound { .. })));
+}
+
+/// Providers without range support fall back to filtering all quotes
+#[tokio::test]
+async fn test_default_get_quotes_range_filters_dates() {
+    let quotes = StaticProvider
+        .get_quotes_range(
+            "TEST",
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 4).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let prices: Vec<f64> = quotes.iter().map(|q| q.price).collect();
+    assert_eq!(prices, vec![102.0, 103.0, 104.0]);
+}
+
 /// Test fetching quotes for investment without ticker or ISIN
 #[tokio::test]
 async fn test_fetch_quotes_no_ticker() {
@@ -126,6 +419,13 @@ async fn test_fetch_quotes_no_ticker() {
         shortname: None,
         quote_provider: Some("yahoo".to_string()),
         ticker_symbol: None,
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     let created_id = investment_repo.create(&investment).await.unwrap();
@@ -166,6 +466,13 @@ async fn test_fetch_quotes_yahoo_online() {
         shortname: Some("AAPL".to_string()),
         quote_provider: Some("yahoo".to_string()),
         ticker_symbol: Some("AAPL".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     let created_id = investment_repo.create(&investment).await.unwrap();
@@ -229,6 +536,13 @@ async fn test_fetch_quotes_multiple_investments_online() {
         shortname: None,
         quote_provider: Some("yahoo".to_string()),
         ticker_symbol: Some("AAPL".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     let inv2 = Investment {
@@ -238,6 +552,13 @@ async fn test_fetch_quotes_multiple_investments_online() {
         shortname: None,
         quote_provider: Some("yahoo".to_string()),
         ticker_symbol: Some("MSFT".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     let created1_id = investment_repo.create(&inv1).await.unwrap();
@@ -285,6 +606,13 @@ async fn test_fetch_quotes_all_with_provider() {
         shortname: None,
         quote_provider: Some("yahoo".to_string()),
         ticker_symbol: Some("AAPL".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     // Create investment without provider
@@ -295,6 +623,13 @@ async fn test_fetch_quotes_all_with_provider() {
         shortname: None,
         quote_provider: None,
         ticker_symbol: Some("MSFT".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     investment_repo.create(&inv1).await.unwrap();
@@ -311,3 +646,134 @@ async fn test_fetch_quotes_all_with_provider() {
         "Should only process investment with provider configured"
     );
 }
+
+/// Test that live quotes are served from the intraday cache
+#[tokio::test]
+async fn test_live_quote_cache() {
+    let repos = Repositories::in_memory();
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Live".to_string()),
+            isin: None,
+            shortname: None,
+            quote_provider: Some("unknown_provider".to_string()),
+            ticker_symbol: Some("LIVE".to_string()),
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let service = QuoteFetcherService::new(
+        repos.investments.clone(),
+        repos.investment_prices.clone(),
+        "EUR".to_string(),
+    )
+    .with_intraday_prices(repos.intraday_prices.clone());
+
+    // Nothing stored and no provider delivering a price
+    let err = service.live_quote(investment_id).await.unwrap_err();
+    assert!(matches!(err, AppError::ExternalApi(_)), "{:?}", err);
+
+    let now = Utc::now();
+    let cached = IntradayPrice {
+        investment_id,
+        price: dec!(101.5),
+        currency: "EUR".to_string(),
+        original_price: dec!(101.5),
+        quoted_at: now - Duration::seconds(20),
+        fetched_at: now,
+        source: "yahoo".to_string(),
+    };
+    repos.intraday_prices.upsert(&cached).await.unwrap();
+    assert_eq!(service.live_quote(investment_id).await.unwrap(), cached);
+
+    // An outdated quote is still returned when it cannot be refreshed
+    let outdated = IntradayPrice {
+        fetched_at: now - Duration::minutes(10),
+        ..cached
+    };
+    repos.intraday_prices.upsert(&outdated).await.unwrap();
+    assert_eq!(service.live_quote(investment_id).await.unwrap(), outdated);
+
+    // The daily closes are not touched
+    assert!(repos
+        .investment_prices
+        .find_all(Some(investment_id), None, None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let err = service.live_quote(investment_id + 1).await.unwrap_err();
+    assert!(matches!(err, AppError::NotFound { .. }));
+}
+
+/// Test that disabled investments and investments fetched within their interval are skipped
+#[tokio::test]
+async fn test_fetch_quotes_schedule() {
+    let repos = Repositories::in_memory();
+    let mut ids = Vec::new();
+    for (name, enabled, interval_days) in [
+        ("Daily", true, None),
+        ("Delisted", false, None),
+        ("Weekly fetched", true, Some(7)),
+        ("Weekly due", true, Some(7)),
+    ] {
+        let investment = Investment {
+            id: 0,
+            name: Some(name.to_string()),
+            isin: None,
+            shortname: None,
+            quote_provider: Some("unknown_provider".to_string()),
+            ticker_symbol: Some(name.to_uppercase()),
+            currency: None,
+            asset_class: None,
+            watchlist: true,
+            partial_exemption: None,
+            quote_fetch_enabled: enabled,
+            quote_fetch_interval_days: interval_days,
+            archived: false,
+        };
+        ids.push(repos.investments.create(&investment).await.unwrap());
+    }
+    for (investment_id, days_ago) in [(ids[2], 2), (ids[3], 7)] {
+        repos
+            .quote_fetch_log
+            .create(&QuoteFetchLog {
+                id: 0,
+                fetched_at: Utc::now() - Duration::days(days_ago),
+                investment_id,
+                provider: Some("yahoo".to_string()),
+                success: false,
+                error: Some("No quote data returned from provider yahoo".to_string()),
+                quotes_stored: 0,
+            })
+            .await
+            .unwrap();
+    }
+
+    let service = QuoteFetcherService::new(
+        repos.investments.clone(),
+        repos.investment_prices.clone(),
+        "EUR".to_string(),
+    )
+    .with_fetch_log(repos.quote_fetch_log.clone());
+    let results = service.fetch_quotes(None).await.unwrap();
+    let fetched: Vec<i64> = results.iter().map(|r| r.investment_id).collect();
+    assert_eq!(fetched, vec![ids[0], ids[3]]);
+
+    // The weekly investment is not due again right after its fetch
+    let results = service.fetch_quotes(None).await.unwrap();
+    let fetched: Vec<i64> = results.iter().map(|r| r.investment_id).collect();
+    assert_eq!(fetched, vec![ids[0]]);
+
+    // Explicitly requested investments are fetched regardless of their schedule
+    let results = service.fetch_quotes(Some(vec![ids[1]])).await.unwrap();
+    assert_eq!(results.len(), 1);
+}
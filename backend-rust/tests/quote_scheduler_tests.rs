@@ -0,0 +1,92 @@
+mod test_helpers;
+
+use portfoliodb_rust::repository::sqlite::{
+    SqliteFxRateRepository, SqliteInvestmentPriceRepository, SqliteInvestmentRepository,
+    SqliteQuoteFetchLogRepository, SqliteSettingsRepository,
+};
+use portfoliodb_rust::repository::traits::{
+    FxRateRepository, InvestmentPriceRepository, InvestmentRepository, QuoteFetchLogRepository,
+    SettingsRepository,
+};
+use portfoliodb_rust::services::{QuoteFetchStatusTracker, QuoteScheduler};
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+async fn create_scheduler(
+    expression: &str,
+    tracker: QuoteFetchStatusTracker,
+) -> portfoliodb_rust::error::Result<QuoteScheduler> {
+    let pool = setup_test_db().await;
+
+    let investment_repo: Arc<dyn InvestmentRepository> =
+        Arc::new(SqliteInvestmentRepository::new(pool.clone()));
+    let price_repo: Arc<dyn InvestmentPriceRepository> =
+        Arc::new(SqliteInvestmentPriceRepository::new(pool.clone()));
+    let settings_repo: Arc<dyn SettingsRepository> =
+        Arc::new(SqliteSettingsRepository::new(pool.clone()));
+    let fetch_log_repo: Arc<dyn QuoteFetchLogRepository> =
+        Arc::new(SqliteQuoteFetchLogRepository::new(pool.clone()));
+    let fx_rate_repo: Arc<dyn FxRateRepository> =
+        Arc::new(SqliteFxRateRepository::new(pool.clone()));
+
+    QuoteScheduler::new(
+        expression,
+        investment_repo,
+        price_repo,
+        settings_repo,
+        fetch_log_repo,
+        fx_rate_repo,
+        tracker,
+    )
+}
+
+#[tokio::test]
+async fn test_invalid_cron_expression_is_rejected() {
+    let result = create_scheduler("not a cron", QuoteFetchStatusTracker::new()).await;
+
+    assert!(result.is_err());
+    assert!(result
+        .err()
+        .unwrap()
+        .to_string()
+        .contains("Invalid cron expression"));
+}
+
+#[tokio::test]
+async fn test_valid_cron_expression_has_next_run() {
+    let scheduler = create_scheduler("0 0 18 * * Mon-Fri", QuoteFetchStatusTracker::new())
+        .await
+        .unwrap();
+
+    let next = scheduler.next_run().unwrap();
+    assert!(next > chrono::Utc::now());
+}
+
+#[tokio::test]
+async fn test_status_is_empty_before_first_run() {
+    let tracker = QuoteFetchStatusTracker::new();
+    let status = tracker.get().await;
+
+    assert!(!status.enabled);
+    assert!(status.last_run_started.is_none());
+    assert!(status.last_run_success.is_none());
+}
+
+#[tokio::test]
+async fn test_run_once_records_status() {
+    let tracker = QuoteFetchStatusTracker::new();
+    let scheduler = create_scheduler("0 0 18 * * *", tracker.clone())
+        .await
+        .unwrap();
+
+    // No investments configured, so the run succeeds without network access
+    scheduler.run_once().await;
+
+    let status = tracker.get().await;
+    assert!(!status.running);
+    assert!(status.last_run_started.is_some());
+    assert!(status.last_run_finished.is_some());
+    assert_eq!(status.last_run_success, Some(true));
+    assert_eq!(status.total, 0);
+    assert_eq!(status.failed, 0);
+}
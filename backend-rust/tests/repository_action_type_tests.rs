@@ -1,7 +1,11 @@
 mod test_helpers;
 
-use portfoliodb_rust::repository::traits::ActionTypeRepository;
-use portfoliodb_rust::repository::SqliteActionTypeRepository;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::{ActionType, Movement};
+use portfoliodb_rust::repository::traits::{ActionTypeRepository, MovementRepository};
+use portfoliodb_rust::repository::{SqliteActionTypeRepository, SqliteMovementRepository};
+use rust_decimal_macros::dec;
 use test_helpers::setup_test_db;
 
 #[tokio::test]
@@ -11,8 +15,8 @@ async fn test_find_all_seeded_data() {
 
     let action_types = repo.find_all().await.unwrap();
 
-    // Should have 3 seeded action types
-    assert_eq!(action_types.len(), 3);
+    // Should have 9 seeded action types
+    assert_eq!(action_types.len(), 9);
 
     // Verify IDs and names
     assert_eq!(action_types[0].id, 1);
@@ -21,6 +25,17 @@ async fn test_find_all_seeded_data() {
     assert_eq!(action_types[1].name, "Sell");
     assert_eq!(action_types[2].id, 3);
     assert_eq!(action_types[2].name, "Payout");
+    assert_eq!(action_types[3].id, 4);
+    assert_eq!(action_types[3].name, "Deposit");
+    assert_eq!(action_types[4].id, 5);
+    assert_eq!(action_types[4].name, "Withdrawal");
+    assert_eq!(action_types[5].id, 6);
+    assert_eq!(action_types[5].name, "Split");
+    assert_eq!(action_types[6].name, "TransferOut");
+    assert_eq!(action_types[7].name, "TransferIn");
+    assert_eq!(action_types[8].id, 9);
+    assert_eq!(action_types[8].name, "Opening");
+    assert_eq!(action_types[8].effect, "opening");
 }
 
 #[tokio::test]
@@ -44,3 +59,89 @@ async fn test_find_by_id_nonexistent() {
     let action_type = repo.find_by_id(999).await.unwrap();
     assert!(action_type.is_none());
 }
+
+#[tokio::test]
+async fn test_built_in_effects() {
+    let pool = setup_test_db().await;
+    let repo = SqliteActionTypeRepository::new(pool);
+
+    let effects: Vec<String> = repo
+        .find_all()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.effect)
+        .collect();
+
+    assert_eq!(
+        effects,
+        [
+            "increases_quantity",
+            "decreases_quantity",
+            "cash_only",
+            "cash_only",
+            "cash_only",
+            "split",
+            "transfer_out",
+            "transfer_in",
+            "opening",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_create_update_and_delete() {
+    let pool = setup_test_db().await;
+    let repo = SqliteActionTypeRepository::new(pool);
+
+    let mut action_type = ActionType {
+        id: 0,
+        name: "Fee".to_string(),
+        effect: "cash_only".to_string(),
+    };
+    let id = repo.create(&action_type).await.unwrap();
+    assert!(id > 9);
+
+    action_type.name = "Custody fee".to_string();
+    repo.update(id, &action_type).await.unwrap();
+    let found = repo.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(found.name, "Custody fee");
+    assert_eq!(found.effect, "cash_only");
+
+    repo.delete(id).await.unwrap();
+    assert!(repo.find_by_id(id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_delete_used_action_type_is_rejected() {
+    let pool = setup_test_db().await;
+    let repo = SqliteActionTypeRepository::new(pool.clone());
+    let id = repo
+        .create(&ActionType {
+            id: 0,
+            name: "Bonus shares".to_string(),
+            effect: "increases_quantity".to_string(),
+        })
+        .await
+        .unwrap();
+    SqliteMovementRepository::new(pool)
+        .create(&Movement {
+            id: 0,
+            date: NaiveDate::from_ymd_opt(2024, 1, 15),
+            action_id: Some(id),
+            investment_id: None,
+            quantity: Some(dec!(1.0)),
+            amount: Some(dec!(0.0)),
+            fee: None,
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        })
+        .await
+        .unwrap();
+
+    let err = repo.delete(id).await.unwrap_err();
+
+    assert!(matches!(err, AppError::Conflict(_)));
+    assert!(repo.find_by_id(id).await.unwrap().is_some());
+}
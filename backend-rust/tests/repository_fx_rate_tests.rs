@@ -0,0 +1,106 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::FxRate;
+use portfoliodb_rust::repository::traits::FxRateRepository;
+use portfoliodb_rust::repository::SqliteFxRateRepository;
+use portfoliodb_rust::services::CurrencyConverter;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn rate(day: u32, from: &str, to: &str, rate: f64) -> FxRate {
+    FxRate {
+        date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+        from_currency: from.to_string(),
+        to_currency: to.to_string(),
+        rate,
+    }
+}
+
+#[tokio::test]
+async fn test_upsert_and_find() {
+    let pool = setup_test_db().await;
+    let repo = SqliteFxRateRepository::new(pool);
+
+    repo.upsert(&rate(2, "USD", "EUR", 0.91)).await.unwrap();
+    repo.upsert(&rate(2, "USD", "EUR", 0.92)).await.unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+    let cached = repo.find(date, "USD", "EUR").await.unwrap().unwrap();
+    assert_eq!(cached.rate, 0.92);
+
+    assert!(repo.find(date, "EUR", "USD").await.unwrap().is_none());
+    assert_eq!(
+        repo.find_all(None, None, None, None).await.unwrap().len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_find_all_filters() {
+    let pool = setup_test_db().await;
+    let repo = SqliteFxRateRepository::new(pool);
+
+    repo.upsert(&rate(2, "USD", "EUR", 0.91)).await.unwrap();
+    repo.upsert(&rate(3, "USD", "EUR", 0.92)).await.unwrap();
+    repo.upsert(&rate(3, "GBP", "EUR", 1.16)).await.unwrap();
+    repo.upsert(&rate(4, "USD", "CHF", 0.86)).await.unwrap();
+
+    let usd = repo.find_all(Some("USD"), None, None, None).await.unwrap();
+    assert_eq!(usd.len(), 3);
+    // Newest first
+    assert_eq!(usd[0].date, NaiveDate::from_ymd_opt(2024, 1, 4).unwrap());
+
+    let usd_eur = repo
+        .find_all(Some("USD"), Some("EUR"), None, None)
+        .await
+        .unwrap();
+    assert_eq!(usd_eur.len(), 2);
+
+    let day_3 = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+    let only_day_3 = repo
+        .find_all(None, None, Some(day_3), Some(day_3))
+        .await
+        .unwrap();
+    assert_eq!(only_day_3.len(), 2);
+    assert!(only_day_3.iter().all(|r| r.date == day_3));
+}
+
+#[tokio::test]
+async fn test_converter_uses_cached_rate() {
+    let pool = setup_test_db().await;
+    let repo: Arc<dyn FxRateRepository> = Arc::new(SqliteFxRateRepository::new(pool));
+    repo.upsert(&rate(2, "USD", "EUR", 0.5)).await.unwrap();
+
+    // The cached rate is served without contacting the exchange rate API
+    let converter = CurrencyConverter::new().with_cache(repo);
+    let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+    let converted = converter
+        .convert(dec!(10.0), "USD", "EUR", date)
+        .await
+        .unwrap();
+    assert_eq!(converted, Some(dec!(5.0)));
+
+    let rate = converter.get_rate("EUR", "EUR", date).await.unwrap();
+    assert_eq!(rate, Some(1.0));
+}
+
+#[tokio::test]
+async fn test_convert_series_uses_cached_rates() {
+    let pool = setup_test_db().await;
+    let repo: Arc<dyn FxRateRepository> = Arc::new(SqliteFxRateRepository::new(pool));
+    repo.upsert(&rate(2, "USD", "EUR", 0.5)).await.unwrap();
+    repo.upsert(&rate(3, "USD", "EUR", 0.25)).await.unwrap();
+
+    let converter = CurrencyConverter::new().with_cache(repo);
+    let values = vec![
+        (NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(), dec!(8.0)),
+        (NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(), dec!(8.0)),
+    ];
+    let converted = converter
+        .convert_series(&values, "USD", "EUR")
+        .await
+        .unwrap();
+    assert_eq!(converted, vec![Some(dec!(2.0)), Some(dec!(4.0))]);
+}
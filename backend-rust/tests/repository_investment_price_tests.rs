@@ -1,9 +1,16 @@
 mod test_helpers;
 
-use chrono::NaiveDate;
-use portfoliodb_rust::models::{Investment, InvestmentPrice};
-use portfoliodb_rust::repository::traits::{InvestmentPriceRepository, InvestmentRepository};
-use portfoliodb_rust::repository::{SqliteInvestmentPriceRepository, SqliteInvestmentRepository};
+use chrono::{NaiveDate, TimeZone, Utc};
+use portfoliodb_rust::db;
+use portfoliodb_rust::models::{IntradayPrice, Investment, InvestmentPrice};
+use portfoliodb_rust::repository::traits::{
+    IntradayPriceRepository, InvestmentPriceRepository, InvestmentRepository,
+};
+use portfoliodb_rust::repository::{
+    SqliteIntradayPriceRepository, SqliteInvestmentPriceRepository, SqliteInvestmentRepository,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use test_helpers::setup_test_db;
 
 #[tokio::test]
@@ -29,6 +36,13 @@ async fn test_create_price() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -36,8 +50,10 @@ async fn test_create_price() {
     let price = InvestmentPrice {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
         investment_id: Some(inv_id),
-        price: Some(50.25),
+        price: Some(dec!(50.25)),
         source: Some("yahoo".to_string()),
+        currency: None,
+        original_price: None,
     };
 
     price_repo.create(&price).await.unwrap();
@@ -61,6 +77,13 @@ async fn test_find_all_with_investment_filter() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -73,6 +96,13 @@ async fn test_find_all_with_investment_filter() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -82,8 +112,10 @@ async fn test_find_all_with_investment_filter() {
         .create(&InvestmentPrice {
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
             investment_id: Some(inv1_id),
-            price: Some(100.0),
+            price: Some(dec!(100.0)),
             source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
         })
         .await
         .unwrap();
@@ -92,8 +124,10 @@ async fn test_find_all_with_investment_filter() {
         .create(&InvestmentPrice {
             date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
             investment_id: Some(inv2_id),
-            price: Some(200.0),
+            price: Some(dec!(200.0)),
             source: Some("test".to_string()),
+            currency: None,
+            original_price: None,
         })
         .await
         .unwrap();
@@ -105,7 +139,7 @@ async fn test_find_all_with_investment_filter() {
         .unwrap();
     assert_eq!(prices.len(), 1);
     assert_eq!(prices[0].investment_id, Some(inv1_id));
-    assert_eq!(prices[0].price, Some(100.0));
+    assert_eq!(prices[0].price, Some(dec!(100.0)));
 }
 
 #[tokio::test]
@@ -122,6 +156,13 @@ async fn test_find_all_with_date_range() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -132,8 +173,10 @@ async fn test_find_all_with_date_range() {
             .create(&InvestmentPrice {
                 date: Some(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
                 investment_id: Some(inv_id),
-                price: Some(100.0 + day as f64),
+                price: Some(Decimal::from(100 + day)),
                 source: Some("test".to_string()),
+                currency: None,
+                original_price: None,
             })
             .await
             .unwrap();
@@ -164,6 +207,13 @@ async fn test_find_all_with_start_date_only() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -174,8 +224,10 @@ async fn test_find_all_with_start_date_only() {
             .create(&InvestmentPrice {
                 date: Some(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
                 investment_id: Some(inv_id),
-                price: Some(100.0),
+                price: Some(dec!(100.0)),
                 source: Some("test".to_string()),
+                currency: None,
+                original_price: None,
             })
             .await
             .unwrap();
@@ -202,6 +254,13 @@ async fn test_upsert_insert() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -209,8 +268,10 @@ async fn test_upsert_insert() {
     let price = InvestmentPrice {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
         investment_id: Some(inv_id),
-        price: Some(100.0),
+        price: Some(dec!(100.0)),
         source: Some("yahoo".to_string()),
+        currency: None,
+        original_price: None,
     };
 
     // Upsert (insert)
@@ -218,7 +279,7 @@ async fn test_upsert_insert() {
 
     let prices = price_repo.find_all(None, None, None).await.unwrap();
     assert_eq!(prices.len(), 1);
-    assert_eq!(prices[0].price, Some(100.0));
+    assert_eq!(prices[0].price, Some(dec!(100.0)));
 }
 
 #[tokio::test]
@@ -235,6 +296,13 @@ async fn test_upsert_update() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -245,8 +313,10 @@ async fn test_upsert_update() {
     let price1 = InvestmentPrice {
         date: Some(date),
         investment_id: Some(inv_id),
-        price: Some(100.0),
+        price: Some(dec!(100.0)),
         source: Some("yahoo".to_string()),
+        currency: None,
+        original_price: None,
     };
     price_repo.create(&price1).await.unwrap();
 
@@ -254,23 +324,27 @@ async fn test_upsert_update() {
     let price2 = InvestmentPrice {
         date: Some(date),
         investment_id: Some(inv_id),
-        price: Some(150.0),
+        price: Some(dec!(150.0)),
         source: Some("yahoo".to_string()),
+        currency: None,
+        original_price: None,
     };
     price_repo.upsert(&price2).await.unwrap();
 
     // Should still have only 1 record, but with updated price
     let prices = price_repo.find_all(None, None, None).await.unwrap();
     assert_eq!(prices.len(), 1);
-    assert_eq!(prices[0].price, Some(150.0));
+    assert_eq!(prices[0].price, Some(dec!(150.0)));
     assert_eq!(prices[0].source, Some("yahoo".to_string()));
 
     // Upsert with different source - should create new record
     let price3 = InvestmentPrice {
         date: Some(date),
         investment_id: Some(inv_id),
-        price: Some(200.0),
+        price: Some(dec!(200.0)),
         source: Some("justetf".to_string()),
+        currency: None,
+        original_price: None,
     };
     price_repo.upsert(&price3).await.unwrap();
 
@@ -293,6 +367,13 @@ async fn test_decimal_to_real_conversion() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -300,14 +381,16 @@ async fn test_decimal_to_real_conversion() {
     let price = InvestmentPrice {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
         investment_id: Some(inv_id),
-        price: Some(123.456),
+        price: Some(dec!(123.456)),
         source: Some("test".to_string()),
+        currency: None,
+        original_price: None,
     };
 
     price_repo.create(&price).await.unwrap();
 
     let prices = price_repo.find_all(None, None, None).await.unwrap();
-    assert_eq!(prices[0].price, Some(123.456));
+    assert_eq!(prices[0].price, Some(dec!(123.456)));
 }
 
 #[tokio::test]
@@ -325,6 +408,13 @@ async fn test_combined_filters() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -337,6 +427,13 @@ async fn test_combined_filters() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -347,8 +444,10 @@ async fn test_combined_filters() {
             .create(&InvestmentPrice {
                 date: Some(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
                 investment_id: Some(inv1_id),
-                price: Some(100.0),
+                price: Some(dec!(100.0)),
                 source: Some("test".to_string()),
+                currency: None,
+                original_price: None,
             })
             .await
             .unwrap();
@@ -357,8 +456,10 @@ async fn test_combined_filters() {
             .create(&InvestmentPrice {
                 date: Some(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
                 investment_id: Some(inv2_id),
-                price: Some(200.0),
+                price: Some(dec!(200.0)),
                 source: Some("test".to_string()),
+                currency: None,
+                original_price: None,
             })
             .await
             .unwrap();
@@ -377,3 +478,223 @@ async fn test_combined_filters() {
         assert_eq!(price.investment_id, Some(inv1_id));
     }
 }
+
+#[tokio::test]
+async fn test_upsert_keeps_original_currency_and_price() {
+    let pool = setup_test_db().await;
+    let price_repo = SqliteInvestmentPriceRepository::new(pool);
+
+    let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+    for (price, original_price) in [(dec!(90.0), dec!(100.0)), (dec!(91.0), dec!(101.0))] {
+        price_repo
+            .upsert(&InvestmentPrice {
+                date: Some(date),
+                investment_id: Some(1),
+                price: Some(price),
+                source: Some("yahoo".to_string()),
+                currency: Some("USD".to_string()),
+                original_price: Some(original_price),
+            })
+            .await
+            .unwrap();
+    }
+
+    let prices = price_repo.find_all(Some(1), None, None).await.unwrap();
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices[0].price, Some(dec!(91.0)));
+    assert_eq!(prices[0].currency.as_deref(), Some("USD"));
+    assert_eq!(prices[0].original_price, Some(dec!(101.0)));
+}
+
+#[tokio::test]
+async fn test_upsert_without_source_updates_manual_price() {
+    let pool = setup_test_db().await;
+    let price_repo = SqliteInvestmentPriceRepository::new(pool);
+
+    for price in [dec!(100.0), dec!(101.0)] {
+        price_repo
+            .upsert(&InvestmentPrice {
+                date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+                investment_id: Some(1),
+                price: Some(price),
+                source: None,
+                currency: None,
+                original_price: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let prices = price_repo.find_all(None, None, None).await.unwrap();
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices[0].price, Some(dec!(101.0)));
+    assert_eq!(prices[0].source.as_deref(), Some("manual"));
+}
+
+#[tokio::test]
+async fn test_find_latest_date_by_source() {
+    let pool = setup_test_db().await;
+    let price_repo = SqliteInvestmentPriceRepository::new(pool);
+
+    assert_eq!(price_repo.find_latest_date(1, "yahoo").await.unwrap(), None);
+
+    for (day, source) in [(10, "yahoo"), (12, "yahoo"), (15, "justetf")] {
+        price_repo
+            .upsert(&InvestmentPrice {
+                date: Some(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
+                investment_id: Some(1),
+                price: Some(dec!(100.0)),
+                source: Some(source.to_string()),
+                currency: None,
+                original_price: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(
+        price_repo.find_latest_date(1, "yahoo").await.unwrap(),
+        NaiveDate::from_ymd_opt(2024, 1, 12)
+    );
+    assert_eq!(
+        price_repo.find_latest_date(1, "justetf").await.unwrap(),
+        NaiveDate::from_ymd_opt(2024, 1, 15)
+    );
+    assert_eq!(price_repo.find_latest_date(2, "yahoo").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_intraday_price_upsert() {
+    let pool = setup_test_db().await;
+    let repo = SqliteIntradayPriceRepository::new(pool.clone());
+    let inv_id = SqliteInvestmentRepository::new(pool)
+        .create(&Investment {
+            id: 0,
+            name: Some("Live".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(repo.find(inv_id).await.unwrap(), None);
+
+    let quoted_at = Utc.with_ymd_and_hms(2024, 6, 5, 16, 25, 0).unwrap();
+    let price = IntradayPrice {
+        investment_id: inv_id,
+        price: dec!(180.5),
+        currency: "USD".to_string(),
+        original_price: dec!(196.45),
+        quoted_at,
+        fetched_at: quoted_at,
+        source: "yahoo".to_string(),
+    };
+    repo.upsert(&price).await.unwrap();
+    assert_eq!(repo.find(inv_id).await.unwrap(), Some(price.clone()));
+
+    // Only the latest quote is kept
+    let later = IntradayPrice {
+        price: dec!(181),
+        quoted_at: quoted_at + chrono::Duration::minutes(5),
+        fetched_at: quoted_at + chrono::Duration::minutes(5),
+        ..price
+    };
+    repo.upsert(&later).await.unwrap();
+    assert_eq!(repo.find(inv_id).await.unwrap(), Some(later));
+}
+
+/// Keep the write lock of the database for the given time from another connection
+async fn hold_write_lock(
+    pool: &sqlx::SqlitePool,
+    duration: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    let mut writer = pool.begin_with("BEGIN IMMEDIATE").await.unwrap();
+    sqlx::query("UPDATE Settings SET BaseCurrency = BaseCurrency")
+        .execute(&mut *writer)
+        .await
+        .unwrap();
+    tokio::spawn(async move {
+        tokio::time::sleep(duration).await;
+        writer.commit().await.unwrap();
+    })
+}
+
+#[tokio::test]
+async fn test_upsert_many_waits_for_locked_database() {
+    let path = std::env::temp_dir().join(format!("portfoliodb-busy-{}.db", std::process::id()));
+    let url = format!("sqlite://{}?mode=rwc", path.display());
+    let pool = sqlx::SqlitePool::connect_with(db::sqlite_options(&url).unwrap())
+        .await
+        .unwrap();
+    db::run_migrations(&pool).await.unwrap();
+
+    let (journal_mode,): (String,) = sqlx::query_as("PRAGMA journal_mode")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(journal_mode, "wal");
+
+    let inv_id = SqliteInvestmentRepository::new(pool.clone())
+        .create(&Investment {
+            id: 0,
+            name: Some("Test".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let prices = vec![InvestmentPrice {
+        date: Some(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
+        investment_id: Some(inv_id),
+        price: Some(dec!(50.25)),
+        source: Some("yahoo".to_string()),
+        currency: None,
+        original_price: None,
+    }];
+
+    // Without a busy timeout only the retries of the repository wait for the lock
+    let impatient = sqlx::SqlitePool::connect_with(
+        db::sqlite_options(&url)
+            .unwrap()
+            .busy_timeout(std::time::Duration::ZERO),
+    )
+    .await
+    .unwrap();
+    let repo = SqliteInvestmentPriceRepository::new(impatient);
+
+    let writer = hold_write_lock(&pool, std::time::Duration::from_millis(150)).await;
+    repo.upsert_many(&prices).await.unwrap();
+    writer.await.unwrap();
+    assert_eq!(repo.find_all(None, None, None).await.unwrap().len(), 1);
+
+    let writer = hold_write_lock(&pool, std::time::Duration::from_secs(3)).await;
+    let error = repo.upsert_many(&prices).await.unwrap_err();
+    assert!(error.is_busy());
+    assert_eq!(
+        error.status_and_message().0,
+        axum::http::StatusCode::SERVICE_UNAVAILABLE
+    );
+    writer.await.unwrap();
+
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+}
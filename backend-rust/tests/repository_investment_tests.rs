@@ -1,8 +1,15 @@
 mod test_helpers;
 
-use portfoliodb_rust::models::Investment;
-use portfoliodb_rust::repository::traits::InvestmentRepository;
-use portfoliodb_rust::repository::SqliteInvestmentRepository;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::{Investment, InvestmentDependents, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::traits::{
+    InvestmentPriceRepository, InvestmentRepository, MovementRepository,
+};
+use portfoliodb_rust::repository::{
+    SqliteInvestmentPriceRepository, SqliteInvestmentRepository, SqliteMovementRepository,
+};
+use rust_decimal_macros::dec;
 use test_helpers::setup_test_db;
 
 #[tokio::test]
@@ -26,6 +33,13 @@ async fn test_create_investment() {
         shortname: Some("TEST".to_string()),
         ticker_symbol: Some("TST".to_string()),
         quote_provider: Some("yahoo".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     let id = repo.create(&investment).await.unwrap();
@@ -44,6 +58,13 @@ async fn test_create_and_find_by_id() {
         shortname: Some("AAPL".to_string()),
         ticker_symbol: Some("AAPL".to_string()),
         quote_provider: Some("yahoo".to_string()),
+        currency: Some("USD".to_string()),
+        asset_class: Some("stock".to_string()),
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     let id = repo.create(&investment).await.unwrap();
@@ -55,6 +76,8 @@ async fn test_create_and_find_by_id() {
     assert_eq!(found.name, Some("Apple Inc.".to_string()));
     assert_eq!(found.isin, Some("US0378331005".to_string()));
     assert_eq!(found.shortname, Some("AAPL".to_string()));
+    assert_eq!(found.currency, Some("USD".to_string()));
+    assert_eq!(found.asset_class, Some("stock".to_string()));
 }
 
 #[tokio::test]
@@ -80,6 +103,13 @@ async fn test_find_all_multiple() {
             shortname: Some(format!("INV{}", i)),
             ticker_symbol: Some(format!("INV{}", i)),
             quote_provider: Some("yahoo".to_string()),
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         };
         repo.create(&investment).await.unwrap();
     }
@@ -101,6 +131,13 @@ async fn test_update_investment() {
         shortname: Some("ORIG".to_string()),
         ticker_symbol: Some("ORIG".to_string()),
         quote_provider: Some("yahoo".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
     let id = repo.create(&investment).await.unwrap();
 
@@ -112,6 +149,13 @@ async fn test_update_investment() {
         shortname: Some("UPD".to_string()),
         ticker_symbol: Some("UPD".to_string()),
         quote_provider: Some("justETF".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: true,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
     repo.update(id, &updated).await.unwrap();
 
@@ -121,6 +165,7 @@ async fn test_update_investment() {
     assert_eq!(found.isin, Some("US0987654321".to_string()));
     assert_eq!(found.shortname, Some("UPD".to_string()));
     assert_eq!(found.quote_provider, Some("justETF".to_string()));
+    assert!(found.watchlist);
 }
 
 #[tokio::test]
@@ -136,6 +181,13 @@ async fn test_delete_investment() {
         shortname: Some("DEL".to_string()),
         ticker_symbol: Some("DEL".to_string()),
         quote_provider: Some("yahoo".to_string()),
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
     let id = repo.create(&investment).await.unwrap();
 
@@ -161,6 +213,13 @@ async fn test_create_with_optional_fields() {
         shortname: None,
         ticker_symbol: None,
         quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
 
     let id = repo.create(&investment).await.unwrap();
@@ -172,3 +231,119 @@ async fn test_create_with_optional_fields() {
     assert!(found.ticker_symbol.is_none());
     assert!(found.quote_provider.is_none());
 }
+
+#[tokio::test]
+async fn test_delete_with_dependents() {
+    let pool = setup_test_db().await;
+    let repo = SqliteInvestmentRepository::new(pool.clone());
+    let movement_repo = SqliteMovementRepository::new(pool.clone());
+    let price_repo = SqliteInvestmentPriceRepository::new(pool);
+
+    let id = repo
+        .create(&Investment {
+            id: 0,
+            name: Some("With dependents".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+    movement_repo
+        .create(&Movement {
+            id: 0,
+            date: Some(date),
+            action_id: Some(1),
+            investment_id: Some(id),
+            quantity: Some(dec!(1.0)),
+            amount: Some(dec!(10.0)),
+            fee: None,
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        })
+        .await
+        .unwrap();
+    price_repo
+        .upsert(&InvestmentPrice {
+            date: Some(date),
+            investment_id: Some(id),
+            price: Some(dec!(10.0)),
+            source: Some("manual".to_string()),
+            currency: None,
+            original_price: None,
+        })
+        .await
+        .unwrap();
+
+    // Rejected without cascade, nothing is deleted
+    let err = repo.delete_with_dependents(id, false).await.unwrap_err();
+    match err {
+        AppError::Conflict(msg) => assert!(msg.contains("1 movement(s) and 1 price(s)")),
+        other => panic!("Expected conflict, got {:?}", other),
+    }
+    assert!(repo.find_by_id(id).await.unwrap().is_some());
+
+    let deleted = repo.delete_with_dependents(id, true).await.unwrap();
+    assert_eq!(
+        deleted,
+        InvestmentDependents {
+            movements: 1,
+            prices: 1
+        }
+    );
+    assert!(repo.find_by_id(id).await.unwrap().is_none());
+    assert!(movement_repo.find_all().await.unwrap().is_empty());
+    assert!(price_repo
+        .find_all(Some(id), None, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_delete_with_dependents_without_dependents() {
+    let pool = setup_test_db().await;
+    let repo = SqliteInvestmentRepository::new(pool);
+
+    let id = repo
+        .create(&Investment {
+            id: 0,
+            name: Some("Unused".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    let deleted = repo.delete_with_dependents(id, false).await.unwrap();
+    assert!(deleted.is_empty());
+    assert!(repo.find_by_id(id).await.unwrap().is_none());
+
+    let err = repo.delete_with_dependents(id, true).await.unwrap_err();
+    assert!(matches!(
+        err,
+        AppError::NotFound {
+            entity: "Investment",
+            ..
+        }
+    ));
+}
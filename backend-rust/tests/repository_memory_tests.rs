@@ -0,0 +1,262 @@
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::{
+    DataExport, ImportMode, Investment, InvestmentPrice, Movement, MovementListOptions, PriceAlert,
+    SortOrder, Tag,
+};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::{PortfolioCalculator, SnapshotService};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+fn date(m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, m, d).unwrap()
+}
+
+fn investment(name: &str, isin: Option<&str>) -> Investment {
+    Investment {
+        id: 0,
+        name: Some(name.to_string()),
+        isin: isin.map(str::to_string),
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    }
+}
+
+fn buy(date: NaiveDate, investment_id: i64, quantity: Decimal, amount: Decimal) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(1),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn price(date: NaiveDate, investment_id: i64, price: Decimal) -> InvestmentPrice {
+    InvestmentPrice {
+        date: Some(date),
+        investment_id: Some(investment_id),
+        price: Some(price),
+        source: None,
+        currency: None,
+        original_price: None,
+    }
+}
+
+#[tokio::test]
+async fn test_in_memory_defaults() {
+    let repos = Repositories::in_memory();
+
+    let action_types = repos.action_types.find_all().await.unwrap();
+    assert_eq!(action_types.len(), 9);
+    assert_eq!(action_types[0].name, "Buy");
+    let settings = repos.settings.get().await.unwrap().unwrap();
+    assert_eq!(settings.base_currency, "EUR");
+    assert!(repos.health.check().await.unwrap().is_current());
+}
+
+#[tokio::test]
+async fn test_in_memory_investment_delete_follows_database_rules() {
+    let repos = Repositories::in_memory();
+    let id = repos
+        .investments
+        .create(&investment("Fund", None))
+        .await
+        .unwrap();
+    repos
+        .movements
+        .create(&buy(date(1, 10), id, dec!(10), dec!(1000)))
+        .await
+        .unwrap();
+    repos
+        .investment_prices
+        .create(&price(date(1, 31), id, dec!(110)))
+        .await
+        .unwrap();
+    repos
+        .price_alerts
+        .create(&PriceAlert {
+            id: 0,
+            investment_id: id,
+            threshold: 120.0,
+            direction: "above".to_string(),
+            active: true,
+        })
+        .await
+        .unwrap();
+    let tag_id = repos
+        .tags
+        .create(&Tag {
+            id: 0,
+            name: "Core".to_string(),
+        })
+        .await
+        .unwrap();
+    repos.tags.set_investment_tags(id, &[tag_id]).await.unwrap();
+
+    let err = repos
+        .investments
+        .delete_with_dependents(id, false)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::Conflict(_)));
+
+    let removed = repos
+        .investments
+        .delete_with_dependents(id, true)
+        .await
+        .unwrap();
+    assert_eq!(removed.movements, 1);
+    assert_eq!(removed.prices, 1);
+    assert!(repos.investments.find_by_id(id).await.unwrap().is_none());
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+    assert!(repos.price_alerts.find_all(None).await.unwrap().is_empty());
+    assert!(repos
+        .tags
+        .find_investment_ids(tag_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_in_memory_movements_page_and_external_ids() {
+    let repos = Repositories::in_memory();
+    let id = repos
+        .investments
+        .create(&investment("Fund", None))
+        .await
+        .unwrap();
+    for day in 1..=5 {
+        repos
+            .movements
+            .create(&Movement {
+                external_id: Some(format!("ext-{}", day)),
+                ..buy(date(1, day), id, dec!(1), dec!(100))
+            })
+            .await
+            .unwrap();
+    }
+
+    let (page, total) = repos
+        .movements
+        .find_page(&MovementListOptions {
+            order: SortOrder::Desc,
+            limit: Some(2),
+            offset: 1,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(total, 5);
+    let dates: Vec<_> = page.iter().map(|m| m.date.unwrap()).collect();
+    assert_eq!(dates, vec![date(1, 4), date(1, 3)]);
+
+    let duplicate = Movement {
+        external_id: Some("ext-1".to_string()),
+        ..buy(date(2, 1), id, dec!(1), dec!(100))
+    };
+    let err = repos.movements.create(&duplicate).await.unwrap_err();
+    assert!(matches!(err, AppError::Conflict(_)));
+    // A failing batch inserts nothing
+    let fresh = buy(date(2, 2), id, dec!(1), dec!(100));
+    assert!(repos
+        .movements
+        .create_many(&[fresh, duplicate])
+        .await
+        .is_err());
+    assert_eq!(repos.movements.find_all().await.unwrap().len(), 5);
+}
+
+#[tokio::test]
+async fn test_in_memory_import_merges_by_isin() {
+    let repos = Repositories::in_memory();
+    let existing = repos
+        .investments
+        .create(&investment("World ETF", Some("IE00B4L5Y983")))
+        .await
+        .unwrap();
+
+    let export = DataExport {
+        version: 1,
+        exported_at: chrono::Utc::now(),
+        settings: None,
+        action_types: vec![],
+        portfolios: vec![],
+        investments: vec![
+            Investment {
+                id: 7,
+                ..investment("World ETF", Some("IE00B4L5Y983"))
+            },
+            Investment {
+                id: 8,
+                ..investment("Bond ETF", None)
+            },
+        ],
+        movements: vec![buy(date(1, 10), 8, dec!(2), dec!(200))],
+        cash_movements: vec![],
+        prices: vec![price(date(1, 31), 7, dec!(90))],
+    };
+    let summary = repos
+        .data_import
+        .import(&export, ImportMode::Merge)
+        .await
+        .unwrap();
+
+    assert_eq!(summary.investments, 1);
+    assert_eq!(summary.movements, 1);
+    assert_eq!(repos.investments.find_all().await.unwrap().len(), 2);
+    let prices = repos
+        .investment_prices
+        .find_all(Some(existing), None, None)
+        .await
+        .unwrap();
+    assert_eq!(prices.len(), 1);
+    let movement = &repos.movements.find_all().await.unwrap()[0];
+    assert_ne!(movement.investment_id, Some(8));
+}
+
+#[tokio::test]
+async fn test_services_run_on_in_memory_repositories() {
+    let repos = Repositories::in_memory();
+    let id = repos
+        .investments
+        .create(&investment("Fund", None))
+        .await
+        .unwrap();
+    repos
+        .movements
+        .create(&buy(date(1, 10), id, dec!(10), dec!(1000)))
+        .await
+        .unwrap();
+    repos
+        .investment_prices
+        .create(&price(date(1, 31), id, dec!(110)))
+        .await
+        .unwrap();
+
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    let snapshots = SnapshotService::new(repos.snapshots.clone(), calculator);
+    let snapshot = snapshots.take(date(1, 31), false).await.unwrap();
+
+    assert_eq!(snapshot.total_value, dec!(1100));
+    assert_eq!(snapshots.list().await.unwrap().len(), 1);
+}
@@ -1,9 +1,22 @@
 mod test_helpers;
 
 use chrono::NaiveDate;
-use portfoliodb_rust::models::{Investment, Movement};
-use portfoliodb_rust::repository::traits::{InvestmentRepository, MovementRepository};
-use portfoliodb_rust::repository::{SqliteInvestmentRepository, SqliteMovementRepository};
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::{
+    Investment, Movement, MovementListOptions, MovementSortField, Portfolio, SortOrder,
+};
+use portfoliodb_rust::repository::traits::{
+    InvestmentRepository, MovementRepository, PortfolioRepository,
+};
+use portfoliodb_rust::repository::{
+    Repositories, SqliteInvestmentRepository, SqliteMovementRepository, SqlitePortfolioRepository,
+};
+use portfoliodb_rust::services::cost_basis::{
+    SPLIT_ACTION_ID, TRANSFER_IN_ACTION_ID, TRANSFER_OUT_ACTION_ID,
+};
+use portfoliodb_rust::services::positions::{current_quantities, quantity_at};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
 use test_helpers::setup_test_db;
 
 #[tokio::test]
@@ -29,6 +42,13 @@ async fn test_create_movement() {
         shortname: None,
         ticker_symbol: None,
         quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
     };
     let inv_id = investment_repo.create(&investment).await.unwrap();
 
@@ -38,9 +58,12 @@ async fn test_create_movement() {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
         action_id: Some(1), // Buy
         investment_id: Some(inv_id),
-        quantity: Some(10.0),
-        amount: Some(100.0),
-        fee: Some(1.5),
+        quantity: Some(dec!(10.0)),
+        amount: Some(dec!(100.0)),
+        fee: Some(dec!(1.5)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     };
 
     let id = movement_repo.create(&movement).await.unwrap();
@@ -61,6 +84,13 @@ async fn test_create_and_find_by_id() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -70,9 +100,12 @@ async fn test_create_and_find_by_id() {
         date: Some(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()),
         action_id: Some(2), // Sell
         investment_id: Some(inv_id),
-        quantity: Some(5.0),
-        amount: Some(60.0),
-        fee: Some(0.5),
+        quantity: Some(dec!(5.0)),
+        amount: Some(dec!(60.0)),
+        fee: Some(dec!(0.5)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     };
 
     let id = movement_repo.create(&movement).await.unwrap();
@@ -87,9 +120,9 @@ async fn test_create_and_find_by_id() {
     );
     assert_eq!(found.action_id, Some(2));
     assert_eq!(found.investment_id, Some(inv_id));
-    assert_eq!(found.quantity, Some(5.0));
-    assert_eq!(found.amount, Some(60.0));
-    assert_eq!(found.fee, Some(0.5));
+    assert_eq!(found.quantity, Some(dec!(5.0)));
+    assert_eq!(found.amount, Some(dec!(60.0)));
+    assert_eq!(found.fee, Some(dec!(0.5)));
 }
 
 #[tokio::test]
@@ -115,6 +148,13 @@ async fn test_update_movement() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -125,9 +165,12 @@ async fn test_update_movement() {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
         action_id: Some(1),
         investment_id: Some(inv_id),
-        quantity: Some(10.0),
-        amount: Some(100.0),
-        fee: Some(1.0),
+        quantity: Some(dec!(10.0)),
+        amount: Some(dec!(100.0)),
+        fee: Some(dec!(1.0)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     };
     let id = movement_repo.create(&movement).await.unwrap();
 
@@ -137,11 +180,15 @@ async fn test_update_movement() {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
         action_id: Some(2),
         investment_id: Some(inv_id),
-        quantity: Some(15.0),
-        amount: Some(150.0),
-        fee: Some(2.0),
+        quantity: Some(dec!(15.0)),
+        amount: Some(dec!(150.0)),
+        fee: Some(dec!(2.0)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     };
-    movement_repo.update(id, &updated).await.unwrap();
+    assert_eq!(movement_repo.update(id, &updated).await.unwrap(), 1);
+    assert_eq!(movement_repo.update(999, &updated).await.unwrap(), 0);
 
     // Verify update
     let found = movement_repo.find_by_id(id).await.unwrap().unwrap();
@@ -150,9 +197,9 @@ async fn test_update_movement() {
         Some(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())
     );
     assert_eq!(found.action_id, Some(2));
-    assert_eq!(found.quantity, Some(15.0));
-    assert_eq!(found.amount, Some(150.0));
-    assert_eq!(found.fee, Some(2.0));
+    assert_eq!(found.quantity, Some(dec!(15.0)));
+    assert_eq!(found.amount, Some(dec!(150.0)));
+    assert_eq!(found.fee, Some(dec!(2.0)));
 }
 
 #[tokio::test]
@@ -169,6 +216,13 @@ async fn test_delete_movement() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -178,9 +232,12 @@ async fn test_delete_movement() {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
         action_id: Some(1),
         investment_id: Some(inv_id),
-        quantity: Some(10.0),
-        amount: Some(100.0),
-        fee: Some(1.0),
+        quantity: Some(dec!(10.0)),
+        amount: Some(dec!(100.0)),
+        fee: Some(dec!(1.0)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     };
     let id = movement_repo.create(&movement).await.unwrap();
 
@@ -188,10 +245,11 @@ async fn test_delete_movement() {
     assert!(movement_repo.find_by_id(id).await.unwrap().is_some());
 
     // Delete it
-    movement_repo.delete(id).await.unwrap();
+    assert_eq!(movement_repo.delete(id).await.unwrap(), 1);
 
     // Verify it's gone
     assert!(movement_repo.find_by_id(id).await.unwrap().is_none());
+    assert_eq!(movement_repo.delete(id).await.unwrap(), 0);
 }
 
 #[tokio::test]
@@ -208,6 +266,13 @@ async fn test_decimal_to_real_conversion() {
             shortname: None,
             ticker_symbol: None,
             quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
         })
         .await
         .unwrap();
@@ -218,16 +283,19 @@ async fn test_decimal_to_real_conversion() {
         date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
         action_id: Some(1),
         investment_id: Some(inv_id),
-        quantity: Some(10.5),
-        amount: Some(105.75),
-        fee: Some(1.25),
+        quantity: Some(dec!(10.5)),
+        amount: Some(dec!(105.75)),
+        fee: Some(dec!(1.25)),
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     };
     let id = movement_repo.create(&movement).await.unwrap();
 
     let found = movement_repo.find_by_id(id).await.unwrap().unwrap();
-    assert_eq!(found.quantity, Some(10.5));
-    assert_eq!(found.amount, Some(105.75));
-    assert_eq!(found.fee, Some(1.25));
+    assert_eq!(found.quantity, Some(dec!(10.5)));
+    assert_eq!(found.amount, Some(dec!(105.75)));
+    assert_eq!(found.fee, Some(dec!(1.25)));
 }
 
 #[tokio::test]
@@ -243,6 +311,9 @@ async fn test_create_with_optional_fields() {
         quantity: None,
         amount: None,
         fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
     };
 
     let id = repo.create(&movement).await.unwrap();
@@ -255,3 +326,369 @@ async fn test_create_with_optional_fields() {
     assert!(found.amount.is_none());
     assert!(found.fee.is_none());
 }
+
+#[tokio::test]
+async fn test_find_page_sorts_and_paginates() {
+    let pool = setup_test_db().await;
+    let movement_repo = SqliteMovementRepository::new(pool);
+
+    for (day, amount) in [(3, dec!(30.0)), (1, dec!(10.0)), (2, dec!(20.0))] {
+        movement_repo
+            .create(&Movement {
+                id: 0,
+                date: Some(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
+                action_id: Some(1),
+                investment_id: None,
+                quantity: Some(dec!(1.0)),
+                amount: Some(amount),
+                fee: None,
+                tax: None,
+                portfolio_id: None,
+                external_id: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let (all, total) = movement_repo
+        .find_page(&MovementListOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(
+        all.iter().map(|m| m.amount).collect::<Vec<_>>(),
+        vec![Some(dec!(30.0)), Some(dec!(10.0)), Some(dec!(20.0))]
+    );
+
+    let (page, total) = movement_repo
+        .find_page(&MovementListOptions {
+            sort_by: MovementSortField::Date,
+            order: SortOrder::Desc,
+            limit: Some(2),
+            offset: 1,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(
+        page.iter().map(|m| m.amount).collect::<Vec<_>>(),
+        vec![Some(dec!(20.0)), Some(dec!(10.0))]
+    );
+}
+
+#[tokio::test]
+async fn test_find_filtered() {
+    let pool = setup_test_db().await;
+    let movement_repo = SqliteMovementRepository::new(pool.clone());
+    let investment_repo = SqliteInvestmentRepository::new(pool);
+
+    let mut investment_ids = Vec::new();
+    for name in ["First", "Second"] {
+        let id = investment_repo
+            .create(&Investment {
+                id: 0,
+                name: Some(name.to_string()),
+                isin: None,
+                shortname: None,
+                ticker_symbol: None,
+                quote_provider: None,
+                currency: None,
+                asset_class: None,
+                watchlist: false,
+                partial_exemption: None,
+                quote_fetch_enabled: true,
+                quote_fetch_interval_days: None,
+                archived: false,
+            })
+            .await
+            .unwrap();
+        investment_ids.push(id);
+    }
+    let (first, second) = (investment_ids[0], investment_ids[1]);
+
+    for (day, action_id, investment_id) in
+        [(3, 1, first), (1, 1, second), (2, 2, first), (4, 3, first)]
+    {
+        movement_repo
+            .create(&Movement {
+                id: 0,
+                date: Some(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
+                action_id: Some(action_id),
+                investment_id: Some(investment_id),
+                quantity: Some(dec!(1.0)),
+                amount: Some(dec!(10.0)),
+                fee: None,
+                tax: None,
+                portfolio_id: None,
+                external_id: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let all = movement_repo
+        .find_filtered(None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 4);
+    // Ordered by date
+    assert_eq!(
+        all[0].date,
+        Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+    );
+
+    let investment_1 = movement_repo
+        .find_filtered(Some(first), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(investment_1.len(), 3);
+
+    let buys_of_1 = movement_repo
+        .find_filtered(Some(first), Some(1), None, None)
+        .await
+        .unwrap();
+    assert_eq!(buys_of_1.len(), 1);
+
+    let in_range = movement_repo
+        .find_filtered(
+            Some(first),
+            None,
+            NaiveDate::from_ymd_opt(2024, 1, 2),
+            NaiveDate::from_ymd_opt(2024, 1, 3),
+        )
+        .await
+        .unwrap();
+    assert_eq!(in_range.len(), 2);
+
+    let (page, total) = movement_repo
+        .find_page(&MovementListOptions {
+            investment_id: Some(first),
+            limit: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(total, 3);
+}
+
+#[tokio::test]
+async fn test_create_many_is_atomic() {
+    let pool = setup_test_db().await;
+    let movement_repo = SqliteMovementRepository::new(pool);
+
+    let movement = |action_id: i64| Movement {
+        id: 0,
+        date: Some(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
+        action_id: Some(action_id),
+        investment_id: None,
+        quantity: Some(dec!(1.0)),
+        amount: Some(dec!(10.0)),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    };
+
+    let ids = movement_repo
+        .create_many(&[movement(1), movement(2)])
+        .await
+        .unwrap();
+    assert_eq!(ids.len(), 2);
+    assert!(ids[0] < ids[1]);
+    let stored = movement_repo.find_by_id(ids[1]).await.unwrap().unwrap();
+    assert_eq!(stored.action_id, Some(2));
+
+    // The unknown action type violates a foreign key, so nothing is stored
+    let result = movement_repo
+        .create_many(&[movement(1), movement(999)])
+        .await;
+    assert!(result.is_err());
+    assert_eq!(movement_repo.find_all().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_external_id_is_unique_per_portfolio() {
+    let pool = setup_test_db().await;
+    let movement_repo = SqliteMovementRepository::new(pool.clone());
+    let portfolio_id = SqlitePortfolioRepository::new(pool)
+        .create(&Portfolio {
+            id: 0,
+            name: "Broker".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+
+    let movement = |portfolio_id: Option<i64>, external_id: &str| Movement {
+        id: 0,
+        date: Some(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
+        action_id: Some(1),
+        investment_id: None,
+        quantity: Some(dec!(1.0)),
+        amount: Some(dec!(10.0)),
+        fee: None,
+        tax: None,
+        portfolio_id,
+        external_id: Some(external_id.to_string()),
+    };
+
+    let id = movement_repo
+        .create(&movement(None, "order-1"))
+        .await
+        .unwrap();
+    let other_id = movement_repo
+        .create(&movement(Some(portfolio_id), "order-1"))
+        .await
+        .unwrap();
+
+    let found = movement_repo
+        .find_by_external_id(None, "order-1")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, id);
+    assert_eq!(found.external_id.as_deref(), Some("order-1"));
+    let found = movement_repo
+        .find_by_external_id(Some(portfolio_id), "order-1")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, other_id);
+    assert!(movement_repo
+        .find_by_external_id(None, "order-2")
+        .await
+        .unwrap()
+        .is_none());
+
+    // The same reference in the same portfolio is rejected, also without portfolio
+    let err = movement_repo
+        .create(&movement(None, "order-1"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::Database(_)));
+    assert!(movement_repo
+        .create(&movement(Some(portfolio_id), "order-1"))
+        .await
+        .is_err());
+
+    // Movements without reference are not affected
+    let without = Movement {
+        external_id: None,
+        ..movement(None, "")
+    };
+    movement_repo.create(&without).await.unwrap();
+    movement_repo.create(&without).await.unwrap();
+}
+
+/// Condenses the movements before a date and checks that they give the same quantities
+async fn check_quantity_totals(repos: Repositories) {
+    let mut ids = Vec::new();
+    for name in ["Split", "Other"] {
+        ids.push(
+            repos
+                .investments
+                .create(&Investment {
+                    id: 0,
+                    name: Some(name.to_string()),
+                    isin: None,
+                    shortname: None,
+                    ticker_symbol: None,
+                    quote_provider: None,
+                    currency: None,
+                    asset_class: None,
+                    watchlist: false,
+                    partial_exemption: None,
+                    quote_fetch_enabled: true,
+                    quote_fetch_interval_days: None,
+                    archived: false,
+                })
+                .await
+                .unwrap(),
+        );
+    }
+    let (split, other) = (ids[0], ids[1]);
+    let broker = repos
+        .portfolios
+        .create(&Portfolio {
+            id: 0,
+            name: "Broker".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+    let day = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+    let movement = |investment_id, d, action_id, quantity, portfolio_id| Movement {
+        id: 0,
+        date: Some(day(d)),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(dec!(10.0)),
+        fee: None,
+        tax: None,
+        portfolio_id,
+        external_id: None,
+    };
+    repos
+        .movements
+        .create_many(&[
+            movement(split, 1, 1, dec!(10.0), None),
+            movement(split, 2, 1, dec!(5.0), None),
+            movement(split, 3, 2, dec!(3.0), None),
+            // 1:2 split, applied before the buy of the same day
+            movement(split, 4, SPLIT_ACTION_ID, dec!(2.0), None),
+            movement(split, 4, 1, dec!(1.0), None),
+            movement(split, 5, TRANSFER_OUT_ACTION_ID, dec!(-4.0), None),
+            movement(split, 5, TRANSFER_IN_ACTION_ID, dec!(4.0), Some(broker)),
+            movement(split, 8, 1, dec!(100.0), None),
+            movement(other, 2, 1, dec!(7.0), Some(broker)),
+        ])
+        .await
+        .unwrap();
+    let all = repos.movements.find_all().await.unwrap();
+
+    let totals = repos
+        .movements
+        .find_quantity_totals_before(None, None, day(6))
+        .await
+        .unwrap();
+    // Two buy totals of the split investment, its sell, split and both transfers, and
+    // the buy of the other
+    assert_eq!(totals.len(), 7);
+    assert!(totals.windows(2).all(|w| w[0].date <= w[1].date));
+    assert_eq!(
+        current_quantities(&totals),
+        HashMap::from([(split, dec!(25.0)), (other, dec!(7.0))])
+    );
+    assert_eq!(quantity_at(&all, split, day(5)), dec!(25.0));
+
+    let in_broker = repos
+        .movements
+        .find_quantity_totals_before(Some(split), Some(broker), day(6))
+        .await
+        .unwrap();
+    assert_eq!(
+        current_quantities(&in_broker),
+        HashMap::from([(split, dec!(4.0))])
+    );
+
+    let in_range = repos
+        .movements
+        .find_by_investment_and_range(Some(split), None, Some(day(4)), Some(day(5)))
+        .await
+        .unwrap();
+    assert_eq!(in_range.len(), 4);
+    assert!(in_range.iter().all(|m| m.investment_id == Some(split)));
+}
+
+#[tokio::test]
+async fn test_quantity_totals_sqlite() {
+    check_quantity_totals(Repositories::sqlite(setup_test_db().await)).await;
+}
+
+#[tokio::test]
+async fn test_quantity_totals_in_memory() {
+    check_quantity_totals(Repositories::in_memory()).await;
+}
@@ -0,0 +1,88 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{Movement, Portfolio};
+use portfoliodb_rust::repository::traits::{MovementRepository, PortfolioRepository};
+use portfoliodb_rust::repository::{SqliteMovementRepository, SqlitePortfolioRepository};
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn portfolio(name: &str) -> Portfolio {
+    Portfolio {
+        id: 0,
+        name: name.to_string(),
+        description: None,
+    }
+}
+
+fn movement(portfolio_id: Option<i64>) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
+        action_id: Some(1),
+        investment_id: None,
+        quantity: Some(dec!(10.0)),
+        amount: Some(dec!(100.0)),
+        fee: None,
+        tax: None,
+        portfolio_id,
+        external_id: None,
+    }
+}
+
+#[tokio::test]
+async fn test_create_and_find_portfolio() {
+    let pool = setup_test_db().await;
+    let repo = SqlitePortfolioRepository::new(pool);
+
+    assert!(repo.find_all().await.unwrap().is_empty());
+
+    let id = repo
+        .create(&Portfolio {
+            description: Some("Tax-advantaged".to_string()),
+            ..portfolio("Retirement")
+        })
+        .await
+        .unwrap();
+
+    let found = repo.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(found.name, "Retirement");
+    assert_eq!(found.description, Some("Tax-advantaged".to_string()));
+    assert_eq!(repo.find_all().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_update_and_delete_portfolio() {
+    let pool = setup_test_db().await;
+    let repo = SqlitePortfolioRepository::new(pool);
+
+    let id = repo.create(&portfolio("Brokerage")).await.unwrap();
+    repo.update(id, &portfolio("Main brokerage")).await.unwrap();
+    assert_eq!(
+        repo.find_by_id(id).await.unwrap().unwrap().name,
+        "Main brokerage"
+    );
+
+    repo.delete(id).await.unwrap();
+    assert!(repo.find_by_id(id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_find_movements_by_portfolio() {
+    let pool = setup_test_db().await;
+    let portfolio_repo = SqlitePortfolioRepository::new(pool.clone());
+    let movement_repo = SqliteMovementRepository::new(pool);
+
+    let first = portfolio_repo.create(&portfolio("First")).await.unwrap();
+    let second = portfolio_repo.create(&portfolio("Second")).await.unwrap();
+
+    movement_repo.create(&movement(Some(first))).await.unwrap();
+    movement_repo.create(&movement(Some(first))).await.unwrap();
+    movement_repo.create(&movement(Some(second))).await.unwrap();
+    movement_repo.create(&movement(None)).await.unwrap();
+
+    let movements = movement_repo.find_by_portfolio(first).await.unwrap();
+    assert_eq!(movements.len(), 2);
+    assert!(movements.iter().all(|m| m.portfolio_id == Some(first)));
+    assert_eq!(movement_repo.find_all().await.unwrap().len(), 4);
+}
@@ -0,0 +1,777 @@
+use chrono::NaiveDate;
+use portfoliodb_rust::db;
+use portfoliodb_rust::models::{
+    ActionType, CashMovement, Development, FxRate, ImportMode, ImportProfile, Investment,
+    InvestmentPrice, Movement, MovementListOptions, MovementSortField, Portfolio, PriceAlert,
+    QuoteFetchLog, SortOrder, Tag,
+};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::{DataTransferService, PriceAlertService};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use sqlx::types::Json;
+use std::collections::BTreeMap;
+
+/// Connect to the PostgreSQL database given in `TEST_POSTGRES_URL`
+///
+/// These tests need a running server and are ignored by default, run with:
+/// `TEST_POSTGRES_URL=postgres://... cargo test --test repository_postgres_tests -- --ignored`
+async fn setup_postgres() -> Option<Repositories> {
+    let url = std::env::var("TEST_POSTGRES_URL").ok()?;
+    Some(
+        db::connect(&url)
+            .await
+            .expect("Failed to connect to Postgres"),
+    )
+}
+
+#[test]
+fn test_backend_selection_from_url() {
+    assert!(db::is_postgres_url("postgres://user@localhost/portfolio"));
+    assert!(db::is_postgres_url("postgresql://user@localhost/portfolio"));
+    assert!(!db::is_postgres_url("sqlite:../backend/db.sqlite3"));
+    assert!(!db::is_postgres_url("sqlite:file::memory:?cache=shared"));
+}
+
+#[tokio::test]
+async fn test_sqlite_backend_connect() {
+    let repos = db::connect("sqlite::memory:").await.unwrap();
+
+    let action_types = repos.action_types.find_all().await.unwrap();
+    assert_eq!(action_types.len(), 9);
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_seeded_data() {
+    let Some(repos) = setup_postgres().await else {
+        println!("TEST_POSTGRES_URL not set, skipping");
+        return;
+    };
+
+    let action_types = repos.action_types.find_all().await.unwrap();
+    assert!(action_types
+        .iter()
+        .any(|a| a.name == "Buy" && a.effect == "increases_quantity"));
+
+    let settings = repos.settings.get().await.unwrap();
+    assert!(settings.is_some());
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_settings_benchmark_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        println!("TEST_POSTGRES_URL not set, skipping");
+        return;
+    };
+
+    let original = repos.settings.get().await.unwrap().unwrap();
+    let mut settings = original.clone();
+    settings.benchmark_investment_id = Some(42);
+    settings.webhook_urls = Json(vec!["https://ha.local/api/webhook/portfolio".to_string()]);
+    settings.webhook_secret = Some("s3cret".to_string());
+    settings.price_source_priority = Json(vec!["justetf".to_string(), "yahoo".to_string()]);
+    repos.settings.update(&settings).await.unwrap();
+
+    let stored = repos.settings.get().await.unwrap().unwrap();
+    assert_eq!(stored.benchmark_investment_id, Some(42));
+    assert_eq!(stored.benchmark_ticker, None);
+    assert_eq!(stored.webhook_urls.0, settings.webhook_urls.0);
+    assert_eq!(stored.webhook_secret.as_deref(), Some("s3cret"));
+    assert_eq!(stored.price_source_priority.0, ["justetf", "yahoo"]);
+
+    repos.settings.update(&original).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_investment_movement_and_price_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        println!("TEST_POSTGRES_URL not set, skipping");
+        return;
+    };
+
+    let inv_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Postgres Test".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: Some("EUR".to_string()),
+            asset_class: Some("etf".to_string()),
+            watchlist: true,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let investment = repos.investments.find_by_id(inv_id).await.unwrap().unwrap();
+    assert_eq!(investment.currency.as_deref(), Some("EUR"));
+    assert_eq!(investment.asset_class.as_deref(), Some("etf"));
+    assert!(investment.watchlist);
+
+    let movement_id = repos
+        .movements
+        .create(&Movement {
+            id: 0,
+            date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+            action_id: Some(1),
+            investment_id: Some(inv_id),
+            quantity: Some(dec!(10.5)),
+            amount: Some(dec!(105.75)),
+            fee: Some(dec!(1.25)),
+            tax: None,
+            portfolio_id: None,
+            external_id: None,
+        })
+        .await
+        .unwrap();
+
+    let (page, total) = repos
+        .movements
+        .find_page(&MovementListOptions {
+            sort_by: MovementSortField::Date,
+            order: SortOrder::Desc,
+            limit: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert!(total >= 1);
+
+    let filtered = repos
+        .movements
+        .find_filtered(
+            Some(inv_id),
+            Some(1),
+            NaiveDate::from_ymd_opt(2024, 1, 1),
+            NaiveDate::from_ymd_opt(2024, 1, 1),
+        )
+        .await
+        .unwrap();
+    assert_eq!(filtered.len(), 1);
+
+    let movement = repos
+        .movements
+        .find_by_id(movement_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(movement.quantity, Some(dec!(10.5)));
+    assert_eq!(movement.amount, Some(dec!(105.75)));
+
+    let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+    for price in [dec!(100.0), dec!(101.0)] {
+        repos
+            .investment_prices
+            .upsert(&InvestmentPrice {
+                date: Some(date),
+                investment_id: Some(inv_id),
+                price: Some(price),
+                source: Some("manual".to_string()),
+                currency: None,
+                original_price: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let prices = repos
+        .investment_prices
+        .find_all(Some(inv_id), None, None)
+        .await
+        .unwrap();
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices[0].price, Some(dec!(101.0)));
+
+    let bulk_prices: Vec<InvestmentPrice> =
+        [(date, dec!(102.0)), (date.succ_opt().unwrap(), dec!(103.0))]
+            .into_iter()
+            .map(|(date, price)| InvestmentPrice {
+                date: Some(date),
+                investment_id: Some(inv_id),
+                price: Some(price),
+                source: Some("manual".to_string()),
+                currency: None,
+                original_price: None,
+            })
+            .collect();
+    repos
+        .investment_prices
+        .upsert_many(&bulk_prices)
+        .await
+        .unwrap();
+    let prices = repos
+        .investment_prices
+        .find_all(Some(inv_id), None, None)
+        .await
+        .unwrap();
+    let stored: Vec<Option<Decimal>> = prices.iter().map(|p| p.price).collect();
+    assert_eq!(stored, [Some(dec!(103.0)), Some(dec!(102.0))]);
+
+    let bulk_ids = repos
+        .movements
+        .create_many(&[movement.clone(), movement.clone()])
+        .await
+        .unwrap();
+    assert_eq!(bulk_ids.len(), 2);
+    for id in bulk_ids {
+        repos.movements.delete(id).await.unwrap();
+    }
+
+    // Movement and price still reference the investment
+    assert!(repos
+        .investments
+        .delete_with_dependents(inv_id, false)
+        .await
+        .is_err());
+    let deleted = repos
+        .investments
+        .delete_with_dependents(inv_id, true)
+        .await
+        .unwrap();
+    assert_eq!(deleted.movements, 1);
+    assert_eq!(deleted.prices, 2);
+    assert!(repos
+        .movements
+        .find_by_id(movement_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_portfolio_and_cash_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        return;
+    };
+
+    let portfolio_id = repos
+        .portfolios
+        .create(&Portfolio {
+            id: 0,
+            name: "Postgres Portfolio".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+
+    let cash_id = repos
+        .cash_movements
+        .create(&CashMovement {
+            id: 0,
+            date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            action_id: 4,
+            amount: dec!(250.5),
+            portfolio_id: Some(portfolio_id),
+            description: Some("Initial deposit".to_string()),
+        })
+        .await
+        .unwrap();
+
+    let cash = repos
+        .cash_movements
+        .find_all(Some(portfolio_id))
+        .await
+        .unwrap();
+    assert_eq!(cash.len(), 1);
+    assert_eq!(cash[0].amount, dec!(250.5));
+    assert!(!repos
+        .cash_movements
+        .find_all(None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    repos.cash_movements.delete(cash_id).await.unwrap();
+    repos.portfolios.delete(portfolio_id).await.unwrap();
+    assert!(repos
+        .portfolios
+        .find_by_id(portfolio_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_quote_fetch_log_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        return;
+    };
+
+    let entry = QuoteFetchLog {
+        id: 0,
+        fetched_at: chrono::Utc::now(),
+        investment_id: -1,
+        provider: Some("yahoo".to_string()),
+        success: true,
+        error: None,
+        quotes_stored: 3,
+    };
+    repos.quote_fetch_log.create(&entry).await.unwrap();
+
+    let log = repos
+        .quote_fetch_log
+        .find_recent(Some(-1), 1)
+        .await
+        .unwrap();
+    assert_eq!(log.len(), 1);
+    assert!(log[0].success);
+    assert_eq!(log[0].quotes_stored, 3);
+    assert!(repos
+        .quote_fetch_log
+        .last_success()
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_price_alert_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        return;
+    };
+
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Postgres Alert Test".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let alert_id = repos
+        .price_alerts
+        .create(&PriceAlert {
+            id: 0,
+            investment_id,
+            threshold: 50.0,
+            direction: "below".to_string(),
+            active: true,
+        })
+        .await
+        .unwrap();
+
+    let triggered = PriceAlertService::new(repos.price_alerts.clone())
+        .evaluate(
+            investment_id,
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            49.5,
+        )
+        .await
+        .unwrap();
+    assert_eq!(triggered.len(), 1);
+    assert!(
+        !repos
+            .price_alerts
+            .find_by_id(alert_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .active
+    );
+    let history = repos
+        .price_alerts
+        .find_triggered(Some(investment_id), 5)
+        .await
+        .unwrap();
+    assert_eq!(history[0].price, 49.5);
+
+    repos
+        .investments
+        .delete_with_dependents(investment_id, true)
+        .await
+        .unwrap();
+    assert!(repos
+        .price_alerts
+        .find_by_id(alert_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_movement_external_id() {
+    let Some(repos) = setup_postgres().await else {
+        return;
+    };
+
+    // External IDs are unique, so the test uses a new one on every run
+    let external_id = format!("pg-{}", chrono::Utc::now().timestamp_nanos_opt().unwrap());
+    let movement = Movement {
+        id: 0,
+        date: NaiveDate::from_ymd_opt(2024, 1, 15),
+        action_id: Some(1),
+        investment_id: None,
+        quantity: Some(dec!(1.0)),
+        amount: Some(dec!(10.0)),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: Some(external_id.clone()),
+    };
+    let id = repos.movements.create(&movement).await.unwrap();
+
+    let found = repos
+        .movements
+        .find_by_external_id(None, &external_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, id);
+    assert_eq!(found.external_id, Some(external_id));
+    assert!(repos.movements.create(&movement).await.is_err());
+
+    repos.movements.delete(id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_tag_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        return;
+    };
+
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Postgres Tag Test".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    // Tag names are unique, so the name includes the new investment ID
+    let name = format!("Retirement {}", investment_id);
+    let tag_id = repos
+        .tags
+        .create(&Tag {
+            id: 0,
+            name: name.clone(),
+        })
+        .await
+        .unwrap();
+
+    let found = repos
+        .tags
+        .find_by_name(&name.to_uppercase())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, tag_id);
+
+    repos
+        .tags
+        .set_investment_tags(investment_id, &[tag_id, tag_id])
+        .await
+        .unwrap();
+    assert_eq!(
+        repos.tags.find_by_investment(investment_id).await.unwrap(),
+        vec![found]
+    );
+    assert_eq!(
+        repos.tags.find_investment_ids(tag_id).await.unwrap(),
+        vec![investment_id]
+    );
+
+    repos.investments.delete(investment_id).await.unwrap();
+    assert!(repos
+        .tags
+        .find_investment_ids(tag_id)
+        .await
+        .unwrap()
+        .is_empty());
+    repos.tags.delete(tag_id).await.unwrap();
+    assert!(repos.tags.find_by_id(tag_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_health_check() {
+    let Some(repos) = setup_postgres().await else {
+        return;
+    };
+
+    let migrations = repos.health.check().await.unwrap();
+    assert!(migrations.is_current());
+    assert_eq!(migrations.latest_version, db::postgres::latest_version());
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_development_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        return;
+    };
+
+    let development = |d: u32, price: Decimal| Development {
+        investment: -1,
+        date: NaiveDate::from_ymd_opt(1990, 1, d).unwrap(),
+        price,
+        quantity: dec!(2.0),
+        value: dec!(2.0) * price,
+        source: Some("yahoo".to_string()),
+    };
+    let stored = |developments: Vec<Development>| -> Vec<Development> {
+        developments
+            .into_iter()
+            .filter(|d| d.investment == -1)
+            .collect()
+    };
+
+    repos
+        .developments
+        .replace(
+            Some(-1),
+            None,
+            &[development(1, dec!(10.0)), development(2, dec!(11.0))],
+        )
+        .await
+        .unwrap();
+    repos
+        .developments
+        .replace(
+            Some(-1),
+            NaiveDate::from_ymd_opt(1990, 1, 2),
+            &[development(2, dec!(12.0)), development(3, dec!(13.0))],
+        )
+        .await
+        .unwrap();
+
+    let developments = stored(repos.developments.find_all(None, None).await.unwrap());
+    assert_eq!(
+        developments,
+        vec![
+            development(1, dec!(10.0)),
+            development(2, dec!(12.0)),
+            development(3, dec!(13.0))
+        ]
+    );
+
+    let range = repos
+        .developments
+        .find_all(
+            NaiveDate::from_ymd_opt(1990, 1, 2),
+            NaiveDate::from_ymd_opt(1990, 1, 2),
+        )
+        .await
+        .unwrap();
+    assert_eq!(stored(range), vec![development(2, dec!(12.0))]);
+
+    repos
+        .developments
+        .replace(Some(-1), None, &[])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_fx_rate_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        return;
+    };
+
+    let date = NaiveDate::from_ymd_opt(1999, 1, 4).unwrap();
+    for rate in [1.17, 1.18] {
+        repos
+            .fx_rates
+            .upsert(&FxRate {
+                date,
+                from_currency: "EUR".to_string(),
+                to_currency: "USD".to_string(),
+                rate,
+            })
+            .await
+            .unwrap();
+    }
+
+    let cached = repos
+        .fx_rates
+        .find(date, "EUR", "USD")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(cached.rate, 1.18);
+
+    let rates = repos
+        .fx_rates
+        .find_all(Some("EUR"), Some("USD"), Some(date), Some(date))
+        .await
+        .unwrap();
+    assert_eq!(rates.len(), 1);
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_data_merge_import() {
+    let Some(repos) = setup_postgres().await else {
+        println!("TEST_POSTGRES_URL not set, skipping");
+        return;
+    };
+    let service = DataTransferService::new(repos.clone());
+
+    let isin = format!(
+        "XX{:010}",
+        chrono::Utc::now().timestamp_micros() % 10_000_000_000
+    );
+    let mut export = service.export().await.unwrap();
+    export.portfolios.clear();
+    export.movements.clear();
+    export.cash_movements.clear();
+    export.investments = vec![Investment {
+        id: -1,
+        name: Some("Imported".to_string()),
+        isin: Some(isin.clone()),
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    }];
+    export.prices = vec![InvestmentPrice {
+        date: NaiveDate::from_ymd_opt(2024, 1, 15),
+        investment_id: Some(-1),
+        price: Some(dec!(12.5)),
+        source: Some("manual".to_string()),
+        currency: None,
+        original_price: None,
+    }];
+
+    let summary = service.import(&export, ImportMode::Merge).await.unwrap();
+    assert_eq!(summary.investments, 1);
+    assert_eq!(summary.prices, 1);
+
+    // A second merge matches the investment by ISIN
+    let summary = service.import(&export, ImportMode::Merge).await.unwrap();
+    assert_eq!(summary.investments, 0);
+
+    let investment = repos
+        .investments
+        .find_all()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|i| i.isin.as_deref() == Some(isin.as_str()))
+        .unwrap();
+    let prices = repos
+        .investment_prices
+        .find_all(Some(investment.id), None, None)
+        .await
+        .unwrap();
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices[0].price, Some(dec!(12.5)));
+
+    repos
+        .investments
+        .delete_with_dependents(investment.id, true)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_import_profile_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        println!("TEST_POSTGRES_URL not set, skipping");
+        return;
+    };
+
+    let mut profile = ImportProfile {
+        id: 0,
+        name: "Roundtrip".to_string(),
+        delimiter: ";".to_string(),
+        date_format: "%d.%m.%Y".to_string(),
+        decimal_separator: ",".to_string(),
+        currency: "EUR".to_string(),
+        date_column: "Datum".to_string(),
+        action_column: "Typ".to_string(),
+        isin_column: "ISIN".to_string(),
+        product_column: None,
+        quantity_column: Some("Stück".to_string()),
+        amount_column: "Betrag".to_string(),
+        fee_column: None,
+        action_keywords: Json(BTreeMap::from([("kauf".to_string(), 1)])),
+    };
+    let id = repos.import_profiles.create(&profile).await.unwrap();
+
+    let found = repos.import_profiles.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(found.delimiter, ";");
+    assert_eq!(found.action_keywords.get("kauf"), Some(&1));
+
+    profile.action_keywords.0.insert("verkauf".to_string(), 2);
+    repos.import_profiles.update(id, &profile).await.unwrap();
+    let found = repos.import_profiles.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(found.action_keywords.len(), 2);
+
+    repos.import_profiles.delete(id).await.unwrap();
+    assert!(repos
+        .import_profiles
+        .find_by_id(id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+#[ignore] // Requires TEST_POSTGRES_URL
+async fn test_postgres_custom_action_type_roundtrip() {
+    let Some(repos) = setup_postgres().await else {
+        println!("TEST_POSTGRES_URL not set, skipping");
+        return;
+    };
+
+    let mut action_type = ActionType {
+        id: 0,
+        name: "Tax refund".to_string(),
+        effect: "cash_only".to_string(),
+    };
+    let id = repos.action_types.create(&action_type).await.unwrap();
+    assert!(id > 8);
+
+    action_type.name = "Withholding tax refund".to_string();
+    repos.action_types.update(id, &action_type).await.unwrap();
+    let found = repos.action_types.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(found.name, "Withholding tax refund");
+    assert_eq!(found.effect, "cash_only");
+
+    repos.action_types.delete(id).await.unwrap();
+    assert!(repos.action_types.find_by_id(id).await.unwrap().is_none());
+}
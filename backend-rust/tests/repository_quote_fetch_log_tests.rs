@@ -0,0 +1,93 @@
+mod test_helpers;
+
+use chrono::{Duration, Utc};
+use portfoliodb_rust::models::{Investment, QuoteFetchLog};
+use portfoliodb_rust::repository::traits::{
+    InvestmentPriceRepository, InvestmentRepository, QuoteFetchLogRepository,
+};
+use portfoliodb_rust::repository::{
+    SqliteInvestmentPriceRepository, SqliteInvestmentRepository, SqliteQuoteFetchLogRepository,
+};
+use portfoliodb_rust::services::QuoteFetcherService;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn entry(investment_id: i64, minutes_ago: i64, success: bool) -> QuoteFetchLog {
+    QuoteFetchLog {
+        id: 0,
+        fetched_at: Utc::now() - Duration::minutes(minutes_ago),
+        investment_id,
+        provider: success.then(|| "yahoo".to_string()),
+        success,
+        error: (!success).then(|| "Provider error".to_string()),
+        quotes_stored: if success { 5 } else { 0 },
+    }
+}
+
+#[tokio::test]
+async fn test_find_recent_orders_and_filters() {
+    let pool = setup_test_db().await;
+    let repo = SqliteQuoteFetchLogRepository::new(pool);
+
+    repo.create(&entry(1, 30, true)).await.unwrap();
+    repo.create(&entry(2, 20, false)).await.unwrap();
+    repo.create(&entry(1, 10, false)).await.unwrap();
+
+    let all = repo.find_recent(None, 10).await.unwrap();
+    assert_eq!(all.len(), 3);
+    assert!(all.windows(2).all(|w| w[0].fetched_at >= w[1].fetched_at));
+
+    let first = repo.find_recent(Some(1), 10).await.unwrap();
+    assert_eq!(first.len(), 2);
+    assert!(!first[0].success);
+    assert_eq!(first[1].provider, Some("yahoo".to_string()));
+    assert_eq!(first[1].quotes_stored, 5);
+
+    assert_eq!(repo.find_recent(None, 1).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_fetch_results_are_logged() {
+    let pool = setup_test_db().await;
+
+    let investment_repo: Arc<dyn InvestmentRepository> =
+        Arc::new(SqliteInvestmentRepository::new(pool.clone()));
+    let price_repo: Arc<dyn InvestmentPriceRepository> =
+        Arc::new(SqliteInvestmentPriceRepository::new(pool.clone()));
+    let log_repo: Arc<dyn QuoteFetchLogRepository> =
+        Arc::new(SqliteQuoteFetchLogRepository::new(pool));
+
+    let investment_id = investment_repo
+        .create(&Investment {
+            id: 0,
+            name: Some("Test Investment".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: Some("TEST".to_string()),
+            quote_provider: Some("unknown_provider".to_string()),
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+
+    let service = QuoteFetcherService::new(investment_repo, price_repo, "EUR".to_string())
+        .with_fetch_log(log_repo.clone());
+
+    let results = service.fetch_quotes(None).await.unwrap();
+    assert_eq!(results.len(), 1);
+
+    let log = log_repo.find_recent(Some(investment_id), 10).await.unwrap();
+    assert_eq!(log.len(), 1);
+    assert!(!log[0].success);
+    assert!(log[0]
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("Unknown provider"));
+}
@@ -1,8 +1,10 @@
 mod test_helpers;
 
-use portfoliodb_rust::models::Settings;
-use portfoliodb_rust::repository::traits::SettingsRepository;
-use portfoliodb_rust::repository::SqliteSettingsRepository;
+use chrono::Utc;
+use portfoliodb_rust::models::{ProviderConfig, Settings};
+use portfoliodb_rust::repository::traits::{ProviderConfigRepository, SettingsRepository};
+use portfoliodb_rust::repository::{SqliteProviderConfigRepository, SqliteSettingsRepository};
+use portfoliodb_rust::services::quotes::ProviderApiKeys;
 use test_helpers::setup_test_db;
 
 #[tokio::test]
@@ -27,6 +29,17 @@ async fn test_update_settings() {
     let updated_settings = Settings {
         id: 1,
         base_currency: "USD".to_string(),
+        cost_basis_method: "fifo".to_string(),
+        benchmark_investment_id: None,
+        benchmark_ticker: None,
+        webhook_urls: Default::default(),
+        webhook_secret: None,
+        price_source_priority: Default::default(),
+        value_decimals: None,
+        price_decimals: None,
+        quantity_decimals: None,
+        rounding_mode: "half_even".to_string(),
+        reject_negative_holdings: false,
     };
     repo.update(&updated_settings).await.unwrap();
 
@@ -44,6 +57,17 @@ async fn test_update_settings_multiple_times() {
     repo.update(&Settings {
         id: 1,
         base_currency: "USD".to_string(),
+        cost_basis_method: "fifo".to_string(),
+        benchmark_investment_id: None,
+        benchmark_ticker: None,
+        webhook_urls: Default::default(),
+        webhook_secret: None,
+        price_source_priority: Default::default(),
+        value_decimals: None,
+        price_decimals: None,
+        quantity_decimals: None,
+        rounding_mode: "half_even".to_string(),
+        reject_negative_holdings: false,
     })
     .await
     .unwrap();
@@ -52,6 +76,17 @@ async fn test_update_settings_multiple_times() {
     repo.update(&Settings {
         id: 1,
         base_currency: "GBP".to_string(),
+        cost_basis_method: "fifo".to_string(),
+        benchmark_investment_id: None,
+        benchmark_ticker: None,
+        webhook_urls: Default::default(),
+        webhook_secret: None,
+        price_source_priority: Default::default(),
+        value_decimals: None,
+        price_decimals: None,
+        quantity_decimals: None,
+        rounding_mode: "half_even".to_string(),
+        reject_negative_holdings: false,
     })
     .await
     .unwrap();
@@ -60,6 +95,17 @@ async fn test_update_settings_multiple_times() {
     repo.update(&Settings {
         id: 1,
         base_currency: "JPY".to_string(),
+        cost_basis_method: "fifo".to_string(),
+        benchmark_investment_id: None,
+        benchmark_ticker: None,
+        webhook_urls: Default::default(),
+        webhook_secret: None,
+        price_source_priority: Default::default(),
+        value_decimals: None,
+        price_decimals: None,
+        quantity_decimals: None,
+        rounding_mode: "half_even".to_string(),
+        reject_negative_holdings: false,
     })
     .await
     .unwrap();
@@ -68,3 +114,81 @@ async fn test_update_settings_multiple_times() {
     let settings = repo.get().await.unwrap().unwrap();
     assert_eq!(settings.base_currency, "JPY");
 }
+
+#[tokio::test]
+async fn test_update_cost_basis_method() {
+    let pool = setup_test_db().await;
+    let repo = SqliteSettingsRepository::new(pool);
+
+    // Seeded default
+    let settings = repo.get().await.unwrap().unwrap();
+    assert_eq!(settings.cost_basis_method, "fifo");
+
+    repo.update(&Settings {
+        cost_basis_method: "lifo".to_string(),
+        ..settings
+    })
+    .await
+    .unwrap();
+
+    let settings = repo.get().await.unwrap().unwrap();
+    assert_eq!(settings.cost_basis_method, "lifo");
+    assert_eq!(settings.base_currency, "EUR");
+}
+
+#[tokio::test]
+async fn test_update_benchmark() {
+    let pool = setup_test_db().await;
+    let repo = SqliteSettingsRepository::new(pool);
+
+    let settings = repo.get().await.unwrap().unwrap();
+    assert_eq!(settings.benchmark_investment_id, None);
+    assert_eq!(settings.benchmark_ticker, None);
+
+    repo.update(&Settings {
+        benchmark_ticker: Some("VWCE.DE".to_string()),
+        webhook_urls: Default::default(),
+        webhook_secret: None,
+        ..settings
+    })
+    .await
+    .unwrap();
+
+    let settings = repo.get().await.unwrap().unwrap();
+    assert_eq!(settings.benchmark_ticker.as_deref(), Some("VWCE.DE"));
+    assert_eq!(settings.benchmark_investment_id, None);
+}
+
+#[tokio::test]
+async fn test_provider_configs() {
+    let pool = setup_test_db().await;
+    let repo = SqliteProviderConfigRepository::new(pool);
+    assert!(repo.find_all().await.unwrap().is_empty());
+
+    for api_key in ["CG-first", "CG-second"] {
+        repo.upsert(&ProviderConfig {
+            provider: "coingecko".to_string(),
+            api_key: api_key.to_string(),
+            updated_at: Utc::now(),
+        })
+        .await
+        .unwrap();
+    }
+    let stored = repo.find_all().await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].api_key, "CG-second");
+    assert!(!format!("{:?}", stored[0]).contains("CG-second"));
+
+    // Stored keys take precedence over the configured ones
+    let configured = ProviderApiKeys {
+        coingecko_api_key: Some("CG-config".to_string()),
+    };
+    let keys = configured.clone().with_stored(&stored);
+    assert_eq!(keys.get("coingecko"), Some("CG-second"));
+
+    repo.delete("coingecko").await.unwrap();
+    let stored = repo.find_all().await.unwrap();
+    assert!(stored.is_empty());
+    let keys = configured.with_stored(&stored);
+    assert_eq!(keys.get("coingecko"), Some("CG-config"));
+}
@@ -0,0 +1,78 @@
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::routing::{get, post};
+use axum::{middleware, Router};
+use portfoliodb_rust::handlers::limits::{body_limit, request_timeout};
+use std::time::Duration;
+use tower::ServiceExt;
+
+fn router() -> Router {
+    Router::new()
+        .route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                "done"
+            }),
+        )
+        .route("/fast", get(|| async { "done" }))
+        .route(
+            "/import",
+            post(|body: String| async move { body.len().to_string() })
+                .layer(middleware::from_fn_with_state(10, body_limit)),
+        )
+        .layer(middleware::from_fn_with_state(
+            Duration::from_millis(50),
+            request_timeout,
+        ))
+}
+
+async fn send(request: Request<Body>) -> (StatusCode, String) {
+    let response = router().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn get_request(uri: &str) -> Request<Body> {
+    Request::builder().uri(uri).body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn test_request_timeout() {
+    let (status, body) = send(get_request("/slow")).await;
+    assert_eq!(status, StatusCode::REQUEST_TIMEOUT);
+    assert!(body.contains("Request timed out"));
+
+    let (status, body) = send(get_request("/fast")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "done");
+}
+
+#[tokio::test]
+async fn test_body_limit() {
+    let import = |body: Body| {
+        Request::builder()
+            .method("POST")
+            .uri("/import")
+            .body(body)
+            .unwrap()
+    };
+
+    let (status, body) = send(import(Body::from("0123456789"))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "10");
+
+    let (status, body) = send(import(Body::from("0123456789a"))).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(body.contains("limit of 10 bytes"));
+
+    // Streamed bodies without Content-Length are limited as well
+    let chunks: Vec<Result<&'static str, std::io::Error>> = vec![Ok("012345"), Ok("6789ab")];
+    let streamed = import(Body::from_stream(futures::stream::iter(chunks)));
+    assert!(streamed.headers().get(header::CONTENT_LENGTH).is_none());
+    let (status, _) = send(streamed).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
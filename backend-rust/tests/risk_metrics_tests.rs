@@ -0,0 +1,84 @@
+use chrono::NaiveDate;
+use portfoliodb_rust::services::portfolio_calculator::GrowthPoint;
+use portfoliodb_rust::services::risk_metrics::calculate_risk_metrics;
+
+fn point(day: u32, value: f64, growth: f64) -> GrowthPoint {
+    GrowthPoint {
+        date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+        value,
+        growth,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn test_drawdowns() {
+    let points = vec![
+        point(1, 100.0, 1.0),
+        point(2, 120.0, 1.2),
+        point(3, 90.0, 0.9),
+        point(4, 100.0, 1.0),
+    ];
+
+    let metrics = calculate_risk_metrics(&points);
+
+    assert_close(metrics.max_drawdown, 0.25);
+    assert_close(metrics.current_drawdown, 1.0 - 1.0 / 1.2);
+}
+
+#[test]
+fn test_volatility_is_annualized_by_valuation_frequency() {
+    // Daily returns of +20%, -25% and +11.1%
+    let daily = vec![
+        point(1, 100.0, 1.0),
+        point(2, 120.0, 1.2),
+        point(3, 90.0, 0.9),
+        point(4, 100.0, 1.0),
+    ];
+    assert_close(calculate_risk_metrics(&daily).volatility, 4.554816166283989);
+
+    // Weekly returns of +10% and -10%
+    let weekly = vec![
+        point(1, 100.0, 1.0),
+        point(8, 110.0, 1.1),
+        point(15, 99.0, 0.99),
+    ];
+    assert_close(
+        calculate_risk_metrics(&weekly).volatility,
+        1.0215534389210525,
+    );
+}
+
+#[test]
+fn test_periods_without_position_are_skipped() {
+    // Sold on day 2, bought again on day 4 with unchanged growth
+    let points = vec![
+        point(1, 100.0, 1.0),
+        point(2, 0.0, 1.1),
+        point(3, 0.0, 1.1),
+        point(4, 50.0, 1.1),
+        point(5, 55.0, 1.21),
+    ];
+
+    let metrics = calculate_risk_metrics(&points);
+
+    assert_close(metrics.max_drawdown, 0.0);
+    // Two returns of +10% on separate days have no spread
+    assert_close(metrics.volatility, 0.0);
+}
+
+#[test]
+fn test_empty_series() {
+    let metrics = calculate_risk_metrics(&[]);
+    assert_eq!(metrics.max_drawdown, 0.0);
+    assert_eq!(metrics.current_drawdown, 0.0);
+    assert_eq!(metrics.volatility, 0.0);
+}
@@ -0,0 +1,281 @@
+mod test_helpers;
+
+use axum::extract::State;
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::savings_plans::{create_savings_plan, SavingsPlanRequest};
+use portfoliodb_rust::models::{Investment, Movement, SavingsInterval, SavingsPlan};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::SavingsPlanState;
+use portfoliodb_rust::services::savings_plans::project_savings_plans;
+use portfoliodb_rust::services::{PortfolioCalculator, SavingsPlanService};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn plan(investment_id: i64, amount: Decimal, interval: &str, start: NaiveDate) -> SavingsPlan {
+    SavingsPlan {
+        id: 0,
+        investment_id,
+        amount,
+        interval: interval.to_string(),
+        start_date: start,
+        end_date: None,
+    }
+}
+
+fn buy(date: NaiveDate, investment_id: i64, amount: Decimal) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(1),
+        investment_id: Some(investment_id),
+        quantity: Some(dec!(1)),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+async fn setup() -> (Repositories, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("World ETF".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: Some("etf".to_string()),
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    (repos, investment_id)
+}
+
+fn state(repos: &Repositories) -> SavingsPlanState {
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    SavingsPlanState {
+        plan_repo: repos.savings_plans.clone(),
+        investment_repo: repos.investments.clone(),
+        service: Arc::new(
+            SavingsPlanService::new(
+                repos.savings_plans.clone(),
+                repos.movements.clone(),
+                calculator,
+            )
+            .with_action_types(repos.action_types.clone()),
+        ),
+    }
+}
+
+#[test]
+fn test_savings_plan_executions() {
+    let monthly = SavingsPlan {
+        end_date: Some(date(2024, 4, 30)),
+        ..plan(1, dec!(100), "monthly", date(2024, 1, 31))
+    };
+    // Executions on the 31st fall on the last day of shorter months
+    assert_eq!(
+        monthly.executions(date(2030, 1, 1)),
+        vec![
+            date(2024, 1, 31),
+            date(2024, 2, 29),
+            date(2024, 3, 31),
+            date(2024, 4, 30)
+        ]
+    );
+
+    let quarterly = plan(1, dec!(100), "quarterly", date(2024, 1, 15));
+    assert_eq!(
+        quarterly.executions(date(2024, 7, 14)),
+        vec![date(2024, 1, 15), date(2024, 4, 15)]
+    );
+    assert!(quarterly.executions(date(2023, 12, 31)).is_empty());
+
+    assert_eq!(
+        "Weekly".parse::<SavingsInterval>().unwrap(),
+        SavingsInterval::Weekly
+    );
+    assert!("daily".parse::<SavingsInterval>().is_err());
+}
+
+#[tokio::test]
+async fn test_savings_plan_repository_crud() {
+    let (repos, investment_id) = setup().await;
+
+    let id = repos
+        .savings_plans
+        .create(&plan(
+            investment_id,
+            dec!(150.50),
+            "monthly",
+            date(2024, 1, 1),
+        ))
+        .await
+        .unwrap();
+    let mut stored = repos.savings_plans.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(stored.amount, dec!(150.50));
+    assert_eq!(stored.end_date, None);
+
+    stored.interval = "yearly".to_string();
+    stored.end_date = Some(date(2030, 12, 31));
+    repos.savings_plans.update(id, &stored).await.unwrap();
+    let plans = repos
+        .savings_plans
+        .find_all(Some(investment_id))
+        .await
+        .unwrap();
+    assert_eq!(plans.len(), 1);
+    assert_eq!(plans[0].interval, "yearly");
+    assert_eq!(plans[0].end_date, Some(date(2030, 12, 31)));
+    assert!(repos
+        .savings_plans
+        .find_all(Some(999))
+        .await
+        .unwrap()
+        .is_empty());
+
+    repos.savings_plans.delete(id).await.unwrap();
+    assert!(repos.savings_plans.find_by_id(id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_create_savings_plan_is_validated() {
+    let (repos, investment_id) = setup().await;
+
+    let err = create_savings_plan(
+        State(state(&repos)),
+        Json(SavingsPlanRequest {
+            investment_id,
+            amount: dec!(0),
+            interval: "daily".to_string(),
+            start_date: date(2024, 2, 1),
+            end_date: Some(date(2024, 1, 1)),
+        }),
+    )
+    .await
+    .unwrap_err();
+    match err {
+        AppError::Validation(errors) => {
+            let fields: Vec<&str> = errors.errors.iter().map(|e| e.field.as_str()).collect();
+            assert_eq!(fields, vec!["amount", "interval", "end_date"]);
+        }
+        other => panic!("expected validation error, got {:?}", other),
+    }
+
+    let err = create_savings_plan(
+        State(state(&repos)),
+        Json(SavingsPlanRequest {
+            investment_id: 999,
+            amount: dec!(100),
+            interval: "monthly".to_string(),
+            start_date: date(2024, 1, 1),
+            end_date: None,
+        }),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+
+    let Json(created) = create_savings_plan(
+        State(state(&repos)),
+        Json(SavingsPlanRequest {
+            investment_id,
+            amount: dec!(100),
+            interval: "Monthly".to_string(),
+            start_date: date(2024, 1, 1),
+            end_date: None,
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(created.interval, "monthly");
+}
+
+#[test]
+fn test_projection_without_return() {
+    let plans = [
+        plan(1, dec!(100), "monthly", date(2024, 1, 15)),
+        plan(2, dec!(600), "yearly", date(2024, 6, 1)),
+    ];
+
+    let projection = project_savings_plans(&plans, dec!(1000), date(2024, 1, 1), 12, 0.0);
+
+    assert_eq!(projection.points.len(), 12);
+    assert_eq!(projection.points[0].date, date(2024, 2, 1));
+    assert_eq!(projection.points[0].contributions, dec!(100));
+    // The yearly execution on June 1st falls into the month ending on that day
+    assert_eq!(projection.points[3].contributions, dec!(400));
+    assert_eq!(projection.points[4].contributions, dec!(1100));
+    let last = projection.points.last().unwrap();
+    assert_eq!(last.contributions, dec!(1800));
+    assert_eq!(last.value, dec!(2800));
+}
+
+#[test]
+fn test_projection_with_return() {
+    let projection = project_savings_plans(&[], dec!(1000), date(2024, 1, 1), 24, 0.1);
+
+    let last = projection.points.last().unwrap();
+    assert_eq!(last.date, date(2026, 1, 1));
+    assert_eq!(last.contributions, dec!(0));
+    assert!((last.value - dec!(1210)).abs() < dec!(0.001));
+}
+
+#[tokio::test]
+async fn test_plan_vs_actual() {
+    let (repos, investment_id) = setup().await;
+    let id = repos
+        .savings_plans
+        .create(&SavingsPlan {
+            end_date: Some(date(2024, 3, 31)),
+            ..plan(investment_id, dec!(100), "monthly", date(2024, 1, 15))
+        })
+        .await
+        .unwrap();
+    for m in [
+        // Before the plan started
+        buy(date(2024, 1, 2), investment_id, dec!(500)),
+        buy(date(2024, 1, 16), investment_id, dec!(100)),
+        // February was skipped, March was bought in two parts
+        buy(date(2024, 3, 15), investment_id, dec!(60)),
+        buy(date(2024, 3, 20), investment_id, dec!(50)),
+    ] {
+        repos.movements.create(&m).await.unwrap();
+    }
+
+    let comparison = state(&repos).service.comparison(id).await.unwrap();
+
+    assert_eq!(comparison.investment_id, investment_id);
+    let actual: Vec<Decimal> = comparison
+        .executions
+        .iter()
+        .map(|e| e.actual_amount)
+        .collect();
+    assert_eq!(actual, vec![dec!(100), dec!(0), dec!(110)]);
+    assert_eq!(comparison.expected_total, dec!(300));
+    assert_eq!(comparison.actual_total, dec!(210));
+
+    let err = state(&repos).service.comparison(999).await.unwrap_err();
+    assert!(matches!(err, AppError::NotFound { .. }));
+}
@@ -0,0 +1,169 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::{PortfolioCalculator, SnapshotService};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+fn date(m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, m, d).unwrap()
+}
+
+fn movement(
+    date: NaiveDate,
+    action_id: i64,
+    investment_id: i64,
+    quantity: Decimal,
+    amount: Decimal,
+) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(action_id),
+        investment_id: Some(investment_id),
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn price(date: NaiveDate, investment_id: i64, price: Decimal) -> InvestmentPrice {
+    InvestmentPrice {
+        date: Some(date),
+        investment_id: Some(investment_id),
+        price: Some(price),
+        source: Some("test".to_string()),
+        currency: None,
+        original_price: None,
+    }
+}
+
+fn service(repos: &Repositories) -> SnapshotService {
+    let calculator = Arc::new(PortfolioCalculator::new(
+        repos.movements.clone(),
+        repos.investment_prices.clone(),
+    ));
+    SnapshotService::new(repos.snapshots.clone(), calculator)
+}
+
+/// A fund bought in January and half sold in February, and a stock bought in February
+async fn setup() -> (Repositories, i64, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let mut ids = Vec::new();
+    for name in ["Fund", "Stock"] {
+        let investment = Investment {
+            id: 0,
+            name: Some(name.to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        };
+        ids.push(repos.investments.create(&investment).await.unwrap());
+    }
+    let (fund, stock) = (ids[0], ids[1]);
+
+    for m in [
+        movement(date(1, 10), 1, fund, dec!(10.0), dec!(1000.0)),
+        movement(date(2, 20), 2, fund, dec!(5.0), dec!(600.0)),
+        movement(date(2, 21), 1, stock, dec!(2.0), dec!(100.0)),
+    ] {
+        repos.movements.create(&m).await.unwrap();
+    }
+    repos
+        .investment_prices
+        .create(&price(date(1, 31), fund, dec!(110.0)))
+        .await
+        .unwrap();
+    (repos, fund, stock)
+}
+
+#[tokio::test]
+async fn test_snapshot_is_not_affected_by_price_corrections() {
+    let (repos, fund, _) = setup().await;
+    let service = service(&repos);
+
+    let snapshot = service.take(date(1, 31), false).await.unwrap();
+    assert_eq!(snapshot.total_value, dec!(1100.0));
+    assert_eq!(snapshot.holdings.len(), 1);
+    assert_eq!(snapshot.holdings[0].investment, fund);
+    assert_eq!(snapshot.holdings[0].quantity, dec!(10.0));
+    assert_eq!(snapshot.holdings[0].price, dec!(110.0));
+
+    repos
+        .investment_prices
+        .upsert(&price(date(1, 31), fund, dec!(105.0)))
+        .await
+        .unwrap();
+
+    let stored = service.get(date(1, 31)).await.unwrap();
+    assert_eq!(stored.total_value, dec!(1100.0));
+
+    // Taking it again needs to be confirmed
+    let err = service.take(date(1, 31), false).await.unwrap_err();
+    assert!(matches!(err, AppError::Conflict(_)));
+    let retaken = service.take(date(1, 31), true).await.unwrap();
+    assert_eq!(retaken.total_value, dec!(1050.0));
+}
+
+#[tokio::test]
+async fn test_compare_snapshots() {
+    let (repos, fund, stock) = setup().await;
+    let service = service(&repos);
+    service.take(date(1, 31), false).await.unwrap();
+    service.take(date(2, 29), false).await.unwrap();
+
+    let summaries = service.list().await.unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[1].date, date(2, 29));
+    assert_eq!(summaries[1].holdings, 2);
+
+    let comparison = service.compare(date(1, 31), date(2, 29)).await.unwrap();
+
+    assert_eq!(comparison.from_value, dec!(1100.0));
+    // 5 units of the fund at the sale price of 120 and the stock at its purchase price
+    assert_eq!(comparison.to_value, dec!(700.0));
+    assert_eq!(comparison.change, dec!(-400.0));
+    assert_eq!(comparison.holdings.len(), 2);
+    let sold = &comparison.holdings[0];
+    assert_eq!(sold.investment, fund);
+    assert_eq!(sold.from_quantity, dec!(10.0));
+    assert_eq!(sold.to_quantity, dec!(5.0));
+    assert_eq!(sold.to_price, Some(dec!(120.0)));
+    let bought = &comparison.holdings[1];
+    assert_eq!(bought.investment, stock);
+    assert_eq!(bought.from_quantity, dec!(0));
+    assert_eq!(bought.from_price, None);
+    assert_eq!(bought.change, dec!(100.0));
+}
+
+#[tokio::test]
+async fn test_snapshot_errors() {
+    let (repos, _, _) = setup().await;
+    let service = service(&repos);
+
+    let err = service.take(date(1, 1), false).await.unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+    let err = service.get(date(1, 31)).await.unwrap_err();
+    assert!(matches!(err, AppError::NotFound { .. }));
+    assert!(err.to_string().starts_with("Snapshot "));
+
+    service.take(date(1, 31), false).await.unwrap();
+    service.delete(date(1, 31)).await.unwrap();
+    assert!(service.list().await.unwrap().is_empty());
+}
@@ -0,0 +1,107 @@
+use chrono::NaiveDate;
+use portfoliodb_rust::error::{AppError, Result};
+use portfoliodb_rust::services::quotes::{QuoteData, QuoteProvider, SymbolMatch};
+use portfoliodb_rust::services::SymbolSearchService;
+use std::sync::Arc;
+
+/// Provider finding the given tickers, or failing without tickers
+struct SearchProvider {
+    name: &'static str,
+    tickers: Option<Vec<&'static str>>,
+}
+
+#[async_trait::async_trait]
+impl QuoteProvider for SearchProvider {
+    async fn get_quote(
+        &self,
+        _ticker: &str,
+        _date: Option<NaiveDate>,
+    ) -> Result<Option<QuoteData>> {
+        Ok(None)
+    }
+
+    async fn get_quotes(&self, _ticker: &str) -> Result<Vec<QuoteData>> {
+        Ok(Vec::new())
+    }
+
+    async fn probe(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn search_symbols(&self, query: &str) -> Result<Vec<SymbolMatch>> {
+        let tickers = self
+            .tickers
+            .clone()
+            .ok_or_else(|| AppError::ExternalApi(format!("{} is down", self.name)))?;
+        Ok(tickers
+            .into_iter()
+            .map(|ticker| SymbolMatch {
+                ticker: ticker.to_string(),
+                name: Some(query.to_string()),
+                exchange: None,
+                currency: Some("EUR".to_string()),
+                quote_type: None,
+                provider: self.name.to_string(),
+            })
+            .collect())
+    }
+
+    fn get_provider_name(&self) -> &str {
+        self.name
+    }
+}
+
+fn provider(name: &'static str, tickers: Option<Vec<&'static str>>) -> Arc<dyn QuoteProvider> {
+    Arc::new(SearchProvider { name, tickers })
+}
+
+fn tickers(matches: &[SymbolMatch]) -> Vec<(&str, &str)> {
+    matches
+        .iter()
+        .map(|m| (m.provider.as_str(), m.ticker.as_str()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_search_merges_providers_in_order() {
+    let service = SymbolSearchService::with_providers(vec![
+        provider("first", Some(vec!["EUNL.DE", "SWDA.L", "EUNL.DE"])),
+        provider("second", Some(vec!["EUNL.DE"])),
+    ]);
+
+    let matches = service.search(" world ", 10).await.unwrap();
+
+    assert_eq!(
+        tickers(&matches),
+        vec![
+            ("first", "EUNL.DE"),
+            ("first", "SWDA.L"),
+            ("second", "EUNL.DE")
+        ]
+    );
+    assert_eq!(matches[0].name.as_deref(), Some("world"));
+
+    let matches = service.search("world", 1).await.unwrap();
+    assert_eq!(tickers(&matches), vec![("first", "EUNL.DE")]);
+}
+
+#[tokio::test]
+async fn test_search_skips_failing_provider() {
+    let service = SymbolSearchService::with_providers(vec![
+        provider("down", None),
+        provider("up", Some(vec!["AAPL"])),
+    ]);
+    let matches = service.search("apple", 10).await.unwrap();
+    assert_eq!(tickers(&matches), vec![("up", "AAPL")]);
+
+    let service = SymbolSearchService::with_providers(vec![provider("down", None)]);
+    let err = service.search("apple", 10).await.unwrap_err();
+    assert!(matches!(err, AppError::ExternalApi(msg) if msg.contains("down")));
+}
+
+#[tokio::test]
+async fn test_search_requires_query() {
+    let service = SymbolSearchService::with_providers(vec![provider("up", Some(vec!["AAPL"]))]);
+    let err = service.search("  ", 10).await.unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+}
@@ -0,0 +1,245 @@
+mod test_helpers;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::performance::{get_time_weighted_return, ReturnQuery};
+use portfoliodb_rust::handlers::tags::{
+    create_tag, set_investment_tags, tagged_investments, update_tag, InvestmentTagsRequest,
+    TagRequest,
+};
+use portfoliodb_rust::models::{Investment, InvestmentPrice, Movement, Tag};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::{CalculatorState, TagState};
+use portfoliodb_rust::services::PortfolioCalculator;
+use rust_decimal_macros::dec;
+use std::collections::HashSet;
+use std::sync::Arc;
+use test_helpers::setup_test_db;
+
+async fn create_investment(repos: &Repositories, name: &str) -> i64 {
+    repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some(name.to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap()
+}
+
+fn state(repos: &Repositories) -> TagState {
+    TagState {
+        tag_repo: repos.tags.clone(),
+        investment_repo: repos.investments.clone(),
+    }
+}
+
+fn tag(name: &str) -> Json<TagRequest> {
+    Json(TagRequest {
+        name: name.to_string(),
+    })
+}
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+#[tokio::test]
+async fn test_tag_crud() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let created = create_tag(State(state(&repos)), tag(" retirement "))
+        .await
+        .unwrap()
+        .0;
+    assert_eq!(created.name, "retirement");
+
+    // Names are unique ignoring case
+    let err = create_tag(State(state(&repos)), tag("Retirement"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::Conflict(_)));
+    let err = create_tag(State(state(&repos)), tag("  "))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+
+    // Changing the case of its own name is allowed
+    let renamed = update_tag(State(state(&repos)), Path(created.id), tag("Retirement"))
+        .await
+        .unwrap()
+        .0;
+    assert_eq!(renamed.name, "Retirement");
+    assert_eq!(
+        repos.tags.find_by_name("RETIREMENT").await.unwrap(),
+        Some(renamed)
+    );
+
+    repos.tags.delete(created.id).await.unwrap();
+    assert!(repos.tags.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_assign_tags_to_investment() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let apple = create_investment(&repos, "Apple").await;
+    let msci = create_investment(&repos, "MSCI World").await;
+    let retirement = create_tag(State(state(&repos)), tag("retirement"))
+        .await
+        .unwrap()
+        .0;
+    let stocks = create_tag(State(state(&repos)), tag("stocks"))
+        .await
+        .unwrap()
+        .0;
+
+    let tags = set_investment_tags(
+        State(state(&repos)),
+        Path(msci),
+        Json(InvestmentTagsRequest {
+            tag_ids: vec![stocks.id, retirement.id, retirement.id],
+        }),
+    )
+    .await
+    .unwrap()
+    .0;
+    assert_eq!(tags, vec![retirement.clone(), stocks.clone()]);
+    repos
+        .tags
+        .set_investment_tags(apple, &[stocks.id])
+        .await
+        .unwrap();
+
+    let err = set_investment_tags(
+        State(state(&repos)),
+        Path(apple),
+        Json(InvestmentTagsRequest { tag_ids: vec![99] }),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+
+    assert_eq!(
+        tagged_investments(repos.tags.as_ref(), Some("Retirement"))
+            .await
+            .unwrap(),
+        Some(HashSet::from([msci]))
+    );
+    assert_eq!(
+        tagged_investments(repos.tags.as_ref(), None).await.unwrap(),
+        None
+    );
+    let err = tagged_investments(repos.tags.as_ref(), Some("bonds"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+
+    // Deleting a tag or an investment removes the assignments
+    repos.tags.delete(stocks.id).await.unwrap();
+    assert_eq!(
+        repos.tags.find_by_investment(msci).await.unwrap(),
+        vec![retirement.clone()]
+    );
+    repos.investments.delete(msci).await.unwrap();
+    assert!(repos
+        .tags
+        .find_investment_ids(retirement.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_time_weighted_return_filtered_by_tag() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let growth = create_investment(&repos, "Growth").await;
+    let flat = create_investment(&repos, "Flat").await;
+    for investment_id in [growth, flat] {
+        repos
+            .movements
+            .create(&Movement {
+                id: 0,
+                date: Some(day(1)),
+                action_id: Some(1),
+                investment_id: Some(investment_id),
+                quantity: Some(dec!(10.0)),
+                amount: Some(dec!(100.0)),
+                fee: None,
+                tax: None,
+                portfolio_id: None,
+                external_id: None,
+            })
+            .await
+            .unwrap();
+    }
+    for (investment_id, price) in [(growth, dec!(20.0)), (flat, dec!(10.0))] {
+        repos
+            .investment_prices
+            .create(&InvestmentPrice {
+                date: Some(day(2)),
+                investment_id: Some(investment_id),
+                price: Some(price),
+                source: None,
+                currency: None,
+                original_price: None,
+            })
+            .await
+            .unwrap();
+    }
+    let tag_id = repos
+        .tags
+        .create(&Tag {
+            id: 0,
+            name: "retirement".to_string(),
+        })
+        .await
+        .unwrap();
+    repos
+        .tags
+        .set_investment_tags(growth, &[tag_id])
+        .await
+        .unwrap();
+
+    let state = CalculatorState {
+        calculator: Arc::new(PortfolioCalculator::new(
+            repos.movements.clone(),
+            repos.investment_prices.clone(),
+        )),
+        tag_repo: repos.tags.clone(),
+    };
+    let query = |tag: Option<&str>| {
+        Query(ReturnQuery {
+            start_date: None,
+            end_date: None,
+            tag: tag.map(str::to_string),
+            currency: None,
+        })
+    };
+
+    let all = get_time_weighted_return(State(state.clone()), query(None))
+        .await
+        .unwrap()
+        .0;
+    assert!((all.total - 0.5).abs() < 1e-9);
+
+    let tagged = get_time_weighted_return(State(state), query(Some("retirement")))
+        .await
+        .unwrap()
+        .0;
+    assert!((tagged.total - 1.0).abs() < 1e-9);
+    assert_eq!(tagged.investments.len(), 1);
+    assert_eq!(tagged.investments[0].investment, growth);
+}
@@ -0,0 +1,196 @@
+mod test_helpers;
+
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::models::Investment;
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::import::{
+    BrokerImportOptions, StatementParser, TradeRepublicParser, UnmatchedSecurity,
+};
+use portfoliodb_rust::services::BrokerImportService;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+const TRADE_REPUBLIC_CSV: &str = "\
+Datum;Typ;Wert;Notiz;ISIN;Stück;Gebühren;Steuern
+2024-01-02;Einlage;1000,00;;;;;
+2024-01-15;Kauf;-1001,00;iShares Core MSCI World;IE00B4L5Y983;12,5;-1,00;
+2024-02-01;Sparplan;-50,00;iShares Core MSCI World;IE00B4L5Y983;0,617;;
+2024-03-20;Dividende;3,12;Apple;US0378331005;;;0,55
+2024-04-10;Verkauf;197,00;Apple;US0378331005;1;-1,00;2,00
+";
+
+fn apple() -> Investment {
+    Investment {
+        id: 0,
+        name: Some("Apple".to_string()),
+        isin: Some("US0378331005".to_string()),
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    }
+}
+
+#[test]
+fn test_parse_trade_republic_export() {
+    let transactions = TradeRepublicParser::new()
+        .parse(TRADE_REPUBLIC_CSV)
+        .unwrap();
+
+    // The deposit is not a movement
+    assert_eq!(transactions.len(), 4);
+
+    let buy = &transactions[0];
+    assert_eq!(buy.date, NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+    assert_eq!(buy.action_id, 1);
+    assert_eq!(buy.isin, "IE00B4L5Y983");
+    assert_eq!(buy.product, "iShares Core MSCI World");
+    assert_eq!(buy.quantity, Some(dec!(12.5)));
+    assert_eq!(buy.amount, dec!(1000.0));
+    assert_eq!(buy.fee, Some(dec!(1.0)));
+    assert_eq!(buy.currency, "EUR");
+
+    let savings_plan = &transactions[1];
+    assert_eq!(savings_plan.action_id, 1);
+    assert_eq!(savings_plan.amount, dec!(50.0));
+    assert_eq!(savings_plan.fee, None);
+
+    let dividend = &transactions[2];
+    assert_eq!(dividend.action_id, 3);
+    assert_eq!(dividend.quantity, None);
+    assert_eq!(dividend.amount, dec!(3.12));
+
+    // Proceeds of the sale before fees and taxes
+    let sell = &transactions[3];
+    assert_eq!(sell.action_id, 2);
+    assert_eq!(sell.quantity, Some(dec!(1.0)));
+    assert_eq!(sell.amount, dec!(200.0));
+    assert_eq!(sell.fee, Some(dec!(1.0)));
+    assert_eq!(sell.tax, Some(dec!(2.0)));
+}
+
+#[test]
+fn test_parse_trade_republic_export_in_english() {
+    let csv = "\
+Date,Type,Value,Note,ISIN,Shares,Fees,Taxes
+15.01.2024,Buy,-101.00,Apple,US0378331005,0.5,-1.00,
+";
+
+    let transactions = TradeRepublicParser::new().parse(csv).unwrap();
+
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(
+        transactions[0].date,
+        NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+    );
+    assert_eq!(transactions[0].amount, dec!(100.0));
+}
+
+#[test]
+fn test_parse_trade_republic_buy_without_shares() {
+    let csv = "Datum;Typ;Wert;Notiz;ISIN;Stück\n2024-01-15;Kauf;-100,00;Apple;US0378331005;\n";
+
+    let err = TradeRepublicParser::new().parse(csv).unwrap_err();
+
+    assert!(
+        matches!(err, AppError::InvalidInput(ref msg) if msg.contains("Line 2")),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_import_reports_unmatched_securities() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    repos.investments.create(&apple()).await.unwrap();
+    let service = BrokerImportService::new(
+        repos.investments.clone(),
+        repos.movements.clone(),
+        repos.portfolios.clone(),
+        repos.settings.clone(),
+    );
+
+    let result = service
+        .import(
+            &TradeRepublicParser::new(),
+            TRADE_REPUBLIC_CSV,
+            BrokerImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(result.movements.is_empty());
+    assert_eq!(
+        result.unmatched,
+        vec![UnmatchedSecurity {
+            isin: "IE00B4L5Y983".to_string(),
+            product: "iShares Core MSCI World".to_string(),
+            transactions: 2,
+        }]
+    );
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_import_after_mapping_unmatched_securities() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let apple_id = repos.investments.create(&apple()).await.unwrap();
+    let msci_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("MSCI World".to_string()),
+            isin: Some("IE00B4L5Y983".to_string()),
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let service = BrokerImportService::new(
+        repos.investments.clone(),
+        repos.movements.clone(),
+        repos.portfolios.clone(),
+        repos.settings.clone(),
+    );
+
+    let result = service
+        .import(
+            &TradeRepublicParser::new(),
+            TRADE_REPUBLIC_CSV,
+            BrokerImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.movements.len(), 4);
+    assert!(result.unmatched.is_empty());
+    assert!(result.created_investments.is_empty());
+
+    let movements = repos.movements.find_all().await.unwrap();
+    let payout = movements.iter().find(|m| m.action_id == Some(3)).unwrap();
+    assert_eq!(payout.investment_id, Some(apple_id));
+    assert_eq!(payout.quantity, None);
+    assert_eq!(payout.amount, Some(dec!(3.12)));
+    assert_eq!(
+        movements
+            .iter()
+            .filter(|m| m.investment_id == Some(msci_id))
+            .count(),
+        2
+    );
+}
@@ -0,0 +1,148 @@
+mod test_helpers;
+
+use axum::extract::State;
+use axum::Json;
+use chrono::NaiveDate;
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::movements::{create_transfer, TransferRequest};
+use portfoliodb_rust::models::{Investment, Movement, Portfolio};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::MovementState;
+use portfoliodb_rust::services::cost_basis::{CostBasisMethod, TRANSFER_IN_ACTION_ID};
+use portfoliodb_rust::services::CostBasisCalculator;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+async fn setup() -> (Repositories, i64, i64, i64) {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let investment_id = repos
+        .investments
+        .create(&Investment {
+            id: 0,
+            name: Some("Test".to_string()),
+            isin: None,
+            shortname: None,
+            ticker_symbol: None,
+            quote_provider: None,
+            currency: None,
+            asset_class: None,
+            watchlist: false,
+            partial_exemption: None,
+            quote_fetch_enabled: true,
+            quote_fetch_interval_days: None,
+            archived: false,
+        })
+        .await
+        .unwrap();
+    let mut portfolio_ids = Vec::new();
+    for name in ["Broker A", "Broker B"] {
+        let portfolio = Portfolio {
+            id: 0,
+            name: name.to_string(),
+            description: None,
+        };
+        portfolio_ids.push(repos.portfolios.create(&portfolio).await.unwrap());
+    }
+    (repos, investment_id, portfolio_ids[0], portfolio_ids[1])
+}
+
+fn state(repos: &Repositories) -> MovementState {
+    MovementState {
+        movement_repo: repos.movements.clone(),
+        investment_repo: repos.investments.clone(),
+        action_type_repo: repos.action_types.clone(),
+        settings_repo: repos.settings.clone(),
+    }
+}
+
+fn transfer(investment_id: i64, from: i64, to: i64, quantity: Decimal) -> TransferRequest {
+    TransferRequest {
+        date: NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+        investment_id,
+        quantity,
+        from_portfolio_id: Some(from),
+        to_portfolio_id: Some(to),
+    }
+}
+
+#[tokio::test]
+async fn test_create_transfer() {
+    let (repos, investment_id, from, to) = setup().await;
+    repos
+        .movements
+        .create(&Movement {
+            id: 0,
+            date: NaiveDate::from_ymd_opt(2024, 1, 10),
+            action_id: Some(1),
+            investment_id: Some(investment_id),
+            quantity: Some(dec!(10.0)),
+            amount: Some(dec!(500.0)),
+            fee: None,
+            tax: None,
+            portfolio_id: Some(from),
+            external_id: None,
+        })
+        .await
+        .unwrap();
+
+    let Json(response) = create_transfer(
+        State(state(&repos)),
+        Json(transfer(investment_id, from, to, dec!(10.0))),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(response.ids.len(), 2);
+    let transfer_in = repos
+        .movements
+        .find_by_id(response.ids[1])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(transfer_in.action_id, Some(TRANSFER_IN_ACTION_ID));
+    assert_eq!(transfer_in.portfolio_id, Some(to));
+
+    let calculator = CostBasisCalculator::new(repos.movements.clone());
+    let target = calculator
+        .calculate_portfolio_gains(to, CostBasisMethod::Fifo, None)
+        .await
+        .unwrap();
+    assert_eq!(target[0].quantity, dec!(10.0));
+    assert_eq!(target[0].cost_basis, dec!(500.0));
+    let source = calculator
+        .calculate_portfolio_gains(from, CostBasisMethod::Fifo, None)
+        .await
+        .unwrap();
+    assert_eq!(source[0].quantity, dec!(0.0));
+}
+
+#[tokio::test]
+async fn test_invalid_transfers_are_rejected() {
+    let (repos, investment_id, from, to) = setup().await;
+
+    let err = create_transfer(
+        State(state(&repos)),
+        Json(transfer(investment_id, from, from, dec!(1.0))),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::InvalidInput(_)));
+
+    let err = create_transfer(
+        State(state(&repos)),
+        Json(transfer(investment_id, from, to, dec!(0.0))),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::Validation(ref e) if e.errors[0].field == "quantity"));
+
+    let err = create_transfer(
+        State(state(&repos)),
+        Json(transfer(investment_id + 1, from, to, dec!(1.0))),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, AppError::Validation(ref e) if e.errors[0].field == "investment_id"));
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+}
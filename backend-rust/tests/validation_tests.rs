@@ -0,0 +1,288 @@
+mod test_helpers;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::{Duration, NaiveDate, Utc};
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::cash::{create_cash_movement, CreateCashMovementRequest};
+use portfoliodb_rust::handlers::investments::{create_investment, CreateInvestmentRequest};
+use portfoliodb_rust::handlers::movements::{
+    create_movement, create_movements_bulk, BulkCreateQuery, CreateMovementRequest,
+};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::routes::MovementState;
+use portfoliodb_rust::validation::{FieldError, ValidationErrors};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use test_helpers::setup_test_db;
+
+fn movement(quantity: Decimal, amount: Decimal) -> CreateMovementRequest {
+    CreateMovementRequest {
+        date: NaiveDate::from_ymd_opt(2024, 1, 15),
+        action_id: Some(1),
+        investment_id: None,
+        quantity: Some(quantity),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn movement_state(repos: &Repositories) -> MovementState {
+    MovementState {
+        movement_repo: repos.movements.clone(),
+        investment_repo: repos.investments.clone(),
+        action_type_repo: repos.action_types.clone(),
+        settings_repo: repos.settings.clone(),
+    }
+}
+
+fn fields(err: AppError) -> Vec<String> {
+    match err {
+        AppError::Validation(errors) => errors.errors.into_iter().map(|e| e.field).collect(),
+        other => panic!("expected validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_checks_collect_every_field() {
+    let mut errors = ValidationErrors::default();
+    errors.currency("currency", Some("EUR"));
+    errors.currency("base_currency", Some("EURO"));
+    errors.non_negative("fee", Some(0.0));
+    errors.non_negative("amount", Some(-1.0));
+    errors.positive("quantity", Some(0.0));
+    errors.date("date", Some(Utc::now().date_naive() + Duration::days(30)));
+    errors.date(
+        "end_date",
+        Some(Utc::now().date_naive() + Duration::days(400)),
+    );
+    errors.isin("isin", None);
+
+    let fields: Vec<&str> = errors.errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(
+        fields,
+        vec!["base_currency", "amount", "quantity", "end_date"]
+    );
+}
+
+#[tokio::test]
+async fn test_validation_error_response() {
+    let mut errors = ValidationErrors::default();
+    errors.add("quantity", "must not be negative");
+
+    let response = AppError::Validation(errors).into_response();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "error": {
+                "code": "VALIDATION_FAILED",
+                "message": "Validation failed",
+                "details": {
+                    "fields": [{"field": "quantity", "message": "must not be negative"}]
+                }
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_error_codes() {
+    async fn body(error: AppError) -> serde_json::Value {
+        let response = error.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    assert_eq!(
+        body(AppError::not_found("Investment", 42)).await,
+        serde_json::json!({"error": {
+            "code": "NOT_FOUND",
+            "message": "Investment 42 not found",
+            "details": {"entity": "Investment", "id": "42"}
+        }})
+    );
+    assert_eq!(
+        body(AppError::InvalidInput("bad date".to_string())).await["error"]["code"],
+        "INVALID_INPUT"
+    );
+    assert_eq!(
+        body(AppError::Internal(anyhow::anyhow!("secret detail"))).await,
+        serde_json::json!({"error": {"code": "INTERNAL_ERROR", "message": "Internal server error"}})
+    );
+
+    let error = body(AppError::InvalidProvider {
+        provider: "bogus".to_string(),
+        valid: vec!["yahoo", "justetf"],
+    })
+    .await;
+    assert_eq!(error["error"]["code"], "INVALID_PROVIDER");
+    assert_eq!(
+        error["error"]["message"],
+        "Invalid quote provider 'bogus'. Valid providers are: yahoo, justetf"
+    );
+    assert_eq!(
+        error["error"]["details"],
+        serde_json::json!({"provider": "bogus", "valid_providers": ["yahoo", "justetf"]})
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_movement_lists_each_field() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let mut request = movement(dec!(-1.0), dec!(-100.0));
+    request.fee = Some(dec!(-2.0));
+    request.date = NaiveDate::from_ymd_opt(2999, 1, 1);
+
+    let err = create_movement(State(movement_state(&repos)), Json(request))
+        .await
+        .unwrap_err();
+
+    assert_eq!(fields(err), vec!["date", "quantity", "amount", "fee"]);
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_bulk_movements_name_the_position() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let err = create_movements_bulk(
+        State(movement_state(&repos)),
+        Query(BulkCreateQuery::default()),
+        Json(vec![
+            movement(dec!(1.0), dec!(100.0)),
+            movement(dec!(1.0), dec!(-100.0)),
+            movement(dec!(-1.0), dec!(100.0)),
+        ]),
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(fields(err), vec!["[1].amount", "[2].quantity"]);
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_movement_must_refer_to_existing_investment_and_action_type() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let mut request = movement(dec!(1.0), dec!(100.0));
+    request.investment_id = Some(42);
+    request.action_id = Some(99);
+
+    let err = create_movement(State(movement_state(&repos)), Json(request))
+        .await
+        .unwrap_err();
+
+    match &err {
+        AppError::Validation(errors) => assert_eq!(
+            errors.errors,
+            vec![
+                FieldError {
+                    field: "investment_id".to_string(),
+                    message: "refers to investment 42, which does not exist".to_string(),
+                },
+                FieldError {
+                    field: "action_id".to_string(),
+                    message: "refers to action type 99, which does not exist".to_string(),
+                },
+            ]
+        ),
+        other => panic!("expected validation error, got {:?}", other),
+    }
+    assert_eq!(
+        err.into_response().status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_bulk_movements_name_the_missing_reference() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let mut unknown_investment = movement(dec!(1.0), dec!(100.0));
+    unknown_investment.investment_id = Some(42);
+    let mut unknown_action = movement(dec!(-1.0), dec!(100.0));
+    unknown_action.action_id = Some(99);
+
+    let err = create_movements_bulk(
+        State(movement_state(&repos)),
+        Query(BulkCreateQuery::default()),
+        Json(vec![
+            movement(dec!(1.0), dec!(100.0)),
+            unknown_investment,
+            unknown_action,
+        ]),
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        fields(err),
+        vec!["[1].investment_id", "[2].quantity", "[2].action_id"]
+    );
+    assert!(repos.movements.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_investment_with_invalid_isin_is_rejected() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let request = CreateInvestmentRequest {
+        name: Some("Apple".to_string()),
+        isin: Some("US0378331006".to_string()),
+        shortname: None,
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist: None,
+        partial_exemption: None,
+        quote_fetch_enabled: None,
+        quote_fetch_interval_days: None,
+        archived: None,
+    };
+
+    let err = create_investment(State(repos.investments.clone()), Json(request))
+        .await
+        .unwrap_err();
+
+    match err {
+        AppError::Validation(errors) => assert_eq!(
+            errors.errors,
+            vec![FieldError {
+                field: "isin".to_string(),
+                message: "'US0378331006' is not a valid ISIN".to_string(),
+            }]
+        ),
+        other => panic!("expected validation error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_cash_movement_is_rejected() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let request = CreateCashMovementRequest {
+        date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        action_id: 1,
+        amount: dec!(0.0),
+        portfolio_id: None,
+        description: None,
+    };
+
+    let err = create_cash_movement(State(repos.cash_movements.clone()), Json(request))
+        .await
+        .unwrap_err();
+
+    assert_eq!(fields(err), vec!["action_id", "amount"]);
+}
@@ -0,0 +1,221 @@
+mod test_helpers;
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::{Json, Router};
+use portfoliodb_rust::error::AppError;
+use portfoliodb_rust::handlers::settings::{update_settings, UpdateSettingsRequest};
+use portfoliodb_rust::repository::Repositories;
+use portfoliodb_rust::services::webhooks::{
+    sign, QuoteFetchSummary, WebhookEvent, EVENT_HEADER, SIGNATURE_HEADER,
+};
+use portfoliodb_rust::services::{QuoteFetchStatusTracker, QuoteScheduler, WebhookNotifier};
+use sqlx::types::Json as JsonColumn;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use test_helpers::setup_test_db;
+
+/// Requests received by the test server, which fails the first `failures` requests
+#[derive(Clone, Default)]
+struct Receiver {
+    failures: usize,
+    calls: Arc<AtomicUsize>,
+    requests: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+}
+
+async fn receive(State(receiver): State<Receiver>, headers: HeaderMap, body: String) -> StatusCode {
+    let call = receiver.calls.fetch_add(1, Ordering::SeqCst);
+    receiver.requests.lock().unwrap().push((headers, body));
+    if call < receiver.failures {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::NO_CONTENT
+    }
+}
+
+/// Start a webhook receiver on a free local port and return its URL
+async fn start_receiver(receiver: Receiver) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route("/hook", post(receive))
+        .with_state(receiver);
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    url
+}
+
+async fn configure(repos: &Repositories, urls: Vec<String>, secret: Option<&str>) {
+    let mut settings = repos.settings.get().await.unwrap().unwrap();
+    settings.webhook_urls = JsonColumn(urls);
+    settings.webhook_secret = secret.map(str::to_string);
+    repos.settings.update(&settings).await.unwrap();
+}
+
+fn summary() -> WebhookEvent {
+    let now = chrono::Utc::now();
+    WebhookEvent::QuoteFetchCompleted(QuoteFetchSummary {
+        started_at: now,
+        finished_at: now,
+        total: 3,
+        successful: 2,
+        failed: 1,
+        error: None,
+    })
+}
+
+#[test]
+fn test_sign() {
+    assert_eq!(
+        sign("key", b"The quick brown fox jumps over the lazy dog"),
+        "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+}
+
+#[tokio::test]
+async fn test_send_without_urls_does_nothing() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+
+    let deliveries = WebhookNotifier::new(repos.settings.clone())
+        .send(&summary())
+        .await
+        .unwrap();
+
+    assert!(deliveries.is_empty());
+}
+
+/// A failed delivery is retried, every request carries event and signature
+#[tokio::test]
+async fn test_send_signs_and_retries() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let receiver = Receiver {
+        failures: 1,
+        ..Receiver::default()
+    };
+    let url = start_receiver(receiver.clone()).await;
+    configure(&repos, vec![url.clone()], Some("s3cret")).await;
+
+    let deliveries = WebhookNotifier::new(repos.settings.clone())
+        .with_retry(3, Duration::from_millis(10))
+        .send(&summary())
+        .await
+        .unwrap();
+
+    assert_eq!(deliveries.len(), 1);
+    assert!(deliveries[0].success);
+    assert_eq!(deliveries[0].attempts, 2);
+    assert_eq!(deliveries[0].url, url);
+
+    let requests = receiver.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    let (headers, body) = &requests[1];
+    assert_eq!(headers[EVENT_HEADER], "quote_fetch_completed");
+    assert_eq!(
+        headers[SIGNATURE_HEADER],
+        sign("s3cret", body.as_bytes()).as_str()
+    );
+
+    let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(payload["event"], "quote_fetch_completed");
+    assert_eq!(payload["data"]["failed"], 1);
+    assert!(payload["sent_at"].is_string());
+}
+
+#[tokio::test]
+async fn test_send_gives_up_after_max_attempts() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let receiver = Receiver {
+        failures: usize::MAX,
+        ..Receiver::default()
+    };
+    let url = start_receiver(receiver.clone()).await;
+    configure(&repos, vec![url], None).await;
+
+    let deliveries = WebhookNotifier::new(repos.settings.clone())
+        .with_retry(2, Duration::from_millis(10))
+        .send(&summary())
+        .await
+        .unwrap();
+
+    assert!(!deliveries[0].success);
+    assert_eq!(deliveries[0].attempts, 2);
+    assert_eq!(
+        deliveries[0].error.as_deref(),
+        Some("HTTP 503 Service Unavailable")
+    );
+
+    // Without a secret the requests are not signed
+    let requests = receiver.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].0.contains_key(SIGNATURE_HEADER));
+}
+
+#[tokio::test]
+async fn test_scheduled_fetch_sends_summary() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let receiver = Receiver::default();
+    let url = start_receiver(receiver.clone()).await;
+    configure(&repos, vec![url], None).await;
+
+    let scheduler = QuoteScheduler::new(
+        "0 0 18 * * *",
+        repos.investments.clone(),
+        repos.investment_prices.clone(),
+        repos.settings.clone(),
+        repos.quote_fetch_log.clone(),
+        repos.fx_rates.clone(),
+        QuoteFetchStatusTracker::new(),
+    )
+    .unwrap()
+    .with_webhooks(WebhookNotifier::new(repos.settings.clone()));
+    scheduler.run_once().await;
+
+    let requests = receiver.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let payload: serde_json::Value = serde_json::from_str(&requests[0].1).unwrap();
+    assert_eq!(payload["event"], "quote_fetch_completed");
+    assert_eq!(payload["data"]["total"], 0);
+}
+
+#[tokio::test]
+async fn test_update_webhook_settings() {
+    let repos = Repositories::sqlite(setup_test_db().await);
+    let request = |urls: Vec<&str>| UpdateSettingsRequest {
+        base_currency: None,
+        cost_basis_method: None,
+        benchmark_investment_id: None,
+        benchmark_ticker: None,
+        webhook_urls: Some(urls.into_iter().map(str::to_string).collect()),
+        webhook_secret: Some(Some("s3cret".to_string())),
+        price_source_priority: None,
+        value_decimals: None,
+        price_decimals: None,
+        quantity_decimals: None,
+        rounding_mode: None,
+        reject_negative_holdings: None,
+    };
+
+    let response = update_settings(
+        State(repos.settings.clone()),
+        Json(request(vec![" https://ha.local/api/webhook/portfolio "])),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        response.0.webhook_urls,
+        vec!["https://ha.local/api/webhook/portfolio"]
+    );
+    assert!(response.0.webhook_secret_set);
+
+    let err = update_settings(
+        State(repos.settings.clone()),
+        Json(request(vec!["https://ok.local", "ftp://files.local"])),
+    )
+    .await
+    .unwrap_err();
+    match err {
+        AppError::Validation(errors) => assert_eq!(errors.errors[0].field, "webhook_urls[1]"),
+        other => panic!("expected validation error, got {:?}", other),
+    }
+}
@@ -0,0 +1,158 @@
+use calamine::{open_workbook_from_rs, Data, Range, Reader, Xlsx};
+use chrono::NaiveDate;
+use portfoliodb_rust::models::{ActionType, Investment, Movement};
+use portfoliodb_rust::services::portfolio_calculator::Development;
+use portfoliodb_rust::services::xlsx_export::{
+    developments_workbook, movements_workbook, sanitize_sheet_name,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::io::Cursor;
+
+fn investment(id: i64, shortname: Option<&str>, name: Option<&str>) -> Investment {
+    Investment {
+        id,
+        name: name.map(String::from),
+        isin: None,
+        shortname: shortname.map(String::from),
+        ticker_symbol: None,
+        quote_provider: None,
+        currency: None,
+        asset_class: None,
+        watchlist: false,
+        partial_exemption: None,
+        quote_fetch_enabled: true,
+        quote_fetch_interval_days: None,
+        archived: false,
+    }
+}
+
+fn movement(investment_id: Option<i64>, date: NaiveDate, amount: Decimal) -> Movement {
+    Movement {
+        id: 0,
+        date: Some(date),
+        action_id: Some(1),
+        investment_id,
+        quantity: Some(dec!(2.0)),
+        amount: Some(amount),
+        fee: None,
+        tax: None,
+        portfolio_id: None,
+        external_id: None,
+    }
+}
+
+fn open(bytes: Vec<u8>) -> Xlsx<Cursor<Vec<u8>>> {
+    open_workbook_from_rs(Cursor::new(bytes)).expect("Generated file should be a valid workbook")
+}
+
+fn date_at(range: &Range<Data>, pos: (u32, u32)) -> NaiveDate {
+    match range.get_value(pos) {
+        Some(Data::DateTime(dt)) => dt.as_datetime().unwrap().date(),
+        other => panic!("Expected a date at {:?}, got {:?}", pos, other),
+    }
+}
+
+#[test]
+fn test_movements_workbook_has_one_sheet_per_investment() {
+    let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    let investments = vec![
+        investment(1, Some("IWDA"), Some("iShares Core MSCI World")),
+        investment(2, None, Some("Apple Inc.")),
+    ];
+    let action_types = vec![ActionType {
+        id: 1,
+        name: "Buy".to_string(),
+        effect: "increases_quantity".to_string(),
+    }];
+    let movements = vec![
+        movement(None, date, dec!(5.0)),
+        movement(Some(2), date, dec!(150.0)),
+        movement(Some(1), date, dec!(80.5)),
+        movement(Some(1), date.succ_opt().unwrap(), dec!(81.0)),
+    ];
+
+    let mut workbook = open(movements_workbook(&movements, &investments, &action_types).unwrap());
+
+    assert_eq!(
+        workbook.sheet_names(),
+        vec!["IWDA", "Apple Inc.", "Unassigned"]
+    );
+
+    let range = workbook.worksheet_range("IWDA").unwrap();
+    assert_eq!(range.height(), 3);
+    assert_eq!(
+        range.get_value((0, 0)),
+        Some(&Data::String("Date".to_string()))
+    );
+    assert_eq!(date_at(&range, (1, 0)), date);
+    assert_eq!(
+        range.get_value((1, 1)),
+        Some(&Data::String("Buy".to_string()))
+    );
+    assert_eq!(range.get_value((1, 2)), Some(&Data::Float(2.0)));
+    assert_eq!(range.get_value((1, 3)), Some(&Data::Float(80.5)));
+    assert_eq!(date_at(&range, (2, 0)), date.succ_opt().unwrap());
+}
+
+#[test]
+fn test_developments_workbook() {
+    let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    let developments = vec![
+        Development {
+            investment: 7,
+            date,
+            price: dec!(10.0),
+            quantity: dec!(3.0),
+            value: dec!(30.0),
+            source: None,
+        },
+        Development {
+            investment: 7,
+            date: date.succ_opt().unwrap(),
+            price: dec!(11.0),
+            quantity: dec!(3.0),
+            value: dec!(33.0),
+            source: None,
+        },
+    ];
+
+    // Investment 7 is unknown, so its ID is used as sheet name
+    let mut workbook = open(developments_workbook(&developments, &[]).unwrap());
+
+    assert_eq!(workbook.sheet_names(), vec!["Investment 7"]);
+    let range = workbook.worksheet_range("Investment 7").unwrap();
+    assert_eq!(range.height(), 3);
+    assert_eq!(date_at(&range, (2, 0)), date.succ_opt().unwrap());
+    assert_eq!(range.get_value((2, 1)), Some(&Data::Float(11.0)));
+    assert_eq!(range.get_value((2, 3)), Some(&Data::Float(33.0)));
+}
+
+#[test]
+fn test_duplicate_sheet_names_are_numbered() {
+    let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    let investments = vec![
+        investment(1, Some("ETF"), None),
+        investment(2, Some("etf"), None),
+    ];
+    let movements = vec![
+        movement(Some(1), date, dec!(1.0)),
+        movement(Some(2), date, dec!(1.0)),
+    ];
+
+    let workbook = open(movements_workbook(&movements, &investments, &[]).unwrap());
+
+    assert_eq!(workbook.sheet_names(), vec!["ETF", "etf (2)"]);
+}
+
+#[test]
+fn test_sanitize_sheet_name() {
+    assert_eq!(sanitize_sheet_name("S&P 500 [Acc]"), "S&P 500 _Acc_");
+    assert_eq!(sanitize_sheet_name("EUR/USD: 1*2?"), "EUR_USD_ 1_2_");
+    assert_eq!(
+        sanitize_sheet_name("Vanguard FTSE All-World UCITS ETF Distributing")
+            .chars()
+            .count(),
+        31
+    );
+}
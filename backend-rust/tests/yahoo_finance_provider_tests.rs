@@ -1,5 +1,10 @@
 use chrono::NaiveDate;
-use portfoliodb_rust::services::quotes::{QuoteProvider, YahooFinanceProvider};
+use portfoliodb_rust::services::quotes::yahoo_finance::{
+    instrument_info_from_chart_response, symbols_from_search_response,
+};
+use portfoliodb_rust::services::quotes::{
+    InstrumentInfo, QuoteProvider, SymbolMatch, YahooFinanceProvider,
+};
 
 /// Test Yahoo Finance provider initialization
 #[test]
@@ -8,6 +13,106 @@ fn test_yahoo_provider_creation() {
     assert_eq!(provider.get_provider_name(), "yahoo");
 }
 
+/// Search entries without symbol are skipped, the long name is preferred
+#[test]
+fn test_symbols_from_search_response() {
+    let body = r#"{
+        "quotes": [
+            {"exchange": "GER", "shortname": "ISHSIII-CORE MSCI WORLD U.ETF", "quoteType": "ETF",
+             "symbol": "EUNL.DE", "longname": "iShares Core MSCI World UCITS ETF USD (Acc)",
+             "exchDisp": "XETRA"},
+            {"exchange": "LSE", "shortname": "ISHARES CORE MSCI WORLD", "quoteType": "ETF",
+             "symbol": "SWDA.L"},
+            {"index": "news", "exchange": "NMS"}
+        ],
+        "news": []
+    }"#;
+
+    let matches = symbols_from_search_response(body).unwrap();
+
+    assert_eq!(
+        matches,
+        vec![
+            SymbolMatch {
+                ticker: "EUNL.DE".to_string(),
+                name: Some("iShares Core MSCI World UCITS ETF USD (Acc)".to_string()),
+                exchange: Some("XETRA".to_string()),
+                currency: None,
+                quote_type: Some("ETF".to_string()),
+                provider: "yahoo".to_string(),
+            },
+            SymbolMatch {
+                ticker: "SWDA.L".to_string(),
+                name: Some("ISHARES CORE MSCI WORLD".to_string()),
+                exchange: Some("LSE".to_string()),
+                currency: None,
+                quote_type: Some("ETF".to_string()),
+                provider: "yahoo".to_string(),
+            },
+        ]
+    );
+    assert!(symbols_from_search_response("Too Many Requests").is_err());
+}
+
+/// Name, currency and asset class are read from the chart metadata
+#[test]
+fn test_instrument_info_from_chart_response() {
+    let body = r#"{
+        "chart": {
+            "result": [{
+                "meta": {"currency": "EUR", "symbol": "EUNL.DE", "instrumentType": "ETF",
+                         "longName": "iShares Core MSCI World UCITS ETF USD (Acc)",
+                         "shortName": "ISHSIII-CORE MSCI WORLD U.ETF"},
+                "timestamp": [1717394400],
+                "indicators": {"quote": [{"close": [95.12]}]}
+            }],
+            "error": null
+        }
+    }"#;
+
+    let info = instrument_info_from_chart_response(body).unwrap();
+
+    assert_eq!(
+        info,
+        Some(InstrumentInfo {
+            name: Some("iShares Core MSCI World UCITS ETF USD (Acc)".to_string()),
+            shortname: Some("ISHSIII-CORE MSCI WORLD U.ETF".to_string()),
+            currency: Some("EUR".to_string()),
+            asset_class: Some("etf".to_string()),
+        })
+    );
+
+    let empty = r#"{"chart": {"result": [], "error": null}}"#;
+    assert_eq!(instrument_info_from_chart_response(empty).unwrap(), None);
+}
+
+/// Test looking up instrument data on Yahoo Finance (online test)
+#[tokio::test]
+#[ignore] // Ignored by default, run with: cargo test -- --ignored
+async fn test_yahoo_get_instrument_info_online() {
+    let provider = YahooFinanceProvider::new();
+
+    let info = provider.get_instrument_info("AAPL").await.unwrap().unwrap();
+
+    assert_eq!(info.currency.as_deref(), Some("USD"));
+    assert_eq!(info.asset_class.as_deref(), Some("stock"));
+}
+
+/// Test searching symbols by ISIN on Yahoo Finance (online test)
+#[tokio::test]
+#[ignore] // Ignored by default, run with: cargo test -- --ignored
+async fn test_yahoo_search_symbols_online() {
+    let provider = YahooFinanceProvider::new();
+
+    let matches = provider.search_symbols("IE00B4L5Y983").await.unwrap();
+
+    let xetra = matches
+        .iter()
+        .find(|m| m.ticker == "EUNL.DE")
+        .expect("XETRA listing of the ETF");
+    assert_eq!(xetra.currency.as_deref(), Some("EUR"));
+}
+
 /// Test fetching quotes from Yahoo Finance (online test)
 /// Set SKIP_ONLINE_TESTS=1 to skip this test
 #[tokio::test]
@@ -41,7 +146,7 @@ async fn test_yahoo_get_quotes_online() {
     let today = chrono::Utc::now().date_naive();
     let days_diff = (today - last_quote.date).num_days();
     assert!(
-        days_diff >= 0 && days_diff <= 7,
+        (0..=7).contains(&days_diff),
         "Latest quote date {} should be within last 7 days (today: {})",
         last_quote.date,
         today
@@ -90,14 +195,14 @@ async fn test_yahoo_get_quote_latest_online() {
     let today = chrono::Utc::now().date_naive();
     let days_diff = (today - quote.date).num_days();
     assert!(
-        days_diff >= 0 && days_diff <= 7,
+        (0..=7).contains(&days_diff),
         "Latest quote date {} should be within last 7 days (today: {})",
         quote.date,
         today
     );
 
     assert_eq!(quote.ticker, "MSFT");
-    assert!((quote.price -401.32).abs() < 50.0);
+    assert!((quote.price - 401.32).abs() < 50.0);
     assert_eq!(quote.source, "yahoo");
 }
 
@@ -143,3 +248,27 @@ async fn test_yahoo_invalid_ticker_online() {
     // Should return error or empty list, but not panic
     assert!(result.is_err() || result.unwrap().is_empty());
 }
+
+/// Test fetching a historical date range from Yahoo Finance (online test)
+#[tokio::test]
+#[ignore] // Ignored by default, run with: cargo test -- --ignored
+async fn test_yahoo_get_quotes_range_online() {
+    if std::env::var("SKIP_ONLINE_TESTS").is_ok() {
+        println!("Skipping online test");
+        return;
+    }
+
+    let provider = YahooFinanceProvider::new();
+    let date_from = NaiveDate::from_ymd_opt(2015, 3, 2).unwrap();
+    let date_to = NaiveDate::from_ymd_opt(2015, 3, 31).unwrap();
+
+    let quotes = provider
+        .get_quotes_range("AAPL", date_from, date_to)
+        .await
+        .expect("Failed to fetch quote range from Yahoo Finance");
+
+    assert!(quotes.len() >= 20, "Expected a month of trading days");
+    assert!(quotes
+        .iter()
+        .all(|q| q.date >= date_from && q.date <= date_to));
+}